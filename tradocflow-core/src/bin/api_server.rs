@@ -19,7 +19,9 @@ use tradocflow_core::{
     User, Document, DocumentMetadata,
     CreateProjectRequest, UpdateProjectRequest, Priority,
     services::{
-        DocumentImportService, project_manager::ProjectManager, TranslationMemoryAdapter
+        DocumentImportService, project_manager::ProjectManager, TranslationMemoryAdapter,
+        TerminologyServiceAdapter, EventBus, DocumentSearchIndex, IndexWorker, TranslationMemoryIndexer,
+        indexing::DEFAULT_DEBOUNCE, ActivityLog,
     },
     database::{
         project_repository::ProjectRepository,
@@ -32,6 +34,10 @@ use tradocflow_core::{
         kanban::{CreateKanbanCardRequest, UpdateKanbanCardRequest, MoveCardRequest},
     }
 };
+use tradocflow_translation_memory::Term;
+
+/// Items of a project's activity feed returned at once
+const ACTIVITY_FEED_LIMIT: usize = 50;
 
 /// Application state shared across all handlers
 #[derive(Clone)]
@@ -42,6 +48,8 @@ pub struct ApiState {
     pub member_repository: Arc<MemberRepository>,
     pub translation_progress_repository: Arc<TranslationProgressRepository>,
     pub import_service: Arc<DocumentImportService>,
+    pub terminology: Arc<TerminologyServiceAdapter>,
+    pub activity_log: Arc<ActivityLog>,
 }
 
 /// Request/Response structures
@@ -58,6 +66,14 @@ pub struct SaveContentRequest {
     pub language: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddTermRequest {
+    pub term: String,
+    pub definition: Option<String>,
+    #[serde(default)]
+    pub do_not_translate: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateUserRequest {
     pub name: String,
@@ -106,6 +122,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let pool = database.pool();
     
+    // One bus for the whole server, shared by every service that publishes
+    let events = EventBus::default();
+    
     // Initialize repositories
     let project_repository = Arc::new(ProjectRepository::new(pool.clone()));
     let kanban_repository = Arc::new(KanbanRepository::new(pool.clone()));
@@ -120,8 +139,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::path::PathBuf::from("./projects/translation_memory")
     ).await.map_err(|e| format!("Failed to initialize translation memory: {}", e))?;
     
+    // Rebuild the search index and translation memory entries of saved
+    // documents, starting with whatever changed while the server was down
+    let index_workers = [
+        IndexWorker::spawn(Arc::new(DocumentSearchIndex::new(pool.clone())), events.subscribe(), DEFAULT_DEBOUNCE),
+        IndexWorker::spawn(
+            Arc::new(TranslationMemoryIndexer::new(pool.clone(), tm_adapter.clone(), "en")),
+            events.subscribe(),
+            DEFAULT_DEBOUNCE,
+        ),
+    ];
+    for worker in &index_workers {
+        worker.reindex_all();
+    }
+    
+    let activity_log = Arc::new(ActivityLog::new(pool.clone()));
+    activity_log.clone().follow(events.subscribe());
+    
     let import_service = Arc::new(DocumentImportService::new(tm_adapter));
     
+    let terminology = Arc::new(
+        TerminologyServiceAdapter::new(std::path::PathBuf::from("./projects/translation_memory"))
+            .await
+            .map_err(|e| format!("Failed to initialize terminology: {}", e))?
+            .with_events(events.clone())
+    );
+    
     // Create application state
     let state = ApiState {
         project_manager,
@@ -130,6 +173,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         member_repository,
         translation_progress_repository,
         import_service,
+        terminology,
+        activity_log,
     };
 
     // Build the router
@@ -164,6 +209,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/api/projects/:id/members/:user_id", delete(remove_project_member))
         .route("/api/projects/:id/structure", get(get_project_structure))
         .route("/api/projects/:id/summary", get(get_project_summary))
+        .route("/api/projects/:id/terms", post(add_term))
+        .route("/api/projects/:id/activity", get(get_project_activity))
         
         // Kanban endpoints
         .route("/api/projects/:id/kanban", get(get_kanban_cards))
@@ -216,12 +263,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  - POST /api/language");
     println!("  - GET  /api/projects");
     println!("  - POST /api/projects");
+    println!("  - POST /api/projects/:id/terms");
+    println!("  - GET  /api/projects/:id/activity");
     println!("  - GET  /api/notifications");
     println!("  - GET  /api/notifications/unread");
     println!("  - GET  /health");
     
     axum::serve(listener, app).await?;
     
+    for worker in index_workers {
+        worker.shutdown().await;
+    }
+    
     Ok(())
 }
 
@@ -249,6 +302,8 @@ async fn get_documents(State(_state): State<ApiState>) -> Result<Json<Vec<Docume
             metadata: DocumentMetadata {
                 project_id: None,
                 screenshots: vec![],
+                version: None,
+                custom: HashMap::new(),
             },
         }
     ];
@@ -266,6 +321,8 @@ async fn create_document(
         metadata: DocumentMetadata {
             project_id: None,
             screenshots: vec![],
+            version: None,
+            custom: HashMap::new(),
         },
     };
     
@@ -287,6 +344,8 @@ async fn get_document(
         metadata: DocumentMetadata {
             project_id: None,
             screenshots: vec![],
+            version: None,
+            custom: HashMap::new(),
         },
     };
     
@@ -304,6 +363,8 @@ async fn update_document(
         metadata: DocumentMetadata {
             project_id: None,
             screenshots: vec![],
+            version: None,
+            custom: HashMap::new(),
         },
     };
     
@@ -546,6 +607,40 @@ async fn get_project_structure(
     Ok(Json(structure))
 }
 
+async fn add_term(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<AddTermRequest>,
+) -> Result<Json<Term>, StatusCode> {
+    let term = Term::new(request.term, request.definition, request.do_not_translate)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    match state.terminology.add_term(term.clone(), id).await {
+        Ok(()) => Ok(Json(term)),
+        // Invalid or duplicate terms
+        Err(_) => Err(StatusCode::UNPROCESSABLE_ENTITY),
+    }
+}
+
+async fn get_project_activity(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let language = tradocflow_core::i18n::get_language();
+    match state.activity_log.feed(id, ACTIVITY_FEED_LIMIT, None).await {
+        Ok(items) => Ok(Json(serde_json::json!(items
+            .iter()
+            .map(|item| serde_json::json!({
+                "id": item.id,
+                "occurred_at": item.occurred_at,
+                "kind": item.kind(),
+                "summary": item.summary(&language),
+                "activity": item.activity,
+            }))
+            .collect::<Vec<_>>()))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
 async fn get_project_summary(
     State(state): State<ApiState>,
     Path(id): Path<Uuid>,
@@ -765,6 +860,8 @@ async fn import_document(State(_state): State<ApiState>) -> Result<Json<Document
         metadata: DocumentMetadata {
             project_id: None,
             screenshots: vec![],
+            version: None,
+            custom: HashMap::new(),
         },
     };
     
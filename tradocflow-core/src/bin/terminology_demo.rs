@@ -4,7 +4,7 @@ use chrono::Utc;
 use tempfile::TempDir;
 
 use tradocflow_core::services::{TerminologyServiceAdapter, TerminologyHighlightingService, HighlightType};
-use tradocflow_translation_memory::Term;
+use tradocflow_translation_memory::{Term, TermStatus};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -30,6 +30,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             term: "API".to_string(),
             definition: Some("Application Programming Interface".to_string()),
             do_not_translate: true,
+            domain: None,
+            language: None,
+            status: TermStatus::Approved,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         },
@@ -38,6 +41,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             term: "JSON".to_string(),
             definition: Some("JavaScript Object Notation".to_string()),
             do_not_translate: true,
+            domain: None,
+            language: None,
+            status: TermStatus::Approved,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         },
@@ -46,6 +52,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             term: "database".to_string(),
             definition: Some("A structured collection of data".to_string()),
             do_not_translate: false,
+            domain: None,
+            language: None,
+            status: TermStatus::Approved,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         },
@@ -54,6 +63,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             term: "user interface".to_string(),
             definition: Some("The means by which a user interacts with a system".to_string()),
             do_not_translate: false,
+            domain: None,
+            language: None,
+            status: TermStatus::Approved,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         },
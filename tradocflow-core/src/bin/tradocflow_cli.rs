@@ -0,0 +1,272 @@
+// Headless export and validation of manuals, for CI.
+//
+// Reads a manual directory (see `services::manual_dir`) and drives the
+// export engine and the checking services directly; no GUI component is
+// initialized.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tradocflow_core::services::{
+    check_screenshot_alt_text, extract_links, lint_markdown, load_manual_dir, validate_against, validate_links,
+    CrossRefResolver, JsonReport, SectionLint,
+};
+use tradocflow_core::{Document, ExportEngine, ExportFormat, Manual, ManualSection};
+use uuid::Uuid;
+
+const USAGE: &str = "\
+Usage:
+  tradocflow_cli export <manual-dir> [--format html|pdf|both]... [--lang <code>]... [--out <dir>]
+                        [--pattern <file-name-pattern>] [--jobs <n>]
+  tradocflow_cli validate <manual-dir> [--lang <code>]... [--strict] [--json]
+
+A manual directory holds manual.json and documents/<document id>.json.
+With --json, validate prints the lint results as a JSON report and the
+other findings on stderr.
+Exit status is 0 on success, 1 when an export or a check fails and 2 when
+the arguments or the manual can't be used.";
+
+/// Arguments could not be used, or the manual could not be loaded
+const EXIT_USAGE: u8 = 2;
+
+#[derive(Debug)]
+struct ExportArgs {
+    manual_dir: PathBuf,
+    formats: Vec<ExportFormat>,
+    languages: Vec<String>,
+    out_dir: PathBuf,
+    file_name_pattern: Option<String>,
+    jobs: Option<usize>,
+}
+
+#[derive(Debug)]
+struct ValidateArgs {
+    manual_dir: PathBuf,
+    languages: Vec<String>,
+    /// Count warnings as failures
+    strict: bool,
+    /// Print lint results as a JSON report
+    json: bool,
+}
+
+#[derive(Debug)]
+enum Command {
+    Export(ExportArgs),
+    Validate(ValidateArgs),
+    Help,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let command = args.next().ok_or("Missing command")?;
+    if matches!(command.as_str(), "help" | "-h" | "--help") {
+        return Ok(Command::Help);
+    }
+
+    let mut manual_dir = None;
+    let mut formats = Vec::new();
+    let mut languages = Vec::new();
+    let mut out_dir = None;
+    let mut file_name_pattern = None;
+    let mut jobs = None;
+    let mut strict = false;
+    let mut json = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "--format" if command == "export" => {
+                formats.push(match value("--format")?.to_ascii_lowercase().as_str() {
+                    "html" => ExportFormat::Html,
+                    "pdf" => ExportFormat::Pdf,
+                    "both" => ExportFormat::Both,
+                    other => return Err(format!("Unknown format: {other}")),
+                });
+            }
+            "--lang" => languages.push(value("--lang")?),
+            "--out" if command == "export" => out_dir = Some(PathBuf::from(value("--out")?)),
+            "--pattern" if command == "export" => file_name_pattern = Some(value("--pattern")?),
+            "--jobs" if command == "export" => {
+                let jobs_value = value("--jobs")?;
+                jobs = Some(jobs_value.parse().map_err(|_| format!("Invalid --jobs value: {jobs_value}"))?);
+            }
+            "--strict" if command == "validate" => strict = true,
+            "--json" if command == "validate" => json = true,
+            other if other.starts_with('-') => return Err(format!("Unknown option for {command}: {other}")),
+            other if manual_dir.is_none() => manual_dir = Some(PathBuf::from(other)),
+            other => return Err(format!("Unexpected argument: {other}")),
+        }
+    }
+
+    let manual_dir = manual_dir.ok_or("Missing manual directory")?;
+    match command.as_str() {
+        "export" => Ok(Command::Export(ExportArgs {
+            out_dir: out_dir.unwrap_or_else(|| manual_dir.join("export")),
+            manual_dir,
+            formats: if formats.is_empty() { vec![ExportFormat::Both] } else { formats },
+            languages,
+            file_name_pattern,
+            jobs,
+        })),
+        "validate" => Ok(Command::Validate(ValidateArgs { manual_dir, languages, strict, json })),
+        other => Err(format!("Unknown command: {other}")),
+    }
+}
+
+/// Load the manual and narrow it to `languages`, when given
+fn load(manual_dir: &Path, languages: &[String]) -> Result<(Manual, HashMap<Uuid, Document>), String> {
+    let (mut manual, documents) =
+        load_manual_dir(manual_dir).map_err(|e| format!("Could not load {}: {e}", manual_dir.display()))?;
+
+    if !languages.is_empty() {
+        if let Some(unknown) = languages.iter().find(|language| !manual.languages.contains(language)) {
+            return Err(format!("The manual has no language {unknown}; it has {}", manual.languages.join(", ")));
+        }
+        manual.languages = languages.to_vec();
+    }
+    Ok((manual, documents))
+}
+
+async fn export(args: ExportArgs) -> Result<ExitCode, String> {
+    let (manual, documents) = load(&args.manual_dir, &args.languages)?;
+
+    let mut engine = ExportEngine::new().with_screenshot_dir(args.manual_dir.join("screenshots"));
+    if let Some(pattern) = args.file_name_pattern {
+        engine = engine.with_file_name_pattern(pattern);
+    }
+    if let Some(jobs) = args.jobs {
+        engine = engine.with_export_concurrency(jobs);
+    }
+
+    let artifacts = engine
+        .export_all(&manual, &documents, &args.formats, &args.out_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut failed = false;
+    for artifact in &artifacts {
+        match &artifact.error {
+            None => println!("{}: {} bytes", artifact.path.display(), artifact.size),
+            Some(error) => {
+                failed = true;
+                eprintln!("error: {} ({:?}, {}): {error}", artifact.path.display(), artifact.format, artifact.language);
+            }
+        }
+    }
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
+
+fn flatten<'a>(sections: &'a [ManualSection], out: &mut Vec<&'a ManualSection>) {
+    let mut sorted: Vec<&ManualSection> = sections.iter().collect();
+    sorted.sort_by_key(|section| section.order);
+    for section in sorted {
+        out.push(section);
+        flatten(&section.subsections, out);
+    }
+}
+
+fn validate(args: ValidateArgs) -> Result<ExitCode, String> {
+    let (manual, documents) = load(&args.manual_dir, &args.languages)?;
+    let mut errors = 0;
+    let mut warnings = 0;
+    let mut lints = Vec::new();
+    // Keep stdout for the JSON report
+    let say = |line: String| {
+        if args.json {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
+        }
+    };
+
+    for violation in validate_against(&manual, &manual.template_type) {
+        errors += 1;
+        say(format!("error: template: {}", violation.message));
+    }
+
+    let resolver = CrossRefResolver::new(&manual);
+    let mut sections = Vec::new();
+    flatten(&manual.sections, &mut sections);
+
+    for language in &manual.languages {
+        for reference in resolver.dangling_references(&documents, language) {
+            errors += 1;
+            let source = reference.source_section.and_then(|id| resolver.section(id)).map_or("?", |s| s.title.as_str());
+            say(format!(
+                "error: {source} [{language}] line {}: reference to missing section {}",
+                reference.line, reference.reference
+            ));
+        }
+
+        for section in &sections {
+            let Some(document_id) = section.document_id else {
+                continue;
+            };
+            let translation = documents.get(&document_id).and_then(|document| Some((document, document.content.get(language)?)));
+            let Some((document, content)) = translation else {
+                warnings += 1;
+                say(format!("warning: {} [{language}]: no translation", section.title));
+                continue;
+            };
+
+            for issue in validate_links(&extract_links(content), &args.manual_dir) {
+                errors += 1;
+                say(format!("error: {} [{language}] line {}: {}", section.title, issue.link.line, issue.message));
+            }
+
+            for issue in check_screenshot_alt_text(document, language) {
+                warnings += 1;
+                say(format!("warning: {} [{language}] line {}: {}", section.title, issue.line, issue.message));
+            }
+
+            let lint = lint_markdown(content);
+            warnings += lint.len();
+            if !args.json {
+                for warning in &lint {
+                    say(format!(
+                        "warning: {} [{language}] line {}: {} ({})",
+                        section.title,
+                        warning.line,
+                        warning.message,
+                        warning.rule.id()
+                    ));
+                }
+            }
+            lints.push(SectionLint {
+                section_id: section.id,
+                section: section.title.clone(),
+                language: language.clone(),
+                warnings: lint,
+            });
+        }
+    }
+
+    if args.json {
+        println!("{}", JsonReport::lint(lints).to_json().map_err(|e| e.to_string())?);
+    }
+    say(format!("{errors} error(s), {warnings} warning(s)"));
+    let failed = errors > 0 || (args.strict && warnings > 0);
+    Ok(if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    env_logger::init();
+
+    let result = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Export(args)) => export(args).await,
+        Ok(Command::Validate(args)) => validate(args),
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("{e}");
+        ExitCode::from(EXIT_USAGE)
+    })
+}
@@ -0,0 +1,232 @@
+use rusqlite::{params, Connection, OptionalExtension, Result as SqlResult};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use chrono::Utc;
+use crate::database::{DatabasePool, Transaction, datetime_to_string};
+use crate::services::image_import::{sanitize_image, ImageImportOptions};
+use crate::{Result, ScreenshotReference};
+
+/// Images stored by content: each unique image is kept once, as a blob
+/// keyed by its hash, and screenshots reference it. A blob is removed with
+/// the last reference to it.
+#[derive(Clone)]
+pub struct ImageRepository {
+    pool: DatabasePool,
+    options: ImageImportOptions,
+}
+
+impl ImageRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self { pool, options: ImageImportOptions::default() }
+    }
+
+    /// Clean embedded images as `options` say rather than by default
+    pub fn with_import_options(mut self, options: ImageImportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Hex SHA-256 of `bytes`, the key their blob is stored under
+    pub fn content_hash(bytes: &[u8]) -> String {
+        Sha256::digest(bytes).iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// `bytes` checked and cleaned by [`sanitize_image`] as this repository
+    /// cleans the images it embeds, `name` naming the image in errors
+    pub fn sanitize(&self, bytes: &[u8], name: &str) -> Result<Vec<u8>> {
+        sanitize_image(bytes, name, &self.options)
+    }
+
+    /// Store `bytes` as the image of `screenshot` in a document, setting its
+    /// `content_hash`. The image is checked and cleaned by [`Self::sanitize`]
+    /// first, and stored under the hash of what is left. An image already
+    /// stored is referenced, not stored again; a blob the screenshot
+    /// referenced before is released.
+    pub async fn embed(&self, document_id: Uuid, screenshot: &mut ScreenshotReference, bytes: &[u8]) -> Result<()> {
+        let bytes = &self.sanitize(bytes, &format!("Screenshot {}", screenshot.id))?[..];
+        let hash = Self::content_hash(bytes);
+        let now = datetime_to_string(Utc::now());
+        let tx = Transaction::begin(&self.pool).await?;
+
+        tx.execute(
+            "INSERT OR IGNORE INTO image_blobs (hash, data, size, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![hash, bytes, bytes.len() as i64, now],
+        )?;
+        let previous = referenced_blob(&tx, document_id, screenshot)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO image_references (document_id, screenshot_id, language, blob_hash, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![document_id.to_string(), screenshot.id, screenshot.language, hash, now],
+        )?;
+        if let Some(previous) = previous.filter(|previous| *previous != hash) {
+            delete_if_unreferenced(&tx, &previous)?;
+        }
+        tx.commit()?;
+
+        screenshot.content_hash = Some(hash);
+        Ok(())
+    }
+
+    /// Bytes of the blob stored under `hash`
+    pub async fn get_blob(&self, hash: &str) -> SqlResult<Option<Vec<u8>>> {
+        let conn = self.pool.lock().await;
+        conn.query_row("SELECT data FROM image_blobs WHERE hash = ?1", params![hash], |row| row.get(0))
+            .optional()
+    }
+
+    /// Bytes of the image a screenshot of a document references
+    pub async fn resolve(&self, document_id: Uuid, screenshot: &ScreenshotReference) -> SqlResult<Option<Vec<u8>>> {
+        let conn = self.pool.lock().await;
+        conn.query_row(
+            "SELECT b.data FROM image_references r JOIN image_blobs b ON b.hash = r.blob_hash
+             WHERE r.document_id = ?1 AND r.screenshot_id = ?2 AND r.language = ?3",
+            params![document_id.to_string(), screenshot.id, screenshot.language],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Remove a screenshot's reference, and its blob when nothing else
+    /// references it. Returns whether there was a reference.
+    pub async fn remove_reference(&self, document_id: Uuid, screenshot: &ScreenshotReference) -> SqlResult<bool> {
+        let tx = Transaction::begin(&self.pool).await?;
+        let Some(hash) = referenced_blob(&tx, document_id, screenshot)? else {
+            return Ok(false);
+        };
+        tx.execute(
+            "DELETE FROM image_references WHERE document_id = ?1 AND screenshot_id = ?2 AND language = ?3",
+            params![document_id.to_string(), screenshot.id, screenshot.language],
+        )?;
+        delete_if_unreferenced(&tx, &hash)?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Remove every reference of a deleted document, and the blobs only it
+    /// referenced. Returns how many blobs were removed.
+    pub async fn remove_document(&self, document_id: Uuid) -> SqlResult<usize> {
+        let tx = Transaction::begin(&self.pool).await?;
+        tx.execute("DELETE FROM image_references WHERE document_id = ?1", params![document_id.to_string()])?;
+        let removed = tx.execute(
+            "DELETE FROM image_blobs WHERE hash NOT IN (SELECT blob_hash FROM image_references)",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// How many screenshots reference the blob stored under `hash`
+    pub async fn reference_count(&self, hash: &str) -> SqlResult<usize> {
+        let conn = self.pool.lock().await;
+        let count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM image_references WHERE blob_hash = ?1", params![hash], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub async fn blob_count(&self) -> SqlResult<usize> {
+        let conn = self.pool.lock().await;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM image_blobs", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+}
+
+fn referenced_blob(conn: &Connection, document_id: Uuid, screenshot: &ScreenshotReference) -> SqlResult<Option<String>> {
+    conn.query_row(
+        "SELECT blob_hash FROM image_references WHERE document_id = ?1 AND screenshot_id = ?2 AND language = ?3",
+        params![document_id.to_string(), screenshot.id, screenshot.language],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn delete_if_unreferenced(conn: &Connection, hash: &str) -> SqlResult<()> {
+    conn.execute(
+        "DELETE FROM image_blobs WHERE hash = ?1
+         AND NOT EXISTS (SELECT 1 FROM image_references WHERE blob_hash = ?1)",
+        params![hash],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use std::collections::HashMap;
+
+    fn screenshot(id: &str) -> ScreenshotReference {
+        ScreenshotReference {
+            id: id.to_string(),
+            language: "en".to_string(),
+            screen_config: "{}".to_string(),
+            generated_at: None,
+            caption: None,
+            content_hash: None,
+            alt_text: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_same_image_embedded_twice_stores_one_blob() {
+        let repository = ImageRepository::new(Database::in_memory().unwrap().pool());
+        let image = b"<svg><rect width=\"10\" height=\"10\"/></svg>";
+        let (installation, maintenance) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut first = screenshot("main");
+        let mut second = screenshot("main");
+
+        repository.embed(installation, &mut first, image).await.unwrap();
+        repository.embed(maintenance, &mut second, image).await.unwrap();
+
+        assert_eq!(repository.blob_count().await.unwrap(), 1);
+        let hash = first.content_hash.clone().unwrap();
+        assert_eq!(second.content_hash.as_deref(), Some(hash.as_str()));
+        assert_eq!(repository.reference_count(&hash).await.unwrap(), 2);
+        assert_eq!(repository.resolve(maintenance, &second).await.unwrap().as_deref(), Some(&image[..]));
+    }
+
+    #[tokio::test]
+    async fn test_blob_kept_until_last_reference_removed() {
+        let repository = ImageRepository::new(Database::in_memory().unwrap().pool());
+        let image = b"<svg><circle r=\"4\"/></svg>";
+        let (installation, maintenance) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut first = screenshot("main");
+        let mut second = screenshot("settings");
+        repository.embed(installation, &mut first, image).await.unwrap();
+        repository.embed(maintenance, &mut second, image).await.unwrap();
+        let hash = first.content_hash.clone().unwrap();
+
+        assert!(repository.remove_reference(installation, &first).await.unwrap());
+        assert!(!repository.remove_reference(installation, &first).await.unwrap());
+        assert_eq!(repository.get_blob(&hash).await.unwrap().as_deref(), Some(&image[..]));
+        assert_eq!(repository.resolve(maintenance, &second).await.unwrap().as_deref(), Some(&image[..]));
+
+        assert!(repository.remove_reference(maintenance, &second).await.unwrap());
+        assert_eq!(repository.get_blob(&hash).await.unwrap(), None);
+        assert_eq!(repository.blob_count().await.unwrap(), 0);
+
+        // Re-embedding a changed image releases the old one
+        repository.embed(installation, &mut first, image).await.unwrap();
+        repository.embed(installation, &mut first, b"<svg/>").await.unwrap();
+        assert_eq!(repository.blob_count().await.unwrap(), 1);
+        assert_ne!(first.content_hash.as_deref(), Some(hash.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_embedded_image_is_sanitized() {
+        let repository = ImageRepository::new(Database::in_memory().unwrap().pool());
+        let document_id = Uuid::new_v4();
+        let mut panel = screenshot("panel");
+
+        let svg = br#"<svg sodipodi:docname="/home/anna/panel.svg"><metadata><rdf:RDF/></metadata><rect/></svg>"#;
+        repository.embed(document_id, &mut panel, svg).await.unwrap();
+        let stored = repository.resolve(document_id, &panel).await.unwrap().unwrap();
+        assert_eq!(stored, b"<svg><rect/></svg>");
+        assert_eq!(panel.content_hash, Some(ImageRepository::content_hash(&stored)));
+
+        let mut script = screenshot("script");
+        let error = repository.embed(document_id, &mut script, b"#!/bin/sh\n").await.unwrap_err();
+        assert!(matches!(error, crate::TradocumentError::UnsupportedFormat(_)));
+        assert_eq!(script.content_hash, None);
+        assert_eq!(repository.blob_count().await.unwrap(), 1);
+    }
+}
@@ -2,7 +2,7 @@ use rusqlite::{params, Row, Result as SqlResult};
 use uuid::Uuid;
 use chrono::Utc;
 use std::collections::HashMap;
-use crate::database::{DatabasePool, datetime_to_string, string_to_datetime};
+use crate::database::{DatabasePool, Transaction, datetime_to_string, string_to_datetime};
 use rusqlite::OptionalExtension;
 use crate::models::{KanbanCard, CardStatus, Priority, CreateKanbanCardRequest, UpdateKanbanCardRequest, MoveCardRequest, KanbanBoard};
 
@@ -114,52 +114,53 @@ impl KanbanRepository {
     }
     
     pub async fn update_card(&self, card_id: Uuid, request: UpdateKanbanCardRequest) -> SqlResult<Option<KanbanCard>> {
-        let conn = self.pool.lock().await;
+        let tx = Transaction::begin(&self.pool).await?;
         let now = Utc::now();
         
         // Build dynamic update query using individual SQL statements for simplicity
         if let Some(title) = &request.title {
-            conn.execute(
+            tx.execute(
                 "UPDATE kanban_cards SET title = ?1, updated_at = ?2 WHERE id = ?3",
                 params![title, datetime_to_string(now), card_id.to_string()],
             )?;
         }
         
         if let Some(description) = &request.description {
-            conn.execute(
+            tx.execute(
                 "UPDATE kanban_cards SET description = ?1, updated_at = ?2 WHERE id = ?3",
                 params![description, datetime_to_string(now), card_id.to_string()],
             )?;
         }
         
         if let Some(priority) = &request.priority {
-            conn.execute(
+            tx.execute(
                 "UPDATE kanban_cards SET priority = ?1, updated_at = ?2 WHERE id = ?3",
                 params![priority.as_str(), datetime_to_string(now), card_id.to_string()],
             )?;
         }
         
         if let Some(assigned_to) = &request.assigned_to {
-            conn.execute(
+            tx.execute(
                 "UPDATE kanban_cards SET assigned_to = ?1, updated_at = ?2 WHERE id = ?3",
                 params![assigned_to, datetime_to_string(now), card_id.to_string()],
             )?;
         }
         
         if let Some(due_date) = &request.due_date {
-            conn.execute(
+            tx.execute(
                 "UPDATE kanban_cards SET due_date = ?1, updated_at = ?2 WHERE id = ?3",
                 params![datetime_to_string(*due_date), datetime_to_string(now), card_id.to_string()],
             )?;
         }
         
         if let Some(document_id) = &request.document_id {
-            conn.execute(
+            tx.execute(
                 "UPDATE kanban_cards SET document_id = ?1, updated_at = ?2 WHERE id = ?3",
                 params![document_id.to_string(), datetime_to_string(now), card_id.to_string()],
             )?;
         }
         
+        tx.commit()?;
         self.get_card_by_id(card_id).await
     }
     
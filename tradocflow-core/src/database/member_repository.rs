@@ -1,7 +1,7 @@
-use rusqlite::{params, Row, Result as SqlResult};
+use rusqlite::{params, Connection, Row, Result as SqlResult};
 use uuid::Uuid;
 use chrono::Utc;
-use crate::database::{DatabasePool, datetime_to_string, string_to_datetime};
+use crate::database::{DatabasePool, Transaction, datetime_to_string, string_to_datetime};
 use rusqlite::OptionalExtension;
 use crate::models::{ProjectMember, MemberRole, AddMemberRequest, MemberWithUserInfo, ProjectMembershipInfo};
 
@@ -9,6 +9,15 @@ pub struct MemberRepository {
     pool: DatabasePool,
 }
 
+/// Outcome of a role change or removal that must leave a project an owner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnerGuardedWrite {
+    Written,
+    NotAMember,
+    /// Nothing was written: the member is the project's last owner
+    LastOwner,
+}
+
 impl MemberRepository {
     pub fn new(pool: DatabasePool) -> Self {
         Self { pool }
@@ -16,6 +25,33 @@ impl MemberRepository {
     
     pub async fn add_member(&self, project_id: Uuid, request: AddMemberRequest, added_by: String) -> SqlResult<ProjectMember> {
         let conn = self.pool.lock().await;
+        Self::insert_member(&conn, project_id, request, added_by)
+    }
+
+    /// Add the member a pending invitation names and mark the invitation
+    /// accepted in one transaction. Returns `None` without adding anyone when
+    /// the invitation is no longer pending.
+    pub async fn add_invited_member(
+        &self,
+        invitation_id: Uuid,
+        project_id: Uuid,
+        request: AddMemberRequest,
+        added_by: String,
+    ) -> SqlResult<Option<ProjectMember>> {
+        let tx = Transaction::begin(&self.pool).await?;
+        let accepted = tx.execute(
+            "UPDATE team_invitations SET status = 'accepted' WHERE id = ?1 AND status = 'pending'",
+            params![invitation_id.to_string()],
+        )?;
+        if accepted == 0 {
+            return Ok(None);
+        }
+        let member = Self::insert_member(&tx, project_id, request, added_by)?;
+        tx.commit()?;
+        Ok(Some(member))
+    }
+
+    fn insert_member(conn: &Connection, project_id: Uuid, request: AddMemberRequest, added_by: String) -> SqlResult<ProjectMember> {
         let id = Uuid::new_v4();
         let now = Utc::now();
         
@@ -109,11 +145,13 @@ impl MemberRepository {
     }
     
     pub async fn update_member_role(&self, project_id: Uuid, user_id: &str, new_role: MemberRole) -> SqlResult<Option<ProjectMember>> {
-        let conn = self.pool.lock().await;
-        let rows_affected = conn.execute(
-            "UPDATE project_members SET role = ?1 WHERE project_id = ?2 AND user_id = ?3",
-            params![new_role.as_str(), project_id.to_string(), user_id],
-        )?;
+        let rows_affected = {
+            let conn = self.pool.lock().await;
+            conn.execute(
+                "UPDATE project_members SET role = ?1 WHERE project_id = ?2 AND user_id = ?3",
+                params![new_role.as_str(), project_id.to_string(), user_id],
+            )?
+        };
         
         if rows_affected > 0 {
             self.get_member(project_id, user_id).await
@@ -132,6 +170,68 @@ impl MemberRepository {
         Ok(rows_affected > 0)
     }
     
+    /// Give a member `new_role` unless that demotes the project's last
+    /// owner. The check and the write are one transaction, so concurrent
+    /// changes can't leave the project without an owner.
+    pub async fn update_member_role_keeping_owner(
+        &self,
+        project_id: Uuid,
+        user_id: &str,
+        new_role: MemberRole,
+    ) -> SqlResult<OwnerGuardedWrite> {
+        let keeps_owner = new_role == MemberRole::Owner;
+        self.write_keeping_owner(project_id, user_id, keeps_owner, |conn| {
+            conn.execute(
+                "UPDATE project_members SET role = ?1 WHERE project_id = ?2 AND user_id = ?3",
+                params![new_role.as_str(), project_id.to_string(), user_id],
+            )
+        })
+        .await
+    }
+
+    /// Remove a member unless they are the project's last owner, checked
+    /// and written in one transaction
+    pub async fn remove_member_keeping_owner(&self, project_id: Uuid, user_id: &str) -> SqlResult<OwnerGuardedWrite> {
+        self.write_keeping_owner(project_id, user_id, false, |conn| {
+            conn.execute(
+                "DELETE FROM project_members WHERE project_id = ?1 AND user_id = ?2",
+                params![project_id.to_string(), user_id],
+            )
+        })
+        .await
+    }
+
+    async fn write_keeping_owner(
+        &self,
+        project_id: Uuid,
+        user_id: &str,
+        keeps_owner: bool,
+        write: impl FnOnce(&Connection) -> SqlResult<usize>,
+    ) -> SqlResult<OwnerGuardedWrite> {
+        let tx = Transaction::begin(&self.pool).await?;
+        let role: Option<String> = tx.query_row(
+            "SELECT role FROM project_members WHERE project_id = ?1 AND user_id = ?2",
+            params![project_id.to_string(), user_id],
+            |row| row.get(0),
+        ).optional()?;
+        let Some(role) = role.map(|role| MemberRole::from_str(&role)) else {
+            return Ok(OwnerGuardedWrite::NotAMember);
+        };
+        if role == MemberRole::Owner && !keeps_owner {
+            let owners: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM project_members WHERE project_id = ?1 AND role = ?2",
+                params![project_id.to_string(), MemberRole::Owner.as_str()],
+                |row| row.get(0),
+            )?;
+            if owners <= 1 {
+                return Ok(OwnerGuardedWrite::LastOwner);
+            }
+        }
+        write(&tx)?;
+        tx.commit()?;
+        Ok(OwnerGuardedWrite::Written)
+    }
+
     pub async fn get_member(&self, project_id: Uuid, user_id: &str) -> SqlResult<Option<ProjectMember>> {
         let conn = self.pool.lock().await;
         let mut stmt = conn.prepare(
@@ -166,11 +266,11 @@ impl MemberRepository {
             "SELECT owner_id FROM projects WHERE id = ?1"
         )?;
         
-        let owner_id: String = owner_stmt.query_row(params![project_id.to_string()], |row| {
+        let owner_id: Option<String> = owner_stmt.query_row(params![project_id.to_string()], |row| {
             row.get(0)
-        })?;
+        }).optional()?;
         
-        if owner_id == user_id {
+        if owner_id.as_deref() == Some(user_id) {
             return Ok(Some(MemberRole::Owner));
         }
         
@@ -34,6 +34,11 @@ pub fn run_all_migrations(pool: &DatabasePool) -> SqlResult<()> {
     run_migration(&conn, "014_create_translation_units", create_translation_units_table)?;
     run_migration(&conn, "015_create_translation_chunks", create_translation_chunks_table)?;
     run_migration(&conn, "016_create_chunk_links", create_chunk_links_table)?;
+    run_migration(&conn, "017_create_image_blobs", create_image_blobs_tables)?;
+    run_migration(&conn, "018_create_tags", create_tags_tables)?;
+    run_migration(&conn, "019_create_saved_searches", create_saved_searches_table)?;
+    run_migration(&conn, "020_create_activity_log", create_activity_log_table)?;
+    run_migration(&conn, "021_create_document_search", create_document_search_table)?;
     
     Ok(())
 }
@@ -527,4 +532,133 @@ fn create_chunk_links_table(conn: &Connection) -> SqlResult<()> {
     )?;
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Migration 017: Create image_blobs and image_references tables, storing
+/// each unique image once however many screenshots embed it
+fn create_image_blobs_tables(conn: &Connection) -> SqlResult<()> {
+    conn.execute(
+        "CREATE TABLE image_blobs (
+            hash TEXT PRIMARY KEY, -- Hex SHA-256 of the image bytes
+            data BLOB NOT NULL,
+            size INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE image_references (
+            document_id TEXT NOT NULL,
+            screenshot_id TEXT NOT NULL,
+            language TEXT NOT NULL,
+            blob_hash TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (document_id, screenshot_id, language),
+            FOREIGN KEY (blob_hash) REFERENCES image_blobs (hash)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_image_references_blob
+         ON image_references(blob_hash)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Migration 018: Create tags, document_tags and project_tags tables for
+/// labeling documents and projects
+fn create_tags_tables(conn: &Connection) -> SqlResult<()> {
+    conn.execute(
+        "CREATE TABLE tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL, -- As first written
+            normalized_name TEXT NOT NULL UNIQUE, -- Lowercase, so tags are unique ignoring case
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE document_tags (
+            document_id TEXT NOT NULL,
+            tag_id INTEGER NOT NULL,
+            tagged_at TEXT NOT NULL,
+            PRIMARY KEY (document_id, tag_id),
+            FOREIGN KEY (document_id) REFERENCES documents (id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE project_tags (
+            project_id TEXT NOT NULL,
+            tag_id INTEGER NOT NULL,
+            tagged_at TEXT NOT NULL,
+            PRIMARY KEY (project_id, tag_id),
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_document_tags_tag ON document_tags(tag_id)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_project_tags_tag ON project_tags(tag_id)", [])?;
+
+    Ok(())
+}
+
+/// Migration 019: Create saved_searches table for searches re-run on demand
+fn create_saved_searches_table(conn: &Connection) -> SqlResult<()> {
+    conn.execute(
+        "CREATE TABLE saved_searches (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            criteria TEXT NOT NULL, -- JSON search criteria
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration 020: Create activity_log table, the audit log behind project activity feeds
+fn create_activity_log_table(conn: &Connection) -> SqlResult<()> {
+    conn.execute(
+        "CREATE TABLE activity_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            subject TEXT NOT NULL, -- What the activity happened to, such as a document id or tag name
+            activity TEXT NOT NULL, -- JSON activity
+            occurred_at INTEGER NOT NULL -- Microseconds since the epoch, so the feed can page by time
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_activity_log_project ON activity_log(project_id, occurred_at, id)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Migration 021: Create the full-text index of documents, one row per
+/// document and language, kept up to date by the index worker
+fn create_document_search_table(conn: &Connection) -> SqlResult<()> {
+    conn.execute(
+        "CREATE VIRTUAL TABLE document_search USING fts5(
+            document_id UNINDEXED,
+            language UNINDEXED,
+            title,
+            content,
+            tokenize = 'unicode61 remove_diacritics 2'
+        )",
+        [],
+    )?;
+    Ok(())
+}
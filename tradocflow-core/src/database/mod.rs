@@ -1,16 +1,34 @@
-use rusqlite::{Connection, Result as SqlResult};
+use rusqlite::{params, Connection, Result as SqlResult};
 use chrono::{DateTime, Utc};
 use tokio::sync::Mutex;
+use std::collections::HashMap;
 use std::sync::Arc;
+use uuid::Uuid;
 
 pub mod migrations;
 pub mod project_repository;
 pub mod kanban_repository;
 pub mod member_repository;
 pub mod translation_progress_repository;
+pub mod image_repository;
+pub mod saved_search_repository;
+pub mod tag_repository;
+pub mod transaction;
+
+pub use transaction::Transaction;
 
 pub type DatabasePool = Arc<Mutex<Connection>>;
 
+/// A stored document whose title or content matched a search
+#[derive(Debug, Clone)]
+pub struct DocumentSearchHit {
+    pub id: Uuid,
+    pub title: String,
+    /// Markdown by language; empty when the stored content isn't a
+    /// language map
+    pub content: HashMap<String, String>,
+}
+
 pub struct Database {
     pool: DatabasePool,
 }
@@ -47,10 +65,46 @@ impl Database {
     pub fn pool(&self) -> DatabasePool {
         self.pool.clone()
     }
+
+    /// Begin a transaction, rolled back unless committed
+    pub async fn transaction(&self) -> SqlResult<Transaction> {
+        Transaction::begin(&self.pool).await
+    }
     
     fn run_migrations(&self) -> SqlResult<()> {
         migrations::run_all_migrations(&self.pool)
     }
+
+    /// Documents whose title or content contains `query`, ignoring ASCII case
+    pub async fn search_documents(&self, query: &str) -> SqlResult<Vec<DocumentSearchHit>> {
+        let pattern = format!(
+            "%{}%",
+            query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+        );
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT id, title, content FROM documents
+             WHERE title LIKE ?1 ESCAPE '\\' OR content LIKE ?1 ESCAPE '\\'
+             ORDER BY title",
+        )?;
+
+        let rows = stmt.query_map(params![pattern], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+        })?;
+
+        let mut hits = Vec::new();
+        for row in rows {
+            let (id, title, content) = row?;
+            let Ok(id) = Uuid::parse_str(&id) else {
+                continue;
+            };
+            let content = content
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+            hits.push(DocumentSearchHit { id, title, content });
+        }
+        Ok(hits)
+    }
 }
 
 // Helper function to convert DateTime<Utc> to string
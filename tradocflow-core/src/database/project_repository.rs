@@ -2,7 +2,7 @@ use rusqlite::{params, Row, Result as SqlResult};
 use uuid::Uuid;
 use chrono::Utc;
 use std::collections::HashMap;
-use crate::database::{DatabasePool, datetime_to_string, string_to_datetime};
+use crate::database::{DatabasePool, Transaction, datetime_to_string, string_to_datetime};
 use rusqlite::OptionalExtension;
 use crate::models::{Project, ProjectStatus, Priority, CreateProjectRequest, UpdateProjectRequest, ProjectSummary};
 
@@ -136,46 +136,47 @@ impl ProjectRepository {
     }
     
     pub async fn update(&self, id: Uuid, request: UpdateProjectRequest) -> SqlResult<Option<Project>> {
-        let conn = self.pool.lock().await;
+        let tx = Transaction::begin(&self.pool).await?;
         let now = Utc::now();
         
         // Build dynamic update query using individual SQL statements for simplicity
         if let Some(name) = &request.name {
-            conn.execute(
+            tx.execute(
                 "UPDATE projects SET name = ?1, updated_at = ?2 WHERE id = ?3",
                 params![name, datetime_to_string(now), id.to_string()],
             )?;
         }
         
         if let Some(description) = &request.description {
-            conn.execute(
+            tx.execute(
                 "UPDATE projects SET description = ?1, updated_at = ?2 WHERE id = ?3",
                 params![description, datetime_to_string(now), id.to_string()],
             )?;
         }
         
         if let Some(status) = &request.status {
-            conn.execute(
+            tx.execute(
                 "UPDATE projects SET status = ?1, updated_at = ?2 WHERE id = ?3",
                 params![status.as_str(), datetime_to_string(now), id.to_string()],
             )?;
         }
         
         if let Some(priority) = &request.priority {
-            conn.execute(
+            tx.execute(
                 "UPDATE projects SET priority = ?1, updated_at = ?2 WHERE id = ?3",
                 params![priority.as_str(), datetime_to_string(now), id.to_string()],
             )?;
         }
         
         if let Some(due_date) = &request.due_date {
-            conn.execute(
+            tx.execute(
                 "UPDATE projects SET due_date = ?1, updated_at = ?2 WHERE id = ?3",
                 params![datetime_to_string(*due_date), datetime_to_string(now), id.to_string()],
             )?;
         }
         
         // Return the updated project
+        tx.commit()?;
         self.get_by_id(id).await
     }
     
@@ -0,0 +1,238 @@
+use rusqlite::{params, types::Type, OptionalExtension, Row, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use crate::database::{tag_repository, DatabasePool, DocumentSearchHit, datetime_to_string, string_to_datetime};
+use crate::models::DocumentStatus;
+
+/// What documents a saved search matches. Every criterion that is set must
+/// hold; an empty criteria matches every document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchCriteria {
+    /// Tags the document must all have, in any case
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Language the document must have content in
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Translation workflow status of the document, such as draft
+    #[serde(default)]
+    pub status: Option<DocumentStatus>,
+    /// Text the title or content must contain, ignoring case. With a
+    /// language set, only that language's content is searched.
+    #[serde(default)]
+    pub query: Option<String>,
+}
+
+/// A named search that is evaluated against the current documents each
+/// time it is run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    pub name: String,
+    pub criteria: SearchCriteria,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct SavedSearchRepository {
+    pool: DatabasePool,
+}
+
+impl SavedSearchRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, name: &str, criteria: &SearchCriteria) -> SqlResult<SavedSearch> {
+        let now = Utc::now();
+        let search = SavedSearch {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            criteria: criteria.clone(),
+            created_at: now,
+            updated_at: now,
+        };
+        let conn = self.pool.lock().await;
+        conn.execute(
+            "INSERT INTO saved_searches (id, name, criteria, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![search.id.to_string(), search.name, criteria_to_json(criteria)?, datetime_to_string(now)],
+        )?;
+        Ok(search)
+    }
+
+    pub async fn get(&self, id: Uuid) -> SqlResult<Option<SavedSearch>> {
+        let conn = self.pool.lock().await;
+        conn.query_row(
+            "SELECT id, name, criteria, created_at, updated_at FROM saved_searches WHERE id = ?1",
+            params![id.to_string()],
+            row_to_saved_search,
+        )
+        .optional()
+    }
+
+    /// Every saved search, by name
+    pub async fn list(&self) -> SqlResult<Vec<SavedSearch>> {
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare("SELECT id, name, criteria, created_at, updated_at FROM saved_searches ORDER BY name")?;
+        let searches = stmt.query_map([], row_to_saved_search)?;
+        searches.collect()
+    }
+
+    /// Rename a saved search and replace its criteria
+    pub async fn update(&self, id: Uuid, name: &str, criteria: &SearchCriteria) -> SqlResult<Option<SavedSearch>> {
+        let rows_affected = {
+            let conn = self.pool.lock().await;
+            conn.execute(
+                "UPDATE saved_searches SET name = ?1, criteria = ?2, updated_at = ?3 WHERE id = ?4",
+                params![name, criteria_to_json(criteria)?, datetime_to_string(Utc::now()), id.to_string()],
+            )?
+        };
+        if rows_affected == 0 {
+            return Ok(None);
+        }
+        self.get(id).await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> SqlResult<bool> {
+        let conn = self.pool.lock().await;
+        let rows_affected = conn.execute("DELETE FROM saved_searches WHERE id = ?1", params![id.to_string()])?;
+        Ok(rows_affected > 0)
+    }
+
+    /// Documents a saved search matches now, or `None` when there is no
+    /// such search
+    pub async fn run(&self, id: Uuid) -> SqlResult<Option<Vec<DocumentSearchHit>>> {
+        let Some(search) = self.get(id).await? else {
+            return Ok(None);
+        };
+        self.find(&search.criteria).await.map(Some)
+    }
+
+    /// Documents matching `criteria`, by title
+    pub async fn find(&self, criteria: &SearchCriteria) -> SqlResult<Vec<DocumentSearchHit>> {
+        let mut sql = String::from("SELECT d.id, d.title, d.content FROM documents d WHERE 1 = 1");
+        let mut values: Vec<String> = Vec::new();
+        if let Some(status) = &criteria.status {
+            values.push(status.as_str().to_string());
+            sql.push_str(&format!(" AND d.status = ?{}", values.len()));
+        }
+        for tag in &criteria.tags {
+            values.push(tag_repository::normalize(tag));
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM document_tags dt JOIN tags t ON t.id = dt.tag_id
+                   WHERE dt.document_id = d.id AND t.normalized_name = ?{})",
+                values.len()
+            ));
+        }
+        sql.push_str(" ORDER BY d.title, d.id");
+
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(&values), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+        })?;
+
+        let query = criteria.query.as_deref().map(str::trim).filter(|query| !query.is_empty()).map(str::to_lowercase);
+        let mut hits = Vec::new();
+        for row in rows {
+            let (id, title, content) = row?;
+            let Ok(id) = Uuid::parse_str(&id) else {
+                continue;
+            };
+            let content: HashMap<String, String> =
+                content.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default();
+            let searched: Vec<&String> = match &criteria.language {
+                Some(language) => match content.get(language).filter(|text| !text.trim().is_empty()) {
+                    Some(text) => vec![text],
+                    None => continue,
+                },
+                None => content.values().collect(),
+            };
+            if let Some(query) = &query {
+                let found = title.to_lowercase().contains(query.as_str())
+                    || searched.iter().any(|text| text.to_lowercase().contains(query.as_str()));
+                if !found {
+                    continue;
+                }
+            }
+            hits.push(DocumentSearchHit { id, title, content });
+        }
+        Ok(hits)
+    }
+}
+
+fn criteria_to_json(criteria: &SearchCriteria) -> SqlResult<String> {
+    serde_json::to_string(criteria).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+fn row_to_saved_search(row: &Row) -> SqlResult<SavedSearch> {
+    Ok(SavedSearch {
+        id: Uuid::parse_str(&row.get::<_, String>(0)?).map_err(|e| conversion_error(0, e))?,
+        name: row.get(1)?,
+        criteria: serde_json::from_str(&row.get::<_, String>(2)?).map_err(|e| conversion_error(2, e))?,
+        created_at: string_to_datetime(&row.get::<_, String>(3)?).map_err(|e| conversion_error(3, e))?,
+        updated_at: string_to_datetime(&row.get::<_, String>(4)?).map_err(|e| conversion_error(4, e))?,
+    })
+}
+
+/// Error for a text column whose value doesn't parse
+fn conversion_error(index: usize, error: impl std::error::Error + Send + Sync + 'static) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::tag_repository::TagRepository;
+    use crate::database::Database;
+
+    async fn insert_document(pool: &DatabasePool, title: &str, status: &str, content: &[(&str, &str)]) -> Uuid {
+        let id = Uuid::new_v4();
+        let content: HashMap<&str, &str> = content.iter().copied().collect();
+        let now = datetime_to_string(Utc::now());
+        pool.lock()
+            .await
+            .execute(
+                "INSERT INTO documents (id, title, content, status, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+                params![id.to_string(), title, serde_json::to_string(&content).unwrap(), status, now],
+            )
+            .unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn test_saved_search_results_follow_current_documents() {
+        let pool = Database::in_memory().unwrap().pool();
+        let searches = SavedSearchRepository::new(pool.clone());
+        let tags = TagRepository::new(pool.clone());
+        let wiring = insert_document(&pool, "Wiring", "draft", &[("en", "Connect the relay."), ("de", "Relais anschließen.")]).await;
+        insert_document(&pool, "Installation", "draft", &[("en", "Mount the controller.")]).await;
+        insert_document(&pool, "Safety", "approved", &[("de", "Nicht berühren.")]).await;
+
+        let criteria = SearchCriteria {
+            language: Some("de".to_string()),
+            status: Some(DocumentStatus::Draft),
+            ..Default::default()
+        };
+        let search = searches.create("German drafts", &criteria).await.unwrap();
+        let titles = |hits: Vec<DocumentSearchHit>| hits.into_iter().map(|hit| hit.title).collect::<Vec<_>>();
+        assert_eq!(titles(searches.run(search.id).await.unwrap().unwrap()), vec!["Wiring"]);
+
+        // A matching document added later is found by the next run
+        insert_document(&pool, "Clapper", "draft", &[("de", "Klöppel prüfen.")]).await;
+        assert_eq!(titles(searches.run(search.id).await.unwrap().unwrap()), vec!["Clapper", "Wiring"]);
+
+        // Narrowed to a tag and a text query
+        tags.tag_documents(&[wiring], &["Legal"]).await.unwrap();
+        let narrowed = SearchCriteria { tags: vec!["legal".to_string()], query: Some("RELAIS".to_string()), ..criteria };
+        let updated = searches.update(search.id, "Legal German drafts", &narrowed).await.unwrap().unwrap();
+        assert_eq!(updated.criteria, narrowed);
+        assert_eq!(titles(searches.run(search.id).await.unwrap().unwrap()), vec!["Wiring"]);
+
+        assert_eq!(searches.list().await.unwrap().len(), 1);
+        assert!(searches.delete(search.id).await.unwrap());
+        assert!(searches.run(search.id).await.unwrap().is_none());
+    }
+}
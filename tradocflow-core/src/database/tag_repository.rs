@@ -0,0 +1,277 @@
+use rusqlite::{params, Connection, OptionalExtension, Result as SqlResult};
+use uuid::Uuid;
+use chrono::Utc;
+use crate::database::{DatabasePool, Transaction, datetime_to_string};
+
+/// A tag with how many documents and projects carry it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagCount {
+    pub name: String,
+    pub documents: usize,
+    pub projects: usize,
+}
+
+/// What tags are put on
+#[derive(Debug, Clone, Copy)]
+enum Tagged {
+    Document,
+    Project,
+}
+
+impl Tagged {
+    fn table(self) -> &'static str {
+        match self {
+            Tagged::Document => "document_tags",
+            Tagged::Project => "project_tags",
+        }
+    }
+
+    fn id_column(self) -> &'static str {
+        match self {
+            Tagged::Document => "document_id",
+            Tagged::Project => "project_id",
+        }
+    }
+}
+
+/// Labels such as "reviewed" or "legal" on documents and projects. Tags are
+/// unique ignoring case and keep the spelling they were first given; names
+/// are trimmed and blank ones ignored.
+pub struct TagRepository {
+    pool: DatabasePool,
+}
+
+impl TagRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Put every tag on every document, in one transaction. Tags a document
+    /// already has are left alone. Returns how many tags were added.
+    pub async fn tag_documents(&self, document_ids: &[Uuid], tags: &[&str]) -> SqlResult<usize> {
+        self.tag(Tagged::Document, document_ids, tags).await
+    }
+
+    /// Take every tag off every document, in one transaction. Returns how
+    /// many tags were removed.
+    pub async fn untag_documents(&self, document_ids: &[Uuid], tags: &[&str]) -> SqlResult<usize> {
+        self.untag(Tagged::Document, document_ids, tags).await
+    }
+
+    /// [`TagRepository::tag_documents`] for projects
+    pub async fn tag_projects(&self, project_ids: &[Uuid], tags: &[&str]) -> SqlResult<usize> {
+        self.tag(Tagged::Project, project_ids, tags).await
+    }
+
+    /// [`TagRepository::untag_documents`] for projects
+    pub async fn untag_projects(&self, project_ids: &[Uuid], tags: &[&str]) -> SqlResult<usize> {
+        self.untag(Tagged::Project, project_ids, tags).await
+    }
+
+    /// Tags of a document, by name
+    pub async fn document_tags(&self, document_id: Uuid) -> SqlResult<Vec<String>> {
+        self.tags_of(Tagged::Document, document_id).await
+    }
+
+    /// Tags of a project, by name
+    pub async fn project_tags(&self, project_id: Uuid) -> SqlResult<Vec<String>> {
+        self.tags_of(Tagged::Project, project_id).await
+    }
+
+    /// Documents with a tag, in any case, ordered by title
+    pub async fn documents_with_tag(&self, tag: &str) -> SqlResult<Vec<Uuid>> {
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT d.id FROM documents d
+             JOIN document_tags dt ON dt.document_id = d.id
+             JOIN tags t ON t.id = dt.tag_id
+             WHERE t.normalized_name = ?1
+             ORDER BY d.title, d.id",
+        )?;
+        let ids = stmt.query_map(params![normalize(tag)], |row| row.get::<_, String>(0))?;
+        collect_ids(ids)
+    }
+
+    /// Projects with a tag, in any case, ordered by name
+    pub async fn projects_with_tag(&self, tag: &str) -> SqlResult<Vec<Uuid>> {
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT p.id FROM projects p
+             JOIN project_tags pt ON pt.project_id = p.id
+             JOIN tags t ON t.id = pt.tag_id
+             WHERE t.normalized_name = ?1
+             ORDER BY p.name, p.id",
+        )?;
+        let ids = stmt.query_map(params![normalize(tag)], |row| row.get::<_, String>(0))?;
+        collect_ids(ids)
+    }
+
+    /// Every tag with its counts, by name
+    pub async fn list_tags(&self) -> SqlResult<Vec<TagCount>> {
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT t.name,
+                    (SELECT COUNT(*) FROM document_tags dt WHERE dt.tag_id = t.id),
+                    (SELECT COUNT(*) FROM project_tags pt WHERE pt.tag_id = t.id)
+             FROM tags t
+             ORDER BY t.normalized_name",
+        )?;
+        let tags = stmt.query_map([], |row| {
+            Ok(TagCount {
+                name: row.get(0)?,
+                documents: row.get::<_, i64>(1)? as usize,
+                projects: row.get::<_, i64>(2)? as usize,
+            })
+        })?;
+        tags.collect()
+    }
+
+    /// Delete a tag and take it off everything. Returns whether it existed.
+    pub async fn delete_tag(&self, tag: &str) -> SqlResult<bool> {
+        let tx = Transaction::begin(&self.pool).await?;
+        let Some(tag_id) = tag_id(&tx, tag)? else {
+            return Ok(false);
+        };
+        // Removed explicitly in case foreign keys are off
+        tx.execute("DELETE FROM document_tags WHERE tag_id = ?1", params![tag_id])?;
+        tx.execute("DELETE FROM project_tags WHERE tag_id = ?1", params![tag_id])?;
+        tx.execute("DELETE FROM tags WHERE id = ?1", params![tag_id])?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    async fn tag(&self, tagged: Tagged, ids: &[Uuid], tags: &[&str]) -> SqlResult<usize> {
+        let now = datetime_to_string(Utc::now());
+        let tx = Transaction::begin(&self.pool).await?;
+        let insert = format!(
+            "INSERT OR IGNORE INTO {} ({}, tag_id, tagged_at) VALUES (?1, ?2, ?3)",
+            tagged.table(),
+            tagged.id_column()
+        );
+        let mut added = 0;
+        for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (name, normalized_name, created_at) VALUES (?1, ?2, ?3)",
+                params![tag, normalize(tag), now],
+            )?;
+            let tag_id = tag_id(&tx, tag)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+            let mut stmt = tx.prepare_cached(&insert)?;
+            for id in ids {
+                added += stmt.execute(params![id.to_string(), tag_id, now])?;
+            }
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    async fn untag(&self, tagged: Tagged, ids: &[Uuid], tags: &[&str]) -> SqlResult<usize> {
+        let tx = Transaction::begin(&self.pool).await?;
+        let delete = format!("DELETE FROM {} WHERE {} = ?1 AND tag_id = ?2", tagged.table(), tagged.id_column());
+        let mut removed = 0;
+        for tag in tags {
+            let Some(tag_id) = tag_id(&tx, tag)? else {
+                continue;
+            };
+            let mut stmt = tx.prepare_cached(&delete)?;
+            for id in ids {
+                removed += stmt.execute(params![id.to_string(), tag_id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    async fn tags_of(&self, tagged: Tagged, id: Uuid) -> SqlResult<Vec<String>> {
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT t.name FROM tags t JOIN {} x ON x.tag_id = t.id WHERE x.{} = ?1 ORDER BY t.normalized_name",
+            tagged.table(),
+            tagged.id_column()
+        ))?;
+        let names = stmt.query_map(params![id.to_string()], |row| row.get(0))?;
+        names.collect()
+    }
+}
+
+/// The form tags are compared in
+pub(super) fn normalize(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+fn tag_id(conn: &Connection, tag: &str) -> SqlResult<Option<i64>> {
+    conn.query_row("SELECT id FROM tags WHERE normalized_name = ?1", params![normalize(tag)], |row| row.get(0))
+        .optional()
+}
+
+fn collect_ids(ids: impl Iterator<Item = SqlResult<String>>) -> SqlResult<Vec<Uuid>> {
+    let mut parsed = Vec::new();
+    for id in ids {
+        if let Ok(id) = Uuid::parse_str(&id?) {
+            parsed.push(id);
+        }
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    async fn insert_document(pool: &DatabasePool, title: &str) -> Uuid {
+        let id = Uuid::new_v4();
+        let now = datetime_to_string(Utc::now());
+        pool.lock()
+            .await
+            .execute(
+                "INSERT INTO documents (id, title, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+                params![id.to_string(), title, now],
+            )
+            .unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn test_bulk_tagging_and_querying_by_tag() {
+        let pool = Database::in_memory().unwrap().pool();
+        let repository = TagRepository::new(pool.clone());
+        let wiring = insert_document(&pool, "Wiring").await;
+        let install = insert_document(&pool, "Installation").await;
+        let faq = insert_document(&pool, "FAQ").await;
+
+        let added = repository.tag_documents(&[wiring, install, faq], &["Reviewed", "v2"]).await.unwrap();
+        assert_eq!(added, 6);
+        repository.tag_documents(&[wiring], &["legal"]).await.unwrap();
+        assert_eq!(repository.untag_documents(&[faq], &["REVIEWED"]).await.unwrap(), 1);
+
+        // Any case finds the tag; documents come by title
+        assert_eq!(repository.documents_with_tag("reviewed").await.unwrap(), vec![install, wiring]);
+        assert_eq!(repository.documents_with_tag("V2").await.unwrap(), vec![faq, install, wiring]);
+        assert!(repository.documents_with_tag("draft").await.unwrap().is_empty());
+        assert_eq!(repository.document_tags(wiring).await.unwrap(), vec!["legal", "Reviewed", "v2"]);
+
+        let counts = repository.list_tags().await.unwrap();
+        let count = |name: &str| counts.iter().find(|tag| tag.name == name).map(|tag| tag.documents);
+        assert_eq!((count("legal"), count("Reviewed"), count("v2")), (Some(1), Some(2), Some(3)));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_tags_are_idempotent() {
+        let pool = Database::in_memory().unwrap().pool();
+        let repository = TagRepository::new(pool.clone());
+        let wiring = insert_document(&pool, "Wiring").await;
+
+        assert_eq!(repository.tag_documents(&[wiring], &["legal"]).await.unwrap(), 1);
+        assert_eq!(repository.tag_documents(&[wiring], &["Legal", " LEGAL ", "legal"]).await.unwrap(), 0);
+
+        assert_eq!(repository.document_tags(wiring).await.unwrap(), vec!["legal"]);
+        let counts = repository.list_tags().await.unwrap();
+        assert_eq!(counts, vec![TagCount { name: "legal".to_string(), documents: 1, projects: 0 }]);
+
+        // A failed bulk write adds nothing: the second document doesn't exist
+        let missing = Uuid::new_v4();
+        assert!(repository.tag_documents(&[wiring, missing], &["v2"]).await.is_err());
+        assert!(repository.documents_with_tag("v2").await.unwrap().is_empty());
+        assert_eq!(repository.list_tags().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,93 @@
+use rusqlite::{Connection, Result as SqlResult};
+use std::ops::Deref;
+use tokio::sync::OwnedMutexGuard;
+use crate::database::DatabasePool;
+
+/// A transaction on the pooled connection, which it holds until it ends.
+///
+/// It is rolled back when dropped without [`Transaction::commit`], so a
+/// write whose future is cancelled between statements leaves nothing
+/// behind, and the connection is free of the transaction before another
+/// caller can lock it.
+pub struct Transaction {
+    conn: OwnedMutexGuard<Connection>,
+}
+
+impl Transaction {
+    /// Lock the connection and begin a transaction
+    pub async fn begin(pool: &DatabasePool) -> SqlResult<Self> {
+        let conn = pool.clone().lock_owned().await;
+        conn.execute_batch("BEGIN")?;
+        Ok(Self { conn })
+    }
+
+    /// Commit the transaction. If the commit fails, it is rolled back.
+    pub fn commit(self) -> SqlResult<()> {
+        self.conn.execute_batch("COMMIT")
+    }
+
+    /// Roll the transaction back, reporting whether that worked
+    pub fn rollback(self) -> SqlResult<()> {
+        self.conn.execute_batch("ROLLBACK")
+    }
+}
+
+impl Deref for Transaction {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        // Runs before the lock on the connection is released. A committed or
+        // rolled back transaction has left the connection in autocommit mode.
+        if !self.conn.is_autocommit() {
+            if let Err(e) = self.conn.execute_batch("ROLLBACK") {
+                log::error!("Rolling back an unfinished transaction failed: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use rusqlite::params;
+    use std::time::Duration;
+
+    fn bell_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM bells", [], |row| row.get(0)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_transaction_leaves_no_partial_writes() {
+        let pool = Database::in_memory().unwrap().pool();
+        pool.lock().await.execute_batch("CREATE TABLE bells (name TEXT NOT NULL)").unwrap();
+
+        let write = async {
+            let tx = Transaction::begin(&pool).await?;
+            tx.execute("INSERT INTO bells (name) VALUES (?1)", params!["Gloriosa"])?;
+            // Slow work between the statements, during which the write is cancelled
+            std::future::pending::<()>().await;
+            tx.execute("INSERT INTO bells (name) VALUES (?1)", params!["Pretiosa"])?;
+            tx.commit()
+        };
+        assert!(tokio::time::timeout(Duration::from_millis(50), write).await.is_err());
+
+        {
+            let conn = pool.lock().await;
+            assert!(conn.is_autocommit());
+            assert_eq!(bell_count(&conn), 0);
+        }
+
+        // The connection takes new transactions
+        let tx = Transaction::begin(&pool).await.unwrap();
+        tx.execute("INSERT INTO bells (name) VALUES (?1)", params!["Osanna"]).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(bell_count(&*pool.lock().await), 1);
+    }
+}
@@ -1,7 +1,7 @@
 use rusqlite::{params, Row, Result as SqlResult};
 use uuid::Uuid;
 use chrono::Utc;
-use crate::database::{DatabasePool, datetime_to_string, string_to_datetime};
+use crate::database::{DatabasePool, Transaction, datetime_to_string, string_to_datetime};
 use rusqlite::OptionalExtension;
 use crate::models::{TranslationProgress, CreateTranslationProgressRequest, UpdateTranslationProgressRequest, TranslationProgressSummary};
 use crate::models::translation_progress::{LanguageProgress};
@@ -115,19 +115,19 @@ impl TranslationProgressRepository {
     }
     
     pub async fn update(&self, id: Uuid, request: UpdateTranslationProgressRequest) -> SqlResult<Option<TranslationProgress>> {
-        let conn = self.pool.lock().await;
+        let tx = Transaction::begin(&self.pool).await?;
         let now = Utc::now();
         
         // Build dynamic update query using individual SQL statements for simplicity
         if let Some(status) = &request.status {
-            conn.execute(
+            tx.execute(
                 "UPDATE translation_progress SET status = ?1, updated_at = ?2 WHERE id = ?3",
                 params![status.as_str(), datetime_to_string(now), id.to_string()],
             )?;
             
             // If status is completed, set completed_at
             if status.is_completed() {
-                conn.execute(
+                tx.execute(
                     "UPDATE translation_progress SET completed_at = ?1, updated_at = ?2 WHERE id = ?3",
                     params![datetime_to_string(now), datetime_to_string(now), id.to_string()],
                 )?;
@@ -135,40 +135,41 @@ impl TranslationProgressRepository {
         }
         
         if let Some(assigned_translator) = &request.assigned_translator {
-            conn.execute(
+            tx.execute(
                 "UPDATE translation_progress SET assigned_translator = ?1, updated_at = ?2 WHERE id = ?3",
                 params![assigned_translator, datetime_to_string(now), id.to_string()],
             )?;
         }
         
         if let Some(progress_percentage) = &request.progress_percentage {
-            conn.execute(
+            tx.execute(
                 "UPDATE translation_progress SET progress_percentage = ?1, updated_at = ?2 WHERE id = ?3",
                 params![*progress_percentage, datetime_to_string(now), id.to_string()],
             )?;
         }
         
         if let Some(due_date) = &request.due_date {
-            conn.execute(
+            tx.execute(
                 "UPDATE translation_progress SET due_date = ?1, updated_at = ?2 WHERE id = ?3",
                 params![datetime_to_string(*due_date), datetime_to_string(now), id.to_string()],
             )?;
         }
         
         if let Some(quality_score) = &request.quality_score {
-            conn.execute(
+            tx.execute(
                 "UPDATE translation_progress SET quality_score = ?1, updated_at = ?2 WHERE id = ?3",
                 params![*quality_score, datetime_to_string(now), id.to_string()],
             )?;
         }
         
         if let Some(notes) = &request.notes {
-            conn.execute(
+            tx.execute(
                 "UPDATE translation_progress SET notes = ?1, updated_at = ?2 WHERE id = ?3",
                 params![notes, datetime_to_string(now), id.to_string()],
             )?;
         }
         
+        tx.commit()?;
         self.get_by_id(id).await
     }
     
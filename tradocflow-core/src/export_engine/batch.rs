@@ -0,0 +1,348 @@
+use super::epub::section_depth_in;
+use super::html_bundle::{flatten_sections, section_anchor, sorted_sections, warn_dangling};
+use super::{CodeTheme, ExportConfig, ExportEngine, ExportFormat, ListDigits, PageSetup, PdfFonts};
+use crate::services::cross_refs::CrossRefResolver;
+use crate::{Document, Manual, Result, TradocumentError};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// File names of [`ExportEngine::export_all`] unless configured otherwise
+pub const DEFAULT_FILE_NAME_PATTERN: &str = "{title}-{lang}.{ext}";
+
+/// One export per core, capped so a batch of large PDFs doesn't hold too
+/// many documents in memory at once
+pub fn default_export_concurrency() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get()).min(4)
+}
+
+/// Fonts loaded once per batch and shared by every PDF export; the error
+/// is kept as text so each PDF artifact can record it
+type SharedFonts = Option<std::result::Result<PdfFonts, String>>;
+
+/// One language in one format from a batch export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportArtifact {
+    pub language: String,
+    pub format: ExportFormat,
+    /// Where the file was, or would have been, written
+    pub path: PathBuf,
+    /// Size of the written file in bytes
+    pub size: u64,
+    /// Why this export failed; nothing was written when set
+    pub error: Option<String>,
+}
+
+impl ExportArtifact {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl ExportEngine {
+    /// Name batch export files with `pattern`, which may use `{title}`,
+    /// `{lang}`, `{version}` and `{ext}`
+    pub fn with_file_name_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.file_name_pattern = pattern.into();
+        self
+    }
+
+    /// Render at most `limit` exports of [`ExportEngine::export_all`] at once;
+    /// 1 exports one after the other
+    pub fn with_export_concurrency(mut self, limit: usize) -> Self {
+        self.export_concurrency = limit.max(1);
+        self
+    }
+
+    /// Export every language of a manual in every requested format into
+    /// `out_dir`.
+    ///
+    /// HTML is the single-page bundle of [`ExportEngine::export_html_bundle`];
+    /// PDF renders all sections in reading order. `ExportFormat::Both`
+    /// stands for HTML and PDF. A language or format that fails to export is
+    /// recorded in its artifact and the others carry on; only failing to
+    /// create `out_dir` is an error.
+    ///
+    /// Exports render in parallel on a pool of the configured concurrency,
+    /// off the async runtime. Artifacts are ordered by language, then
+    /// format, whatever order the exports finish in.
+    pub async fn export_all(
+        &self,
+        manual: &Manual,
+        documents: &HashMap<Uuid, Document>,
+        formats: &[ExportFormat],
+        out_dir: &Path,
+    ) -> Result<Vec<ExportArtifact>> {
+        fs::create_dir_all(out_dir)?;
+
+        let mut single_formats = Vec::new();
+        for format in formats {
+            let expanded: &[ExportFormat] = match format {
+                ExportFormat::Both => &[ExportFormat::Html, ExportFormat::Pdf],
+                ExportFormat::Html => &[ExportFormat::Html],
+                ExportFormat::Pdf => &[ExportFormat::Pdf],
+            };
+            for format in expanded {
+                if !single_formats.contains(format) {
+                    single_formats.push(*format);
+                }
+            }
+        }
+
+        let fonts: SharedFonts = single_formats
+            .contains(&ExportFormat::Pdf)
+            .then(|| PdfFonts::load(&self.font_config).map_err(|e| e.to_string()));
+        let jobs: Vec<(String, ExportFormat)> = manual
+            .languages
+            .iter()
+            .flat_map(|language| single_formats.iter().map(move |format| (language.clone(), *format)))
+            .collect();
+
+        // The exports run on blocking threads, which drive their futures on
+        // the runtime's handle so the runtime's own threads stay free
+        let handle = tokio::runtime::Handle::current();
+        let (engine, manual, documents, out_dir) = (self.clone(), manual.clone(), documents.clone(), out_dir.to_path_buf());
+        tokio::task::spawn_blocking(move || -> Result<Vec<ExportArtifact>> {
+            let run = |(language, format): &(String, ExportFormat)| {
+                let exported = handle.block_on(engine.export_manual_as(&manual, &documents, language, *format, &fonts));
+                engine.write_artifact(&manual, language, *format, &out_dir, exported)
+            };
+            if engine.export_concurrency <= 1 {
+                return Ok(jobs.iter().map(run).collect());
+            }
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(engine.export_concurrency)
+                .build()
+                .map_err(|e| TradocumentError::FileError(format!("Export worker pool could not start: {e}")))?;
+            // Indexed parallel iterators collect in input order
+            Ok(pool.install(|| jobs.par_iter().map(run).collect()))
+        })
+        .await
+        .map_err(|e| TradocumentError::FileError(format!("Export workers stopped: {e}")))?
+    }
+
+    /// Write the export of one language in one format to `out_dir`
+    fn write_artifact(
+        &self,
+        manual: &Manual,
+        language: &str,
+        format: ExportFormat,
+        out_dir: &Path,
+        exported: Result<Vec<u8>>,
+    ) -> ExportArtifact {
+        let path = out_dir.join(self.artifact_file_name(manual, language, format));
+        let written = exported
+            .and_then(|bytes| fs::write(&path, &bytes).map(|_| bytes.len() as u64).map_err(TradocumentError::from));
+
+        let (size, error) = match written {
+            Ok(size) => (size, None),
+            Err(e) => {
+                log::warn!("Exporting {} as {format:?} in {language} failed: {e}", manual.title);
+                (0, Some(e.to_string()))
+            }
+        };
+        ExportArtifact { language: language.to_string(), format, path, size, error }
+    }
+
+    async fn export_manual_as(
+        &self,
+        manual: &Manual,
+        documents: &HashMap<Uuid, Document>,
+        language: &str,
+        format: ExportFormat,
+        fonts: &SharedFonts,
+    ) -> Result<Vec<u8>> {
+        match format {
+            ExportFormat::Pdf => {
+                let loaded;
+                let fonts = match fonts {
+                    Some(Ok(fonts)) => fonts,
+                    Some(Err(e)) => return Err(TradocumentError::Pdf(e.clone())),
+                    None => {
+                        loaded = PdfFonts::load(&self.font_config)?;
+                        &loaded
+                    }
+                };
+                let markdown = self.manual_markdown(manual, documents, language).await?;
+                let config = ExportConfig {
+                    format: ExportFormat::Pdf,
+                    include_screenshots: true,
+                    template: None,
+                    css_file: None,
+                    languages: vec![language.to_string()],
+                    page_setup: PageSetup::default(),
+                    code_theme: CodeTheme::default(),
+                    image_policy: self.image_policy,
+                    header_template: None,
+                    watermark: None,
+                    review_status: None,
+                    list_digits: ListDigits::default(),
+                };
+                self.render_pdf(&markdown, language, &config, fonts, &mut 0)
+            }
+            _ => Ok(self.export_html_bundle(manual, documents, language).await?.into_bytes()),
+        }
+    }
+
+    /// All sections of a manual as one markdown document, each under a
+    /// heading of its depth
+    pub(super) async fn manual_markdown(&self, manual: &Manual, documents: &HashMap<Uuid, Document>, language: &str) -> Result<String> {
+        let cross_refs = CrossRefResolver::new(manual);
+        let mut markdown = format!("# {}\n\n", manual.title);
+
+        for chapter in sorted_sections(&manual.sections) {
+            // Lets captions be numbered per chapter once rendered
+            if self.caption_lists.is_some() {
+                markdown.push_str(&format!("{}\n\n", super::captions::CHAPTER_MARK));
+            }
+            let mut sections = Vec::new();
+            flatten_sections(chapter, &mut sections);
+            for section in sections {
+                let depth = section_depth_in(chapter, section.id);
+                markdown.push_str(&format!("{} {}\n\n", "#".repeat((depth + 1).min(6)), section.localized_title(language)));
+
+                let document = section.document_id.and_then(|id| documents.get(&id));
+                let Some((document, content)) = document.and_then(|d| d.content.get(language).map(|c| (d, c))) else {
+                    continue;
+                };
+                let resolved = cross_refs.resolve(content, language, |id| format!("#{}", section_anchor(id)));
+                warn_dangling(section, &resolved.dangling);
+                let content = self.process_fragments(&resolved.text, language);
+                let content = self.process_screenshots(&content, &document.metadata.screenshots, language).await?;
+                markdown.push_str(content.trim_end());
+                markdown.push_str("\n\n");
+            }
+        }
+
+        Ok(markdown)
+    }
+
+    fn artifact_file_name(&self, manual: &Manual, language: &str, format: ExportFormat) -> String {
+        let extension = match format {
+            ExportFormat::Pdf => "pdf",
+            _ => "html",
+        };
+        self.file_name_pattern
+            .replace("{title}", &file_name_part(&manual.title))
+            .replace("{lang}", &file_name_part(language))
+            .replace("{version}", &file_name_part(&manual.version))
+            .replace("{ext}", extension)
+    }
+}
+
+/// Text safe to use in a file name: whitespace becomes `-` and characters
+/// that are special on common file systems are dropped
+fn file_name_part(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .filter(|c| !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DocumentMetadata, ManualSection, ManualTemplate, SectionType};
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn fixture() -> (Manual, HashMap<Uuid, Document>) {
+        let document_id = Uuid::new_v4();
+        let documents = HashMap::from([(
+            document_id,
+            Document {
+                title: "Setup".to_string(),
+                content: HashMap::from([
+                    ("en".to_string(), "Mount the controller.".to_string()),
+                    ("de".to_string(), "Steuerung montieren.".to_string()),
+                ]),
+                metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+            },
+        )]);
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Controller Manual".to_string(),
+            description: String::new(),
+            sections: vec![ManualSection {
+                id: Uuid::new_v4(),
+                title: "Setup".to_string(),
+                order: 1,
+                document_id: Some(document_id),
+                subsections: Vec::new(),
+                section_type: SectionType::Installation,
+                required: true,
+                localized_titles: HashMap::from([("de".to_string(), "Einrichtung".to_string())]),
+            }],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "1.0".to_string(),
+            languages: vec!["en".to_string(), "de".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        };
+        (manual, documents)
+    }
+
+    #[tokio::test]
+    async fn test_export_all_languages_and_formats() {
+        let (manual, documents) = fixture();
+        let out_dir = TempDir::new().unwrap();
+        let artifacts = ExportEngine::new()
+            .export_all(&manual, &documents, &[ExportFormat::Html, ExportFormat::Pdf], out_dir.path())
+            .await
+            .unwrap();
+
+        assert_eq!(artifacts.len(), 4);
+        let names: Vec<String> = artifacts
+            .iter()
+            .map(|artifact| artifact.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["Controller-Manual-en.html", "Controller-Manual-en.pdf", "Controller-Manual-de.html", "Controller-Manual-de.pdf"]);
+
+        for artifact in &artifacts {
+            if artifact.is_ok() {
+                assert_eq!(fs::metadata(&artifact.path).unwrap().len(), artifact.size);
+            } else {
+                // PDF needs fonts that may not be installed; the failure is recorded
+                assert_eq!(artifact.format, ExportFormat::Pdf);
+                assert!(!artifact.path.exists());
+            }
+        }
+        let german = fs::read_to_string(&artifacts[2].path).unwrap();
+        assert!(german.contains("Steuerung montieren."));
+    }
+
+    #[tokio::test]
+    async fn test_parallel_export_matches_serial_export() {
+        let (manual, documents) = fixture();
+        let formats = [ExportFormat::Both];
+        let serial_dir = TempDir::new().unwrap();
+        let parallel_dir = TempDir::new().unwrap();
+
+        let serial = ExportEngine::new()
+            .with_export_concurrency(1)
+            .export_all(&manual, &documents, &formats, serial_dir.path())
+            .await
+            .unwrap();
+        let parallel = ExportEngine::new()
+            .with_export_concurrency(4)
+            .export_all(&manual, &documents, &formats, parallel_dir.path())
+            .await
+            .unwrap();
+
+        assert_eq!(serial.len(), parallel.len());
+        for (serial, parallel) in serial.iter().zip(&parallel) {
+            assert_eq!((&serial.language, serial.format), (&parallel.language, parallel.format));
+            assert_eq!(serial.path.file_name(), parallel.path.file_name());
+            assert_eq!(serial.error, parallel.error);
+            // PDFs carry their creation time, so only HTML is compared byte for byte
+            if serial.format == ExportFormat::Html {
+                assert_eq!(fs::read(&serial.path).unwrap(), fs::read(&parallel.path).unwrap());
+            }
+        }
+    }
+}
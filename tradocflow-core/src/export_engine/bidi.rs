@@ -0,0 +1,415 @@
+//! Bidirectional text layout for PDF exports
+//!
+//! genpdf draws strings left to right as given, so text of right-to-left
+//! languages is put in visual order here: a paragraph's embedding levels
+//! are resolved with a reduced Unicode Bidirectional Algorithm (UAX #9),
+//! its lines are broken in logical order and each line is then reordered.
+//! Left-to-right runs inside a right-to-left line, such as URLs, numbers
+//! and code isolated between U+2066 and U+2069, keep their reading order.
+//! Explicit embeddings and overrides are not supported.
+
+use crate::services::TextDirection;
+use serde::{Deserialize, Serialize};
+
+/// Opens a left-to-right isolate, as put around inline code
+pub const LEFT_TO_RIGHT_ISOLATE: char = '\u{2066}';
+const RIGHT_TO_LEFT_ISOLATE: char = '\u{2067}';
+/// Closes an isolate
+pub const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+/// Digits of list numbers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ListDigits {
+    /// 0123456789
+    #[default]
+    Latin,
+    /// ٠١٢٣٤٥٦٧٨٩, as used in Arabic
+    ArabicIndic,
+    /// ۰۱۲۳۴۵۶۷۸۹, as used in Persian and Urdu
+    ExtendedArabicIndic,
+}
+
+impl ListDigits {
+    /// `number` written in these digits
+    pub fn format(&self, number: u32) -> String {
+        let zero = match self {
+            ListDigits::Latin => return number.to_string(),
+            ListDigits::ArabicIndic => 0x0660,
+            ListDigits::ExtendedArabicIndic => 0x06F0,
+        };
+        number
+            .to_string()
+            .chars()
+            .map(|digit| char::from_u32(zero + digit.to_digit(10).unwrap_or(0)).unwrap_or(digit))
+            .collect()
+    }
+}
+
+/// Marker of a list item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListMarker {
+    Bullet,
+    Number(u32),
+}
+
+impl ListMarker {
+    /// The marker in logical order
+    pub fn text(&self, digits: ListDigits) -> String {
+        match self {
+            ListMarker::Bullet => "•".to_string(),
+            ListMarker::Number(number) => format!("{}.", digits.format(*number)),
+        }
+    }
+}
+
+/// A line of a laid out paragraph, in visual order
+#[derive(Debug, Clone, PartialEq)]
+pub struct BidiLine {
+    pub text: String,
+    /// Space kept free at the start edge of the line, the right edge for
+    /// right-to-left text, so continuation lines of a list item align
+    /// with its first line rather than its marker
+    pub indent: f64,
+}
+
+/// Bidi character types, named as in UAX #9
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BidiClass {
+    L,
+    R,
+    AL,
+    EN,
+    AN,
+    ES,
+    ET,
+    CS,
+    WS,
+    ON,
+    LRI,
+    RLI,
+    PDI,
+}
+
+/// Bidi class of `c`, from the Unicode blocks of the scripts this covers
+fn bidi_class(c: char) -> BidiClass {
+    use BidiClass::*;
+    match c {
+        '0'..='9' | '\u{06F0}'..='\u{06F9}' => EN,
+        '\u{0660}'..='\u{0669}' | '\u{066B}' | '\u{066C}' => AN,
+        '+' | '-' | '\u{2212}' => ES,
+        '#' | '$' | '%' | '°' | '¢' | '£' | '¥' | '€' | '\u{066A}' => ET,
+        ',' | '.' | '/' | ':' | '\u{00A0}' | '\u{060C}' => CS,
+        LEFT_TO_RIGHT_ISOLATE => LRI,
+        RIGHT_TO_LEFT_ISOLATE => RLI,
+        POP_DIRECTIONAL_ISOLATE => PDI,
+        '\u{0590}'..='\u{05FF}' | '\u{07C0}'..='\u{085F}' | '\u{FB1D}'..='\u{FB4F}' => R,
+        '\u{0600}'..='\u{07BF}' | '\u{0860}'..='\u{08FF}' | '\u{FB50}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}' => AL,
+        c if c.is_whitespace() => WS,
+        c if c.is_alphanumeric() => L,
+        _ => ON,
+    }
+}
+
+fn is_isolate_control(c: char) -> bool {
+    matches!(c, LEFT_TO_RIGHT_ISOLATE | RIGHT_TO_LEFT_ISOLATE | POP_DIRECTIONAL_ISOLATE)
+}
+
+/// Embedding level of each character of a paragraph at `base_level`
+fn resolve_levels(chars: &[char], base_level: u8) -> Vec<u8> {
+    use BidiClass::*;
+    let mut levels = vec![base_level; chars.len()];
+
+    // Isolates are resolved on their own; outside they count as a neutral
+    let mut outer = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        outer.push(i);
+        let class = bidi_class(chars[i]);
+        if matches!(class, LRI | RLI) {
+            let mut depth = 1;
+            let mut end = i + 1;
+            while end < chars.len() {
+                match bidi_class(chars[end]) {
+                    LRI | RLI => depth += 1,
+                    PDI => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    break;
+                }
+                end += 1;
+            }
+            let inner_level = match class {
+                LRI => (base_level + 2) & !1,
+                _ => (base_level + 1) | 1,
+            };
+            let inner = resolve_levels(&chars[i + 1..end], inner_level);
+            levels[i + 1..end].copy_from_slice(&inner);
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+
+    let embedding = if base_level & 1 == 0 { L } else { R };
+    let mut classes: Vec<BidiClass> = outer
+        .iter()
+        .map(|&i| match bidi_class(chars[i]) {
+            LRI | RLI | PDI => ON,
+            class => class,
+        })
+        .collect();
+
+    // W2, W3: European numbers after Arabic letters are Arabic numbers
+    let mut last_strong = embedding;
+    for class in classes.iter_mut() {
+        match *class {
+            L | R | AL => last_strong = *class,
+            EN if last_strong == AL => *class = AN,
+            _ => {}
+        }
+        if *class == AL {
+            *class = R;
+        }
+    }
+    // W4: a single separator between two numbers of one kind joins them
+    for i in 1..classes.len().saturating_sub(1) {
+        let (before, after) = (classes[i - 1], classes[i + 1]);
+        classes[i] = match (classes[i], before, after) {
+            (ES, EN, EN) | (CS, EN, EN) => EN,
+            (CS, AN, AN) => AN,
+            (class, _, _) => class,
+        };
+    }
+    // W5: terminators next to European numbers belong to them
+    for i in 0..classes.len() {
+        if classes[i] != ET {
+            continue;
+        }
+        let mut end = i;
+        while end < classes.len() && classes[end] == ET {
+            end += 1;
+        }
+        if (i > 0 && classes[i - 1] == EN) || classes.get(end) == Some(&EN) {
+            classes[i..end].fill(EN);
+        }
+    }
+    // W6, W7: other separators are neutral; numbers in left-to-right text
+    // are left to right
+    let mut last_strong = embedding;
+    for class in classes.iter_mut() {
+        match *class {
+            ES | ET | CS => *class = ON,
+            L | R => last_strong = *class,
+            EN if last_strong == L => *class = L,
+            _ => {}
+        }
+    }
+    // N1, N2: neutrals take the direction around them when both sides
+    // agree, and the embedding direction otherwise
+    let strong = |class: BidiClass| match class {
+        L => Some(L),
+        R | EN | AN => Some(R),
+        _ => None,
+    };
+    let mut i = 0;
+    while i < classes.len() {
+        if strong(classes[i]).is_some() {
+            i += 1;
+            continue;
+        }
+        let mut end = i;
+        while end < classes.len() && strong(classes[end]).is_none() {
+            end += 1;
+        }
+        let before = if i == 0 { embedding } else { strong(classes[i - 1]).unwrap_or(embedding) };
+        let after = classes.get(end).and_then(|&class| strong(class)).unwrap_or(embedding);
+        classes[i..end].fill(if before == after { before } else { embedding });
+        i = end;
+    }
+    // I1, I2
+    for (&index, class) in outer.iter().zip(classes) {
+        levels[index] = match (base_level % 2, class) {
+            (0, R) => base_level + 1,
+            (0, AN | EN) => base_level + 2,
+            (1, L | EN | AN) => base_level + 1,
+            _ => base_level,
+        };
+    }
+    levels
+}
+
+/// Mirrored form of a paired bracket, shown in right-to-left runs
+fn mirrored(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        other => other,
+    }
+}
+
+/// One line of a paragraph in visual order, from its characters and their
+/// resolved levels
+fn visual_order(chars: &[char], levels: &[u8], base_level: u8) -> String {
+    let mut levels = levels.to_vec();
+    // L1: whitespace at the end of the line takes the paragraph level
+    for i in (0..chars.len()).rev() {
+        if !chars[i].is_whitespace() && !is_isolate_control(chars[i]) {
+            break;
+        }
+        levels[i] = base_level;
+    }
+
+    // L4, then L2: from the highest level to the lowest odd one, reverse
+    // every run at that level or above
+    let mut order: Vec<(char, u8)> =
+        chars.iter().zip(&levels).map(|(&c, &level)| (if level % 2 == 1 { mirrored(c) } else { c }, level)).collect();
+    let highest = levels.iter().copied().max().unwrap_or(base_level);
+    let lowest_odd = levels.iter().copied().filter(|level| level % 2 == 1).min().unwrap_or(highest + 1);
+    for level in (lowest_odd..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if order[i].1 < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < order.len() && order[i].1 >= level {
+                i += 1;
+            }
+            order[start..i].reverse();
+        }
+    }
+    order.into_iter().map(|(c, _)| c).filter(|&c| !is_isolate_control(c)).collect()
+}
+
+fn base_level(direction: TextDirection) -> u8 {
+    u8::from(direction.is_rtl())
+}
+
+/// A single line of logical text in visual order for a paragraph running
+/// in `direction`
+pub fn reorder_line(text: &str, direction: TextDirection) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let base = base_level(direction);
+    visual_order(&chars, &resolve_levels(&chars, base), base)
+}
+
+/// Lay out a paragraph running in `direction` as lines in visual order no
+/// wider than `max_width` as `measure` counts it, breaking at spaces.
+///
+/// A list item's `marker` goes before the first line, on the right of
+/// right-to-left text, and the following lines are indented by its width.
+pub fn layout_paragraph(
+    text: &str,
+    marker: Option<&str>,
+    direction: TextDirection,
+    max_width: f64,
+    measure: impl Fn(&str) -> f64,
+) -> Vec<BidiLine> {
+    let chars: Vec<char> = text.trim().chars().collect();
+    let base = base_level(direction);
+    let levels = resolve_levels(&chars, base);
+    let width_of = |range: std::ops::Range<usize>| {
+        measure(&chars[range].iter().filter(|&&c| !is_isolate_control(c)).collect::<String>())
+    };
+    let indent = marker.map_or(0.0, |marker| measure(&format!("{marker} ")));
+    let available = max_width - indent;
+
+    // Break greedily in logical order; a word wider than the line gets a
+    // line of its own
+    let mut lines: Vec<std::ops::Range<usize>> = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == ' ' {
+            i += 1;
+            continue;
+        }
+        let word_start = i;
+        while i < chars.len() && chars[i] != ' ' {
+            i += 1;
+        }
+        if end > start && width_of(start..i) > available {
+            lines.push(start..end);
+            start = word_start;
+        }
+        end = i;
+    }
+    if end > start {
+        lines.push(start..end);
+    }
+
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(number, range)| {
+            let text = visual_order(&chars[range.clone()], &levels[range], base);
+            match marker {
+                Some(marker) if number == 0 => {
+                    let marker = reorder_line(marker, direction);
+                    let text = if direction.is_rtl() { format!("{text} {marker}") } else { format!("{marker} {text}") };
+                    BidiLine { text, indent: 0.0 }
+                }
+                Some(_) => BidiLine { text, indent },
+                None => BidiLine { text, indent: 0.0 },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> f64 {
+        text.chars().count() as f64
+    }
+
+    #[test]
+    fn test_mixed_line_reordered() {
+        let rtl = TextDirection::RightToLeft;
+        // Hebrew words read right to left; the URL and the version number
+        // keep their order
+        assert_eq!(reorder_line("קרא את https://example.com/docs עכשיו.", rtl), ".וישכע https://example.com/docs תא ארק");
+        assert_eq!(reorder_line("גרסה 2.5 זמינה (חדש)", rtl), "(שדח) הנימז 2.5 הסרג");
+        // Isolated code stays left to right, even with a leading number
+        assert_eq!(reorder_line("הרץ \u{2066}3 -x make\u{2069} עכשיו", rtl), "וישכע 3 -x make ץרה");
+        // Arabic digits after Arabic letters stay in order too
+        assert_eq!(reorder_line("صفحة ١٢", rtl), "١٢ ةحفص");
+        // A Hebrew word in English text is reversed in place
+        assert_eq!(reorder_line("Open תפריט now", TextDirection::LeftToRight), "Open טירפת now");
+    }
+
+    #[test]
+    fn test_list_marker_on_the_right_of_rtl_lines() {
+        let rtl = TextDirection::RightToLeft;
+        assert_eq!(layout_paragraph("שלום עולם", Some("•"), rtl, 40.0, chars), [BidiLine {
+            text: "םלוע םולש •".to_string(),
+            indent: 0.0
+        }]);
+
+        // Numbered in Arabic-Indic digits and wrapped: continuation lines
+        // are indented past the marker
+        let marker = ListMarker::Number(12).text(ListDigits::ArabicIndic);
+        assert_eq!(marker, "١٢.");
+        let lines = layout_paragraph("אחת שתיים שלוש", Some(&marker), rtl, 13.0, chars);
+        assert_eq!(lines, [
+            BidiLine { text: "םייתש תחא .١٢".to_string(), indent: 0.0 },
+            BidiLine { text: "שולש".to_string(), indent: 4.0 },
+        ]);
+
+        let ltr = layout_paragraph("one two", Some("1."), TextDirection::LeftToRight, 40.0, chars);
+        assert_eq!(ltr[0].text, "1. one two");
+    }
+}
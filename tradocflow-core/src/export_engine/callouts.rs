@@ -0,0 +1,340 @@
+//! Callout boxes in exported markdown
+//!
+//! A callout is written as a blockquote opening with `[!TYPE]`, as on
+//! GitHub, or as a `:::type` ... `:::` fence:
+//!
+//! ```markdown
+//! > [!WARNING]
+//! > Disconnect the power first.
+//!
+//! :::tip
+//! Label the cables before removing them.
+//! :::
+//! ```
+//!
+//! Before rendering, the callout's content is taken out of the blockquote
+//! or fence and put between marker paragraphs, so lists, code and nested
+//! callouts inside it render like anywhere else. The markers then become
+//! the box around the content.
+
+use super::html_bundle::escape_html;
+use crate::i18n;
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+fn blockquote_regex() -> &'static Regex {
+    static BLOCKQUOTE: OnceLock<Regex> = OnceLock::new();
+    BLOCKQUOTE.get_or_init(|| {
+        Regex::new(r"^ {0,3}> ?\[!([A-Za-z][\w-]*)\][ \t]*(.*)$").expect("valid callout blockquote regex")
+    })
+}
+
+fn fence_regex() -> &'static Regex {
+    static FENCE: OnceLock<Regex> = OnceLock::new();
+    FENCE.get_or_init(|| Regex::new(r"^ {0,3}:::[ \t]*([A-Za-z][\w-]*)[ \t]*$").expect("valid callout fence regex"))
+}
+
+fn marker_regex() -> &'static Regex {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    MARKER.get_or_init(|| Regex::new(r"<p>\{\{callout-(\d+)\}\}</p>|<p>\{\{/callout\}\}</p>").expect("valid callout marker regex"))
+}
+
+/// The type of a callout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalloutKind {
+    Note,
+    Tip,
+    Warning,
+    Danger,
+    /// A type the exporter doesn't know, shown as a note
+    Unknown(String),
+}
+
+impl CalloutKind {
+    /// The kind written as `raw`, in any case
+    pub fn parse(raw: &str) -> Self {
+        match raw.to_lowercase().as_str() {
+            "note" => CalloutKind::Note,
+            "tip" => CalloutKind::Tip,
+            "warning" => CalloutKind::Warning,
+            "danger" => CalloutKind::Danger,
+            _ => CalloutKind::Unknown(raw.to_string()),
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            CalloutKind::Note | CalloutKind::Unknown(_) => "note",
+            CalloutKind::Tip => "tip",
+            CalloutKind::Warning => "warning",
+            CalloutKind::Danger => "danger",
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            CalloutKind::Note | CalloutKind::Unknown(_) => "\u{2139}",
+            CalloutKind::Tip => "\u{1F4A1}",
+            CalloutKind::Warning => "\u{26A0}",
+            CalloutKind::Danger => "\u{26D4}",
+        }
+    }
+
+    /// Title of the box, localized to `language`. Unknown kinds show the
+    /// type as written.
+    pub fn label(&self, language: &str) -> String {
+        let label = i18n::t_for(&format!("manuals.callouts.{}", self.key()), language);
+        match self {
+            CalloutKind::Unknown(raw) => format!("{label} ({raw})"),
+            _ => label,
+        }
+    }
+
+    /// Red, green and blue of the box's title and border
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            CalloutKind::Note | CalloutKind::Unknown(_) => (41, 128, 185),
+            CalloutKind::Tip => (39, 174, 96),
+            CalloutKind::Warning => (211, 84, 0),
+            CalloutKind::Danger => (192, 57, 43),
+        }
+    }
+}
+
+/// Markdown with its callouts replaced by marker paragraphs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalloutText {
+    pub text: String,
+    /// Callouts in the order they open, so nested ones follow the one
+    /// around them
+    pub callouts: Vec<CalloutKind>,
+}
+
+/// Part of a rendered document, for output that draws callout boxes itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalloutPart {
+    Text(String),
+    /// A top-level callout. Callouts nested in it are flattened into its
+    /// HTML, each with its title as a paragraph.
+    Callout { kind: CalloutKind, title: String, html: String },
+}
+
+/// Take the callouts out of `markdown`. Callout syntax in fenced code is
+/// left alone.
+pub fn extract_callouts(markdown: &str) -> CalloutText {
+    let lines: Vec<&str> = markdown.split_inclusive('\n').collect();
+    let mut callout_text = CalloutText { text: String::new(), callouts: Vec::new() };
+    callout_text.push_lines(&lines);
+    callout_text
+}
+
+/// Whether `line` opens or closes a fenced code block
+fn is_code_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+impl CalloutText {
+    fn push_lines(&mut self, lines: &[&str]) {
+        let mut in_code = false;
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            let content = line.trim_end_matches(['\n', '\r']);
+            if is_code_fence(line) {
+                in_code = !in_code;
+            }
+            if in_code {
+                self.text.push_str(line);
+                i += 1;
+                continue;
+            }
+
+            if let Some(captures) = blockquote_regex().captures(content) {
+                let mut body = Vec::new();
+                let first = captures[2].trim();
+                if !first.is_empty() {
+                    body.push(format!("{first}\n"));
+                }
+                i += 1;
+                while let Some(quoted) = lines.get(i).and_then(|line| line.trim_start().strip_prefix('>')) {
+                    body.push(quoted.strip_prefix(' ').unwrap_or(quoted).to_string());
+                    i += 1;
+                }
+                self.push_callout(&captures[1], &body.iter().map(String::as_str).collect::<Vec<_>>());
+                continue;
+            }
+
+            if let Some(captures) = fence_regex().captures(content) {
+                let (body, closed) = fenced_body(&lines[i + 1..]);
+                i += 1 + body.len() + usize::from(closed);
+                self.push_callout(&captures[1], body);
+                continue;
+            }
+
+            self.text.push_str(line);
+            i += 1;
+        }
+    }
+
+    fn push_callout(&mut self, raw_type: &str, body: &[&str]) {
+        let index = self.callouts.len();
+        self.callouts.push(CalloutKind::parse(raw_type));
+        self.text.push_str(&format!("\n{{{{callout-{index}}}}}\n\n"));
+        self.push_lines(body);
+        if !self.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+        self.text.push_str("\n{{/callout}}\n\n");
+    }
+
+    /// Turn the marker paragraphs of `html`, rendered from
+    /// [`CalloutText::text`], into boxes titled in `language`
+    pub fn to_html(&self, html: &str, language: &str) -> String {
+        marker_regex()
+            .replace_all(html, |captures: &Captures| {
+                let Some(index) = captures.get(1) else {
+                    return "</div>".to_string();
+                };
+                let Some(kind) = self.kind(index.as_str()) else {
+                    return captures[0].to_string();
+                };
+                let raw_type = match kind {
+                    CalloutKind::Unknown(raw) => format!(" data-type=\"{}\"", escape_html(raw)),
+                    _ => String::new(),
+                };
+                format!(
+                    "<div class=\"callout callout-{}\" role=\"note\"{raw_type}><p class=\"callout-title\"><span class=\"callout-icon\" aria-hidden=\"true\">{}</span> {}</p>",
+                    kind.key(),
+                    kind.icon(),
+                    escape_html(&kind.label(language))
+                )
+            })
+            .into_owned()
+    }
+
+    /// Split `html`, rendered from [`CalloutText::text`], into text and
+    /// top-level callouts
+    pub fn split_html(&self, html: &str, language: &str) -> Vec<CalloutPart> {
+        let mut parts = Vec::new();
+        let mut depth = 0;
+        let mut rest = 0;
+        let mut open: Option<(&CalloutKind, String)> = None;
+
+        for captures in marker_regex().captures_iter(html) {
+            let marker = captures.get(0).expect("whole match");
+            let start = captures.get(1).and_then(|index| self.kind(index.as_str()));
+            match (&mut open, start) {
+                (None, Some(kind)) => {
+                    if marker.start() > rest {
+                        parts.push(CalloutPart::Text(html[rest..marker.start()].to_string()));
+                    }
+                    open = Some((kind, String::new()));
+                    depth = 1;
+                }
+                (None, None) => {}
+                (Some((_, body)), Some(kind)) => {
+                    body.push_str(&html[rest..marker.start()]);
+                    body.push_str(&format!("<p>{}</p>", escape_html(&kind.label(language))));
+                    depth += 1;
+                }
+                (Some((_, body)), None) => {
+                    body.push_str(&html[rest..marker.start()]);
+                    depth -= 1;
+                    if depth == 0 {
+                        if let Some((kind, html)) = open.take() {
+                            parts.push(CalloutPart::Callout { kind: kind.clone(), title: kind.label(language), html });
+                        }
+                    }
+                }
+            }
+            rest = marker.end();
+        }
+
+        match open {
+            Some((kind, mut body)) => {
+                body.push_str(&html[rest..]);
+                parts.push(CalloutPart::Callout { kind: kind.clone(), title: kind.label(language), html: body });
+            }
+            None if rest < html.len() => parts.push(CalloutPart::Text(html[rest..].to_string())),
+            None => {}
+        }
+        parts
+    }
+
+    fn kind(&self, index: &str) -> Option<&CalloutKind> {
+        index.parse::<usize>().ok().and_then(|index| self.callouts.get(index))
+    }
+}
+
+/// The lines of a `:::` fence up to its closing line, and whether there was
+/// one. Fences of nested callouts are kept in the body.
+fn fenced_body<'a, 'b>(lines: &'b [&'a str]) -> (&'b [&'a str], bool) {
+    let mut depth = 0;
+    let mut in_code = false;
+    for (i, line) in lines.iter().enumerate() {
+        if is_code_fence(line) {
+            in_code = !in_code;
+        }
+        if in_code {
+            continue;
+        }
+        let content = line.trim_end_matches(['\n', '\r']);
+        if fence_regex().is_match(content) {
+            depth += 1;
+        } else if content.trim() == ":::" {
+            if depth == 0 {
+                return (&lines[..i], true);
+            }
+            depth -= 1;
+        }
+    }
+    (lines, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_engine::ExportEngine;
+
+    const WARNING: &str = "Open the housing.\n\n> [!WARNING]\n> Before you start:\n>\n> - disconnect the power\n> - wait for the bell to stop\n>\n> ```\n> > [!NOTE] stays in the code\n> ```\n\nLift the cover.\n";
+
+    #[test]
+    fn test_warning_callout_renders_nested_list() {
+        let html = ExportEngine::new().render_markdown(WARNING, &[], "de");
+
+        let open = html
+            .find("<div class=\"callout callout-warning\" role=\"note\"><p class=\"callout-title\"><span class=\"callout-icon\" aria-hidden=\"true\">\u{26A0}</span> Achtung</p>")
+            .expect("warning box");
+        let close = html.find("</div>").expect("end of the box");
+        let list = html.find("<li>disconnect the power</li>").expect("nested list");
+        assert!(open < list && list < close);
+        assert!(html[open..close].contains("&gt; [!NOTE] stays in the code"));
+        assert!(html[close..].contains("Lift the cover."));
+        assert!(!html.contains("<blockquote>"));
+        assert!(!html.contains("{{"));
+
+        // The fenced form renders the same box
+        let fenced = ExportEngine::new().render_markdown(":::warning\n- disconnect the power\n:::\n", &[], "en");
+        assert!(fenced.contains("</span> Warning</p>\n<ul>\n<li>disconnect the power</li>\n</ul>\n</div>"));
+
+        let callouts = extract_callouts(WARNING);
+        assert_eq!(callouts.callouts, [CalloutKind::Warning]);
+        let parts = callouts.split_html(&comrak::markdown_to_html(&callouts.text, &ExportEngine::new().comrak_options), "de");
+        assert!(matches!(&parts[1], CalloutPart::Callout { kind: CalloutKind::Warning, title, html }
+            if title == "Achtung" && html.contains("<li>wait for the bell to stop</li>")));
+    }
+
+    #[test]
+    fn test_unknown_callout_falls_back_to_note() {
+        let markdown = ":::Important\nKeep this manual with the controller.\n\n:::tip\nStore it dry.\n:::\n:::\n";
+        let callouts = extract_callouts(markdown);
+        assert_eq!(callouts.callouts, [CalloutKind::Unknown("Important".to_string()), CalloutKind::Tip]);
+
+        let html = ExportEngine::new().render_markdown(markdown, &[], "en");
+        assert!(html.contains("<div class=\"callout callout-note\" role=\"note\" data-type=\"Important\"><p class=\"callout-title\"><span class=\"callout-icon\" aria-hidden=\"true\">\u{2139}</span> Note (Important)</p>"));
+        assert!(html.contains("<div class=\"callout callout-tip\""));
+        assert_eq!(html.matches("</div>").count(), 2);
+        assert!(html.trim_end().ends_with("</div>\n</div>"));
+    }
+}
@@ -0,0 +1,431 @@
+use super::html_bundle::escape_html;
+use crate::i18n;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CaptionKind {
+    Figure,
+    Table,
+}
+
+impl CaptionKind {
+    fn key(&self) -> &'static str {
+        match self {
+            CaptionKind::Figure => "figure",
+            CaptionKind::Table => "table",
+        }
+    }
+}
+
+/// Paragraph put before each chapter of a manual rendered as one document,
+/// so its captions can still be numbered per chapter
+pub const CHAPTER_MARK: &str = "{{chapter}}";
+
+/// How figures and tables are numbered across a manual
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CaptionNumbering {
+    /// 1, 2, 3 through the whole manual
+    #[default]
+    Continuous,
+    /// 1.1, 1.2, 2.1, starting over in each chapter
+    PerChapter,
+}
+
+/// Lists of figures and tables placed ahead of the first section of
+/// manual exports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptionListConfig {
+    pub numbering: CaptionNumbering,
+    pub list_figures: bool,
+    pub list_tables: bool,
+}
+
+impl Default for CaptionListConfig {
+    fn default() -> Self {
+        Self { numbering: CaptionNumbering::Continuous, list_figures: true, list_tables: true }
+    }
+}
+
+/// A numbered figure or table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Caption {
+    pub kind: CaptionKind,
+    /// Such as `3` or `2.1`
+    pub number: String,
+    /// Caption text, escaped for HTML
+    pub text: String,
+    /// Id of the figure or table element
+    pub anchor: String,
+}
+
+/// One configured list of figures or tables
+pub struct CaptionList<'a> {
+    pub kind: CaptionKind,
+    /// Localized title, such as "List of Figures"
+    pub title: String,
+    /// Localized label of the entries, such as "Figure"
+    pub label: String,
+    pub captions: Vec<&'a Caption>,
+}
+
+/// Numbers the figures and tables of a manual's rendered sections in
+/// reading order.
+///
+/// An image with a title is a figure, captioned by its title. A table is
+/// captioned by a `Table: ...` paragraph right before or after it.
+/// Uncaptioned images and tables are not numbered.
+pub struct CaptionNumberer {
+    numbering: CaptionNumbering,
+    language: String,
+    chapter: usize,
+    figures: usize,
+    tables: usize,
+    captions: Vec<Caption>,
+}
+
+fn caption_regex() -> &'static Regex {
+    static CAPTION: OnceLock<Regex> = OnceLock::new();
+    CAPTION.get_or_init(|| {
+        Regex::new(r#"<p>(?P<img><img [^>]*title="(?P<title>[^"]*)"[^>]*/>)</p>|<p>Table: (?P<table>[^<]*)</p>\s*<table>"#)
+            .expect("valid caption regex")
+    })
+}
+
+fn trailing_table_caption_regex() -> &'static Regex {
+    static TRAILING: OnceLock<Regex> = OnceLock::new();
+    TRAILING.get_or_init(|| Regex::new(r"</table>\s*<p>Table: ([^<]*)</p>").expect("valid table caption regex"))
+}
+
+fn chapter_mark_regex() -> &'static Regex {
+    static CHAPTER: OnceLock<Regex> = OnceLock::new();
+    CHAPTER.get_or_init(|| Regex::new(r"<p>\{\{chapter\}\}</p>\n?").expect("valid chapter mark regex"))
+}
+
+fn numbered_caption_regex() -> &'static Regex {
+    static NUMBERED: OnceLock<Regex> = OnceLock::new();
+    NUMBERED.get_or_init(|| {
+        Regex::new(r#"(?s)<figure id="([^"]+)">(.*?)<figcaption>(.*?)</figcaption></figure>|<table id="([^"]+)"><caption>(.*?)</caption>"#)
+            .expect("valid numbered caption regex")
+    })
+}
+
+fn caption_token_regex() -> &'static Regex {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    TOKEN.get_or_init(|| Regex::new(r"^\{\{caption:([^}]+)\}\}").expect("valid caption token regex"))
+}
+
+/// Numbered captions of HTML as paragraphs of their own, each starting with
+/// a `{{caption:<anchor>}}` token, for output that lays captions out
+/// itself, such as PDF
+pub fn caption_paragraphs(html: &str) -> String {
+    numbered_caption_regex()
+        .replace_all(html, |captures: &Captures| match captures.get(1) {
+            Some(anchor) => format!("<p>{}</p>\n<p>{{{{caption:{}}}}}{}</p>\n", &captures[2], anchor.as_str(), &captures[3]),
+            None => format!("<p>{{{{caption:{}}}}}{}</p>\n<table>", &captures[4], &captures[5]),
+        })
+        .into_owned()
+}
+
+/// The anchor of a caption paragraph made by [`caption_paragraphs`], and
+/// its text without the token
+pub fn split_caption_token(text: &str) -> Option<(&str, &str)> {
+    let token = caption_token_regex().captures(text)?;
+    let (whole, anchor) = (token.get(0)?, token.get(1)?);
+    Some((anchor.as_str(), &text[whole.end()..]))
+}
+
+impl CaptionNumberer {
+    /// Numberer for a manual exported in `language`, which the caption
+    /// labels are localized to
+    pub fn new(numbering: CaptionNumbering, language: &str) -> Self {
+        Self { numbering, language: language.to_string(), chapter: 0, figures: 0, tables: 0, captions: Vec::new() }
+    }
+
+    /// Move on to the next chapter, where per-chapter numbering starts over
+    pub fn start_chapter(&mut self) {
+        self.chapter += 1;
+        if self.numbering == CaptionNumbering::PerChapter {
+            self.figures = 0;
+            self.tables = 0;
+        }
+    }
+
+    /// Captions numbered so far, in reading order
+    pub fn captions(&self) -> &[Caption] {
+        &self.captions
+    }
+
+    fn label(&self, kind: CaptionKind) -> String {
+        i18n::t_for(&format!("manuals.captions.{}", kind.key()), &self.language)
+    }
+
+    /// The next caption of `kind`
+    fn push(&mut self, kind: CaptionKind, text: &str) -> &Caption {
+        let count = match kind {
+            CaptionKind::Figure => &mut self.figures,
+            CaptionKind::Table => &mut self.tables,
+        };
+        *count += 1;
+        let number = match self.numbering {
+            CaptionNumbering::Continuous => count.to_string(),
+            CaptionNumbering::PerChapter => format!("{}.{count}", self.chapter.max(1)),
+        };
+        let anchor = format!("{}-{}", kind.key(), number.replace('.', "-"));
+        self.captions.push(Caption { kind, number, text: text.to_string(), anchor });
+        self.captions.last().expect("caption just pushed")
+    }
+
+    /// Number the captioned figures and tables of a section's HTML, giving
+    /// each an id and a visible numbered caption
+    pub fn number(&mut self, html: &str) -> String {
+        // Move captions that follow their table in front of it
+        let mut html = html.to_string();
+        while let Some(captures) = trailing_table_caption_regex().captures(&html) {
+            let whole = captures.get(0).expect("whole match");
+            let caption = format!("<p>Table: {}</p>\n", &captures[1]);
+            let table_start = html[..whole.start()].rfind("<table>").unwrap_or(whole.start());
+            html.replace_range(whole.start()..whole.end(), "</table>");
+            html.insert_str(table_start, &caption);
+        }
+
+        caption_regex()
+            .replace_all(&html, |captures: &Captures| {
+                if let Some(image) = captures.name("img") {
+                    let label = self.label(CaptionKind::Figure);
+                    let caption = self.push(CaptionKind::Figure, &captures["title"]);
+                    format!(
+                        "<figure id=\"{}\">{}<figcaption>{label} {}: {}</figcaption></figure>",
+                        caption.anchor,
+                        image.as_str(),
+                        caption.number,
+                        caption.text
+                    )
+                } else {
+                    let label = self.label(CaptionKind::Table);
+                    let caption = self.push(CaptionKind::Table, captures["table"].trim());
+                    format!(
+                        "<table id=\"{}\"><caption>{label} {}: {}</caption>",
+                        caption.anchor, caption.number, caption.text
+                    )
+                }
+            })
+            .into_owned()
+    }
+
+    /// Number the HTML of a whole manual rendered as one document, starting
+    /// a chapter at each [`CHAPTER_MARK`] paragraph and removing the marks
+    pub fn number_manual(&mut self, html: &str) -> String {
+        let mut numbered = String::with_capacity(html.len());
+        let mut last = 0;
+        for mark in chapter_mark_regex().find_iter(html) {
+            numbered.push_str(&self.number(&html[last..mark.start()]));
+            self.start_chapter();
+            last = mark.end();
+        }
+        numbered.push_str(&self.number(&html[last..]));
+        numbered
+    }
+
+    /// The configured lists that have entries, figures first
+    pub fn lists(&self, config: &CaptionListConfig) -> Vec<CaptionList<'_>> {
+        [(CaptionKind::Figure, config.list_figures), (CaptionKind::Table, config.list_tables)]
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(kind, _)| CaptionList {
+                kind,
+                title: i18n::t_for(&format!("manuals.captions.{}", list_key(kind)), &self.language),
+                label: self.label(kind),
+                captions: self.captions.iter().filter(|caption| caption.kind == kind).collect(),
+            })
+            .filter(|list| !list.captions.is_empty())
+            .collect()
+    }
+
+    /// HTML for the configured lists, linking each entry to its figure or
+    /// table. Empty lists are left out.
+    pub fn render_lists(&self, config: &CaptionListConfig) -> String {
+        self.render_lists_linked(config, |caption| format!("#{}", caption.anchor))
+    }
+
+    /// HTML for the configured lists, linking each entry to `href` of its
+    /// caption, for output where the figures are in other files
+    pub fn render_lists_linked(&self, config: &CaptionListConfig, href: impl Fn(&Caption) -> String) -> String {
+        let mut html = String::new();
+        for list in self.lists(config) {
+            html.push_str(&format!(
+                "<section class=\"caption-list\" id=\"{}\">\n<h1>{}</h1>\n<ul>\n",
+                list_key(list.kind).replace('_', "-"),
+                escape_html(&list.title)
+            ));
+            for caption in list.captions {
+                html.push_str(&format!(
+                    "<li><a href=\"{}\">{} {}: {}</a></li>\n",
+                    href(caption),
+                    list.label,
+                    caption.number,
+                    caption.text
+                ));
+            }
+            html.push_str("</ul>\n</section>\n");
+        }
+        html
+    }
+}
+
+fn list_key(kind: CaptionKind) -> &'static str {
+    match kind {
+        CaptionKind::Figure => "list_of_figures",
+        CaptionKind::Table => "list_of_tables",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_engine::ExportEngine;
+    use crate::{Document, DocumentMetadata, Manual, ManualSection, ManualTemplate, SectionType};
+    use chrono::Utc;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    const WIRING: &str = "Connect the cables.\n\n![wiring](wiring.png \"Controller wiring\")\n\n| Pin | Signal |\n|-----|--------|\n| 1   | Bell   |\n\nTable: Pin assignment\n";
+    const MOUNTING: &str = "![photo](bracket.png)\n\n![bracket](bracket.png \"Wall bracket\")\n";
+
+    fn chapter(title: &str, order: u32, document_id: Uuid) -> ManualSection {
+        ManualSection {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            order,
+            document_id: Some(document_id),
+            subsections: Vec::new(),
+            section_type: SectionType::Installation,
+            required: true,
+            localized_titles: HashMap::new(),
+        }
+    }
+
+    fn document(content: &str) -> Document {
+        Document {
+            title: "Chapter".to_string(),
+            content: HashMap::from([("en".to_string(), content.to_string())]),
+            metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+        }
+    }
+
+    /// A manual with a wiring and a mounting chapter, and their documents
+    fn controller_manual() -> (Manual, HashMap<Uuid, Document>) {
+        let (wiring_id, mounting_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let documents = HashMap::from([(wiring_id, document(WIRING)), (mounting_id, document(MOUNTING))]);
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Controller Manual".to_string(),
+            description: String::new(),
+            sections: vec![chapter("Wiring", 1, wiring_id), chapter("Mounting", 2, mounting_id)],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "1.0".to_string(),
+            languages: vec!["en".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        };
+        (manual, documents)
+    }
+
+    #[test]
+    fn test_figures_and_tables_are_numbered_in_reading_order() {
+        let engine = ExportEngine::new();
+        let mut numberer = CaptionNumberer::new(CaptionNumbering::Continuous, "en");
+        numberer.start_chapter();
+        let wiring = numberer.number(&engine.render_markdown(WIRING, &[], "en"));
+        numberer.start_chapter();
+        let mounting = numberer.number(&engine.render_markdown(MOUNTING, &[], "en"));
+
+        let numbered: Vec<(CaptionKind, &str, &str)> =
+            numberer.captions().iter().map(|c| (c.kind, c.number.as_str(), c.text.as_str())).collect();
+        assert_eq!(numbered, [
+            (CaptionKind::Figure, "1", "Controller wiring"),
+            (CaptionKind::Table, "1", "Pin assignment"),
+            (CaptionKind::Figure, "2", "Wall bracket"),
+        ]);
+        assert!(wiring.contains("<figcaption>Figure 1: Controller wiring</figcaption></figure>"));
+        assert!(wiring.contains("<table id=\"table-1\"><caption>Table 1: Pin assignment</caption>"));
+        assert!(!wiring.contains("Table: Pin assignment"));
+        // The image without a title is not a figure
+        assert_eq!(mounting.matches("<figure").count(), 1);
+
+        let lists = numberer.render_lists(&CaptionListConfig::default());
+        assert!(lists.contains("<h1>List of Figures</h1>"));
+        assert!(lists.contains("<li><a href=\"#figure-2\">Figure 2: Wall bracket</a></li>"));
+        assert!(lists.contains("<li><a href=\"#table-1\">Table 1: Pin assignment</a></li>"));
+        assert!(lists.find("List of Figures").unwrap() < lists.find("List of Tables").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_html_bundle_lists_figures_numbered_per_chapter() {
+        let (manual, documents) = controller_manual();
+        let config = CaptionListConfig { numbering: CaptionNumbering::PerChapter, ..CaptionListConfig::default() };
+
+        let html = ExportEngine::new()
+            .with_caption_lists(config)
+            .export_html_bundle(&manual, &documents, "en")
+            .await
+            .unwrap();
+
+        assert!(html.contains("<a href=\"#figure-1-1\">Figure 1.1: Controller wiring</a>"));
+        assert!(html.contains("<a href=\"#figure-2-1\">Figure 2.1: Wall bracket</a>"));
+        assert!(html.contains("<a href=\"#table-1-1\">Table 1.1: Pin assignment</a>"));
+        assert!(html.contains("<figure id=\"figure-2-1\">"));
+        // The lists come ahead of the first chapter
+        assert!(html.find("List of Tables").unwrap() < html.find("Connect the cables.").unwrap());
+    }
+
+    #[test]
+    fn test_manual_numbered_per_chapter_mark() {
+        let engine = ExportEngine::new();
+        let markdown = format!("{CHAPTER_MARK}\n\n{WIRING}\n{CHAPTER_MARK}\n\n{MOUNTING}");
+        let mut numberer = CaptionNumberer::new(CaptionNumbering::PerChapter, "en");
+
+        let html = numberer.number_manual(&engine.render_markdown(&markdown, &[], "en"));
+        let anchors: Vec<&str> = numberer.captions().iter().map(|c| c.anchor.as_str()).collect();
+        assert_eq!(anchors, ["figure-1-1", "table-1-1", "figure-2-1"]);
+        assert!(!html.contains(CHAPTER_MARK));
+
+        // Output that lays captions out itself gets them as paragraphs
+        let paragraphs = caption_paragraphs(&html);
+        assert!(!paragraphs.contains("<figure"));
+        assert!(paragraphs.contains("<p>{{caption:table-1-1}}Table 1.1: Pin assignment</p>\n<table>"));
+        assert_eq!(
+            split_caption_token("{{caption:figure-2-1}}Figure 2.1: Wall bracket"),
+            Some(("figure-2-1", "Figure 2.1: Wall bracket"))
+        );
+        assert_eq!(split_caption_token("Figure 2.1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_epub_lists_link_to_chapter_files() {
+        let (manual, documents) = controller_manual();
+        let config = CaptionListConfig { numbering: CaptionNumbering::PerChapter, ..CaptionListConfig::default() };
+
+        let bytes = ExportEngine::new()
+            .with_caption_lists(config)
+            .export_epub(&manual, &documents, "en")
+            .await
+            .unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut read = |name: &str| {
+            let mut text = String::new();
+            std::io::Read::read_to_string(&mut archive.by_name(name).unwrap(), &mut text).unwrap();
+            text
+        };
+
+        let lists = read("OEBPS/captions.xhtml");
+        assert!(lists.contains("<a href=\"section-1.xhtml#figure-1-1\">Figure 1.1: Controller wiring</a>"));
+        assert!(lists.contains("<a href=\"section-2.xhtml#figure-2-1\">Figure 2.1: Wall bracket</a>"));
+        assert!(lists.contains("<a href=\"section-1.xhtml#table-1-1\">Table 1.1: Pin assignment</a>"));
+        assert!(read("OEBPS/section-2.xhtml").contains("<figure id=\"figure-2-1\">"));
+        // The lists open the spine, ahead of the first chapter
+        let opf = read("OEBPS/content.opf");
+        assert!(opf.find("idref=\"captions\"").unwrap() < opf.find("idref=\"section-1\"").unwrap());
+    }
+}
@@ -0,0 +1,706 @@
+//! Diagram code blocks in exports
+//!
+//! Code blocks in a diagram language, such as ```` ```mermaid ````, are
+//! drawn instead of shown as code. A [`DiagramRenderer`] turns the source
+//! into SVG, which HTML exports embed with the source kept alongside it.
+//! PDF exports draw the diagram when the renderer also lays it out as
+//! boxes and lines, since PDF pages can't embed SVG; other diagrams stay
+//! code there. A diagram that fails to render stays a code block, with a
+//! note saying why.
+//!
+//! The built-in [`MermaidRenderer`] draws Mermaid flowcharts. Renderers
+//! for other languages or diagram types can be added with
+//! [`ExportEngine::with_diagram_renderer`].
+
+use super::html_bundle::escape_html;
+use super::highlight::unescape_html;
+use super::ExportEngine;
+use crate::{i18n, Result, TradocumentError};
+use genpdf::style::{Color, LineStyle, Style};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+const FONT_SIZE: f64 = 13.0;
+const CHAR_WIDTH: f64 = 7.5;
+const NODE_HEIGHT: f64 = 40.0;
+const NODE_GAP: f64 = 30.0;
+const RANK_GAP: f64 = 50.0;
+const MARGIN: f64 = 10.0;
+const ARROW_LENGTH: f64 = 8.0;
+const MM_PER_PX: f64 = 25.4 / 96.0;
+const MM_PER_POINT: f64 = 25.4 / 72.0;
+
+fn code_block_regex() -> &'static Regex {
+    static CODE_BLOCK: OnceLock<Regex> = OnceLock::new();
+    CODE_BLOCK.get_or_init(|| {
+        Regex::new(r#"(?s)<pre><code class="language-([^"]+)">(.*?)</code></pre>"#).expect("valid code block regex")
+    })
+}
+
+/// Draws the code blocks of one or more diagram languages
+pub trait DiagramRenderer: Send + Sync {
+    /// Whether code blocks labelled `language`, such as `mermaid`, are
+    /// diagrams this renderer draws
+    fn supports(&self, language: &str) -> bool;
+
+    /// Draw a diagram. Fails on source the renderer can't read.
+    fn render(&self, language: &str, source: &str) -> Result<Diagram>;
+}
+
+/// A rendered diagram
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagram {
+    pub svg: String,
+    /// Boxes and lines PDF exports draw the diagram with
+    pub layout: Option<DiagramLayout>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeShape {
+    Rectangle,
+    Rounded,
+    Diamond,
+    Circle,
+}
+
+/// A box of a laid out diagram, in pixels from its top left corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaidOutNode {
+    pub label: String,
+    pub shape: NodeShape,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl LaidOutNode {
+    fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Where the line from the centre towards `target` leaves the box
+    fn border_towards(&self, target: (f64, f64)) -> (f64, f64) {
+        let (cx, cy) = self.center();
+        let (dx, dy) = (target.0 - cx, target.1 - cy);
+        let (half_width, half_height) = (self.width / 2.0, self.height / 2.0);
+        let t = match self.shape {
+            // |x|/a + |y|/b = 1 for a diamond, x²/a² + y²/b² = 1 for an ellipse
+            NodeShape::Diamond => 1.0 / (dx.abs() / half_width + dy.abs() / half_height),
+            NodeShape::Circle => 1.0 / ((dx / half_width).powi(2) + (dy / half_height).powi(2)).sqrt(),
+            NodeShape::Rectangle | NodeShape::Rounded => (half_width / dx.abs()).min(half_height / dy.abs()),
+        };
+        (cx + dx * t, cy + dy * t)
+    }
+
+    /// Corners of the box outline, closing where it starts
+    fn outline(&self) -> Vec<(f64, f64)> {
+        let (cx, cy) = self.center();
+        let (right, bottom) = (self.x + self.width, self.y + self.height);
+        match self.shape {
+            NodeShape::Rectangle | NodeShape::Rounded => {
+                vec![(self.x, self.y), (right, self.y), (right, bottom), (self.x, bottom), (self.x, self.y)]
+            }
+            NodeShape::Diamond => vec![(cx, self.y), (right, cy), (cx, bottom), (self.x, cy), (cx, self.y)],
+            NodeShape::Circle => (0..=24)
+                .map(|i| {
+                    let angle = f64::from(i) * std::f64::consts::TAU / 24.0;
+                    (cx + self.width / 2.0 * angle.cos(), cy + self.height / 2.0 * angle.sin())
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A line between two boxes of a laid out diagram
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaidOutEdge {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub arrow: bool,
+    pub dashed: bool,
+    pub label: Option<String>,
+}
+
+impl LaidOutEdge {
+    /// Tip and back corners of the arrow head
+    fn arrow_head(&self) -> [(f64, f64); 3] {
+        let (dx, dy) = (self.to.0 - self.from.0, self.to.1 - self.from.1);
+        let length = dx.hypot(dy).max(f64::EPSILON);
+        let (ux, uy) = (dx / length, dy / length);
+        let back = (self.to.0 - ux * ARROW_LENGTH, self.to.1 - uy * ARROW_LENGTH);
+        let half = ARROW_LENGTH / 2.0;
+        [self.to, (back.0 - uy * half, back.1 + ux * half), (back.0 + uy * half, back.1 - ux * half)]
+    }
+
+    fn midpoint(&self) -> (f64, f64) {
+        ((self.from.0 + self.to.0) / 2.0, (self.from.1 + self.to.1) / 2.0)
+    }
+}
+
+/// A diagram as boxes and lines, in pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagramLayout {
+    pub width: f64,
+    pub height: f64,
+    pub nodes: Vec<LaidOutNode>,
+    pub edges: Vec<LaidOutEdge>,
+}
+
+impl DiagramLayout {
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" class=\"diagram-svg\" role=\"img\" viewBox=\"0 0 {w:.1} {h:.1}\" width=\"{w:.1}\" height=\"{h:.1}\" font-family=\"sans-serif\" font-size=\"{FONT_SIZE}\">",
+            w = self.width,
+            h = self.height
+        );
+        for node in &self.nodes {
+            let style = "fill=\"#eef6fb\" stroke=\"#2980b9\" stroke-width=\"1.5\"";
+            let (cx, cy) = node.center();
+            svg.push_str(&match node.shape {
+                NodeShape::Rectangle | NodeShape::Rounded => format!(
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"{}\" {style}/>",
+                    node.x,
+                    node.y,
+                    node.width,
+                    node.height,
+                    if node.shape == NodeShape::Rounded { 10 } else { 0 }
+                ),
+                NodeShape::Diamond => format!("<polygon points=\"{}\" {style}/>", svg_points(&node.outline()[..4])),
+                NodeShape::Circle => format!(
+                    "<ellipse cx=\"{cx:.1}\" cy=\"{cy:.1}\" rx=\"{:.1}\" ry=\"{:.1}\" {style}/>",
+                    node.width / 2.0,
+                    node.height / 2.0
+                ),
+            });
+            svg.push_str(&svg_text(cx, cy, &node.label));
+        }
+        for edge in &self.edges {
+            svg.push_str(&format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#555\" stroke-width=\"1.5\"{}/>",
+                edge.from.0,
+                edge.from.1,
+                edge.to.0,
+                edge.to.1,
+                if edge.dashed { " stroke-dasharray=\"5,4\"" } else { "" }
+            ));
+            if edge.arrow {
+                svg.push_str(&format!("<polygon points=\"{}\" fill=\"#555\"/>", svg_points(&edge.arrow_head())));
+            }
+            if let Some(label) = &edge.label {
+                let (x, y) = edge.midpoint();
+                let width = text_width(label) + 8.0;
+                svg.push_str(&format!(
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{width:.1}\" height=\"{:.1}\" fill=\"#fff\"/>",
+                    x - width / 2.0,
+                    y - FONT_SIZE * 0.75,
+                    FONT_SIZE * 1.5
+                ));
+                svg.push_str(&svg_text(x, y, label));
+            }
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+fn svg_points(points: &[(f64, f64)]) -> String {
+    points.iter().map(|(x, y)| format!("{x:.1},{y:.1}")).collect::<Vec<_>>().join(" ")
+}
+
+fn svg_text(x: f64, y: f64, text: &str) -> String {
+    format!(
+        "<text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+        escape_html(text)
+    )
+}
+
+/// Estimated width of `text` in the diagram font
+fn text_width(text: &str) -> f64 {
+    text.chars().count() as f64 * CHAR_WIDTH
+}
+
+/// Renders Mermaid flowcharts (`graph` and `flowchart`): nodes with
+/// rectangle, rounded, diamond and circle shapes, and solid, dotted and
+/// thick links with optional labels. Styling statements and subgraph
+/// boundaries are ignored. Other Mermaid diagram types fail to render.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MermaidRenderer;
+
+impl DiagramRenderer for MermaidRenderer {
+    fn supports(&self, language: &str) -> bool {
+        language == "mermaid"
+    }
+
+    fn render(&self, _language: &str, source: &str) -> Result<Diagram> {
+        let layout = Flowchart::parse(source)?.layout();
+        Ok(Diagram { svg: layout.to_svg(), layout: Some(layout) })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    TopDown,
+    BottomUp,
+    LeftRight,
+    RightLeft,
+}
+
+#[derive(Debug)]
+struct FlowNode {
+    label: String,
+    shape: NodeShape,
+}
+
+#[derive(Debug)]
+struct FlowEdge {
+    from: usize,
+    to: usize,
+    arrow: bool,
+    dashed: bool,
+    label: Option<String>,
+}
+
+#[derive(Debug)]
+struct Flowchart {
+    direction: Direction,
+    nodes: Vec<FlowNode>,
+    edges: Vec<FlowEdge>,
+}
+
+const OTHER_MERMAID_DIAGRAMS: &[&str] = &[
+    "sequenceDiagram", "classDiagram", "stateDiagram", "stateDiagram-v2", "erDiagram", "gantt", "pie", "journey",
+    "gitGraph", "mindmap", "timeline", "quadrantChart", "requirementDiagram",
+];
+
+const IGNORED_STATEMENTS: &[&str] = &["classDef ", "class ", "style ", "linkStyle ", "click ", "subgraph", "direction "];
+
+/// Link syntax and whether it is (arrow, dashed)
+const LINKS: &[(&str, bool, bool)] = &[
+    ("-.->", true, true),
+    ("-.-", false, true),
+    ("==>", true, false),
+    ("===", false, false),
+    ("-->", true, false),
+    ("---", false, false),
+];
+
+fn diagram_error(line: usize, message: impl std::fmt::Display) -> TradocumentError {
+    TradocumentError::Validation(format!("Line {line}: {message}"))
+}
+
+impl Flowchart {
+    fn parse(source: &str) -> Result<Self> {
+        let mut statements = source
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split("%%").next().unwrap_or_default()))
+            .flat_map(|(line, text)| text.split(';').map(move |statement| (line, statement.trim())))
+            .filter(|(_, statement)| !statement.is_empty());
+
+        let Some((line, header)) = statements.next() else {
+            return Err(TradocumentError::Validation("The diagram is empty".to_string()));
+        };
+        let mut words = header.split_whitespace();
+        let kind = words.next().unwrap_or_default();
+        if kind != "graph" && kind != "flowchart" {
+            if OTHER_MERMAID_DIAGRAMS.contains(&kind) {
+                return Err(diagram_error(line, format!("{kind} diagrams are not supported")));
+            }
+            return Err(diagram_error(line, format!("unknown diagram type '{kind}'")));
+        }
+        let direction = match words.next() {
+            None | Some("TD" | "TB") => Direction::TopDown,
+            Some("BT") => Direction::BottomUp,
+            Some("LR") => Direction::LeftRight,
+            Some("RL") => Direction::RightLeft,
+            Some(other) => return Err(diagram_error(line, format!("unknown direction '{other}'"))),
+        };
+
+        let mut chart = Flowchart { direction, nodes: Vec::new(), edges: Vec::new() };
+        let mut ids = HashMap::new();
+        for (line, statement) in statements {
+            if statement == "end" || IGNORED_STATEMENTS.iter().any(|keyword| statement.starts_with(keyword)) {
+                continue;
+            }
+            chart.parse_statement(statement, &mut ids).map_err(|message| diagram_error(line, message))?;
+        }
+        Ok(chart)
+    }
+
+    /// A node followed by any number of links to further nodes
+    fn parse_statement(&mut self, statement: &str, ids: &mut HashMap<String, usize>) -> std::result::Result<(), String> {
+        let mut rest = statement;
+        let mut previous = self.parse_node(&mut rest, ids)?;
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                return Ok(());
+            }
+            let Some(&(syntax, arrow, dashed)) = LINKS.iter().find(|(syntax, ..)| rest.starts_with(syntax)) else {
+                return Err(format!("expected a link at '{rest}'"));
+            };
+            rest = rest[syntax.len()..].trim_start();
+            let mut label = None;
+            if let Some(after) = rest.strip_prefix('|') {
+                let end = after.find('|').ok_or("unclosed link label")?;
+                label = Some(after[..end].trim().to_string());
+                rest = &after[end + 1..];
+            }
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                return Err("expected a node after the link".to_string());
+            }
+            let next = self.parse_node(&mut rest, ids)?;
+            self.edges.push(FlowEdge { from: previous, to: next, arrow, dashed, label });
+            previous = next;
+        }
+    }
+
+    fn parse_node(&mut self, rest: &mut &str, ids: &mut HashMap<String, usize>) -> std::result::Result<usize, String> {
+        let text = rest.trim_start();
+        let id_length = text.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(text.len());
+        if id_length == 0 {
+            return Err(format!("expected a node at '{text}'"));
+        }
+        let id = &text[..id_length];
+        let mut after = &text[id_length..];
+
+        let shapes = [
+            ("((", "))", NodeShape::Circle),
+            ("([", "])", NodeShape::Rounded),
+            ("[", "]", NodeShape::Rectangle),
+            ("(", ")", NodeShape::Rounded),
+            ("{", "}", NodeShape::Diamond),
+        ];
+        let mut shape = None;
+        for (open, close, node_shape) in shapes {
+            if let Some(inner) = after.strip_prefix(open) {
+                let end = inner.find(close).ok_or_else(|| format!("unclosed '{open}' after node {id}"))?;
+                let label = inner[..end].trim().trim_matches('"').to_string();
+                shape = Some((label, node_shape));
+                after = &inner[end + close.len()..];
+                break;
+            }
+        }
+        *rest = after;
+
+        let index = *ids.entry(id.to_string()).or_insert_with(|| {
+            self.nodes.push(FlowNode { label: id.to_string(), shape: NodeShape::Rectangle });
+            self.nodes.len() - 1
+        });
+        if let Some((label, shape)) = shape {
+            self.nodes[index] = FlowNode { label, shape };
+        }
+        Ok(index)
+    }
+
+    /// Place the nodes in ranks following the links, each rank a row, or a
+    /// column for left to right charts
+    fn layout(&self) -> DiagramLayout {
+        let count = self.nodes.len();
+        let mut ranks = vec![0usize; count];
+        // Longest path from a start node; ranks stop growing at the node
+        // count, so cycles end
+        for _ in 0..count {
+            let mut changed = false;
+            for edge in self.edges.iter().filter(|edge| edge.from != edge.to) {
+                if ranks[edge.to] <= ranks[edge.from] && ranks[edge.from] + 1 < count {
+                    ranks[edge.to] = ranks[edge.from] + 1;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let sizes: Vec<(f64, f64)> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let text = text_width(&node.label);
+                match node.shape {
+                    NodeShape::Diamond => (text + 50.0, NODE_HEIGHT + 16.0),
+                    NodeShape::Circle => {
+                        let diameter = (text + 20.0).max(NODE_HEIGHT + 10.0);
+                        (diameter, diameter)
+                    }
+                    NodeShape::Rectangle | NodeShape::Rounded => ((text + 24.0).max(60.0), NODE_HEIGHT),
+                }
+            })
+            .collect();
+
+        let rank_count = ranks.iter().max().map_or(0, |max| max + 1);
+        let mut by_rank: Vec<Vec<usize>> = vec![Vec::new(); rank_count];
+        for (node, rank) in ranks.iter().enumerate() {
+            by_rank[*rank].push(node);
+        }
+        if matches!(self.direction, Direction::BottomUp | Direction::RightLeft) {
+            by_rank.reverse();
+        }
+
+        let vertical = matches!(self.direction, Direction::TopDown | Direction::BottomUp);
+        // Along a rank, and across ranks
+        let along = |node: usize| if vertical { sizes[node].0 } else { sizes[node].1 };
+        let across = |node: usize| if vertical { sizes[node].1 } else { sizes[node].0 };
+        let rank_length = |rank: &Vec<usize>| {
+            rank.iter().map(|&node| along(node)).sum::<f64>() + NODE_GAP * rank.len().saturating_sub(1) as f64
+        };
+        let longest = by_rank.iter().map(rank_length).fold(0.0, f64::max);
+
+        let mut positions = vec![(0.0, 0.0); count];
+        let mut offset = MARGIN;
+        for rank in &by_rank {
+            let depth = rank.iter().map(|&node| across(node)).fold(0.0, f64::max);
+            let mut position = MARGIN + (longest - rank_length(rank)) / 2.0;
+            for &node in rank {
+                let centered = offset + (depth - across(node)) / 2.0;
+                positions[node] = if vertical { (position, centered) } else { (centered, position) };
+                position += along(node) + NODE_GAP;
+            }
+            offset += depth + RANK_GAP;
+        }
+        let depth_total = offset - RANK_GAP + MARGIN;
+        let (width, height) = if vertical {
+            (longest + 2.0 * MARGIN, depth_total)
+        } else {
+            (depth_total, longest + 2.0 * MARGIN)
+        };
+
+        let nodes: Vec<LaidOutNode> = self
+            .nodes
+            .iter()
+            .zip(positions.iter().zip(&sizes))
+            .map(|(node, (&(x, y), &(width, height)))| LaidOutNode {
+                label: node.label.clone(),
+                shape: node.shape,
+                x,
+                y,
+                width,
+                height,
+            })
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .filter(|edge| edge.from != edge.to)
+            .map(|edge| {
+                let (from, to) = (&nodes[edge.from], &nodes[edge.to]);
+                LaidOutEdge {
+                    from: from.border_towards(to.center()),
+                    to: to.border_towards(from.center()),
+                    arrow: edge.arrow,
+                    dashed: edge.dashed,
+                    label: edge.label.clone(),
+                }
+            })
+            .collect();
+
+        DiagramLayout { width: width.max(1.0), height: height.max(1.0), nodes, edges }
+    }
+}
+
+/// The first of `renderers` that supports `language`
+fn renderer_for<'a>(renderers: &'a [Arc<dyn DiagramRenderer>], language: &str) -> Option<&'a dyn DiagramRenderer> {
+    renderers.iter().find(|renderer| renderer.supports(language)).map(|renderer| renderer.as_ref())
+}
+
+/// Replace the diagram code blocks of rendered HTML with their SVG, keeping
+/// the source in a collapsed code block. Blocks that fail to render stay
+/// code, after a note labelled in `language`.
+pub(super) fn render_diagrams(html: &str, renderers: &[Arc<dyn DiagramRenderer>], language: &str) -> String {
+    code_block_regex()
+        .replace_all(html, |captures: &Captures| {
+            let diagram_language = &captures[1];
+            let Some(renderer) = renderer_for(renderers, diagram_language) else {
+                return captures[0].to_string();
+            };
+            match renderer.render(diagram_language, &unescape_html(&captures[2])) {
+                Ok(diagram) => format!(
+                    "<figure class=\"diagram\" data-language=\"{}\">{}<details class=\"diagram-source\"><summary>{}</summary>{}</details></figure>",
+                    escape_html(diagram_language),
+                    diagram.svg,
+                    escape_html(&i18n::t_for("manuals.diagrams.source", language)),
+                    &captures[0]
+                ),
+                Err(e) => {
+                    log::warn!("Diagram could not be rendered: {e}");
+                    format!("{}\n{}", error_note(&e, language), &captures[0])
+                }
+            }
+        })
+        .into_owned()
+}
+
+fn error_note(error: &TradocumentError, language: &str) -> String {
+    format!(
+        "<div class=\"diagram-error\" role=\"alert\">{}: {}</div>",
+        escape_html(&i18n::t_for("manuals.diagrams.invalid", language)),
+        escape_html(&error.to_string())
+    )
+}
+
+/// How a diagram code block goes into a PDF
+pub(super) enum PdfDiagram {
+    Drawing(DiagramElement),
+    /// Show the block as code, after this note
+    Code(Option<String>),
+}
+
+/// Draw a code block labelled `label` in a PDF whose text column is
+/// `max_width_mm` by `max_height_mm`, if it is a diagram that renders to a
+/// layout
+pub(super) fn pdf_diagram(
+    renderers: &[Arc<dyn DiagramRenderer>],
+    label: &str,
+    source: &str,
+    max_width_mm: f64,
+    max_height_mm: f64,
+    language: &str,
+) -> PdfDiagram {
+    let Some(renderer) = renderer_for(renderers, label) else {
+        return PdfDiagram::Code(None);
+    };
+    match renderer.render(label, source) {
+        Ok(Diagram { layout: Some(layout), .. }) => {
+            PdfDiagram::Drawing(DiagramElement::new(layout, max_width_mm, max_height_mm))
+        }
+        Ok(_) => PdfDiagram::Code(None),
+        Err(e) => {
+            log::warn!("Diagram could not be rendered: {e}");
+            let label = i18n::t_for("manuals.diagrams.invalid", language);
+            PdfDiagram::Code(Some(format!("{label}: {e}")))
+        }
+    }
+}
+
+/// Draws a laid out diagram, scaled to fit the text column
+pub(super) struct DiagramElement {
+    layout: DiagramLayout,
+    /// Millimetres per layout pixel
+    scale: f64,
+    /// Left offset that centres the diagram
+    offset_mm: f64,
+}
+
+impl DiagramElement {
+    fn new(layout: DiagramLayout, max_width_mm: f64, max_height_mm: f64) -> Self {
+        let scale = MM_PER_PX.min(max_width_mm / layout.width).min(max_height_mm * 0.8 / layout.height);
+        let offset_mm = ((max_width_mm - layout.width * scale) / 2.0).max(0.0);
+        Self { layout, scale, offset_mm }
+    }
+
+    /// Height the diagram takes up on the page
+    pub(super) fn height_mm(&self) -> f64 {
+        self.layout.height * self.scale
+    }
+
+    fn position(&self, (x, y): (f64, f64)) -> genpdf::Position {
+        genpdf::Position::new(self.offset_mm + x * self.scale, y * self.scale)
+    }
+
+    fn print_centered(
+        &self,
+        context: &genpdf::Context,
+        area: &genpdf::render::Area<'_>,
+        (x, y): (f64, f64),
+        text: &str,
+        style: Style,
+    ) -> std::result::Result<(), genpdf::error::Error> {
+        let top_left = (x - text_width(text) / 2.0, y - FONT_SIZE / 2.0);
+        area.print_str(&context.font_cache, self.position(top_left), style, text)?;
+        Ok(())
+    }
+}
+
+impl genpdf::Element for DiagramElement {
+    fn render(
+        &mut self,
+        context: &genpdf::Context,
+        area: genpdf::render::Area<'_>,
+        style: Style,
+    ) -> std::result::Result<genpdf::RenderResult, genpdf::error::Error> {
+        let size = genpdf::Size::new(self.offset_mm * 2.0 + self.layout.width * self.scale, self.layout.height * self.scale);
+        if area.size().height < size.height {
+            return Ok(genpdf::RenderResult { size: genpdf::Size::new(0.0, 0.0), has_more: true });
+        }
+
+        let font_size = (FONT_SIZE * self.scale / MM_PER_POINT).clamp(4.0, FONT_SIZE) as u8;
+        let text_style = style.with_font_size(font_size);
+        let box_line = LineStyle::new().with_thickness(0.3).with_color(Color::Rgb(41, 128, 185));
+        let link_line = LineStyle::new().with_thickness(0.3).with_color(Color::Rgb(85, 85, 85));
+
+        for node in &self.layout.nodes {
+            area.draw_line(node.outline().into_iter().map(|point| self.position(point)), box_line);
+            self.print_centered(context, &area, node.center(), &node.label, text_style)?;
+        }
+        for edge in &self.layout.edges {
+            area.draw_line([self.position(edge.from), self.position(edge.to)], link_line);
+            if edge.arrow {
+                let [tip, left, right] = edge.arrow_head();
+                area.draw_line([left, tip, right, left].map(|point| self.position(point)), link_line);
+            }
+            if let Some(label) = &edge.label {
+                self.print_centered(context, &area, edge.midpoint(), label, text_style)?;
+            }
+        }
+        Ok(genpdf::RenderResult { size, has_more: false })
+    }
+}
+
+impl ExportEngine {
+    /// Draw code blocks the renderer supports, ahead of the renderers
+    /// added before it and the built-in [`MermaidRenderer`]
+    pub fn with_diagram_renderer(mut self, renderer: impl DiagramRenderer + 'static) -> Self {
+        self.diagram_renderers.insert(0, Arc::new(renderer));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHART: &str = "```mermaid\ngraph TD\n  A[Power on] --> B{Bell rings?}\n  B -->|No| C(Check the fuse)\n  B -.-> D((Done))\n```\n";
+
+    #[test]
+    fn test_mermaid_block_renders_as_svg() {
+        let html = ExportEngine::new().render_markdown(CHART, &[], "en");
+
+        assert!(html.contains("<figure class=\"diagram\" data-language=\"mermaid\"><svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(html.contains(">Power on</text>"));
+        assert!(html.contains(">Bell rings?</text>"));
+        assert!(html.contains(">No</text>"));
+        // The source stays with the drawing
+        assert!(html.contains("<details class=\"diagram-source\"><summary>Diagram source</summary><pre><code class=\"language-mermaid\">graph TD\n  A[Power on] --&gt; B{Bell rings?}"));
+        assert!(!html.contains("diagram-error"));
+
+        let layout = MermaidRenderer.render("mermaid", &CHART[11..CHART.len() - 4]).unwrap().layout.unwrap();
+        let labels: Vec<(&str, NodeShape)> = layout.nodes.iter().map(|n| (n.label.as_str(), n.shape)).collect();
+        assert_eq!(labels, [
+            ("Power on", NodeShape::Rectangle),
+            ("Bell rings?", NodeShape::Diamond),
+            ("Check the fuse", NodeShape::Rounded),
+            ("Done", NodeShape::Circle),
+        ]);
+        // Each link leads down a rank
+        assert!(layout.nodes[0].y < layout.nodes[1].y && layout.nodes[1].y < layout.nodes[2].y);
+        assert_eq!(layout.nodes[2].center().1, layout.nodes[3].center().1);
+        assert!(layout.edges[2].dashed);
+    }
+
+    #[test]
+    fn test_invalid_mermaid_block_falls_back_to_code() {
+        let markdown = "```mermaid\ngraph TD\n  A[Power on --> B\n```\n\n```mermaid\nsequenceDiagram\n  A->>B: ring\n```\n";
+        let html = ExportEngine::new().render_markdown(markdown, &[], "en");
+
+        assert!(!html.contains("<svg"));
+        assert!(html.contains("<div class=\"diagram-error\" role=\"alert\">Diagram could not be rendered: Validation error: Line 2: unclosed '[' after node A</div>\n<pre><code class=\"language-mermaid\">graph TD\n  A[Power on --&gt; B\n</code></pre>"));
+        assert!(html.contains("Line 1: sequenceDiagram diagrams are not supported"));
+
+        let error = MermaidRenderer.render("mermaid", "graph TD\n  A -->\n").unwrap_err();
+        assert!(error.to_string().contains("Line 2: expected a node after the link"));
+    }
+}
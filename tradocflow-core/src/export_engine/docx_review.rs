@@ -0,0 +1,759 @@
+use super::epub::check_well_formed;
+use super::html_bundle::escape_html;
+use super::markdown::split_front_matter;
+use super::ExportEngine;
+use crate::review_system::{ChangeRequest, ChangeStatus, ChangeType, Comment, CommentPosition, ReviewSystem};
+use crate::{Document, Result, TradocumentError};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::io::{Cursor, Write};
+use std::ops::Range;
+use uuid::Uuid;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const W_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+
+const CONTENT_TYPES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="xml" ContentType="application/xml"/>
+  <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+  <Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
+  <Override PartName="/word/comments.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.comments+xml"/>
+</Types>
+"#;
+
+const PACKAGE_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+</Relationships>
+"#;
+
+const DOCUMENT_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/comments" Target="comments.xml"/>
+</Relationships>
+"#;
+
+/// Font of inline code and code blocks
+const CODE_FONT: &str = "Consolas";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct RunStyle {
+    bold: bool,
+    italic: bool,
+    code: bool,
+}
+
+/// One character of the converted text
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Glyph {
+    pub(super) ch: char,
+    /// Byte offset in the Markdown of the character it came from
+    pub(super) source: usize,
+    style: RunStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockKind {
+    Paragraph,
+    Heading(usize),
+    ListItem,
+    Code,
+}
+
+impl BlockKind {
+    fn style(self) -> Option<String> {
+        match self {
+            BlockKind::Paragraph => None,
+            BlockKind::Heading(level) => Some(format!("Heading{level}")),
+            BlockKind::ListItem => Some("ListParagraph".to_string()),
+            BlockKind::Code => Some("Code".to_string()),
+        }
+    }
+}
+
+/// A Word paragraph, made of a range of the glyphs
+#[derive(Debug, Clone)]
+pub(super) struct Block {
+    kind: BlockKind,
+    pub(super) glyphs: Range<usize>,
+}
+
+/// Markdown converted to Word paragraphs, keeping for every character where
+/// in the Markdown it came from so review positions can be mapped onto the
+/// converted text. Markup such as `**` and link targets produce no glyphs.
+#[derive(Debug, Default)]
+pub(super) struct ConvertedText {
+    pub(super) glyphs: Vec<Glyph>,
+    pub(super) blocks: Vec<Block>,
+}
+
+impl ConvertedText {
+    pub(super) fn from_markdown(markdown: &str) -> Self {
+        let mut converted = Self::default();
+        let (_, body) = split_front_matter(markdown);
+        let mut offset = markdown.len() - body.len();
+        // Paragraph or list item that the next plain line continues
+        let mut open: Option<(BlockKind, usize)> = None;
+        let mut fence: Option<&str> = None;
+
+        for line in body.split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            let text = line.trim_end_matches(['\n', '\r']);
+            let trimmed = text.trim_start();
+            let indent = start + text.len() - trimmed.len();
+
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                } else {
+                    let first = converted.glyphs.len();
+                    converted.push_plain(text, start, RunStyle { code: true, ..Default::default() });
+                    converted.push_block(BlockKind::Code, first);
+                }
+                continue;
+            }
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                converted.close(&mut open);
+                fence = Some(&trimmed[..3]);
+                continue;
+            }
+            if trimmed.is_empty() || is_thematic_break(trimmed) {
+                converted.close(&mut open);
+                continue;
+            }
+
+            if let Some((level, heading)) = heading(trimmed) {
+                converted.close(&mut open);
+                let first = converted.glyphs.len();
+                let heading_start = indent + trimmed.len() - heading.len();
+                converted.push_inline(heading.trim_end_matches([' ', '#']), heading_start);
+                converted.push_block(BlockKind::Heading(level), first);
+            } else if let Some(marker_len) = list_marker(trimmed) {
+                converted.close(&mut open);
+                let first = converted.glyphs.len();
+                let marker = &trimmed[..marker_len];
+                if matches!(marker, "-" | "*" | "+") {
+                    converted.push_glyph('•', indent, RunStyle::default());
+                } else {
+                    converted.push_plain(marker, indent, RunStyle::default());
+                }
+                converted.push_glyph(' ', indent + marker_len, RunStyle::default());
+                let item = trimmed[marker_len..].trim_start();
+                converted.push_inline(item, indent + trimmed.len() - item.len());
+                open = Some((BlockKind::ListItem, first));
+            } else {
+                let (content, content_start) = match trimmed.strip_prefix('>') {
+                    Some(quoted) => {
+                        let quoted = quoted.trim_start();
+                        (quoted, indent + trimmed.len() - quoted.len())
+                    }
+                    None => (trimmed, indent),
+                };
+                match open {
+                    // A soft line break becomes a space, standing for the newline
+                    Some(_) => converted.push_glyph(' ', start.saturating_sub(1), RunStyle::default()),
+                    None => open = Some((BlockKind::Paragraph, converted.glyphs.len())),
+                }
+                converted.push_inline(content, content_start);
+            }
+        }
+        converted.close(&mut open);
+
+        if converted.blocks.is_empty() {
+            converted.blocks.push(Block { kind: BlockKind::Paragraph, glyphs: 0..0 });
+        }
+        converted
+    }
+
+    fn close(&mut self, open: &mut Option<(BlockKind, usize)>) {
+        if let Some((kind, first)) = open.take() {
+            self.push_block(kind, first);
+        }
+    }
+
+    fn push_block(&mut self, kind: BlockKind, first: usize) {
+        self.blocks.push(Block { kind, glyphs: first..self.glyphs.len() });
+    }
+
+    fn push_glyph(&mut self, ch: char, source: usize, style: RunStyle) {
+        // Characters XML can't carry are dropped
+        if !ch.is_control() || ch == '\t' {
+            self.glyphs.push(Glyph { ch, source, style });
+        }
+    }
+
+    fn push_plain(&mut self, text: &str, offset: usize, style: RunStyle) {
+        for (i, ch) in text.char_indices() {
+            self.push_glyph(ch, offset + i, style);
+        }
+    }
+
+    /// Push inline Markdown: emphasis, code spans, links and escapes are
+    /// turned into styled text
+    fn push_inline(&mut self, text: &str, offset: usize) {
+        let mut style = RunStyle::default();
+        // Byte index of the `]` of the current link and where its target ends
+        let mut link: Option<(usize, usize)> = None;
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((i, ch)) = chars.next() {
+            let previous = text[..i].chars().next_back();
+            let next = chars.peek().map(|&(_, c)| c);
+
+            if let Some((close, end)) = link {
+                if i == close {
+                    while chars.next_if(|&(j, _)| j < end).is_some() {}
+                    link = None;
+                    continue;
+                }
+            }
+            match ch {
+                '`' => style.code = !style.code,
+                _ if style.code => self.push_glyph(ch, offset + i, style),
+                '\\' if next.is_some_and(|c| c.is_ascii_punctuation()) => {
+                    let (j, escaped) = chars.next().expect("peeked character");
+                    self.push_glyph(escaped, offset + j, style);
+                }
+                '*' | '_' if next == Some(ch) => {
+                    chars.next();
+                    style.bold = !style.bold;
+                }
+                '*' | '_' if is_emphasis_marker(ch, previous, next) => style.italic = !style.italic,
+                // The alt text of an image stands in for it
+                '!' if next == Some('[') && link_target(text, i + 1).is_some() => {}
+                '[' if link.is_none() => match link_target(text, i) {
+                    Some(target) => link = Some(target),
+                    None => self.push_glyph(ch, offset + i, style),
+                },
+                _ => self.push_glyph(ch, offset + i, style),
+            }
+        }
+    }
+
+    /// Index of the first glyph at or after byte `offset` of the Markdown
+    pub(super) fn glyph_at(&self, offset: usize) -> usize {
+        self.glyphs.partition_point(|glyph| glyph.source < offset)
+    }
+
+    /// Glyphs that came from the Markdown in `range`
+    pub(super) fn glyphs_in(&self, range: &Range<usize>) -> Range<usize> {
+        let start = self.glyph_at(range.start);
+        start..self.glyph_at(range.end).max(start)
+    }
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' '))).then(|| (level, rest.trim_start()))
+}
+
+/// Length of the marker opening a list item, such as `-` or `12.`
+fn list_marker(line: &str) -> Option<usize> {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let marker_len = match line.as_bytes().first()? {
+        b'-' | b'*' | b'+' => 1,
+        _ if digits > 0 && matches!(line.as_bytes().get(digits), Some(b'.' | b')')) => digits + 1,
+        _ => return None,
+    };
+    matches!(line.as_bytes().get(marker_len), Some(b' ' | b'\t')).then_some(marker_len)
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    let mut marks = line.chars().filter(|c| !c.is_whitespace());
+    let first = marks.next();
+    matches!(first, Some('-' | '*' | '_')) && marks.clone().count() >= 2 && marks.all(|c| Some(c) == first)
+}
+
+/// Whether a single `*` or `_` opens or closes emphasis rather than being
+/// literal, as in `2 * 3` or `snake_case`
+fn is_emphasis_marker(marker: char, previous: Option<char>, next: Option<char>) -> bool {
+    let spaced = |c: Option<char>| c.is_none_or(char::is_whitespace);
+    let word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+    match marker {
+        '_' => !(word(previous) && word(next)),
+        _ => !(spaced(previous) && spaced(next)),
+    }
+}
+
+/// For a link whose `[` is at byte `open`, the byte index of its `]` and
+/// the end of its `(target)`
+fn link_target(text: &str, open: usize) -> Option<(usize, usize)> {
+    let rest = text.get(open + 1..)?;
+    let close = rest.find("](")?;
+    let target = &rest[close + 2..];
+    let end = target.find(')')?;
+    let close = open + 1 + close;
+    Some((close, close + 2 + end + 1))
+}
+
+/// Byte range of the Markdown that a review position covers.
+///
+/// Lines count from 1 and columns are character offsets counting from 0,
+/// with the end column exclusive. A position with both columns 0 covers its
+/// lines whole. Line 0 refers to the document as a whole and is anchored at
+/// its start.
+pub(super) fn source_range(markdown: &str, position: &CommentPosition) -> Range<usize> {
+    if position.line_start == 0 {
+        return 0..0;
+    }
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in markdown.split_inclusive('\n') {
+        lines.push(offset..offset + line.trim_end_matches(['\n', '\r']).len());
+        offset += line.len();
+    }
+    let line = |number: u32| lines.get(number as usize - 1).cloned().unwrap_or(markdown.len()..markdown.len());
+    let column = |range: Range<usize>, column: u32| {
+        let text = &markdown[range.clone()];
+        range.start + text.char_indices().nth(column as usize).map_or(text.len(), |(i, _)| i)
+    };
+
+    let first = line(position.line_start);
+    let last = line(position.line_end.max(position.line_start));
+    if position.column_start == 0 && position.column_end == 0 {
+        return first.start..last.end;
+    }
+    let start = column(first, position.column_start);
+    start..column(last, position.column_end).max(start)
+}
+
+/// What is written into the text between two glyphs
+#[derive(Debug, Clone, Copy)]
+enum Mark<'a> {
+    CommentStart(usize),
+    CommentEnd(usize),
+    Insertion(&'a ChangeRequest),
+}
+
+/// The review laid over the converted text
+struct Annotations<'a> {
+    /// Marks written just before each glyph; the last entry is for the end
+    /// of the text
+    before: Vec<Vec<Mark<'a>>>,
+    /// Marks written just after the glyph before each index
+    after: Vec<Vec<Mark<'a>>>,
+    /// The change deleting each glyph, if any
+    deleted: Vec<Option<&'a ChangeRequest>>,
+}
+
+impl<'a> Annotations<'a> {
+    fn new(markdown: &str, text: &ConvertedText, comments: &[&Comment], changes: &[&'a ChangeRequest]) -> Self {
+        let count = text.glyphs.len();
+        let mut annotations = Self {
+            before: vec![Vec::new(); count + 1],
+            after: vec![Vec::new(); count + 1],
+            deleted: vec![None; count],
+        };
+
+        for (id, comment) in comments.iter().enumerate() {
+            let glyphs = text.glyphs_in(&source_range(markdown, &comment.position));
+            annotations.before[glyphs.start].push(Mark::CommentStart(id));
+            if glyphs.is_empty() {
+                annotations.before[glyphs.start].push(Mark::CommentEnd(id));
+            } else {
+                annotations.after[glyphs.end].push(Mark::CommentEnd(id));
+            }
+        }
+
+        for &change in changes {
+            let range = source_range(markdown, &change.position);
+            if matches!(change.change_type, ChangeType::Insert) {
+                annotations.before[text.glyph_at(range.start)].push(Mark::Insertion(change));
+                continue;
+            }
+            let glyphs = text.glyphs_in(&range);
+            for deleted in &mut annotations.deleted[glyphs.clone()] {
+                deleted.get_or_insert(change);
+            }
+            if !matches!(change.change_type, ChangeType::Delete) {
+                // The replacement follows the text it replaces
+                match glyphs.is_empty() {
+                    true => annotations.before[glyphs.start].push(Mark::Insertion(change)),
+                    false => annotations.after[glyphs.end].push(Mark::Insertion(change)),
+                }
+            }
+        }
+        annotations
+    }
+}
+
+/// Writes the body of `word/document.xml`, numbering tracked changes after
+/// the comments so every annotation id is unique
+struct BodyWriter<'a> {
+    xml: String,
+    run: String,
+    run_style: RunStyle,
+    run_deletion: Option<&'a ChangeRequest>,
+    next_revision: usize,
+}
+
+impl<'a> BodyWriter<'a> {
+    fn write(text: &ConvertedText, annotations: &Annotations<'a>, first_revision: usize) -> String {
+        let mut writer = Self {
+            xml: String::new(),
+            run: String::new(),
+            run_style: RunStyle::default(),
+            run_deletion: None,
+            next_revision: first_revision,
+        };
+
+        for (index, block) in text.blocks.iter().enumerate() {
+            writer.xml.push_str("<w:p>");
+            if let Some(style) = block.kind.style() {
+                writer.xml.push_str(&format!("<w:pPr><w:pStyle w:val=\"{style}\"/></w:pPr>"));
+            }
+            for i in block.glyphs.clone() {
+                if i > block.glyphs.start {
+                    writer.marks(&annotations.after[i]);
+                }
+                writer.marks(&annotations.before[i]);
+                let glyph = text.glyphs[i];
+                let deletion = annotations.deleted[i];
+                if glyph.style != writer.run_style || !same_change(deletion, writer.run_deletion) {
+                    writer.flush();
+                    writer.run_style = glyph.style;
+                    writer.run_deletion = deletion;
+                }
+                writer.run.push(glyph.ch);
+            }
+            if !block.glyphs.is_empty() {
+                writer.marks(&annotations.after[block.glyphs.end]);
+            }
+            if index + 1 == text.blocks.len() {
+                writer.marks(&annotations.before[text.glyphs.len()]);
+            }
+            writer.flush();
+            writer.xml.push_str("</w:p>");
+        }
+        writer.xml
+    }
+
+    fn marks(&mut self, marks: &[Mark<'a>]) {
+        if marks.is_empty() {
+            return;
+        }
+        self.flush();
+        for mark in marks {
+            match mark {
+                Mark::CommentStart(id) => self.xml.push_str(&format!("<w:commentRangeStart w:id=\"{id}\"/>")),
+                Mark::CommentEnd(id) => self.xml.push_str(&format!(
+                    "<w:commentRangeEnd w:id=\"{id}\"/><w:r><w:commentReference w:id=\"{id}\"/></w:r>"
+                )),
+                Mark::Insertion(change) => {
+                    let attributes = self.revision(change);
+                    self.xml.push_str(&format!(
+                        "<w:ins {attributes}><w:r><w:t xml:space=\"preserve\">{}</w:t></w:r></w:ins>",
+                        escape_html(&change.new_content)
+                    ));
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.run.is_empty() {
+            return;
+        }
+        let text = escape_html(&std::mem::take(&mut self.run));
+        let properties = run_properties(self.run_style);
+        match self.run_deletion {
+            Some(change) => {
+                let attributes = self.revision(change);
+                self.xml.push_str(&format!(
+                    "<w:del {attributes}><w:r>{properties}<w:delText xml:space=\"preserve\">{text}</w:delText></w:r></w:del>"
+                ));
+            }
+            None => self.xml.push_str(&format!("<w:r>{properties}<w:t xml:space=\"preserve\">{text}</w:t></w:r>")),
+        }
+    }
+
+    fn revision(&mut self, change: &ChangeRequest) -> String {
+        let id = self.next_revision;
+        self.next_revision += 1;
+        format!(
+            "w:id=\"{id}\" w:author=\"{}\" w:date=\"{}\"",
+            escape_html(&change.author_id),
+            word_date(&change.created_at)
+        )
+    }
+}
+
+fn same_change(a: Option<&ChangeRequest>, b: Option<&ChangeRequest>) -> bool {
+    a.map(|change| change.id) == b.map(|change| change.id)
+}
+
+fn run_properties(style: RunStyle) -> String {
+    if style == RunStyle::default() {
+        return String::new();
+    }
+    let mut properties = String::from("<w:rPr>");
+    if style.code {
+        properties.push_str(&format!("<w:rFonts w:ascii=\"{CODE_FONT}\" w:hAnsi=\"{CODE_FONT}\"/>"));
+    }
+    if style.bold {
+        properties.push_str("<w:b/>");
+    }
+    if style.italic {
+        properties.push_str("<w:i/>");
+    }
+    properties.push_str("</w:rPr>");
+    properties
+}
+
+fn word_date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn comments_xml(comments: &[&Comment]) -> String {
+    let paragraph = |text: &str| format!("<w:p><w:r><w:t xml:space=\"preserve\">{}</w:t></w:r></w:p>", escape_html(text));
+    let mut xml = format!("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<w:comments xmlns:w=\"{W_NS}\">");
+    for (id, comment) in comments.iter().enumerate() {
+        xml.push_str(&format!(
+            "<w:comment w:id=\"{id}\" w:author=\"{}\" w:date=\"{}\">{}",
+            escape_html(&comment.author_id),
+            word_date(&comment.created_at),
+            paragraph(&comment.content)
+        ));
+        for reply in &comment.replies {
+            xml.push_str(&paragraph(&format!("{}: {}", reply.author_id, reply.content)));
+        }
+        xml.push_str("</w:comment>");
+    }
+    xml.push_str("</w:comments>\n");
+    xml
+}
+
+fn styles_xml() -> String {
+    let mut styles = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<w:styles xmlns:w=\"{W_NS}\">\
+         <w:style w:type=\"paragraph\" w:default=\"1\" w:styleId=\"Normal\"><w:name w:val=\"Normal\"/></w:style>"
+    );
+    for (level, size) in (1..=6).zip([32, 28, 26, 24, 22, 22]) {
+        styles.push_str(&format!(
+            "<w:style w:type=\"paragraph\" w:styleId=\"Heading{level}\"><w:name w:val=\"heading {level}\"/>\
+             <w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/><w:pPr><w:keepNext/><w:outlineLvl w:val=\"{}\"/></w:pPr>\
+             <w:rPr><w:b/><w:sz w:val=\"{size}\"/></w:rPr></w:style>",
+            level - 1
+        ));
+    }
+    styles.push_str(&format!(
+        "<w:style w:type=\"paragraph\" w:styleId=\"ListParagraph\"><w:name w:val=\"List Paragraph\"/>\
+         <w:basedOn w:val=\"Normal\"/><w:pPr><w:ind w:left=\"720\" w:hanging=\"360\"/></w:pPr></w:style>\
+         <w:style w:type=\"paragraph\" w:styleId=\"Code\"><w:name w:val=\"Code\"/><w:basedOn w:val=\"Normal\"/>\
+         <w:pPr><w:spacing w:after=\"0\"/></w:pPr><w:rPr><w:rFonts w:ascii=\"{CODE_FONT}\" w:hAnsi=\"{CODE_FONT}\"/></w:rPr></w:style>\
+         </w:styles>\n"
+    ));
+    styles
+}
+
+impl ExportEngine {
+    /// Export a document in `language` as a Word document carrying the
+    /// comments and change requests of a review, see
+    /// [`ExportEngine::render_review_docx`].
+    pub fn export_review_docx(
+        &self,
+        document: &Document,
+        language: &str,
+        review_system: &ReviewSystem,
+        review_id: Uuid,
+    ) -> Result<Vec<u8>> {
+        let comments = review_system.get_comments_for_review(review_id).map(Vec::as_slice).unwrap_or_default();
+        let changes = review_system.get_change_requests_for_review(review_id).map(Vec::as_slice).unwrap_or_default();
+        self.render_review_docx(document, language, comments, changes)
+    }
+
+    /// Render a document in `language` as a Word document with review
+    /// comments and tracked changes.
+    ///
+    /// Each unresolved comment in `language` becomes a Word comment, with its
+    /// replies, anchored at the text its position covers. Each proposed
+    /// change request in `language` becomes a tracked change: an insertion,
+    /// a deletion, or for replacements and formatting changes a deletion of
+    /// the covered text followed by an insertion of the new content.
+    /// Positions refer to the Markdown source; they are mapped through the
+    /// conversion, so markup such as `**` around a word doesn't shift them.
+    pub fn render_review_docx(
+        &self,
+        document: &Document,
+        language: &str,
+        comments: &[Comment],
+        changes: &[ChangeRequest],
+    ) -> Result<Vec<u8>> {
+        let markdown = document
+            .content
+            .get(language)
+            .ok_or_else(|| TradocumentError::UnsupportedLanguage(format!("{} has no {language} content", document.title)))?;
+        let comments: Vec<&Comment> =
+            comments.iter().filter(|c| !c.resolved && c.position.language == language).collect();
+        let changes: Vec<&ChangeRequest> = changes
+            .iter()
+            .filter(|c| matches!(c.status, ChangeStatus::Proposed) && c.position.language == language)
+            .collect();
+
+        let text = ConvertedText::from_markdown(markdown);
+        let annotations = Annotations::new(markdown, &text, &comments, &changes);
+        let body = BodyWriter::write(&text, &annotations, comments.len());
+        let document_xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <w:document xmlns:w=\"{W_NS}\"><w:body>{body}<w:sectPr/></w:body></w:document>\n"
+        );
+        let comments_xml = comments_xml(&comments);
+        check_well_formed("word/document.xml", &document_xml)?;
+        check_well_formed("word/comments.xml", &comments_xml)?;
+
+        let docx_error = |e: zip::result::ZipError| TradocumentError::FileError(format!("DOCX packaging failed: {e}"));
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        let parts = [
+            ("[Content_Types].xml", CONTENT_TYPES_XML.to_string()),
+            ("_rels/.rels", PACKAGE_RELS_XML.to_string()),
+            ("word/_rels/document.xml.rels", DOCUMENT_RELS_XML.to_string()),
+            ("word/document.xml", document_xml),
+            ("word/styles.xml", styles_xml()),
+            ("word/comments.xml", comments_xml),
+        ];
+        for (name, xml) in parts {
+            zip.start_file(name, deflated).map_err(docx_error)?;
+            zip.write_all(xml.as_bytes())?;
+        }
+        Ok(zip.finish().map_err(docx_error)?.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review_system::CommentReply;
+    use crate::DocumentMetadata;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    fn document(content: &str) -> Document {
+        Document {
+            title: "Setup".to_string(),
+            content: HashMap::from([("en".to_string(), content.to_string())]),
+            metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+        }
+    }
+
+    fn position(line: u32, columns: Range<u32>) -> CommentPosition {
+        CommentPosition { line_start: line, line_end: line, column_start: columns.start, column_end: columns.end, language: "en".to_string(), unit_id: None }
+    }
+
+    fn date() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap()
+    }
+
+    fn comment(content: &str, position: CommentPosition) -> Comment {
+        Comment {
+            id: Uuid::new_v4(),
+            author_id: "ben".to_string(),
+            content: content.to_string(),
+            position,
+            created_at: date(),
+            resolved: false,
+            replies: Vec::new(),
+        }
+    }
+
+    fn change(change_type: ChangeType, new_content: &str, position: CommentPosition) -> ChangeRequest {
+        ChangeRequest {
+            id: Uuid::new_v4(),
+            document_id: Uuid::new_v4(),
+            author_id: "anna".to_string(),
+            change_type,
+            old_content: String::new(),
+            new_content: new_content.to_string(),
+            position,
+            status: ChangeStatus::Proposed,
+            created_at: date(),
+        }
+    }
+
+    fn read_entry(docx: &[u8], name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(Cursor::new(docx)).unwrap();
+        let mut content = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_comment_and_suggested_change_become_word_comment_and_tracked_change() {
+        let markdown = "# Setup\n\nPress the **red** button.\n";
+        let mut remark = comment("Is it red on every model?", position(3, 12..15));
+        remark.replies.push(CommentReply { author_id: "anna".to_string(), content: "Yes".to_string(), created_at: date() });
+        let suggestion = change(ChangeType::Replace, "switch", position(3, 18..24));
+
+        let docx = ExportEngine::new().render_review_docx(&document(markdown), "en", &[remark], &[suggestion]).unwrap();
+
+        let body = read_entry(&docx, "word/document.xml");
+        assert!(body.contains("<w:pPr><w:pStyle w:val=\"Heading1\"/></w:pPr><w:r><w:t xml:space=\"preserve\">Setup</w:t></w:r>"));
+        // The comment covers the bold word, not the `**` around it
+        assert!(body.contains(
+            "<w:commentRangeStart w:id=\"0\"/><w:r><w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">red</w:t></w:r>\
+             <w:commentRangeEnd w:id=\"0\"/><w:r><w:commentReference w:id=\"0\"/></w:r>"
+        ));
+        let revision = "w:author=\"anna\" w:date=\"2026-03-02T09:30:00Z\"";
+        assert!(body.contains(&format!(
+            "<w:del w:id=\"1\" {revision}><w:r><w:delText xml:space=\"preserve\">button</w:delText></w:r></w:del>\
+             <w:ins w:id=\"2\" {revision}><w:r><w:t xml:space=\"preserve\">switch</w:t></w:r></w:ins>\
+             <w:r><w:t xml:space=\"preserve\">.</w:t></w:r>"
+        )));
+
+        let comments = read_entry(&docx, "word/comments.xml");
+        assert!(comments.contains("<w:comment w:id=\"0\" w:author=\"ben\" w:date=\"2026-03-02T09:30:00Z\">"));
+        assert!(comments.contains("Is it red on every model?"));
+        assert!(comments.contains("anna: Yes"));
+        assert!(read_entry(&docx, "word/_rels/document.xml.rels").contains("Target=\"comments.xml\""));
+    }
+
+    #[test]
+    fn test_positions_map_through_links_and_joined_lines() {
+        let markdown = "Open the [settings](https://example.com/settings)\nand *choose* a language.\n";
+        // Spans the end of the first line, past the link target, into the second
+        let across = CommentPosition { line_start: 1, line_end: 2, column_start: 9, column_end: 3, language: "en".to_string(), unit_id: None };
+        let remark = comment("Link the page instead", across);
+        let mut resolved = comment("Done", position(1, 0..4));
+        resolved.resolved = true;
+        let mut german = comment("Nicht hier", position(1, 0..4));
+        german.position.language = "de".to_string();
+
+        let deletion = change(ChangeType::Delete, "", position(2, 4..12));
+        let insertion = change(ChangeType::Insert, "first ", position(1, 0..0));
+        let mut rejected = change(ChangeType::Delete, "", position(1, 0..4));
+        rejected.status = ChangeStatus::Rejected;
+
+        let docx = ExportEngine::new()
+            .render_review_docx(&document(markdown), "en", &[resolved, remark, german], &[deletion, insertion, rejected])
+            .unwrap();
+
+        let body = read_entry(&docx, "word/document.xml");
+        assert!(!body.contains("example.com"));
+        assert!(body.contains(
+            "<w:commentRangeStart w:id=\"0\"/><w:r><w:t xml:space=\"preserve\">settings and</w:t></w:r>\
+             <w:commentRangeEnd w:id=\"0\"/>"
+        ));
+        // Tracked changes are numbered in the order they appear
+        let revision = "w:author=\"anna\" w:date=\"2026-03-02T09:30:00Z\"";
+        assert!(body.contains(&format!(
+            "<w:p><w:ins w:id=\"1\" {revision}><w:r><w:t xml:space=\"preserve\">first </w:t></w:r></w:ins>\
+             <w:r><w:t xml:space=\"preserve\">Open the </w:t></w:r>"
+        )));
+        assert!(body.contains(&format!(
+            "<w:del w:id=\"2\" {revision}><w:r><w:rPr><w:i/></w:rPr>\
+             <w:delText xml:space=\"preserve\">choose</w:delText></w:r></w:del>\
+             <w:r><w:t xml:space=\"preserve\"> a language.</w:t></w:r>"
+        )));
+        assert_eq!(body.matches("<w:del ").count(), 1);
+
+        let comments = read_entry(&docx, "word/comments.xml");
+        assert_eq!(comments.matches("<w:comment ").count(), 1);
+        assert!(comments.contains("Link the page instead"));
+    }
+}
@@ -0,0 +1,743 @@
+use super::docx_review::{source_range, ConvertedText, Glyph};
+use crate::review_system::{ChangeRequest, ChangeStatus, ChangeType, Comment, CommentPosition, CommentReply, ReviewSystem};
+use crate::{Document, Result, TradocumentError};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::ops::Range;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// What importing a reviewed Word document added to the review
+#[derive(Debug, Clone, Default)]
+pub struct DocxReviewImport {
+    /// New comments, with their replies
+    pub comments: Vec<Comment>,
+    /// New change requests from the tracked changes
+    pub change_requests: Vec<ChangeRequest>,
+    /// Number of replies added to comments the review already had
+    pub replies: usize,
+    /// Tracked changes and comments whose place in the document couldn't be found
+    pub unmatched: Vec<UnmatchedRevision>,
+}
+
+/// A tracked change or comment that couldn't be anchored in the Markdown,
+/// for example because its paragraph was edited without tracking or has
+/// changed since the export. It is left to be applied by hand.
+#[derive(Debug, Clone)]
+pub enum UnmatchedRevision {
+    Change {
+        change_type: ChangeType,
+        author_id: String,
+        /// Text the change deletes
+        old_content: String,
+        /// Text the change inserts
+        new_content: String,
+        /// Text of the Word paragraph the change was made in
+        paragraph: String,
+    },
+    Comment {
+        author_id: String,
+        content: String,
+        /// Text of the Word paragraph the comment starts in
+        paragraph: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RevisionKind {
+    Insertion,
+    Deletion,
+}
+
+/// A tracked insertion or deletion in a Word paragraph
+#[derive(Debug, Clone)]
+struct WordRevision {
+    kind: RevisionKind,
+    author: String,
+    date: Option<String>,
+    /// Character offset in the paragraph's original text where it applies
+    at: usize,
+    text: String,
+}
+
+impl WordRevision {
+    /// Offset after the text a deletion removes
+    fn end(&self) -> usize {
+        match self.kind {
+            RevisionKind::Insertion => self.at,
+            RevisionKind::Deletion => self.at + self.text.chars().count(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct WordParagraph {
+    /// The text before the tracked changes: unchanged and deleted text
+    original: Vec<char>,
+    revisions: Vec<WordRevision>,
+}
+
+impl WordParagraph {
+    fn text(&self) -> String {
+        self.original.iter().collect()
+    }
+
+    fn push_text(&mut self, text: &str, revision: Option<&(RevisionKind, String, Option<String>)>) {
+        let at = self.original.len();
+        let Some((kind, author, date)) = revision else {
+            self.original.extend(text.chars());
+            return;
+        };
+        if *kind == RevisionKind::Deletion {
+            self.original.extend(text.chars());
+        }
+        // Word splits a revision into one element per run
+        if let Some(last) = self.revisions.last_mut() {
+            if last.kind == *kind && &last.author == author && &last.date == date && last.end() == at {
+                last.text.push_str(text);
+                return;
+            }
+        }
+        self.revisions.push(WordRevision { kind: *kind, author: author.clone(), date: date.clone(), at, text: text.to_string() });
+    }
+}
+
+/// A place in the Word text: a paragraph and a character offset in its
+/// original text
+type WordPoint = (usize, usize);
+
+#[derive(Debug, Default)]
+struct WordBody {
+    paragraphs: Vec<WordParagraph>,
+    comment_starts: HashMap<String, WordPoint>,
+    comment_ends: HashMap<String, WordPoint>,
+}
+
+#[derive(Debug, Default)]
+struct WordComment {
+    id: String,
+    author: String,
+    date: Option<String>,
+    paragraphs: Vec<String>,
+    /// Identifier of its last paragraph, which threads refer to
+    para_id: Option<String>,
+}
+
+/// Where a comment sits in a thread, from `word/commentsExtended.xml`
+#[derive(Debug, Default)]
+struct CommentThread {
+    parent: Option<String>,
+    done: bool,
+}
+
+/// An XML tag or the text between two tags
+enum XmlToken<'a> {
+    Open { name: &'a str, attributes: &'a str, empty: bool },
+    Close(&'a str),
+    Text(&'a str),
+}
+
+fn xml_tag_regex() -> &'static Regex {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    TAG.get_or_init(|| {
+        Regex::new(r#"(?s)<\?.*?\?>|<!--.*?-->|<(/?)([\w:.-]+)((?:[^>"']|"[^"]*"|'[^']*')*?)(/?)>"#).expect("valid XML tag regex")
+    })
+}
+
+fn xml_attribute_regex() -> &'static Regex {
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    ATTRIBUTE.get_or_init(|| Regex::new(r#"([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid XML attribute regex"))
+}
+
+fn xml_entity_regex() -> &'static Regex {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+    ENTITY.get_or_init(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-z]+);").expect("valid XML entity regex"))
+}
+
+fn xml_tokens(xml: &str) -> Vec<XmlToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut last = 0;
+    for captures in xml_tag_regex().captures_iter(xml) {
+        let tag = captures.get(0).expect("whole match");
+        if tag.start() > last {
+            tokens.push(XmlToken::Text(&xml[last..tag.start()]));
+        }
+        last = tag.end();
+        let Some(name) = captures.get(2) else {
+            continue;
+        };
+        if &captures[1] == "/" {
+            tokens.push(XmlToken::Close(name.as_str()));
+        } else {
+            let attributes = captures.get(3).map_or("", |m| m.as_str());
+            tokens.push(XmlToken::Open { name: name.as_str(), attributes, empty: &captures[4] == "/" });
+        }
+    }
+    if last < xml.len() {
+        tokens.push(XmlToken::Text(&xml[last..]));
+    }
+    tokens
+}
+
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    xml_attribute_regex()
+        .captures_iter(attributes)
+        .find(|captures| &captures[1] == name)
+        .map(|captures| unescape_xml(captures.get(2).or(captures.get(3)).map_or("", |m| m.as_str())))
+}
+
+fn unescape_xml(text: &str) -> String {
+    xml_entity_regex()
+        .replace_all(text, |captures: &regex::Captures| {
+            let entity = &captures[1];
+            let code = match entity.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => entity.strip_prefix('#').and_then(|decimal| decimal.parse().ok()),
+            };
+            match (entity, code.and_then(char::from_u32)) {
+                (_, Some(ch)) => ch.to_string(),
+                ("amp", _) => "&".to_string(),
+                ("lt", _) => "<".to_string(),
+                ("gt", _) => ">".to_string(),
+                ("quot", _) => "\"".to_string(),
+                ("apos", _) => "'".to_string(),
+                _ => captures[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// Read the paragraphs, tracked changes and comment anchors of `word/document.xml`
+fn parse_body(xml: &str) -> WordBody {
+    let mut body = WordBody::default();
+    let mut revision: Option<(RevisionKind, String, Option<String>)> = None;
+    let mut in_run = false;
+    let mut in_text = false;
+
+    for token in xml_tokens(xml) {
+        let point = |body: &WordBody| {
+            let paragraph = body.paragraphs.len().saturating_sub(1);
+            (paragraph, body.paragraphs.last().map_or(0, |p| p.original.len()))
+        };
+        match token {
+            XmlToken::Open { name: "w:p", .. } => body.paragraphs.push(WordParagraph::default()),
+            XmlToken::Open { name: name @ ("w:ins" | "w:del" | "w:moveTo" | "w:moveFrom"), attributes, empty: false } => {
+                let kind = match name {
+                    "w:ins" | "w:moveTo" => RevisionKind::Insertion,
+                    _ => RevisionKind::Deletion,
+                };
+                let author = attribute(attributes, "w:author").unwrap_or_default();
+                revision = Some((kind, author, attribute(attributes, "w:date")));
+            }
+            XmlToken::Close("w:ins" | "w:del" | "w:moveTo" | "w:moveFrom") => revision = None,
+            XmlToken::Open { name: "w:r", empty: false, .. } => in_run = true,
+            XmlToken::Close("w:r") => in_run = false,
+            XmlToken::Open { name: "w:t" | "w:delText", empty: false, .. } => in_text = true,
+            XmlToken::Close("w:t" | "w:delText") => in_text = false,
+            // Tab stops in paragraph properties are also `w:tab`
+            XmlToken::Open { name: "w:tab", .. } if in_run => push_text(&mut body, "\t", revision.as_ref()),
+            XmlToken::Open { name: "w:commentRangeStart", attributes, .. } => {
+                if let Some(id) = attribute(attributes, "w:id") {
+                    let point = point(&body);
+                    body.comment_starts.insert(id, point);
+                }
+            }
+            XmlToken::Open { name: name @ ("w:commentRangeEnd" | "w:commentReference"), attributes, .. } => {
+                if let Some(id) = attribute(attributes, "w:id") {
+                    let point = point(&body);
+                    // A comment without a range is anchored at its reference
+                    body.comment_starts.entry(id.clone()).or_insert(point);
+                    if name == "w:commentRangeEnd" {
+                        body.comment_ends.insert(id, point);
+                    } else {
+                        body.comment_ends.entry(id).or_insert(point);
+                    }
+                }
+            }
+            XmlToken::Text(text) if in_text => push_text(&mut body, &unescape_xml(text), revision.as_ref()),
+            _ => {}
+        }
+    }
+    body
+}
+
+fn push_text(body: &mut WordBody, text: &str, revision: Option<&(RevisionKind, String, Option<String>)>) {
+    if body.paragraphs.is_empty() {
+        body.paragraphs.push(WordParagraph::default());
+    }
+    body.paragraphs.last_mut().expect("a paragraph").push_text(text, revision);
+}
+
+/// Read the comments of `word/comments.xml`
+fn parse_comments(xml: &str) -> Vec<WordComment> {
+    let mut comments: Vec<WordComment> = Vec::new();
+    let mut in_comment = false;
+    let mut in_text = false;
+
+    for token in xml_tokens(xml) {
+        match token {
+            XmlToken::Open { name: "w:comment", attributes, empty } => {
+                comments.push(WordComment {
+                    id: attribute(attributes, "w:id").unwrap_or_default(),
+                    author: attribute(attributes, "w:author").unwrap_or_default(),
+                    date: attribute(attributes, "w:date"),
+                    ..Default::default()
+                });
+                in_comment = !empty;
+            }
+            XmlToken::Close("w:comment") => in_comment = false,
+            XmlToken::Open { name: "w:p", attributes, .. } if in_comment => {
+                let comment = comments.last_mut().expect("an open comment");
+                comment.paragraphs.push(String::new());
+                if let Some(para_id) = attribute(attributes, "w14:paraId") {
+                    comment.para_id = Some(para_id);
+                }
+            }
+            XmlToken::Open { name: "w:t", empty: false, .. } => in_text = in_comment,
+            XmlToken::Close("w:t") => in_text = false,
+            XmlToken::Text(text) if in_text => {
+                let comment = comments.last_mut().expect("an open comment");
+                if comment.paragraphs.is_empty() {
+                    comment.paragraphs.push(String::new());
+                }
+                comment.paragraphs.last_mut().expect("a paragraph").push_str(&unescape_xml(text));
+            }
+            _ => {}
+        }
+    }
+    comments
+}
+
+/// Read the threads of `word/commentsExtended.xml`, keyed by the paragraph
+/// identifier of each comment
+fn parse_comment_threads(xml: &str) -> HashMap<String, CommentThread> {
+    xml_tokens(xml)
+        .into_iter()
+        .filter_map(|token| match token {
+            XmlToken::Open { name: "w15:commentEx", attributes, .. } => {
+                let para_id = attribute(attributes, "w15:paraId")?;
+                let thread = CommentThread {
+                    parent: attribute(attributes, "w15:paraIdParent"),
+                    done: attribute(attributes, "w15:done").is_some_and(|done| done == "1" || done == "true"),
+                };
+                Some((para_id, thread))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Maps places in the Word text to the Markdown. Word paragraphs are matched
+/// to the paragraphs of the converted Markdown by their original text, in
+/// order; a paragraph whose text no longer matches anchors nothing.
+struct Anchoring<'a> {
+    markdown: &'a str,
+    text: ConvertedText,
+    blocks: Vec<Option<usize>>,
+}
+
+impl<'a> Anchoring<'a> {
+    fn new(markdown: &'a str, paragraphs: &[WordParagraph]) -> Self {
+        let text = ConvertedText::from_markdown(markdown);
+        let block_texts: Vec<String> =
+            text.blocks.iter().map(|block| text.glyphs[block.glyphs.clone()].iter().map(|g| g.ch).collect()).collect();
+        let mut used = vec![false; block_texts.len()];
+        let mut next = 0;
+        let blocks = paragraphs
+            .iter()
+            .map(|paragraph| {
+                let original = paragraph.text();
+                let found = (next..block_texts.len()).chain(0..next).find(|&i| !used[i] && block_texts[i] == original)?;
+                used[found] = true;
+                next = found + 1;
+                Some(found)
+            })
+            .collect();
+        Self { markdown, text, blocks }
+    }
+
+    fn glyphs(&self, paragraph: usize) -> Option<&[Glyph]> {
+        let block = &self.text.blocks[self.blocks.get(paragraph).copied().flatten()?];
+        Some(&self.text.glyphs[block.glyphs.clone()])
+    }
+
+    /// Byte offset in the Markdown after the character a glyph came from
+    fn after(&self, glyph: &Glyph) -> usize {
+        glyph.source + self.markdown[glyph.source..].chars().next().map_or(0, char::len_utf8)
+    }
+
+    /// Byte offset in the Markdown of the place before `offset` in a paragraph
+    fn start(&self, (paragraph, offset): WordPoint) -> Option<usize> {
+        let glyphs = self.glyphs(paragraph)?;
+        match glyphs.get(offset) {
+            Some(glyph) => Some(glyph.source),
+            None => glyphs.last().map(|glyph| self.after(glyph)),
+        }
+    }
+
+    /// Byte offset in the Markdown of the place after the character before
+    /// `offset` in a paragraph
+    fn end(&self, (paragraph, offset): WordPoint) -> Option<usize> {
+        let glyphs = self.glyphs(paragraph)?;
+        match offset.checked_sub(1).and_then(|i| glyphs.get(i)) {
+            Some(glyph) => Some(self.after(glyph)),
+            None => glyphs.first().map(|glyph| glyph.source),
+        }
+    }
+
+    /// Glyphs a change or comment on a byte range of the Markdown covers, so
+    /// that ranges differing only in markup compare equal. An insertion
+    /// covers none, at the glyph it comes before.
+    fn covered(&self, change_type: Option<&ChangeType>, range: &Range<usize>) -> Range<usize> {
+        match change_type {
+            Some(ChangeType::Insert) => {
+                let at = self.text.glyph_at(range.start);
+                at..at
+            }
+            _ => self.text.glyphs_in(range),
+        }
+    }
+
+    /// Review position of a byte range of the Markdown, see
+    /// [`source_range`] for how positions count
+    fn position(&self, range: &Range<usize>, language: &str) -> CommentPosition {
+        let point = |offset: usize| {
+            let before = &self.markdown[..offset];
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            (before.matches('\n').count() as u32 + 1, before[line_start..].chars().count() as u32)
+        };
+        let (line_start, column_start) = point(range.start);
+        let (line_end, column_end) = point(range.end);
+        CommentPosition { line_start, line_end, column_start, column_end, language: language.to_string(), unit_id: None }
+    }
+}
+
+fn word_date(date: Option<&str>) -> DateTime<Utc> {
+    date.and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+        .map_or_else(Utc::now, |date| date.with_timezone(&Utc))
+}
+
+/// Import the tracked changes and comments of a Word document returned by
+/// a reviewer into a review of `document` in `language`.
+///
+/// Tracked insertions and deletions become change requests; a deletion
+/// directly followed by an insertion from the same author is a replacement.
+/// Comments become review comments, and Word replies become replies on
+/// their comment. Both are anchored in the Markdown by matching the
+/// paragraphs of the Word document to the document's converted text, so
+/// the file should come from [`ExportEngine::render_review_docx`] of the
+/// same content. Changes and comments already in the review, such as
+/// those exported into the file, are not added again. What can't be
+/// anchored is returned as unmatched instead of being dropped.
+///
+/// [`ExportEngine::render_review_docx`]: super::ExportEngine::render_review_docx
+pub fn import_review_docx(
+    docx: &[u8],
+    document: &Document,
+    language: &str,
+    review_system: &mut ReviewSystem,
+    review_id: Uuid,
+) -> Result<DocxReviewImport> {
+    let markdown = document
+        .content
+        .get(language)
+        .ok_or_else(|| TradocumentError::UnsupportedLanguage(format!("{} has no {language} content", document.title)))?;
+    let document_id = review_system
+        .get_review(review_id)
+        .map(|review| review.document_id)
+        .ok_or_else(|| TradocumentError::Review("Review not found".to_string()))?;
+
+    let not_docx = |reason: String| TradocumentError::DocumentImport(format!("Not a Word document: {reason}"));
+    let mut archive = zip::ZipArchive::new(Cursor::new(docx)).map_err(|e| not_docx(e.to_string()))?;
+    let mut read_part = |name: &str| -> Result<Option<String>> {
+        let mut part = match archive.by_name(name) {
+            Ok(part) => part,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(not_docx(e.to_string())),
+        };
+        let mut xml = String::new();
+        part.read_to_string(&mut xml)?;
+        Ok(Some(xml))
+    };
+    let body = parse_body(&read_part("word/document.xml")?.ok_or_else(|| not_docx("it has no word/document.xml".to_string()))?);
+    let comments = read_part("word/comments.xml")?.map(|xml| parse_comments(&xml)).unwrap_or_default();
+    let threads = read_part("word/commentsExtended.xml")?.map(|xml| parse_comment_threads(&xml)).unwrap_or_default();
+
+    let anchoring = Anchoring::new(markdown, &body.paragraphs);
+    let mut import = DocxReviewImport::default();
+
+    let existing_changes: Vec<(String, String, Range<usize>)> = review_system
+        .get_change_requests_for_review(review_id)
+        .into_iter()
+        .flatten()
+        .filter(|change| change.position.language == language)
+        .map(|change| {
+            let covered = anchoring.covered(Some(&change.change_type), &source_range(markdown, &change.position));
+            (change.author_id.clone(), change.new_content.clone(), covered)
+        })
+        .collect();
+    for (index, paragraph) in body.paragraphs.iter().enumerate() {
+        let mut revisions = paragraph.revisions.iter().peekable();
+        while let Some(revision) = revisions.next() {
+            let (change_type, inserted) = match revision.kind {
+                RevisionKind::Insertion => (ChangeType::Insert, revision.text.as_str()),
+                RevisionKind::Deletion => match revisions.next_if(|next| {
+                    next.kind == RevisionKind::Insertion && next.author == revision.author && next.at == revision.end()
+                }) {
+                    Some(insertion) => (ChangeType::Replace, insertion.text.as_str()),
+                    None => (ChangeType::Delete, ""),
+                },
+            };
+            let range = match change_type {
+                ChangeType::Insert => anchoring.start((index, revision.at)).map(|start| start..start),
+                _ => anchoring
+                    .start((index, revision.at))
+                    .zip(anchoring.end((index, revision.end())))
+                    .map(|(start, end)| start..end.max(start)),
+            };
+            let Some(range) = range else {
+                let old_content = match revision.kind {
+                    RevisionKind::Deletion => revision.text.clone(),
+                    RevisionKind::Insertion => String::new(),
+                };
+                import.unmatched.push(UnmatchedRevision::Change {
+                    change_type,
+                    author_id: revision.author.clone(),
+                    old_content,
+                    new_content: inserted.to_string(),
+                    paragraph: paragraph.text(),
+                });
+                continue;
+            };
+            let covered = anchoring.covered(Some(&change_type), &range);
+            if existing_changes.contains(&(revision.author.clone(), inserted.to_string(), covered)) {
+                continue;
+            }
+            let change = ChangeRequest {
+                id: Uuid::new_v4(),
+                document_id,
+                author_id: revision.author.clone(),
+                change_type,
+                old_content: markdown[range.clone()].to_string(),
+                new_content: inserted.to_string(),
+                position: anchoring.position(&range, language),
+                status: ChangeStatus::Proposed,
+                created_at: word_date(revision.date.as_deref()),
+            };
+            review_system.add_change_request(review_id, change.clone())?;
+            import.change_requests.push(change);
+        }
+    }
+
+    let thread = |comment: &WordComment| comment.para_id.as_ref().and_then(|para_id| threads.get(para_id));
+    for comment in &comments {
+        if thread(comment).is_some_and(|thread| thread.parent.is_some()) {
+            continue;
+        }
+        let replies: Vec<CommentReply> = comments
+            .iter()
+            .filter(|reply| comment.para_id.is_some() && thread(reply).and_then(|t| t.parent.as_ref()) == comment.para_id.as_ref())
+            .map(|reply| CommentReply {
+                author_id: reply.author.clone(),
+                content: reply.paragraphs.join("\n"),
+                created_at: word_date(reply.date.as_deref()),
+            })
+            .collect();
+        let content = comment.paragraphs.join("\n");
+
+        let start = body.comment_starts.get(&comment.id).copied();
+        let end = body.comment_ends.get(&comment.id).copied();
+        let range = start
+            .and_then(|start| anchoring.start(start))
+            .zip(end.and_then(|end| anchoring.end(end)))
+            .map(|(start, end)| start..end.max(start));
+        let Some(range) = range else {
+            let paragraph = start.and_then(|(paragraph, _)| body.paragraphs.get(paragraph)).map(WordParagraph::text);
+            import.unmatched.push(UnmatchedRevision::Comment {
+                author_id: comment.author.clone(),
+                content,
+                paragraph: paragraph.unwrap_or_default(),
+            });
+            continue;
+        };
+
+        let existing = review_system.get_comments_for_review(review_id).into_iter().flatten().find(|existing| {
+            existing.author_id == comment.author
+                && comment.paragraphs.first() == Some(&existing.content)
+                && existing.position.language == language
+                && anchoring.covered(None, &source_range(markdown, &existing.position)) == anchoring.covered(None, &range)
+        });
+        if let Some(existing) = existing {
+            let (comment_id, known) = (existing.id, existing.replies.clone());
+            for reply in replies {
+                if !known.iter().any(|known| known.author_id == reply.author_id && known.content == reply.content) {
+                    review_system.add_comment_reply(review_id, comment_id, reply)?;
+                    import.replies += 1;
+                }
+            }
+            continue;
+        }
+
+        let imported = Comment {
+            id: Uuid::new_v4(),
+            author_id: comment.author.clone(),
+            content,
+            position: anchoring.position(&range, language),
+            created_at: word_date(comment.date.as_deref()),
+            resolved: thread(comment).is_some_and(|thread| thread.done),
+            replies,
+        };
+        review_system.add_comment_sync(review_id, imported.clone())?;
+        import.comments.push(imported);
+    }
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_engine::ExportEngine;
+    use crate::{DocumentMetadata, ReviewStatus};
+    use std::io::Write;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    const W: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main""#;
+
+    fn document(content: &str) -> Document {
+        Document {
+            title: "Setup".to_string(),
+            content: HashMap::from([("en".to_string(), content.to_string())]),
+            metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+        }
+    }
+
+    fn docx(parts: &[(&str, String)]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, xml) in parts {
+            zip.start_file(*name, FileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn run(text: &str) -> String {
+        format!(r#"<w:r><w:t xml:space="preserve">{text}</w:t></w:r>"#)
+    }
+
+    #[test]
+    fn test_tracked_insertion_and_comment_become_change_request_and_comment() {
+        let markdown = "# Setup\n\nPress the **red** button.\n\nWait for the beep.\n";
+        let mut review_system = ReviewSystem::new();
+        let review = review_system.create_review_sync(Uuid::new_v4(), "legal".to_string()).unwrap();
+
+        let inserted = |text: &str| {
+            format!(r#"<w:ins w:id="9" w:author="legal" w:date="2026-03-05T14:00:00Z">{}</w:ins>"#, run(text))
+        };
+        let document_xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document {W}><w:body>
+<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr>{}</w:p>
+<w:p>{}<w:commentRangeStart w:id="0"/><w:r><w:rPr><w:b/></w:rPr><w:t>red</w:t></w:r><w:commentRangeEnd w:id="0"/><w:r><w:commentReference w:id="0"/></w:r>{}{}{}</w:p>
+<w:p>{}{}</w:p>
+</w:body></w:document>"#,
+            run("Setup"),
+            run("Press the "),
+            run(" button"),
+            inserted(" firmly"),
+            run("."),
+            // Edited without tracking since the export, so it matches nothing
+            run("Wait for the signal."),
+            inserted(" Then release."),
+        );
+        let comments_xml = format!(
+            r#"<w:comments {W} xmlns:w14="http://schemas.microsoft.com/office/word/2010/wordml">
+<w:comment w:id="0" w:author="legal" w:date="2026-03-05T14:02:00Z"><w:p w14:paraId="1A2B"><w:r><w:t>Use &quot;press and hold&quot;?</w:t></w:r></w:p></w:comment>
+<w:comment w:id="1" w:author="anna" w:date="2026-03-06T08:00:00Z"><w:p w14:paraId="3C4D"><w:r><w:t>Agreed</w:t></w:r></w:p></w:comment>
+</w:comments>"#
+        );
+        let threads_xml = r#"<w15:commentsEx xmlns:w15="http://schemas.microsoft.com/office/word/2012/wordml">
+<w15:commentEx w15:paraId="1A2B"/><w15:commentEx w15:paraId="3C4D" w15:paraIdParent="1A2B"/>
+</w15:commentsEx>"#
+            .to_string();
+        let bytes = docx(&[
+            ("word/document.xml", document_xml),
+            ("word/comments.xml", comments_xml),
+            ("word/commentsExtended.xml", threads_xml),
+        ]);
+
+        let import = import_review_docx(&bytes, &document(markdown), "en", &mut review_system, review.id).unwrap();
+
+        assert_eq!(import.change_requests.len(), 1);
+        let change = &review_system.get_change_requests_for_review(review.id).unwrap()[0];
+        assert!(matches!(change.change_type, ChangeType::Insert));
+        assert_eq!((change.author_id.as_str(), change.new_content.as_str()), ("legal", " firmly"));
+        // Before the full stop, after the `**` that Word never saw
+        let position = &change.position;
+        assert_eq!((position.line_start, position.column_start, position.column_end), (3, 24, 24));
+        assert_eq!(change.created_at.to_rfc3339(), "2026-03-05T14:00:00+00:00");
+        assert_eq!(review_system.get_review(review.id).unwrap().status, ReviewStatus::ChangesRequested);
+
+        let comments = review_system.get_comments_for_review(review.id).unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].content, "Use \"press and hold\"?");
+        let position = &comments[0].position;
+        assert_eq!((position.line_start, position.column_start, position.column_end), (3, 12, 15));
+        assert_eq!(comments[0].replies.len(), 1);
+        assert_eq!((comments[0].replies[0].author_id.as_str(), comments[0].replies[0].content.as_str()), ("anna", "Agreed"));
+
+        assert_eq!(import.unmatched.len(), 1);
+        assert!(matches!(
+            &import.unmatched[0],
+            UnmatchedRevision::Change { change_type: ChangeType::Insert, new_content, paragraph, .. }
+                if new_content == " Then release." && paragraph == "Wait for the signal."
+        ));
+    }
+
+    #[test]
+    fn test_exported_review_reads_back_without_duplicates() {
+        let markdown = "Open the [settings](https://example.com/settings)\nand *choose* a language.\n";
+        let mut review_system = ReviewSystem::new();
+        let review = review_system.create_review_sync(Uuid::new_v4(), "ben".to_string()).unwrap();
+        let position = |column_start, column_end| CommentPosition {
+            line_start: 2,
+            line_end: 2,
+            column_start,
+            column_end,
+            language: "en".to_string(),
+            unit_id: None,
+        };
+        let comment = Comment {
+            id: Uuid::new_v4(),
+            author_id: "ben".to_string(),
+            content: "Which language?".to_string(),
+            position: position(4, 12),
+            created_at: Utc::now(),
+            resolved: false,
+            replies: Vec::new(),
+        };
+        let change = ChangeRequest {
+            id: Uuid::new_v4(),
+            document_id: review.document_id,
+            author_id: "ben".to_string(),
+            change_type: ChangeType::Replace,
+            old_content: "a language".to_string(),
+            new_content: "the display language".to_string(),
+            position: position(13, 23),
+            status: ChangeStatus::Proposed,
+            created_at: Utc::now(),
+        };
+        review_system.add_comment_sync(review.id, comment).unwrap();
+        review_system.add_change_request(review.id, change).unwrap();
+
+        let document = document(markdown);
+        let bytes = ExportEngine::new().export_review_docx(&document, "en", &review_system, review.id).unwrap();
+        let import = import_review_docx(&bytes, &document, "en", &mut review_system, review.id).unwrap();
+
+        assert!(import.comments.is_empty() && import.change_requests.is_empty() && import.unmatched.is_empty());
+        assert_eq!(review_system.get_comments_for_review(review.id).unwrap().len(), 1);
+        assert_eq!(review_system.get_change_requests_for_review(review.id).unwrap().len(), 1);
+    }
+}
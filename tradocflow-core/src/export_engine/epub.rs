@@ -0,0 +1,656 @@
+use super::captions::CaptionNumberer;
+use super::footnotes::scope_footnote_ids;
+use super::html_bundle::{escape_html, flatten_sections, section_anchor, sorted_sections, warn_dangling};
+use super::images::local_images;
+use super::{isolate_code, ExportEngine};
+use crate::database::image_repository::ImageRepository;
+use crate::services::cross_refs::CrossRefResolver;
+use crate::services::heading_ids::HeadingIdRegistry;
+use crate::services::TextDirection;
+use crate::{Document, Manual, ManualSection, Result, TradocumentError};
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use uuid::Uuid;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+/// An image copied into the EPUB, referenced from the manifest
+struct EpubImage {
+    id: String,
+    href: String,
+    media_type: &'static str,
+    /// Manifest properties, such as `cover-image`
+    properties: Option<&'static str>,
+    data: Vec<u8>,
+}
+
+impl ExportEngine {
+    /// Package a manual as an EPUB3 book in `language`.
+    ///
+    /// Each top-level section becomes one XHTML chapter containing its
+    /// subsections; `documents` maps the `document_id` of each section to its
+    /// content. Screenshots for `language` are embedded from the screenshot
+    /// directory. If front matter is configured, the cover and front matter
+    /// pages open the spine. If caption lists are configured, the lists of
+    /// figures and tables follow them, linking into the chapter files.
+    /// Cross-references link to the chapter file that holds their section.
+    /// The package document and navigation document are checked for
+    /// well-formedness before the archive is written.
+    pub async fn export_epub(
+        &self,
+        manual: &Manual,
+        documents: &HashMap<Uuid, Document>,
+        language: &str,
+    ) -> Result<Vec<u8>> {
+        let chapters = sorted_sections(&manual.sections);
+        let mut images: Vec<EpubImage> = Vec::new();
+        let mut chapter_files = Vec::new();
+
+        if let Some(config) = &self.front_matter {
+            let logo = config.load_logo().map(|logo| logo.processed(self.image_policy.as_ref()));
+            let logo_href = logo.map(|logo| {
+                let href = format!("images/cover-logo.{}", logo.extension);
+                images.push(EpubImage {
+                    id: "cover-logo".to_string(),
+                    href: href.clone(),
+                    media_type: logo.media_type,
+                    properties: Some("cover-image"),
+                    data: logo.data,
+                });
+                href
+            });
+            let cover = xhtml_page(&manual.title, language, &config.render_cover(manual, logo_href.as_deref()));
+            let front_matter = xhtml_page(&manual.title, language, &config.render_front_matter(manual, language));
+            chapter_files.push(("cover.xhtml".to_string(), cover));
+            chapter_files.push(("front-matter.xhtml".to_string(), front_matter));
+        }
+
+        // Chapter file of every section, for cross-reference links
+        let mut section_files = HashMap::new();
+        for (index, chapter) in chapters.iter().enumerate() {
+            let mut sections = Vec::new();
+            flatten_sections(chapter, &mut sections);
+            for section in sections {
+                section_files.insert(section.id, chapter_file_name(index));
+            }
+        }
+        let cross_refs = CrossRefResolver::new(manual);
+        let href = |id: Uuid| format!("{}#{}", section_files.get(&id).map_or("", String::as_str), section_anchor(id));
+        let mut captions = self.caption_lists.as_ref().map(|config| CaptionNumberer::new(config.numbering, language));
+        // Chapter file of every numbered caption, by anchor
+        let mut caption_files = HashMap::new();
+        let front_pages = chapter_files.len();
+
+        for (index, chapter) in chapters.iter().enumerate() {
+            let file_name = chapter_file_name(index);
+            if let Some(captions) = &mut captions {
+                captions.start_chapter();
+            }
+            let mut sections = Vec::new();
+            flatten_sections(chapter, &mut sections);
+
+            let mut body = String::new();
+            for section in sections {
+                let level = section_depth_in(chapter, section.id).min(6);
+                body.push_str(&format!(
+                    "<section id=\"{}\">\n<h{level}>{}</h{level}>\n",
+                    section_anchor(section.id),
+                    escape_html(&section.title)
+                ));
+
+                let content = section.document_id.and_then(|id| documents.get(&id)).and_then(|document| {
+                    document.content.get(language).map(|content| (document, content))
+                });
+                match (section.document_id, content) {
+                    (Some(_), Some((document, content))) => {
+                        let resolved = cross_refs.resolve(content, language, href);
+                        warn_dangling(section, &resolved.dangling);
+                        let html = self.render_epub_section(document, &resolved.text, language, &mut images).await?;
+                        let mut html = scope_footnote_ids(&html, &section_anchor(section.id));
+                        if let Some(captions) = &mut captions {
+                            let numbered = captions.captions().len();
+                            html = captions.number(&html);
+                            for caption in &captions.captions()[numbered..] {
+                                caption_files.insert(caption.anchor.clone(), file_name.clone());
+                            }
+                        }
+                        body.push_str(&html);
+                    }
+                    (Some(_), None) => {
+                        body.push_str(&format!(
+                            "<p class=\"missing-translation\">Translation not available in {}.</p>\n",
+                            escape_html(language)
+                        ));
+                    }
+                    (None, _) => {}
+                }
+                body.push_str("</section>\n");
+            }
+
+            let xhtml = xhtml_page(&chapter.title, language, &body);
+            check_well_formed(&file_name, &xhtml)?;
+            chapter_files.push((file_name, xhtml));
+        }
+
+        if let (Some(captions), Some(config)) = (&captions, &self.caption_lists) {
+            let lists = captions.render_lists_linked(config, |caption| {
+                format!("{}#{}", caption_files.get(&caption.anchor).map_or("", String::as_str), caption.anchor)
+            });
+            if !lists.is_empty() {
+                let xhtml = xhtml_page(&manual.title, language, &lists);
+                check_well_formed("captions.xhtml", &xhtml)?;
+                chapter_files.insert(front_pages, ("captions.xhtml".to_string(), xhtml));
+            }
+        }
+
+        let nav = nav_document(manual, &chapters, language);
+        check_well_formed("nav.xhtml", &nav)?;
+        let opf = package_document(manual, language, &chapter_files, &images);
+        check_well_formed("content.opf", &opf)?;
+
+        let epub_error = |e: zip::result::ZipError| TradocumentError::FileError(format!("EPUB packaging failed: {e}"));
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+        let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        // The mimetype entry must come first and be uncompressed
+        zip.start_file("mimetype", stored).map_err(epub_error)?;
+        zip.write_all(b"application/epub+zip")?;
+        zip.start_file("META-INF/container.xml", deflated).map_err(epub_error)?;
+        zip.write_all(CONTAINER_XML.as_bytes())?;
+        zip.start_file("OEBPS/content.opf", deflated).map_err(epub_error)?;
+        zip.write_all(opf.as_bytes())?;
+        zip.start_file("OEBPS/nav.xhtml", deflated).map_err(epub_error)?;
+        zip.write_all(nav.as_bytes())?;
+        zip.start_file("OEBPS/style.css", deflated).map_err(epub_error)?;
+        zip.write_all(include_str!("default.css").as_bytes())?;
+        for (file_name, xhtml) in &chapter_files {
+            zip.start_file(format!("OEBPS/{file_name}"), deflated).map_err(epub_error)?;
+            zip.write_all(xhtml.as_bytes())?;
+        }
+        for image in &images {
+            zip.start_file(format!("OEBPS/{}", image.href), deflated).map_err(epub_error)?;
+            zip.write_all(&image.data)?;
+        }
+
+        Ok(zip.finish().map_err(epub_error)?.into_inner())
+    }
+
+    /// Render a section's markdown, collecting the screenshots it references
+    async fn render_epub_section(
+        &self,
+        document: &Document,
+        content: &str,
+        language: &str,
+        images: &mut Vec<EpubImage>,
+    ) -> Result<String> {
+        let content_with_fragments = self.process_fragments(content, language);
+        let processed = self
+            .process_screenshots(&content_with_fragments, &document.metadata.screenshots, language)
+            .await?;
+        let heading_ids = HeadingIdRegistry::from_metadata(&document.metadata, language).resolve(&processed);
+        let mut html = self.render_markdown(&processed, &heading_ids, language);
+
+        for screenshot in &document.metadata.screenshots {
+            let relative = format!("screenshots/{}/{}.svg", screenshot.language, screenshot.id);
+            if !html.contains(&relative) {
+                continue;
+            }
+
+            // Screenshots of one stored image share its file
+            let href = match &screenshot.content_hash {
+                Some(hash) => format!("images/{hash}.svg"),
+                None => format!("images/{}-{}.svg", screenshot.language, screenshot.id),
+            };
+            if images.iter().any(|image| image.href == href) {
+                html = html.replace(&relative, &href);
+                continue;
+            }
+
+            match self.screenshot_bytes(screenshot).await {
+                Ok(data) => {
+                    images.push(EpubImage {
+                        id: format!("image-{}", images.len() + 1),
+                        href: href.clone(),
+                        media_type: "image/svg+xml",
+                        properties: None,
+                        data,
+                    });
+                    html = html.replace(&relative, &href);
+                }
+                Err(e) => {
+                    log::warn!("Screenshot {} not embedded: {e}", screenshot.id);
+                }
+            }
+        }
+
+        // Raster images are copied into the book as the image policy
+        // processes them, each stored once under its content hash
+        if let Some(policy) = &self.image_policy {
+            let mut embedded = String::with_capacity(html.len());
+            let mut last = 0;
+            for (tag, src, image) in local_images(&html, policy) {
+                let image = match image {
+                    Ok(image) => image,
+                    Err(e) => {
+                        log::warn!("Image {src} not embedded: {e}");
+                        continue;
+                    }
+                };
+                let href = format!("images/{}.{}", ImageRepository::content_hash(&image.data), image.extension());
+                embedded.push_str(&html[last..tag.start]);
+                embedded.push_str(&html[tag.clone()].replacen(src, &href, 1));
+                last = tag.end;
+                if !images.iter().any(|existing| existing.href == href) {
+                    images.push(EpubImage {
+                        id: format!("image-{}", images.len() + 1),
+                        href,
+                        media_type: image.media_type,
+                        properties: None,
+                        data: image.data,
+                    });
+                }
+            }
+            embedded.push_str(&html[last..]);
+            html = embedded;
+        }
+
+        Ok(isolate_code(html, language))
+    }
+}
+
+/// 1-based heading level of `id` within a chapter, the chapter itself being 1
+fn chapter_file_name(index: usize) -> String {
+    format!("section-{}.xhtml", index + 1)
+}
+
+/// 1-based depth of the section with `id` below `section`
+pub(super) fn section_depth_in(section: &ManualSection, id: Uuid) -> usize {
+    fn find(section: &ManualSection, id: Uuid, depth: usize) -> Option<usize> {
+        if section.id == id {
+            return Some(depth);
+        }
+        section.subsections.iter().find_map(|child| find(child, id, depth + 1))
+    }
+    find(section, id, 1).unwrap_or(1)
+}
+
+fn xhtml_page(title: &str, language: &str, body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" lang="{lang}" xml:lang="{lang}" dir="{dir}">
+<head>
+  <meta charset="UTF-8"/>
+  <title>{title}</title>
+  <link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        lang = escape_html(language),
+        dir = TextDirection::for_language(language).as_css(),
+        title = escape_html(title),
+    )
+}
+
+fn nav_document(manual: &Manual, chapters: &[&ManualSection], language: &str) -> String {
+    fn nav_list(sections: &[&ManualSection], file_name: Option<&str>, out: &mut String) {
+        out.push_str("<ol>\n");
+        for (index, section) in sections.iter().enumerate() {
+            // Top-level entries pick their own file, nested entries stay in their chapter's
+            let file_name = file_name
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("section-{}.xhtml", index + 1));
+            out.push_str(&format!(
+                "<li><a href=\"{file_name}#{}\">{}</a>",
+                section_anchor(section.id),
+                escape_html(&section.title)
+            ));
+            if !section.subsections.is_empty() {
+                out.push('\n');
+                nav_list(&sorted_sections(&section.subsections), Some(file_name.as_str()), out);
+            }
+            out.push_str("</li>\n");
+        }
+        out.push_str("</ol>\n");
+    }
+
+    let mut toc = String::new();
+    nav_list(chapters, None, &mut toc);
+    xhtml_page(
+        &manual.title,
+        language,
+        &format!("<nav epub:type=\"toc\" id=\"toc\">\n<h1>{}</h1>\n{toc}</nav>\n", escape_html(&manual.title)),
+    )
+}
+
+fn package_document(manual: &Manual, language: &str, chapters: &[(String, String)], images: &[EpubImage]) -> String {
+    let mut manifest = String::from(
+        "    <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n    <item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>\n",
+    );
+    let mut spine = String::new();
+    for (file_name, _) in chapters {
+        let id = file_name.trim_end_matches(".xhtml");
+        manifest.push_str(&format!(
+            "    <item id=\"{id}\" href=\"{file_name}\" media-type=\"application/xhtml+xml\"/>\n"
+        ));
+        spine.push_str(&format!("    <itemref idref=\"{id}\"/>\n"));
+    }
+    for image in images {
+        manifest.push_str(&format!(
+            "    <item id=\"{}\" href=\"{}\" media-type=\"{}\"{}/>\n",
+            image.id,
+            image.href,
+            image.media_type,
+            image.properties.map(|p| format!(" properties=\"{p}\"")).unwrap_or_default()
+        ));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id" xml:lang="{lang}">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="book-id">urn:uuid:{id}</dc:identifier>
+    <dc:title>{title}</dc:title>
+    <dc:language>{lang}</dc:language>
+    <meta property="dcterms:modified">{modified}</meta>
+    <meta property="dcterms:hasVersion">{version}</meta>
+  </metadata>
+  <manifest>
+{manifest}  </manifest>
+  <spine page-progression-direction="{direction}">
+{spine}  </spine>
+</package>
+"#,
+        id = manual.id,
+        title = escape_html(&manual.title),
+        lang = escape_html(language),
+        direction = TextDirection::for_language(language).as_css(),
+        modified = manual.updated_at.format("%Y-%m-%dT%H:%M:%SZ"),
+        version = escape_html(&manual.version),
+    )
+}
+
+/// Check that every element in `xml` is closed in the right order
+pub(super) fn check_well_formed(name: &str, xml: &str) -> Result<()> {
+    let invalid = |reason: String| TradocumentError::Validation(format!("{name} is not well-formed: {reason}"));
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        let end = after.find('>').ok_or_else(|| invalid("unterminated tag".to_string()))?;
+        let tag = &after[..end];
+        rest = &after[end + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') || tag.ends_with('/') {
+            continue;
+        }
+        if let Some(closing) = tag.strip_prefix('/') {
+            let closing = closing.trim();
+            match open.pop() {
+                Some(name) if name == closing => {}
+                Some(name) => return Err(invalid(format!("</{closing}> closes <{name}>"))),
+                None => return Err(invalid(format!("</{closing}> has no opening tag"))),
+            }
+        } else {
+            let name = tag.split_whitespace().next().unwrap_or_default();
+            open.push(name);
+        }
+    }
+
+    match open.last() {
+        Some(name) => Err(invalid(format!("<{name}> is never closed"))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::export_engine::front_matter::FrontMatterConfig;
+    use crate::export_engine::ImagePolicy;
+    use crate::{DocumentMetadata, ManualTemplate, ScreenshotReference, SectionType};
+    use chrono::Utc;
+    use image::GenericImageView;
+    use std::fs;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    fn section(title: &str, order: u32, document_id: Option<Uuid>) -> ManualSection {
+        ManualSection {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            order,
+            document_id,
+            subsections: Vec::new(),
+            section_type: SectionType::UserGuide,
+            required: true,
+            localized_titles: HashMap::new(),
+        }
+    }
+
+    fn document(language: &str, content: &str, screenshots: Vec<ScreenshotReference>) -> Document {
+        Document {
+            title: "Doc".to_string(),
+            content: HashMap::from([(language.to_string(), content.to_string())]),
+            metadata: DocumentMetadata { project_id: None, screenshots, version: None, custom: HashMap::new() },
+        }
+    }
+
+    fn read_entry(archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut content = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[tokio::test]
+    async fn test_epub_contains_spine_and_nav_for_sections() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("de")).unwrap();
+        fs::write(temp_dir.path().join("de/wiring.svg"), "<svg></svg>").unwrap();
+
+        let intro_id = Uuid::new_v4();
+        let wiring_id = Uuid::new_v4();
+        let documents = HashMap::from([
+            (intro_id, document("de", "Willkommen.", Vec::new())),
+            (
+                wiring_id,
+                document(
+                    "de",
+                    "Verkabelung:\n\n{screenshot:wiring}\n",
+                    vec![ScreenshotReference {
+                        id: "wiring".to_string(),
+                        language: "de".to_string(),
+                        screen_config: "{}".to_string(),
+                        generated_at: None,
+                        caption: None,
+                        content_hash: None,
+                        alt_text: HashMap::new(),
+                    }],
+                ),
+            ),
+        ]);
+
+        let intro = section("Einleitung", 1, Some(intro_id));
+        let mut install = section("Installation", 2, None);
+        let wiring = section("Verkabelung", 1, Some(wiring_id));
+        install.subsections.push(wiring.clone());
+
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Handbuch".to_string(),
+            description: String::new(),
+            sections: vec![install.clone(), intro.clone()],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "2.1".to_string(),
+            languages: vec!["de".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        };
+
+        let engine = ExportEngine::new().with_screenshot_dir(temp_dir.path());
+        let bytes = engine.export_epub(&manual, &documents, "de").await.unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        assert_eq!(archive.by_index(0).unwrap().name(), "mimetype");
+        assert!(archive.by_name("OEBPS/images/de-wiring.svg").is_ok());
+
+        let opf = read_entry(&mut archive, "OEBPS/content.opf");
+        check_well_formed("content.opf", &opf).unwrap();
+        assert!(opf.contains("<dc:title>Handbuch</dc:title>"));
+        assert!(opf.contains("<dc:language>de</dc:language>"));
+        assert!(opf.contains(">2.1</meta>"));
+        let first = opf.find("<itemref idref=\"section-1\"/>").unwrap();
+        let second = opf.find("<itemref idref=\"section-2\"/>").unwrap();
+        assert!(first < second);
+        assert!(!opf.contains("section-3"));
+
+        let nav = read_entry(&mut archive, "OEBPS/nav.xhtml");
+        check_well_formed("nav.xhtml", &nav).unwrap();
+        assert!(nav.contains(&format!("href=\"section-1.xhtml#section-{}\">Einleitung", intro.id)));
+        assert!(nav.contains(&format!("href=\"section-2.xhtml#section-{}\">Installation", install.id)));
+        assert!(nav.contains(&format!("href=\"section-2.xhtml#section-{}\">Verkabelung", wiring.id)));
+
+        let chapter = read_entry(&mut archive, "OEBPS/section-2.xhtml");
+        assert!(chapter.contains("src=\"images/de-wiring.svg\""));
+    }
+
+    #[tokio::test]
+    async fn test_epub_cover_opens_spine_when_logo_is_missing() {
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Handbuch".to_string(),
+            description: String::new(),
+            sections: vec![section("Einleitung", 1, None)],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "2.1".to_string(),
+            languages: vec!["de".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        };
+        let engine = ExportEngine::new().with_front_matter(FrontMatterConfig {
+            logo_path: Some("/nonexistent/logo.png".into()),
+            ..Default::default()
+        });
+
+        let bytes = engine.export_epub(&manual, &HashMap::new(), "de").await.unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let opf = read_entry(&mut archive, "OEBPS/content.opf");
+        let spine_start = opf.find("<spine").unwrap();
+        let itemrefs: Vec<&str> = opf[spine_start..]
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("<itemref idref=\""))
+            .map(|rest| rest.trim_end_matches("\"/>"))
+            .collect();
+        assert_eq!(itemrefs, vec!["cover", "front-matter", "section-1"]);
+        assert!(!opf.contains("cover-image"));
+
+        let cover = read_entry(&mut archive, "OEBPS/cover.xhtml");
+        assert!(cover.contains("<h1 class=\"cover-title\">Handbuch</h1>"));
+        assert!(!cover.contains("<img"));
+    }
+
+    #[tokio::test]
+    async fn test_epub_embeds_shared_image_once() {
+        let store = ImageRepository::new(Database::in_memory().unwrap().pool());
+        let mut documents = HashMap::new();
+        let mut sections = Vec::new();
+        for (order, title) in [(1, "Installation"), (2, "Wartung")] {
+            let document_id = Uuid::new_v4();
+            let mut screenshot = ScreenshotReference {
+                id: format!("panel-{order}"),
+                language: "de".to_string(),
+                screen_config: "{}".to_string(),
+                generated_at: None,
+                caption: None,
+                content_hash: None,
+                alt_text: HashMap::new(),
+            };
+            store.embed(document_id, &mut screenshot, b"<svg></svg>").await.unwrap();
+            let content = format!("{{screenshot:panel-{order}}}\n");
+            documents.insert(document_id, document("de", &content, vec![screenshot]));
+            sections.push(section(title, order, Some(document_id)));
+        }
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Handbuch".to_string(),
+            description: String::new(),
+            sections,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "2.1".to_string(),
+            languages: vec!["de".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        };
+
+        // Nothing in the screenshot directory: the images come from the store
+        let engine = ExportEngine::new().with_screenshot_dir("/nonexistent").with_image_store(store);
+        let bytes = engine.export_epub(&manual, &documents, "de").await.unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let href = format!("images/{}.svg", ImageRepository::content_hash(b"<svg></svg>"));
+        let images: Vec<String> = archive.file_names().filter(|name| name.contains("images/")).map(str::to_string).collect();
+        assert_eq!(images, [format!("OEBPS/{href}")]);
+        for chapter in ["OEBPS/section-1.xhtml", "OEBPS/section-2.xhtml"] {
+            assert!(read_entry(&mut archive, chapter).contains(&format!("src=\"{href}\"")));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_epub_images_follow_image_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("bell.png");
+        image::RgbImage::from_fn(1200, 600, |x, y| image::Rgb([(x % 251) as u8, (y % 241) as u8, 90])).save(&path).unwrap();
+        let original = fs::read(&path).unwrap();
+
+        let document_id = Uuid::new_v4();
+        let content = format!("![Glocke]({})\n", path.display());
+        let documents = HashMap::from([(document_id, document("de", &content, Vec::new()))]);
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Handbuch".to_string(),
+            description: String::new(),
+            sections: vec![section("Glocke", 1, Some(document_id))],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "2.1".to_string(),
+            languages: vec!["de".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        };
+
+        let engine = ExportEngine::new().with_image_policy(ImagePolicy { max_dimension: 300, ..ImagePolicy::default() });
+        let bytes = engine.export_epub(&manual, &documents, "de").await.unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let entry = archive.file_names().find(|name| name.starts_with("OEBPS/images/")).unwrap().to_string();
+        assert!(entry.ends_with(".jpg"));
+        let mut data = Vec::new();
+        archive.by_name(&entry).unwrap().read_to_end(&mut data).unwrap();
+        assert_eq!(image::load_from_memory(&data).unwrap().dimensions(), (300, 150));
+        let href = entry.trim_start_matches("OEBPS/");
+        assert!(read_entry(&mut archive, "OEBPS/section-1.xhtml").contains(&format!("src=\"{href}\"")));
+        assert!(read_entry(&mut archive, "OEBPS/content.opf").contains(&format!("href=\"{href}\" media-type=\"image/jpeg\"")));
+        // The file on disk is left as it was
+        assert_eq!(fs::read(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_check_well_formed_rejects_mismatched_tags() {
+        assert!(check_well_formed("ok", "<?xml?><a><b/><c>text</c></a>").is_ok());
+        assert!(check_well_formed("bad", "<a><b></a>").is_err());
+        assert!(check_well_formed("open", "<a>").is_err());
+    }
+}
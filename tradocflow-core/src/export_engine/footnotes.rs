@@ -0,0 +1,261 @@
+//! Footnotes in exported markdown
+//!
+//! References (`[^label]`) and definitions (`[^label]: text`) are taken out
+//! of the markdown before it is rendered. Definitions may appear anywhere;
+//! notes are numbered in the order they are first referred to. References
+//! stand in the rendered text as tokens, which become links in HTML and
+//! bracketed numbers in PDF. A reference to a label nobody defined is
+//! flagged instead of numbered.
+
+use super::html_bundle::escape_html;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+fn reference_regex() -> &'static Regex {
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    REFERENCE.get_or_init(|| Regex::new(r"\[\^([^\]\s]+)\]").expect("valid footnote reference regex"))
+}
+
+fn definition_regex() -> &'static Regex {
+    static DEFINITION: OnceLock<Regex> = OnceLock::new();
+    DEFINITION.get_or_init(|| Regex::new(r"^\[\^([^\]\s]+)\]:[ \t]*(.*)$").expect("valid footnote definition regex"))
+}
+
+fn token_regex() -> &'static Regex {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    TOKEN.get_or_init(|| Regex::new(r"\{\{fn(ref|missing)-(\d+)(?:-(\d+))?\}\}").expect("valid footnote token regex"))
+}
+
+/// A defined and referenced footnote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footnote {
+    pub label: String,
+    /// Markdown of the note
+    pub text: String,
+    /// How often the text refers to the note
+    pub references: usize,
+}
+
+/// Markdown with its footnotes taken out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FootnotedText {
+    /// The markdown with definitions removed and references replaced by
+    /// tokens
+    pub text: String,
+    /// Notes in order of first reference, numbered from 1
+    pub notes: Vec<Footnote>,
+    /// Labels referred to but never defined, in order of first reference
+    pub undefined: Vec<String>,
+}
+
+/// Take the footnotes out of `markdown`. References and definitions in
+/// code are left alone. Continuation lines of a definition are indented;
+/// the first definition of a label wins. Notes nothing refers to are
+/// dropped.
+pub fn extract_footnotes(markdown: &str) -> FootnotedText {
+    let mut definitions: HashMap<String, String> = HashMap::new();
+    let mut body = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut continued: Option<String> = None;
+
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            body.push((line, true));
+            continue;
+        }
+        if let Some(label) = continued.take() {
+            if !trimmed.is_empty() && (line.starts_with("    ") || line.starts_with('\t')) {
+                if let Some(text) = definitions.get_mut(&label) {
+                    text.push(' ');
+                    text.push_str(trimmed);
+                }
+                continued = Some(label);
+                continue;
+            }
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            body.push((line, true));
+            continue;
+        }
+        if let Some(captures) = definition_regex().captures(line.trim_end_matches(['\n', '\r'])) {
+            let label = captures[1].to_string();
+            definitions.entry(label.clone()).or_insert_with(|| captures[2].trim().to_string());
+            continued = Some(label);
+            continue;
+        }
+        body.push((line, false));
+    }
+
+    let mut footnoted = FootnotedText { text: String::new(), notes: Vec::new(), undefined: Vec::new() };
+    for (line, in_code) in body {
+        if in_code {
+            footnoted.text.push_str(line);
+            continue;
+        }
+        // Odd parts lie between backticks, in inline code
+        for (i, part) in line.split('`').enumerate() {
+            if i > 0 {
+                footnoted.text.push('`');
+            }
+            if i % 2 == 1 {
+                footnoted.text.push_str(part);
+                continue;
+            }
+            let replaced = reference_regex().replace_all(part, |captures: &Captures| {
+                footnoted.reference(&captures[1], &definitions)
+            });
+            footnoted.text.push_str(&replaced);
+        }
+    }
+    footnoted
+}
+
+impl FootnotedText {
+    /// Token standing in for a reference to `label`
+    fn reference(&mut self, label: &str, definitions: &HashMap<String, String>) -> String {
+        let Some(text) = definitions.get(label) else {
+            let index = match self.undefined.iter().position(|undefined| undefined == label) {
+                Some(index) => index,
+                None => {
+                    log::warn!("Footnote [^{label}] is referred to but not defined");
+                    self.undefined.push(label.to_string());
+                    self.undefined.len() - 1
+                }
+            };
+            return format!("{{{{fnmissing-{index}}}}}");
+        };
+
+        let index = match self.notes.iter().position(|note| note.label == label) {
+            Some(index) => index,
+            None => {
+                self.notes.push(Footnote { label: label.to_string(), text: text.clone(), references: 0 });
+                self.notes.len() - 1
+            }
+        };
+        self.notes[index].references += 1;
+        format!("{{{{fnref-{}-{}}}}}", index + 1, self.notes[index].references)
+    }
+
+    /// Turn the tokens of `html`, rendered from [`FootnotedText::text`],
+    /// into links and add the notes as endnotes with links back to their
+    /// references. `render` turns a note's markdown into HTML.
+    pub fn to_html(&self, html: &str, render: impl Fn(&str) -> String) -> String {
+        let mut html = token_regex()
+            .replace_all(html, |captures: &Captures| {
+                let index: usize = captures[2].parse().unwrap_or_default();
+                match (&captures[1], captures.get(3)) {
+                    ("ref", Some(reference)) => format!(
+                        "<sup class=\"footnote-ref\"><a href=\"#fn-{index}\" id=\"fnref-{index}-{}\">{index}</a></sup>",
+                        reference.as_str()
+                    ),
+                    _ => {
+                        let label = escape_html(self.undefined.get(index).map_or("", String::as_str));
+                        format!("<sup class=\"footnote-missing\" title=\"Undefined footnote\">[^{label}?]</sup>")
+                    }
+                }
+            })
+            .into_owned();
+        if self.notes.is_empty() {
+            return html;
+        }
+
+        html.push_str("<section class=\"footnotes\">\n<ol>\n");
+        for (i, note) in self.notes.iter().enumerate() {
+            let number = i + 1;
+            let back_links: String = (1..=note.references)
+                .map(|reference| {
+                    format!(
+                        " <a href=\"#fnref-{number}-{reference}\" class=\"footnote-backref\" aria-label=\"Back to reference {number}\">&#8617;</a>"
+                    )
+                })
+                .collect();
+            let mut note_html = render(&note.text).trim_end().to_string();
+            match note_html.strip_suffix("</p>") {
+                Some(paragraph) => note_html = format!("{paragraph}{back_links}</p>"),
+                None => note_html.push_str(&back_links),
+            }
+            html.push_str(&format!("<li id=\"fn-{number}\">{note_html}</li>\n"));
+        }
+        html.push_str("</ol>\n</section>\n");
+        html
+    }
+
+    /// Replace the tokens of `text` with bracketed numbers, and flagged
+    /// labels for undefined notes, for output without links
+    pub fn to_plain_text(&self, text: &str) -> String {
+        token_regex()
+            .replace_all(text, |captures: &Captures| {
+                let index: usize = captures[2].parse().unwrap_or_default();
+                match &captures[1] {
+                    "ref" => format!("[{index}]"),
+                    _ => format!("[^{}?]", self.undefined.get(index).map_or("", String::as_str)),
+                }
+            })
+            .into_owned()
+    }
+
+    /// The markdown followed by the notes as numbered paragraphs, for output
+    /// that can't place notes anywhere but after the text
+    pub fn with_endnotes(&self) -> String {
+        let mut markdown = self.text.trim_end().to_string();
+        for (i, note) in self.notes.iter().enumerate() {
+            markdown.push_str(&format!("\n\n\\[{}\\] {}", i + 1, note.text));
+        }
+        markdown.push('\n');
+        markdown
+    }
+}
+
+/// Prefix the footnote ids of a rendered section with `scope`, so notes of
+/// sections that share a page don't collide
+pub fn scope_footnote_ids(html: &str, scope: &str) -> String {
+    html.replace("\"#fn-", &format!("\"#{scope}-fn-"))
+        .replace("\"fn-", &format!("\"{scope}-fn-"))
+        .replace("\"#fnref-", &format!("\"#{scope}-fnref-"))
+        .replace("\"fnref-", &format!("\"{scope}-fnref-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_engine::ExportEngine;
+
+    const MANUAL: &str = "[^oil]: Use SAE 30 oil.\n\nLubricate the bearing[^oil] and check the rope[^rope].\n\n[^rope]: Replace it when frayed,\n    or every five years.\n\nKeep `arr[^oil]` in code. Oil again[^oil].\n";
+
+    #[test]
+    fn test_footnote_renders_with_back_link() {
+        let footnoted = extract_footnotes(MANUAL);
+        let numbered: Vec<(&str, usize)> = footnoted.notes.iter().map(|n| (n.label.as_str(), n.references)).collect();
+        // Ordered by first reference, not by definition
+        assert_eq!(numbered, [("oil", 2), ("rope", 1)]);
+        assert_eq!(footnoted.notes[1].text, "Replace it when frayed, or every five years.");
+        assert!(footnoted.text.contains("`arr[^oil]`"));
+        assert!(!footnoted.text.contains("SAE 30"));
+
+        let html = ExportEngine::new().render_markdown(MANUAL, &[], "en");
+        assert!(html.contains(r##"bearing<sup class="footnote-ref"><a href="#fn-1" id="fnref-1-1">1</a></sup>"##));
+        assert!(html.contains(r##"rope<sup class="footnote-ref"><a href="#fn-2" id="fnref-2-1">2</a></sup>"##));
+        assert!(html.contains(r#"<li id="fn-2"><p>Replace it when frayed, or every five years. <a href="#fnref-2-1" class="footnote-backref""#));
+        assert!(html.contains(r##"<a href="#fnref-1-2" class="footnote-backref""##));
+        assert_eq!(footnoted.to_plain_text("bearing{{fnref-1-1}}"), "bearing[1]");
+    }
+
+    #[test]
+    fn test_undefined_reference_is_flagged() {
+        let markdown = "Torque the bolts to spec[^torque].\n";
+        let footnoted = extract_footnotes(markdown);
+        assert_eq!(footnoted.undefined, ["torque"]);
+        assert!(footnoted.notes.is_empty());
+
+        let html = ExportEngine::new().render_markdown(markdown, &[], "en");
+        assert!(html.contains(r#"spec<sup class="footnote-missing" title="Undefined footnote">[^torque?]</sup>."#));
+        assert!(!html.contains("class=\"footnotes\""));
+        assert_eq!(footnoted.to_plain_text(&footnoted.text), "Torque the bolts to spec[^torque?].\n");
+    }
+}
@@ -0,0 +1,194 @@
+use super::html_bundle::escape_html;
+use super::ImagePolicy;
+use crate::i18n::{self, Language};
+use crate::Manual;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Entries listed on the front matter page, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrontMatterField {
+    Version,
+    Date,
+    Languages,
+    LegalNotice,
+}
+
+/// Cover page and front matter prepended to manual exports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrontMatterConfig {
+    /// Logo shown on the cover; a missing or unreadable file is skipped
+    pub logo_path: Option<PathBuf>,
+    pub subtitle: Option<String>,
+    /// Overrides the localized default notice
+    pub legal_notice: Option<String>,
+    pub fields: Vec<FrontMatterField>,
+    /// `chrono` format string for the manual's date
+    pub date_format: String,
+}
+
+impl Default for FrontMatterConfig {
+    fn default() -> Self {
+        Self {
+            logo_path: None,
+            subtitle: None,
+            legal_notice: None,
+            fields: vec![
+                FrontMatterField::Version,
+                FrontMatterField::Date,
+                FrontMatterField::Languages,
+                FrontMatterField::LegalNotice,
+            ],
+            date_format: "%Y-%m-%d".to_string(),
+        }
+    }
+}
+
+/// Logo image read from [`FrontMatterConfig::logo_path`]
+pub struct CoverLogo {
+    pub data: Vec<u8>,
+    pub media_type: &'static str,
+    pub extension: &'static str,
+}
+
+impl CoverLogo {
+    /// The logo downscaled and recompressed by `policy`, if there is one
+    /// and the logo is a raster image
+    pub fn processed(self, policy: Option<&ImagePolicy>) -> Self {
+        let Some(policy) = policy.filter(|_| self.media_type != "image/svg+xml") else {
+            return self;
+        };
+        match policy.apply(&self.data) {
+            Ok(image) => Self { extension: image.extension(), media_type: image.media_type, data: image.data },
+            Err(e) => {
+                log::warn!("Cover logo not processed: {e}");
+                self
+            }
+        }
+    }
+}
+
+impl FrontMatterConfig {
+    /// Read the logo, logging and returning `None` if it can't be used
+    pub fn load_logo(&self) -> Option<CoverLogo> {
+        let path = self.logo_path.as_ref()?;
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        let (media_type, extension) = match extension.as_str() {
+            "png" => ("image/png", "png"),
+            "jpg" | "jpeg" => ("image/jpeg", "jpg"),
+            "svg" => ("image/svg+xml", "svg"),
+            "gif" => ("image/gif", "gif"),
+            other => {
+                log::warn!("Cover logo {} has unsupported type {other}", path.display());
+                return None;
+            }
+        };
+
+        match fs::read(path) {
+            Ok(data) => Some(CoverLogo { data, media_type, extension }),
+            Err(e) => {
+                log::warn!("Cover logo {} not loaded: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    /// XHTML for the cover page; `logo_src` is where the caller placed the logo
+    pub fn render_cover(&self, manual: &Manual, logo_src: Option<&str>) -> String {
+        let mut html = String::from("<section class=\"cover-page\" id=\"cover\">\n");
+        if let Some(src) = logo_src {
+            html.push_str(&format!("<img class=\"cover-logo\" src=\"{}\" alt=\"\"/>\n", escape_html(src)));
+        }
+        html.push_str(&format!("<h1 class=\"cover-title\">{}</h1>\n", escape_html(&manual.title)));
+        if let Some(subtitle) = &self.subtitle {
+            html.push_str(&format!("<p class=\"cover-subtitle\">{}</p>\n", escape_html(subtitle)));
+        }
+        html.push_str(&format!("<p class=\"cover-version\">{}</p>\n", escape_html(&manual.version)));
+        html.push_str("</section>\n");
+        html
+    }
+
+    /// XHTML for the front matter page, with labels localized to `language`
+    pub fn render_front_matter(&self, manual: &Manual, language: &str) -> String {
+        let label = |key: &str| escape_html(&i18n::t_for(&format!("manuals.front_matter.{key}"), language));
+
+        let mut html = String::from("<section class=\"front-matter\" id=\"front-matter\">\n");
+        html.push_str(&format!("<h1>{}</h1>\n<dl>\n", escape_html(&manual.title)));
+        for field in &self.fields {
+            let (name, value) = match field {
+                FrontMatterField::Version => (label("version"), escape_html(&manual.version)),
+                FrontMatterField::Date => (
+                    label("date"),
+                    escape_html(&manual.updated_at.format(&self.date_format).to_string()),
+                ),
+                FrontMatterField::Languages => {
+                    let names: Vec<&str> = manual
+                        .languages
+                        .iter()
+                        .map(|code| Language::from_code(code).map_or(code.as_str(), |l| l.display_name()))
+                        .collect();
+                    (label("languages"), escape_html(&names.join(", ")))
+                }
+                FrontMatterField::LegalNotice => {
+                    let notice = match &self.legal_notice {
+                        Some(notice) => escape_html(notice),
+                        None => label("default_legal_notice"),
+                    };
+                    (label("legal_notice"), notice)
+                }
+            };
+            html.push_str(&format!("<dt>{name}</dt>\n<dd>{value}</dd>\n"));
+        }
+        html.push_str("</dl>\n</section>\n");
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManualTemplate;
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    fn manual() -> Manual {
+        Manual {
+            id: Uuid::new_v4(),
+            title: "Controller & Tower".to_string(),
+            description: String::new(),
+            sections: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(),
+            version: "3.2".to_string(),
+            languages: vec!["en".to_string(), "de".to_string(), "pt".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        }
+    }
+
+    #[test]
+    fn test_front_matter_lists_configured_fields() {
+        let config = FrontMatterConfig {
+            fields: vec![FrontMatterField::Languages, FrontMatterField::Date],
+            date_format: "%d.%m.%Y".to_string(),
+            ..Default::default()
+        };
+        let html = config.render_front_matter(&manual(), "de");
+
+        assert!(html.contains("<h1>Controller &amp; Tower</h1>"));
+        assert!(html.contains("<dd>English, Deutsch, pt</dd>"));
+        assert!(html.contains("<dd>01.03.2026</dd>"));
+        assert!(!html.contains("3.2"));
+        assert!(html.find("English").unwrap() < html.find("01.03.2026").unwrap());
+    }
+
+    #[test]
+    fn test_missing_logo_is_skipped() {
+        let config = FrontMatterConfig {
+            logo_path: Some(PathBuf::from("/nonexistent/logo.png")),
+            ..Default::default()
+        };
+        assert!(config.load_logo().is_none());
+        assert!(!config.render_cover(&manual(), None).contains("<img"));
+    }
+}
@@ -0,0 +1,164 @@
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Color, Style, Theme, ThemeSet};
+use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Color theme for code blocks, chosen from the themes bundled with the highlighter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CodeTheme {
+    #[default]
+    InspiredGitHub,
+    SolarizedLight,
+    SolarizedDark,
+    Base16OceanLight,
+    Base16OceanDark,
+    Base16EightiesDark,
+    Base16MochaDark,
+}
+
+impl CodeTheme {
+    fn theme_name(self) -> &'static str {
+        match self {
+            CodeTheme::InspiredGitHub => "InspiredGitHub",
+            CodeTheme::SolarizedLight => "Solarized (light)",
+            CodeTheme::SolarizedDark => "Solarized (dark)",
+            CodeTheme::Base16OceanLight => "base16-ocean.light",
+            CodeTheme::Base16OceanDark => "base16-ocean.dark",
+            CodeTheme::Base16EightiesDark => "base16-eighties.dark",
+            CodeTheme::Base16MochaDark => "base16-mocha.dark",
+        }
+    }
+
+    fn theme(self) -> &'static Theme {
+        static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+        &THEMES.get_or_init(ThemeSet::load_defaults).themes[self.theme_name()]
+    }
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// Find the syntax for a code block. An explicit fence label wins; without
+/// one the first line is used, e.g. a shebang. Unknown labels and
+/// undetectable code give `None` so the block renders as plain text.
+fn find_syntax(code: &str, label: Option<&str>) -> Option<&'static SyntaxReference> {
+    let syntaxes = syntax_set();
+    let syntax = match label.map(str::trim).filter(|label| !label.is_empty()) {
+        Some(label) => syntaxes.find_syntax_by_token(label),
+        None => syntaxes.find_syntax_by_first_line(code.lines().next().unwrap_or("")),
+    };
+    syntax.filter(|syntax| syntax.name != "Plain Text")
+}
+
+/// Split code into colored fragments, line by line, or `None` when the
+/// language isn't recognized
+pub fn highlight_code(code: &str, label: Option<&str>, theme: CodeTheme) -> Option<Vec<Vec<(Style, String)>>> {
+    let syntax = find_syntax(code, label)?;
+    let mut highlighter = HighlightLines::new(syntax, theme.theme());
+    LinesWithEndings::from(code)
+        .map(|line| {
+            highlighter
+                .highlight_line(line, syntax_set())
+                .ok()
+                .map(|regions| regions.into_iter().map(|(style, text)| (style, text.to_string())).collect())
+        })
+        .collect()
+}
+
+fn code_block_regex() -> &'static Regex {
+    static CODE_BLOCK: OnceLock<Regex> = OnceLock::new();
+    CODE_BLOCK.get_or_init(|| {
+        Regex::new(r#"(?s)<pre><code(?: class="language-([^"]*)")?>(.*?)</code></pre>"#).expect("valid code block regex")
+    })
+}
+
+/// Highlight the code blocks of rendered HTML in place. Blocks in a
+/// language the highlighter doesn't know keep their plain markup.
+pub fn highlight_html(html: &str, theme: CodeTheme) -> String {
+    code_block_regex()
+        .replace_all(html, |captures: &Captures| {
+            let label = captures.get(1).map(|m| m.as_str());
+            let code = unescape_html(&captures[2]);
+            let Some(lines) = highlight_code(&code, label, theme) else {
+                return captures[0].to_string();
+            };
+
+            let mut body = String::new();
+            for regions in &lines {
+                let regions: Vec<(Style, &str)> = regions.iter().map(|(style, text)| (*style, text.as_str())).collect();
+                match styled_line_to_highlighted_html(&regions, IncludeBackground::No) {
+                    Ok(line) => body.push_str(&line),
+                    Err(_) => return captures[0].to_string(),
+                }
+            }
+
+            let background = theme.theme().settings.background.map(css_color).unwrap_or_default();
+            let class = label.map(|label| format!(" class=\"language-{label}\"")).unwrap_or_default();
+            format!("<pre class=\"highlighted\" style=\"background-color:{background}\"><code{class}>{body}</code></pre>")
+        })
+        .into_owned()
+}
+
+/// Piece of rendered HTML: running text or the code of a code block
+pub(super) enum HtmlPart<'a> {
+    Text(&'a str),
+    Code { label: Option<&'a str>, code: String },
+}
+
+/// Split rendered HTML around its code blocks
+pub(super) fn split_code_blocks(html: &str) -> Vec<HtmlPart<'_>> {
+    let mut parts = Vec::new();
+    let mut last = 0;
+    for captures in code_block_regex().captures_iter(html) {
+        let whole = captures.get(0).expect("whole match");
+        parts.push(HtmlPart::Text(&html[last..whole.start()]));
+        parts.push(HtmlPart::Code {
+            label: captures.get(1).map(|m| m.as_str()),
+            code: unescape_html(&captures[2]),
+        });
+        last = whole.end();
+    }
+    parts.push(HtmlPart::Text(&html[last..]));
+    parts
+}
+
+fn css_color(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+pub(crate) fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_block_is_highlighted() {
+        let html = "<p>Run:</p>\n<pre><code class=\"language-bash\">export PATH=&quot;$HOME/bin&quot;\necho done\n</code></pre>\n";
+        let highlighted = highlight_html(html, CodeTheme::InspiredGitHub);
+
+        assert!(highlighted.starts_with("<p>Run:</p>\n<pre class=\"highlighted\""));
+        assert!(highlighted.contains("<code class=\"language-bash\"><span style=\""));
+        assert!(highlighted.contains(">echo</span>"));
+        assert!(highlight_code("#!/bin/sh\nls\n", None, CodeTheme::SolarizedDark).is_some());
+    }
+
+    #[test]
+    fn test_unknown_language_falls_back_to_plain() {
+        let html = "<pre><code class=\"language-frobnicate\">x := &lt;y&gt;\n</code></pre>\n";
+        assert_eq!(highlight_html(html, CodeTheme::InspiredGitHub), html);
+        assert!(highlight_code("just some words\n", None, CodeTheme::InspiredGitHub).is_none());
+    }
+}
@@ -0,0 +1,441 @@
+use super::captions::CaptionNumberer;
+use super::footnotes::scope_footnote_ids;
+use super::{images, isolate_code, ExportEngine};
+use crate::services::cross_refs::{CrossRefResolver, DanglingCrossRef};
+use crate::services::heading_ids::HeadingIdRegistry;
+use crate::services::TextDirection;
+use crate::{Document, Manual, ManualSection, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+impl ExportEngine {
+    /// Render every section of a manual into one self-contained HTML file.
+    ///
+    /// `documents` maps the `document_id` of each section to its content.
+    /// Screenshots for `language` are inlined as data URIs, the sidebar is
+    /// built from the section tree, and sections whose document has no
+    /// translation in `language` render a placeholder instead. Configured
+    /// front matter is placed ahead of the first section, followed by the
+    /// lists of figures and tables if configured. Cross-references link to
+    /// the section anchors on the same page. A heading whose id an earlier
+    /// section already uses gets an id scoped to its own section.
+    pub async fn export_html_bundle(
+        &self,
+        manual: &Manual,
+        documents: &HashMap<Uuid, Document>,
+        language: &str,
+    ) -> Result<String> {
+        let sections = sorted_sections(&manual.sections);
+
+        // Reading order, used for previous/next links
+        let mut reading_order = Vec::new();
+        for section in &sections {
+            flatten_sections(section, &mut reading_order);
+        }
+        let cross_refs = CrossRefResolver::new(manual);
+        let mut captions = self.caption_lists.as_ref().map(|config| CaptionNumberer::new(config.numbering, language));
+
+        let mut body = String::new();
+        let mut heading_ids = HashSet::new();
+        for (index, section) in reading_order.iter().enumerate() {
+            if let Some(captions) = &mut captions {
+                if sections.iter().any(|chapter| chapter.id == section.id) {
+                    captions.start_chapter();
+                }
+            }
+            let depth = section_depth(&sections, section.id).unwrap_or(1);
+            let heading_level = (depth + 1).min(6);
+            let title = escape_html(&section.title);
+
+            body.push_str(&format!("<section id=\"{}\" class=\"manual-section\">\n", section_anchor(section.id)));
+            body.push_str(&format!("<h{heading_level}>{title}</h{heading_level}>\n"));
+
+            if let Some(document_id) = section.document_id {
+                let content = documents
+                    .get(&document_id)
+                    .and_then(|document| document.content.get(language).map(|content| (document, content)));
+
+                match content {
+                    Some((document, content)) => {
+                        let resolved = cross_refs.resolve(content, language, |id| format!("#{}", section_anchor(id)));
+                        warn_dangling(section, &resolved.dangling);
+                        let html = self.render_bundle_section(document, &resolved.text, language).await?;
+                        let html = scope_footnote_ids(&html, &section_anchor(section.id));
+                        let mut html = dedupe_heading_ids(&html, &section_anchor(section.id), &mut heading_ids);
+                        if let Some(captions) = &mut captions {
+                            html = captions.number(&html);
+                        }
+                        body.push_str(&html);
+                    }
+                    None => {
+                        body.push_str(&format!(
+                            "<div class=\"missing-translation\">Translation of \"{title}\" is not available in {}.</div>\n",
+                            escape_html(language)
+                        ));
+                    }
+                }
+            }
+
+            body.push_str("<nav class=\"section-links\">");
+            if let Some(previous) = index.checked_sub(1).and_then(|i| reading_order.get(i)) {
+                body.push_str(&format!(
+                    "<a class=\"previous\" href=\"#{}\">&larr; {}</a>",
+                    section_anchor(previous.id),
+                    escape_html(&previous.title)
+                ));
+            }
+            if let Some(next) = reading_order.get(index + 1) {
+                body.push_str(&format!(
+                    "<a class=\"next\" href=\"#{}\">{} &rarr;</a>",
+                    section_anchor(next.id),
+                    escape_html(&next.title)
+                ));
+            }
+            body.push_str("</nav>\n</section>\n");
+        }
+
+        let mut front_matter = String::new();
+        if let Some(config) = &self.front_matter {
+            let logo_src = config
+                .load_logo()
+                .map(|logo| logo.processed(self.image_policy.as_ref()))
+                .map(|logo| format!("data:{};base64,{}", logo.media_type, STANDARD.encode(&logo.data)));
+            front_matter.push_str(&config.render_cover(manual, logo_src.as_deref()));
+            front_matter.push_str(&config.render_front_matter(manual, language));
+        }
+        if let (Some(captions), Some(config)) = (&captions, &self.caption_lists) {
+            front_matter.push_str(&captions.render_lists(config));
+        }
+
+        let mut sidebar = String::new();
+        render_sidebar(&sections, &mut sidebar);
+
+        let css = include_str!("default.css");
+        let bundle_css = include_str!("bundle.css");
+        let title = escape_html(&manual.title);
+        let lang = escape_html(language);
+        let dir = TextDirection::for_language(language).as_css();
+        let version = escape_html(&manual.version);
+
+        Ok(format!(
+            r#"<!DOCTYPE html>
+<html lang="{lang}" dir="{dir}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{title}</title>
+    <style>{css}{bundle_css}</style>
+</head>
+<body class="html-bundle">
+    <nav class="bundle-sidebar">
+        <div class="bundle-title">{title} <span class="bundle-version">{version}</span></div>
+        {sidebar}
+    </nav>
+    <main class="document-content">
+        {front_matter}
+        {body}
+    </main>
+</body>
+</html>"#
+        ))
+    }
+
+    /// Render one section's markdown with its screenshots inlined
+    async fn render_bundle_section(&self, document: &Document, content: &str, language: &str) -> Result<String> {
+        let content_with_fragments = self.process_fragments(content, language);
+        let processed = self
+            .process_screenshots(&content_with_fragments, &document.metadata.screenshots, language)
+            .await?;
+        let heading_ids = HeadingIdRegistry::from_metadata(&document.metadata, language).resolve(&processed);
+        let mut html = self.render_markdown(&processed, &heading_ids, language);
+
+        // Inline after rendering: comrak strips data URIs it considers unsafe, such as SVG
+        for screenshot in &document.metadata.screenshots {
+            let relative = format!("screenshots/{}/{}.svg", screenshot.language, screenshot.id);
+            if !html.contains(&relative) {
+                continue;
+            }
+
+            match self.screenshot_bytes(screenshot).await {
+                Ok(bytes) => {
+                    let data_uri = format!("data:image/svg+xml;base64,{}", STANDARD.encode(bytes));
+                    html = html.replace(&relative, &data_uri);
+                }
+                Err(e) => {
+                    log::warn!("Screenshot {} not inlined: {e}", screenshot.id);
+                }
+            }
+        }
+        if let Some(policy) = &self.image_policy {
+            html = images::embed_images(&html, policy).0;
+        }
+
+        Ok(isolate_code(html, language))
+    }
+}
+
+/// Dangling references are left in the output as written, so exports
+/// succeed and the author is told where to look
+pub(super) fn warn_dangling(section: &ManualSection, dangling: &[DanglingCrossRef]) {
+    for reference in dangling {
+        log::warn!(
+            "Section \"{}\" refers to missing section {} on line {}",
+            section.title,
+            reference.reference,
+            reference.line
+        );
+    }
+}
+
+pub(super) fn section_anchor(id: Uuid) -> String {
+    format!("section-{id}")
+}
+
+/// Sections in their configured order
+pub(super) fn sorted_sections(sections: &[ManualSection]) -> Vec<&ManualSection> {
+    let mut sorted: Vec<&ManualSection> = sections.iter().collect();
+    sorted.sort_by_key(|s| s.order);
+    sorted
+}
+
+/// Depth-first list of a section and its descendants, in order
+pub(super) fn flatten_sections<'a>(section: &'a ManualSection, out: &mut Vec<&'a ManualSection>) {
+    out.push(section);
+    for child in sorted_sections(&section.subsections) {
+        flatten_sections(child, out);
+    }
+}
+
+/// 1-based depth of the section with `id` in the tree
+fn section_depth(sections: &[&ManualSection], id: Uuid) -> Option<usize> {
+    sections.iter().find_map(|section| {
+        if section.id == id {
+            return Some(1);
+        }
+        let children: Vec<&ManualSection> = section.subsections.iter().collect();
+        section_depth(&children, id).map(|depth| depth + 1)
+    })
+}
+
+fn heading_anchor_id_regex() -> &'static Regex {
+    static ANCHOR_ID: OnceLock<Regex> = OnceLock::new();
+    ANCHOR_ID.get_or_init(|| Regex::new(r#"(<a [^>]*class="anchor"[^>]*id=")([^"]+)(")"#).expect("valid anchor id regex"))
+}
+
+fn fragment_link_regex() -> &'static Regex {
+    static FRAGMENT_LINK: OnceLock<Regex> = OnceLock::new();
+    FRAGMENT_LINK.get_or_init(|| Regex::new(r##"href="#([^"]+)""##).expect("valid fragment link regex"))
+}
+
+/// Rename the heading ids of one section that an earlier section already
+/// used to `{scope}-{id}`, along with the section's links to them. `used`
+/// collects the heading ids of the bundle so far.
+fn dedupe_heading_ids(html: &str, scope: &str, used: &mut HashSet<String>) -> String {
+    let mut renamed = HashMap::new();
+    for captures in heading_anchor_id_regex().captures_iter(html) {
+        let id = &captures[2];
+        if !used.insert(id.to_string()) {
+            let scoped = format!("{scope}-{id}");
+            used.insert(scoped.clone());
+            renamed.insert(id.to_string(), scoped);
+        }
+    }
+    if renamed.is_empty() {
+        return html.to_string();
+    }
+
+    let html = heading_anchor_id_regex().replace_all(html, |captures: &regex::Captures| {
+        let id = renamed.get(&captures[2]).map_or(&captures[2], String::as_str);
+        format!("{}{id}{}", &captures[1], &captures[3])
+    });
+    fragment_link_regex()
+        .replace_all(&html, |captures: &regex::Captures| {
+            let id = renamed.get(&captures[1]).map_or(&captures[1], String::as_str);
+            format!("href=\"#{id}\"")
+        })
+        .into_owned()
+}
+
+/// Nested list of section links; sections with children collapse
+fn render_sidebar(sections: &[&ManualSection], out: &mut String) {
+    out.push_str("<ul>");
+    for section in sections {
+        let link = format!(
+            "<a href=\"#{}\">{}</a>",
+            section_anchor(section.id),
+            escape_html(&section.title)
+        );
+
+        if section.subsections.is_empty() {
+            out.push_str(&format!("<li>{link}</li>"));
+        } else {
+            out.push_str(&format!("<li><details open><summary>{link}</summary>"));
+            render_sidebar(&sorted_sections(&section.subsections), out);
+            out.push_str("</details></li>");
+        }
+    }
+    out.push_str("</ul>");
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DocumentMetadata, ManualTemplate, ScreenshotReference, SectionType};
+    use chrono::Utc;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn section(title: &str, order: u32, document_id: Option<Uuid>) -> ManualSection {
+        ManualSection {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            order,
+            document_id,
+            subsections: Vec::new(),
+            section_type: SectionType::UserGuide,
+            required: true,
+            localized_titles: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_html_bundle_inlines_images_and_links_sections() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("en")).unwrap();
+        fs::write(temp_dir.path().join("en/main.svg"), "<svg></svg>").unwrap();
+
+        let intro_id = Uuid::new_v4();
+        let setup_id = Uuid::new_v4();
+        let mut documents = HashMap::new();
+        documents.insert(intro_id, Document {
+            title: "Intro".to_string(),
+            content: HashMap::from([("en".to_string(), "Welcome.\n\n{screenshot:main}\n".to_string())]),
+            metadata: DocumentMetadata {
+                project_id: None,
+                screenshots: vec![ScreenshotReference {
+                    id: "main".to_string(),
+                    language: "en".to_string(),
+                    screen_config: "{}".to_string(),
+                    generated_at: None,
+                    caption: None,
+                    content_hash: None,
+                    alt_text: HashMap::new(),
+                }],
+                version: None,
+                custom: HashMap::new(),
+            },
+        });
+        documents.insert(setup_id, Document {
+            title: "Setup".to_string(),
+            content: HashMap::from([("de".to_string(), "Nur Deutsch.".to_string())]),
+            metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+        });
+
+        let intro = section("Introduction", 1, Some(intro_id));
+        let setup = section("Setup", 2, Some(setup_id));
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Controller Manual".to_string(),
+            description: String::new(),
+            sections: vec![setup.clone(), intro.clone()],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "1.0".to_string(),
+            languages: vec!["en".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        };
+
+        let engine = ExportEngine::new().with_screenshot_dir(temp_dir.path());
+        let html = engine.export_html_bundle(&manual, &documents, "en").await.unwrap();
+
+        let expected_image = format!("data:image/svg+xml;base64,{}", STANDARD.encode("<svg></svg>"));
+        assert!(html.contains(&expected_image));
+        assert!(!html.contains("screenshots/en/main.svg"));
+
+        let intro_link = format!("href=\"#section-{}\"", intro.id);
+        let setup_link = format!("href=\"#section-{}\"", setup.id);
+        assert!(html.contains(&intro_link));
+        assert!(html.contains(&setup_link));
+        assert!(html.find(&intro_link).unwrap() < html.find(&setup_link).unwrap());
+        assert!(html.contains(&format!("id=\"section-{}\"", setup.id)));
+        assert!(html.contains("missing-translation"));
+    }
+
+    #[tokio::test]
+    async fn test_html_bundle_prefers_explicit_heading_ids() {
+        let document_id = Uuid::new_v4();
+        let documents = HashMap::from([(
+            document_id,
+            Document {
+                title: "Setup".to_string(),
+                content: HashMap::from([("en".to_string(), "## Mounting the unit {#mounting}\n\n## Wiring\n".to_string())]),
+                metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+            },
+        )]);
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Controller Manual".to_string(),
+            description: String::new(),
+            sections: vec![section("Setup", 1, Some(document_id))],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "1.0".to_string(),
+            languages: vec!["en".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        };
+
+        let html = ExportEngine::new().export_html_bundle(&manual, &documents, "en").await.unwrap();
+        assert!(html.contains("id=\"mounting\""));
+        assert!(html.contains("id=\"wiring\""));
+        assert!(!html.contains("{#mounting}"));
+    }
+
+    #[tokio::test]
+    async fn test_html_bundle_keeps_heading_ids_unique_across_sections() {
+        let overview = "## Overview\n\nSee the [overview](#overview).\n";
+        let document_ids = [Uuid::new_v4(), Uuid::new_v4()];
+        let documents: HashMap<Uuid, Document> = document_ids
+            .iter()
+            .map(|&id| {
+                let document = Document {
+                    title: "Part".to_string(),
+                    content: HashMap::from([("en".to_string(), overview.to_string())]),
+                    metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+                };
+                (id, document)
+            })
+            .collect();
+        let pump = section("Pump", 1, Some(document_ids[0]));
+        let valve = section("Valve", 2, Some(document_ids[1]));
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Controller Manual".to_string(),
+            description: String::new(),
+            sections: vec![pump.clone(), valve.clone()],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "1.0".to_string(),
+            languages: vec!["en".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        };
+
+        let html = ExportEngine::new().export_html_bundle(&manual, &documents, "en").await.unwrap();
+        // The first section keeps its id; the second is scoped to its section
+        let scoped = format!("section-{}-overview", valve.id);
+        assert_eq!(html.matches("id=\"overview\"").count(), 1);
+        assert_eq!(html.matches(&format!("id=\"{scoped}\"")).count(), 1);
+        // Each section's links point at its own heading
+        assert_eq!(html.matches("href=\"#overview\"").count(), 2);
+        assert_eq!(html.matches(&format!("href=\"#{scoped}\"")).count(), 2);
+    }
+}
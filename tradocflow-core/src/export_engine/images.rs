@@ -0,0 +1,227 @@
+use crate::{Result, TradocumentError};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Cursor;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PreferredImageFormat {
+    Png,
+    /// Smaller for photos and screenshots; images with an alpha channel
+    /// are still written as PNG
+    Jpeg,
+}
+
+/// How raster images are downscaled and recompressed before they are
+/// embedded in an export. Files on disk are never modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImagePolicy {
+    /// Longest side in pixels; larger images are scaled down to it
+    pub max_dimension: u32,
+    /// JPEG quality from 1 to 100
+    pub jpeg_quality: u8,
+    pub prefer_format: PreferredImageFormat,
+}
+
+impl Default for ImagePolicy {
+    fn default() -> Self {
+        Self {
+            max_dimension: 1600,
+            jpeg_quality: 85,
+            prefer_format: PreferredImageFormat::Jpeg,
+        }
+    }
+}
+
+/// An image ready to embed
+#[derive(Debug, Clone)]
+pub struct ProcessedImage {
+    pub data: Vec<u8>,
+    pub media_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub original_size: usize,
+}
+
+impl ProcessedImage {
+    pub fn bytes_saved(&self) -> u64 {
+        self.original_size.saturating_sub(self.data.len()) as u64
+    }
+
+    /// File extension of the image's format
+    pub fn extension(&self) -> &'static str {
+        ImageFormat::from_mime_type(self.media_type)
+            .and_then(|format| format.extensions_str().first().copied())
+            .unwrap_or("img")
+    }
+
+    /// The image with any transparency flattened onto white, for PDF output,
+    /// which can't draw an alpha channel
+    pub fn opaque_data(&self) -> Result<Vec<u8>> {
+        let image = image::load_from_memory(&self.data)
+            .map_err(|e| TradocumentError::UnsupportedFormat(format!("Image could not be decoded: {e}")))?;
+        if !image.color().has_alpha() {
+            return Ok(self.data.clone());
+        }
+        let mut flattened = image::RgbImage::new(image.width(), image.height());
+        for (x, y, pixel) in image.to_rgba8().enumerate_pixels() {
+            let alpha = u16::from(pixel[3]);
+            let blend = |channel: u8| ((u16::from(channel) * alpha + 255 * (255 - alpha)) / 255) as u8;
+            flattened.put_pixel(x, y, image::Rgb([blend(pixel[0]), blend(pixel[1]), blend(pixel[2])]));
+        }
+        let mut data = Vec::new();
+        DynamicImage::ImageRgb8(flattened)
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .map_err(|e| TradocumentError::UnsupportedFormat(format!("Image could not be encoded: {e}")))?;
+        Ok(data)
+    }
+}
+
+impl ImagePolicy {
+    /// Downscale and recompress an encoded image. Images that are already
+    /// small enough and don't get smaller by recompressing keep their
+    /// original bytes.
+    pub fn apply(&self, bytes: &[u8]) -> Result<ProcessedImage> {
+        let decode_error = |e: image::ImageError| TradocumentError::UnsupportedFormat(format!("Image could not be decoded: {e}"));
+        let original_format = image::guess_format(bytes).map_err(decode_error)?;
+        let image = image::load_from_memory_with_format(bytes, original_format).map_err(decode_error)?;
+
+        let max_dimension = self.max_dimension.max(1);
+        let oversized = image.width().max(image.height()) > max_dimension;
+        // `resize` keeps the aspect ratio within the bounds
+        let image = if oversized {
+            image.resize(max_dimension, max_dimension, FilterType::Lanczos3)
+        } else {
+            image
+        };
+
+        let format = match self.prefer_format {
+            PreferredImageFormat::Jpeg if !image.color().has_alpha() => ImageFormat::Jpeg,
+            _ => ImageFormat::Png,
+        };
+        let data = self.encode(&image, format)?;
+        let (width, height) = image.dimensions();
+
+        if !oversized && data.len() >= bytes.len() {
+            return Ok(ProcessedImage {
+                data: bytes.to_vec(),
+                media_type: original_format.to_mime_type(),
+                width,
+                height,
+                original_size: bytes.len(),
+            });
+        }
+        Ok(ProcessedImage { data, media_type: format.to_mime_type(), width, height, original_size: bytes.len() })
+    }
+
+    fn encode(&self, image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>> {
+        let encode_error = |e: image::ImageError| TradocumentError::UnsupportedFormat(format!("Image could not be encoded: {e}"));
+        let mut data = Vec::new();
+        if format == ImageFormat::Jpeg {
+            let encoder = JpegEncoder::new_with_quality(&mut data, self.jpeg_quality.clamp(1, 100));
+            image.to_rgb8().write_with_encoder(encoder).map_err(encode_error)?;
+        } else {
+            image.write_to(&mut Cursor::new(&mut data), ImageFormat::Png).map_err(encode_error)?;
+        }
+        Ok(data)
+    }
+}
+
+fn image_src_regex() -> &'static Regex {
+    static IMAGE_SRC: OnceLock<Regex> = OnceLock::new();
+    IMAGE_SRC.get_or_init(|| Regex::new(r#"<img src="([^"]+)"[^>]*>"#).expect("valid image src regex"))
+}
+
+/// The image file `src` names read and processed by `policy`; `None` for
+/// remote images, data URIs and images the policy can't recompress
+fn process_local_image(src: &str, policy: &ImagePolicy) -> Option<Result<ProcessedImage>> {
+    if src.contains("://") || src.starts_with("data:") || !is_raster_image(src) {
+        return None;
+    }
+    let path = src.strip_prefix("file://").unwrap_or(src);
+    Some(fs::read(path).map_err(TradocumentError::from).and_then(|bytes| policy.apply(&bytes)))
+}
+
+/// Embed the local raster images of rendered HTML as data URIs, processed
+/// by `policy`. Returns the HTML and the bytes saved against the original
+/// files. Images that can't be read or decoded stay linked.
+pub(super) fn embed_images(html: &str, policy: &ImagePolicy) -> (String, u64) {
+    let mut bytes_saved = 0;
+    let html = image_src_regex().replace_all(html, |captures: &Captures| {
+        let (tag, src) = (&captures[0], &captures[1]);
+        match process_local_image(src, policy) {
+            Some(Ok(image)) => {
+                bytes_saved += image.bytes_saved();
+                let data_uri = format!("data:{};base64,{}", image.media_type, STANDARD.encode(&image.data));
+                tag.replacen(src, &data_uri, 1)
+            }
+            Some(Err(e)) => {
+                log::warn!("Image {src} not embedded: {e}");
+                tag.to_string()
+            }
+            None => tag.to_string(),
+        }
+    });
+    (html.into_owned(), bytes_saved)
+}
+
+/// The `<img>` tags of rendered HTML that show local raster images, each
+/// with its image processed by `policy`, for exports that carry the images
+/// themselves
+pub(super) fn local_images<'a>(
+    html: &'a str,
+    policy: &'a ImagePolicy,
+) -> impl Iterator<Item = (Range<usize>, &'a str, Result<ProcessedImage>)> + 'a {
+    image_src_regex().captures_iter(html).filter_map(|captures| {
+        let (tag, src) = (captures.get(0)?, captures.get(1)?);
+        process_local_image(src.as_str(), policy).map(|image| (tag.range(), src.as_str(), image))
+    })
+}
+
+/// Whether a path names a raster image the policy can recompress
+fn is_raster_image(path: &str) -> bool {
+    let extension = path.rsplit('.').next().unwrap_or("").to_ascii_lowercase();
+    matches!(extension.as_str(), "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "tif" | "tiff")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    fn png(image: DynamicImage) -> Vec<u8> {
+        let mut data = Vec::new();
+        image.write_to(&mut Cursor::new(&mut data), ImageFormat::Png).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_oversized_png_is_downscaled() {
+        let photo = RgbImage::from_fn(3000, 1500, |x, y| Rgb([(x % 251) as u8, (y % 241) as u8, ((x * y) % 239) as u8]));
+        let bytes = png(DynamicImage::ImageRgb8(photo));
+        let policy = ImagePolicy { max_dimension: 1200, ..ImagePolicy::default() };
+
+        let processed = policy.apply(&bytes).unwrap();
+        assert_eq!((processed.width, processed.height), (1200, 600));
+        assert_eq!(processed.media_type, "image/jpeg");
+        assert!(processed.bytes_saved() > 0);
+        assert_eq!(image::load_from_memory(&processed.data).unwrap().dimensions(), (1200, 600));
+    }
+
+    #[test]
+    fn test_alpha_image_stays_png() {
+        let overlay = RgbaImage::from_fn(2400, 800, |x, _| Rgba([200, 30, 30, (x % 256) as u8]));
+        let bytes = png(DynamicImage::ImageRgba8(overlay));
+
+        let processed = ImagePolicy { max_dimension: 600, ..ImagePolicy::default() }.apply(&bytes).unwrap();
+        assert_eq!(processed.media_type, "image/png");
+        assert_eq!((processed.width, processed.height), (600, 200));
+        assert!(image::load_from_memory(&processed.data).unwrap().color().has_alpha());
+    }
+}
@@ -0,0 +1,303 @@
+//! Shared content included into exported markdown
+//!
+//! A line holding only `{{include: path}}` is replaced by the markdown
+//! found at `path`; `{{include: path#section}}` takes just the section
+//! under the heading with that anchor, down to the next heading of the same
+//! or a higher level. Content comes from the [`IncludeSource`]s registered
+//! with [`ExportEngine::with_include_source`], the first that has the path
+//! winning, and is taken in the exported language when it has a localized
+//! variant.
+//!
+//! Included content may include more content. An include that leads back
+//! to content it is already part of, or whose target can't be found, is
+//! replaced by a danger callout saying so, so the gap shows in the export.
+//!
+//! [`ExportEngine::with_include_source`]: super::ExportEngine::with_include_source
+
+use crate::services::outline::extract_outline;
+use crate::{i18n, Document};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+fn include_regex() -> &'static Regex {
+    static INCLUDE: OnceLock<Regex> = OnceLock::new();
+    INCLUDE.get_or_init(|| {
+        Regex::new(r"^ {0,3}\{\{include:[ \t]*([^#}\s]+)(?:#([^}\s]+))?[ \t]*\}\}[ \t]*$").expect("valid include regex")
+    })
+}
+
+/// Where included content comes from
+pub trait IncludeSource: Send + Sync {
+    /// Markdown at `path` in `language`, or in its default language when
+    /// there is no localized variant; `None` when there is nothing at `path`
+    fn load(&self, path: &str, language: &str) -> Option<String>;
+}
+
+/// A directory of markdown snippets. `path` is relative to the directory;
+/// a localized variant lives at `<language>/<path>` and is preferred over
+/// `<path>`. Paths leading out of the directory are never read.
+#[derive(Debug, Clone)]
+pub struct SnippetLibrary {
+    dir: PathBuf,
+}
+
+impl SnippetLibrary {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self { dir: dir.as_ref().to_path_buf() }
+    }
+}
+
+impl IncludeSource for SnippetLibrary {
+    fn load(&self, path: &str, language: &str) -> Option<String> {
+        let relative = Path::new(path);
+        if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+            log::warn!("Not including {path}: it leads out of the snippet library");
+            return None;
+        }
+        [self.dir.join(language).join(relative), self.dir.join(relative)]
+            .iter()
+            .find_map(|file| fs::read_to_string(file).ok())
+    }
+}
+
+/// Documents to include from, by the path they are included with. A
+/// document without content in the exported language is included in
+/// `default_language`.
+#[derive(Debug, Clone)]
+pub struct DocumentLibrary {
+    documents: HashMap<String, Document>,
+    default_language: String,
+}
+
+impl DocumentLibrary {
+    pub fn new(default_language: impl Into<String>) -> Self {
+        Self { documents: HashMap::new(), default_language: default_language.into() }
+    }
+
+    /// Make `document` includable as `path`
+    pub fn with_document(mut self, path: impl Into<String>, document: Document) -> Self {
+        self.documents.insert(path.into(), document);
+        self
+    }
+}
+
+impl IncludeSource for DocumentLibrary {
+    fn load(&self, path: &str, language: &str) -> Option<String> {
+        let content = &self.documents.get(path)?.content;
+        content.get(language).or_else(|| content.get(&self.default_language)).cloned()
+    }
+}
+
+/// Why an include was left out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncludeError {
+    /// No source has the target, or the target has no such section
+    NotFound { target: String },
+    /// The target is already being included; `chain` runs from the
+    /// outermost include to the repeated target
+    Cycle { chain: Vec<String> },
+}
+
+impl IncludeError {
+    fn message(&self, language: &str) -> String {
+        match self {
+            IncludeError::NotFound { target } => i18n::t_for_with_args(
+                "manuals.includes.not_found",
+                language,
+                &HashMap::from([("target".to_string(), target.clone())]),
+            ),
+            IncludeError::Cycle { chain } => i18n::t_for_with_args(
+                "manuals.includes.cycle",
+                language,
+                &HashMap::from([("chain".to_string(), chain.join(" → "))]),
+            ),
+        }
+    }
+}
+
+/// Markdown with its includes resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludedText {
+    pub text: String,
+    /// Includes replaced by an error callout, in document order
+    pub errors: Vec<IncludeError>,
+}
+
+/// Replace the include directives of `markdown` with the content they name,
+/// in `language`. Directives in fenced code are left alone.
+pub fn resolve_includes(markdown: &str, language: &str, sources: &[Arc<dyn IncludeSource>]) -> IncludedText {
+    let mut included = IncludedText { text: String::new(), errors: Vec::new() };
+    if !markdown.contains("{{include:") {
+        included.text = markdown.to_string();
+        return included;
+    }
+    included.push(markdown, language, sources, &mut Vec::new());
+    included
+}
+
+impl IncludedText {
+    /// Add `markdown` with its includes resolved; `open` holds the targets
+    /// being included around it
+    fn push(&mut self, markdown: &str, language: &str, sources: &[Arc<dyn IncludeSource>], open: &mut Vec<String>) {
+        let mut fence: Option<&str> = None;
+        for line in markdown.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+                self.text.push_str(line);
+                continue;
+            }
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fence = Some(&trimmed[..3]);
+                self.text.push_str(line);
+                continue;
+            }
+            let Some(captures) = include_regex().captures(line.trim_end_matches(['\n', '\r'])) else {
+                self.text.push_str(line);
+                continue;
+            };
+
+            let (path, section) = (&captures[1], captures.get(2).map(|section| section.as_str()));
+            let target = section.map_or_else(|| path.to_string(), |section| format!("{path}#{section}"));
+            let content = if open.contains(&target) {
+                let mut chain = open.clone();
+                chain.push(target.clone());
+                Err(IncludeError::Cycle { chain })
+            } else {
+                load(path, section, language, sources).ok_or_else(|| IncludeError::NotFound { target: target.clone() })
+            };
+
+            match content {
+                Ok(content) => {
+                    open.push(target);
+                    self.push(&content, language, sources, open);
+                    open.pop();
+                    if !self.text.ends_with('\n') {
+                        self.text.push('\n');
+                    }
+                }
+                Err(error) => {
+                    let message = error.message(language);
+                    log::warn!("{message}");
+                    // A blank line after the callout keeps the next line out of it
+                    self.text.push_str(&format!("> [!DANGER]\n> {}\n\n", escape_markdown(&message)));
+                    self.errors.push(error);
+                }
+            }
+        }
+    }
+}
+
+/// The content at `path`, cut down to `section` when given
+fn load(path: &str, section: Option<&str>, language: &str, sources: &[Arc<dyn IncludeSource>]) -> Option<String> {
+    let content = sources.iter().find_map(|source| source.load(path, language))?;
+    let Some(section) = section else {
+        return Some(content);
+    };
+
+    let outline = extract_outline(&content);
+    let headings: Vec<_> = outline.iter().flat_map(|node| node.flatten()).collect();
+    let index = headings.iter().position(|heading| heading.slug == section)?;
+    let heading = headings[index];
+    let end = headings[index + 1..]
+        .iter()
+        .find(|next| next.level <= heading.level)
+        .map_or(content.len(), |next| next.offset);
+    Some(content[heading.offset..end].to_string())
+}
+
+/// Backslash-escape the ASCII punctuation of `text`, so it shows as written
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii_punctuation() {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_engine::ExportEngine;
+    use crate::DocumentMetadata;
+    use tempfile::TempDir;
+
+    fn library() -> (TempDir, Vec<Arc<dyn IncludeSource>>) {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("legal")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("de/legal")).unwrap();
+        fs::write(
+            temp_dir.path().join("legal/notices.md"),
+            "# Notices\n\n## Warranty {#warranty}\n\nTwo years.\n\n### Exclusions\n\nWear parts.\n\n## Disposal\n\nRecycle it.\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("de/legal/notices.md"), "## Gewährleistung {#warranty}\n\nZwei Jahre.\n").unwrap();
+
+        let document = |content: &str| Document {
+            title: "Safety".to_string(),
+            content: HashMap::from([("en".to_string(), content.to_string())]),
+            metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+        };
+        let documents = DocumentLibrary::new("en")
+            .with_document("safety", document("Wear gloves.\n\n{{include: legal/notices.md#warranty}}\n"))
+            .with_document("loop-a", document("A\n{{include: loop-b}}\n"))
+            .with_document("loop-b", document("B\n{{include: loop-a}}\n"));
+        let sources: Vec<Arc<dyn IncludeSource>> = vec![Arc::new(SnippetLibrary::new(temp_dir.path())), Arc::new(documents)];
+        (temp_dir, sources)
+    }
+
+    #[test]
+    fn test_include_resolves_section_in_language() {
+        let (_temp_dir, sources) = library();
+        let markdown = "# Manual\n\n{{include: safety}}\n\n```\n{{include: safety}}\n```\n";
+
+        let english = resolve_includes(markdown, "en", &sources);
+        assert!(english.errors.is_empty());
+        assert_eq!(
+            english.text,
+            "# Manual\n\nWear gloves.\n\n## Warranty {#warranty}\n\nTwo years.\n\n### Exclusions\n\nWear parts.\n\n\n```\n{{include: safety}}\n```\n"
+        );
+
+        // The snippet has a German variant; the document falls back to English
+        let german = resolve_includes(markdown, "de", &sources);
+        assert!(german.text.contains("Wear gloves.\n\n## Gewährleistung {#warranty}\n\nZwei Jahre.\n"));
+        assert!(!german.text.contains("Two years"));
+    }
+
+    #[test]
+    fn test_missing_target_is_flagged() {
+        let (temp_dir, sources) = library();
+        let included = resolve_includes("{{include: legal/notices.md#recall}}\n{{include: ../secrets.md}}\n", "en", &sources);
+        assert_eq!(
+            included.errors,
+            [
+                IncludeError::NotFound { target: "legal/notices.md#recall".to_string() },
+                IncludeError::NotFound { target: "../secrets.md".to_string() },
+            ]
+        );
+
+        let engine = ExportEngine::new().with_include_source(SnippetLibrary::new(temp_dir.path()));
+        let html = engine.render_markdown(&engine.process_fragments("{{include: legal/notices.md#recall}}\n", "en"), &[], "en");
+        assert!(html.contains(r#"<div class="callout callout-danger" role="note">"#));
+        assert!(html.contains("legal/notices.md#recall"));
+    }
+
+    #[test]
+    fn test_include_cycle_is_detected() {
+        let (_temp_dir, sources) = library();
+        let included = resolve_includes("{{include: loop-a}}\n", "en", &sources);
+        assert_eq!(
+            included.errors,
+            [IncludeError::Cycle { chain: vec!["loop-a".to_string(), "loop-b".to_string(), "loop-a".to_string()] }]
+        );
+        assert!(included.text.starts_with("A\nB\n> [!DANGER]\n> "));
+    }
+}
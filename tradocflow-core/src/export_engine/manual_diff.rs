@@ -0,0 +1,374 @@
+use super::html_bundle::{escape_html, flatten_sections, sorted_sections};
+use super::review_report::word_diff_html;
+use super::{ExportConfig, ExportEngine, ExportFormat};
+use crate::services::TextDirection;
+use crate::{Document, Manual, ManualSection, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// How a section differs between two versions of a manual
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SectionChangeKind {
+    Added,
+    Removed,
+    Modified,
+    Unchanged,
+}
+
+/// One section of a [`ManualDiff`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionChange {
+    /// Id in the newer version, or in the older one for removed sections
+    pub section_id: Uuid,
+    pub kind: SectionChangeKind,
+    /// Title in the diff's language, in the older and newer version
+    pub old_title: Option<String>,
+    pub new_title: Option<String>,
+    /// Section text in the diff's language; empty when the section has none
+    pub old_text: String,
+    pub new_text: String,
+    /// 1-based nesting depth in the version the section is taken from
+    pub depth: usize,
+}
+
+impl SectionChange {
+    pub fn title(&self) -> &str {
+        self.new_title.as_deref().or(self.old_title.as_deref()).unwrap_or_default()
+    }
+}
+
+/// Section-level changes from one version of a manual to another, in one
+/// language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManualDiff {
+    pub title: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub language: String,
+    /// Sections of the newer version in order, followed by removed ones
+    pub sections: Vec<SectionChange>,
+}
+
+impl ManualDiff {
+    /// Sections that were added, removed or modified
+    pub fn changes(&self) -> impl Iterator<Item = &SectionChange> {
+        self.sections.iter().filter(|section| section.kind != SectionChangeKind::Unchanged)
+    }
+
+    pub fn count(&self, kind: SectionChangeKind) -> usize {
+        self.sections.iter().filter(|section| section.kind == kind).count()
+    }
+}
+
+/// Compare two versions of a manual in `language`.
+///
+/// Sections are matched by id, and sections the newer version gave a new id
+/// by their title. A section is modified when its title or its document's
+/// text in `language` changed; other languages are not looked at.
+pub fn diff_manuals(
+    old: &Manual,
+    old_documents: &HashMap<Uuid, Document>,
+    new: &Manual,
+    new_documents: &HashMap<Uuid, Document>,
+    language: &str,
+) -> ManualDiff {
+    let old_sections = flattened(&old.sections);
+    let new_sections = flattened(&new.sections);
+    let text = |section: &ManualSection, documents: &HashMap<Uuid, Document>| {
+        section
+            .document_id
+            .and_then(|id| documents.get(&id))
+            .and_then(|document| document.content.get(language))
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let new_ids: HashSet<Uuid> = new_sections.iter().map(|(section, _)| section.id).collect();
+    let mut matched = HashSet::new();
+    let mut sections = Vec::new();
+    for (section, depth) in &new_sections {
+        let title = section.localized_title(language);
+        let previous = old_sections
+            .iter()
+            .find(|(old_section, _)| old_section.id == section.id)
+            .or_else(|| {
+                old_sections.iter().find(|(old_section, _)| {
+                    !new_ids.contains(&old_section.id)
+                        && !matched.contains(&old_section.id)
+                        && old_section.localized_title(language).eq_ignore_ascii_case(title)
+                })
+            });
+
+        let new_text = text(section, new_documents);
+        sections.push(match previous {
+            Some((old_section, _)) => {
+                matched.insert(old_section.id);
+                let old_title = old_section.localized_title(language);
+                let old_text = text(old_section, old_documents);
+                let kind = if old_title != title || old_text != new_text {
+                    SectionChangeKind::Modified
+                } else {
+                    SectionChangeKind::Unchanged
+                };
+                SectionChange {
+                    section_id: section.id,
+                    kind,
+                    old_title: Some(old_title.to_string()),
+                    new_title: Some(title.to_string()),
+                    old_text,
+                    new_text,
+                    depth: *depth,
+                }
+            }
+            None => SectionChange {
+                section_id: section.id,
+                kind: SectionChangeKind::Added,
+                old_title: None,
+                new_title: Some(title.to_string()),
+                old_text: String::new(),
+                new_text,
+                depth: *depth,
+            },
+        });
+    }
+
+    for (section, depth) in &old_sections {
+        if !matched.contains(&section.id) {
+            sections.push(SectionChange {
+                section_id: section.id,
+                kind: SectionChangeKind::Removed,
+                old_title: Some(section.localized_title(language).to_string()),
+                new_title: None,
+                old_text: text(section, old_documents),
+                new_text: String::new(),
+                depth: *depth,
+            });
+        }
+    }
+
+    ManualDiff {
+        title: new.title.clone(),
+        old_version: old.version.clone(),
+        new_version: new.version.clone(),
+        language: language.to_string(),
+        sections,
+    }
+}
+
+/// Sections depth-first in order, with their 1-based depth
+fn flattened(sections: &[ManualSection]) -> Vec<(&ManualSection, usize)> {
+    let mut out = Vec::new();
+    for root in sorted_sections(sections) {
+        let mut subtree = Vec::new();
+        flatten_sections(root, &mut subtree);
+        out.extend(subtree.into_iter().map(|section| (section, depth_below(root, section.id))));
+    }
+    out
+}
+
+fn depth_below(section: &ManualSection, id: Uuid) -> usize {
+    if section.id == id {
+        return 1;
+    }
+    section
+        .subsections
+        .iter()
+        .find_map(|child| {
+            let depth = depth_below(child, id);
+            (depth > 0).then_some(depth + 1)
+        })
+        .unwrap_or(0)
+}
+
+fn label(kind: SectionChangeKind) -> &'static str {
+    match kind {
+        SectionChangeKind::Added => "Added",
+        SectionChangeKind::Removed => "Removed",
+        SectionChangeKind::Modified => "Modified",
+        SectionChangeKind::Unchanged => "Unchanged",
+    }
+}
+
+impl ExportEngine {
+    /// Export a change summary between two versions of a manual, such as
+    /// two tagged releases, with one file per format and language.
+    ///
+    /// Returns the rendered files keyed by name, like the output of
+    /// [`ExportEngine::export_document`].
+    pub fn export_manual_diff(
+        &self,
+        old: (&Manual, &HashMap<Uuid, Document>),
+        new: (&Manual, &HashMap<Uuid, Document>),
+        config: &ExportConfig,
+    ) -> Result<HashMap<String, Vec<u8>>> {
+        let mut results = HashMap::new();
+        for language in &config.languages {
+            let diff = diff_manuals(old.0, old.1, new.0, new.1, language);
+            let name = format!("changes-{}-{}-{language}", diff.old_version, diff.new_version);
+            if matches!(config.format, ExportFormat::Html | ExportFormat::Both) {
+                results.insert(format!("{name}.html"), self.render_manual_diff(&diff).into_bytes());
+            }
+            if matches!(config.format, ExportFormat::Pdf | ExportFormat::Both) {
+                results.insert(format!("{name}.pdf"), self.generate_pdf(&manual_diff_markdown(&diff), language, config, &mut 0)?);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Render a change summary as HTML.
+    ///
+    /// Added and removed sections are labeled and show their whole text;
+    /// modified sections show the text before and after with removed words
+    /// struck through and added words highlighted. Unchanged sections are
+    /// left out.
+    pub fn render_manual_diff(&self, diff: &ManualDiff) -> String {
+        let mut body = String::new();
+        for section in diff.changes() {
+            let kind = label(section.kind);
+            let class = kind.to_lowercase();
+            let title = escape_html(section.title());
+            let renamed = match (&section.old_title, &section.new_title) {
+                (Some(old), Some(new)) if old != new => {
+                    format!("<p class=\"review-meta\">Renamed from &ldquo;{}&rdquo;</p>\n", escape_html(old))
+                }
+                _ => String::new(),
+            };
+            let content = match section.kind {
+                SectionChangeKind::Modified => {
+                    let (before, after) = word_diff_html(&section.old_text, &section.new_text);
+                    format!(
+                        "<table class=\"review-diff\"><tr><th>{}</th><th>{}</th></tr>\
+                         <tr><td class=\"before\">{before}</td><td class=\"after\">{after}</td></tr></table>\n",
+                        escape_html(&diff.old_version),
+                        escape_html(&diff.new_version)
+                    )
+                }
+                SectionChangeKind::Removed => format!("<pre class=\"change-text\"><del>{}</del></pre>\n", escape_html(&section.old_text)),
+                _ => format!("<pre class=\"change-text\"><ins>{}</ins></pre>\n", escape_html(&section.new_text)),
+            };
+            body.push_str(&format!(
+                "<section class=\"review-section change-{class}\">\n\
+                 <h2><span class=\"change-label\">{kind}</span> {title}</h2>\n{renamed}{content}</section>\n"
+            ));
+        }
+        if body.is_empty() {
+            body.push_str("<p>No sections changed.</p>\n");
+        }
+
+        let css = include_str!("default.css");
+        let report_css = include_str!("review_report.css");
+        let lang = escape_html(&diff.language);
+        let dir = TextDirection::for_language(&diff.language).as_css();
+        let title = escape_html(&format!("{}: changes from {} to {}", diff.title, diff.old_version, diff.new_version));
+        let summary = format!(
+            "{} added, {} removed, {} modified, {} unchanged",
+            diff.count(SectionChangeKind::Added),
+            diff.count(SectionChangeKind::Removed),
+            diff.count(SectionChangeKind::Modified),
+            diff.count(SectionChangeKind::Unchanged)
+        );
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="{lang}" dir="{dir}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{title}</title>
+    <style>{css}{report_css}</style>
+</head>
+<body class="review-report">
+    <div class="document-content">
+        <h1>{title}</h1>
+        <p class="review-summary">{summary}</p>
+        {body}
+    </div>
+</body>
+</html>"#
+        )
+    }
+}
+
+/// Plain rendition of the change summary for PDF output
+fn manual_diff_markdown(diff: &ManualDiff) -> String {
+    let mut markdown = format!("# {}: changes from {} to {}\n\n", diff.title, diff.old_version, diff.new_version);
+    for section in diff.changes() {
+        markdown.push_str(&format!("## {}: {}\n\n", label(section.kind), section.title()));
+        match section.kind {
+            SectionChangeKind::Modified => markdown.push_str(&format!(
+                "Before: {}\n\nAfter: {}\n\n",
+                section.old_text.trim(),
+                section.new_text.trim()
+            )),
+            SectionChangeKind::Removed => markdown.push_str(&format!("{}\n\n", section.old_text.trim())),
+            _ => markdown.push_str(&format!("{}\n\n", section.new_text.trim())),
+        }
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::templates::scaffold;
+    use crate::{DocumentMetadata, ManualTemplate};
+
+    fn document(texts: &[(&str, &str)]) -> Document {
+        Document {
+            title: String::new(),
+            content: texts.iter().map(|(language, text)| (language.to_string(), text.to_string())).collect(),
+            metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+        }
+    }
+
+    #[test]
+    fn test_added_and_modified_sections_are_reported() {
+        let languages = vec!["en".to_string(), "de".to_string()];
+        let mut old = scaffold(&ManualTemplate::UserGuide, &languages);
+        old.version = "1.1".to_string();
+        let (intro_doc, start_doc) = (Uuid::new_v4(), Uuid::new_v4());
+        old.sections[0].document_id = Some(intro_doc);
+        old.sections[1].document_id = Some(start_doc);
+        let old_documents = HashMap::from([
+            (intro_doc, document(&[("en", "Welcome to the controller."), ("de", "Willkommen.")])),
+            (start_doc, document(&[("en", "Press the power button."), ("de", "Drücken Sie den Knopf.")])),
+        ]);
+
+        let mut new = old.clone();
+        new.version = "1.2".to_string();
+        let mut wiring = new.sections[1].clone();
+        wiring.id = Uuid::new_v4();
+        wiring.title = "Wiring".to_string();
+        wiring.order = 2;
+        wiring.document_id = Some(Uuid::new_v4());
+        new.sections[1].subsections.push(wiring.clone());
+        let mut new_documents = old_documents.clone();
+        new_documents.insert(start_doc, document(&[("en", "Press and hold the power button."), ("de", "Drücken Sie den Knopf.")]));
+        new_documents.insert(wiring.document_id.unwrap(), document(&[("en", "Connect the relay.")]));
+
+        let diff = diff_manuals(&old, &old_documents, &new, &new_documents, "en");
+        let changes: Vec<&SectionChange> = diff.changes().collect();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].kind, SectionChangeKind::Modified);
+        assert_eq!(changes[0].title(), "Getting Started");
+        assert_eq!(changes[1].kind, SectionChangeKind::Added);
+        assert_eq!((changes[1].title(), changes[1].depth), ("Wiring", 2));
+
+        // The German text of the modified section is the same
+        let german = diff_manuals(&old, &old_documents, &new, &new_documents, "de");
+        assert_eq!(german.count(SectionChangeKind::Modified), 0);
+        assert_eq!(german.count(SectionChangeKind::Added), 1);
+
+        let removed = diff_manuals(&new, &new_documents, &old, &old_documents, "en");
+        assert_eq!(removed.sections.last().unwrap().kind, SectionChangeKind::Removed);
+        assert_eq!(removed.sections.last().unwrap().title(), "Wiring");
+
+        let html = ExportEngine::new().render_manual_diff(&diff);
+        assert!(html.contains("<span class=\"change-label\">Added</span> Wiring"));
+        assert!(html.contains("<span class=\"change-label\">Modified</span> Getting Started"));
+        assert!(html.contains("Press <ins>and hold </ins>"));
+        assert!(html.contains("1 added, 0 removed, 1 modified"));
+        assert!(!html.contains("Introduction"));
+    }
+}
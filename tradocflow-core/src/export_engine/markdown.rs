@@ -0,0 +1,159 @@
+use super::ExportEngine;
+use crate::{Document, Result, TradocumentError};
+use regex::{Captures, Regex};
+use std::path::Path;
+use std::sync::OnceLock;
+
+fn image_regex() -> &'static Regex {
+    static IMAGE: OnceLock<Regex> = OnceLock::new();
+    IMAGE.get_or_init(|| Regex::new(r#"!\[([^\]]*)\]\(([^)\s]+)((?:\s+"[^"]*")?)\)"#).expect("valid image regex"))
+}
+
+impl ExportEngine {
+    /// Export one language of a document as Markdown with YAML front matter,
+    /// for static site generators.
+    ///
+    /// The front matter holds the title, project id, language, version, the
+    /// images the body references, and every `custom` metadata entry.
+    /// Screenshot placeholders are expanded and absolute image paths are
+    /// rewritten to `images/<file name>`; remote images are left as they are.
+    pub async fn export_markdown(&self, document: &Document, language: &str) -> Result<String> {
+        let content = document.content.get(language).ok_or_else(|| {
+            TradocumentError::Validation(format!("Document \"{}\" has no content in {language}", document.title))
+        })?;
+        let content = self.process_fragments(content, language);
+        let content = self.process_screenshots(&content, &document.metadata.screenshots, language).await?;
+
+        let mut images: Vec<String> = Vec::new();
+        let body = image_regex().replace_all(&content, |captures: &Captures| {
+            let path = relative_image_path(&captures[2]);
+            if !is_remote(&path) && !images.contains(&path) {
+                images.push(path.clone());
+            }
+            format!("![{}]({}{})", &captures[1], path, &captures[3])
+        });
+
+        let metadata = &document.metadata;
+        let mut front_matter = String::from("---\n");
+        front_matter.push_str(&format!("title: {}\n", yaml_string(&document.title)));
+        if let Some(project_id) = &metadata.project_id {
+            front_matter.push_str(&format!("project_id: {}\n", yaml_string(project_id)));
+        }
+        front_matter.push_str(&format!("language: {}\n", yaml_string(language)));
+        if let Some(version) = &metadata.version {
+            front_matter.push_str(&format!("version: {}\n", yaml_string(version)));
+        }
+        if !images.is_empty() {
+            front_matter.push_str("images:\n");
+            for image in &images {
+                front_matter.push_str(&format!("  - {}\n", yaml_string(image)));
+            }
+        }
+        if !metadata.custom.is_empty() {
+            let mut custom: Vec<_> = metadata.custom.iter().collect();
+            custom.sort();
+            front_matter.push_str("custom:\n");
+            for (key, value) in custom {
+                front_matter.push_str(&format!("  {}: {}\n", yaml_string(key), yaml_string(value)));
+            }
+        }
+        front_matter.push_str("---\n\n");
+        front_matter.push_str(&body);
+
+        Ok(front_matter)
+    }
+}
+
+/// Split YAML front matter delimited by `---` lines from the body of a
+/// Markdown file. Returns `None` for the front matter if there is none.
+pub fn split_front_matter(text: &str) -> (Option<&str>, &str) {
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return (None, text);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let body = &rest[offset + line.len()..];
+            // The exporter separates the front matter from the body with a blank line
+            let body = body.strip_prefix("\r\n").or_else(|| body.strip_prefix('\n')).unwrap_or(body);
+            return (Some(&rest[..offset]), body);
+        }
+        offset += line.len();
+    }
+    (None, text)
+}
+
+fn is_remote(path: &str) -> bool {
+    ["http://", "https://", "data:"].iter().any(|scheme| path.starts_with(scheme))
+}
+
+fn relative_image_path(path: &str) -> String {
+    if is_remote(path) {
+        return path.to_string();
+    }
+    let local = path.strip_prefix("file://").unwrap_or(path);
+    if Path::new(local).is_absolute() || local.starts_with('/') {
+        let file_name = Path::new(local).file_name().and_then(|name| name.to_str()).unwrap_or(local);
+        return format!("images/{file_name}");
+    }
+    local.strip_prefix("./").unwrap_or(local).to_string()
+}
+
+/// Double-quoted YAML scalar; JSON string escaping is valid YAML
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{value}\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DocumentMetadata, ScreenshotReference};
+    use std::collections::HashMap;
+
+    fn document(content: &str) -> Document {
+        Document {
+            title: "Setup: \"Quick\" Guide".to_string(),
+            content: HashMap::from([("de".to_string(), content.to_string())]),
+            metadata: DocumentMetadata {
+                project_id: Some("7f8e1c2a".to_string()),
+                screenshots: vec![ScreenshotReference {
+                    id: "main".to_string(),
+                    language: "de".to_string(),
+                    screen_config: "{}".to_string(),
+                    generated_at: None,
+                    caption: None,
+                    content_hash: None,
+                    alt_text: HashMap::new(),
+                }],
+                version: Some("2.1".to_string()),
+                custom: HashMap::from([("audience".to_string(), "installers".to_string())]),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_front_matter_fields_and_custom_metadata() {
+        let content = "# Einrichtung\n\n{screenshot:main}\n\n![Schaltplan](/home/docs/assets/wiring.png \"Plan\")\n";
+        let markdown = ExportEngine::new().export_markdown(&document(content), "de").await.unwrap();
+
+        let (front_matter, body) = split_front_matter(&markdown);
+        let front_matter = front_matter.unwrap();
+        assert!(front_matter.contains("title: \"Setup: \\\"Quick\\\" Guide\"\n"));
+        assert!(front_matter.contains("project_id: \"7f8e1c2a\"\n"));
+        assert!(front_matter.contains("language: \"de\"\n"));
+        assert!(front_matter.contains("version: \"2.1\"\n"));
+        assert!(front_matter.contains("custom:\n  \"audience\": \"installers\"\n"));
+        assert!(front_matter.contains("images:\n  - \"screenshots/de/main.svg\"\n  - \"images/wiring.png\"\n"));
+        assert!(body.contains("![Schaltplan](images/wiring.png \"Plan\")"));
+        assert!(body.contains("![Screenshot main](screenshots/de/main.svg)"));
+    }
+
+    #[tokio::test]
+    async fn test_body_round_trips_through_front_matter_split() {
+        let content = "# Wartung\n\n---\n\nText mit *Betonung*.\n";
+        let markdown = ExportEngine::new().export_markdown(&document(content), "de").await.unwrap();
+
+        assert_eq!(split_front_matter(&markdown).1, content);
+        assert!(ExportEngine::new().export_markdown(&document(content), "fr").await.is_err());
+    }
+}
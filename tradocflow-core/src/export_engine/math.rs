@@ -0,0 +1,768 @@
+//! Equations in exported markdown
+//!
+//! `$...$` is inline math and `$$...$$` display math. Equations are taken
+//! out of the markdown before it is rendered, so markdown syntax doesn't
+//! touch the LaTeX, and come back as MathML in HTML and as Unicode text in
+//! PDF. The LaTeX is read by the small parser here, which covers the
+//! notation of engineering manuals: scripts, fractions, roots, Greek
+//! letters, common operators, functions and text. An equation it can't
+//! read is shown as its source in an error box.
+//!
+//! A `$` only opens inline math when it is followed by a non-space and
+//! closes when preceded by a non-space and not followed by a digit, so
+//! amounts like "$5 to $10" stay text. `\$` is always a dollar sign.
+
+use super::html_bundle::escape_html;
+use crate::{i18n, Result, TradocumentError};
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+fn token_regex() -> &'static Regex {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    TOKEN.get_or_init(|| Regex::new(r"(<p>)?\{\{math-(\d+)\}\}(</p>)?").expect("valid math token regex"))
+}
+
+/// An equation of a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equation {
+    pub latex: String,
+    /// Display math, set apart from the text, rather than inline math
+    pub display: bool,
+}
+
+/// Markdown with its equations replaced by tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MathText {
+    pub text: String,
+    /// Equations in document order
+    pub equations: Vec<Equation>,
+}
+
+/// Take the equations out of `markdown`. Dollar signs in code are left
+/// alone.
+pub fn extract_math(markdown: &str) -> MathText {
+    let mut math = MathText { text: String::new(), equations: Vec::new() };
+    let mut chunk = String::new();
+    let mut in_code = false;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let fence = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+        if fence && !in_code {
+            math.push_chunk(&std::mem::take(&mut chunk));
+        }
+        if fence {
+            in_code = !in_code;
+            math.text.push_str(line);
+        } else if in_code {
+            math.text.push_str(line);
+        } else {
+            chunk.push_str(line);
+        }
+    }
+    math.push_chunk(&chunk);
+    math
+}
+
+impl MathText {
+    /// Replace the equations of text outside fenced code
+    fn push_chunk(&mut self, chunk: &str) {
+        let chars: Vec<char> = chunk.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '\\' if i + 1 < chars.len() => {
+                    self.text.push(chars[i]);
+                    self.text.push(chars[i + 1]);
+                    i += 2;
+                }
+                '`' => {
+                    let run = chars[i..].iter().take_while(|&&c| c == '`').count();
+                    let end = find_backtick_run(&chars, i + run, run).map_or(i + run, |end| end + run);
+                    self.text.extend(&chars[i..end]);
+                    i = end;
+                }
+                '$' if chars.get(i + 1) == Some(&'$') => match find_display_end(&chars, i + 2) {
+                    Some(end) => {
+                        self.push_equation(chars[i + 2..end].iter().collect(), true);
+                        i = end + 2;
+                    }
+                    None => {
+                        self.text.push_str("$$");
+                        i += 2;
+                    }
+                },
+                '$' => match find_inline_end(&chars, i + 1) {
+                    Some(end) => {
+                        self.push_equation(chars[i + 1..end].iter().collect(), false);
+                        i = end + 1;
+                    }
+                    None => {
+                        self.text.push('$');
+                        i += 1;
+                    }
+                },
+                c => {
+                    self.text.push(c);
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    fn push_equation(&mut self, latex: String, display: bool) {
+        self.text.push_str(&format!("{{{{math-{}}}}}", self.equations.len()));
+        self.equations.push(Equation { latex: latex.trim().to_string(), display });
+    }
+
+    /// Turn the tokens of `html`, rendered from [`MathText::text`], into
+    /// MathML. A display equation on its own becomes a block in place of
+    /// its paragraph. Errors are labelled in `language`.
+    pub fn to_html(&self, html: &str, language: &str) -> String {
+        token_regex()
+            .replace_all(html, |captures: &Captures| {
+                let Some(equation) = captures[2].parse::<usize>().ok().and_then(|i| self.equations.get(i)) else {
+                    return captures[0].to_string();
+                };
+                let paragraph = captures.get(1).is_some() && captures.get(3).is_some();
+                let rendered = match latex_to_mathml(&equation.latex, equation.display) {
+                    Ok(mathml) if equation.display && paragraph => {
+                        return format!("<div class=\"math math-display\">{mathml}</div>");
+                    }
+                    Ok(mathml) => mathml,
+                    Err(e) => {
+                        let label = escape_html(&i18n::t_for("manuals.math.invalid", language));
+                        let source = escape_html(&equation.latex);
+                        let message = escape_html(&e.to_string());
+                        if equation.display && paragraph {
+                            return format!(
+                                "<div class=\"math-error\" role=\"alert\" title=\"{message}\"><strong>{label}</strong><pre><code>{source}</code></pre></div>"
+                            );
+                        }
+                        format!("<span class=\"math-error\" role=\"alert\" title=\"{message}\"><code>{source}</code></span>")
+                    }
+                };
+                format!(
+                    "{}{rendered}{}",
+                    captures.get(1).map_or("", |m| m.as_str()),
+                    captures.get(3).map_or("", |m| m.as_str())
+                )
+            })
+            .into_owned()
+    }
+
+    /// Replace the tokens of `text` with the equations as Unicode text, for
+    /// output without MathML. Errors are labelled in `language`.
+    pub fn to_plain_text(&self, text: &str, language: &str) -> String {
+        token_regex()
+            .replace_all(text, |captures: &Captures| {
+                let Some(equation) = captures[2].parse::<usize>().ok().and_then(|i| self.equations.get(i)) else {
+                    return captures[0].to_string();
+                };
+                let rendered = latex_to_text(&equation.latex).unwrap_or_else(|_| {
+                    format!("[{}: {}]", i18n::t_for("manuals.math.invalid", language), equation.latex)
+                });
+                let rendered = escape_html(&rendered);
+                format!(
+                    "{}{rendered}{}",
+                    captures.get(1).map_or("", |m| m.as_str()),
+                    captures.get(3).map_or("", |m| m.as_str())
+                )
+            })
+            .into_owned()
+    }
+}
+
+/// Index of the next run of exactly `run` backticks from `from`
+fn find_backtick_run(chars: &[char], from: usize, run: usize) -> Option<usize> {
+    let mut i = from;
+    while i < chars.len() {
+        if chars[i] != '`' {
+            i += 1;
+            continue;
+        }
+        let length = chars[i..].iter().take_while(|&&c| c == '`').count();
+        if length == run {
+            return Some(i);
+        }
+        i += length;
+    }
+    None
+}
+
+/// Index of the `$$` closing display math opened before `from`
+fn find_display_end(chars: &[char], from: usize) -> Option<usize> {
+    let mut i = from;
+    while i + 1 < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '$' if chars[i + 1] == '$' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Index of the `$` closing inline math opened before `from`. Inline math
+/// doesn't run across paragraphs.
+fn find_inline_end(chars: &[char], from: usize) -> Option<usize> {
+    if chars.get(from).is_none_or(|c| c.is_whitespace()) {
+        return None;
+    }
+    let mut i = from;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '\n' if chars[i + 1..].iter().take_while(|c| **c != '\n').all(|c| c.is_whitespace()) => return None,
+            '$' if !chars[i - 1].is_whitespace() && !chars.get(i + 1).is_some_and(char::is_ascii_digit) => {
+                return Some(i);
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Render LaTeX math as a MathML `<math>` element, with the source as its
+/// annotation
+pub fn latex_to_mathml(latex: &str, display: bool) -> Result<String> {
+    let nodes = Parser::new(latex).parse()?;
+    let mut mathml = String::new();
+    for node in &nodes {
+        node.write_mathml(&mut mathml);
+    }
+    Ok(format!(
+        "<math xmlns=\"{MATHML_NAMESPACE}\" display=\"{}\"><semantics><mrow>{mathml}</mrow><annotation encoding=\"application/x-tex\">{}</annotation></semantics></math>",
+        if display { "block" } else { "inline" },
+        escape_html(latex)
+    ))
+}
+
+/// Render LaTeX math as Unicode text, such as `x² + √(a/b)`
+pub fn latex_to_text(latex: &str) -> Result<String> {
+    Ok(row_text(&Parser::new(latex).parse()?))
+}
+
+/// Part of an equation
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Identifier(String),
+    Number(String),
+    Operator(String),
+    /// An upright name such as `sin`
+    Function(String),
+    Text(String),
+    /// Width in em
+    Space(f32),
+    Row(Vec<Node>),
+    Fraction(Box<Node>, Box<Node>),
+    Root { radicand: Box<Node>, index: Option<Box<Node>> },
+    Scripts { base: Box<Node>, sub: Option<Box<Node>>, sup: Option<Box<Node>> },
+    Bold(Box<Node>),
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+fn invalid(message: String) -> TradocumentError {
+    TradocumentError::Validation(format!("Invalid LaTeX: {message}"))
+}
+
+impl Parser {
+    fn new(latex: &str) -> Self {
+        Self { chars: latex.chars().collect(), pos: 0 }
+    }
+
+    fn parse(mut self) -> Result<Vec<Node>> {
+        let nodes = self.row(None)?;
+        if self.pos < self.chars.len() {
+            return Err(invalid(format!("unexpected '{}'", self.chars[self.pos])));
+        }
+        Ok(nodes)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Nodes up to `end`, which is consumed, or the end of the input
+    fn row(&mut self, end: Option<char>) -> Result<Vec<Node>> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_spaces();
+            match self.chars.get(self.pos) {
+                None if end.is_some() => return Err(invalid("missing '}'".to_string())),
+                None => return Ok(nodes),
+                Some(&c) if Some(c) == end => {
+                    self.pos += 1;
+                    return Ok(nodes);
+                }
+                Some('}') => return Err(invalid("unexpected '}'".to_string())),
+                Some('^' | '_') => {
+                    let base = nodes.pop().unwrap_or(Node::Row(Vec::new()));
+                    nodes.push(self.scripts(base)?);
+                }
+                Some(_) => {
+                    let atom = self.atom()?;
+                    nodes.push(atom);
+                }
+            }
+        }
+    }
+
+    /// The sub- and superscript following `base`, in either order
+    fn scripts(&mut self, base: Node) -> Result<Node> {
+        let (mut sub, mut sup) = (None, None);
+        loop {
+            self.skip_spaces();
+            let (slot, name) = match self.chars.get(self.pos) {
+                Some('_') => (&mut sub, "subscript"),
+                Some('^') => (&mut sup, "superscript"),
+                _ => return Ok(Node::Scripts { base: Box::new(base), sub, sup }),
+            };
+            if slot.is_some() {
+                return Err(invalid(format!("double {name}")));
+            }
+            self.pos += 1;
+            *slot = Some(Box::new(self.argument(name)?));
+        }
+    }
+
+    /// A command argument: a group or a single atom
+    fn argument(&mut self, what: &str) -> Result<Node> {
+        self.skip_spaces();
+        match self.chars.get(self.pos) {
+            None | Some('}' | '^' | '_') => Err(invalid(format!("missing {what}"))),
+            Some(_) => self.atom(),
+        }
+    }
+
+    /// The text of a braced argument, taken as is
+    fn text_argument(&mut self, command: &str) -> Result<String> {
+        self.skip_spaces();
+        if self.chars.get(self.pos) != Some(&'{') {
+            return Err(invalid(format!("\\{command} needs a braced argument")));
+        }
+        let start = self.pos + 1;
+        let mut depth = 0;
+        for i in start..self.chars.len() {
+            match self.chars[i] {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    self.pos = i + 1;
+                    return Ok(self.chars[start..i].iter().collect());
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        Err(invalid("missing '}'".to_string()))
+    }
+
+    fn atom(&mut self) -> Result<Node> {
+        let c = self.chars[self.pos];
+        self.pos += 1;
+        match c {
+            '{' => {
+                let mut nodes = self.row(Some('}'))?;
+                if nodes.len() == 1 {
+                    return Ok(nodes.remove(0));
+                }
+                Ok(Node::Row(nodes))
+            }
+            '\\' => self.command(),
+            '0'..='9' | '.' => {
+                let mut number = c.to_string();
+                while let Some(&next) = self.chars.get(self.pos).filter(|c| c.is_ascii_digit() || **c == '.') {
+                    number.push(next);
+                    self.pos += 1;
+                }
+                Ok(Node::Number(number))
+            }
+            c if c.is_alphabetic() => Ok(Node::Identifier(c.to_string())),
+            '-' => Ok(Node::Operator("\u{2212}".to_string())),
+            '*' => Ok(Node::Operator("\u{2217}".to_string())),
+            '\'' => Ok(Node::Operator("\u{2032}".to_string())),
+            '~' => Ok(Node::Space(0.333)),
+            '&' | '#' | '%' | '$' => Err(invalid(format!("unsupported '{c}'"))),
+            c => Ok(Node::Operator(c.to_string())),
+        }
+    }
+
+    fn command(&mut self) -> Result<Node> {
+        let Some(&first) = self.chars.get(self.pos) else {
+            return Err(invalid("'\\' at the end".to_string()));
+        };
+        if !first.is_ascii_alphabetic() {
+            self.pos += 1;
+            return match first {
+                ',' => Ok(Node::Space(0.167)),
+                ':' | '>' => Ok(Node::Space(0.222)),
+                ';' => Ok(Node::Space(0.278)),
+                ' ' => Ok(Node::Space(0.333)),
+                '!' => Ok(Node::Space(0.0)),
+                '{' | '}' | '%' | '$' | '&' | '#' | '_' | '|' => Ok(Node::Operator(first.to_string())),
+                _ => Err(invalid(format!("unknown command \\{first}"))),
+            };
+        }
+
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(char::is_ascii_alphabetic) {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.argument("numerator")?;
+                let denominator = self.argument("denominator")?;
+                Ok(Node::Fraction(Box::new(numerator), Box::new(denominator)))
+            }
+            "sqrt" => {
+                self.skip_spaces();
+                let index = if self.chars.get(self.pos) == Some(&'[') {
+                    self.pos += 1;
+                    Some(Box::new(Node::Row(self.row(Some(']'))?)))
+                } else {
+                    None
+                };
+                Ok(Node::Root { radicand: Box::new(self.argument("radicand")?), index })
+            }
+            "text" | "textrm" | "mbox" => Ok(Node::Text(self.text_argument(&name)?)),
+            "mathrm" | "operatorname" => Ok(Node::Function(self.text_argument(&name)?)),
+            "mathbf" | "boldsymbol" => Ok(Node::Bold(Box::new(self.argument("argument")?))),
+            "left" | "right" | "big" | "Big" | "bigg" | "Bigg" => {
+                self.skip_spaces();
+                match self.chars.get(self.pos) {
+                    Some('.') => {
+                        self.pos += 1;
+                        Ok(Node::Row(Vec::new()))
+                    }
+                    Some(_) => match self.argument("delimiter")? {
+                        operator @ Node::Operator(_) => Ok(operator),
+                        _ => Err(invalid(format!("\\{name} needs a delimiter"))),
+                    },
+                    None => Err(invalid(format!("\\{name} needs a delimiter"))),
+                }
+            }
+            "quad" => Ok(Node::Space(1.0)),
+            "qquad" => Ok(Node::Space(2.0)),
+            _ => {
+                if let Some(letter) = greek_letter(&name) {
+                    return Ok(Node::Identifier(letter.to_string()));
+                }
+                if let Some(symbol) = symbol(&name) {
+                    return Ok(Node::Operator(symbol.to_string()));
+                }
+                if FUNCTIONS.contains(&name.as_str()) {
+                    return Ok(Node::Function(name));
+                }
+                Err(invalid(format!("unknown command \\{name}")))
+            }
+        }
+    }
+}
+
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh", "log", "ln",
+    "lg", "exp", "lim", "max", "min", "det", "dim", "deg", "arg", "sup", "inf",
+];
+
+fn greek_letter(name: &str) -> Option<char> {
+    let letter = match name {
+        "alpha" => 'α',
+        "beta" => 'β',
+        "gamma" => 'γ',
+        "delta" => 'δ',
+        "epsilon" => 'ϵ',
+        "varepsilon" => 'ε',
+        "zeta" => 'ζ',
+        "eta" => 'η',
+        "theta" => 'θ',
+        "vartheta" => 'ϑ',
+        "iota" => 'ι',
+        "kappa" => 'κ',
+        "lambda" => 'λ',
+        "mu" => 'μ',
+        "nu" => 'ν',
+        "xi" => 'ξ',
+        "pi" => 'π',
+        "rho" => 'ρ',
+        "sigma" => 'σ',
+        "tau" => 'τ',
+        "upsilon" => 'υ',
+        "phi" => 'ϕ',
+        "varphi" => 'φ',
+        "chi" => 'χ',
+        "psi" => 'ψ',
+        "omega" => 'ω',
+        "Gamma" => 'Γ',
+        "Delta" => 'Δ',
+        "Theta" => 'Θ',
+        "Lambda" => 'Λ',
+        "Xi" => 'Ξ',
+        "Pi" => 'Π',
+        "Sigma" => 'Σ',
+        "Upsilon" => 'Υ',
+        "Phi" => 'Φ',
+        "Psi" => 'Ψ',
+        "Omega" => 'Ω',
+        _ => return None,
+    };
+    Some(letter)
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        "cdot" => "·",
+        "times" => "×",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "propto" => "∝",
+        "infty" => "∞",
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        "oint" => "∮",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "to" | "rightarrow" => "→",
+        "leftarrow" => "←",
+        "Rightarrow" => "⇒",
+        "Leftrightarrow" => "⇔",
+        "cdots" => "⋯",
+        "ldots" | "dots" => "…",
+        "circ" => "∘",
+        "degree" => "°",
+        "in" => "∈",
+        "notin" => "∉",
+        "subset" => "⊂",
+        "cup" => "∪",
+        "cap" => "∩",
+        "forall" => "∀",
+        "exists" => "∃",
+        "angle" => "∠",
+        "perp" => "⊥",
+        "parallel" => "∥",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lbrace" => "{",
+        "rbrace" => "}",
+        "vert" | "mid" => "|",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+/// Operators set apart by spaces in text
+const SPACED_OPERATORS: &[&str] = &[
+    "=", "<", ">", "+", "\u{2212}", "±", "∓", "×", "÷", "·", "≤", "≥", "≠", "≈", "≡", "∼", "∝", "→", "←", "⇒", "⇔",
+    "∈", "∉",
+];
+
+impl Node {
+    fn write_mathml(&self, out: &mut String) {
+        match self {
+            Node::Identifier(name) => out.push_str(&format!("<mi>{}</mi>", escape_html(name))),
+            Node::Number(number) => out.push_str(&format!("<mn>{number}</mn>")),
+            Node::Operator(operator) => out.push_str(&format!("<mo>{}</mo>", escape_html(operator))),
+            Node::Function(name) => out.push_str(&format!("<mi mathvariant=\"normal\">{}</mi>", escape_html(name))),
+            Node::Text(text) => out.push_str(&format!("<mtext>{}</mtext>", escape_html(text))),
+            Node::Space(width) => out.push_str(&format!("<mspace width=\"{width}em\"/>")),
+            Node::Row(nodes) => {
+                out.push_str("<mrow>");
+                for node in nodes {
+                    node.write_mathml(out);
+                }
+                out.push_str("</mrow>");
+            }
+            Node::Fraction(numerator, denominator) => {
+                out.push_str("<mfrac>");
+                numerator.write_mathml(out);
+                denominator.write_mathml(out);
+                out.push_str("</mfrac>");
+            }
+            Node::Root { radicand, index: None } => {
+                out.push_str("<msqrt>");
+                radicand.write_mathml(out);
+                out.push_str("</msqrt>");
+            }
+            Node::Root { radicand, index: Some(index) } => {
+                out.push_str("<mroot>");
+                radicand.write_mathml(out);
+                index.write_mathml(out);
+                out.push_str("</mroot>");
+            }
+            Node::Scripts { base, sub, sup } => {
+                let element = match (sub, sup) {
+                    (Some(_), Some(_)) => "msubsup",
+                    (Some(_), None) => "msub",
+                    _ => "msup",
+                };
+                out.push_str(&format!("<{element}>"));
+                base.write_mathml(out);
+                for script in [sub, sup].into_iter().flatten() {
+                    script.write_mathml(out);
+                }
+                out.push_str(&format!("</{element}>"));
+            }
+            Node::Bold(node) => {
+                out.push_str("<mstyle mathvariant=\"bold\">");
+                node.write_mathml(out);
+                out.push_str("</mstyle>");
+            }
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Node::Identifier(text) | Node::Number(text) | Node::Operator(text) | Node::Function(text) | Node::Text(text) => {
+                text.clone()
+            }
+            Node::Space(width) if *width >= 0.25 => " ".to_string(),
+            Node::Space(_) => String::new(),
+            Node::Row(nodes) => row_text(nodes),
+            Node::Fraction(numerator, denominator) => {
+                format!("{}/{}", numerator.grouped_text(), denominator.grouped_text())
+            }
+            Node::Root { radicand, index } => {
+                let sign = match index.as_deref().map(Node::text).as_deref() {
+                    None => "√".to_string(),
+                    Some("3") => "∛".to_string(),
+                    Some("4") => "∜".to_string(),
+                    Some(index) => {
+                        format!("{}√", script_text(index, SUPERSCRIPTS).unwrap_or_else(|| format!("({index})")))
+                    }
+                };
+                format!("{sign}{}", radicand.grouped_text())
+            }
+            Node::Scripts { base, sub, sup } => {
+                let mut text = base.text();
+                if let Some(sub) = sub {
+                    text.push_str(&script_text(&sub.text(), SUBSCRIPTS).unwrap_or_else(|| format!("_{}", sub.grouped_text())));
+                }
+                if let Some(sup) = sup {
+                    text.push_str(&script_text(&sup.text(), SUPERSCRIPTS).unwrap_or_else(|| format!("^{}", sup.grouped_text())));
+                }
+                text
+            }
+            Node::Bold(node) => node.text(),
+        }
+    }
+
+    /// Text in parentheses unless it is a single symbol
+    fn grouped_text(&self) -> String {
+        let text = self.text();
+        let single = match self {
+            Node::Row(nodes) => nodes.len() == 1 && !matches!(nodes[0], Node::Fraction(..)),
+            Node::Fraction(..) => false,
+            _ => true,
+        };
+        if single || text.chars().count() == 1 {
+            text
+        } else {
+            format!("({text})")
+        }
+    }
+}
+
+fn row_text(nodes: &[Node]) -> String {
+    let mut text = String::new();
+    for (i, node) in nodes.iter().enumerate() {
+        match node {
+            // Leading signs stay with what they sign
+            Node::Operator(operator) if i > 0 && SPACED_OPERATORS.contains(&operator.as_str()) => {
+                text.push_str(&format!(" {operator} "));
+            }
+            Node::Function(name) => {
+                text.push_str(name);
+                if matches!(nodes.get(i + 1), Some(Node::Identifier(_) | Node::Number(_))) {
+                    text.push(' ');
+                }
+            }
+            _ => text.push_str(&node.text()),
+        }
+    }
+    text
+}
+
+const SUPERSCRIPTS: &[(char, char)] = &[
+    ('0', '⁰'), ('1', '¹'), ('2', '²'), ('3', '³'), ('4', '⁴'), ('5', '⁵'), ('6', '⁶'), ('7', '⁷'), ('8', '⁸'),
+    ('9', '⁹'), ('+', '⁺'), ('\u{2212}', '⁻'), ('=', '⁼'), ('(', '⁽'), (')', '⁾'), ('n', 'ⁿ'), ('i', 'ⁱ'),
+];
+
+const SUBSCRIPTS: &[(char, char)] = &[
+    ('0', '₀'), ('1', '₁'), ('2', '₂'), ('3', '₃'), ('4', '₄'), ('5', '₅'), ('6', '₆'), ('7', '₇'), ('8', '₈'),
+    ('9', '₉'), ('+', '₊'), ('\u{2212}', '₋'), ('=', '₌'), ('(', '₍'), (')', '₎'), ('a', 'ₐ'), ('e', 'ₑ'),
+    ('o', 'ₒ'), ('x', 'ₓ'), ('i', 'ᵢ'), ('n', 'ₙ'), ('m', 'ₘ'), ('k', 'ₖ'), ('t', 'ₜ'),
+];
+
+/// `text` in script letters, if there is one for each of its characters
+fn script_text(text: &str, table: &[(char, char)]) -> Option<String> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if text.is_empty() {
+        return None;
+    }
+    text.chars().map(|c| table.iter().find(|(plain, _)| *plain == c).map(|(_, script)| *script)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_engine::ExportEngine;
+
+    #[test]
+    fn test_inline_and_display_equations_render() {
+        let markdown = "The clapper swings at $\\omega = \\sqrt{g/l}$, not at $5 to $10.\n\n$$\nT = 2\\pi \\sqrt{\\frac{l}{g}}\n$$\n\nKeep `$x$` as code.\n";
+        let math = extract_math(markdown);
+        assert_eq!(
+            math.equations,
+            [
+                Equation { latex: "\\omega = \\sqrt{g/l}".to_string(), display: false },
+                Equation { latex: "T = 2\\pi \\sqrt{\\frac{l}{g}}".to_string(), display: true },
+            ]
+        );
+        assert!(math.text.contains("not at $5 to $10."));
+
+        let html = ExportEngine::new().render_markdown(markdown, &[], "en");
+        assert!(html.contains(&format!(
+            "swings at <math xmlns=\"{MATHML_NAMESPACE}\" display=\"inline\"><semantics><mrow><mi>ω</mi><mo>=</mo><msqrt><mrow><mi>g</mi><mo>/</mo><mi>l</mi></mrow></msqrt></mrow>"
+        )));
+        assert!(html.contains(&format!(
+            "<div class=\"math math-display\"><math xmlns=\"{MATHML_NAMESPACE}\" display=\"block\"><semantics><mrow><mi>T</mi><mo>=</mo><mn>2</mn><mi>π</mi><msqrt><mfrac><mi>l</mi><mi>g</mi></mfrac></msqrt></mrow>"
+        )));
+        assert!(html.contains("<annotation encoding=\"application/x-tex\">T = 2\\pi \\sqrt{\\frac{l}{g}}</annotation>"));
+        assert!(html.contains("<code>$x$</code>"));
+
+        assert_eq!(latex_to_text("T = 2\\pi \\sqrt{\\frac{l}{g}}").unwrap(), "T = 2π√(l/g)");
+        assert_eq!(latex_to_text("x_1^2 + y^{n+1}").unwrap(), "x₁² + yⁿ⁺¹");
+    }
+
+    #[test]
+    fn test_invalid_latex_renders_error_placeholder() {
+        for latex in ["\\frac{1}", "x^", "\\unknown{x}", "{a + b", "a}"] {
+            assert!(latex_to_mathml(latex, true).is_err(), "{latex} should be invalid");
+        }
+
+        let markdown = "Before.\n\n$$\\frac{1}{$$\n\nInline $x^$ too.\n";
+        let html = ExportEngine::new().render_markdown(markdown, &[], "en");
+        assert!(html.contains("<div class=\"math-error\" role=\"alert\" title=\"Validation error: Invalid LaTeX: missing '}'\"><strong>Invalid equation</strong><pre><code>\\frac{1}{</code></pre></div>"));
+        assert!(html.contains("Inline <span class=\"math-error\" role=\"alert\""));
+        assert!(html.contains("<code>x^</code></span> too."));
+        assert!(html.contains("<p>Before.</p>"));
+
+        let math = extract_math(markdown);
+        assert_eq!(math.to_plain_text("{{math-1}}", "de"), "[Ungültige Formel: x^]");
+    }
+}
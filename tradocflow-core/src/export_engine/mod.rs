@@ -1,11 +1,64 @@
-use crate::{Document, ScreenshotReference, Result};
+use crate::services::dnt::{dnt_spans_to_html, strip_dnt_markers};
+use crate::services::heading_ids::{strip_heading_ids, HeadingIdRegistry};
+use crate::services::variables::{missing_variables_to_html, ProjectVariables};
+use crate::services::TextDirection;
+use crate::database::image_repository::ImageRepository;
+use captions::{Caption, CaptionNumberer};
+use crate::{Document, ReviewStatus, ScreenshotReference, Result};
 use comrak::{markdown_to_html, ComrakOptions};
-use genpdf::{elements, fonts};
+use regex::Regex;
+use genpdf::{elements, Element};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use toml::Value;
 
+mod batch;
+pub mod bidi;
+pub mod callouts;
+pub mod captions;
+pub mod diagrams;
+mod docx_review;
+mod docx_review_import;
+mod epub;
+pub mod footnotes;
+pub mod front_matter;
+pub mod highlight;
+mod html_bundle;
+pub mod images;
+pub mod includes;
+mod manual_diff;
+pub mod markdown;
+pub mod math;
+pub mod page_setup;
+pub mod pagination;
+pub mod pdf_fonts;
+mod presets;
+mod review_report;
+pub mod watermark;
+
+pub use batch::{default_export_concurrency, ExportArtifact, DEFAULT_FILE_NAME_PATTERN};
+pub use bidi::{ListDigits, ListMarker};
+pub use callouts::CalloutKind;
+pub use captions::{CaptionListConfig, CaptionNumbering};
+pub use diagrams::{Diagram, DiagramRenderer, MermaidRenderer};
+pub use docx_review_import::{import_review_docx, DocxReviewImport, UnmatchedRevision};
+pub use footnotes::{extract_footnotes, Footnote, FootnotedText};
+pub use front_matter::FrontMatterConfig;
+pub use highlight::CodeTheme;
+pub use images::{ImagePolicy, PreferredImageFormat};
+pub use includes::{DocumentLibrary, IncludeSource, SnippetLibrary};
+pub use manual_diff::{diff_manuals, ManualDiff, SectionChange, SectionChangeKind};
+pub use markdown::split_front_matter;
+pub use math::{latex_to_mathml, latex_to_text};
+pub use page_setup::PageSetup;
+pub use pagination::{paginate, LayoutBlock, Pagination};
+pub use pdf_fonts::{FontConfig, FontFallback, PdfFonts, Script};
+pub use presets::{ExportPreset, ExportPresets};
+pub use watermark::Watermark;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportConfig {
     pub format: ExportFormat,
@@ -13,18 +66,101 @@ pub struct ExportConfig {
     pub template: Option<String>,
     pub css_file: Option<String>,
     pub languages: Vec<String>,
+    #[serde(default)]
+    pub page_setup: PageSetup,
+    /// Color theme for code blocks in HTML and PDF output
+    #[serde(default)]
+    pub code_theme: CodeTheme,
+    /// Downscale and embed local raster images; `None` links them as they are
+    #[serde(default)]
+    pub image_policy: Option<ImagePolicy>,
+    /// Text at the top of each PDF page, in which `{page}` is the page
+    /// number; `None` shows the number alone
+    #[serde(default)]
+    pub header_template: Option<String>,
+    /// Watermark stamped on every PDF page
+    #[serde(default)]
+    pub watermark: Option<Watermark>,
+    /// Review status of the exported document, which decides whether a
+    /// watermark for unapproved documents applies
+    #[serde(default)]
+    pub review_status: Option<ReviewStatus>,
+    /// Digits of numbered lists in PDF output
+    #[serde(default)]
+    pub list_digits: ListDigits,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Html,
+            include_screenshots: true,
+            template: None,
+            css_file: None,
+            languages: Vec::new(),
+            page_setup: PageSetup::default(),
+            code_theme: CodeTheme::default(),
+            image_policy: None,
+            header_template: None,
+            watermark: None,
+            review_status: None,
+            list_digits: ListDigits::default(),
+        }
+    }
+}
+
+impl ExportConfig {
+    /// The watermark to stamp on PDF pages, unless there is none or it
+    /// doesn't apply to the document's review status
+    pub fn active_watermark(&self) -> Option<&Watermark> {
+        self.watermark.as_ref().filter(|watermark| watermark.applies_to(self.review_status.as_ref()))
+    }
+}
+
+/// Files produced by [`ExportEngine::export_document`]
+#[derive(Debug, Clone, Default)]
+pub struct ExportOutput {
+    /// File contents by file name
+    pub files: HashMap<String, Vec<u8>>,
+    /// Bytes saved by the image policy against the original image files
+    pub image_bytes_saved: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExportFormat {
     Html,
     Pdf,
     Both,
 }
 
+#[derive(Clone)]
 pub struct ExportEngine {
     comrak_options: ComrakOptions<'static>,
     fragments: HashMap<String, String>,
+    screenshot_dir: PathBuf,
+    front_matter: Option<FrontMatterConfig>,
+    /// Number figures and tables and list them ahead of the first section
+    caption_lists: Option<CaptionListConfig>,
+    /// File name pattern of [`ExportEngine::export_all`]
+    file_name_pattern: String,
+    /// Most exports [`ExportEngine::export_all`] renders at once
+    export_concurrency: usize,
+    /// Presets [`ExportEngine::export_with_preset`] can export with
+    presets: ExportPresets,
+    /// Renderers of diagram code blocks, the first that supports a block
+    /// drawing it
+    diagram_renderers: Vec<Arc<dyn DiagramRenderer>>,
+    /// Where `{{include: ...}}` directives find their content, the first
+    /// source that has a path providing it
+    include_sources: Vec<Arc<dyn IncludeSource>>,
+    /// Values of the `{{var:name}}` placeholders
+    variables: ProjectVariables,
+    /// Primary and fallback fonts of PDF exports
+    font_config: FontConfig,
+    /// Shared images that screenshots with a content hash are read from
+    image_store: Option<ImageRepository>,
+    /// Image policy of manual exports, which take no [`ExportConfig`]
+    image_policy: Option<ImagePolicy>,
 }
 
 // Explicitly implement Send and Sync for ExportEngine
@@ -56,9 +192,72 @@ impl ExportEngine {
         Self {
             comrak_options: options,
             fragments,
+            screenshot_dir: PathBuf::from("screenshots"),
+            front_matter: None,
+            caption_lists: None,
+            file_name_pattern: DEFAULT_FILE_NAME_PATTERN.to_string(),
+            export_concurrency: default_export_concurrency(),
+            presets: ExportPresets::default(),
+            diagram_renderers: vec![Arc::new(MermaidRenderer)],
+            include_sources: Vec::new(),
+            variables: ProjectVariables::default(),
+            font_config: FontConfig::default(),
+            image_store: None,
+            image_policy: None,
         }
     }
 
+    /// Directory holding rendered screenshots as `<language>/<id>.svg`
+    pub fn with_screenshot_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.screenshot_dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Prepend a cover page and front matter to manual exports
+    pub fn with_front_matter(mut self, config: FrontMatterConfig) -> Self {
+        self.front_matter = Some(config);
+        self
+    }
+
+    /// Number the captioned figures and tables of manual exports and list
+    /// them after the front matter
+    pub fn with_caption_lists(mut self, config: CaptionListConfig) -> Self {
+        self.caption_lists = Some(config);
+        self
+    }
+
+    /// Look up included content in `source` after the sources added before
+    pub fn with_include_source(mut self, source: impl IncludeSource + 'static) -> Self {
+        self.include_sources.push(Arc::new(source));
+        self
+    }
+
+    /// Substitute `{{var:name}}` placeholders with the project's values
+    pub fn with_variables(mut self, variables: ProjectVariables) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    /// Fonts PDF exports are set in, with fallbacks for other scripts
+    pub fn with_font_config(mut self, config: FontConfig) -> Self {
+        self.font_config = config;
+        self
+    }
+
+    /// Read screenshots that have a content hash from `store`, so an image
+    /// embedded in many sections is stored and read once
+    pub fn with_image_store(mut self, store: ImageRepository) -> Self {
+        self.image_store = Some(store);
+        self
+    }
+
+    /// Downscale and embed the local raster images of HTML bundles, EPUB
+    /// books and batch exports as `policy` says
+    pub fn with_image_policy(mut self, policy: ImagePolicy) -> Self {
+        self.image_policy = Some(policy);
+        self
+    }
+
     fn load_fragments() -> Result<HashMap<String, String>> {
         let fragments_content = fs::read_to_string("fragments.toml")?;
         let fragments_value: Value = toml::from_str(&fragments_content)?;
@@ -75,8 +274,11 @@ impl ExportEngine {
         Ok(fragments_map)
     }
 
-    fn process_fragments(&self, content: &str) -> String {
-        let mut processed_content = content.to_string();
+    /// Resolve the include directives of `content` in `language`, then
+    /// substitute the project variables and the fragments of `fragments.toml`
+    fn process_fragments(&self, content: &str, language: &str) -> String {
+        let included = includes::resolve_includes(content, language, &self.include_sources);
+        let mut processed_content = self.variables.substitute(&included.text, language).text;
         for (key, value) in &self.fragments {
             let placeholder = format!("§{{{key}}}");
             processed_content = processed_content.replace(&placeholder, value);
@@ -88,34 +290,35 @@ impl ExportEngine {
         &self,
         document: &Document,
         config: &ExportConfig,
-    ) -> Result<HashMap<String, Vec<u8>>> {
-        let mut results = HashMap::new();
+    ) -> Result<ExportOutput> {
+        let mut output = ExportOutput::default();
 
         for language in &config.languages {
             if let Some(content) = document.content.get(language) {
-                let content_with_fragments = self.process_fragments(content);
+                let content_with_fragments = self.process_fragments(content, language);
                 let processed_content = self.process_screenshots(&content_with_fragments, &document.metadata.screenshots, language).await?;
+                let heading_ids = HeadingIdRegistry::from_metadata(&document.metadata, language).resolve(&processed_content);
 
                 match config.format {
                     ExportFormat::Html => {
-                        let html = self.generate_html(&processed_content, config)?;
-                        results.insert(format!("{language}.html"), html.into_bytes());
+                        let html = self.generate_html(&processed_content, &heading_ids, language, config, &mut output.image_bytes_saved)?;
+                        output.files.insert(format!("{language}.html"), html.into_bytes());
                     }
                     ExportFormat::Pdf => {
-                        let pdf = self.generate_pdf(&processed_content, config)?;
-                        results.insert(format!("{language}.pdf"), pdf);
+                        let pdf = self.generate_pdf(&processed_content, language, config, &mut output.image_bytes_saved)?;
+                        output.files.insert(format!("{language}.pdf"), pdf);
                     }
                     ExportFormat::Both => {
-                        let html = self.generate_html(&processed_content, config)?;
-                        let pdf = self.generate_pdf(&processed_content, config)?;
-                        results.insert(format!("{language}.html"), html.into_bytes());
-                        results.insert(format!("{language}.pdf"), pdf);
+                        let html = self.generate_html(&processed_content, &heading_ids, language, config, &mut output.image_bytes_saved)?;
+                        let pdf = self.generate_pdf(&processed_content, language, config, &mut output.image_bytes_saved)?;
+                        output.files.insert(format!("{language}.html"), html.into_bytes());
+                        output.files.insert(format!("{language}.pdf"), pdf);
                     }
                 }
             }
         }
 
-        Ok(results)
+        Ok(output)
     }
 
     fn extract_language_variable(&self, content: &str) -> Option<String> {
@@ -150,10 +353,19 @@ impl ExportEngine {
             if screenshot.language == document_language {
                 let placeholder = format!("{{screenshot:{}}}", screenshot.id);
                 if processed.contains(&placeholder) {
-                    let img_tag = format!(
-                        "![Screenshot {}](screenshots/{}/{}.svg)",
-                        screenshot.id, document_language, screenshot.id
-                    );
+                    let path = format!("screenshots/{}/{}.svg", document_language, screenshot.id);
+                    // The alt text of the document's language, else the caption
+                    let alt = match (screenshot.alt_text_for(&document_language), &screenshot.caption) {
+                        (Some(alt), _) => alt.to_string(),
+                        (None, Some(caption)) => caption.clone(),
+                        (None, None) => format!("Screenshot {}", screenshot.id),
+                    };
+                    let alt = alt.replace('[', "\\[").replace(']', "\\]");
+                    // A title makes the image a captioned figure
+                    let img_tag = match &screenshot.caption {
+                        Some(caption) => format!("![{alt}]({path} \"{}\")", caption.replace('"', "\\\"")),
+                        None => format!("![{alt}]({path})"),
+                    };
                     processed = processed.replace(&placeholder, &img_tag);
                 }
             }
@@ -175,8 +387,63 @@ impl ExportEngine {
         Ok(processed)
     }
 
-    fn generate_html(&self, content: &str, config: &ExportConfig) -> Result<String> {
-        let html_body = markdown_to_html(content, &self.comrak_options);
+    /// Render markdown to HTML with `heading_ids` as the heading anchors, in
+    /// document order. Explicit `{#id}` suffixes are removed first. If the
+    /// rendered headings don't line up with the ids, comrak's anchors stay.
+    /// "Do not translate" spans become `translate="no"` elements. Footnotes
+    /// become endnotes with links back to where they are referred to,
+    /// callouts become boxes titled in `language` and equations MathML.
+    /// Variables left undefined are flagged.
+    fn render_markdown(&self, markdown: &str, heading_ids: &[String], language: &str) -> String {
+        let footnoted = footnotes::extract_footnotes(&strip_heading_ids(markdown));
+        let callouts = callouts::extract_callouts(&footnoted.text);
+        let math = math::extract_math(&callouts.text);
+        let html = missing_variables_to_html(&dnt_spans_to_html(&markdown_to_html(&math.text, &self.comrak_options)));
+        let mut html = diagrams::render_diagrams(&html, &self.diagram_renderers, language);
+        let anchors: Vec<_> = heading_anchor_regex().find_iter(&html).collect();
+        if anchors.len() == heading_ids.len() {
+            let mut ids = heading_ids.iter();
+            html = heading_anchor_regex()
+                .replace_all(&html, |captures: &regex::Captures| {
+                    let id = ids.next().map(|id| html_bundle::escape_html(id)).unwrap_or_default();
+                    format!("{}<a href=\"#{id}\" aria-hidden=\"true\" class=\"anchor\" id=\"{id}\"></a>", &captures[1])
+                })
+                .into_owned();
+        } else {
+            log::debug!("{} rendered headings for {} heading ids; keeping derived anchors", anchors.len(), heading_ids.len());
+        }
+
+        let html = callouts.to_html(&math.to_html(&html, language), language);
+        footnoted.to_html(&html, |note| {
+            dnt_spans_to_html(&markdown_to_html(note, &self.comrak_options))
+        })
+    }
+
+    fn generate_html(
+        &self,
+        content: &str,
+        heading_ids: &[String],
+        language: &str,
+        config: &ExportConfig,
+        image_bytes_saved: &mut u64,
+    ) -> Result<String> {
+        let html_body = self.render_markdown(content, heading_ids, language);
+        // Manuals number their captions and list them ahead of the body
+        let html_body = match self.caption_lists.as_ref().filter(|_| html_body.contains(captions::CHAPTER_MARK)) {
+            Some(list_config) => {
+                let mut numberer = CaptionNumberer::new(list_config.numbering, language);
+                let numbered = numberer.number_manual(&html_body);
+                numberer.render_lists(list_config) + &numbered
+            }
+            None => html_body,
+        };
+        let html_body = highlight::highlight_html(&html_body, config.code_theme);
+        let mut html_body = isolate_code(html_body, language);
+        if let Some(policy) = &config.image_policy {
+            let (embedded, saved) = images::embed_images(&html_body, policy);
+            html_body = embedded;
+            *image_bytes_saved += saved;
+        }
 
         let css = if let Some(css_file) = &config.css_file {
             std::fs::read_to_string(css_file)?
@@ -184,9 +451,11 @@ impl ExportEngine {
             include_str!("default.css").to_string()
         };
 
+        let lang = html_bundle::escape_html(language);
+        let dir = TextDirection::for_language(language).as_css();
         let full_html = format!(
             r#"<!DOCTYPE html>
-<html lang="en">
+<html lang="{lang}" dir="{dir}">
 <head>
     <meta charset="UTF-8">
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
@@ -204,47 +473,158 @@ impl ExportEngine {
         Ok(full_html)
     }
 
-    fn generate_pdf(&self, content: &str, _config: &ExportConfig) -> Result<Vec<u8>> {
-        // Load fonts or return error
-        let font_family = fonts::from_files("fonts", "LiberationSans", None)
-            .map_err(|e| crate::TradocumentError::Pdf(format!("Font loading failed: {e}")))?;
+    fn generate_pdf(&self, content: &str, language: &str, config: &ExportConfig, image_bytes_saved: &mut u64) -> Result<Vec<u8>> {
+        self.render_pdf(content, language, config, &PdfFonts::load(&self.font_config)?, image_bytes_saved)
+    }
 
-        let mut doc = genpdf::Document::new(font_family);
+    /// Render a PDF with fonts that were already loaded, so batch exports
+    /// read the font files once. Text of right-to-left languages is right
+    /// aligned. Only the glyphs the text uses are embedded. Local raster
+    /// images are drawn as the image policy processes them; without a
+    /// policy their alt text stands in for them.
+    fn render_pdf(
+        &self,
+        content: &str,
+        language: &str,
+        config: &ExportConfig,
+        fonts: &PdfFonts,
+        image_bytes_saved: &mut u64,
+    ) -> Result<Vec<u8>> {
+        let labels = [config.header_template.as_deref(), config.active_watermark().map(|w| w.text.as_str())];
+        let doc_text = labels.into_iter().flatten().fold(content.to_string(), |text, label| text + "\n" + label);
+        let prepared = fonts.prepare(&doc_text)?;
+        let mut doc = genpdf::Document::new(prepared.primary);
+        let families = prepared.fallbacks.into_iter().map(|(index, family)| (index, doc.add_font_family(family))).collect();
+        let runs = pdf_fonts::FontRuns::new(fonts, families);
         doc.set_title("Tradocument Review");
+        config.page_setup.apply_to(&mut doc, config.header_template.clone(), config.active_watermark());
 
-        // Convert markdown to HTML then to plain text for PDF
-        let html_content = markdown_to_html(content, &self.comrak_options);
-        
-        // Basic HTML stripping for simple text content
-        let mut text_content = html_content;
-        let replacements = [
-            ("<h1>", "\n\n"), ("</h1>", "\n"),
-            ("<h2>", "\n"), ("</h2>", "\n"),
-            ("<h3>", "\n"), ("</h3>", "\n"),
-            ("<p>", ""), ("</p>", "\n"),
-            ("<strong>", ""), ("</strong>", ""),
-            ("<em>", ""), ("</em>", ""),
-            ("<code>", ""), ("</code>", ""),
-            ("&amp;", "&"), ("&lt;", "<"), ("&gt;", ">"), ("&quot;", "\""),
-        ];
-        
-        for (from, to) in &replacements {
-            text_content = text_content.replace(from, to);
-        }
-
-        // Add content as paragraphs
-        for paragraph in text_content.split("\n\n") {
-            let trimmed = paragraph.trim();
-            if !trimmed.is_empty() {
-                doc.push(elements::Paragraph::new(trimmed));
+        // Convert markdown to HTML then to plain text for PDF, keeping code
+        // blocks apart so they can be highlighted. Footnotes follow the text
+        // as numbered endnotes, since the flowing layout has no room kept
+        // for notes at the foot of a page.
+        let footnoted = footnotes::extract_footnotes(&strip_dnt_markers(&strip_heading_ids(content)));
+        let callouts = callouts::extract_callouts(&footnoted.with_endnotes());
+        let math = math::extract_math(&callouts.text);
+        let html_content = markdown_to_html(&math.text, &self.comrak_options);
+        let html_content = footnoted.to_plain_text(&math.to_plain_text(&html_content, language));
+        // Manuals number their captions and list them ahead of the body
+        let captions = self
+            .caption_lists
+            .as_ref()
+            .filter(|_| html_content.contains(captions::CHAPTER_MARK))
+            .map(|list_config| (list_config, CaptionNumberer::new(list_config.numbering, language)));
+        let (html_content, captions) = match captions {
+            Some((list_config, mut numberer)) => {
+                let numbered = captions::caption_paragraphs(&numberer.number_manual(&html_content));
+                (numbered, Some((list_config, numberer)))
             }
+            None => (html_content, None),
+        };
+        let mut body = PdfFlow::default();
+        for part in callouts.split_html(&html_content, language) {
+            match part {
+                callouts::CalloutPart::Text(html) => {
+                    self.push_html_paragraphs(&mut body, &runs, &html, language, config, config.page_setup.content_width_mm())
+                }
+                callouts::CalloutPart::Callout { kind, title, html } => {
+                    let (red, green, blue) = kind.color();
+                    let title_style = genpdf::style::Style::new().bold().with_color(genpdf::style::Color::Rgb(red, green, blue));
+                    let width = config.page_setup.content_width_mm() - 2.0 * CALLOUT_PADDING_MM;
+                    let mut callout = PdfFlow::default();
+                    if let Some((title, block)) = text_paragraph(&runs, &title, title_style, None, TextDirection::LeftToRight, width) {
+                        callout.push(title, block);
+                    }
+                    self.push_html_paragraphs(&mut callout, &runs, &html, language, config, width);
+                    body.image_bytes_saved += callout.image_bytes_saved;
+                    let block = body.blocks.len();
+                    body.anchors.extend(callout.anchors.drain(..).map(|(anchor, _)| (anchor, block)));
+                    let height = callout.height_mm() + 2.0 * CALLOUT_PADDING_MM;
+                    let framed = callout.into_layout().padded(genpdf::Margins::all(CALLOUT_PADDING_MM)).framed();
+                    body.push(framed, LayoutBlock::other(height));
+                }
+            }
+        }
+        *image_bytes_saved += body.image_bytes_saved;
+        if let Some((list_config, numberer)) = &captions {
+            push_caption_lists(&mut doc, &runs, numberer, list_config, &body, &config.page_setup, language);
         }
+        body.finish(&mut doc, &config.page_setup);
 
         let mut pdf_bytes = Vec::new();
         doc.render(&mut pdf_bytes).map_err(|e| crate::TradocumentError::Pdf(e.to_string()))?;
         Ok(pdf_bytes)
     }
 
+    /// Add rendered HTML to a PDF as paragraphs `width_mm` wide, with its
+    /// code blocks highlighted and its diagrams and images drawn
+    fn push_html_paragraphs(
+        &self,
+        doc: &mut PdfFlow,
+        runs: &pdf_fonts::FontRuns,
+        html: &str,
+        language: &str,
+        config: &ExportConfig,
+        width_mm: f64,
+    ) {
+        for part in highlight::split_code_blocks(html) {
+            let (label, code) = match part {
+                highlight::HtmlPart::Text(html) => {
+                    let mut last = 0;
+                    let images = config.image_policy.iter().flat_map(|policy| images::local_images(html, policy));
+                    for (tag, src, image) in images {
+                        match image.and_then(|image| Ok((pdf_image(&image, width_mm)?, image.bytes_saved()))) {
+                            Ok(((element, height), saved)) => {
+                                push_text_paragraphs(doc, runs, &html[last..tag.start], language, config.list_digits, width_mm);
+                                doc.push(element, LayoutBlock::other(height));
+                                doc.image_bytes_saved += saved;
+                                last = tag.end;
+                            }
+                            // The alt text stands in for the image
+                            Err(e) => log::warn!("Image {src} not drawn: {e}"),
+                        }
+                    }
+                    push_text_paragraphs(doc, runs, &html[last..], language, config.list_digits, width_mm);
+                    continue;
+                }
+                highlight::HtmlPart::Code { label, code } => (label, code),
+            };
+            let diagram = label.map(|label| {
+                let setup = &config.page_setup;
+                let (width, height) = (setup.content_width_mm(), setup.content_height_mm());
+                diagrams::pdf_diagram(&self.diagram_renderers, label, &code, width, height, language)
+            });
+            match diagram {
+                Some(diagrams::PdfDiagram::Drawing(drawing)) => {
+                    let height = drawing.height_mm();
+                    doc.push(drawing, LayoutBlock::other(height));
+                }
+                Some(diagrams::PdfDiagram::Code(Some(note))) => {
+                    let note_style = genpdf::style::Style::new().with_color(genpdf::style::Color::Rgb(192, 57, 43));
+                    if let Some((note, block)) = text_paragraph(runs, &note, note_style, None, TextDirection::LeftToRight, width_mm) {
+                        doc.push(note, block);
+                    }
+                    push_code_paragraphs(doc, runs, &code, label, config.code_theme);
+                }
+                _ => push_code_paragraphs(doc, runs, &code, label, config.code_theme),
+            }
+        }
+    }
+
+    /// Image bytes of a screenshot: its blob in the image store when it has
+    /// a content hash, else `<language>/<id>.svg` in the screenshot directory
+    async fn screenshot_bytes(&self, screenshot: &ScreenshotReference) -> Result<Vec<u8>> {
+        if let (Some(store), Some(hash)) = (&self.image_store, &screenshot.content_hash) {
+            match store.get_blob(hash).await {
+                Ok(Some(bytes)) => return Ok(bytes),
+                Ok(None) => log::warn!("Image {hash} of screenshot {} is not stored", screenshot.id),
+                Err(e) => log::warn!("Image {hash} of screenshot {} could not be read: {e}", screenshot.id),
+            }
+        }
+        let path = self.screenshot_dir.join(&screenshot.language).join(format!("{}.svg", screenshot.id));
+        fs::read(&path).map_err(|e| crate::TradocumentError::FileError(format!("{}: {e}", path.display())))
+    }
+
     pub async fn generate_screenshots(
         &self,
         document: &Document,
@@ -262,10 +642,587 @@ impl ExportEngine {
                     r#"{{"project_id": "{project_id}", "language": "{language}"}}"#
                 ),
                 generated_at: Some(chrono::Utc::now()),
+                caption: None,
+                content_hash: None,
+                alt_text: HashMap::new(),
             };
             screenshots.push(screenshot_ref);
         }
 
         Ok(screenshots)
     }
-}
\ No newline at end of file
+}
+
+/// The anchor comrak puts at the start of each heading for `header_ids`
+fn heading_anchor_regex() -> &'static Regex {
+    static HEADING_ANCHOR: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    HEADING_ANCHOR.get_or_init(|| Regex::new(r#"(<h[1-6][^>]*>)<a [^>]*class="anchor"[^>]*></a>"#).expect("valid heading anchor regex"))
+}
+
+/// Mark code in the HTML of a right-to-left language as left to right, so
+/// it keeps its order and is isolated from the surrounding text
+pub(super) fn isolate_code(html: String, language: &str) -> String {
+    if !TextDirection::for_language(language).is_rtl() {
+        return html;
+    }
+    code_tag_regex().replace_all(&html, r#"<$1 dir="ltr"$2"#).into_owned()
+}
+
+fn code_tag_regex() -> &'static Regex {
+    static CODE_TAG: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    CODE_TAG.get_or_init(|| Regex::new(r"<(pre|code)([\s>])").expect("valid code tag regex"))
+}
+
+/// Elements of a PDF, or of a box in it, each with its estimated layout so
+/// the page breaks can be planned with [`paginate`]
+#[derive(Default)]
+struct PdfFlow {
+    elements: Vec<Box<dyn Element>>,
+    blocks: Vec<LayoutBlock>,
+    /// Bytes the image policy saved on the images drawn
+    image_bytes_saved: u64,
+    /// Anchors of numbered captions, with the block each is in
+    anchors: Vec<(String, usize)>,
+}
+
+impl PdfFlow {
+    fn push(&mut self, element: impl genpdf::IntoBoxedElement, block: LayoutBlock) {
+        self.elements.push(element.into_boxed_element());
+        self.blocks.push(block);
+    }
+
+    fn height_mm(&self) -> f64 {
+        self.blocks.iter().map(|block| block.height_mm).sum()
+    }
+
+    /// The elements one below the other, as one element
+    fn into_layout(self) -> elements::LinearLayout {
+        let mut layout = elements::LinearLayout::vertical();
+        for element in self.elements {
+            layout.push(element);
+        }
+        layout
+    }
+
+    fn pagination(&self, setup: &PageSetup) -> Pagination {
+        paginate(&self.blocks, &setup.below_header(PDF_FONT_SIZE_PT))
+    }
+
+    /// Blocks that [`paginate`] moves to the next page whole, in order
+    fn page_breaks(&self, setup: &PageSetup) -> Vec<usize> {
+        let pagination = self.pagination(setup);
+        pagination.breaks.iter().filter(|point| point.line == 0).map(|point| point.block).collect()
+    }
+
+    /// Add the elements to `doc`, starting a new page before each block
+    /// that would leave a heading, an orphan or a split short list behind.
+    /// genpdf breaks paragraphs and long lists where a page is full.
+    fn finish(self, doc: &mut genpdf::Document, setup: &PageSetup) {
+        let mut breaks = self.page_breaks(setup).into_iter().peekable();
+        for (index, element) in self.elements.into_iter().enumerate() {
+            if breaks.next_if_eq(&index).is_some() {
+                doc.push(elements::PageBreak::new());
+            }
+            doc.push(element);
+        }
+    }
+}
+
+/// Share of the width of a list of figures or tables taken by the page
+/// numbers, against [`CAPTION_ENTRY_WEIGHT`] for the captions
+const CAPTION_PAGE_WEIGHT: usize = 1;
+const CAPTION_ENTRY_WEIGHT: usize = 9;
+
+/// Add the lists of figures and tables of a manual ahead of its body, each
+/// entry with the page its caption is estimated to be on
+fn push_caption_lists(
+    doc: &mut genpdf::Document,
+    runs: &pdf_fonts::FontRuns,
+    numberer: &CaptionNumberer,
+    list_config: &CaptionListConfig,
+    body: &PdfFlow,
+    setup: &PageSetup,
+    language: &str,
+) {
+    let width_mm = setup.content_width_mm();
+    // Page numbers have a column of their own, so the lists take the same
+    // pages whichever numbers they show
+    let list_pages = caption_list_flow(runs, numberer, list_config, language, width_mm, |_| None)
+        .pagination(setup)
+        .page_count;
+    if list_pages == 0 {
+        return;
+    }
+    let body_pages = body.pagination(setup);
+    let page_of = |caption: &Caption| {
+        let (_, block) = body.anchors.iter().find(|(anchor, _)| *anchor == caption.anchor)?;
+        body_pages.page_of(*block).map(|page| list_pages + page + 1)
+    };
+    caption_list_flow(runs, numberer, list_config, language, width_mm, page_of).finish(doc, setup);
+    doc.push(elements::PageBreak::new());
+}
+
+/// The lists of figures and tables of a manual, each entry with the page
+/// `page_of` its caption, if known
+fn caption_list_flow(
+    runs: &pdf_fonts::FontRuns,
+    numberer: &CaptionNumberer,
+    list_config: &CaptionListConfig,
+    language: &str,
+    width_mm: f64,
+    page_of: impl Fn(&Caption) -> Option<usize>,
+) -> PdfFlow {
+    let direction = TextDirection::for_language(language);
+    let style = genpdf::style::Style::new();
+    let (entry_align, page_align) = if direction.is_rtl() {
+        (genpdf::Alignment::Right, genpdf::Alignment::Left)
+    } else {
+        (genpdf::Alignment::Left, genpdf::Alignment::Right)
+    };
+    let entry_share = CAPTION_ENTRY_WEIGHT as f64 / (CAPTION_ENTRY_WEIGHT + CAPTION_PAGE_WEIGHT) as f64;
+    let mut flow = PdfFlow::default();
+    for list in numberer.lists(list_config) {
+        if let Some((title, block)) = text_paragraph(runs, &list.title, style.bold(), None, direction, width_mm) {
+            flow.push(title, LayoutBlock::heading(block.height_mm));
+        }
+        for caption in list.captions {
+            let entry = strip_text_markup(&format!("{} {}: {}", list.label, caption.number, caption.text), direction);
+            let page = page_of(caption).map(|page| page.to_string()).unwrap_or_default();
+            let lines = page_setup::estimate_line_count(&entry, width_mm * entry_share * 0.9, PDF_FONT_SIZE_PT);
+            let entry = runs.paragraph(&entry, style).aligned(entry_align);
+            let page = runs.paragraph(&page, style).aligned(page_align);
+            let mut row = elements::TableLayout::new(vec![CAPTION_ENTRY_WEIGHT, CAPTION_PAGE_WEIGHT]);
+            let pushed = if direction.is_rtl() {
+                row.row().element(page).element(entry).push()
+            } else {
+                row.row().element(entry).element(page).push()
+            };
+            pushed.expect("a cell for each column");
+            flow.push(row, LayoutBlock::paragraph(lines, page_setup::line_height_mm(PDF_FONT_SIZE_PT)));
+        }
+    }
+    flow
+}
+
+/// Resolution images are drawn at in PDF output, unless they are too wide
+/// for the text column at it
+const PDF_IMAGE_DPI: f64 = 96.0;
+
+/// An image processed by the image policy as a PDF element at most
+/// `width_mm` wide, with its height
+fn pdf_image(image: &images::ProcessedImage, width_mm: f64) -> Result<(elements::Image, f64)> {
+    let element = elements::Image::from_reader(std::io::Cursor::new(image.opaque_data()?))
+        .map_err(|e| crate::TradocumentError::Pdf(e.to_string()))?;
+    let dpi = PDF_IMAGE_DPI.max(f64::from(image.width) * 25.4 / width_mm);
+    let height_mm = f64::from(image.height) * 25.4 / dpi;
+    Ok((element.with_alignment(genpdf::Alignment::Center).with_dpi(dpi), height_mm))
+}
+
+/// Padding inside the frame of a callout box in PDF output
+const CALLOUT_PADDING_MM: f64 = 2.0;
+/// Font size of PDF body text, genpdf's default
+const PDF_FONT_SIZE_PT: f64 = 12.0;
+
+fn list_regex() -> &'static Regex {
+    static LIST: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    LIST.get_or_init(|| Regex::new(r#"<(/?)(ul|ol|li)(?:\s+start="(\d+)")?>"#).expect("valid list regex"))
+}
+
+/// Rendered HTML split at its list items, each item with its marker
+fn split_list_items(html: &str, digits: ListDigits) -> Vec<(Option<String>, String)> {
+    // Next number of each open list, `None` for bullet lists
+    let mut lists: Vec<Option<u32>> = Vec::new();
+    let mut blocks = Vec::new();
+    let mut marker = None;
+    let mut text = String::new();
+    let mut last = 0;
+    for tag in list_regex().captures_iter(html) {
+        let whole = tag.get(0).expect("whole match");
+        text.push_str(&html[last..whole.start()]);
+        last = whole.end();
+        // Each list and item boundary ends the text before it
+        if !text.trim().is_empty() || marker.is_some() {
+            blocks.push((marker.take(), std::mem::take(&mut text)));
+        }
+        text.clear();
+        match (&tag[1], &tag[2]) {
+            ("", "ul") => lists.push(None),
+            ("", "ol") => lists.push(Some(tag.get(3).and_then(|start| start.as_str().parse().ok()).unwrap_or(1))),
+            ("", _) => {
+                marker = Some(match lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        ListMarker::Number(*number - 1).text(digits)
+                    }
+                    _ => ListMarker::Bullet.text(digits),
+                });
+            }
+            (_, "li") => {}
+            _ => {
+                lists.pop();
+            }
+        }
+    }
+    text.push_str(&html[last..]);
+    if !text.trim().is_empty() || marker.is_some() {
+        blocks.push((marker, text));
+    }
+    blocks
+}
+
+fn heading_regex() -> &'static Regex {
+    static HEADING: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    HEADING.get_or_init(|| Regex::new(r"(?s)<h[1-6][^>]*>.*?</h[1-6]>").expect("valid heading regex"))
+}
+
+/// Strip the markup of rendered HTML and add its text as PDF paragraphs
+/// `width_mm` wide, each run of list items as one list. Right-to-left text
+/// is laid out line by line in visual order, with list markers on the
+/// right.
+fn push_text_paragraphs(
+    doc: &mut PdfFlow,
+    runs: &pdf_fonts::FontRuns,
+    html: &str,
+    language: &str,
+    digits: ListDigits,
+    width_mm: f64,
+) {
+    let direction = TextDirection::for_language(language);
+    let style = genpdf::style::Style::new();
+    let mut list = PdfFlow::default();
+    for (marker, html) in split_list_items(html, digits) {
+        if let Some(marker) = marker {
+            let text = strip_text_markup(&html, direction).split_whitespace().collect::<Vec<_>>().join(" ");
+            if let Some((item, block)) = text_paragraph(runs, &text, style, Some(&marker), direction, width_mm) {
+                list.push(item, block);
+            }
+            continue;
+        }
+        push_list(doc, std::mem::take(&mut list));
+
+        // Headings are kept with what follows them
+        let mut paragraphs = Vec::new();
+        let mut last = 0;
+        for heading in heading_regex().find_iter(&html) {
+            paragraphs.push((false, &html[last..heading.start()]));
+            paragraphs.push((true, heading.as_str()));
+            last = heading.end();
+        }
+        paragraphs.push((false, &html[last..]));
+        for (is_heading, html) in paragraphs {
+            let text = strip_text_markup(&heading_anchor_regex().replace_all(html, "$1"), direction);
+            for paragraph in text.split("\n\n") {
+                let paragraph = paragraph.trim();
+                let (anchor, paragraph) = match captions::split_caption_token(paragraph) {
+                    Some((anchor, caption)) => (Some(anchor), caption),
+                    None => (None, paragraph),
+                };
+                if let Some((paragraph, block)) = text_paragraph(runs, paragraph, style, None, direction, width_mm) {
+                    let block = if is_heading { LayoutBlock::heading(block.height_mm) } else { block };
+                    if let Some(anchor) = anchor {
+                        doc.anchors.push((anchor.to_string(), doc.blocks.len()));
+                    }
+                    doc.push(paragraph, block);
+                }
+            }
+        }
+    }
+    push_list(doc, list);
+}
+
+/// Add the items of a list as one block, which is kept on one page when
+/// the list is short
+fn push_list(doc: &mut PdfFlow, items: PdfFlow) {
+    let count = items.blocks.len();
+    if count > 0 {
+        let height = items.height_mm();
+        doc.push(items.into_layout(), LayoutBlock::list(count, height / count as f64));
+    }
+}
+
+/// A paragraph of text as a PDF element `width_mm` wide, with the block it
+/// is estimated to take up; `None` when there is nothing to show
+fn text_paragraph(
+    runs: &pdf_fonts::FontRuns,
+    text: &str,
+    style: genpdf::style::Style,
+    marker: Option<&str>,
+    direction: TextDirection,
+    width_mm: f64,
+) -> Option<(Box<dyn Element>, LayoutBlock)> {
+    if text.is_empty() && marker.is_none() {
+        return None;
+    }
+    // Measuring against a narrower column errs towards more lines, which
+    // leaves room for the glyph width estimate being short
+    let width_mm = width_mm * 0.9;
+    let line_height = page_setup::line_height_mm(PDF_FONT_SIZE_PT);
+    if !direction.is_rtl() {
+        let text = marker.map_or_else(|| text.to_string(), |marker| format!("{marker} {text}"));
+        let lines = page_setup::estimate_line_count(&text, width_mm, PDF_FONT_SIZE_PT);
+        let paragraph = runs.paragraph(&text, style).aligned(genpdf::Alignment::Left);
+        return Some((Box::new(paragraph), LayoutBlock::paragraph(lines, line_height)));
+    }
+    let glyph_width = page_setup::average_glyph_width_mm(PDF_FONT_SIZE_PT);
+    let measure = |text: &str| text.chars().count() as f64 * glyph_width;
+    let lines = bidi::layout_paragraph(text, marker, direction, width_mm, measure);
+    let block = LayoutBlock::paragraph(lines.len(), line_height);
+    let mut layout = elements::LinearLayout::vertical();
+    for line in lines {
+        let paragraph = runs.paragraph(&line.text, style).aligned(genpdf::Alignment::Right);
+        layout.push(paragraph.padded(genpdf::Margins::trbl(0.0, line.indent, 0.0, 0.0)));
+    }
+    Some((Box::new(layout), block))
+}
+
+/// Text of rendered HTML, with inline code isolated as left to right in
+/// right-to-left text
+fn strip_text_markup(html: &str, direction: TextDirection) -> String {
+    // Basic HTML stripping for simple text content
+    let mut text_content = if direction.is_rtl() {
+        html.replace("<code>", &bidi::LEFT_TO_RIGHT_ISOLATE.to_string())
+            .replace("</code>", &bidi::POP_DIRECTIONAL_ISOLATE.to_string())
+    } else {
+        html.to_string()
+    };
+    let replacements = [
+        ("<h1>", "\n\n"), ("</h1>", "\n"),
+        ("<h2>", "\n"), ("</h2>", "\n"),
+        ("<h3>", "\n"), ("</h3>", "\n"),
+        ("<p>", ""), ("</p>", "\n"),
+        ("<strong>", ""), ("</strong>", ""),
+        ("<em>", ""), ("</em>", ""),
+        ("<code>", ""), ("</code>", ""),
+        ("&amp;", "&"), ("&lt;", "<"), ("&gt;", ">"), ("&quot;", "\""),
+    ];
+
+    // Images aren't drawn, so their alt text stands in for them
+    text_content = img_tag_regex().replace_all(&text_content, "$alt").into_owned();
+    for (from, to) in &replacements {
+        text_content = text_content.replace(from, to);
+    }
+    text_content
+}
+
+fn img_tag_regex() -> &'static Regex {
+    static IMG_TAG: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    IMG_TAG.get_or_init(|| Regex::new(r#"<img [^>]*?(?:alt="(?P<alt>[^"]*)"[^>]*)?/?>"#).expect("valid img tag regex"))
+}
+
+/// Add a code block as one paragraph per line, colored by the theme when
+/// the highlighter knows the language
+fn push_code_paragraphs(
+    doc: &mut PdfFlow,
+    runs: &pdf_fonts::FontRuns,
+    code: &str,
+    label: Option<&str>,
+    theme: CodeTheme,
+) {
+    let mut block = elements::LinearLayout::vertical();
+    let mut lines = 0;
+    match highlight::highlight_code(code, label, theme) {
+        Some(highlighted) => {
+            for regions in highlighted {
+                let mut paragraph = elements::Paragraph::default();
+                for (style, text) in regions {
+                    let color = genpdf::style::Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b);
+                    runs.push(&mut paragraph, text.trim_end_matches('\n'), genpdf::style::Style::new().with_color(color));
+                }
+                block.push(paragraph);
+                lines += 1;
+            }
+        }
+        None => {
+            for line in code.lines() {
+                block.push(runs.paragraph(line, genpdf::style::Style::new()));
+                lines += 1;
+            }
+        }
+    }
+    // Lines of code are never wrapped, so a block breaks like a paragraph
+    doc.push(block, LayoutBlock::paragraph(lines, page_setup::line_height_mm(PDF_FONT_SIZE_PT)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn html_config(language: &str) -> ExportConfig {
+        ExportConfig {
+            format: ExportFormat::Html,
+            include_screenshots: false,
+            template: None,
+            css_file: None,
+            languages: vec![language.to_string()],
+            page_setup: PageSetup::default(),
+            code_theme: CodeTheme::default(),
+            image_policy: None,
+            header_template: None,
+            watermark: None,
+            review_status: None,
+            list_digits: ListDigits::default(),
+        }
+    }
+
+    #[test]
+    fn test_rtl_language_sets_html_direction() {
+        let engine = ExportEngine::new();
+        let content = "# Title\n\nRun `make install` first.\n";
+        let mut saved = 0;
+
+        let arabic = engine.generate_html(content, &[], "ar", &html_config("ar"), &mut saved).unwrap();
+        assert!(arabic.contains(r#"<html lang="ar" dir="rtl">"#));
+        assert!(arabic.contains(r#"<code dir="ltr">make install</code>"#));
+
+        let english = engine.generate_html(content, &[], "en", &html_config("en"), &mut saved).unwrap();
+        assert!(english.contains(r#"<html lang="en" dir="ltr">"#));
+        assert!(english.contains("<code>make install</code>"));
+    }
+
+    #[test]
+    fn test_list_items_numbered_in_configured_digits() {
+        let html = "<p>Steps</p>\n<ol start=\"9\">\n<li>Open</li>\n<li><code>make</code></li>\n</ol>\n<ul>\n<li>Note</li>\n</ul>\n";
+        let items = split_list_items(html, ListDigits::ArabicIndic);
+        let items: Vec<(Option<&str>, &str)> = items.iter().map(|(marker, html)| (marker.as_deref(), html.as_str())).collect();
+        assert_eq!(items, [
+            (None, "<p>Steps</p>\n"),
+            (Some("٩."), "Open"),
+            (Some("١٠."), "<code>make</code>"),
+            (Some("•"), "Note"),
+        ]);
+        assert_eq!(strip_text_markup("<code>make</code>", TextDirection::RightToLeft), "\u{2066}make\u{2069}");
+    }
+
+    #[test]
+    fn test_pdf_pages_break_between_blocks() {
+        use pagination::BlockKind::{Heading, List, Paragraph};
+        let engine = ExportEngine::new();
+        let fonts = PdfFonts::load(&FontConfig::default()).unwrap();
+        let runs = pdf_fonts::FontRuns::new(&fonts, HashMap::new());
+        let config = ExportConfig { format: ExportFormat::Pdf, ..ExportConfig::default() };
+        let markdown: String = (1..=20)
+            .map(|i| format!("## Step {i}\n\nCheck the bell frame.\n\n- Clapper\n- Rope guide\n\n"))
+            .collect();
+        let html = markdown_to_html(&markdown, &engine.comrak_options);
+        let mut flow = PdfFlow::default();
+        engine.push_html_paragraphs(&mut flow, &runs, &html, "en", &config, config.page_setup.content_width_mm());
+
+        let kinds: Vec<_> = flow.blocks.iter().take(4).map(|block| block.kind).collect();
+        assert_eq!(kinds, [Heading, Paragraph, List, Heading]);
+        assert_eq!(flow.blocks[2].lines, 2);
+
+        // Headings move to the next page with the text after them
+        let breaks = flow.page_breaks(&config.page_setup);
+        assert!(!breaks.is_empty());
+        assert!(breaks.iter().all(|&block| flow.blocks[block - 1].kind != Heading));
+    }
+
+    #[test]
+    fn test_pdf_lists_captions_with_their_pages() {
+        let engine = ExportEngine::new().with_caption_lists(CaptionListConfig::default());
+        let fonts = PdfFonts::load(&FontConfig::default()).unwrap();
+        let runs = pdf_fonts::FontRuns::new(&fonts, HashMap::new());
+        let config = ExportConfig { format: ExportFormat::Pdf, ..ExportConfig::default() };
+        let filler = "Check the bell frame.\n\n".repeat(80);
+        let markdown = format!(
+            "{mark}\n\n![wiring](wiring.png \"Controller wiring\")\n\n{filler}{mark}\n\n| Pin |\n|-----|\n| 1 |\n\nTable: Pins\n\n![bracket](bracket.png \"Wall bracket\")\n",
+            mark = captions::CHAPTER_MARK
+        );
+
+        let mut numberer = CaptionNumberer::new(CaptionNumbering::Continuous, "en");
+        let html = captions::caption_paragraphs(&numberer.number_manual(&markdown_to_html(&markdown, &engine.comrak_options)));
+        let mut body = PdfFlow::default();
+        engine.push_html_paragraphs(&mut body, &runs, &html, "en", &config, config.page_setup.content_width_mm());
+        let anchors: Vec<&str> = body.anchors.iter().map(|(anchor, _)| anchor.as_str()).collect();
+        assert_eq!(anchors, ["figure-1", "table-1", "figure-2"]);
+
+        // Captions after the filler are pages further on
+        let pages = body.pagination(&config.page_setup);
+        let page_of = |anchor: &str| {
+            let (_, block) = body.anchors.iter().find(|(a, _)| a == anchor).unwrap();
+            pages.page_of(*block).unwrap()
+        };
+        assert_eq!(page_of("figure-1"), 0);
+        assert!(page_of("figure-2") > 1);
+        let width = config.page_setup.content_width_mm();
+        let lists = caption_list_flow(&runs, &numberer, &CaptionListConfig::default(), "en", width, |_| Some(1));
+        let kinds: Vec<_> = lists.blocks.iter().map(|block| block.kind).collect();
+        use pagination::BlockKind::{Heading, Paragraph};
+        assert_eq!(kinds, [Heading, Paragraph, Paragraph, Heading, Paragraph]);
+
+        let mut saved = 0;
+        let pdf = engine.render_pdf(&markdown, "en", &config, &fonts, &mut saved).unwrap();
+        // The lists take a page of their own
+        assert!(lopdf::Document::load_mem(&pdf).unwrap().get_pages().len() > 1);
+    }
+
+    #[test]
+    fn test_pdf_draws_images_by_image_policy() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("overlay.png");
+        image::RgbaImage::from_fn(1200, 400, |x, _| image::Rgba([200, 30, 30, (x % 256) as u8])).save(&path).unwrap();
+        let content = format!("Fit the cover.\n\n![Cover]({})\n", path.display());
+        let engine = ExportEngine::new();
+        let fonts = PdfFonts::load(&FontConfig::default()).unwrap();
+        let config = ExportConfig { format: ExportFormat::Pdf, ..ExportConfig::default() };
+        let image_count = |pdf: &[u8]| {
+            let document = lopdf::Document::load_mem(pdf).unwrap();
+            let is_image = |stream: &lopdf::Stream| stream.dict.get(b"Subtype").and_then(lopdf::Object::as_name).ok() == Some(&b"Image"[..]);
+            document.objects.values().filter(|object| matches!(object, lopdf::Object::Stream(stream) if is_image(stream))).count()
+        };
+
+        // Without a policy the alt text stands in for the image
+        let mut saved = 0;
+        let plain = engine.render_pdf(&content, "en", &config, &fonts, &mut saved).unwrap();
+        assert_eq!(image_count(&plain), 0);
+        assert_eq!(saved, 0);
+
+        let policy = ImagePolicy { max_dimension: 300, ..ImagePolicy::default() };
+        let config = ExportConfig { image_policy: Some(policy), ..config };
+        let drawn = engine.render_pdf(&content, "en", &config, &fonts, &mut saved).unwrap();
+        assert_eq!(image_count(&drawn), 1);
+        assert!(saved > 0);
+    }
+
+    #[test]
+    fn test_dnt_markers_are_not_exported() {
+        let engine = ExportEngine::new();
+        let content = "Open {{dnt}}TradocFlow *Pro*{{/dnt}} and call {{dnt}}init_v2(){{/dnt}}.\n";
+        let mut saved = 0;
+
+        let html = engine.generate_html(content, &[], "en", &html_config("en"), &mut saved).unwrap();
+        assert!(html.contains(r#"Open <span class="dnt" translate="no">TradocFlow <em>Pro</em></span> and call"#));
+        assert!(html.contains(r#"<span class="dnt" translate="no">init_v2()</span>."#));
+        assert!(!html.contains("{{"));
+    }
+
+    #[tokio::test]
+    async fn test_screenshot_alt_text_follows_export_language() {
+        let engine = ExportEngine::new();
+        let screenshot = |language: &str| ScreenshotReference {
+            id: "panel".to_string(),
+            language: language.to_string(),
+            screen_config: "{}".to_string(),
+            generated_at: None,
+            caption: None,
+            content_hash: None,
+            alt_text: HashMap::from([
+                ("en".to_string(), "Control panel with the Start button".to_string()),
+                ("de".to_string(), "Bedienfeld mit der Start-Taste".to_string()),
+            ]),
+        };
+        let screenshots = vec![screenshot("en"), screenshot("de"), screenshot("fr")];
+        let mut saved = 0;
+
+        let german = engine.process_screenshots("{screenshot:panel}\n", &screenshots, "de").await.unwrap();
+        let html = engine.generate_html(&german, &[], "de", &html_config("de"), &mut saved).unwrap();
+        assert!(html.contains(r#"alt="Bedienfeld mit der Start-Taste""#));
+        // PDF output shows the alt text in place of the image
+        let pdf_text = strip_text_markup(&engine.render_markdown(&german, &[], "de"), TextDirection::LeftToRight);
+        assert_eq!(pdf_text.trim(), "Bedienfeld mit der Start-Taste");
+
+        // Without alt text in the language, the image is named by its id
+        let french = engine.process_screenshots("{screenshot:panel}\n", &screenshots, "fr").await.unwrap();
+        assert_eq!(french, "![Screenshot panel](screenshots/fr/panel.svg)\n");
+    }
+}
@@ -0,0 +1,263 @@
+use super::watermark::{Watermark, WatermarkDecorator};
+use serde::{Deserialize, Serialize};
+
+/// Paper size of exported pages
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PageSize {
+    A4,
+    A3,
+    Letter,
+    Legal,
+    /// Portrait width and height in millimetres
+    Custom { width_mm: f64, height_mm: f64 },
+}
+
+impl PageSize {
+    /// Portrait width and height in millimetres
+    pub fn dimensions_mm(&self) -> (f64, f64) {
+        match *self {
+            PageSize::A4 => (210.0, 297.0),
+            PageSize::A3 => (297.0, 420.0),
+            PageSize::Letter => (215.9, 279.4),
+            PageSize::Legal => (215.9, 355.6),
+            PageSize::Custom { width_mm, height_mm } => (width_mm, height_mm),
+        }
+    }
+}
+
+/// Page margins in millimetres
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Margins {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Margins {
+    pub fn uniform(mm: f64) -> Self {
+        Self { top: mm, right: mm, bottom: mm, left: mm }
+    }
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Self::uniform(25.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// Page geometry used when paginating PDF exports
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PageSetup {
+    pub size: PageSize,
+    pub margins: Margins,
+    pub orientation: Orientation,
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        Self {
+            size: PageSize::A4,
+            margins: Margins::default(),
+            orientation: Orientation::Portrait,
+        }
+    }
+}
+
+/// Average glyph width relative to the font size, for proportional body fonts
+const AVERAGE_GLYPH_WIDTH: f64 = 0.5;
+/// Line height relative to the font size
+const LINE_SPACING: f64 = 1.2;
+const MM_PER_POINT: f64 = 25.4 / 72.0;
+
+/// Lines of the header [`PageSetup::apply_to`] puts on each page: the
+/// header text and a blank line
+const HEADER_LINES: f64 = 2.0;
+
+/// Estimated width of an average glyph of body text at `font_size_pt`
+pub fn average_glyph_width_mm(font_size_pt: f64) -> f64 {
+    font_size_pt * MM_PER_POINT * AVERAGE_GLYPH_WIDTH
+}
+
+/// Height of one line of body text at `font_size_pt`
+pub fn line_height_mm(font_size_pt: f64) -> f64 {
+    font_size_pt * MM_PER_POINT * LINE_SPACING
+}
+
+/// Estimated number of lines `text` word-wraps to in a column `width_mm`
+/// wide
+pub fn estimate_line_count(text: &str, width_mm: f64, font_size_pt: f64) -> usize {
+    wrapped_line_count(text, ((width_mm / average_glyph_width_mm(font_size_pt)) as usize).max(1))
+}
+
+impl PageSetup {
+    pub fn new(size: PageSize, margins: Margins, orientation: Orientation) -> Self {
+        Self { size, margins, orientation }
+    }
+
+    /// Page width and height in millimetres, after applying the orientation
+    pub fn page_dimensions_mm(&self) -> (f64, f64) {
+        let (width, height) = self.size.dimensions_mm();
+        let (short, long) = (width.min(height), width.max(height));
+        match self.orientation {
+            Orientation::Portrait => (short, long),
+            Orientation::Landscape => (long, short),
+        }
+    }
+
+    /// Width of the text column, which headers and footers share
+    pub fn content_width_mm(&self) -> f64 {
+        let (width, _) = self.page_dimensions_mm();
+        (width - self.margins.left - self.margins.right).max(0.0)
+    }
+
+    /// Height available for headers, body text, and footers
+    pub fn content_height_mm(&self) -> f64 {
+        let (_, height) = self.page_dimensions_mm();
+        (height - self.margins.top - self.margins.bottom).max(0.0)
+    }
+
+    /// Approximate number of characters of body text that fit on one line
+    pub fn chars_per_line(&self, font_size_pt: f64) -> usize {
+        ((self.content_width_mm() / average_glyph_width_mm(font_size_pt)) as usize).max(1)
+    }
+
+    /// Number of body text lines that fit on one page
+    pub fn lines_per_page(&self, font_size_pt: f64) -> usize {
+        ((self.content_height_mm() / line_height_mm(font_size_pt)) as usize).max(1)
+    }
+
+    /// The page below the header [`PageSetup::apply_to`] puts on each page,
+    /// whose text column the body text of a PDF fills
+    pub fn below_header(&self, font_size_pt: f64) -> PageSetup {
+        let top = self.margins.top + HEADER_LINES * line_height_mm(font_size_pt);
+        PageSetup { margins: Margins { top, ..self.margins }, ..*self }
+    }
+
+    /// Estimate how many pages `paragraphs` reflow to at this geometry.
+    ///
+    /// Paragraphs are word-wrapped to [`PageSetup::chars_per_line`] and
+    /// separated by a blank line.
+    pub fn estimate_page_count(&self, paragraphs: &[&str], font_size_pt: f64) -> usize {
+        let width = self.chars_per_line(font_size_pt);
+        let lines: usize = paragraphs
+            .iter()
+            .map(|paragraph| wrapped_line_count(paragraph, width) + 1)
+            .sum();
+        lines.div_ceil(self.lines_per_page(font_size_pt)).max(1)
+    }
+
+    /// Set the paper size and margins of `doc`, put `header_template`, or
+    /// the page number alone, at the top of each page and stamp `watermark`
+    /// behind each page's text
+    pub(super) fn apply_to(&self, doc: &mut genpdf::Document, header_template: Option<String>, watermark: Option<&Watermark>) {
+        let (width, height) = self.page_dimensions_mm();
+        doc.set_paper_size(genpdf::Size::new(width, height));
+
+        // The decorator applies margins before rendering the header, so the
+        // header sits inside the same text column as the body
+        let mut decorator = genpdf::SimplePageDecorator::new();
+        decorator.set_margins(genpdf::Margins::trbl(
+            self.margins.top,
+            self.margins.right,
+            self.margins.bottom,
+            self.margins.left,
+        ));
+        decorator.set_header(move |page| {
+            let header = match &header_template {
+                Some(template) => template.replace("{page}", &page.to_string()),
+                None => page.to_string(),
+            };
+            let mut layout = genpdf::elements::LinearLayout::vertical();
+            layout.push(genpdf::elements::Paragraph::new(header).aligned(genpdf::Alignment::Right));
+            layout.push(genpdf::elements::Break::new(1));
+            layout
+        });
+        match watermark {
+            Some(watermark) => doc.set_page_decorator(WatermarkDecorator::new(decorator, watermark, self)),
+            None => doc.set_page_decorator(decorator),
+        }
+    }
+}
+
+fn wrapped_line_count(paragraph: &str, width: usize) -> usize {
+    let mut lines = 1;
+    let mut current = 0;
+    for word in paragraph.split_whitespace() {
+        let len = word.chars().count();
+        if current > 0 && current + 1 + len > width {
+            lines += 1;
+            current = len;
+        } else if current > 0 {
+            current += 1 + len;
+        } else {
+            current = len;
+        }
+        // Words longer than a line are broken across lines
+        while current > width {
+            lines += 1;
+            current -= width;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_document() -> Vec<String> {
+        (0..200)
+            .map(|i| format!("Paragraph {i} explains how to wire the bell tower controller to the mains supply safely."))
+            .collect()
+    }
+
+    #[test]
+    fn test_a4_and_letter_reflow_differently() {
+        let paragraphs = long_document();
+        let paragraphs: Vec<&str> = paragraphs.iter().map(String::as_str).collect();
+        let a4 = PageSetup::default();
+        let letter = PageSetup { size: PageSize::Letter, ..a4 };
+
+        assert!(letter.content_width_mm() > a4.content_width_mm());
+        assert!(letter.content_height_mm() < a4.content_height_mm());
+        assert_ne!(
+            a4.estimate_page_count(&paragraphs, 11.0),
+            letter.estimate_page_count(&paragraphs, 11.0)
+        );
+    }
+
+    #[test]
+    fn test_landscape_widens_text_column() {
+        let portrait = PageSetup::default();
+        let landscape = PageSetup { orientation: Orientation::Landscape, ..portrait };
+
+        assert_eq!(landscape.page_dimensions_mm(), (297.0, 210.0));
+        assert!(landscape.content_width_mm() > portrait.content_width_mm());
+        assert!(landscape.chars_per_line(11.0) > portrait.chars_per_line(11.0));
+    }
+
+    #[test]
+    fn test_custom_size_and_margins() {
+        let setup = PageSetup::new(
+            PageSize::Custom { width_mm: 100.0, height_mm: 150.0 },
+            Margins { top: 10.0, right: 5.0, bottom: 20.0, left: 15.0 },
+            Orientation::Portrait,
+        );
+        assert_eq!(setup.content_width_mm(), 80.0);
+        assert_eq!(setup.content_height_mm(), 120.0);
+    }
+
+    #[test]
+    fn test_wrapping_counts_lines() {
+        assert_eq!(wrapped_line_count("", 10), 1);
+        assert_eq!(wrapped_line_count("aaaa bbbb cccc", 9), 2);
+        assert_eq!(wrapped_line_count("abcdefghijklmnopqrstuvwxy", 10), 3);
+    }
+}
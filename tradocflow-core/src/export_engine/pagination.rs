@@ -0,0 +1,253 @@
+use super::PageSetup;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Fewest lines of a paragraph or items of a list left on either side of a
+/// page break, so no page starts with a widow or ends with an orphan
+const MIN_LINES_AT_BREAK: usize = 2;
+/// Lists with at most this many items are always kept on one page
+const SHORT_LIST_ITEMS: usize = 6;
+/// Slack for floating point error when adding up heights
+const EPSILON_MM: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockKind {
+    /// Kept on the same page as the start of the block after it
+    Heading,
+    /// May break between lines
+    Paragraph,
+    /// May break between items, unless the list is short
+    List,
+    /// Images, tables, code, and anything else that is never split
+    Other,
+}
+
+/// A laid-out block with its measured height. Paragraph lines and list
+/// items are assumed to be of equal height.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LayoutBlock {
+    pub kind: BlockKind,
+    pub height_mm: f64,
+    /// Number of lines of a paragraph or items of a list; 1 for other blocks
+    pub lines: usize,
+}
+
+impl LayoutBlock {
+    pub fn heading(height_mm: f64) -> Self {
+        Self { kind: BlockKind::Heading, height_mm, lines: 1 }
+    }
+
+    pub fn paragraph(lines: usize, line_height_mm: f64) -> Self {
+        Self { kind: BlockKind::Paragraph, height_mm: lines as f64 * line_height_mm, lines: lines.max(1) }
+    }
+
+    pub fn list(items: usize, item_height_mm: f64) -> Self {
+        Self { kind: BlockKind::List, height_mm: items as f64 * item_height_mm, lines: items.max(1) }
+    }
+
+    pub fn other(height_mm: f64) -> Self {
+        Self { kind: BlockKind::Other, height_mm, lines: 1 }
+    }
+
+    fn line_height_mm(&self) -> f64 {
+        self.height_mm / self.lines as f64
+    }
+
+    fn is_splittable(&self) -> bool {
+        let min_lines = 2 * MIN_LINES_AT_BREAK;
+        match self.kind {
+            BlockKind::Paragraph => self.lines >= min_lines,
+            BlockKind::List => self.lines > SHORT_LIST_ITEMS && self.lines >= min_lines,
+            BlockKind::Heading | BlockKind::Other => false,
+        }
+    }
+
+    /// Height that has to fit on a page for the block to start there
+    fn min_start_height_mm(&self) -> f64 {
+        if self.is_splittable() {
+            self.line_height_mm() * MIN_LINES_AT_BREAK as f64
+        } else {
+            self.height_mm
+        }
+    }
+}
+
+/// The lines of one block placed on one page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placement {
+    pub block: usize,
+    /// 0-based page index
+    pub page: usize,
+    pub lines: Range<usize>,
+}
+
+/// Where a new page starts: before `line` of `block`, or before the whole
+/// block when `line` is 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BreakPoint {
+    pub block: usize,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pagination {
+    /// Placements in document order
+    pub placements: Vec<Placement>,
+    pub breaks: Vec<BreakPoint>,
+    pub page_count: usize,
+}
+
+impl Pagination {
+    /// Page on which a block starts
+    pub fn page_of(&self, block: usize) -> Option<usize> {
+        self.placements.iter().find(|placement| placement.block == block).map(|placement| placement.page)
+    }
+
+    /// Placements on one page, in document order
+    pub fn page(&self, page: usize) -> impl Iterator<Item = &Placement> {
+        self.placements.iter().filter(move |placement| placement.page == page)
+    }
+}
+
+/// Assign laid-out blocks to pages of the body text column of `setup`.
+///
+/// Headings move to the next page together with the start of the block that
+/// follows them, paragraphs and long lists break with at least two lines or
+/// items on each page, and short lists are kept whole. Blocks taller than a
+/// page start on a fresh page and overflow it.
+pub fn paginate(blocks: &[LayoutBlock], setup: &PageSetup) -> Pagination {
+    let page_height = setup.content_height_mm();
+    let mut pagination = Pagination::default();
+    if blocks.is_empty() {
+        return pagination;
+    }
+
+    let mut page = 0;
+    let mut used = 0.0;
+    for (index, block) in blocks.iter().enumerate() {
+        let line_height = block.line_height_mm();
+        let mut start = 0;
+        loop {
+            let available = page_height - used;
+            let remaining = line_height * (block.lines - start) as f64;
+
+            if start == 0 && used > 0.0 && keep_height_mm(blocks, index) > available + EPSILON_MM {
+                next_page(&mut pagination, &mut page, &mut used, index, 0);
+                continue;
+            }
+            if remaining <= available + EPSILON_MM || !block.is_splittable() {
+                pagination.placements.push(Placement { block: index, page, lines: start..block.lines });
+                used += remaining;
+                break;
+            }
+
+            // Break inside the block, leaving enough lines on both pages
+            let fitting = ((available + EPSILON_MM) / line_height) as usize;
+            let left = block.lines - start;
+            let mut take = fitting.min(left.saturating_sub(MIN_LINES_AT_BREAK));
+            if take < MIN_LINES_AT_BREAK {
+                if used > 0.0 {
+                    next_page(&mut pagination, &mut page, &mut used, index, start);
+                    continue;
+                }
+                // Not even the minimum fits on an empty page
+                take = fitting.clamp(1, left);
+            }
+            pagination.placements.push(Placement { block: index, page, lines: start..start + take });
+            start += take;
+            used += line_height * take as f64;
+            if start == block.lines {
+                break;
+            }
+            next_page(&mut pagination, &mut page, &mut used, index, start);
+        }
+    }
+
+    pagination.page_count = page + 1;
+    pagination
+}
+
+fn next_page(pagination: &mut Pagination, page: &mut usize, used: &mut f64, block: usize, line: usize) {
+    *page += 1;
+    *used = 0.0;
+    pagination.breaks.push(BreakPoint { block, line });
+}
+
+/// Height that must fit for `blocks[index]` to start on the current page: a
+/// heading needs room for itself and the start of what follows it
+fn keep_height_mm(blocks: &[LayoutBlock], index: usize) -> f64 {
+    let block = &blocks[index];
+    match block.kind {
+        BlockKind::Heading => block.height_mm + blocks.get(index + 1).map_or(0.0, |_| keep_height_mm(blocks, index + 1)),
+        _ => block.min_start_height_mm(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::page_setup::{Margins, Orientation, PageSize};
+    use super::*;
+
+    /// Pages with a 100 mm tall text column
+    fn setup() -> PageSetup {
+        PageSetup::new(PageSize::Custom { width_mm: 100.0, height_mm: 100.0 }, Margins::uniform(0.0), Orientation::Portrait)
+    }
+
+    fn lines_on_page(pagination: &Pagination, block: usize, page: usize) -> usize {
+        pagination.page(page).filter(|placement| placement.block == block).map(|placement| placement.lines.len()).sum()
+    }
+
+    #[test]
+    fn test_breaks_avoid_widows_and_orphans() {
+        // 95 mm used, room for one more 5 mm line: moving the whole paragraph
+        // beats leaving a single orphan line behind
+        let blocks = [LayoutBlock::other(95.0), LayoutBlock::paragraph(6, 5.0)];
+        let pagination = paginate(&blocks, &setup());
+        assert_eq!(pagination.page_of(1), Some(1));
+        assert_eq!(pagination.breaks, vec![BreakPoint { block: 1, line: 0 }]);
+
+        // Room for 9 of 10 lines: only 8 stay so the next page doesn't start
+        // with a widow
+        let blocks = [LayoutBlock::other(55.0), LayoutBlock::paragraph(10, 5.0)];
+        let pagination = paginate(&blocks, &setup());
+        assert_eq!(lines_on_page(&pagination, 1, 0), 8);
+        assert_eq!(lines_on_page(&pagination, 1, 1), 2);
+        assert_eq!(pagination.breaks, vec![BreakPoint { block: 1, line: 8 }]);
+        assert_eq!(pagination.page_count, 2);
+    }
+
+    #[test]
+    fn test_heading_is_never_last_on_a_page() {
+        let blocks = [
+            LayoutBlock::paragraph(17, 5.0),
+            LayoutBlock::heading(8.0),
+            LayoutBlock::heading(6.0),
+            LayoutBlock::paragraph(4, 5.0),
+            LayoutBlock::paragraph(30, 3.0),
+            LayoutBlock::heading(8.0),
+        ];
+        let pagination = paginate(&blocks, &setup());
+
+        for page in 0..pagination.page_count {
+            let last = pagination.page(page).last().unwrap();
+            if last.block + 1 < blocks.len() {
+                assert_ne!(blocks[last.block].kind, BlockKind::Heading, "page {page} ends with a heading");
+            }
+        }
+        // Both headings moved with the paragraph after them
+        assert_eq!(pagination.page_of(1), Some(1));
+        assert_eq!(pagination.page_of(2), Some(1));
+        assert_eq!(pagination.page_of(3), Some(1));
+    }
+
+    #[test]
+    fn test_short_lists_are_kept_whole() {
+        let blocks = [LayoutBlock::other(80.0), LayoutBlock::list(5, 6.0), LayoutBlock::list(12, 6.0)];
+        let pagination = paginate(&blocks, &setup());
+
+        assert_eq!(lines_on_page(&pagination, 1, 1), 5);
+        // The long list fills the rest of page two and breaks between items
+        assert_eq!(lines_on_page(&pagination, 2, 1), 10);
+        assert_eq!(lines_on_page(&pagination, 2, 2), 2);
+    }
+}
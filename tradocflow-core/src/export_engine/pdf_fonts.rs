@@ -0,0 +1,664 @@
+//! Fonts of PDF exports
+//!
+//! The body font lacks glyphs for scripts such as Chinese or Arabic, so
+//! each script can have fallback fonts. Text is split into runs drawn in
+//! the first configured font that has glyphs for them; characters no font
+//! covers are drawn as [`TOFU`] and reported. Fonts are embedded as
+//! subsets: the glyph outlines the document doesn't use are dropped,
+//! which leaves a CJK font a fraction of its size.
+//!
+//! Fonts are TrueType files named like genpdf expects them,
+//! `<Family>-Regular.ttf` with optional `-Bold`, `-Italic` and
+//! `-BoldItalic` variants.
+
+use crate::{Result, TradocumentError};
+use genpdf::elements::Paragraph;
+use genpdf::fonts::{Font, FontData, FontFamily};
+use genpdf::style::Style;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Drawn in place of a character no configured font has a glyph for
+pub const TOFU: char = '□';
+/// What the checksum of a whole TrueType font adds up to, by way of the
+/// `checkSumAdjustment` field of its `head` table
+const FONT_CHECKSUM: u32 = 0xB1B0_AFBA;
+
+/// Writing system of a character, which decides the fallback fonts tried
+/// for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Arabic,
+    Hebrew,
+    /// Chinese, Japanese and Korean
+    Cjk,
+    Other,
+}
+
+impl Script {
+    pub fn of(c: char) -> Self {
+        match c {
+            '\u{0000}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' => Script::Latin,
+            '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Script::Greek,
+            '\u{0400}'..='\u{052F}' => Script::Cyrillic,
+            '\u{0590}'..='\u{05FF}' | '\u{FB1D}'..='\u{FB4F}' => Script::Hebrew,
+            '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' | '\u{08A0}'..='\u{08FF}' | '\u{FB50}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFF}' => Script::Arabic,
+            '\u{1100}'..='\u{11FF}'
+            | '\u{2E80}'..='\u{2FDF}'
+            | '\u{3000}'..='\u{30FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{31F0}'..='\u{31FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+            | '\u{20000}'..='\u{3134F}' => Script::Cjk,
+            _ => Script::Other,
+        }
+    }
+}
+
+/// A font family tried for characters of `script` the primary font lacks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FontFallback {
+    pub script: Script,
+    pub family: String,
+}
+
+/// Fonts PDF exports are set in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FontConfig {
+    /// Directory holding the font files
+    pub directory: PathBuf,
+    /// Family of body text
+    pub primary: String,
+    /// Fallback families, tried in order for characters of their script,
+    /// then for any character the others lack. Missing files are skipped.
+    #[serde(default)]
+    pub fallbacks: Vec<FontFallback>,
+    /// Embed only the glyphs the document uses
+    #[serde(default = "default_subset")]
+    pub subset: bool,
+}
+
+fn default_subset() -> bool {
+    true
+}
+
+impl Default for FontConfig {
+    fn default() -> Self {
+        let fallback = |script, family: &str| FontFallback { script, family: family.to_string() };
+        Self {
+            directory: PathBuf::from("fonts"),
+            primary: "LiberationSans".to_string(),
+            fallbacks: vec![
+                fallback(Script::Cjk, "NotoSansCJKsc"),
+                fallback(Script::Arabic, "NotoSansArabic"),
+                fallback(Script::Hebrew, "NotoSansHebrew"),
+            ],
+            subset: default_subset(),
+        }
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn malformed(what: &str) -> TradocumentError {
+    TradocumentError::Pdf(format!("Malformed font: {what}"))
+}
+
+/// Offset and length of each table of a TrueType font, by tag
+fn table_directory(data: &[u8]) -> Result<Vec<([u8; 4], usize, usize)>> {
+    let count = read_u16(data, 4).ok_or_else(|| malformed("no table directory"))? as usize;
+    (0..count)
+        .map(|i| {
+            let record = 12 + i * 16;
+            let tag = data.get(record..record + 4).ok_or_else(|| malformed("truncated table directory"))?;
+            let offset = read_u32(data, record + 8).ok_or_else(|| malformed("truncated table directory"))? as usize;
+            let length = read_u32(data, record + 12).ok_or_else(|| malformed("truncated table directory"))? as usize;
+            if offset.checked_add(length).is_none_or(|end| end > data.len()) {
+                return Err(malformed("table beyond the end of the file"));
+            }
+            Ok(([tag[0], tag[1], tag[2], tag[3]], offset, length))
+        })
+        .collect()
+}
+
+fn table<'a>(data: &'a [u8], tag: &[u8; 4]) -> Result<Option<&'a [u8]>> {
+    Ok(table_directory(data)?.into_iter().find(|(found, ..)| found == tag).map(|(_, offset, length)| &data[offset..offset + length]))
+}
+
+/// The characters a font has glyphs for, read from its `cmap` table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlyphCoverage {
+    glyphs: HashMap<u32, u16>,
+}
+
+impl GlyphCoverage {
+    pub fn from_font(data: &[u8]) -> Result<Self> {
+        let cmap = table(data, b"cmap")?.ok_or_else(|| malformed("no cmap table"))?;
+        let count = read_u16(cmap, 2).ok_or_else(|| malformed("truncated cmap"))? as usize;
+        // Prefer full Unicode tables over those of the Basic Multilingual Plane
+        let mut best: Option<(u8, usize)> = None;
+        for i in 0..count {
+            let record = 4 + i * 8;
+            let (Some(platform), Some(encoding), Some(offset)) =
+                (read_u16(cmap, record), read_u16(cmap, record + 2), read_u32(cmap, record + 4))
+            else {
+                return Err(malformed("truncated cmap"));
+            };
+            let format = read_u16(cmap, offset as usize).unwrap_or(0);
+            let rank = match (platform, encoding, format) {
+                (3, 10, 12) | (0, _, 12) => 2,
+                (3, 1, 4) | (0, _, 4) => 1,
+                _ => continue,
+            };
+            if best.is_none_or(|(best_rank, _)| rank > best_rank) {
+                best = Some((rank, offset as usize));
+            }
+        }
+        let (_, offset) = best.ok_or_else(|| malformed("no Unicode cmap subtable"))?;
+        let subtable = cmap.get(offset..).ok_or_else(|| malformed("cmap subtable beyond the table"))?;
+        let glyphs = match read_u16(subtable, 0) {
+            Some(12) => Self::read_format_12(subtable),
+            _ => Self::read_format_4(subtable),
+        }
+        .ok_or_else(|| malformed("truncated cmap subtable"))?;
+        Ok(Self { glyphs })
+    }
+
+    fn read_format_4(subtable: &[u8]) -> Option<HashMap<u32, u16>> {
+        let segments = read_u16(subtable, 6)? as usize / 2;
+        let (ends, starts) = (14, 16 + segments * 2);
+        let (deltas, range_offsets) = (starts + segments * 2, starts + segments * 4);
+        let mut glyphs = HashMap::new();
+        for segment in 0..segments {
+            let end = read_u16(subtable, ends + segment * 2)?;
+            let start = read_u16(subtable, starts + segment * 2)?;
+            let delta = read_u16(subtable, deltas + segment * 2)?;
+            let range_offset_at = range_offsets + segment * 2;
+            let range_offset = read_u16(subtable, range_offset_at)? as usize;
+            for code in start..=end {
+                if code == 0xFFFF {
+                    break;
+                }
+                let glyph = if range_offset == 0 {
+                    code.wrapping_add(delta)
+                } else {
+                    match read_u16(subtable, range_offset_at + range_offset + 2 * (code - start) as usize)? {
+                        0 => 0,
+                        glyph => glyph.wrapping_add(delta),
+                    }
+                };
+                if glyph != 0 {
+                    glyphs.insert(u32::from(code), glyph);
+                }
+            }
+        }
+        Some(glyphs)
+    }
+
+    fn read_format_12(subtable: &[u8]) -> Option<HashMap<u32, u16>> {
+        let groups = read_u32(subtable, 12)? as usize;
+        let mut glyphs = HashMap::new();
+        for group in 0..groups {
+            let record = 16 + group * 12;
+            let (start, end, first_glyph) = (read_u32(subtable, record)?, read_u32(subtable, record + 4)?, read_u32(subtable, record + 8)?);
+            for code in start..=end.min(0x10FFFF) {
+                let glyph = first_glyph + (code - start);
+                if glyph != 0 && glyph <= u32::from(u16::MAX) {
+                    glyphs.insert(code, glyph as u16);
+                }
+            }
+        }
+        Some(glyphs)
+    }
+
+    pub fn covers(&self, c: char) -> bool {
+        self.glyphs.contains_key(&u32::from(c))
+    }
+
+    fn glyph(&self, c: char) -> Option<u16> {
+        self.glyphs.get(&u32::from(c)).copied()
+    }
+}
+
+/// `font` without the outlines of glyphs other than those of `chars`, the
+/// components they are built from and the missing glyph. Glyph ids,
+/// metrics and the character map stay as they are. Fonts without TrueType
+/// outlines are returned whole.
+pub fn subset_font(font: &[u8], coverage: &GlyphCoverage, chars: &BTreeSet<char>) -> Result<Vec<u8>> {
+    let (Some(head), Some(loca), Some(glyf)) = (table(font, b"head")?, table(font, b"loca")?, table(font, b"glyf")?) else {
+        return Ok(font.to_vec());
+    };
+    let long_offsets = read_u16(head, 50).ok_or_else(|| malformed("truncated head"))? == 1;
+    // loca has an offset per glyph and one for the end of the last
+    let glyph_count = (loca.len() / if long_offsets { 4 } else { 2 })
+        .checked_sub(1)
+        .ok_or_else(|| malformed("empty loca table"))?;
+    let glyph_range = |glyph: usize| -> Option<(usize, usize)> {
+        let (start, end) = if long_offsets {
+            (read_u32(loca, glyph * 4)? as usize, read_u32(loca, glyph * 4 + 4)? as usize)
+        } else {
+            (read_u16(loca, glyph * 2)? as usize * 2, read_u16(loca, glyph * 2 + 2)? as usize * 2)
+        };
+        (start <= end && end <= glyf.len()).then_some((start, end))
+    };
+
+    let mut kept = BTreeSet::from([0usize]);
+    let mut pending: Vec<usize> = chars.iter().filter_map(|&c| coverage.glyph(c)).map(usize::from).collect();
+    while let Some(glyph) = pending.pop() {
+        if glyph >= glyph_count || !kept.insert(glyph) {
+            continue;
+        }
+        pending.extend(composite_components(glyph_range(glyph).map_or(&[][..], |(start, end)| &glyf[start..end])));
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::new();
+    for glyph in 0..=glyph_count {
+        if long_offsets {
+            new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        } else {
+            new_loca.extend_from_slice(&((new_glyf.len() / 2) as u16).to_be_bytes());
+        }
+        if glyph < glyph_count && kept.contains(&glyph) {
+            if let Some((start, end)) = glyph_range(glyph) {
+                new_glyf.extend_from_slice(&glyf[start..end]);
+                new_glyf.resize(new_glyf.len().next_multiple_of(if long_offsets { 4 } else { 2 }), 0);
+            }
+        }
+    }
+    rebuild_font(font, &[(*b"glyf", new_glyf), (*b"loca", new_loca)])
+}
+
+/// Glyph ids a composite glyph is built from
+fn composite_components(glyph: &[u8]) -> Vec<usize> {
+    const ARGS_ARE_WORDS: u16 = 0x0001;
+    const HAS_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const HAS_XY_SCALE: u16 = 0x0040;
+    const HAS_TWO_BY_TWO: u16 = 0x0080;
+
+    let mut components = Vec::new();
+    // Simple glyphs have a non-negative number of contours
+    if read_u16(glyph, 0).is_none_or(|contours| (contours as i16) >= 0) {
+        return components;
+    }
+    let mut offset = 10;
+    while let (Some(flags), Some(component)) = (read_u16(glyph, offset), read_u16(glyph, offset + 2)) {
+        components.push(usize::from(component));
+        offset += 4 + if flags & ARGS_ARE_WORDS != 0 { 4 } else { 2 };
+        offset += if flags & HAS_SCALE != 0 {
+            2
+        } else if flags & HAS_XY_SCALE != 0 {
+            4
+        } else if flags & HAS_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    components
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// `font` with the tables of `replaced` swapped in and its checksums
+/// recomputed
+fn rebuild_font(font: &[u8], replaced: &[([u8; 4], Vec<u8>)]) -> Result<Vec<u8>> {
+    let directory = table_directory(font)?;
+    let header_length = 12 + directory.len() * 16;
+    let mut rebuilt = font.get(..12).ok_or_else(|| malformed("truncated header"))?.to_vec();
+    rebuilt.resize(header_length, 0);
+
+    let mut head_offset = None;
+    for (i, (tag, offset, length)) in directory.iter().enumerate() {
+        let mut data = replaced
+            .iter()
+            .find(|(replaced_tag, _)| replaced_tag == tag)
+            .map_or_else(|| font[*offset..offset + length].to_vec(), |(_, data)| data.clone());
+        if tag == b"head" {
+            // checkSumAdjustment is zero while the checksums are computed
+            data.get_mut(8..12).ok_or_else(|| malformed("truncated head"))?.fill(0);
+            head_offset = Some(rebuilt.len());
+        }
+        let record = 12 + i * 16;
+        let position = rebuilt.len() as u32;
+        rebuilt[record..record + 4].copy_from_slice(tag);
+        rebuilt[record + 4..record + 8].copy_from_slice(&checksum(&data).to_be_bytes());
+        rebuilt[record + 8..record + 12].copy_from_slice(&position.to_be_bytes());
+        rebuilt[record + 12..record + 16].copy_from_slice(&(data.len() as u32).to_be_bytes());
+        rebuilt.extend_from_slice(&data);
+        rebuilt.resize(rebuilt.len().next_multiple_of(4), 0);
+    }
+    if let Some(head_offset) = head_offset {
+        let adjustment = FONT_CHECKSUM.wrapping_sub(checksum(&rebuilt));
+        rebuilt[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    Ok(rebuilt)
+}
+
+/// A font family's files and the characters it covers
+#[derive(Debug, Clone)]
+pub struct LoadedFamily {
+    pub name: String,
+    /// Script the family is a fallback for; `None` for the primary family
+    pub script: Option<Script>,
+    /// Regular, bold, italic and bold italic font files
+    files: [Vec<u8>; 4],
+    coverage: GlyphCoverage,
+}
+
+impl LoadedFamily {
+    /// A family from its font files; a missing style uses the regular one
+    pub fn new(name: &str, script: Option<Script>, regular: Vec<u8>, styles: [Option<Vec<u8>>; 3]) -> Result<Self> {
+        let coverage = GlyphCoverage::from_font(&regular)?;
+        let [bold, italic, bold_italic] = styles.map(|style| style.unwrap_or_else(|| regular.clone()));
+        Ok(Self { name: name.to_string(), script, files: [regular, bold, italic, bold_italic], coverage })
+    }
+
+    fn load(directory: &Path, name: &str, script: Option<Script>) -> Result<Self> {
+        let read = |style: &str| std::fs::read(directory.join(format!("{name}-{style}.ttf")));
+        let regular = read("Regular")?;
+        Self::new(name, script, regular, ["Bold", "Italic", "BoldItalic"].map(|style| read(style).ok()))
+    }
+
+    pub fn coverage(&self) -> &GlyphCoverage {
+        &self.coverage
+    }
+
+    /// The family for genpdf, subset to `chars` when given
+    fn font_family(&self, chars: Option<&BTreeSet<char>>) -> Result<FontFamily<FontData>> {
+        let [regular, bold, italic, bold_italic] = self.files.each_ref().map(|file| {
+            let file = match chars {
+                Some(chars) => subset_font(file, &self.coverage, chars)?,
+                None => file.clone(),
+            };
+            FontData::new(file, None).map_err(|e| TradocumentError::Pdf(format!("Font loading failed for {}: {e}", self.name)))
+        });
+        Ok(FontFamily { regular: regular?, bold: bold?, italic: italic?, bold_italic: bold_italic? })
+    }
+}
+
+/// The fonts of a [`FontConfig`], loaded once for any number of exports
+#[derive(Debug, Clone)]
+pub struct PdfFonts {
+    /// The primary family first, then the fallbacks in order
+    families: Vec<LoadedFamily>,
+    subset: bool,
+}
+
+impl PdfFonts {
+    /// Load the configured fonts. The primary family must exist; missing
+    /// fallbacks are skipped with a warning.
+    pub fn load(config: &FontConfig) -> Result<Self> {
+        let primary = LoadedFamily::load(&config.directory, &config.primary, None)
+            .map_err(|e| TradocumentError::Pdf(format!("Font loading failed: {e}")))?;
+        let mut families = vec![primary];
+        for fallback in &config.fallbacks {
+            match LoadedFamily::load(&config.directory, &fallback.family, Some(fallback.script)) {
+                Ok(family) => families.push(family),
+                Err(e) => log::warn!("Fallback font {} for {:?} text is not available: {e}", fallback.family, fallback.script),
+            }
+        }
+        Ok(Self { families, subset: config.subset })
+    }
+
+    pub fn from_families(primary: LoadedFamily, fallbacks: Vec<LoadedFamily>, subset: bool) -> Self {
+        Self { families: std::iter::once(primary).chain(fallbacks).collect(), subset }
+    }
+
+    /// Index of the family `c` is drawn in: the primary family when it
+    /// covers `c`, else the first fallback for its script that does, else
+    /// the first other fallback that does
+    fn family_for(&self, c: char) -> Option<usize> {
+        if self.families[0].coverage.covers(c) {
+            return Some(0);
+        }
+        let script = Script::of(c);
+        let covering = |(_, family): &(usize, &LoadedFamily)| family.coverage.covers(c);
+        let mut fallbacks = self.families.iter().enumerate().skip(1);
+        fallbacks
+            .clone()
+            .filter(|(_, family)| family.script == Some(script))
+            .find(covering)
+            .or_else(|| fallbacks.find(covering))
+            .map(|(index, _)| index)
+    }
+
+    /// Characters of `text` no family has a glyph for, in code point order
+    pub fn missing_glyphs(&self, text: &str) -> Vec<char> {
+        let chars: BTreeSet<char> = text.chars().filter(|&c| !c.is_control() && !is_invisible(c)).collect();
+        chars.into_iter().filter(|&c| self.family_for(c).is_none()).collect()
+    }
+
+    /// `text` as runs drawn in one family each, by family index. Spaces
+    /// stay in the run they follow when its family has them; characters
+    /// no family covers become [`TOFU`] in the primary family.
+    fn font_runs(&self, text: &str) -> Vec<(usize, String)> {
+        let mut runs: Vec<(usize, String)> = Vec::new();
+        for c in text.chars().filter(|&c| !is_invisible(c)) {
+            let current = runs.last().map(|(family, _)| *family);
+            let (family, c) = match (current, self.family_for(c)) {
+                (Some(current), _) if c.is_whitespace() && self.families[current].coverage.covers(c) => (current, c),
+                (_, Some(family)) => (family, c),
+                (_, None) => (0, TOFU),
+            };
+            match runs.last_mut() {
+                Some((run_family, run)) if *run_family == family => run.push(c),
+                _ => runs.push((family, c.to_string())),
+            }
+        }
+        runs
+    }
+
+    /// The families `doc_text` needs, subset to the characters it and the
+    /// export's own labels use. Warns about the characters no family
+    /// covers.
+    pub(super) fn prepare(&self, doc_text: &str) -> Result<PreparedFonts> {
+        let missing = self.missing_glyphs(doc_text);
+        if !missing.is_empty() {
+            log::warn!("No configured font has glyphs for {}", format_code_points(&missing));
+        }
+
+        // Labels, numbers and punctuation the export adds itself
+        let mut chars: BTreeSet<char> = ('\u{20}'..='\u{7E}').chain('\u{A0}'..='\u{FF}').chain('\u{2010}'..='\u{2027}').collect();
+        chars.insert(TOFU);
+        chars.extend(doc_text.chars());
+        let subset = self.subset.then_some(&chars);
+
+        let mut used = BTreeSet::new();
+        for c in doc_text.chars() {
+            if let Some(family) = self.family_for(c) {
+                used.insert(family);
+            }
+        }
+        let primary = self.families[0].font_family(subset)?;
+        let fallbacks = used
+            .into_iter()
+            .filter(|&family| family > 0)
+            .map(|family| Ok((family, self.families[family].font_family(subset)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(PreparedFonts { primary, fallbacks })
+    }
+}
+
+/// Font data to add to a PDF document
+pub(super) struct PreparedFonts {
+    pub(super) primary: FontFamily<FontData>,
+    /// Fallback families the text uses, by family index
+    pub(super) fallbacks: Vec<(usize, FontFamily<FontData>)>,
+}
+
+/// Format characters that take no glyph, such as bidi isolates
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2069}' | '\u{FEFF}')
+}
+
+/// `chars` as `U+XXXX` code points
+pub fn format_code_points(chars: &[char]) -> String {
+    chars.iter().map(|&c| format!("U+{:04X} ({c})", u32::from(c))).collect::<Vec<_>>().join(", ")
+}
+
+/// Splits the text of PDF paragraphs into runs in the document's fonts
+pub(super) struct FontRuns<'a> {
+    fonts: &'a PdfFonts,
+    /// Fallback families added to the document, by family index
+    families: HashMap<usize, FontFamily<Font>>,
+}
+
+impl<'a> FontRuns<'a> {
+    pub(super) fn new(fonts: &'a PdfFonts, families: HashMap<usize, FontFamily<Font>>) -> Self {
+        Self { fonts, families }
+    }
+
+    /// Add `text` to `paragraph` in `style`, each run in its family
+    pub(super) fn push(&self, paragraph: &mut Paragraph, text: &str, style: Style) {
+        for (family, run) in self.fonts.font_runs(text) {
+            match self.families.get(&family) {
+                Some(&family) => paragraph.push_styled(run, style.with_font_family(family)),
+                None => paragraph.push_styled(run, style),
+            }
+        }
+    }
+
+    pub(super) fn paragraph(&self, text: &str, style: Style) -> Paragraph {
+        let mut paragraph = Paragraph::default();
+        self.push(&mut paragraph, text, style);
+        paragraph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn liberation_sans() -> LoadedFamily {
+        LoadedFamily::load(Path::new("fonts"), "LiberationSans", None).unwrap()
+    }
+
+    /// A font made of `tables`, in order
+    fn font_with_tables(tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut font = vec![0, 1, 0, 0, 0, tables.len() as u8, 0, 16, 0, 0, 0, 0];
+        let mut offset = 12 + tables.len() * 16;
+        for (tag, data) in tables {
+            font.extend_from_slice(&tag[..]);
+            font.extend_from_slice(&checksum(data).to_be_bytes());
+            font.extend_from_slice(&(offset as u32).to_be_bytes());
+            font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            offset += data.len();
+        }
+        for (_, data) in tables {
+            font.extend_from_slice(data);
+        }
+        font
+    }
+
+    /// A font holding only a character map from `first` to `last`
+    fn cmap_only_font(first: char, last: char) -> Vec<u8> {
+        let mut cmap = Vec::new();
+        cmap.extend_from_slice(&[0, 0, 0, 1, 0, 3, 0, 10, 0, 0, 0, 12]);
+        cmap.extend_from_slice(&[0, 12, 0, 0, 0, 0, 0, 28, 0, 0, 0, 0, 0, 0, 0, 1]);
+        for value in [u32::from(first), u32::from(last), 1] {
+            cmap.extend_from_slice(&value.to_be_bytes());
+        }
+        font_with_tables(&[(b"cmap", cmap)])
+    }
+
+    /// Outline of the glyph `font` draws `c` with; empty for glyphs without one
+    fn outline<'a>(font: &'a [u8], coverage: &GlyphCoverage, c: char) -> &'a [u8] {
+        let long_offsets = read_u16(table(font, b"head").unwrap().unwrap(), 50) == Some(1);
+        let (loca, glyf) = (table(font, b"loca").unwrap().unwrap(), table(font, b"glyf").unwrap().unwrap());
+        let glyph = usize::from(coverage.glyph(c).unwrap());
+        let (start, end) = if long_offsets {
+            (read_u32(loca, glyph * 4).unwrap() as usize, read_u32(loca, glyph * 4 + 4).unwrap() as usize)
+        } else {
+            (read_u16(loca, glyph * 2).unwrap() as usize * 2, read_u16(loca, glyph * 2 + 2).unwrap() as usize * 2)
+        };
+        &glyf[start..end]
+    }
+
+    #[test]
+    fn test_cjk_text_uses_cjk_fallback() {
+        let cjk = LoadedFamily::new("NotoSansCJKsc", Some(Script::Cjk), cmap_only_font('\u{4E00}', '\u{9FFF}'), [None, None, None]).unwrap();
+        let fonts = PdfFonts::from_families(liberation_sans(), vec![cjk], true);
+
+        assert_eq!(fonts.font_runs("Press 開始 now"), [
+            (0, "Press ".to_string()),
+            (1, "開始".to_string()),
+            (0, " now".to_string()),
+        ]);
+        assert!(fonts.missing_glyphs("Press 開始 now").is_empty());
+    }
+
+    #[test]
+    fn test_missing_glyphs_reported_and_drawn_as_tofu() {
+        let fonts = PdfFonts::from_families(liberation_sans(), Vec::new(), true);
+        let missing = fonts.missing_glyphs("Play 𝄞 at 開始");
+        assert_eq!(missing, ['始', '開', '𝄞']);
+        assert_eq!(format_code_points(&missing), "U+59CB (始), U+958B (開), U+1D11E (𝄞)");
+        assert_eq!(fonts.font_runs("Play 𝄞!"), [(0, "Play □!".to_string())]);
+    }
+
+    #[test]
+    fn test_subset_keeps_only_used_glyphs() {
+        let family = liberation_sans();
+        let regular = &family.files[0];
+        let subset = subset_font(regular, family.coverage(), &BTreeSet::from(['H', 'i'])).unwrap();
+        assert!(subset.len() * 2 < regular.len());
+        // The character map is whole, so glyph ids stay valid
+        assert_eq!(GlyphCoverage::from_font(&subset).unwrap(), family.coverage);
+        for c in ['H', 'i'] {
+            assert!(!outline(regular, family.coverage(), c).is_empty());
+            assert_eq!(outline(&subset, family.coverage(), c), outline(regular, family.coverage(), c));
+        }
+        assert!(!outline(regular, family.coverage(), 'A').is_empty());
+        assert!(outline(&subset, family.coverage(), 'A').is_empty());
+
+        // Each table's recorded checksum is that of its data, head's taken
+        // without its checkSumAdjustment
+        for (i, (tag, offset, length)) in table_directory(&subset).unwrap().into_iter().enumerate() {
+            let mut data = subset[offset..offset + length].to_vec();
+            if &tag == b"head" {
+                data[8..12].fill(0);
+            }
+            assert_eq!(read_u32(&subset, 12 + i * 16 + 4), Some(checksum(&data)), "{}", String::from_utf8_lossy(&tag));
+        }
+    }
+
+    #[test]
+    fn test_malformed_fonts_rejected() {
+        let mut head = vec![0; 54];
+        head[50..52].copy_from_slice(&1u16.to_be_bytes());
+        let no_glyphs = font_with_tables(&[(b"glyf", Vec::new()), (b"head", head), (b"loca", Vec::new())]);
+        let error = subset_font(&no_glyphs, &GlyphCoverage::default(), &BTreeSet::from(['H'])).unwrap_err();
+        assert_eq!(error.to_string(), "PDF generation error: Malformed font: empty loca table");
+
+        assert!(rebuild_font(&[0, 1, 0, 0, 0, 0], &[]).is_err());
+        let mut beyond_end = cmap_only_font('a', 'z');
+        beyond_end.truncate(40);
+        assert!(GlyphCoverage::from_font(&beyond_end).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use super::{CodeTheme, ExportConfig, ExportEngine, ExportFormat, ExportOutput, ImagePolicy, PageSetup, Watermark};
+use crate::{Document, Manual, Result, TradocumentError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// A named bundle of export options, such as one client's house style.
+/// Options it leaves unset come from its base preset, or else from
+/// [`ExportConfig::default`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportPreset {
+    pub name: String,
+    /// Name of the preset this one overrides
+    #[serde(default)]
+    pub base: Option<String>,
+    #[serde(default)]
+    pub format: Option<ExportFormat>,
+    #[serde(default)]
+    pub include_screenshots: Option<bool>,
+    #[serde(default)]
+    pub css_file: Option<String>,
+    #[serde(default)]
+    pub page_setup: Option<PageSetup>,
+    #[serde(default)]
+    pub code_theme: Option<CodeTheme>,
+    #[serde(default)]
+    pub image_policy: Option<ImagePolicy>,
+    #[serde(default)]
+    pub header_template: Option<String>,
+    #[serde(default)]
+    pub watermark: Option<Watermark>,
+}
+
+impl ExportPreset {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), ..Self::default() }
+    }
+
+    /// Overwrite the options of `config` that this preset sets
+    fn apply_to(&self, config: &mut ExportConfig) {
+        if let Some(format) = self.format {
+            config.format = format;
+        }
+        if let Some(include_screenshots) = self.include_screenshots {
+            config.include_screenshots = include_screenshots;
+        }
+        if let Some(css_file) = &self.css_file {
+            config.css_file = Some(css_file.clone());
+        }
+        if let Some(page_setup) = self.page_setup {
+            config.page_setup = page_setup;
+        }
+        if let Some(code_theme) = self.code_theme {
+            config.code_theme = code_theme;
+        }
+        if let Some(image_policy) = self.image_policy {
+            config.image_policy = Some(image_policy);
+        }
+        if let Some(header_template) = &self.header_template {
+            config.header_template = Some(header_template.clone());
+        }
+        if let Some(watermark) = &self.watermark {
+            config.watermark = Some(watermark.clone());
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExportPresetsFile {
+    #[serde(default)]
+    presets: Vec<ExportPreset>,
+}
+
+/// Export presets by name
+#[derive(Debug, Clone, Default)]
+pub struct ExportPresets {
+    presets: HashMap<String, ExportPreset>,
+}
+
+impl ExportPresets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the presets from [`ExportPresets::config_path`], or none if
+    /// the file doesn't exist
+    pub fn load() -> Result<Self> {
+        match Self::config_path() {
+            Some(path) if path.exists() => Self::load_from_path(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Location of the export presets file
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tradocflow").join("export_presets.toml"))
+    }
+
+    pub fn load_from_path(path: &Path) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parse `[[presets]]` entries, each with a `name` and the options it
+    /// sets
+    pub fn parse(content: &str) -> Result<Self> {
+        let file: ExportPresetsFile = toml::from_str(content)?;
+        let mut presets = Self::default();
+        for preset in file.presets {
+            presets.register(preset);
+        }
+        Ok(presets)
+    }
+
+    /// Save the presets to [`ExportPresets::config_path`]
+    pub fn save(&self) -> Result<()> {
+        let path = Self::config_path()
+            .ok_or_else(|| TradocumentError::FileError("No configuration directory for export presets".to_string()))?;
+        self.save_to_path(&path)
+    }
+
+    pub fn save_to_path(&self, path: &Path) -> Result<()> {
+        let mut presets: Vec<ExportPreset> = self.presets.values().cloned().collect();
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+        let content = toml::to_string_pretty(&ExportPresetsFile { presets })
+            .map_err(|e| TradocumentError::FileError(format!("Export presets could not be serialized: {e}")))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Add a preset, replacing one of the same name
+    pub fn register(&mut self, preset: ExportPreset) {
+        self.presets.insert(preset.name.clone(), preset);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ExportPreset> {
+        self.presets.get(name)
+    }
+
+    /// Export options of the named preset, applied over its bases in turn
+    pub fn resolve(&self, name: &str) -> Result<ExportConfig> {
+        let mut chain = Vec::new();
+        let mut next = Some(name);
+        while let Some(name) = next {
+            let preset = self
+                .get(name)
+                .ok_or_else(|| TradocumentError::Validation(format!("Unknown export preset: {name}")))?;
+            if chain.iter().any(|p: &&ExportPreset| p.name == preset.name) {
+                return Err(TradocumentError::Validation(format!("Export preset {name} is based on itself")));
+            }
+            chain.push(preset);
+            next = preset.base.as_deref();
+        }
+
+        let mut config = ExportConfig::default();
+        for preset in chain.iter().rev() {
+            preset.apply_to(&mut config);
+        }
+        Ok(config)
+    }
+}
+
+impl ExportEngine {
+    /// Presets [`ExportEngine::export_with_preset`] can export with
+    pub fn with_presets(mut self, presets: ExportPresets) -> Self {
+        self.presets = presets;
+        self
+    }
+
+    /// Export one language of a manual with the options of the named
+    /// preset. Files are named like those of
+    /// [`ExportEngine::export_document`].
+    pub async fn export_with_preset(
+        &self,
+        manual: &Manual,
+        documents: &HashMap<Uuid, Document>,
+        language: &str,
+        preset_name: &str,
+    ) -> Result<ExportOutput> {
+        let mut config = self.presets.resolve(preset_name)?;
+        config.languages = vec![language.to_string()];
+        let markdown = self.manual_markdown(manual, documents, language).await?;
+
+        let mut output = ExportOutput::default();
+        if matches!(config.format, ExportFormat::Html | ExportFormat::Both) {
+            let html = self.generate_html(&markdown, &[], language, &config, &mut output.image_bytes_saved)?;
+            output.files.insert(format!("{language}.html"), html.into_bytes());
+        }
+        if matches!(config.format, ExportFormat::Pdf | ExportFormat::Both) {
+            let pdf = self.generate_pdf(&markdown, language, &config, &mut output.image_bytes_saved)?;
+            output.files.insert(format!("{language}.pdf"), pdf);
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_engine::page_setup::{Margins, Orientation, PageSize};
+    use crate::export_engine::PreferredImageFormat;
+    use tempfile::TempDir;
+
+    fn print_preset() -> ExportPreset {
+        ExportPreset {
+            format: Some(ExportFormat::Pdf),
+            page_setup: Some(PageSetup::new(PageSize::Letter, Margins::uniform(20.0), Orientation::Portrait)),
+            code_theme: Some(CodeTheme::SolarizedLight),
+            header_template: Some("Bell Tower Controller - page {page}".to_string()),
+            ..ExportPreset::new("print")
+        }
+    }
+
+    #[test]
+    fn test_saved_preset_loads_with_all_options() {
+        let preset = ExportPreset {
+            base: Some("print".to_string()),
+            format: Some(ExportFormat::Both),
+            include_screenshots: Some(false),
+            css_file: Some("styles/acme.css".to_string()),
+            page_setup: Some(PageSetup::new(
+                PageSize::Custom { width_mm: 180.0, height_mm: 250.0 },
+                Margins { top: 15.0, right: 12.5, bottom: 20.0, left: 12.5 },
+                Orientation::Landscape,
+            )),
+            code_theme: Some(CodeTheme::Base16OceanDark),
+            image_policy: Some(ImagePolicy { max_dimension: 800, jpeg_quality: 70, prefer_format: PreferredImageFormat::Png }),
+            header_template: Some("ACME - {page}".to_string()),
+            watermark: Some(Watermark::draft()),
+            ..ExportPreset::new("acme")
+        };
+        let mut presets = ExportPresets::new();
+        presets.register(print_preset());
+        presets.register(preset.clone());
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tradocflow").join("export_presets.toml");
+        presets.save_to_path(&path).unwrap();
+        let loaded = ExportPresets::load_from_path(&path).unwrap();
+
+        assert_eq!(loaded.get("acme"), Some(&preset));
+        assert_eq!(loaded.get("print"), Some(&print_preset()));
+    }
+
+    #[tokio::test]
+    async fn test_preset_overrides_defaults_and_its_base() {
+        let mut presets = ExportPresets::new();
+        presets.register(print_preset());
+        presets.register(ExportPreset {
+            base: Some("print".to_string()),
+            format: Some(ExportFormat::Html),
+            image_policy: Some(ImagePolicy::default()),
+            ..ExportPreset::new("client-web")
+        });
+
+        let config = presets.resolve("client-web").unwrap();
+        let defaults = ExportConfig::default();
+        // Set by the preset itself
+        assert_eq!(config.format, ExportFormat::Html);
+        assert_eq!(config.image_policy, Some(ImagePolicy::default()));
+        // Set by its base
+        assert_eq!(config.code_theme, CodeTheme::SolarizedLight);
+        assert_eq!(config.page_setup.size, PageSize::Letter);
+        assert_eq!(config.header_template.as_deref(), Some("Bell Tower Controller - page {page}"));
+        // Set by neither
+        assert_eq!(config.include_screenshots, defaults.include_screenshots);
+        assert_eq!(config.css_file, defaults.css_file);
+
+        let error = presets.resolve("client-print").unwrap_err();
+        assert!(error.to_string().contains("Unknown export preset: client-print"));
+
+        let engine = ExportEngine::new().with_presets(presets);
+        let manual = Manual {
+            id: Uuid::new_v4(),
+            title: "Controller Manual".to_string(),
+            description: String::new(),
+            sections: Vec::new(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            version: "1.0".to_string(),
+            languages: vec!["en".to_string()],
+            template_type: crate::ManualTemplate::TechnicalManual,
+        };
+        let output = engine.export_with_preset(&manual, &HashMap::new(), "en", "client-web").await.unwrap();
+        assert_eq!(output.files.keys().collect::<Vec<_>>(), ["en.html"]);
+        assert!(engine.export_with_preset(&manual, &HashMap::new(), "en", "client-print").await.is_err());
+    }
+}
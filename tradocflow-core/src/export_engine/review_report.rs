@@ -0,0 +1,349 @@
+use super::html_bundle::escape_html;
+use super::{ExportConfig, ExportEngine, ExportFormat};
+use crate::git_integration::diff_tools::{DetailedTranslationDiff, DiffOptions, GitDiffTools, TranslationUnitDiff, UnitChangeType};
+use crate::git_integration::ReviewRequest;
+use crate::review_system::{Comment, ReviewSystem};
+use crate::services::TextDirection;
+use crate::Result;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// One changed unit of the report
+struct ReportSection<'a> {
+    change: &'a TranslationUnitDiff,
+    old_text: &'a str,
+    new_text: &'a str,
+}
+
+impl ReportSection<'_> {
+    /// The comments on this section's unit
+    fn comments<'c>(&self, by_unit: &HashMap<&str, Vec<&'c Comment>>) -> Vec<&'c Comment> {
+        by_unit.get(self.change.unit_id.as_str()).cloned().unwrap_or_default()
+    }
+}
+
+impl ExportEngine {
+    /// Export the changes of a review as a standalone "what changed" report.
+    ///
+    /// The diff between `base` and the review branch comes from `diff_tools`
+    /// and the reviewer comments from `review_system`. Returns the rendered
+    /// files keyed by name, like the output of [`ExportEngine::export_document`].
+    pub async fn export_review_report(
+        &self,
+        review: &ReviewRequest,
+        base: &str,
+        diff_tools: &GitDiffTools,
+        review_system: &ReviewSystem,
+        config: &ExportConfig,
+    ) -> Result<HashMap<String, Vec<u8>>> {
+        let options = DiffOptions { language_filter: Some(review.language.clone()), ..Default::default() };
+        let diff = diff_tools
+            .compare_translations(base, &review.branch, &review.chapter, Some(options))
+            .await?;
+        let comments = review_system.get_comments_for_review(review.id).cloned().unwrap_or_default();
+
+        let name = format!("review-{}", review.pr_number);
+        let mut results = HashMap::new();
+        if matches!(config.format, ExportFormat::Html | ExportFormat::Both) {
+            let html = self.render_review_report(review, &diff, &comments);
+            results.insert(format!("{name}.html"), html.into_bytes());
+        }
+        if matches!(config.format, ExportFormat::Pdf | ExportFormat::Both) {
+            let markdown = review_report_markdown(review, &diff, &comments);
+            results.insert(format!("{name}.pdf"), self.generate_pdf(&markdown, &review.language, config, &mut 0)?);
+        }
+        Ok(results)
+    }
+
+    /// Render a review report as HTML.
+    ///
+    /// Each changed unit gets a section with the text before and after the
+    /// review; removed words are struck through and added words highlighted.
+    /// Changes that only touch formatting are collapsed. Comments are shown
+    /// in the section of the unit they are on, and comments on no changed
+    /// unit are listed at the end.
+    pub fn render_review_report(&self, review: &ReviewRequest, diff: &DetailedTranslationDiff, comments: &[Comment]) -> String {
+        let sections = report_sections(diff);
+        let by_unit = comments_by_unit(comments);
+
+        let mut body = String::new();
+        for section in &sections {
+            let section_comments = section.comments(&by_unit);
+
+            let (before, after) = word_diff_html(section.old_text, section.new_text);
+            let title = escape_html(&section.change.unit_id);
+            let table = format!(
+                "<table class=\"review-diff\"><tr><th>Before</th><th>After</th></tr>\
+                 <tr><td class=\"before\">{before}</td><td class=\"after\">{after}</td></tr></table>\n"
+            );
+
+            if is_formatting_only(section.old_text, section.new_text) {
+                body.push_str(&format!(
+                    "<details class=\"review-section formatting-only\"><summary>{title} (formatting only)</summary>\n{table}"
+                ));
+                render_comments(&section_comments, &mut body);
+                body.push_str("</details>\n");
+            } else {
+                body.push_str(&format!("<section class=\"review-section\">\n<h2>{title}</h2>\n{table}"));
+                render_comments(&section_comments, &mut body);
+                body.push_str("</section>\n");
+            }
+        }
+
+        let general: Vec<&Comment> = comments
+            .iter()
+            .filter(|c| {
+                c.position.unit_id.as_deref().is_none_or(|unit_id| !sections.iter().any(|s| s.change.unit_id == unit_id))
+            })
+            .collect();
+        if !general.is_empty() {
+            body.push_str("<section class=\"review-section\">\n<h2>General comments</h2>\n");
+            render_comments(&general, &mut body);
+            body.push_str("</section>\n");
+        }
+
+        let css = include_str!("default.css");
+        let report_css = include_str!("review_report.css");
+        let title = escape_html(&format!("Review #{}: {}", review.pr_number, review.chapter));
+        let lang = escape_html(&review.language);
+        let dir = TextDirection::for_language(&review.language).as_css();
+        let reviewer = escape_html(review.reviewer.as_deref().unwrap_or("-"));
+        let translator = escape_html(&review.translator);
+        let summary = escape_html(&review.changes_summary);
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="{lang}" dir="{dir}">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{title}</title>
+    <style>{css}{report_css}</style>
+</head>
+<body class="review-report">
+    <div class="document-content">
+        <h1>{title}</h1>
+        <p class="review-meta">Translator: {translator} &middot; Reviewer: {reviewer}</p>
+        <p class="review-summary">{summary}</p>
+        {body}
+    </div>
+</body>
+</html>"#
+        )
+    }
+}
+
+/// Units whose text changed
+fn report_sections(diff: &DetailedTranslationDiff) -> Vec<ReportSection<'_>> {
+    diff.unit_changes
+        .iter()
+        .filter(|change| {
+            matches!(change.change_type, UnitChangeType::Added | UnitChangeType::Modified | UnitChangeType::Deleted)
+        })
+        .map(|change| {
+            let (old_text, new_text) = match &change.text_diff {
+                Some(text_diff) => (text_diff.old_text.as_str(), text_diff.new_text.as_str()),
+                None => (
+                    change.old_translation.as_ref().map_or("", |t| t.text.as_str()),
+                    change.new_translation.as_ref().map_or("", |t| t.text.as_str()),
+                ),
+            };
+            ReportSection { change, old_text, new_text }
+        })
+        .collect()
+}
+
+/// Comments by the ID of the unit they are on
+fn comments_by_unit(comments: &[Comment]) -> HashMap<&str, Vec<&Comment>> {
+    let mut by_unit: HashMap<&str, Vec<&Comment>> = HashMap::new();
+    for comment in comments {
+        if let Some(unit_id) = comment.position.unit_id.as_deref() {
+            by_unit.entry(unit_id).or_default().push(comment);
+        }
+    }
+    by_unit
+}
+
+fn render_comments(comments: &[&Comment], out: &mut String) {
+    for comment in comments {
+        out.push_str(&format!(
+            "<aside class=\"review-comment\"><span class=\"comment-author\">{}</span> {}",
+            escape_html(&comment.author_id),
+            escape_html(&comment.content)
+        ));
+        for reply in &comment.replies {
+            out.push_str(&format!(
+                "<div class=\"comment-reply\"><span class=\"comment-author\">{}</span> {}</div>",
+                escape_html(&reply.author_id),
+                escape_html(&reply.content)
+            ));
+        }
+        out.push_str("</aside>\n");
+    }
+}
+
+/// Text with markdown emphasis and code markers removed and whitespace collapsed
+fn without_formatting(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '~'))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_formatting_only(old: &str, new: &str) -> bool {
+    old != new && without_formatting(old) == without_formatting(new)
+}
+
+/// Words with their trailing whitespace
+fn tokens(text: &str) -> Vec<&str> {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    TOKEN
+        .get_or_init(|| Regex::new(r"\s*\S+\s*").expect("valid token regex"))
+        .find_iter(text)
+        .map(|m| m.as_str())
+        .collect()
+}
+
+/// Render the old and new text with removed words in `<del>` and added
+/// words in `<ins>`, based on the longest common subsequence of words
+pub(super) fn word_diff_html(old: &str, new: &str) -> (String, String) {
+    let (a, b) = (tokens(old), tokens(new));
+    let same = |x: &str, y: &str| x.trim() == y.trim();
+
+    // lcs[i][j] is the common length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if same(a[i], b[j]) { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let (mut before, mut after) = (String::new(), String::new());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && same(a[i], b[j]) {
+            before.push_str(&escape_html(a[i]));
+            after.push_str(&escape_html(b[j]));
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            after.push_str(&format!("<ins>{}</ins>", escape_html(b[j])));
+            j += 1;
+        } else {
+            before.push_str(&format!("<del>{}</del>", escape_html(a[i])));
+            i += 1;
+        }
+    }
+    // Merge runs of changed words into one highlight
+    (before.replace("</del><del>", ""), after.replace("</ins><ins>", ""))
+}
+
+/// Plain rendition of the report for PDF output, where colors aren't available
+fn review_report_markdown(review: &ReviewRequest, diff: &DetailedTranslationDiff, comments: &[Comment]) -> String {
+    let mut markdown = format!("# Review #{}: {}\n\n{}\n\n", review.pr_number, review.chapter, review.changes_summary);
+    let by_unit = comments_by_unit(comments);
+    for section in report_sections(diff) {
+        if is_formatting_only(section.old_text, section.new_text) {
+            continue;
+        }
+        markdown.push_str(&format!(
+            "## {}\n\nBefore: {}\n\nAfter: {}\n\n",
+            section.change.unit_id, section.old_text, section.new_text
+        ));
+        for comment in section.comments(&by_unit) {
+            markdown.push_str(&format!("{}: {}\n\n", comment.author_id, comment.content));
+        }
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_integration::diff_tools::{TextDiff, TranslationDiffStats};
+    use crate::git_integration::models::ReviewStatus;
+    use crate::review_system::CommentPosition;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn unit_diff(unit_id: &str, old_text: &str, new_text: &str) -> TranslationUnitDiff {
+        TranslationUnitDiff {
+            unit_id: unit_id.to_string(),
+            change_type: UnitChangeType::Modified,
+            old_translation: None,
+            new_translation: None,
+            text_diff: Some(TextDiff {
+                old_text: old_text.to_string(),
+                new_text: new_text.to_string(),
+                word_changes: 1,
+                character_changes: 1,
+                similarity_score: 0.8,
+            }),
+            quality_change: None,
+            status_change: None,
+        }
+    }
+
+    #[test]
+    fn test_report_shows_text_change_and_comment() {
+        let review = ReviewRequest {
+            id: Uuid::new_v4(),
+            pr_number: 42,
+            branch: "translation/de/setup".to_string(),
+            chapter: "setup".to_string(),
+            language: "de".to_string(),
+            translator: "anna".to_string(),
+            reviewer: Some("ben".to_string()),
+            status: ReviewStatus::Approved,
+            created_at: Utc::now(),
+            changes_summary: "Terminology fixes".to_string(),
+            status_changed_at: None,
+        };
+        let diff = DetailedTranslationDiff {
+            chapter: "setup".to_string(),
+            language: "de".to_string(),
+            from_commit: "a".to_string(),
+            to_commit: "b".to_string(),
+            from_timestamp: Utc::now(),
+            to_timestamp: Utc::now(),
+            unit_changes: vec![
+                unit_diff("intro", "Drücken Sie den Knopf.", "Drücken Sie die Taste."),
+                unit_diff("note", "Siehe *Kapitel 2*.", "Siehe **Kapitel 2**."),
+            ],
+            metadata_changes: Vec::new(),
+            stats: TranslationDiffStats {
+                units_added: 0,
+                units_modified: 2,
+                units_deleted: 0,
+                total_word_changes: 2,
+                quality_improvements: 0,
+                quality_regressions: 0,
+                status_promotions: 0,
+                metadata_changes: 0,
+                overall_progress_score: 0.0,
+            },
+        };
+        let comment = Comment {
+            id: Uuid::new_v4(),
+            author_id: "ben".to_string(),
+            content: "\"Taste\" is the approved term".to_string(),
+            position: CommentPosition { line_start: 1, line_end: 1, column_start: 0, column_end: 0, language: "de".to_string(), unit_id: Some("intro".to_string()) },
+            created_at: Utc::now(),
+            resolved: false,
+            replies: Vec::new(),
+        };
+
+        let html = ExportEngine::new().render_review_report(&review, &diff, &[comment]);
+
+        assert!(html.contains("Drücken Sie <del>den Knopf.</del>"));
+        assert!(html.contains("Drücken Sie <ins>die Taste.</ins>"));
+        let section = html.find("<h2>intro</h2>").unwrap();
+        let comment = html.find("&quot;Taste&quot; is the approved term").unwrap();
+        assert!(section < comment && comment < html.find("<details").unwrap());
+        assert!(html.contains("<details class=\"review-section formatting-only\"><summary>note"));
+        assert!(!html.contains("General comments"));
+    }
+}
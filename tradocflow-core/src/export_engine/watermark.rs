@@ -0,0 +1,221 @@
+use super::PageSetup;
+use crate::ReviewStatus;
+use genpdf::style::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+const MM_PER_POINT: f64 = 25.4 / 72.0;
+/// Distance between letter centres relative to the font size
+const LETTER_ADVANCE: f64 = 0.75;
+/// Share of the page diagonal the text runs along
+const DIAGONAL_SHARE: f64 = 0.6;
+const MIN_FONT_SIZE_PT: f64 = 12.0;
+const MAX_FONT_SIZE_PT: f64 = 120.0;
+
+/// Text stamped diagonally across every page of a PDF export, behind the
+/// body text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Watermark {
+    pub text: String,
+    /// From 0, invisible, to 1, as strong as `color`. Pages are assumed to
+    /// be white, so the color is blended with white.
+    pub opacity: f64,
+    /// Red, green and blue
+    pub color: (u8, u8, u8),
+    /// Leave the watermark off documents whose review is approved
+    #[serde(default)]
+    pub unless_approved: bool,
+}
+
+/// One letter of a watermark, with its top left corner in millimetres from
+/// the top left of the page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WatermarkGlyph {
+    pub letter: char,
+    pub x_mm: f64,
+    pub y_mm: f64,
+}
+
+impl Watermark {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into(), opacity: 0.2, color: (128, 128, 128), unless_approved: false }
+    }
+
+    /// "DRAFT" in red on every document that isn't approved yet
+    pub fn draft() -> Self {
+        Self { color: (200, 0, 0), unless_approved: true, ..Self::new("DRAFT") }
+    }
+
+    /// Whether the watermark goes on a document with `review_status`. A
+    /// document without one counts as not approved.
+    pub fn applies_to(&self, review_status: Option<&ReviewStatus>) -> bool {
+        !(self.unless_approved && review_status == Some(&ReviewStatus::Approved))
+    }
+
+    /// `color` at `opacity` over white paper
+    pub fn blended_color(&self) -> (u8, u8, u8) {
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        let blend = |channel: u8| (255.0 - (255.0 - f64::from(channel)) * opacity).round() as u8;
+        (blend(self.color.0), blend(self.color.1), blend(self.color.2))
+    }
+
+    /// Font size at which the text spans most of the page diagonal
+    pub fn font_size_pt(&self, setup: &PageSetup) -> u8 {
+        let (width, height) = setup.page_dimensions_mm();
+        let letters = self.text.chars().count().max(1) as f64;
+        let size = width.hypot(height) * DIAGONAL_SHARE / (letters * LETTER_ADVANCE * MM_PER_POINT);
+        size.clamp(MIN_FONT_SIZE_PT, MAX_FONT_SIZE_PT) as u8
+    }
+
+    /// The letters of the text, centred on the page and rising from its
+    /// bottom left towards its top right. Letters stay upright, since PDF
+    /// pages are laid out without rotated text.
+    pub fn glyphs(&self, setup: &PageSetup) -> Vec<WatermarkGlyph> {
+        let (width, height) = setup.page_dimensions_mm();
+        let diagonal = width.hypot(height);
+        let (dx, dy) = (width / diagonal, -height / diagonal);
+        let font_mm = f64::from(self.font_size_pt(setup)) * MM_PER_POINT;
+        let advance = font_mm * LETTER_ADVANCE;
+        let middle = (self.text.chars().count() as f64 - 1.0) / 2.0;
+
+        self.text
+            .chars()
+            .enumerate()
+            .map(|(i, letter)| {
+                let along = (i as f64 - middle) * advance;
+                WatermarkGlyph {
+                    letter,
+                    x_mm: width / 2.0 + along * dx - advance / 2.0,
+                    y_mm: height / 2.0 + along * dy - font_mm / 2.0,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Draws a watermark on each page before the page decorator it wraps lays
+/// out the page, so the watermark sits behind everything else and leaves
+/// the text area as it is
+pub(super) struct WatermarkDecorator<D> {
+    inner: D,
+    glyphs: Vec<WatermarkGlyph>,
+    style: Style,
+}
+
+impl<D> WatermarkDecorator<D> {
+    pub(super) fn new(inner: D, watermark: &Watermark, setup: &PageSetup) -> Self {
+        let (red, green, blue) = watermark.blended_color();
+        let style = Style::new().with_font_size(watermark.font_size_pt(setup)).with_color(Color::Rgb(red, green, blue));
+        Self { inner, glyphs: watermark.glyphs(setup), style }
+    }
+}
+
+impl<D: genpdf::PageDecorator> genpdf::PageDecorator for WatermarkDecorator<D> {
+    fn decorate_page<'a>(
+        &mut self,
+        context: &genpdf::Context,
+        area: genpdf::render::Area<'a>,
+        style: Style,
+    ) -> Result<genpdf::render::Area<'a>, genpdf::error::Error> {
+        for glyph in &self.glyphs {
+            let position = genpdf::Position::new(glyph.x_mm, glyph.y_mm);
+            area.print_str(&context.font_cache, position, self.style, glyph.letter.to_string())?;
+        }
+        self.inner.decorate_page(context, area, style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_engine::{ExportConfig, ExportEngine, ExportFormat, FontConfig, PdfFonts};
+    use regex::Regex;
+
+    fn page_count(pdf: &[u8]) -> usize {
+        Regex::new(r"/Type\s*/Page[^s]").unwrap().find_iter(&String::from_utf8_lossy(pdf)).count()
+    }
+
+    /// Per page, the pieces of text drawn in `color`
+    fn text_drawn_in(pdf: &[u8], color: (u8, u8, u8)) -> Vec<usize> {
+        let number = |object: &lopdf::Object| match *object {
+            lopdf::Object::Integer(value) => value as f64,
+            lopdf::Object::Real(value) => value,
+            _ => f64::NAN,
+        };
+        let wanted = [color.0, color.1, color.2].map(|channel| f64::from(channel) / 255.0);
+        let document = lopdf::Document::load_mem(pdf).unwrap();
+        document
+            .get_pages()
+            .values()
+            .map(|&page| {
+                let content = lopdf::content::Content::decode(&document.get_page_content(page).unwrap()).unwrap();
+                // Fill color in effect, saved and restored with the graphics state
+                let (mut in_color, mut saved) = (false, Vec::new());
+                let mut drawn = 0;
+                for operation in &content.operations {
+                    match operation.operator.as_str() {
+                        "q" => saved.push(in_color),
+                        "Q" => in_color = saved.pop().unwrap_or(false),
+                        "rg" => {
+                            in_color = operation.operands.len() == 3
+                                && operation.operands.iter().zip(wanted).all(|(operand, channel)| (number(operand) - channel).abs() < 0.005);
+                        }
+                        "Tj" | "TJ" if in_color => drawn += 1,
+                        _ => {}
+                    }
+                }
+                drawn
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_watermark_is_stamped_on_every_page() {
+        let watermark = Watermark { opacity: 0.25, color: (0, 0, 200), ..Watermark::new("CONFIDENTIAL") };
+        let config = ExportConfig { format: ExportFormat::Pdf, watermark: Some(watermark.clone()), ..ExportConfig::default() };
+        let setup = config.page_setup;
+
+        // The same letters go on every page, all of them on the paper
+        let glyphs = watermark.glyphs(&setup);
+        let (width, height) = setup.page_dimensions_mm();
+        assert_eq!(glyphs.iter().map(|g| g.letter).collect::<String>(), "CONFIDENTIAL");
+        assert!(glyphs.iter().all(|g| (0.0..width).contains(&g.x_mm) && (0.0..height).contains(&g.y_mm)));
+        assert!(glyphs.windows(2).all(|pair| pair[1].x_mm > pair[0].x_mm && pair[1].y_mm < pair[0].y_mm));
+        assert_eq!(watermark.blended_color(), (191, 191, 241));
+
+        // Stamping every page leaves the pagination alone
+        let engine = ExportEngine::new();
+        let content: String = (1..=80).map(|i| format!("Step {i}: check the clapper and the rope guide.\n\n")).collect();
+        let fonts = PdfFonts::load(&FontConfig::default()).unwrap();
+        let stamped = engine.render_pdf(&content, "en", &config, &fonts, &mut 0).unwrap();
+        let plain_config = ExportConfig { watermark: None, ..config.clone() };
+        let plain = engine.render_pdf(&content, "en", &plain_config, &fonts, &mut 0).unwrap();
+        assert!(page_count(&plain) > 1);
+        assert_eq!(page_count(&stamped), page_count(&plain));
+
+        // Each page of the export has the letters drawn in the watermark color
+        let color = watermark.blended_color();
+        assert_eq!(text_drawn_in(&stamped, color), vec![glyphs.len(); page_count(&stamped)]);
+        assert!(text_drawn_in(&plain, color).iter().all(|&drawn| drawn == 0));
+    }
+
+    #[test]
+    fn test_approved_document_is_not_auto_stamped() {
+        let config = |review_status| ExportConfig {
+            watermark: Some(Watermark::draft()),
+            review_status,
+            ..ExportConfig::default()
+        };
+
+        assert_eq!(config(Some(ReviewStatus::Approved)).active_watermark(), None);
+        assert_eq!(config(Some(ReviewStatus::ChangesRequested)).active_watermark(), Some(&Watermark::draft()));
+        assert_eq!(config(None).active_watermark().map(|w| w.text.as_str()), Some("DRAFT"));
+
+        // A watermark that isn't tied to the review goes on regardless
+        let confidential = ExportConfig {
+            watermark: Some(Watermark::new("CONFIDENTIAL")),
+            review_status: Some(ReviewStatus::Approved),
+            ..ExportConfig::default()
+        };
+        assert!(confidential.active_watermark().is_some());
+    }
+}
@@ -2,10 +2,14 @@
 //! 
 //! Standardizes commit messages for translation workflows with proper metadata.
 
+use crate::{Result, SectionType};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::Utc;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 /// Builder for standardized commit messages
 #[derive(Debug, Default)]
@@ -540,6 +544,131 @@ impl CommitTemplates {
     }
 }
 
+/// Message used for section changes when no configured template applies
+const DEFAULT_SECTION_TEMPLATE: &str = "docs({language}): update {section_title}";
+
+/// What a section commit template can refer to, as `{section_title}`,
+/// `{section_type}`, `{chapter}`, `{language}`, `{user}` and `{review_id}`
+#[derive(Debug, Clone, Default)]
+pub struct SectionCommitContext {
+    pub section_title: String,
+    pub chapter: String,
+    pub language: String,
+    pub user: String,
+    pub review_id: Option<String>,
+}
+
+/// A commit message template for changes to sections of one type, in one
+/// language
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionCommitTemplate {
+    /// Applies to sections of any type when absent
+    #[serde(default)]
+    pub section_type: Option<SectionType>,
+    /// Applies in any language when absent
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Subject line, optionally followed by a blank line and a body
+    pub template: String,
+}
+
+/// On-disk representation of `commit_templates.toml`
+#[derive(Debug, Default, Deserialize)]
+struct CommitTemplatesFile {
+    #[serde(default)]
+    templates: Vec<SectionCommitTemplate>,
+}
+
+/// Commit message templates chosen by the type of the changed section and
+/// the language it is written in
+#[derive(Debug, Clone, Default)]
+pub struct SectionCommitTemplates {
+    templates: Vec<SectionCommitTemplate>,
+}
+
+impl SectionCommitTemplates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load templates from the config directory; no file means every
+    /// section change gets the default message
+    pub fn load() -> Result<Self> {
+        match Self::config_path() {
+            Some(path) if path.exists() => Self::load_from_path(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Location of the commit templates file
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tradocflow").join("commit_templates.toml"))
+    }
+
+    pub fn load_from_path(path: &Path) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse `[[templates]]` entries, each with a `template` and optionally
+    /// the `section_type` and `language` it is for
+    pub fn parse(content: &str) -> Result<Self> {
+        let file: CommitTemplatesFile = toml::from_str(content)?;
+        let mut templates = Self::default();
+        for template in file.templates {
+            templates.register(template);
+        }
+        Ok(templates)
+    }
+
+    /// Add a template, replacing one for the same section type and language
+    pub fn register(&mut self, template: SectionCommitTemplate) {
+        self.templates
+            .retain(|t| t.section_type != template.section_type || t.language != template.language);
+        self.templates.push(template);
+    }
+
+    /// The most specific template for `section_type` in `language`: one for
+    /// both, then one for the section type, then one for the language
+    fn select(&self, section_type: &SectionType, language: &str) -> &str {
+        let find = |section: Option<&SectionType>, language: Option<&str>| {
+            self.templates
+                .iter()
+                .find(|t| t.section_type.as_ref() == section && t.language.as_deref() == language)
+        };
+        find(Some(section_type), Some(language))
+            .or_else(|| find(Some(section_type), None))
+            .or_else(|| find(None, Some(language)))
+            .or_else(|| find(None, None))
+            .map_or(DEFAULT_SECTION_TEMPLATE, |t| t.template.as_str())
+    }
+
+    /// Commit message for a change to a section of `section_type`.
+    /// Placeholders without a value, or unknown ones, render empty.
+    pub fn message(&self, section_type: &SectionType, context: &SectionCommitContext) -> String {
+        static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+        let placeholder = PLACEHOLDER.get_or_init(|| Regex::new(r"\{(\w+)\}").expect("valid placeholder regex"));
+
+        let section_type_name = match section_type {
+            SectionType::Custom(name) => name.clone(),
+            builtin => format!("{builtin:?}"),
+        };
+        placeholder
+            .replace_all(self.select(section_type, &context.language), |captures: &regex::Captures| {
+                match &captures[1] {
+                    "section_title" => context.section_title.as_str(),
+                    "section_type" => section_type_name.as_str(),
+                    "chapter" => context.chapter.as_str(),
+                    "language" => context.language.as_str(),
+                    "user" => context.user.as_str(),
+                    "review_id" => context.review_id.as_deref().unwrap_or(""),
+                    _ => "",
+                }
+                .to_string()
+            })
+            .into_owned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -596,4 +725,50 @@ mod tests {
         assert!(message.contains("Translator: translator.user"));
         assert!(message.contains(&session_id.to_string()));
     }
+
+    const COMMIT_TEMPLATES: &str = r#"
+[[templates]]
+section_type = "Troubleshooting"
+language = "de"
+template = """
+docs({language}): Fehlerbehebung in „{section_title}“ überarbeitet
+
+Review: {review_id}{ticket}"""
+
+[[templates]]
+section_type = "Troubleshooting"
+template = "docs({language}): revise troubleshooting in {section_title}"
+
+[[templates]]
+section_type = "Appendix"
+template = "docs({language}): update appendix {section_title}"
+"#;
+
+    #[test]
+    fn test_section_commit_template_by_type_and_language() {
+        let templates = SectionCommitTemplates::parse(COMMIT_TEMPLATES).unwrap();
+        let context = |language: &str| SectionCommitContext {
+            section_title: "Störungen beheben".to_string(),
+            language: language.to_string(),
+            ..Default::default()
+        };
+
+        // No review ID and an unknown placeholder both render empty
+        assert_eq!(
+            templates.message(&SectionType::Troubleshooting, &context("de")),
+            "docs(de): Fehlerbehebung in „Störungen beheben“ überarbeitet\n\nReview: "
+        );
+        assert_eq!(
+            templates.message(&SectionType::Troubleshooting, &context("fr")),
+            "docs(fr): revise troubleshooting in Störungen beheben"
+        );
+        assert_eq!(
+            templates.message(&SectionType::Appendix, &context("de")),
+            "docs(de): update appendix Störungen beheben"
+        );
+        assert_eq!(
+            templates.message(&SectionType::Installation, &context("de")),
+            "docs(de): update Störungen beheben"
+        );
+    }
 }
\ No newline at end of file
@@ -0,0 +1,321 @@
+//! Markdown Merge Conflict Resolution
+//!
+//! Parses the conflict markers Git leaves in a markdown file after merging
+//! language branches, resolves conflicts whose two sides changed different
+//! words, and leaves the rest for a person to resolve.
+
+use crate::{Result, TradocumentError};
+use serde::{Deserialize, Serialize};
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// One conflicted region of a file. Text keeps its line endings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictHunk {
+    /// Line of the opening marker, counting from 1
+    pub line: usize,
+    pub ours_label: String,
+    pub theirs_label: String,
+    pub ours: String,
+    /// The common ancestor, present when Git wrote diff3-style markers
+    pub base: Option<String>,
+    pub base_label: String,
+    pub theirs: String,
+}
+
+impl ConflictHunk {
+    /// The hunk with its markers, as Git wrote it
+    fn to_markers(&self) -> String {
+        let mut text = format!("{OURS_MARKER} {}\n{}", self.ours_label, self.ours);
+        if let Some(base) = &self.base {
+            text.push_str(&format!("{BASE_MARKER} {}\n{base}", self.base_label));
+        }
+        text.push_str(&format!("{SEPARATOR}\n{}{THEIRS_MARKER} {}\n", self.theirs, self.theirs_label));
+        text
+    }
+
+    /// The merged text, if both sides changed it independently. Without a
+    /// base there is no telling what each side changed, so only identical
+    /// or one-sided changes resolve.
+    pub fn auto_resolve(&self) -> Option<String> {
+        if self.ours == self.theirs {
+            return Some(self.ours.clone());
+        }
+        let base = self.base.as_deref()?;
+        if base == self.ours {
+            return Some(self.theirs.clone());
+        }
+        if base == self.theirs {
+            return Some(self.ours.clone());
+        }
+        merge_words(base, &self.ours, &self.theirs)
+    }
+}
+
+/// Part of a file with conflict markers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarkdownSegment {
+    Clean(String),
+    Conflict(ConflictHunk),
+}
+
+/// A markdown file after automatic conflict resolution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkdownMergeResult {
+    /// The file with resolved conflicts merged in and markers left around
+    /// the others
+    pub content: String,
+    pub auto_resolved: usize,
+    /// Conflicts that need a person to resolve them
+    pub manual_conflicts: Vec<ConflictHunk>,
+}
+
+impl MarkdownMergeResult {
+    pub fn is_resolved(&self) -> bool {
+        self.manual_conflicts.is_empty()
+    }
+}
+
+/// Split a file into clean text and conflicts. Fails on markers that don't
+/// form a complete conflict.
+pub fn parse_conflicts(text: &str) -> Result<Vec<MarkdownSegment>> {
+    enum Side {
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let malformed =
+        |line: usize, what: &str| TradocumentError::Validation(format!("Malformed conflict marker at line {line}: {what}"));
+
+    let mut segments = Vec::new();
+    let mut clean = String::new();
+    let mut hunk: Option<(ConflictHunk, Side)> = None;
+
+    for (line, raw) in (1..).zip(text.split_inclusive('\n')) {
+        let content = raw.trim_end_matches(['\n', '\r']);
+        let label = |marker: &str| content[marker.len()..].trim().to_string();
+
+        match &mut hunk {
+            None if content.starts_with(OURS_MARKER) => {
+                if !clean.is_empty() {
+                    segments.push(MarkdownSegment::Clean(std::mem::take(&mut clean)));
+                }
+                let conflict = ConflictHunk {
+                    line,
+                    ours_label: label(OURS_MARKER),
+                    theirs_label: String::new(),
+                    ours: String::new(),
+                    base: None,
+                    base_label: String::new(),
+                    theirs: String::new(),
+                };
+                hunk = Some((conflict, Side::Ours));
+            }
+            None if [BASE_MARKER, SEPARATOR, THEIRS_MARKER].iter().any(|m| content.starts_with(m)) => {
+                return Err(malformed(line, "marker outside a conflict"));
+            }
+            None => clean.push_str(raw),
+            Some((conflict, side)) => match side {
+                Side::Ours | Side::Base if content.starts_with(SEPARATOR) => *side = Side::Theirs,
+                Side::Ours if content.starts_with(BASE_MARKER) => {
+                    conflict.base = Some(String::new());
+                    conflict.base_label = label(BASE_MARKER);
+                    *side = Side::Base;
+                }
+                Side::Theirs if content.starts_with(THEIRS_MARKER) => {
+                    conflict.theirs_label = label(THEIRS_MARKER);
+                    if let Some((conflict, _)) = hunk.take() {
+                        segments.push(MarkdownSegment::Conflict(conflict));
+                    }
+                }
+                _ if [OURS_MARKER, BASE_MARKER, SEPARATOR, THEIRS_MARKER].iter().any(|m| content.starts_with(m)) => {
+                    return Err(malformed(line, "marker out of order"));
+                }
+                Side::Ours => conflict.ours.push_str(raw),
+                Side::Base => conflict.base.get_or_insert_with(String::new).push_str(raw),
+                Side::Theirs => conflict.theirs.push_str(raw),
+            },
+        }
+    }
+
+    if let Some((conflict, _)) = hunk {
+        return Err(malformed(conflict.line, "conflict is never closed"));
+    }
+    if !clean.is_empty() {
+        segments.push(MarkdownSegment::Clean(clean));
+    }
+    Ok(segments)
+}
+
+/// Resolve the conflicts of a file that both sides changed independently
+pub fn resolve_markdown_conflicts(text: &str) -> Result<MarkdownMergeResult> {
+    let mut result = MarkdownMergeResult { content: String::new(), auto_resolved: 0, manual_conflicts: Vec::new() };
+    for segment in parse_conflicts(text)? {
+        match segment {
+            MarkdownSegment::Clean(text) => result.content.push_str(&text),
+            MarkdownSegment::Conflict(conflict) => match conflict.auto_resolve() {
+                Some(merged) => {
+                    result.content.push_str(&merged);
+                    result.auto_resolved += 1;
+                }
+                None => {
+                    result.content.push_str(&conflict.to_markers());
+                    result.manual_conflicts.push(conflict);
+                }
+            },
+        }
+    }
+    Ok(result)
+}
+
+/// Words and the whitespace between them, so that joining them gives back
+/// the text
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut in_space = None;
+    for (i, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if in_space.is_some_and(|s| s != space) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        in_space = Some(space);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// A replacement of `base[start..end]`
+#[derive(Debug, PartialEq)]
+struct Edit<'a> {
+    start: usize,
+    end: usize,
+    replacement: Vec<&'a str>,
+}
+
+/// The edits turning `base` into `changed`, in order, from their longest
+/// common subsequence
+fn edits<'a>(base: &[&str], changed: &[&'a str]) -> Vec<Edit<'a>> {
+    // common[i][j]: length of the longest common subsequence of base[i..] and changed[j..]
+    let mut common = vec![vec![0usize; changed.len() + 1]; base.len() + 1];
+    for i in (0..base.len()).rev() {
+        for j in (0..changed.len()).rev() {
+            common[i][j] = if base[i] == changed[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut pending: Option<Edit> = None;
+    while i < base.len() || j < changed.len() {
+        if i < base.len() && j < changed.len() && base[i] == changed[j] {
+            edits.extend(pending.take());
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let edit = pending.get_or_insert(Edit { start: i, end: i, replacement: Vec::new() });
+        if j < changed.len() && (i == base.len() || common[i][j + 1] >= common[i + 1][j]) {
+            edit.replacement.push(changed[j]);
+            j += 1;
+        } else {
+            edit.end += 1;
+            i += 1;
+        }
+    }
+    edits.extend(pending);
+    edits
+}
+
+/// Three-way merge of word-level changes. Gives up when the two sides
+/// touch the same or neighbouring words, unless they made the same change,
+/// so no edit of either side is lost.
+fn merge_words(base: &str, ours: &str, theirs: &str) -> Option<String> {
+    let base_tokens = tokens(base);
+    let mut all_edits = edits(&base_tokens, &tokens(ours));
+    for theirs_edit in edits(&base_tokens, &tokens(theirs)) {
+        if all_edits.contains(&theirs_edit) {
+            continue;
+        }
+        let clashes = all_edits.iter().any(|e| e.start <= theirs_edit.end && theirs_edit.start <= e.end);
+        if clashes {
+            return None;
+        }
+        all_edits.push(theirs_edit);
+    }
+    all_edits.sort_by_key(|e| e.start);
+
+    let mut merged = String::new();
+    let mut next = 0;
+    for edit in &all_edits {
+        merged.push_str(&base_tokens[next..edit.start].concat());
+        merged.push_str(&edit.replacement.concat());
+        next = edit.end;
+    }
+    merged.push_str(&base_tokens[next..].concat());
+    Some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conflicted(ours: &str, base: &str, theirs: &str) -> String {
+        format!(
+            "# Wartung\n\n<<<<<<< HEAD\n{ours}\n||||||| base\n{base}\n=======\n{theirs}\n>>>>>>> translate/de/bob\n\nEnde.\n"
+        )
+    }
+
+    #[test]
+    fn test_independent_edits_in_a_paragraph_are_merged() {
+        let text = conflicted(
+            "Prüfen Sie die Glocke monatlich. Ölen Sie das Lager jährlich.",
+            "Prüfen Sie die Glocke wöchentlich. Ölen Sie das Lager jährlich.",
+            "Prüfen Sie die Glocke wöchentlich. Schmieren Sie das Lager jährlich.",
+        );
+        let result = resolve_markdown_conflicts(&text).unwrap();
+
+        assert!(result.is_resolved());
+        assert_eq!(result.auto_resolved, 1);
+        assert_eq!(
+            result.content,
+            "# Wartung\n\nPrüfen Sie die Glocke monatlich. Schmieren Sie das Lager jährlich.\n\nEnde.\n"
+        );
+    }
+
+    #[test]
+    fn test_overlapping_edits_are_left_for_manual_resolution() {
+        let text = conflicted(
+            "Prüfen Sie die Glocke monatlich.",
+            "Prüfen Sie die Glocke wöchentlich.",
+            "Prüfen Sie die Glocke täglich.",
+        );
+        let result = resolve_markdown_conflicts(&text).unwrap();
+
+        assert_eq!(result.auto_resolved, 0);
+        assert_eq!(result.manual_conflicts.len(), 1);
+        let conflict = &result.manual_conflicts[0];
+        assert_eq!(conflict.line, 3);
+        assert_eq!(conflict.ours, "Prüfen Sie die Glocke monatlich.\n");
+        assert_eq!(conflict.theirs, "Prüfen Sie die Glocke täglich.\n");
+        assert_eq!(conflict.theirs_label, "translate/de/bob");
+        // The markers stay in place, with both versions intact
+        assert_eq!(result.content, text);
+
+        // Without a base, differing sides can't be told apart
+        let two_way = "<<<<<<< HEAD\nA b c.\n=======\nA b d.\n>>>>>>> other\n";
+        assert_eq!(resolve_markdown_conflicts(two_way).unwrap().manual_conflicts.len(), 1);
+        assert!(parse_conflicts("<<<<<<< HEAD\nA\n=======\n").is_err());
+    }
+}
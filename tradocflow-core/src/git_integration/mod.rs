@@ -13,10 +13,13 @@ pub mod kanban_sync;
 pub mod toml_data;
 pub mod toml_io;
 pub mod diff_tools;
+pub mod merge_conflicts;
 #[cfg(test)]
 pub mod toml_tests;
 
-pub use workflow_manager::{GitWorkflowManager, TranslationBranchInfo};
+pub use workflow_manager::{
+    GitWorkflowManager, TranslationBranchInfo, IntegrityIssue, IntegrityIssueKind, IntegrityReport, ReleaseTag,
+};
 pub use models::{
     WorkSession, ReviewRequest, TranslationDiff, TranslationChange, 
     ChangeType, ProjectData, ProjectMetadata, ChapterData, ChapterMetadata,
@@ -40,11 +43,17 @@ pub use toml_io::{
     TomlFileManager, ValidationReport, ProjectStatistics, utils as toml_utils,
 };
 pub use session_manager::{
-    SessionManager, SessionInfo, EnhancedSessionInfo, ActiveSession,
+    SessionManager, SessionStore, LockAttempt, LockGuard, LockRecord, SessionInfo, EnhancedSessionInfo, ActiveSession,
     SessionManagerConfig, SessionUpdateResult, SessionSaveResult,
     ProductivityMetrics, SessionRecoveryInfo, SessionMetadata, EditorPreferences,
 };
-pub use commit_builder::{CommitMessageBuilder, CommitTemplates, CommitType};
+pub use merge_conflicts::{
+    parse_conflicts, resolve_markdown_conflicts, ConflictHunk, MarkdownMergeResult, MarkdownSegment,
+};
+pub use commit_builder::{
+    CommitMessageBuilder, CommitTemplates, CommitType, SectionCommitContext, SectionCommitTemplate,
+    SectionCommitTemplates,
+};
 pub use task_manager::{
     TaskManager, CreateTodoRequest, UpdateTodoRequest, CreateCommentRequest,
     CreateCommentReplyRequest, TaskFilter, TaskNotification, TaskEventType
@@ -147,6 +156,10 @@ exports/
 *.tmp
 *.log
 
+# Who has which file open or locked, kept per checkout
+.tradocflow/sessions/
+.tradocflow/locks/
+
 # IDE and editor files
 .vscode/
 .idea/
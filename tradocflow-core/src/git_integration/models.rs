@@ -63,6 +63,28 @@ pub struct ReviewRequest {
     pub status: ReviewStatus,
     pub created_at: DateTime<Utc>,
     pub changes_summary: String,
+    /// When the request entered its current status, if it has left the one
+    /// it was created in
+    #[serde(default)]
+    pub status_changed_at: Option<DateTime<Utc>>,
+}
+
+impl ReviewRequest {
+    /// Move the request to `status`, which it entered at `at`
+    pub fn set_status(&mut self, status: ReviewStatus, at: DateTime<Utc>) {
+        self.status = status;
+        self.status_changed_at = Some(at);
+    }
+
+    /// When the request entered its current status
+    pub fn status_since(&self) -> DateTime<Utc> {
+        self.status_changed_at.unwrap_or(self.created_at)
+    }
+
+    /// Whether the request still awaits a decision
+    pub fn is_open(&self) -> bool {
+        matches!(self.status, ReviewStatus::Pending | ReviewStatus::InReview | ReviewStatus::ChangesRequested)
+    }
 }
 
 /// Status of a review request
@@ -567,6 +589,10 @@ pub mod toml_integration {
 *.tmp
 *.backup.*
 
+# Who has which file open or locked, kept per checkout
+/.tradocflow/sessions/
+/.tradocflow/locks/
+
 # IDE and editor files
 .vscode/
 .idea/
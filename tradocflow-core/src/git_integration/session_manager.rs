@@ -6,13 +6,310 @@ use super::{WorkSession, GitWorkflowManager, ChapterData};
 use crate::{Result, TradocumentError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::{RwLock, Mutex};
 use tokio::time::{interval, Duration};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+/// Directory, relative to the repository root, holding a record of each
+/// open session so others working in the same checkout can see it
+const SESSIONS_DIR: &str = ".tradocflow/sessions";
+
+/// Directory, relative to the repository root, holding a record of each
+/// file locked for editing
+const LOCKS_DIR: &str = ".tradocflow/locks";
+
+/// How long after its last save a session still counts as editing, unless
+/// configured otherwise
+const DEFAULT_PRESENCE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// How long a lock lasts without being renewed by a save, after which it is
+/// assumed abandoned and anyone may take it over, unless configured otherwise
+const DEFAULT_LOCK_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// How long a lock record that can't be read counts as held, as one an
+/// older client is still writing, before it is taken for the remains of a
+/// crash
+const LOCK_WRITE_GRACE: Duration = Duration::from_secs(10);
+
+/// Whether `timestamp` lies within `ttl` of now. A timestamp in the future
+/// is a clock difference, not staleness.
+fn is_fresh(timestamp: DateTime<Utc>, ttl: Duration) -> bool {
+    match Utc::now().signed_duration_since(timestamp).to_std() {
+        Ok(age) => age <= ttl,
+        Err(_) => true,
+    }
+}
+
+/// Whether the file at `path` was modified within `grace` of now. A time in
+/// the future counts as recent, as in [`is_fresh`].
+fn modified_within(path: &Path, grace: Duration) -> bool {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| modified.elapsed().map_or(true, |age| age <= grace))
+        .unwrap_or(false)
+}
+
+/// Who holds the edit lock on a file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockRecord {
+    /// Locked file, relative to the repository
+    pub file: String,
+    pub user_id: String,
+    pub session_id: Uuid,
+    /// When the lock was taken or last renewed
+    pub acquired_at: DateTime<Utc>,
+}
+
+/// Outcome of trying to lock a file
+#[derive(Debug)]
+pub enum LockAttempt {
+    Acquired(LockGuard),
+    /// A lock that hasn't expired is held already, possibly by the same
+    /// session through another guard
+    Held(LockRecord),
+    /// Another client is writing the lock on this file or taking it over,
+    /// so it counts as held by someone not yet known
+    Busy(String),
+}
+
+/// An edit lock on a file, released when dropped
+#[derive(Debug)]
+pub struct LockGuard {
+    path: PathBuf,
+    record: LockRecord,
+}
+
+impl LockGuard {
+    pub fn record(&self) -> &LockRecord {
+        &self.record
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        // Once expired, the lock may have been taken over by someone else
+        // whose record must stay
+        let still_ours = std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<LockRecord>(&json).ok())
+            .is_some_and(|record| record.session_id == self.record.session_id);
+        if still_ours {
+            if let Err(e) = std::fs::remove_file(&self.path) {
+                eprintln!("Failed to release lock on {}: {e}", self.record.file);
+            }
+        }
+    }
+}
+
+/// Open sessions persisted in the repository, showing who is editing which
+/// file, along with the opt-in edit locks they hold. Locks are advisory:
+/// they are honoured by clients that ask for them, not by the file system.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    repo_path: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new(repo_path: &Path) -> Self {
+        Self { repo_path: repo_path.to_path_buf() }
+    }
+
+    fn record_path(&self, session_id: Uuid) -> PathBuf {
+        self.repo_path.join(SESSIONS_DIR).join(format!("{session_id}.json"))
+    }
+
+    /// Record `session` as open, replacing any earlier record of it
+    pub fn save(&self, session: &WorkSession) -> Result<()> {
+        let path = self.record_path(session.id);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(session)?)?;
+        Ok(())
+    }
+
+    fn lock_path(&self, file: &str) -> PathBuf {
+        let name = file.replace('%', "%25").replace(['/', '\\'], "%2F");
+        self.repo_path.join(LOCKS_DIR).join(format!("{name}.json"))
+    }
+
+    fn relative<'a>(&self, file: &'a Path) -> &'a Path {
+        file.strip_prefix(&self.repo_path).unwrap_or(file)
+    }
+
+    fn read_lock(path: &Path) -> Option<LockRecord> {
+        serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+    }
+
+    /// Write `record` in full to a new file beside the lock at `path`, for
+    /// linking or renaming into place
+    fn write_pending(path: &Path, record: &LockRecord) -> Result<PathBuf> {
+        let pending = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        std::fs::write(&pending, serde_json::to_string_pretty(record)?)?;
+        Ok(pending)
+    }
+
+    /// Lock `file` for `session_id`, unless another session holds a lock on
+    /// it taken or renewed within `ttl`. An expired lock, such as one left
+    /// behind by a crashed client, is taken over.
+    pub fn try_lock(&self, file: &Path, user_id: &str, session_id: Uuid, ttl: Duration) -> Result<LockAttempt> {
+        let file = self.relative(file).to_string_lossy().into_owned();
+        let path = self.lock_path(&file);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let record = LockRecord { file, user_id: user_id.to_string(), session_id, acquired_at: Utc::now() };
+
+        // The record is complete before it is linked into place, so others
+        // never read part of it, and linking fails if a lock exists, which
+        // decides races between clients
+        let pending = Self::write_pending(&path, &record)?;
+        let attempt = Self::link_lock(path, &pending, record, ttl);
+        match std::fs::remove_file(&pending) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => eprintln!("Failed to remove {}: {e}", pending.display()),
+            _ => {}
+        }
+        attempt
+    }
+
+    fn link_lock(path: PathBuf, pending: &Path, record: LockRecord, ttl: Duration) -> Result<LockAttempt> {
+        // One retry, after clearing an expired lock out of the way
+        for _ in 0..2 {
+            match std::fs::hard_link(pending, &path) {
+                Ok(()) => return Ok(LockAttempt::Acquired(LockGuard { path, record })),
+                Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e.into()),
+                Err(_) => {}
+            }
+
+            // Released since, so the link can be tried again
+            let Ok(judged) = std::fs::read(&path) else {
+                continue;
+            };
+            match serde_json::from_slice::<LockRecord>(&judged) {
+                Ok(holder) if is_fresh(holder.acquired_at, ttl) => return Ok(LockAttempt::Held(holder)),
+                Err(_) if modified_within(&path, LOCK_WRITE_GRACE) => return Ok(LockAttempt::Busy(record.file)),
+                // Expired, or the remains of a crash mid-write
+                _ => {
+                    if !Self::clear_stale(&path, pending, &judged)? {
+                        return Ok(LockAttempt::Busy(record.file));
+                    }
+                }
+            }
+        }
+
+        match Self::read_lock(&path) {
+            Some(holder) => Ok(LockAttempt::Held(holder)),
+            None => Err(TradocumentError::ApiError(format!("Could not lock {}", record.file))),
+        }
+    }
+
+    /// Remove the lock at `path`, found stale with the contents `judged`.
+    /// Clients clearing the same lock at once could remove one that another
+    /// took over in the meantime, so only the client that links `pending`
+    /// as the takeover marker beside it may, and only while it is still the
+    /// lock judged stale. Returns `false` when another client is taking the
+    /// lock over.
+    fn clear_stale(path: &Path, pending: &Path, judged: &[u8]) -> Result<bool> {
+        let takeover = path.with_extension("takeover");
+        match std::fs::hard_link(pending, &takeover) {
+            Ok(()) => {}
+            Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e.into()),
+            Err(_) => {
+                // Left behind by a client that crashed while taking over
+                if !modified_within(&takeover, LOCK_WRITE_GRACE) {
+                    match std::fs::remove_file(&takeover) {
+                        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                        _ => {}
+                    }
+                }
+                return Ok(false);
+            }
+        }
+
+        let cleared = match std::fs::read(path) {
+            Ok(current) if current == judged => std::fs::remove_file(path).map_err(Into::into),
+            // Released, or renewed by its holder, since it was read
+            _ => Ok(()),
+        };
+        std::fs::remove_file(&takeover)?;
+        cleared.map(|()| true)
+    }
+
+    /// Renew the locks held by a session, so they don't expire while it is
+    /// still working
+    pub fn renew_locks(&self, session_id: Uuid) -> Result<()> {
+        for (path, mut record) in self.locks_of(session_id) {
+            record.acquired_at = Utc::now();
+            // Replaced whole, so the record is never read half written
+            std::fs::rename(Self::write_pending(&path, &record)?, path)?;
+        }
+        Ok(())
+    }
+
+    /// Release every lock held by a session
+    pub fn release_locks(&self, session_id: Uuid) -> Result<()> {
+        for (path, _) in self.locks_of(session_id) {
+            match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn locks_of(&self, session_id: Uuid) -> Vec<(PathBuf, LockRecord)> {
+        let Ok(entries) = std::fs::read_dir(self.repo_path.join(LOCKS_DIR)) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            // Records not yet or no longer in place are no locks
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .filter_map(|path| Some((path.clone(), Self::read_lock(&path)?)))
+            .filter(|(_, record)| record.session_id == session_id)
+            .collect()
+    }
+
+    /// Forget a session; forgetting one that isn't recorded does nothing
+    pub fn remove(&self, session_id: Uuid) -> Result<()> {
+        match std::fs::remove_file(self.record_path(session_id)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Every recorded session, however old. Unreadable records are skipped.
+    pub fn sessions(&self) -> Vec<WorkSession> {
+        let Ok(entries) = std::fs::read_dir(self.repo_path.join(SESSIONS_DIR)) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+            .filter_map(|json| serde_json::from_str::<WorkSession>(&json).ok())
+            .collect()
+    }
+
+    /// Sessions editing `file` that were started or last saved within
+    /// `ttl`, oldest first. `file` may be absolute or relative to the
+    /// repository.
+    pub fn active_sessions_for(&self, file: &Path, ttl: Duration) -> Vec<WorkSession> {
+        let file = self.relative(file);
+        let mut sessions: Vec<WorkSession> = self
+            .sessions()
+            .into_iter()
+            .filter(|session| Path::new(&session.markdown_path) == file)
+            .filter(|session| is_fresh(session.last_save.unwrap_or(session.started_at), ttl))
+            .collect();
+        sessions.sort_by_key(|session| session.started_at);
+        sessions
+    }
+}
+
 /// Additional session metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionMetadata {
@@ -40,6 +337,9 @@ pub struct SessionManager {
     active_sessions: Arc<RwLock<HashMap<Uuid, Arc<Mutex<ActiveSession>>>>>,
     auto_save_enabled: bool,
     auto_save_interval: Duration,
+    store: SessionStore,
+    presence_ttl: Duration,
+    lock_ttl: Duration,
 }
 
 /// Active session with enhanced state tracking
@@ -67,13 +367,30 @@ impl SessionManager {
         auto_save_interval_seconds: u64,
     ) -> Self {
         Self {
+            store: SessionStore::new(git_manager.repo_path()),
             git_manager,
             active_sessions: Arc::new(RwLock::new(HashMap::new())),
             auto_save_enabled: true,
             auto_save_interval: Duration::from_secs(auto_save_interval_seconds),
+            presence_ttl: DEFAULT_PRESENCE_TTL,
+            lock_ttl: DEFAULT_LOCK_TTL,
         }
     }
 
+    /// Count sessions as editing for `ttl` after their last save, instead
+    /// of 30 minutes
+    pub fn with_presence_ttl(mut self, ttl: Duration) -> Self {
+        self.presence_ttl = ttl;
+        self
+    }
+
+    /// Let locks expire `ttl` after they were taken or last renewed by a
+    /// save, instead of 30 minutes
+    pub fn with_lock_ttl(mut self, ttl: Duration) -> Self {
+        self.lock_ttl = ttl;
+        self
+    }
+
     /// Clone for shared access - shares the same active sessions and git manager
     pub fn clone_for_shared_access(&self) -> Self {
         Self {
@@ -81,6 +398,9 @@ impl SessionManager {
             active_sessions: Arc::clone(&self.active_sessions),
             auto_save_enabled: self.auto_save_enabled,
             auto_save_interval: self.auto_save_interval,
+            store: self.store.clone(),
+            presence_ttl: self.presence_ttl,
+            lock_ttl: self.lock_ttl,
         }
     }
 
@@ -120,6 +440,9 @@ impl SessionManager {
         // Store active session
         let mut sessions = self.active_sessions.write().await;
         sessions.insert(work_session.id, Arc::new(Mutex::new(active_session)));
+        if let Err(e) = self.store.save(&work_session) {
+            eprintln!("Failed to record session {} for presence: {e}", work_session.id);
+        }
 
         // Start auto-save task for this session
         if self.auto_save_enabled {
@@ -194,6 +517,13 @@ impl SessionManager {
                 // Mark as saved
                 active_session.last_save_content = active_session.current_content.clone();
                 active_session.has_unsaved_changes = false;
+                active_session.session.last_save = Some(Utc::now());
+                if let Err(e) = self.store.save(&active_session.session) {
+                    eprintln!("Failed to record session {session_id} for presence: {e}");
+                }
+                if let Err(e) = self.store.renew_locks(session_id) {
+                    eprintln!("Failed to renew locks of session {session_id}: {e}");
+                }
             }
         } else {
             return Err(TradocumentError::ApiError(
@@ -245,6 +575,12 @@ impl SessionManager {
         // Remove from active sessions
         let mut sessions = self.active_sessions.write().await;
         sessions.remove(&session_id);
+        if let Err(e) = self.store.remove(session_id) {
+            eprintln!("Failed to clear presence record of session {session_id}: {e}");
+        }
+        if let Err(e) = self.store.release_locks(session_id) {
+            eprintln!("Failed to release locks of session {session_id}: {e}");
+        }
 
         Ok(())
     }
@@ -262,6 +598,34 @@ impl SessionManager {
         Ok(result)
     }
 
+    /// Sessions of anyone working in this checkout that have `file` open,
+    /// leaving out those not saved within the presence TTL. This is for
+    /// showing who else is editing a file and does not lock it.
+    pub fn active_sessions_for(&self, file: &Path) -> Vec<WorkSession> {
+        self.store.active_sessions_for(file, self.presence_ttl)
+    }
+
+    /// Lock `file` for editing in a session, or report who holds the lock.
+    /// The lock lasts until the guard is dropped or the session ends, and
+    /// expires if the session goes without saving for the lock TTL.
+    pub async fn try_acquire(&self, file: &Path, session_id: Uuid) -> Result<LockAttempt> {
+        let user_id = self.get_session_info(session_id).await?.session.user_id;
+        self.store.try_lock(file, &user_id, session_id, self.lock_ttl)
+    }
+
+    /// Lock `file` for editing in a session, failing if the lock is
+    /// already held
+    pub async fn acquire_lock(&self, file: &Path, session_id: Uuid) -> Result<LockGuard> {
+        match self.try_acquire(file, session_id).await? {
+            LockAttempt::Acquired(guard) => Ok(guard),
+            LockAttempt::Held(holder) => Err(TradocumentError::ApiError(format!(
+                "{} is locked by {} since {}",
+                holder.file, holder.user_id, holder.acquired_at
+            ))),
+            LockAttempt::Busy(file) => Err(TradocumentError::ApiError(format!("{file} is being locked by someone else"))),
+        }
+    }
+
     /// Get session info including unsaved changes status
     pub async fn get_session_info(&self, session_id: Uuid) -> Result<SessionInfo> {
         let sessions = self.active_sessions.read().await;
@@ -611,4 +975,190 @@ impl From<EnhancedSessionInfo> for SessionInfo {
             toml_loaded: enhanced.toml_loaded,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn session(user_id: &str, language: &str, started_at: DateTime<Utc>, last_save: Option<DateTime<Utc>>) -> WorkSession {
+        WorkSession {
+            id: Uuid::new_v4(),
+            branch: format!("translate/intro/{language}/{user_id}"),
+            chapter: "intro".to_string(),
+            language: language.to_string(),
+            user_id: user_id.to_string(),
+            markdown_path: format!("generated/markdown/{language}/intro.md"),
+            started_at,
+            last_save,
+            auto_save_enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_recent_sessions_are_reported_and_stale_ones_filtered() {
+        let repo = TempDir::new().unwrap();
+        let store = SessionStore::new(repo.path());
+        let now = Utc::now();
+        let hours_ago = |hours| now - chrono::Duration::hours(hours);
+
+        // Started long ago but saved recently
+        let alice = session("alice", "de", hours_ago(2), Some(now - chrono::Duration::minutes(5)));
+        // Never saved since starting hours ago
+        let bob = session("bob", "de", hours_ago(3), None);
+        let carol = session("carol", "fr", now, None);
+        for session in [&alice, &bob, &carol] {
+            store.save(session).unwrap();
+        }
+
+        let ttl = Duration::from_secs(30 * 60);
+        let editing = store.active_sessions_for(Path::new("generated/markdown/de/intro.md"), ttl);
+        assert_eq!(editing.iter().map(|s| s.user_id.as_str()).collect::<Vec<_>>(), ["alice"]);
+        assert_eq!(editing[0].language, "de");
+
+        // Absolute paths inside the repository work too, and a longer TTL keeps bob
+        let absolute = repo.path().join("generated/markdown/de/intro.md");
+        let editing = store.active_sessions_for(&absolute, Duration::from_secs(4 * 3600));
+        assert_eq!(editing.iter().map(|s| s.user_id.as_str()).collect::<Vec<_>>(), ["bob", "alice"]);
+
+        store.remove(alice.id).unwrap();
+        store.remove(alice.id).unwrap();
+        assert!(store.active_sessions_for(&absolute, ttl).is_empty());
+    }
+
+    fn lock(store: &SessionStore, file: &Path, user_id: &str, session_id: Uuid) -> LockAttempt {
+        store.try_lock(file, user_id, session_id, Duration::from_secs(30 * 60)).unwrap()
+    }
+
+    #[test]
+    fn test_lock_is_exclusive_until_released() {
+        let repo = TempDir::new().unwrap();
+        let store = SessionStore::new(repo.path());
+        let file = Path::new("generated/markdown/de/intro.md");
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let LockAttempt::Acquired(guard) = lock(&store, file, "alice", alice) else {
+            panic!("free file should lock");
+        };
+        assert_eq!(guard.record().user_id, "alice");
+        // Each lock has a single guard, even within a session
+        assert!(matches!(lock(&store, file, "alice", alice), LockAttempt::Held(_)));
+
+        let LockAttempt::Held(holder) = lock(&store, &repo.path().join(file), "bob", bob) else {
+            panic!("locked file should be reported as held");
+        };
+        assert_eq!((holder.user_id.as_str(), holder.session_id), ("alice", alice));
+        assert_eq!(holder.file, "generated/markdown/de/intro.md");
+
+        // Other files are unaffected
+        assert!(matches!(lock(&store, Path::new("generated/markdown/fr/intro.md"), "bob", bob), LockAttempt::Acquired(_)));
+
+        drop(guard);
+        let LockAttempt::Acquired(guard) = lock(&store, file, "bob", bob) else {
+            panic!("released lock should be free");
+        };
+        store.release_locks(bob).unwrap();
+        assert!(matches!(lock(&store, file, "alice", alice), LockAttempt::Acquired(_)));
+        drop(guard);
+    }
+
+    #[test]
+    fn test_stale_lock_is_reclaimed() {
+        let repo = TempDir::new().unwrap();
+        let store = SessionStore::new(repo.path());
+        let file = Path::new("generated/markdown/de/intro.md");
+
+        // Left behind by a client that died two hours ago
+        let crashed = LockRecord {
+            file: "generated/markdown/de/intro.md".to_string(),
+            user_id: "alice".to_string(),
+            session_id: Uuid::new_v4(),
+            acquired_at: Utc::now() - chrono::Duration::hours(2),
+        };
+        let path = store.lock_path(&crashed.file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, serde_json::to_string(&crashed).unwrap()).unwrap();
+
+        let bob = Uuid::new_v4();
+        let long_ttl = Duration::from_secs(4 * 3600);
+        assert!(matches!(store.try_lock(file, "bob", bob, long_ttl).unwrap(), LockAttempt::Held(_)));
+
+        let LockAttempt::Acquired(guard) = lock(&store, file, "bob", bob) else {
+            panic!("expired lock should be taken over");
+        };
+        assert_eq!(SessionStore::read_lock(&path).unwrap().user_id, "bob");
+        drop(guard);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_one_client_takes_over_a_stale_lock() {
+        let repo = TempDir::new().unwrap();
+        let store = SessionStore::new(repo.path());
+        let file = Path::new("generated/markdown/de/intro.md");
+        let crashed = LockRecord {
+            file: "generated/markdown/de/intro.md".to_string(),
+            user_id: "alice".to_string(),
+            session_id: Uuid::new_v4(),
+            acquired_at: Utc::now() - chrono::Duration::hours(2),
+        };
+        let path = store.lock_path(&crashed.file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        for _ in 0..20 {
+            std::fs::write(&path, serde_json::to_string(&crashed).unwrap()).unwrap();
+            let barrier = std::sync::Barrier::new(8);
+            let attempts: Vec<LockAttempt> = std::thread::scope(|scope| {
+                let clients: Vec<_> = (0..8)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            barrier.wait();
+                            lock(&store, file, "bob", Uuid::new_v4())
+                        })
+                    })
+                    .collect();
+                clients.into_iter().map(|client| client.join().unwrap()).collect()
+            });
+
+            let acquired: Vec<&LockGuard> = attempts
+                .iter()
+                .filter_map(|attempt| match attempt {
+                    LockAttempt::Acquired(guard) => Some(guard),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(acquired.len(), 1);
+            // The others find the new lock, or the takeover under way
+            assert!(attempts.iter().all(|attempt| match attempt {
+                LockAttempt::Held(holder) => holder == acquired[0].record(),
+                _ => true,
+            }));
+            drop(attempts);
+            // Neither pending records nor takeover markers are left behind
+            assert_eq!(std::fs::read_dir(path.parent().unwrap()).unwrap().count(), 0);
+        }
+    }
+
+    #[test]
+    fn test_unreadable_lock_is_held_until_the_grace_period_ends() {
+        let repo = TempDir::new().unwrap();
+        let store = SessionStore::new(repo.path());
+        let file = Path::new("generated/markdown/de/intro.md");
+        let path = store.lock_path("generated/markdown/de/intro.md");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        // Being written by an older client
+        std::fs::write(&path, "").unwrap();
+        assert!(matches!(lock(&store, file, "bob", Uuid::new_v4()), LockAttempt::Busy(_)));
+
+        // Cut short by a crash a minute ago
+        let lock_file = std::fs::File::options().write(true).open(&path).unwrap();
+        lock_file.set_modified(std::time::SystemTime::now() - Duration::from_secs(60)).unwrap();
+        let LockAttempt::Acquired(guard) = lock(&store, file, "bob", Uuid::new_v4()) else {
+            panic!("a record cut short long ago should be taken over");
+        };
+        assert_eq!(SessionStore::read_lock(&path).unwrap().user_id, "bob");
+        drop(guard);
+    }
+}
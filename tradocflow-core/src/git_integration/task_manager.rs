@@ -1519,6 +1519,7 @@ impl TaskManager {
                 title: format!("Task: {}", todo.title),
                 message: notification_content,
                 metadata: crate::NotificationMetadata {
+                    project_id: Some(self.project_id),
                     document_id: None,
                     document_title: None,
                     review_id: None,
@@ -1526,10 +1527,14 @@ impl TaskManager {
                     priority: crate::NotificationPriority::Normal,
                     action_required: false,
                     action_url: None,
+                    ..Default::default()
                 },
                 created_at: Utc::now(),
                 read_at: None,
                 delivered: false,
+                status: crate::DeliveryStatus::Queued,
+                delivered_at: None,
+                escalated_at: None,
             };
             
             // Create temporary user object - TODO: Replace with proper user lookup
@@ -1620,6 +1625,7 @@ impl TaskManager {
                 title: format!("Comment: {action}"),
                 message: notification_content,
                 metadata: crate::NotificationMetadata {
+                    project_id: Some(self.project_id),
                     document_id: None,
                     document_title: None,
                     review_id: None,
@@ -1627,10 +1633,14 @@ impl TaskManager {
                     priority: crate::NotificationPriority::Normal,
                     action_required: false,
                     action_url: None,
+                    ..Default::default()
                 },
                 created_at: Utc::now(),
                 read_at: None,
                 delivered: false,
+                status: crate::DeliveryStatus::Queued,
+                delivered_at: None,
+                escalated_at: None,
             };
             
             // Create temporary user object - TODO: Replace with proper user lookup
@@ -60,19 +60,29 @@
 use super::{
     GitConfig, GitError, WorkSession, ReviewRequest, 
     toml_data::{ChapterData, TranslationUnit, TranslationVersion, TranslationStatus},
+    models::ReviewStatus,
+    session_manager::SessionStore,
     models::toml_integration::GitTomlManager,
-    commit_builder::CommitTemplates,
+    commit_builder::{CommitTemplates, SectionCommitContext, SectionCommitTemplates},
 };
-use crate::{Result, User, TradocumentError};
+use crate::services::atomic_file::write_atomic_blocking;
+use crate::services::authz::{Action, Authorizer, Resource};
+use crate::services::events::{AppEvent, EventBus};
+use crate::services::manual_dir::{document_id, DOCUMENTS_DIR, MANUAL_FILE};
+use crate::{Result, User, TradocumentError, Document, Manual, ManualSection};
 use git2::{Repository, BranchType, Signature, Oid};
 use std::path::{Path, PathBuf};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Directory of the manual (see `services::manual_dir`), relative to the
+/// repository root
+pub const MANUAL_DIR: &str = "content/manual";
+
 /// Thread-safe wrapper around git2::Repository for safe concurrent access
 /// 
 /// This wrapper serializes all git operations through a Mutex since git2::Repository
@@ -190,6 +200,9 @@ pub struct GitWorkflowManager {
     config: GitConfig,
     current_user: User,
     toml_manager: GitTomlManager,
+    authorizer: Authorizer,
+    commit_templates: SectionCommitTemplates,
+    events: Option<EventBus>,
 }
 
 impl GitWorkflowManager {
@@ -210,6 +223,11 @@ impl GitWorkflowManager {
             toml_manager.init_git_toml_structure()
                 .map_err(|e| GitError::InvalidOperation(format!("Failed to initialize TOML structure: {e}")))?;
         }
+
+        let commit_templates = SectionCommitTemplates::load().unwrap_or_else(|e| {
+            log::warn!("Commit templates not loaded, section commits get the default message: {e}");
+            SectionCommitTemplates::default()
+        });
         
         Ok(Self {
             repo,
@@ -218,9 +236,38 @@ impl GitWorkflowManager {
             config,
             current_user,
             toml_manager,
+            authorizer: Authorizer::default(),
+            commit_templates,
+            events: None,
         })
     }
 
+    /// Decide who may merge approved translations with `authorizer` rather
+    /// than the default permissions
+    pub fn with_authorizer(mut self, authorizer: Authorizer) -> Self {
+        self.authorizer = authorizer;
+        self
+    }
+
+    /// Write section commit messages with `templates` rather than the ones
+    /// in the config directory
+    pub fn with_commit_templates(mut self, templates: SectionCommitTemplates) -> Self {
+        self.commit_templates = templates;
+        self
+    }
+
+    /// Publish an [`AppEvent::SectionMoved`] to `events` for each section
+    /// moved in the manual
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Root of the repository's working tree
+    pub fn repo_path(&self) -> &Path {
+        &self.repo_path
+    }
+
     /// Clone the workflow manager with shared repository access
     /// Useful for creating multiple managers that share the same underlying repository
     pub fn clone_for_shared_access(&self) -> Self {
@@ -231,6 +278,9 @@ impl GitWorkflowManager {
             config: self.config.clone(),
             current_user: self.current_user.clone(),
             toml_manager: self.toml_manager.clone(),
+            authorizer: self.authorizer.clone(),
+            commit_templates: self.commit_templates.clone(),
+            events: self.events.clone(),
         }
     }
 
@@ -353,9 +403,10 @@ impl GitWorkflowManager {
             language: session.language.clone(),
             translator: session.user_id.clone(),
             reviewer: None,
-            status: super::models::ReviewStatus::Pending,
+            status: ReviewStatus::Pending,
             created_at: Utc::now(),
             changes_summary: description.to_string(),
+            status_changed_at: None,
         };
         
         Ok(review_request)
@@ -370,6 +421,8 @@ impl GitWorkflowManager {
         feature_branch: &str,
         translator: &str,
     ) -> Result<()> {
+        self.authorizer.require(reviewer, Action::Merge, &Resource::Branch(feature_branch.to_string()))?;
+
         // Switch to main branch
         self.checkout_branch(&self.config.default_branch).await?;
         
@@ -528,6 +581,250 @@ Chapter-Number: {chapter_number}\nChapter-Slug: {chapter_slug}\nSource-Language:
         Ok(())
     }
 
+    /// Commit the changes to a section of `manual` in `language`, with the
+    /// message the commit templates give its section type and language
+    pub async fn commit_section_change(
+        &self,
+        manual: &Manual,
+        section_id: Uuid,
+        language: &str,
+        review_id: Option<&str>,
+    ) -> Result<Oid> {
+        let (chapter, section) = manual
+            .sections
+            .iter()
+            .find_map(|chapter| find_section(chapter, section_id).map(|section| (chapter, section)))
+            .ok_or_else(|| TradocumentError::Validation(format!("Section {section_id} is not in \"{}\"", manual.title)))?;
+        let context = SectionCommitContext {
+            section_title: section.localized_title(language).to_string(),
+            chapter: chapter.localized_title(language).to_string(),
+            language: language.to_string(),
+            user: self.current_user.id.clone(),
+            review_id: review_id.map(str::to_string),
+        };
+        self.create_commit_with_message(&self.commit_templates.message(&section.section_type, &context)).await
+    }
+
+    /// Move a section of the manual in the working tree under `parent_id`,
+    /// or to the top level for `None`, as the `order`th of its siblings
+    /// (counting from 1), and commit the new outline
+    pub async fn move_section(&self, section_id: Uuid, parent_id: Option<Uuid>, order: u32) -> Result<Oid> {
+        let manual_path = self.repo_path.join(MANUAL_DIR).join(MANUAL_FILE);
+        let mut manual: Manual = serde_json::from_str(&std::fs::read_to_string(&manual_path)?)?;
+        let not_found = |id: Uuid| TradocumentError::Validation(format!("Section {id} is not in \"{}\"", manual.title));
+        let section = take_section(&mut manual.sections, section_id).ok_or_else(|| not_found(section_id))?;
+        let title = section.title.clone();
+
+        // The section is out of the tree by now, so no parent is found under it
+        let siblings = match parent_id {
+            None => &mut manual.sections,
+            Some(parent_id) => {
+                &mut manual.sections.iter_mut().find_map(|s| find_section_mut(s, parent_id)).ok_or_else(|| not_found(parent_id))?.subsections
+            }
+        };
+        let order = place_section(siblings, section, order);
+        manual.updated_at = Utc::now();
+        write_atomic_blocking(&manual_path, &serde_json::to_vec_pretty(&manual)?)?;
+
+        let oid = self.create_commit_with_message(&format!("docs: move \"{title}\" to position {order}")).await?;
+        if let Some(events) = &self.events {
+            events.publish(AppEvent::SectionMoved { manual_id: manual.id, section_id, parent_id, order });
+        }
+        Ok(oid)
+    }
+
+    /// The manual as it was at `tag`, read from the tagged tree.
+    ///
+    /// The working directory, index and HEAD are left alone, so this can
+    /// run while translators have uncommitted changes.
+    pub async fn load_manual_at(&self, tag: &str) -> Result<Manual> {
+        let path = Path::new(MANUAL_DIR).join(MANUAL_FILE);
+        let bytes = self.read_file_at(tag, &path).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// The manual's section documents as they were at `tag`
+    pub async fn load_documents_at(&self, tag: &str) -> Result<HashMap<Uuid, Document>> {
+        let tag = tag.to_string();
+        let repo_ref = self.repo.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<HashMap<Uuid, Document>> {
+            let repo = repo_ref.lock()?;
+            let tree = tagged_tree(&repo, &tag)?;
+            let mut documents = HashMap::new();
+            let Ok(entry) = tree.get_path(&Path::new(MANUAL_DIR).join(DOCUMENTS_DIR)) else {
+                return Ok(documents);
+            };
+            let documents_tree = entry.to_object(&repo).and_then(|object| object.peel_to_tree()).map_err(GitError::from)?;
+
+            for entry in documents_tree.iter() {
+                let Some(id) = entry.name().and_then(document_id) else {
+                    continue;
+                };
+                let blob = entry.to_object(&repo).and_then(|object| object.peel_to_blob()).map_err(GitError::from)?;
+                documents.insert(id, serde_json::from_slice(blob.content())?);
+            }
+            Ok(documents)
+        }).await.map_err(|e| TradocumentError::Git(GitError::InvalidOperation(format!("Task join error: {e}"))))?
+    }
+
+    /// Contents of a file, such as a screenshot, as it was at `tag`.
+    /// `path` is relative to the repository root.
+    pub async fn read_file_at(&self, tag: &str, path: &Path) -> Result<Vec<u8>> {
+        let tag = tag.to_string();
+        let path = path.to_path_buf();
+        let repo_ref = self.repo.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
+            let repo = repo_ref.lock()?;
+            let tree = tagged_tree(&repo, &tag)?;
+            let entry = tree.get_path(&path).map_err(|_| {
+                GitError::InvalidOperation(format!("{} does not exist at tag {tag}", path.display()))
+            })?;
+            let blob = entry.to_object(&repo).and_then(|object| object.peel_to_blob()).map_err(GitError::from)?;
+            Ok(blob.content().to_vec())
+        }).await.map_err(|e| TradocumentError::Git(GitError::InvalidOperation(format!("Task join error: {e}"))))?
+    }
+
+    /// The repository's tags, oldest first, each with who released it and
+    /// why. A tag's commit must come after the commits of the tags before
+    /// it. With `path`, a file or directory relative to the repository
+    /// root, only tags at which `path` differs from the tag before are
+    /// listed, so releases that left it alone are skipped.
+    ///
+    /// Annotated tags are described by their tagger and message, and
+    /// lightweight tags by the author and summary of their commit.
+    pub async fn release_tags(&self, path: Option<&Path>) -> Result<Vec<ReleaseTag>> {
+        let path = path.map(Path::to_path_buf);
+        let repo_ref = self.repo.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<ReleaseTag>> {
+            let repo = repo_ref.lock()?;
+            let mut tagged = Vec::new();
+            for name in repo.tag_names(None).map_err(GitError::from)?.iter().flatten() {
+                let object = repo.revparse_single(&format!("refs/tags/{name}")).map_err(GitError::from)?;
+                let Ok(commit) = object.peel_to_commit() else {
+                    continue;
+                };
+                let (author, time, message) = match object.as_tag().and_then(|tag| tag.tagger().map(|tagger| (tag, tagger))) {
+                    Some((tag, tagger)) => (tagger.name().map(str::to_string), tagger.when(), tag.message().map(str::to_string)),
+                    None => (commit.author().name().map(str::to_string), commit.time(), commit.summary().map(str::to_string)),
+                };
+                let content = match &path {
+                    Some(path) => commit.tree().map_err(GitError::from)?.get_path(path).ok().map(|entry| entry.id()),
+                    None => None,
+                };
+                let release = ReleaseTag {
+                    name: name.to_string(),
+                    commit: commit.id().to_string(),
+                    author: author.unwrap_or_else(|| "unknown".to_string()),
+                    date: DateTime::from_timestamp(time.seconds(), 0).unwrap_or_else(Utc::now),
+                    summary: message.map(|message| message.lines().next().unwrap_or_default().trim().to_string()).unwrap_or_default(),
+                };
+                tagged.push((release, commit.id(), content));
+            }
+
+            // Tags of one commit keep their name order; commits are ordered
+            // by ancestry, then by time
+            let mut revwalk = repo.revwalk().map_err(GitError::from)?;
+            revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME | git2::Sort::REVERSE).map_err(GitError::from)?;
+            for (_, id, _) in &tagged {
+                revwalk.push(*id).map_err(GitError::from)?;
+            }
+            let mut order = HashMap::new();
+            for (position, id) in revwalk.enumerate() {
+                order.insert(id.map_err(GitError::from)?, position);
+            }
+            tagged.sort_by(|(a, a_id, _), (b, b_id, _)| order.get(a_id).cmp(&order.get(b_id)).then_with(|| a.name.cmp(&b.name)));
+
+            let mut releases = Vec::new();
+            let mut previous = None;
+            for (release, _, content) in tagged {
+                if path.is_some() {
+                    if content.is_none() || content == previous {
+                        continue;
+                    }
+                    previous = content;
+                }
+                releases.push(release);
+            }
+            Ok(releases)
+        }).await.map_err(|e| TradocumentError::Git(GitError::InvalidOperation(format!("Task join error: {e}"))))?
+    }
+
+    /// Problems that build up in a repository over time, each with a
+    /// suggested fix. Nothing is changed, in the repository or on disk.
+    ///
+    /// Review requests live with whatever hosts the pull requests, so the
+    /// caller passes the ones it knows about.
+    pub async fn check_integrity(&self, review_requests: &[ReviewRequest]) -> Result<IntegrityReport> {
+        let repo_ref = self.repo.clone();
+        let (branches, head, dirty) = tokio::task::spawn_blocking(move || -> Result<(BTreeSet<String>, Option<String>, bool)> {
+            let repo = repo_ref.lock()?;
+            let mut branches = BTreeSet::new();
+            for branch_result in repo.branches(Some(BranchType::Local)).map_err(GitError::from)? {
+                let (branch, _branch_type) = branch_result.map_err(GitError::from)?;
+                if let Some(branch_name) = branch.name().map_err(GitError::from)? {
+                    branches.insert(branch_name.to_string());
+                }
+            }
+            let head = repo.head().ok().and_then(|head| head.shorthand().map(str::to_string));
+            let mut status_options = git2::StatusOptions::new();
+            status_options.include_untracked(true);
+            let dirty = !repo.statuses(Some(&mut status_options)).map_err(GitError::from)?.is_empty();
+            Ok((branches, head, dirty))
+        }).await.map_err(|e| TradocumentError::Git(GitError::InvalidOperation(format!("Task join error: {e}"))))??;
+
+        let mut report = IntegrityReport::default();
+        let manual_dir = self.repo_path.join(MANUAL_DIR);
+        let manual: Option<Manual> = match std::fs::read_to_string(manual_dir.join(MANUAL_FILE)) {
+            Ok(json) => Some(serde_json::from_str(&json)?),
+            Err(_) => None,
+        };
+
+        if let Some(manual) = &manual {
+            // Branches are named translate/chapter/language/session_id
+            for branch in &branches {
+                let parts: Vec<&str> = branch.split('/').collect();
+                if parts.len() >= 4 && parts[0] == "translate" && !manual.languages.iter().any(|l| l == parts[2]) {
+                    report.issues.push(IntegrityIssue {
+                        kind: IntegrityIssueKind::OrphanedBranch { branch: branch.clone(), language: parts[2].to_string() },
+                        message: format!("Branch {branch} translates into {}, which the manual doesn't list", parts[2]),
+                        remediation: format!("Delete the branch, or add {} to the manual's languages", parts[2]),
+                    });
+                }
+            }
+            missing_documents(&manual.sections, &manual_dir.join(DOCUMENTS_DIR), &mut report);
+        }
+
+        for review in review_requests {
+            if review.is_open() && !branches.contains(&review.branch) {
+                report.issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::DanglingReviewRequest { review_id: review.id, branch: review.branch.clone() },
+                    message: format!("Review request {} is for branch {}, which no longer exists", review.id, review.branch),
+                    remediation: "Close the review request, or restore the branch from its last commit".to_string(),
+                });
+            }
+        }
+
+        // Only the checked-out branch has a working tree to be dirty
+        for session in SessionStore::new(&self.repo_path).sessions() {
+            if dirty && head.as_deref() == Some(session.branch.as_str()) {
+                report.issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::UncommittedSession {
+                        session_id: session.id,
+                        user_id: session.user_id.clone(),
+                        branch: session.branch.clone(),
+                    },
+                    message: format!("Session of {} on {} has uncommitted changes", session.user_id, session.branch),
+                    remediation: "Save the session to commit its work, or discard the changes and end it".to_string(),
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
     // Private helper methods
 
     async fn load_or_create_chapter_data(
@@ -772,6 +1069,77 @@ Chapter-Number: {chapter_number}\nChapter-Slug: {chapter_slug}\nSource-Language:
     }
 }
 
+/// `section` or the subsection of it, at any depth, with the id `id`
+fn find_section(section: &ManualSection, id: Uuid) -> Option<&ManualSection> {
+    if section.id == id {
+        return Some(section);
+    }
+    section.subsections.iter().find_map(|subsection| find_section(subsection, id))
+}
+
+fn find_section_mut(section: &mut ManualSection, id: Uuid) -> Option<&mut ManualSection> {
+    if section.id == id {
+        return Some(section);
+    }
+    section.subsections.iter_mut().find_map(|subsection| find_section_mut(subsection, id))
+}
+
+/// Remove the section `id`, at any depth, closing the gap it leaves
+fn take_section(sections: &mut Vec<ManualSection>, id: Uuid) -> Option<ManualSection> {
+    match sections.iter().position(|s| s.id == id) {
+        Some(index) => {
+            let section = sections.remove(index);
+            renumber(sections);
+            Some(section)
+        }
+        None => sections.iter_mut().find_map(|s| take_section(&mut s.subsections, id)),
+    }
+}
+
+/// Insert `section` as the `order`th of `sections`, or the last if there
+/// are fewer, and return the order it got
+fn place_section(sections: &mut Vec<ManualSection>, section: ManualSection, order: u32) -> u32 {
+    sections.sort_by_key(|s| s.order);
+    let index = (order.max(1) as usize - 1).min(sections.len());
+    sections.insert(index, section);
+    for (section, order) in sections.iter_mut().zip(1..) {
+        section.order = order;
+    }
+    index as u32 + 1
+}
+
+/// Number `sections` 1, 2, ... in their current order
+fn renumber(sections: &mut [ManualSection]) {
+    sections.sort_by_key(|s| s.order);
+    for (section, order) in sections.iter_mut().zip(1..) {
+        section.order = order;
+    }
+}
+
+/// Report sections, at any depth, whose document file is missing
+fn missing_documents(sections: &[ManualSection], documents_dir: &Path, report: &mut IntegrityReport) {
+    for section in sections {
+        if let Some(document_id) = section.document_id {
+            if !documents_dir.join(format!("{document_id}.json")).exists() {
+                report.issues.push(IntegrityIssue {
+                    kind: IntegrityIssueKind::MissingDocument { section_id: section.id, document_id },
+                    message: format!("Section \"{}\" refers to document {document_id}, which doesn't exist", section.title),
+                    remediation: "Restore the document from history, or unlink it from the section".to_string(),
+                });
+            }
+        }
+        missing_documents(&section.subsections, documents_dir, report);
+    }
+}
+
+/// Tree of the commit `tag` points to
+fn tagged_tree<'r>(repo: &'r Repository, tag: &str) -> Result<git2::Tree<'r>> {
+    let object = repo
+        .revparse_single(&format!("refs/tags/{tag}"))
+        .map_err(|_| GitError::InvalidOperation(format!("Tag {tag} not found")))?;
+    Ok(object.peel_to_tree().map_err(GitError::from)?)
+}
+
 /// Information about an active translation branch
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranslationBranchInfo {
@@ -784,4 +1152,279 @@ pub struct TranslationBranchInfo {
     pub author: String,
 }
 
-// Note: ReviewStatus, TranslationVersion, and DiffStats are defined in models.rs
\ No newline at end of file
+// Note: ReviewStatus, TranslationVersion, and DiffStats are defined in models.rs
+
+/// A tagged release of the repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseTag {
+    pub name: String,
+    pub commit: String,
+    pub author: String,
+    pub date: DateTime<Utc>,
+    /// First line of the tag's or commit's message
+    pub summary: String,
+}
+
+/// What is wrong in a repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntegrityIssueKind {
+    /// A translation branch for a language the manual isn't translated into
+    OrphanedBranch { branch: String, language: String },
+    /// A section refers to a document file that doesn't exist
+    MissingDocument { section_id: Uuid, document_id: Uuid },
+    /// An open review request for a branch that no longer exists
+    DanglingReviewRequest { review_id: Uuid, branch: String },
+    /// An open session whose work hasn't been committed
+    UncommittedSession { session_id: Uuid, user_id: String, branch: String },
+}
+
+/// A problem found by [`GitWorkflowManager::check_integrity`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    pub kind: IntegrityIssueKind,
+    pub message: String,
+    /// Suggested way to fix it
+    pub remediation: String,
+}
+
+/// Outcome of [`GitWorkflowManager::check_integrity`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+// Note: ReviewStatus, TranslationVersion, and DiffStats are defined in models.rs
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_integration::initialize_translation_repository;
+    use crate::services::manual_dir::{load_manual_dir, save_manual_dir};
+    use crate::services::templates::scaffold;
+    use crate::{DocumentMetadata, ManualTemplate, UserRole};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn document(content: &str) -> Document {
+        Document {
+            title: "Introduction".to_string(),
+            content: HashMap::from([("en".to_string(), content.to_string())]),
+            metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+        }
+    }
+
+    /// Manager of a freshly initialized repository
+    async fn manager(repo_path: &Path) -> GitWorkflowManager {
+        initialize_translation_repository(repo_path, "Controller", &GitConfig::default()).await.unwrap();
+        let user = User {
+            id: "editor".to_string(),
+            name: "Editor".to_string(),
+            email: "editor@example.com".to_string(),
+            role: UserRole::Member,
+            created_at: Utc::now(),
+            active: true,
+        };
+        GitWorkflowManager::new(repo_path, Uuid::new_v4(), user, GitConfig::default()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_load_manual_at_tag_ignores_working_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        let manager = manager(repo_path).await;
+
+        let manual_dir = repo_path.join(MANUAL_DIR);
+        let screenshot = Path::new("content/assets/screenshots/main.png");
+        let mut manual = scaffold(&ManualTemplate::UserGuide, &["en".to_string()]);
+        manual.version = "1.2".to_string();
+        let document_id = Uuid::new_v4();
+        manual.sections[0].document_id = Some(document_id);
+        save_manual_dir(&manual_dir, &manual, &HashMap::from([(document_id, document("Released text."))])).unwrap();
+        fs::write(repo_path.join(screenshot), [0x89, b'P', b'N', b'G', 1]).unwrap();
+        let released = manager.create_commit_with_message("Release 1.2").await.unwrap();
+        {
+            let repo = Repository::open(repo_path).unwrap();
+            let commit = repo.find_object(released, None).unwrap();
+            repo.tag_lightweight("v1.2", &commit, false).unwrap();
+        }
+
+        // Keep working on the manual after the release, partly uncommitted
+        manual.version = "1.3".to_string();
+        manual.sections.remove(1);
+        save_manual_dir(&manual_dir, &manual, &HashMap::from([(document_id, document("Draft text."))])).unwrap();
+        fs::write(repo_path.join(screenshot), [0x89, b'P', b'N', b'G', 2]).unwrap();
+        manager.create_commit_with_message("Work on 1.3").await.unwrap();
+        save_manual_dir(&manual_dir, &manual, &HashMap::from([(document_id, document("Uncommitted text."))])).unwrap();
+
+        let tagged = manager.load_manual_at("v1.2").await.unwrap();
+        assert_eq!(tagged.version, "1.2");
+        assert_eq!(tagged.sections.len(), manual.sections.len() + 1);
+        let documents = manager.load_documents_at("v1.2").await.unwrap();
+        assert_eq!(documents[&document_id].content["en"], "Released text.");
+        assert_eq!(manager.read_file_at("v1.2", screenshot).await.unwrap(), [0x89, b'P', b'N', b'G', 1]);
+
+        // The working copy is untouched
+        let (current, current_documents) = load_manual_dir(&manual_dir).unwrap();
+        assert_eq!(current.version, "1.3");
+        assert_eq!(current_documents[&document_id].content["en"], "Uncommitted text.");
+        assert!(manager.load_manual_at("v9.9").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_integrity_check_reports_each_problem() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        let manager = manager(repo_path).await;
+
+        // The second section's document was never saved
+        let mut manual = scaffold(&ManualTemplate::UserGuide, &["en".to_string(), "de".to_string()]);
+        let (present, missing) = (Uuid::new_v4(), Uuid::new_v4());
+        manual.sections[0].document_id = Some(present);
+        manual.sections[1].document_id = Some(missing);
+        save_manual_dir(&repo_path.join(MANUAL_DIR), &manual, &HashMap::from([(present, document("Text."))])).unwrap();
+        manager.create_commit_with_message("Add manual").await.unwrap();
+        assert!(manager.check_integrity(&[]).await.unwrap().issues.iter().all(|issue| matches!(
+            issue.kind,
+            IntegrityIssueKind::MissingDocument { .. }
+        )));
+
+        let head = {
+            let repo = Repository::open(repo_path).unwrap();
+            let commit = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.branch("translate/intro/de/1", &commit, false).unwrap();
+            repo.branch("translate/intro/fr/2", &commit, false).unwrap();
+            repo.head().unwrap().shorthand().unwrap().to_string()
+        };
+
+        let review = |branch: &str, status: ReviewStatus| ReviewRequest {
+            id: Uuid::new_v4(),
+            pr_number: 1,
+            branch: branch.to_string(),
+            chapter: "intro".to_string(),
+            language: "de".to_string(),
+            translator: "alice".to_string(),
+            reviewer: None,
+            status,
+            created_at: Utc::now(),
+            changes_summary: String::new(),
+            status_changed_at: None,
+        };
+        let dangling = review("translate/intro/de/3", ReviewStatus::Pending);
+        let reviews = [
+            review("translate/intro/de/1", ReviewStatus::Pending),
+            dangling.clone(),
+            // Merged reviews have their branch deleted on purpose
+            review("translate/intro/de/4", ReviewStatus::Approved),
+        ];
+
+        // An open session on the checked-out branch with unsaved work
+        let session = WorkSession {
+            id: Uuid::new_v4(),
+            branch: head.clone(),
+            chapter: "intro".to_string(),
+            language: "de".to_string(),
+            user_id: "alice".to_string(),
+            markdown_path: "generated/markdown/de/intro.md".to_string(),
+            started_at: Utc::now(),
+            last_save: None,
+            auto_save_enabled: true,
+        };
+        SessionStore::new(repo_path).save(&session).unwrap();
+        fs::write(repo_path.join("content/chapters/notes.md"), "Unsaved").unwrap();
+
+        let report = manager.check_integrity(&reviews).await.unwrap();
+        let kinds: Vec<_> = report.issues.iter().map(|issue| issue.kind.clone()).collect();
+        assert_eq!(kinds, [
+            IntegrityIssueKind::OrphanedBranch { branch: "translate/intro/fr/2".to_string(), language: "fr".to_string() },
+            IntegrityIssueKind::MissingDocument { section_id: manual.sections[1].id, document_id: missing },
+            IntegrityIssueKind::DanglingReviewRequest { review_id: dangling.id, branch: dangling.branch.clone() },
+            IntegrityIssueKind::UncommittedSession { session_id: session.id, user_id: "alice".to_string(), branch: head },
+        ]);
+        assert!(report.issues.iter().all(|issue| !issue.remediation.is_empty()));
+
+        // Nothing was cleaned up
+        assert!(repo_path.join("content/chapters/notes.md").exists());
+        assert!(Repository::open(repo_path).unwrap().find_branch("translate/intro/fr/2", BranchType::Local).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_section_commit_uses_template_for_type_and_language() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        let templates = SectionCommitTemplates::parse(
+            r#"
+[[templates]]
+section_type = "Troubleshooting"
+language = "de"
+template = "docs({language}): Fehlerbehebung in „{section_title}“ überarbeitet\n\nReview: {review_id}"
+"#,
+        )
+        .unwrap();
+        let manager = manager(repo_path).await.with_commit_templates(templates);
+
+        let mut manual = scaffold(&ManualTemplate::UserGuide, &["en".to_string(), "de".to_string()]);
+        let section = manual.sections.iter_mut().find(|s| s.section_type == crate::SectionType::Troubleshooting).unwrap();
+        section.localized_titles.insert("de".to_string(), "Störungen beheben".to_string());
+        let section_id = section.id;
+        save_manual_dir(&repo_path.join(MANUAL_DIR), &manual, &HashMap::new()).unwrap();
+
+        let oid = manager.commit_section_change(&manual, section_id, "de", None).await.unwrap();
+        let message = Repository::open(repo_path).unwrap().find_commit(oid).unwrap().message().unwrap().to_string();
+        assert_eq!(message, "docs(de): Fehlerbehebung in „Störungen beheben“ überarbeitet\n\nReview: ");
+
+        // Other section types and unknown sections
+        let introduction = manual.sections[0].id;
+        let oid = manager.commit_section_change(&manual, introduction, "de", None).await.unwrap();
+        let message = Repository::open(repo_path).unwrap().find_commit(oid).unwrap().message().unwrap().to_string();
+        assert_eq!(message, format!("docs(de): update {}", manual.sections[0].localized_title("de")));
+        assert!(manager.commit_section_change(&manual, Uuid::new_v4(), "de", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_move_section_commits_outline_and_publishes() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        let events = EventBus::default();
+        let mut subscriber = events.subscribe();
+        let manager = manager(repo_path).await.with_events(events);
+
+        let manual = scaffold(&ManualTemplate::UserGuide, &["en".to_string()]);
+        let manual_dir = repo_path.join(MANUAL_DIR);
+        save_manual_dir(&manual_dir, &manual, &HashMap::new()).unwrap();
+        let (moved, parent) = (manual.sections.last().unwrap().id, manual.sections[0].id);
+
+        let oid = manager.move_section(moved, Some(parent), 99).await.unwrap();
+        let (current, _) = load_manual_dir(&manual_dir).unwrap();
+        assert_eq!(current.sections.len(), manual.sections.len() - 1);
+        let parent_section = current.sections.iter().find(|s| s.id == parent).unwrap();
+        let placed = parent_section.subsections.last().unwrap();
+        assert_eq!((placed.id, placed.order as usize), (moved, parent_section.subsections.len()));
+        assert_eq!(subscriber.try_recv(), Some(AppEvent::SectionMoved {
+            manual_id: manual.id,
+            section_id: moved,
+            parent_id: Some(parent),
+            order: placed.order,
+        }));
+        let repo = Repository::open(repo_path).unwrap();
+        assert!(repo.find_commit(oid).unwrap().message().unwrap().starts_with("docs: move"));
+
+        // Back to the top, as the first section
+        manager.move_section(moved, None, 1).await.unwrap();
+        assert!(matches!(subscriber.try_recv(), Some(AppEvent::SectionMoved { parent_id: None, order: 1, .. })));
+        let (current, _) = load_manual_dir(&manual_dir).unwrap();
+        let mut top: Vec<_> = current.sections.iter().map(|s| (s.order, s.id)).collect();
+        top.sort();
+        assert_eq!(top[0], (1, moved));
+        assert!(top.iter().map(|(order, _)| *order).eq(1..=manual.sections.len() as u32));
+
+        // Not under itself, and nothing is written
+        assert!(manager.move_section(parent, Some(parent), 1).await.is_err());
+        assert_eq!(load_manual_dir(&manual_dir).unwrap().0.sections.len(), current.sections.len());
+        assert!(subscriber.try_recv().is_none());
+    }
+}
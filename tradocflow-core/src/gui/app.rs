@@ -2,12 +2,24 @@ use slint::{ComponentHandle, ModelRc, VecModel};
 use std::sync::{Arc, Mutex};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use uuid::Uuid;
 use crate::{MainWindow, TradocumentError, Result};
-use crate::services::{ProjectService};
+use crate::database::Database;
+use crate::database::image_repository::ImageRepository;
+use crate::services::{content_revision, html_to_markdown, write_atomic, AutoSaveConfig, DocumentStateError, DocumentStateManager, ImagePaster, ProjectManager, ProjectService, SaveOutcome, SaveProgress, TextDirection};
 use crate::services::project_service::{CreateProjectRequest, TeamMemberRequest};
 // use crate::services::document_import_service::ImportConfig; // Temporarily disabled
 use crate::gui::ExportBridge;
+use crate::gui::keymap::{KeyChord, KeymapConfig};
+use crate::gui::bilingual_review::{self, AlignedParagraph};
+use crate::gui::clipboard::{read_clipboard, ClipboardContent};
+use crate::models::document::TranslationUnit;
+use crate::services::outline::{extract_outline, OutlineNode};
+use crate::services::markdown_ops::{self, ListKind, Selection};
+
+/// Delay after the last edit before the outline panel is rebuilt
+const OUTLINE_DEBOUNCE: Duration = Duration::from_millis(300);
 
 /// Document state tracking
 #[derive(Debug, Clone)]
@@ -16,7 +28,12 @@ struct DocumentState {
     content: String,
     modified: bool,
     last_saved: Option<Instant>,
+    /// Revision of the file as last loaded or saved, so auto-save can tell
+    /// when someone else changed it since
+    disk_revision: Option<String>,
     language: String,
+    /// Editor selection as the window last reported it, if it has
+    selection: Option<Selection>,
 }
 
 impl Default for DocumentState {
@@ -26,28 +43,16 @@ impl Default for DocumentState {
             content: String::new(),
             modified: false,
             last_saved: None,
+            disk_revision: None,
             language: "en".to_string(),
+            selection: None,
         }
     }
 }
 
-/// Auto-save configuration
-#[derive(Debug, Clone)]
-struct AutoSaveConfig {
-    enabled: bool,
-    interval_seconds: u64,
-    min_changes: usize,
-}
-
-impl Default for AutoSaveConfig {
-    fn default() -> Self {
-        Self {
-            enabled: true,
-            interval_seconds: 30, // Auto-save every 30 seconds
-            min_changes: 5, // Minimum 5 changes before auto-save
-        }
-    }
-}
+/// State manager of the file the editor last opened or saved, which
+/// auto-saves go through so they notice changes made by someone else
+type TrackedFile = Arc<Mutex<Option<Arc<DocumentStateManager>>>>;
 
 /// Main application struct that manages the Slint GUI
 pub struct App {
@@ -59,7 +64,10 @@ pub struct App {
     current_wizard_data: Arc<Mutex<WizardData>>,
     document_state: Arc<Mutex<DocumentState>>,
     auto_save_config: Arc<Mutex<AutoSaveConfig>>,
+    tracked_file: TrackedFile,
     auto_save_tx: Option<mpsc::UnboundedSender<()>>,
+    keymap: Arc<KeymapConfig>,
+    image_store: ImageRepository,
     runtime_handle: tokio::runtime::Handle,
 }
 
@@ -101,6 +109,7 @@ impl App {
         main_window.set_current_layout("single".into());
         main_window.set_status_message("TradocFlow ready".to_string().into());
         main_window.set_status_type("info".into());
+        Self::refresh_outline(&main_window, &main_window.get_document_content());
 
         // Initialize services
         let project_service = Arc::new(ProjectService::new("./projects"));
@@ -124,10 +133,28 @@ impl App {
         
         // Initialize export bridge
         let export_bridge = Arc::new(ExportBridge::new());
+
+        // Pasted images are stored by content in the application database
+        let database = Database::new("tradocflow.db")?;
+        let image_store = ImageRepository::new(database.pool());
         
         // Initialize document state and auto-save
         let document_state = Arc::new(Mutex::new(DocumentState::default()));
-        let auto_save_config = Arc::new(Mutex::new(AutoSaveConfig::default()));
+        let auto_save_config = AutoSaveConfig::load().unwrap_or_else(|e| {
+            log::warn!("{e}; auto-saving with the default settings");
+            AutoSaveConfig::default()
+        });
+        let auto_save_config = Arc::new(Mutex::new(auto_save_config));
+
+        // Load keyboard shortcuts; bad bindings are reported and fall back to defaults
+        let (keymap, keymap_issues) = KeymapConfig::load();
+        for issue in &keymap_issues {
+            log::warn!("{issue}");
+        }
+        if let Some(issue) = keymap_issues.first() {
+            main_window.set_status_message(format!("Keymap: {issue} (using defaults)").into());
+            main_window.set_status_type("warning".into());
+        }
 
         // Set up callbacks
         let app = Self { 
@@ -139,7 +166,10 @@ impl App {
             current_wizard_data: wizard_data,
             document_state,
             auto_save_config,
+            tracked_file: Arc::new(Mutex::new(None)),
             auto_save_tx: None,
+            keymap: Arc::new(keymap),
+            image_store,
             runtime_handle,
         };
         app.setup_callbacks();
@@ -160,6 +190,91 @@ impl App {
             .map_err(|e| TradocumentError::SlintError(format!("Failed to run application: {e}")))
     }
 
+    /// Languages and translation memory units of the open project, for the
+    /// bilingual review. Without a project there are no units to compare.
+    async fn bilingual_review_memory(project_service: &ProjectService) -> Option<(String, Option<String>, Vec<TranslationUnit>)> {
+        let project_path = Self::get_last_project_path().ok()?;
+        let project = project_service.load_project(&project_path).await.ok()?;
+        let units = ProjectManager::load_translation_units_at(&project.project_path).await.unwrap_or_else(|e| {
+            log::warn!("Translation memory of {} not loaded: {e}", project.name);
+            Vec::new()
+        });
+        Some((project.source_language, project.target_languages.into_iter().next(), units))
+    }
+
+    /// Align the editor and translation panes in the bilingual review,
+    /// flagging paragraphs whose source changed since the project's
+    /// translation memory recorded their translation
+    fn refresh_bilingual_review(window: &MainWindow, project_service: Arc<ProjectService>, runtime_handle: &tokio::runtime::Handle) {
+        let source = window.get_document_content().to_string();
+        let target = window.get_translation_content().to_string();
+        let window_weak = window.as_weak();
+
+        runtime_handle.spawn(async move {
+            let memory = Self::bilingual_review_memory(&project_service).await;
+            let _ = window_weak.upgrade_in_event_loop(move |window| {
+                let mut units = Vec::new();
+                if let Some((source_language, target_language, project_units)) = memory {
+                    window.set_bilingual_source_language(source_language.into());
+                    if window.get_bilingual_target_language().is_empty() {
+                        window.set_bilingual_target_language(target_language.unwrap_or_default().into());
+                    }
+                    units = project_units;
+                }
+
+                let paragraphs = bilingual_review::align_paragraphs(
+                    &source,
+                    &target,
+                    &window.get_bilingual_target_language(),
+                    &units,
+                );
+                let flagged = paragraphs.iter().filter(|p| p.status.needs_attention()).count();
+                Self::apply_bilingual_paragraphs(&window, &paragraphs);
+                window.set_status_message(format!("{flagged} paragraph(s) need review").into());
+                window.set_status_type(if flagged > 0 { "warning" } else { "success" }.into());
+            });
+        });
+    }
+
+    /// Push aligned paragraphs into the bilingual review model
+    fn apply_bilingual_paragraphs(window: &MainWindow, paragraphs: &[AlignedParagraph]) {
+        let items: Vec<crate::BilingualParagraph> = paragraphs
+            .iter()
+            .map(|p| crate::BilingualParagraph {
+                index: p.index as i32,
+                source: p.source.clone().unwrap_or_default().into(),
+                target: p.target.clone().unwrap_or_default().into(),
+                status: p.status.as_str().into(),
+            })
+            .collect();
+        window.set_bilingual_paragraphs(ModelRc::new(VecModel::from(items)));
+    }
+
+    /// Rebuild the outline panel from the given markdown
+    fn refresh_outline(window: &MainWindow, markdown: &str) {
+        fn push_items(nodes: &[OutlineNode], depth: i32, items: &mut Vec<crate::OutlineItem>) {
+            for node in nodes {
+                items.push(crate::OutlineItem {
+                    title: node.text.clone().into(),
+                    slug: node.slug.clone().into(),
+                    level: node.level as i32,
+                    depth,
+                    offset: node.offset as i32,
+                });
+                push_items(&node.children, depth + 1, items);
+            }
+        }
+
+        let mut items = Vec::new();
+        push_items(&extract_outline(markdown), 0, &mut items);
+        window.set_outline_items(ModelRc::new(VecModel::from(items)));
+    }
+
+    /// Keyboard shortcuts in effect for this session
+    pub fn keymap(&self) -> &KeymapConfig {
+        &self.keymap
+    }
+
     /// Set up all the callbacks for the Slint UI
     fn setup_callbacks(&self) {
         let main_window_weak = self.main_window.as_weak();
@@ -191,10 +306,12 @@ impl App {
                             state.content = "# New Document\n\nStart editing here...".to_string();
                             state.modified = false;
                             state.last_saved = None;
+                            state.disk_revision = None;
                             state.language = "en".to_string();
 
                             // Update UI
                             if let Some(window) = window_weak.upgrade() {
+                                Self::refresh_outline(&window, &state.content);
                                 window.set_document_content(state.content.clone().into());
                                 window.set_status_message("New document created".into());
                                 window.set_status_type("success".into());
@@ -207,11 +324,15 @@ impl App {
 
         self.main_window.on_file_open({
             let document_state = Arc::clone(&self.document_state);
+            let tracked_file = Arc::clone(&self.tracked_file);
+            let auto_save_config = Arc::clone(&self.auto_save_config);
             let main_window_weak = main_window_weak.clone();
             let runtime_handle = self.runtime_handle.clone();
             move || {
                 if let Some(window) = main_window_weak.upgrade() {
                     let document_state = Arc::clone(&document_state);
+                    let tracked_file = Arc::clone(&tracked_file);
+                    let auto_save_config = Arc::clone(&auto_save_config);
                     let window_weak = window.as_weak();
                     
                     runtime_handle.spawn(async move {
@@ -245,11 +366,14 @@ impl App {
                                     state.content = content.clone();
                                     state.modified = false;
                                     state.last_saved = Some(Instant::now());
+                                    state.disk_revision = Some(content_revision(content.as_bytes()));
                                     state.language = "en".to_string();
                                 }
+                                Self::track_file(&tracked_file, &auto_save_config, &window_weak, &test_file).await;
 
                                 // Update UI
                                 if let Some(window) = window_weak.upgrade() {
+                                    Self::refresh_outline(&window, &content);
                                     window.set_document_content(content.into());
                                     window.set_status_message(
                                         format!("Opened: {}", test_file.file_name().unwrap_or_default().to_string_lossy()).into()
@@ -271,11 +395,15 @@ impl App {
 
         self.main_window.on_file_save({
             let document_state = Arc::clone(&self.document_state);
+            let tracked_file = Arc::clone(&self.tracked_file);
+            let auto_save_config = Arc::clone(&self.auto_save_config);
             let main_window_weak = main_window_weak.clone();
             let runtime_handle = self.runtime_handle.clone();
             move || {
                 if let Some(window) = main_window_weak.upgrade() {
                     let document_state = Arc::clone(&document_state);
+                    let tracked_file = Arc::clone(&tracked_file);
+                    let auto_save_config = Arc::clone(&auto_save_config);
                     let window_weak = window.as_weak();
                     
                     runtime_handle.spawn(async move {
@@ -303,27 +431,26 @@ impl App {
                         };
 
                         // Save to filesystem
-                        match tokio::fs::write(&path, &content).await {
+                        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                        let (progress, shown) = Self::show_save_progress(window_weak.clone(), name.clone());
+                        let saved = write_atomic(&path, content.as_bytes(), Some(&progress)).await;
+                        drop(progress);
+                        let _ = shown.await;
+                        match saved {
                             Ok(_) => {
                                 // Update state
                                 if let Ok(mut state) = document_state.lock() {
                                     state.current_path = Some(path.clone());
                                     state.modified = false;
                                     state.last_saved = Some(Instant::now());
+                                    state.disk_revision = Some(content_revision(content.as_bytes()));
                                 }
+                                Self::track_file(&tracked_file, &auto_save_config, &window_weak, &path).await;
 
-                                if let Some(window) = window_weak.upgrade() {
-                                    window.set_status_message(
-                                        format!("Saved: {}", path.file_name().unwrap_or_default().to_string_lossy()).into()
-                                    );
-                                    window.set_status_type("success".into());
-                                }
+                                Self::post_status(&window_weak, format!("Saved: {name}"), "success");
                             },
                             Err(e) => {
-                                if let Some(window) = window_weak.upgrade() {
-                                    window.set_status_message(format!("Failed to save document: {e}").into());
-                                    window.set_status_type("error".into());
-                                }
+                                Self::post_status(&window_weak, format!("Failed to save document: {e}"), "error");
                             }
                         }
                     });
@@ -333,11 +460,15 @@ impl App {
 
         self.main_window.on_file_save_as({
             let document_state = Arc::clone(&self.document_state);
+            let tracked_file = Arc::clone(&self.tracked_file);
+            let auto_save_config = Arc::clone(&self.auto_save_config);
             let main_window_weak = main_window_weak.clone();
             let runtime_handle = self.runtime_handle.clone();
             move || {
                 if let Some(window) = main_window_weak.upgrade() {
                     let document_state = Arc::clone(&document_state);
+                    let tracked_file = Arc::clone(&tracked_file);
+                    let auto_save_config = Arc::clone(&auto_save_config);
                     let window_weak = window.as_weak();
                     
                     runtime_handle.spawn(async move {
@@ -367,27 +498,26 @@ impl App {
                         let save_path = current_dir.join(format!("document_{timestamp}.md"));
 
                         // Save to filesystem
-                        match tokio::fs::write(&save_path, &content).await {
+                        let name = save_path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                        let (progress, shown) = Self::show_save_progress(window_weak.clone(), name.clone());
+                        let saved = write_atomic(&save_path, content.as_bytes(), Some(&progress)).await;
+                        drop(progress);
+                        let _ = shown.await;
+                        match saved {
                             Ok(_) => {
                                 // Update state
                                 if let Ok(mut state) = document_state.lock() {
                                     state.current_path = Some(save_path.clone());
                                     state.modified = false;
                                     state.last_saved = Some(Instant::now());
+                                    state.disk_revision = Some(content_revision(content.as_bytes()));
                                 }
+                                Self::track_file(&tracked_file, &auto_save_config, &window_weak, &save_path).await;
 
-                                if let Some(window) = window_weak.upgrade() {
-                                    window.set_status_message(
-                                        format!("Saved as: {}", save_path.file_name().unwrap_or_default().to_string_lossy()).into()
-                                    );
-                                    window.set_status_type("success".into());
-                                }
+                                Self::post_status(&window_weak, format!("Saved as: {name}"), "success");
                             },
                             Err(e) => {
-                                if let Some(window) = window_weak.upgrade() {
-                                    window.set_status_message(format!("Failed to save document: {e}").into());
-                                    window.set_status_type("error".into());
-                                }
+                                Self::post_status(&window_weak, format!("Failed to save document: {e}"), "error");
                             }
                         }
                     });
@@ -397,12 +527,16 @@ impl App {
 
         self.main_window.on_file_import({
             let document_state = Arc::clone(&self.document_state);
+            let tracked_file = Arc::clone(&self.tracked_file);
+            let auto_save_config = Arc::clone(&self.auto_save_config);
             // let document_import_service = Arc::clone(&self.document_import_service); // Temporarily disabled
             let main_window_weak = main_window_weak.clone();
             let runtime_handle = self.runtime_handle.clone();
             move || {
                 if let Some(window) = main_window_weak.upgrade() {
                     let document_state = Arc::clone(&document_state);
+                    let tracked_file = Arc::clone(&tracked_file);
+                    let auto_save_config = Arc::clone(&auto_save_config);
                     // let _document_import_service = Arc::clone(&document_import_service); // Temporarily disabled
                     let window_weak = window.as_weak();
                     
@@ -443,11 +577,14 @@ impl App {
                                                 state.content = content.clone();
                                                 state.modified = false;
                                                 state.last_saved = Some(Instant::now());
+                                                state.disk_revision = Some(content_revision(content.as_bytes()));
                                                 state.language = "en".to_string();
                                             }
+                                            Self::track_file(&tracked_file, &auto_save_config, &window_weak, &import_file).await;
 
                                             // Update UI
                                             if let Some(window) = window_weak.upgrade() {
+                                                Self::refresh_outline(&window, &content);
                                                 window.set_document_content(content.into());
                                                 window.set_status_message("Document imported successfully".into());
                                                 window.set_status_type("success".into());
@@ -489,11 +626,13 @@ impl App {
                                                 state.content = markdown_content.clone();
                                                 state.modified = true; // Mark as modified since it's imported/converted
                                                 state.last_saved = None; // Not saved yet
+                                                state.disk_revision = None;
                                                 state.language = detected_language;
                                             }
 
                                             // Update UI with converted content
                                             if let Some(window) = window_weak.upgrade() {
+                                                Self::refresh_outline(&window, &markdown_content);
                                                 window.set_document_content(markdown_content.into());
                                                 window.set_status_message(
                                                     format!("Successfully imported Word document: {}", 
@@ -600,11 +739,82 @@ impl App {
         });
 
         self.main_window.on_edit_paste({
+            let document_state = Arc::clone(&self.document_state);
+            let image_store = self.image_store.clone();
+            let runtime_handle = self.runtime_handle.clone();
             let main_window_weak = main_window_weak.clone();
             move || {
-                if let Some(window) = main_window_weak.upgrade() {
-                    window.set_status_message("Paste".into());
-                    window.set_status_type("info".into());
+                let Some(window) = main_window_weak.upgrade() else {
+                    return;
+                };
+                let (path, language, selection) = match document_state.lock() {
+                    Ok(state) => (state.current_path.clone(), state.language.clone(), state.selection),
+                    Err(_) => {
+                        window.set_status_message("Failed to access document state".into());
+                        window.set_status_type("error".into());
+                        return;
+                    }
+                };
+                let content = window.get_document_content().to_string();
+                let selection = selection.unwrap_or(Selection::cursor(content.len()));
+
+                match read_clipboard() {
+                    Ok(ClipboardContent::Text(text)) => {
+                        let (new_content, cursor) = markdown_ops::insert_text(&content, selection, &text);
+                        Self::apply_paste(&window, &document_state, new_content, cursor);
+                        window.set_status_message("Pasted text".into());
+                        window.set_status_type("success".into());
+                    }
+                    Ok(ClipboardContent::Html(html)) => {
+                        // Formatting copied from browsers and Word is kept as markdown
+                        let markdown = html_to_markdown(&html);
+                        let (new_content, cursor) = markdown_ops::insert_text(&content, selection, &markdown);
+                        Self::apply_paste(&window, &document_state, new_content, cursor);
+                        window.set_status_message("Pasted formatted text as markdown".into());
+                        window.set_status_type("success".into());
+                    }
+                    Ok(ClipboardContent::Image(png)) => {
+                        // Pasted images are saved next to the document
+                        let Some(path) = path else {
+                            window.set_status_message("Save the document before pasting images".into());
+                            window.set_status_type("warning".into());
+                            return;
+                        };
+                        let document_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                        let title = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+                        let paster = ImagePaster::new(document_dir, image_store.clone());
+                        let document_state = Arc::clone(&document_state);
+                        let window_weak = window.as_weak();
+                        window.set_status_message("Saving pasted image...".into());
+                        window.set_status_type("info".into());
+
+                        runtime_handle.spawn(async move {
+                            let pasted = paster.save(Self::document_id(&path), &title, &language, Some(&png)).await;
+                            let _ = window_weak.upgrade_in_event_loop(move |window| match pasted {
+                                Ok(pasted) => {
+                                    let content = window.get_document_content().to_string();
+                                    let (new_content, selection) = pasted.insert_into(&content, selection);
+                                    Self::apply_paste(&window, &document_state, new_content, selection);
+                                    window.set_status_message(
+                                        format!("Pasted image saved as {}", pasted.path.file_name().unwrap_or_default().to_string_lossy()).into()
+                                    );
+                                    window.set_status_type("success".into());
+                                }
+                                Err(e) => {
+                                    window.set_status_message(format!("Failed to paste image: {e}").into());
+                                    window.set_status_type("error".into());
+                                }
+                            });
+                        });
+                    }
+                    Ok(ClipboardContent::Empty) => {
+                        window.set_status_message("The clipboard is empty".into());
+                        window.set_status_type("info".into());
+                    }
+                    Err(e) => {
+                        window.set_status_message(format!("Failed to read the clipboard: {e}").into());
+                        window.set_status_type("error".into());
+                    }
                 }
             }
         });
@@ -765,28 +975,102 @@ impl App {
             move |lang| {
                 if let Some(window) = main_window_weak.upgrade() {
                     window.set_current_language(lang.clone());
+                    window.set_current_language_rtl(TextDirection::for_language(&lang).is_rtl());
                     window.set_status_message(format!("Language changed to {lang}").into());
                     window.set_status_type("success".into());
                 }
             }
         });
 
+        // Remember where the editor's cursor is, for pasting
+        self.main_window.on_update_cursor_position({
+            let document_state = Arc::clone(&self.document_state);
+            move |_editor_id, position| {
+                if let Ok(mut state) = document_state.lock() {
+                    state.selection = Some(Selection::cursor(position.max(0) as usize));
+                }
+            }
+        });
+
+        self.main_window.on_editor_focus_lost({
+            let document_state = Arc::clone(&self.document_state);
+            let tracked_file = Arc::clone(&self.tracked_file);
+            let main_window_weak = main_window_weak.clone();
+            let runtime_handle = self.runtime_handle.clone();
+            move |_editor_id| {
+                let document_state = Arc::clone(&document_state);
+                let tracked_file = Arc::clone(&tracked_file);
+                let window_weak = main_window_weak.clone();
+                runtime_handle.spawn(async move {
+                    if let Some(manager) = Self::tracked_manager(&tracked_file, &document_state).await {
+                        let saved = manager.focus_lost().await;
+                        Self::report_auto_save(&window_weak, &document_state, &manager, saved).await;
+                    }
+                });
+            }
+        });
+
+        self.main_window.on_reload_from_disk({
+            let document_state = Arc::clone(&self.document_state);
+            let tracked_file = Arc::clone(&self.tracked_file);
+            let main_window_weak = main_window_weak.clone();
+            let runtime_handle = self.runtime_handle.clone();
+            move || {
+                let resolved = Self::resolve_save_conflict(main_window_weak.clone(), Arc::clone(&document_state), Arc::clone(&tracked_file), false);
+                runtime_handle.spawn(resolved);
+            }
+        });
+
+        self.main_window.on_merge_disk_changes({
+            let document_state = Arc::clone(&self.document_state);
+            let tracked_file = Arc::clone(&self.tracked_file);
+            let main_window_weak = main_window_weak.clone();
+            let runtime_handle = self.runtime_handle.clone();
+            move || {
+                let resolved = Self::resolve_save_conflict(main_window_weak.clone(), Arc::clone(&document_state), Arc::clone(&tracked_file), true);
+                runtime_handle.spawn(resolved);
+            }
+        });
+
+        self.main_window.on_update_selection({
+            let document_state = Arc::clone(&self.document_state);
+            move |_editor_id, start, end| {
+                if let Ok(mut state) = document_state.lock() {
+                    state.selection = Some(Selection::new(start.max(0) as usize, end.max(0) as usize));
+                }
+            }
+        });
+
         self.main_window.on_content_changed({
             let document_state = Arc::clone(&self.document_state);
             let auto_save_config = Arc::clone(&self.auto_save_config);
+            let tracked_file = Arc::clone(&self.tracked_file);
             let main_window_weak = main_window_weak.clone();
             let runtime_handle = self.runtime_handle.clone();
+            // Restarted on every edit so the outline is only rebuilt once typing pauses
+            let outline_timer = slint::Timer::default();
             move |content, language| {
                 if let Some(window) = main_window_weak.upgrade() {
+                    outline_timer.start(slint::TimerMode::SingleShot, OUTLINE_DEBOUNCE, {
+                        let window_weak = window.as_weak();
+                        let content = content.clone();
+                        move || {
+                            if let Some(window) = window_weak.upgrade() {
+                                Self::refresh_outline(&window, &content);
+                            }
+                        }
+                    });
+
                     let document_state = Arc::clone(&document_state);
                     let auto_save_config = Arc::clone(&auto_save_config);
+                    let tracked_file = Arc::clone(&tracked_file);
                     let window_weak = window.as_weak();
                     let content_str = content.to_string();
                     let language_str = language.to_string();
                     
                     runtime_handle.spawn(async move {
-                        // Update document state
-                        let should_auto_save = if let Ok(mut state) = document_state.lock() {
+                        // Update document state, and find when to auto-save if at all
+                        let auto_save_after = if let Ok(mut state) = document_state.lock() {
                             let content_changed = state.content != content_str;
                             
                             if content_changed {
@@ -795,16 +1079,17 @@ impl App {
                                 state.language = language_str.clone();
                                 
                                 // Check if auto-save should be triggered
-                                if let Ok(config) = auto_save_config.lock() {
-                                    config.enabled && state.current_path.is_some()
-                                } else {
-                                    false
+                                match auto_save_config.lock() {
+                                    Ok(config) if config.enabled && state.current_path.is_some() => {
+                                        Some(Duration::from_secs(config.interval_seconds))
+                                    }
+                                    _ => None,
                                 }
                             } else {
-                                false
+                                None
                             }
                         } else {
-                            false
+                            None
                         };
 
                         // Update UI status (don't set document content to avoid circular callback)
@@ -814,9 +1099,9 @@ impl App {
                         }
 
                         // Trigger auto-save if conditions are met
-                        if should_auto_save {
-                            // Delay auto-save to avoid saving on every keystroke
-                            tokio::time::sleep(Duration::from_secs(2)).await;
+                        if let Some(interval) = auto_save_after {
+                            // Wait out the interval to avoid saving on every keystroke
+                            tokio::time::sleep(interval).await;
                             
                             // Check if content is still the same (user hasn't made more changes)
                             let should_still_save = if let Ok(state) = document_state.lock() {
@@ -825,32 +1110,11 @@ impl App {
                                 false
                             };
 
+                            // Only overwrite the file if nobody else changed it since it was loaded or saved
                             if should_still_save {
-                                // Perform auto-save
-                                let (path, content_to_save) = {
-                                    let state = document_state.lock().unwrap();
-                                    (state.current_path.as_ref().unwrap().clone(), state.content.clone())
-                                };
-
-                                match tokio::fs::write(&path, &content_to_save).await {
-                                    Ok(_) => {
-                                        // Update state
-                                        if let Ok(mut state) = document_state.lock() {
-                                            state.modified = false;
-                                            state.last_saved = Some(Instant::now());
-                                        }
-
-                                        if let Some(window) = window_weak.upgrade() {
-                                            window.set_status_message("Auto-saved".into());
-                                            window.set_status_type("success".into());
-                                        }
-                                    },
-                                    Err(e) => {
-                                        if let Some(window) = window_weak.upgrade() {
-                                            window.set_status_message(format!("Auto-save failed: {e}").into());
-                                            window.set_status_type("warning".into());
-                                        }
-                                    }
+                                if let Some(manager) = Self::tracked_manager(&tracked_file, &document_state).await {
+                                    let saved = manager.auto_save().await;
+                                    Self::report_auto_save(&window_weak, &document_state, &manager, saved).await;
                                 }
                             }
                         }
@@ -888,6 +1152,69 @@ impl App {
         
         // Project Browser Callbacks
         self.setup_project_browser_callbacks();
+
+        // Keyboard shortcut callbacks
+        self.setup_shortcut_callbacks();
+    }
+
+    /// Set up keyboard shortcut dispatch through the keymap
+    fn setup_shortcut_callbacks(&self) {
+        let main_window_weak = self.main_window.as_weak();
+
+        self.main_window.on_shortcut_pressed({
+            let keymap = Arc::clone(&self.keymap);
+            move |text, control, shift, alt| {
+                let Some(window) = main_window_weak.upgrade() else {
+                    return false;
+                };
+                KeyChord::from_event(&text, control, shift, alt)
+                    .and_then(|chord| keymap.action_for(&chord))
+                    .map(|action| Self::dispatch_action(&window, action))
+                    .unwrap_or(false)
+            }
+        });
+    }
+
+    /// Run the GUI action registered under `action`, returning whether it was handled
+    fn dispatch_action(window: &MainWindow, action: &str) -> bool {
+        match action {
+            "new_file" => window.invoke_file_new(),
+            "open_file" => window.invoke_file_open(),
+            "save_file" => window.invoke_file_save(),
+            "export" => {
+                window.set_show_export_dialog(true);
+                window.invoke_open_export_dialog();
+            }
+            "toggle_mode" => window.invoke_toggle_mode(),
+            "switch_layout_1" => window.invoke_set_layout("single".into()),
+            "switch_layout_2" => window.invoke_set_layout("horizontal".into()),
+            "switch_layout_3" => window.invoke_set_layout("vertical".into()),
+            "switch_layout_4" => window.invoke_set_layout("grid_2x2".into()),
+            "bilingual_review" => window.invoke_set_layout("bilingual".into()),
+            "toggle_ribbon" => window.invoke_toggle_ribbon(),
+            "open_project_browser" => window.invoke_open_project_browser(),
+            "toggle_chunk_linking" => {
+                let show = !window.get_show_chunk_linking();
+                window.set_show_chunk_linking(show);
+                if show {
+                    window.invoke_show_chunk_linking_panel();
+                } else {
+                    window.invoke_hide_chunk_linking_panel();
+                }
+            }
+            "toggle_outline" => window.set_show_outline(!window.get_show_outline()),
+            "bold" => window.invoke_format_bold(),
+            "italic" => window.invoke_format_italic(),
+            "underline" => window.invoke_format_underline(),
+            "next_editor" => window.invoke_tab_to_next_editor(),
+            "previous_editor" => window.invoke_tab_to_previous_editor(),
+            "focus_editor_1" => window.invoke_focus_editor_by_index(0),
+            "focus_editor_2" => window.invoke_focus_editor_by_index(1),
+            "focus_editor_3" => window.invoke_focus_editor_by_index(2),
+            "focus_editor_4" => window.invoke_focus_editor_by_index(3),
+            _ => return false,
+        }
+        true
     }
 
     /// Set up project wizard specific callbacks
@@ -1304,7 +1631,7 @@ impl App {
 
     /// Try to reopen the last project that was worked on
     async fn try_reopen_last_project(&self) -> Result<()> {
-        if let Ok(last_project_path) = self.get_last_project_path() {
+        if let Ok(last_project_path) = Self::get_last_project_path() {
             if last_project_path.exists() {
                 match self.project_service.load_project(&last_project_path).await {
                     Ok(project) => {
@@ -1326,7 +1653,7 @@ impl App {
     }
 
     /// Get the path to the last opened project from settings
-    fn get_last_project_path(&self) -> Result<std::path::PathBuf> {
+    fn get_last_project_path() -> Result<std::path::PathBuf> {
         let settings_dir = dirs::config_dir()
             .ok_or_else(|| TradocumentError::IoError(std::io::Error::new(std::io::ErrorKind::NotFound, "Could not find config directory")))?
             .join("tradocflow");
@@ -1504,6 +1831,189 @@ impl App {
         });
     }
     
+    /// Set the status bar from any thread, once the event loop gets to it
+    fn post_status(window_weak: &slint::Weak<MainWindow>, message: String, status_type: &'static str) {
+        let _ = window_weak.upgrade_in_event_loop(move |window| {
+            window.set_status_message(message.into());
+            window.set_status_type(status_type.into());
+        });
+    }
+
+    /// Have auto-save track `path` as the version on disk, after the editor
+    /// opened or saved it. A conflict shown for an earlier version is over.
+    async fn track_file(
+        tracked_file: &TrackedFile,
+        auto_save_config: &Arc<Mutex<AutoSaveConfig>>,
+        window_weak: &slint::Weak<MainWindow>,
+        path: &Path,
+    ) {
+        let config = auto_save_config.lock().map(|config| config.clone()).unwrap_or_default();
+        let manager = match DocumentStateManager::new(None).await {
+            Ok(manager) => manager.with_auto_save_config(config),
+            Err(e) => {
+                log::warn!("Auto-save cannot track {}: {e}", path.display());
+                return;
+            }
+        };
+        if let Err(e) = manager.load_from_file(path).await {
+            log::warn!("Auto-save cannot track {}: {e}", path.display());
+            return;
+        }
+        if let Ok(mut tracked) = tracked_file.lock() {
+            *tracked = Some(Arc::new(manager));
+        }
+        let _ = window_weak.upgrade_in_event_loop(|window| window.set_save_conflict(false));
+    }
+
+    /// The manager tracking the file the editor shows, given the editor's
+    /// unsaved edits. None when that file was never opened or saved here.
+    async fn tracked_manager(tracked_file: &TrackedFile, document_state: &Arc<Mutex<DocumentState>>) -> Option<Arc<DocumentStateManager>> {
+        let manager = tracked_file.lock().ok()?.clone()?;
+        let (path, edits) = {
+            let state = document_state.lock().ok()?;
+            (state.current_path.clone()?, state.modified.then(|| state.content.clone()))
+        };
+        if manager.get_state().await.file_path.as_deref() != Some(path.as_path()) {
+            return None;
+        }
+        if let Some(edits) = edits {
+            if edits != manager.get_content().await {
+                if let Err(e) = manager.set_content(edits).await {
+                    log::warn!("Auto-save of {} skipped: {e}", path.display());
+                    return None;
+                }
+            }
+        }
+        Some(manager)
+    }
+
+    /// Show what an auto-save did. On a conflict the edits stay unsaved and
+    /// the status bar offers to reload or merge.
+    async fn report_auto_save(
+        window_weak: &slint::Weak<MainWindow>,
+        document_state: &Arc<Mutex<DocumentState>>,
+        manager: &DocumentStateManager,
+        saved: std::result::Result<SaveOutcome, DocumentStateError>,
+    ) {
+        match saved {
+            Ok(SaveOutcome::Saved) => {
+                let revision = manager.get_state().await.disk_revision;
+                if let Ok(mut state) = document_state.lock() {
+                    state.modified = false;
+                    state.last_saved = Some(Instant::now());
+                    state.disk_revision = revision;
+                }
+                Self::post_status(window_weak, "Auto-saved".to_string(), "success");
+            }
+            Ok(SaveOutcome::Skipped) => {}
+            Ok(SaveOutcome::Conflict(change)) => {
+                let name = change.path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                let message = format!("Not auto-saved: {name} was changed on disk. Your edits are kept; reload or merge before saving");
+                let _ = window_weak.upgrade_in_event_loop(move |window| {
+                    window.set_save_conflict(true);
+                    window.set_status_message(message.into());
+                    window.set_status_type("warning".into());
+                });
+            }
+            Err(e) => Self::post_status(window_weak, format!("Auto-save failed: {e}"), "warning"),
+        }
+    }
+
+    /// Settle an auto-save conflict by reloading the file, dropping the
+    /// edits, or by merging the changes on disk into them
+    async fn resolve_save_conflict(
+        window_weak: slint::Weak<MainWindow>,
+        document_state: Arc<Mutex<DocumentState>>,
+        tracked_file: TrackedFile,
+        merge: bool,
+    ) {
+        let Some(manager) = tracked_file.lock().ok().and_then(|tracked| tracked.clone()) else {
+            return;
+        };
+        let resolved = if merge {
+            manager.merge_external_change().await
+        } else {
+            manager.reload_from_disk().await
+        };
+        if let Err(e) = resolved {
+            Self::post_status(&window_weak, format!("Failed to resolve the conflict: {e}"), "error");
+            return;
+        }
+
+        let content = manager.get_content().await;
+        let file = manager.get_state().await;
+        if let Ok(mut state) = document_state.lock() {
+            state.content = content.clone();
+            state.modified = file.is_modified;
+            state.disk_revision = file.disk_revision;
+        }
+        let (message, status_type) = if merge {
+            ("Merged the changes on disk into your edits; check for conflict markers, then save", "warning")
+        } else {
+            ("Reloaded the file from disk", "success")
+        };
+        let _ = window_weak.upgrade_in_event_loop(move |window| {
+            Self::refresh_outline(&window, &content);
+            window.set_document_content(content.into());
+            window.set_save_conflict(false);
+            window.set_status_message(message.into());
+            window.set_status_type(status_type.into());
+        });
+    }
+
+    /// Show in the status bar how far saving `name` has got. The save
+    /// reports to the returned sender; once that is dropped, the returned
+    /// task ends after posting the last report, so later messages follow it.
+    fn show_save_progress(
+        window_weak: slint::Weak<MainWindow>,
+        name: String,
+    ) -> (watch::Sender<SaveProgress>, tokio::task::JoinHandle<()>) {
+        let (progress, mut reports) = watch::channel(SaveProgress::default());
+        let shown = tokio::spawn(async move {
+            while reports.changed().await.is_ok() {
+                let percent = reports.borrow_and_update().fraction() * 100.0;
+                Self::post_status(&window_weak, format!("Saving {name}: {percent:.0}%"), "info");
+            }
+        });
+        (progress, shown)
+    }
+
+    /// Show pasted content in the editor and record it, with the selection
+    /// after the paste, in the document state
+    fn apply_paste(window: &MainWindow, document_state: &Mutex<DocumentState>, content: String, selection: Selection) {
+        Self::refresh_outline(window, &content);
+        if let Ok(mut state) = document_state.lock() {
+            state.content = content.clone();
+            state.modified = true;
+            state.selection = Some(selection);
+        }
+        window.set_document_content(content.into());
+    }
+
+    /// Id the image store keys the screenshots of the document at `path` by,
+    /// the same every time the document is opened
+    fn document_id(path: &Path) -> Uuid {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        Uuid::new_v5(&Uuid::NAMESPACE_URL, path.to_string_lossy().as_bytes())
+    }
+
+    /// Append `placeholder` to the document and format it with a
+    /// [`markdown_ops`] toggle. The window doesn't expose the editor
+    /// selection yet, so the toolbar formats new placeholder text.
+    fn append_formatted(
+        content: &str,
+        placeholder: &str,
+        separator: &str,
+        toggle: impl Fn(&str, Selection) -> (String, Selection),
+    ) -> String {
+        let (text, start) = if content.trim().is_empty() {
+            (placeholder.to_string(), 0)
+        } else {
+            (format!("{content}{separator}{placeholder}"), content.len() + separator.len())
+        };
+        toggle(&text, Selection::new(start, text.len())).0
+    }
+
     /// Set up text formatting callbacks
     fn setup_formatting_callbacks(&self) {
         let main_window_weak = self.main_window.as_weak();
@@ -1512,13 +2022,9 @@ impl App {
             let main_window_weak = main_window_weak.clone();
             move || {
                 if let Some(window) = main_window_weak.upgrade() {
-                    // Get current content and apply bold formatting
                     let current_content = window.get_document_content().to_string();
-                    let new_content = if current_content.trim().is_empty() {
-                        "**Bold text here**".to_string()
-                    } else {
-                        format!("{}\n**Bold text**", current_content)
-                    };
+                    let new_content = Self::append_formatted(&current_content, "Bold text", "\n", markdown_ops::toggle_bold);
+                    Self::refresh_outline(&window, &new_content);
                     window.set_document_content(new_content.into());
                     
                     window.set_status_message("Bold formatting applied".into());
@@ -1531,13 +2037,9 @@ impl App {
             let main_window_weak = main_window_weak.clone();
             move || {
                 if let Some(window) = main_window_weak.upgrade() {
-                    // Get current content and apply italic formatting
                     let current_content = window.get_document_content().to_string();
-                    let new_content = if current_content.trim().is_empty() {
-                        "*Italic text here*".to_string()
-                    } else {
-                        format!("{}\n*Italic text*", current_content)
-                    };
+                    let new_content = Self::append_formatted(&current_content, "Italic text", "\n", markdown_ops::toggle_italic);
+                    Self::refresh_outline(&window, &new_content);
                     window.set_document_content(new_content.into());
                     
                     window.set_status_message("Italic formatting applied".into());
@@ -1560,14 +2062,12 @@ impl App {
             let main_window_weak = main_window_weak.clone();
             move |level| {
                 if let Some(window) = main_window_weak.upgrade() {
-                    // Get current content and apply heading formatting
                     let current_content = window.get_document_content().to_string();
-                    let heading_prefix = "#".repeat(level as usize);
-                    let new_content = if current_content.trim().is_empty() {
-                        format!("{} Heading Level {}", heading_prefix, level)
-                    } else {
-                        format!("{}\n{} Heading Level {}", current_content, heading_prefix, level)
-                    };
+                    let placeholder = format!("Heading Level {level}");
+                    let new_content = Self::append_formatted(&current_content, &placeholder, "\n", |text, selection| {
+                        markdown_ops::toggle_heading(text, selection, level.clamp(1, 6) as u8)
+                    });
+                    Self::refresh_outline(&window, &new_content);
                     window.set_document_content(new_content.into());
                     
                     window.set_status_message(format!("Heading {level} applied").into());
@@ -1587,6 +2087,7 @@ impl App {
                     } else {
                         format!("{}\n`code`", current_content)
                     };
+                    Self::refresh_outline(&window, &new_content);
                     window.set_document_content(new_content.into());
                     
                     window.set_status_message("Code formatting applied".into());
@@ -1610,13 +2111,11 @@ impl App {
             let main_window_weak = main_window_weak.clone();
             move || {
                 if let Some(window) = main_window_weak.upgrade() {
-                    // Get current content and insert bullet list
                     let current_content = window.get_document_content().to_string();
-                    let new_content = if current_content.trim().is_empty() {
-                        "- First item\n- Second item\n- Third item".to_string()
-                    } else {
-                        format!("{}\n\n- New item\n- Another item", current_content)
-                    };
+                    let new_content = Self::append_formatted(&current_content, "First item\nSecond item\nThird item", "\n\n", |text, selection| {
+                        markdown_ops::toggle_list(text, selection, ListKind::Bullet)
+                    });
+                    Self::refresh_outline(&window, &new_content);
                     window.set_document_content(new_content.into());
                     
                     window.set_status_message("Bullet list inserted".into());
@@ -1629,13 +2128,11 @@ impl App {
             let main_window_weak = main_window_weak.clone();
             move || {
                 if let Some(window) = main_window_weak.upgrade() {
-                    // Get current content and insert numbered list
                     let current_content = window.get_document_content().to_string();
-                    let new_content = if current_content.trim().is_empty() {
-                        "1. First item\n2. Second item\n3. Third item".to_string()
-                    } else {
-                        format!("{}\n\n1. First item\n2. Second item", current_content)
-                    };
+                    let new_content = Self::append_formatted(&current_content, "First item\nSecond item\nThird item", "\n\n", |text, selection| {
+                        markdown_ops::toggle_list(text, selection, ListKind::Numbered)
+                    });
+                    Self::refresh_outline(&window, &new_content);
                     window.set_document_content(new_content.into());
                     
                     window.set_status_message("Numbered list inserted".into());
@@ -1941,11 +2438,17 @@ impl App {
         
         self.main_window.on_set_layout({
             let main_window_weak = main_window_weak.clone();
+            let project_service = Arc::clone(&self.project_service);
+            let runtime_handle = self.runtime_handle.clone();
             move |layout| {
                 if let Some(window) = main_window_weak.upgrade() {
                     window.set_current_layout(layout.clone());
                     window.set_status_message(format!("Layout changed to {layout}").into());
                     window.set_status_type("success".into());
+                    if layout == "bilingual" {
+                        // Compare the open document against the translation pane
+                        Self::refresh_bilingual_review(&window, Arc::clone(&project_service), &runtime_handle);
+                    }
                 }
             }
         });
@@ -2045,9 +2548,11 @@ impl App {
             state.content = "# New Document\n\nStart editing here...".to_string();
             state.modified = false;
             state.last_saved = None;
+            state.disk_revision = None;
             state.language = "en".to_string();
 
             // Update UI
+            Self::refresh_outline(&self.main_window, &state.content);
             self.main_window.set_document_content(state.content.clone().into());
             self.show_status_message("New document created", "success");
         }
@@ -2081,10 +2586,12 @@ impl App {
             state.content = content.clone();
             state.modified = false;
             state.last_saved = Some(Instant::now());
+            state.disk_revision = Some(content_revision(content.as_bytes()));
             state.language = "en".to_string(); // Could be detected from file
         }
 
         // Update UI
+        Self::refresh_outline(&self.main_window, &content);
         self.main_window.set_document_content(content.into());
         self.show_status_message(
             &format!("Opened: {}", path.file_name().unwrap_or_default().to_string_lossy()),
@@ -2112,16 +2619,22 @@ impl App {
         };
 
         // Save to filesystem
-        tokio::fs::write(&path, &content).await
-            .map_err(TradocumentError::IoError)?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let (progress, shown) = Self::show_save_progress(self.main_window.as_weak(), name);
+        let saved = write_atomic(&path, content.as_bytes(), Some(&progress)).await;
+        drop(progress);
+        let _ = shown.await;
+        saved.map_err(TradocumentError::IoError)?;
 
         // Update state
         if let Ok(mut state) = self.document_state.lock() {
             state.modified = false;
             state.last_saved = Some(Instant::now());
+            state.disk_revision = Some(content_revision(content.as_bytes()));
         }
 
-        self.show_status_message("Document saved successfully", "success");
+        // Queued after the progress reports, so shown after them
+        Self::post_status(&self.main_window.as_weak(), "Document saved successfully".to_string(), "success");
         Ok(())
     }
 
@@ -2140,20 +2653,22 @@ impl App {
             };
 
             // Save to filesystem
-            tokio::fs::write(&path, &content).await
-                .map_err(TradocumentError::IoError)?;
+            let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let (progress, shown) = Self::show_save_progress(self.main_window.as_weak(), name.clone());
+            let saved = write_atomic(&path, content.as_bytes(), Some(&progress)).await;
+            drop(progress);
+            let _ = shown.await;
+            saved.map_err(TradocumentError::IoError)?;
 
             // Update state
             if let Ok(mut state) = self.document_state.lock() {
                 state.current_path = Some(path.clone());
                 state.modified = false;
                 state.last_saved = Some(Instant::now());
+                state.disk_revision = Some(content_revision(content.as_bytes()));
             }
 
-            self.show_status_message(
-                &format!("Saved as: {}", path.file_name().unwrap_or_default().to_string_lossy()),
-                "success"
-            );
+            Self::post_status(&self.main_window.as_weak(), format!("Saved as: {name}"), "success");
         }
         
         Ok(())
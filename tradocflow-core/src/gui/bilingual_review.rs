@@ -0,0 +1,191 @@
+//! Paragraph alignment for the bilingual review (split-view diff) mode.
+//!
+//! The alignment is pure logic so it can be computed and tested without Slint;
+//! `App` converts the result into the `BilingualParagraph` model shown in the UI.
+//! The panes keep their scroll positions in proportion within the Slint view.
+
+use std::collections::HashMap;
+
+use crate::models::document::TranslationUnit;
+
+/// Review state of an aligned paragraph pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParagraphStatus {
+    /// Source and target both present, nothing to flag
+    Aligned,
+    /// The target language has no paragraph at this index, or it is empty
+    MissingTarget,
+    /// The target has a paragraph the source does not
+    MissingSource,
+    /// The source text differs from the one the target was translated from
+    SourceChanged,
+}
+
+impl ParagraphStatus {
+    /// Identifier used by the Slint view to pick a highlight colour
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParagraphStatus::Aligned => "aligned",
+            ParagraphStatus::MissingTarget => "missing_target",
+            ParagraphStatus::MissingSource => "missing_source",
+            ParagraphStatus::SourceChanged => "source_changed",
+        }
+    }
+
+    /// Whether the pair should be highlighted for the reviewer
+    pub fn needs_attention(&self) -> bool {
+        !matches!(self, ParagraphStatus::Aligned)
+    }
+}
+
+/// A source/target paragraph pair at the same index
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedParagraph {
+    pub index: usize,
+    pub source: Option<String>,
+    pub target: Option<String>,
+    pub status: ParagraphStatus,
+}
+
+/// Split markdown into paragraphs separated by one or more blank lines
+pub fn split_paragraphs(markdown: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for line in markdown.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(current.join("\n"));
+                current.clear();
+            }
+        } else {
+            current.push(line.trim_end());
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current.join("\n"));
+    }
+
+    paragraphs
+}
+
+/// Align paragraphs of two languages by index.
+///
+/// `units` supplies translation memory metadata: when a unit records the
+/// target paragraph as its translation but was made from a different source
+/// text, the pair is flagged as [`ParagraphStatus::SourceChanged`].
+pub fn align_paragraphs(
+    source: &str,
+    target: &str,
+    target_language: &str,
+    units: &[TranslationUnit],
+) -> Vec<AlignedParagraph> {
+    let source_paragraphs = split_paragraphs(source);
+    let target_paragraphs = split_paragraphs(target);
+
+    // Target text -> source text it was translated from
+    let translated_from: HashMap<&str, &str> = units
+        .iter()
+        .filter_map(|unit| {
+            unit.translations
+                .get(target_language)
+                .map(|version| (version.text.trim(), unit.source_text.trim()))
+        })
+        .collect();
+
+    let count = source_paragraphs.len().max(target_paragraphs.len());
+    (0..count)
+        .map(|index| {
+            let source = source_paragraphs.get(index).cloned();
+            let target = target_paragraphs.get(index).cloned();
+
+            let status = match (&source, &target) {
+                (None, _) => ParagraphStatus::MissingSource,
+                (Some(_), None) => ParagraphStatus::MissingTarget,
+                (Some(_), Some(t)) if t.trim().is_empty() => ParagraphStatus::MissingTarget,
+                (Some(s), Some(t)) => match translated_from.get(t.trim()) {
+                    Some(original) if *original != s.trim() => ParagraphStatus::SourceChanged,
+                    _ => ParagraphStatus::Aligned,
+                },
+            };
+
+            AlignedParagraph { index, source, target, status }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::document::{TranslationStatus, TranslationVersion};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn unit(source: &str, language: &str, target: &str) -> TranslationUnit {
+        let mut unit = TranslationUnit::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "en".to_string(),
+            source.to_string(),
+            language.to_string(),
+            target.to_string(),
+            1.0,
+            None,
+        )
+        .unwrap();
+        unit.translations.insert(
+            language.to_string(),
+            TranslationVersion {
+                text: target.to_string(),
+                translator: "tester".to_string(),
+                status: TranslationStatus::Completed,
+                quality_score: None,
+                created_at: Utc::now(),
+                reviewed_at: None,
+                reviewer: None,
+            },
+        );
+        unit
+    }
+
+    #[test]
+    fn test_split_paragraphs() {
+        let paragraphs = split_paragraphs("# Title\n\nFirst line\nsecond line\n\n\n\nLast  \n");
+        assert_eq!(paragraphs, vec!["# Title", "First line\nsecond line", "Last"]);
+    }
+
+    #[test]
+    fn test_missing_target_paragraph() {
+        let aligned = align_paragraphs("One\n\nTwo\n\nThree", "Uno\n\nDos", "es", &[]);
+
+        assert_eq!(aligned.len(), 3);
+        assert_eq!(aligned[0].status, ParagraphStatus::Aligned);
+        assert_eq!(aligned[1].status, ParagraphStatus::Aligned);
+        assert_eq!(aligned[2].status, ParagraphStatus::MissingTarget);
+        assert_eq!(aligned[2].source.as_deref(), Some("Three"));
+        assert_eq!(aligned[2].target, None);
+    }
+
+    #[test]
+    fn test_mismatched_count_with_extra_target() {
+        let aligned = align_paragraphs("One", "Uno\n\nDos\n\nTres", "es", &[]);
+
+        assert_eq!(aligned.len(), 3);
+        assert_eq!(aligned[0].status, ParagraphStatus::Aligned);
+        assert!(aligned[1..].iter().all(|p| p.status == ParagraphStatus::MissingSource));
+        assert_eq!(aligned[2].target.as_deref(), Some("Tres"));
+    }
+
+    #[test]
+    fn test_source_changed_since_translation() {
+        let units = vec![
+            unit("Hello world", "de", "Hallo Welt"),
+            unit("Goodbye", "de", "Auf Wiedersehen"),
+        ];
+        let aligned = align_paragraphs("Hello brave world\n\nGoodbye", "Hallo Welt\n\nAuf Wiedersehen", "de", &units);
+
+        assert_eq!(aligned[0].status, ParagraphStatus::SourceChanged);
+        assert_eq!(aligned[1].status, ParagraphStatus::Aligned);
+    }
+}
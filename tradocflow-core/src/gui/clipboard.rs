@@ -0,0 +1,63 @@
+use image::{ImageFormat, RgbaImage};
+use std::io::Cursor;
+
+/// What the system clipboard holds, as the editor pastes it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardContent {
+    /// An image, encoded as PNG
+    Image(Vec<u8>),
+    /// Rich text copied from a browser or word processor
+    Html(String),
+    Text(String),
+    Empty,
+}
+
+/// Read the system clipboard, preferring an image over rich text, and rich
+/// text over plain text
+pub fn read_clipboard() -> Result<ClipboardContent, arboard::Error> {
+    let mut clipboard = arboard::Clipboard::new()?;
+    match clipboard.get_image() {
+        Ok(image) => {
+            let png = encode_png(image.width, image.height, image.bytes.into_owned())
+                .ok_or(arboard::Error::ConversionFailure)?;
+            return Ok(ClipboardContent::Image(png));
+        }
+        Err(arboard::Error::ContentNotAvailable) => {}
+        Err(e) => return Err(e),
+    }
+    match clipboard.get().html() {
+        Ok(html) if !html.trim().is_empty() => return Ok(ClipboardContent::Html(html)),
+        Ok(_) | Err(arboard::Error::ContentNotAvailable) => {}
+        Err(e) => return Err(e),
+    }
+    match clipboard.get_text() {
+        Ok(text) => Ok(ClipboardContent::Text(text)),
+        Err(arboard::Error::ContentNotAvailable) => Ok(ClipboardContent::Empty),
+        Err(e) => Err(e),
+    }
+}
+
+/// RGBA pixels, as the clipboard hands images out, encoded as PNG
+fn encode_png(width: usize, height: usize, rgba: Vec<u8>) -> Option<Vec<u8>> {
+    let image = RgbaImage::from_raw(width.try_into().ok()?, height.try_into().ok()?, rgba)?;
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).ok()?;
+    Some(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clipboard_pixels_encoded_as_png() {
+        let rgba: Vec<u8> = (0..4 * 3 * 2).map(|i| i as u8).collect();
+        let png = encode_png(3, 2, rgba.clone()).unwrap();
+
+        let decoded = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (3, 2));
+        assert_eq!(decoded.into_raw(), rgba);
+        // Fewer bytes than the size needs
+        assert_eq!(encode_png(3, 2, vec![0; 5]), None);
+    }
+}
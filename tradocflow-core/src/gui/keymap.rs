@@ -0,0 +1,331 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Default bindings. Most mirror the shortcuts historically hardcoded in
+/// `main.slint`. Bold, italic and underline are new: `main.slint` left
+/// Ctrl+B/I/U to the focused text editor, whereas these bindings catch them
+/// for the whole window and run the formatting actions. Remap them in
+/// `keymap.toml` to give the chords back to the editor.
+const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    ("new_file", "Ctrl+N"),
+    ("open_file", "Ctrl+O"),
+    ("save_file", "Ctrl+S"),
+    ("export", "Ctrl+E"),
+    ("toggle_mode", "Ctrl+M"),
+    ("switch_layout_1", "Ctrl+1"),
+    ("switch_layout_2", "Ctrl+2"),
+    ("switch_layout_3", "Ctrl+3"),
+    ("switch_layout_4", "Ctrl+4"),
+    ("bilingual_review", "Ctrl+5"),
+    ("toggle_ribbon", "Ctrl+R"),
+    ("open_project_browser", "Ctrl+P"),
+    ("toggle_chunk_linking", "Ctrl+L"),
+    ("toggle_outline", "Ctrl+Shift+O"),
+    ("bold", "Ctrl+B"),
+    ("italic", "Ctrl+I"),
+    ("underline", "Ctrl+U"),
+    ("next_editor", "Ctrl+Tab"),
+    ("previous_editor", "Ctrl+Shift+Tab"),
+    ("focus_editor_1", "Alt+1"),
+    ("focus_editor_2", "Alt+2"),
+    ("focus_editor_3", "Alt+3"),
+    ("focus_editor_4", "Alt+4"),
+];
+
+/// A key combination such as `Ctrl+Shift+Tab`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyChord {
+    pub control: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// Normalized key name: a lowercase character or a named key like `Tab`
+    pub key: String,
+}
+
+impl KeyChord {
+    /// Parse a chord written as modifiers and a key joined by `+`, e.g. `Ctrl+M`
+    pub fn parse(input: &str) -> Option<Self> {
+        let parts: Vec<&str> = input.split('+').map(str::trim).collect();
+        let (key, modifiers) = parts.split_last()?;
+
+        let mut chord = KeyChord {
+            control: false,
+            shift: false,
+            alt: false,
+            key: Self::normalize_key(key)?,
+        };
+
+        for modifier in modifiers {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.control = true,
+                "shift" => chord.shift = true,
+                "alt" => chord.alt = true,
+                _ => return None,
+            }
+        }
+
+        // A bare key would swallow ordinary typing in the editor
+        if !chord.control && !chord.alt {
+            return None;
+        }
+
+        Some(chord)
+    }
+
+    /// Build a chord from a Slint key event
+    pub fn from_event(text: &str, control: bool, shift: bool, alt: bool) -> Option<Self> {
+        let key = match text {
+            "\t" | "\u{19}" => "Tab".to_string(),
+            other => Self::normalize_key(other)?,
+        };
+
+        Some(KeyChord { control, shift, alt, key })
+    }
+
+    fn normalize_key(key: &str) -> Option<String> {
+        let mut chars = key.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if !c.is_control() && !c.is_whitespace() => {
+                Some(c.to_lowercase().collect())
+            }
+            _ if key.eq_ignore_ascii_case("tab") => Some("Tab".to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.control {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.key.chars().count() == 1 {
+            write!(f, "{}", self.key.to_uppercase())
+        } else {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+/// Problems found while loading a user keymap; none of them are fatal
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum KeymapIssue {
+    #[error("Keymap file could not be read: {0}")]
+    Unreadable(String),
+    #[error("Unknown keymap action '{0}'")]
+    UnknownAction(String),
+    #[error("Invalid key chord '{chord}' for action '{action}'")]
+    InvalidChord { action: String, chord: String },
+    #[error("Key chord {chord} is bound to multiple actions: {}", actions.join(", "))]
+    Conflict { chord: String, actions: Vec<String> },
+}
+
+/// On-disk representation of `keymap.toml`
+#[derive(Debug, Default, Deserialize)]
+struct KeymapFile {
+    #[serde(default)]
+    bindings: BTreeMap<String, String>,
+}
+
+/// Maps GUI action names to key chords
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeymapConfig {
+    bindings: BTreeMap<String, KeyChord>,
+}
+
+impl Default for KeymapConfig {
+    fn default() -> Self {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .map(|(action, chord)| {
+                (action.to_string(), KeyChord::parse(chord).expect("default chords are valid"))
+            })
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl KeymapConfig {
+    /// Load the user keymap from the config directory, falling back to defaults
+    pub fn load() -> (Self, Vec<KeymapIssue>) {
+        match Self::config_path() {
+            Some(path) if path.exists() => Self::load_from_path(&path),
+            _ => (Self::default(), Vec::new()),
+        }
+    }
+
+    /// Load a keymap from a specific TOML file
+    pub fn load_from_path(path: &Path) -> (Self, Vec<KeymapIssue>) {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) => (Self::default(), vec![KeymapIssue::Unreadable(e.to_string())]),
+        }
+    }
+
+    /// Location of the user keymap file
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tradocflow").join("keymap.toml"))
+    }
+
+    /// Parse a keymap, reporting invalid or conflicting bindings.
+    ///
+    /// Any rejected binding leaves its action on the default chord.
+    pub fn parse(content: &str) -> (Self, Vec<KeymapIssue>) {
+        let mut config = Self::default();
+        let mut issues = Vec::new();
+
+        let file: KeymapFile = match toml::from_str(content) {
+            Ok(file) => file,
+            Err(e) => return (config, vec![KeymapIssue::Unreadable(e.to_string())]),
+        };
+
+        let mut overridden = BTreeSet::new();
+        for (action, chord) in file.bindings {
+            if !config.bindings.contains_key(&action) {
+                issues.push(KeymapIssue::UnknownAction(action));
+                continue;
+            }
+            match KeyChord::parse(&chord) {
+                Some(parsed) => {
+                    config.bindings.insert(action.clone(), parsed);
+                    overridden.insert(action);
+                }
+                None => issues.push(KeymapIssue::InvalidChord { action, chord }),
+            }
+        }
+
+        // Revert user overrides involved in a conflict until every chord is unique.
+        // Defaults never conflict with each other, so this always terminates.
+        while let Some((chord, actions)) = config.first_conflict(&overridden) {
+            for action in &actions {
+                if overridden.remove(action) {
+                    config.bindings.insert(action.clone(), Self::default_chord(action));
+                }
+            }
+            issues.push(KeymapIssue::Conflict {
+                chord: chord.to_string(),
+                actions,
+            });
+        }
+
+        (config, issues)
+    }
+
+    /// Find a chord shared by several actions where at least one is a user override
+    fn first_conflict(&self, overridden: &BTreeSet<String>) -> Option<(KeyChord, Vec<String>)> {
+        let mut by_chord: BTreeMap<&KeyChord, Vec<String>> = BTreeMap::new();
+        for (action, chord) in &self.bindings {
+            by_chord.entry(chord).or_default().push(action.clone());
+        }
+
+        by_chord
+            .into_iter()
+            .find(|(_, actions)| actions.len() > 1 && actions.iter().any(|a| overridden.contains(a)))
+            .map(|(chord, actions)| (chord.clone(), actions))
+    }
+
+    fn default_chord(action: &str) -> KeyChord {
+        DEFAULT_BINDINGS
+            .iter()
+            .find(|(name, _)| *name == action)
+            .and_then(|(_, chord)| KeyChord::parse(chord))
+            .expect("default chords are valid")
+    }
+
+    /// Action bound to a chord, if any
+    pub fn action_for(&self, chord: &KeyChord) -> Option<&str> {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| *bound == chord)
+            .map(|(action, _)| action.as_str())
+    }
+
+    /// Chord bound to an action, if the action exists
+    pub fn chord_for(&self, action: &str) -> Option<&KeyChord> {
+        self.bindings.get(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_match_historic_shortcuts() {
+        let keymap = KeymapConfig::default();
+        let chord = KeyChord::parse("Ctrl+M").unwrap();
+        assert_eq!(keymap.action_for(&chord), Some("toggle_mode"));
+        assert_eq!(keymap.chord_for("bold").unwrap().to_string(), "Ctrl+B");
+        assert_eq!(keymap.chord_for("previous_editor").unwrap().to_string(), "Ctrl+Shift+Tab");
+    }
+
+    #[test]
+    fn test_remap_action() {
+        let (keymap, issues) = KeymapConfig::parse("[bindings]\nbold = \"Ctrl+Shift+F\"\n");
+        assert!(issues.is_empty());
+
+        let chord = KeyChord::from_event("F", true, true, false).unwrap();
+        assert_eq!(keymap.action_for(&chord), Some("bold"));
+        assert_eq!(keymap.action_for(&KeyChord::parse("Ctrl+B").unwrap()), None);
+    }
+
+    #[test]
+    fn test_conflict_reported_and_defaults_used() {
+        let toml = "[bindings]\nbold = \"Ctrl+K\"\nitalic = \"Ctrl+K\"\nunderline = \"Ctrl+Y\"\n";
+        let (keymap, issues) = KeymapConfig::parse(toml);
+
+        assert_eq!(
+            issues,
+            vec![KeymapIssue::Conflict {
+                chord: "Ctrl+K".to_string(),
+                actions: vec!["bold".to_string(), "italic".to_string()],
+            }]
+        );
+        assert_eq!(keymap.chord_for("bold").unwrap().to_string(), "Ctrl+B");
+        assert_eq!(keymap.chord_for("italic").unwrap().to_string(), "Ctrl+I");
+        assert_eq!(keymap.chord_for("underline").unwrap().to_string(), "Ctrl+Y");
+    }
+
+    #[test]
+    fn test_conflict_with_default_binding() {
+        let (keymap, issues) = KeymapConfig::parse("[bindings]\nbold = \"Ctrl+M\"\n");
+
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(&issues[0], KeymapIssue::Conflict { actions, .. } if actions.contains(&"toggle_mode".to_string())));
+        assert_eq!(keymap.chord_for("bold").unwrap().to_string(), "Ctrl+B");
+        assert_eq!(keymap.chord_for("toggle_mode").unwrap().to_string(), "Ctrl+M");
+    }
+
+    #[test]
+    fn test_swapped_bindings_are_not_conflicts() {
+        let (keymap, issues) = KeymapConfig::parse("[bindings]\nbold = \"Ctrl+I\"\nitalic = \"Ctrl+B\"\n");
+        assert!(issues.is_empty());
+        assert_eq!(keymap.action_for(&KeyChord::parse("Ctrl+I").unwrap()), Some("bold"));
+    }
+
+    #[test]
+    fn test_invalid_entries_fall_back() {
+        let toml = "[bindings]\nbold = \"Hyper+B\"\nitalic = \"Q\"\nfly = \"Ctrl+F\"\n";
+        let (keymap, issues) = KeymapConfig::parse(toml);
+
+        assert_eq!(issues.len(), 3);
+        assert!(issues.contains(&KeymapIssue::UnknownAction("fly".to_string())));
+        assert_eq!(keymap, KeymapConfig::default());
+    }
+
+    #[test]
+    fn test_malformed_toml_uses_defaults() {
+        let (keymap, issues) = KeymapConfig::parse("[bindings\nbold = ");
+        assert!(matches!(issues.as_slice(), [KeymapIssue::Unreadable(_)]));
+        assert_eq!(keymap, KeymapConfig::default());
+    }
+}
@@ -12,6 +12,9 @@ pub mod main_window_focus_bridge;
 pub mod alignment_confidence_bridge;
 pub mod enhanced_formatting_functions;
 pub mod enhanced_markdown_bridge;
+pub mod keymap;
+pub mod bilingual_review;
+pub mod clipboard;
 
 pub use app::App;
 pub use state::AppState;
@@ -24,4 +27,6 @@ pub use export_bridge::ExportBridge;
 pub use focus_management_bridge::FocusManagementUIBridge;
 pub use alignment_confidence_bridge::AlignmentConfidenceBridge;
 pub use enhanced_formatting_functions::{EnhancedFormattingEngine, TextSelection, FormattingResult};
-pub use enhanced_markdown_bridge::EnhancedMarkdownBridge;
\ No newline at end of file
+pub use enhanced_markdown_bridge::EnhancedMarkdownBridge;
+pub use keymap::{KeymapConfig, KeyChord, KeymapIssue};
+pub use bilingual_review::{AlignedParagraph, ParagraphStatus};
\ No newline at end of file
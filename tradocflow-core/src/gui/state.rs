@@ -1461,13 +1461,16 @@ impl AppState {
     
     /// Sort projects in browser
     pub async fn sort_browser_projects(&self, sort_config: SortConfig) -> Result<(), TradocumentError> {
+        let language = self.current_language.read().await.clone();
         let mut browser_state = self.project_browser_state.write().await;
+        // Not `Send`, so created after the last await
+        let collator = crate::services::collation::collator(&language);
         
         browser_state.filtered_projects.sort_by(|a, b| {
             use crate::models::project_browser::{SortField, SortDirection};
             
             let comparison = match sort_config.field {
-                SortField::Name => a.name.cmp(&b.name),
+                SortField::Name => collator.compare(&a.name, &b.name),
                 SortField::Created => a.created_at.cmp(&b.created_at),
                 SortField::Updated => a.updated_at.cmp(&b.updated_at),
                 SortField::DueDate => {
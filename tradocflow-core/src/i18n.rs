@@ -5,10 +5,10 @@ use unic_langid::LanguageIdentifier;
 
 /// Supported languages in the application
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[derive(Default)]
+#[derive(Default)]
 pub enum Language {
     #[serde(rename = "en")]
-    #[default]
+    #[default]
     English,
     #[serde(rename = "de")] 
     German,
@@ -76,6 +76,62 @@ impl Language {
     pub fn to_lang_id(&self) -> LanguageIdentifier {
         self.code().parse().expect("Valid language identifier")
     }
+
+    /// Whether the language is written right to left
+    pub fn is_rtl(&self) -> bool {
+        tradocflow_translation_memory::is_rtl_code(self.code())
+    }
+
+    /// Plural category of `count` in this language
+    pub fn plural_category(&self, count: u64) -> PluralCategory {
+        PluralCategory::for_count(self.code(), count)
+    }
+}
+
+/// CLDR cardinal plural category, the last segment of plural-keyed
+/// locale entries such as `counts.documents.one`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluralCategory {
+    One,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    pub fn key(&self) -> &'static str {
+        match self {
+            PluralCategory::One => "one",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+
+    /// Category of the whole number `count` under the CLDR rules of the
+    /// language with the BCP 47 tag `code`. Languages without rules of
+    /// their own follow English.
+    pub fn for_count(code: &str, count: u64) -> Self {
+        let language = code.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        let (last, last_two) = (count % 10, count % 100);
+        let few = (2..=4).contains(&last) && !(12..=14).contains(&last_two);
+        let millions = count != 0 && count.is_multiple_of(1_000_000);
+
+        match language.as_str() {
+            "ja" | "ko" | "zh" | "th" | "vi" | "id" | "ms" => PluralCategory::Other,
+            "fr" if count < 2 => PluralCategory::One,
+            "fr" | "es" | "it" | "pt" | "ca" if millions => PluralCategory::Many,
+            "ru" | "uk" | "be" if last == 1 && last_two != 11 => PluralCategory::One,
+            "ru" | "uk" | "be" if few => PluralCategory::Few,
+            "ru" | "uk" | "be" => PluralCategory::Many,
+            "pl" if count == 1 => PluralCategory::One,
+            "pl" if few => PluralCategory::Few,
+            "pl" => PluralCategory::Many,
+            "cs" | "sk" if (2..=4).contains(&count) => PluralCategory::Few,
+            _ if count == 1 => PluralCategory::One,
+            _ => PluralCategory::Other,
+        }
+    }
 }
 
 
@@ -122,6 +178,11 @@ pub fn t(key: &str) -> String {
     rust_i18n::t!(key).to_string()
 }
 
+/// Translate a key into a specific locale, regardless of the current language
+pub fn t_for(key: &str, locale: &str) -> String {
+    rust_i18n::t!(key, locale = locale).to_string()
+}
+
 /// Translate a key with arguments using rust-i18n
 pub fn t_with_args(key: &str, args: &HashMap<String, String>) -> String {
     // For more complex argument handling, we can use rust-i18n's built-in support
@@ -137,6 +198,51 @@ pub fn t_with_args(key: &str, args: &HashMap<String, String>) -> String {
     result
 }
 
+/// [`t_with_args`] into a specific locale, falling back along the chain to
+/// English for keys the locale lacks. A key no locale has comes back as it
+/// is.
+pub fn t_for_with_args(key: &str, locale: &str, args: &HashMap<String, String>) -> String {
+    let mut result = lookup(key, locale).unwrap_or_else(|| key.to_string());
+    for (placeholder_key, value) in args {
+        result = result.replace(&format!("{{{placeholder_key}}}"), value);
+    }
+    result
+}
+
+/// Translate a plural-keyed entry for `count` in the current language.
+///
+/// The entry holds one form per plural category, such as
+/// `counts.documents.one` and `counts.documents.other`. `{count}` and the
+/// `args` placeholders are filled in.
+pub fn translate_plural(key: &str, count: u64, args: &HashMap<String, String>) -> String {
+    translate_plural_for(key, count, get_language().code(), args)
+}
+
+/// [`translate_plural`] into a specific locale. A form missing from the
+/// locale is looked up along the fallback chain to English, and a category
+/// no locale has falls back to the `other` form.
+pub fn translate_plural_for(key: &str, count: u64, locale: &str, args: &HashMap<String, String>) -> String {
+    let category = PluralCategory::for_count(locale, count);
+    let Some(mut result) = [category, PluralCategory::Other]
+        .iter()
+        .find_map(|category| lookup(&format!("{key}.{}", category.key()), locale))
+    else {
+        return key.to_string();
+    };
+
+    result = result.replace("{count}", &count.to_string());
+    for (placeholder_key, value) in args {
+        result = result.replace(&format!("{{{placeholder_key}}}"), value);
+    }
+    result
+}
+
+/// Translation of `key`, or `None` when rust-i18n only echoes the key back
+fn lookup(key: &str, locale: &str) -> Option<String> {
+    let text = t_for(key, locale);
+    (text != key && text != format!("{locale}.{key}")).then_some(text)
+}
+
 /// Helper macro for translations with current language context
 #[macro_export]
 macro_rules! t {
@@ -234,6 +340,50 @@ mod tests {
         assert_eq!(Language::from_code("invalid"), None);
     }
 
+    #[test]
+    fn test_english_plural_forms() {
+        let args = HashMap::new();
+        assert_eq!(Language::English.plural_category(1), PluralCategory::One);
+        assert_eq!(Language::English.plural_category(0), PluralCategory::Other);
+        assert_eq!(translate_plural_for("counts.documents", 1, "en", &args), "1 document");
+        assert_eq!(translate_plural_for("counts.documents", 2, "en", &args), "2 documents");
+        assert_eq!(translate_plural_for("counts.comments", 0, "de", &args), "0 Kommentare");
+        assert_eq!(Language::French.plural_category(0), PluralCategory::One);
+    }
+
+    #[test]
+    fn test_russian_and_polish_plural_categories() {
+        let russian = |count| PluralCategory::for_count("ru", count);
+        assert_eq!(russian(1), PluralCategory::One);
+        assert_eq!(russian(21), PluralCategory::One);
+        assert_eq!(russian(3), PluralCategory::Few);
+        assert_eq!(russian(24), PluralCategory::Few);
+        assert_eq!(russian(5), PluralCategory::Many);
+        assert_eq!(russian(11), PluralCategory::Many);
+        assert_eq!(russian(12), PluralCategory::Many);
+
+        let polish = |count| PluralCategory::for_count("pl-PL", count);
+        assert_eq!(polish(1), PluralCategory::One);
+        assert_eq!(polish(22), PluralCategory::Few);
+        assert_eq!(polish(21), PluralCategory::Many);
+        assert_eq!(polish(13), PluralCategory::Many);
+    }
+
+    #[test]
+    fn test_missing_plural_form_falls_back() {
+        let args = HashMap::new();
+        // No Russian locale and no English "few" form: English "other"
+        assert_eq!(translate_plural_for("counts.documents", 3, "ru", &args), "3 documents");
+        // French has no "many" form for millions
+        assert_eq!(translate_plural_for("counts.documents", 2_000_000, "fr", &args), "2000000 documents");
+        assert_eq!(translate_plural_for("counts.unknown", 3, "en", &args), "counts.unknown");
+    }
+
+    #[test]
+    fn test_no_interface_language_is_rtl() {
+        assert!(Language::all().iter().all(|lang| !lang.is_rtl()));
+    }
+
     #[test]
     fn test_detect_language_from_header() {
         assert_eq!(
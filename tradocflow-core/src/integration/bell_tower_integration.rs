@@ -69,6 +69,9 @@ impl EnhancedBellTowerIntegration {
                     language: result.language,
                     screen_config: serde_json::to_string(&result.parameters_used).unwrap_or_default(),
                     generated_at: Some(result.generated_at),
+                    caption: None,
+                    content_hash: None,
+                    alt_text: HashMap::new(),
                 }));
             }
         }
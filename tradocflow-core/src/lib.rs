@@ -43,7 +43,8 @@ pub use notification_system::{
     NotificationType, 
     NotificationPreferences,
     NotificationMetadata,
-    NotificationPriority
+    NotificationPriority,
+    DeliveryStatus
 };
 pub use i18n::{Language, I18nContext};
 pub use database::Database;
@@ -89,6 +90,11 @@ pub struct Document {
 pub struct DocumentMetadata {
     pub project_id: Option<String>,
     pub screenshots: Vec<ScreenshotReference>,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Free-form key/value metadata carried into exports
+    #[serde(default)]
+    pub custom: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +103,25 @@ pub struct ScreenshotReference {
     pub language: String,
     pub screen_config: String, // JSON config for screenshot_creator
     pub generated_at: Option<DateTime<Utc>>,
+    /// Figure caption in exports; captioned screenshots are numbered and
+    /// listed as figures
+    #[serde(default)]
+    pub caption: Option<String>,
+    /// Hash of the image in the shared image store (see
+    /// `database::image_repository`); exports read the stored image rather
+    /// than the screenshot directory when it is set
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// Alt text by language code, translated like the document content
+    #[serde(default)]
+    pub alt_text: HashMap<String, String>,
+}
+
+impl ScreenshotReference {
+    /// The screenshot's alt text in `language`, if it has any
+    pub fn alt_text_for(&self, language: &str) -> Option<&str> {
+        self.alt_text.get(language).map(|text| text.trim()).filter(|text| !text.is_empty())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -121,9 +146,20 @@ pub struct ManualSection {
     pub subsections: Vec<ManualSection>,
     pub section_type: SectionType,
     pub required: bool,
+    /// Section titles by language code; `title` is used for languages
+    /// without an entry
+    #[serde(default)]
+    pub localized_titles: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ManualSection {
+    /// The section's title in `language`
+    pub fn localized_title(&self, language: &str) -> &str {
+        self.localized_titles.get(language).map_or(&self.title, String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SectionType {
     Introduction,
     Installation,
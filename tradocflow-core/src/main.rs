@@ -25,11 +25,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 eprintln!("⚠️ Warning: Failed to initialize async components: {}", e);
             }
             
-            println!("💡 Press Ctrl+M to toggle between Markdown and Presentation modes");
-            println!("💡 Press Ctrl+1/2/3 to switch layouts");
-            println!("💡 Use toolbar buttons or Ctrl+B/I/U for formatting");
+            let keymap = app.keymap();
+            let chord = |action: &str| keymap.chord_for(action).map(|c| c.to_string()).unwrap_or_default();
+            println!("💡 Press {} to toggle between Markdown and Presentation modes", chord("toggle_mode"));
+            println!("💡 Press {}/{}/{} to switch layouts", chord("switch_layout_1"), chord("switch_layout_2"), chord("switch_layout_3"));
+            println!("💡 Use toolbar buttons or {}/{}/{} for formatting", chord("bold"), chord("italic"), chord("underline"));
             println!("💡 Click on language selector to change editing language");
-            println!("💡 Press Ctrl+P to open project browser");
+            println!("💡 Press {} to open project browser", chord("open_project_browser"));
             
             // Run the application - this will block until the window is closed
             app.run()?;
@@ -12,7 +12,7 @@ pub struct ProjectMember {
     pub added_by: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum MemberRole {
     #[serde(rename = "owner")]
     Owner,
@@ -1,11 +1,17 @@
+use crate::i18n::{self, Language};
 use crate::{Result, TradocumentError, User};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// How long a high or urgent priority notification may stay unread before
+/// it is escalated, unless configured otherwise
+const DEFAULT_ESCALATION_THRESHOLD: chrono::Duration = chrono::Duration::hours(4);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Notification {
     pub id: Uuid,
@@ -17,7 +23,36 @@ pub struct Notification {
     pub metadata: NotificationMetadata,
     pub created_at: DateTime<Utc>,
     pub read_at: Option<DateTime<Utc>>,
+    /// Whether the notification got to the recipient, kept in step with
+    /// `status`
     pub delivered: bool,
+    #[serde(default)]
+    pub status: DeliveryStatus,
+    #[serde(default)]
+    pub delivered_at: Option<DateTime<Utc>>,
+    /// When the notification was brought back to the attention of a
+    /// recipient who hadn't read it
+    #[serde(default)]
+    pub escalated_at: Option<DateTime<Utc>>,
+}
+
+impl Notification {
+    /// When the notification last came to the top of the recipient's list
+    fn surfaced_at(&self) -> DateTime<Utc> {
+        self.escalated_at.unwrap_or(self.created_at)
+    }
+}
+
+/// How far a notification got on its way to the recipient
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeliveryStatus {
+    /// Not sent yet, such as while snoozed
+    #[default]
+    Queued,
+    Delivered,
+    Read,
+    /// Every channel failed to send it
+    Failed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,8 +67,17 @@ pub enum NotificationType {
     ReviewStatusChanged,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NotificationMetadata {
+    /// Entry under `notifications.messages` in the locale files whose
+    /// `title` and `body` are rendered in the recipient's language. Without
+    /// one the notification's own title and message are sent as they are.
+    #[serde(default)]
+    pub message_key: Option<String>,
+    /// Project the notification is about, which snoozing and muting a
+    /// project go by
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
     pub document_id: Option<Uuid>,
     pub document_title: Option<String>,
     pub review_id: Option<Uuid>,
@@ -41,11 +85,35 @@ pub struct NotificationMetadata {
     pub priority: NotificationPriority,
     pub action_required: bool,
     pub action_url: Option<String>,
+    /// Name of whoever caused the notification, such as the commenter
+    #[serde(default)]
+    pub actor_name: Option<String>,
+    #[serde(default)]
+    pub section_title: Option<String>,
+    /// Free text such as a comment excerpt or a rejection reason
+    #[serde(default)]
+    pub details: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+impl NotificationMetadata {
+    /// Values of the placeholders a message template may use
+    fn template_args(&self) -> HashMap<String, String> {
+        [
+            ("document_title", &self.document_title),
+            ("actor", &self.actor_name),
+            ("section", &self.section_title),
+            ("details", &self.details),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.clone().unwrap_or_default()))
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub enum NotificationPriority {
     Low,
+    #[default]
     Normal,
     High,
     Urgent,
@@ -59,6 +127,38 @@ pub struct NotificationPreferences {
     pub notification_types: HashMap<NotificationType, bool>,
     pub quiet_hours_start: Option<String>, // HH:MM format
     pub quiet_hours_end: Option<String>,   // HH:MM format
+    /// Projects whose notifications are held back until the given time and
+    /// delivered then
+    #[serde(default)]
+    pub snoozed_projects: HashMap<Uuid, DateTime<Utc>>,
+    /// Notification types held back until the given time
+    #[serde(default)]
+    pub snoozed_types: HashMap<NotificationType, DateTime<Utc>>,
+    /// Projects whose notifications are dropped
+    #[serde(default)]
+    pub muted_projects: HashSet<Uuid>,
+    /// Notification types that are dropped
+    #[serde(default)]
+    pub muted_types: HashSet<NotificationType>,
+    /// Language notifications are rendered in
+    #[serde(default)]
+    pub language: Language,
+}
+
+impl NotificationPreferences {
+    /// Whether `notification` is dropped instead of delivered
+    pub fn is_muted(&self, notification: &Notification) -> bool {
+        self.muted_types.contains(&notification.notification_type)
+            || notification.metadata.project_id.is_some_and(|project| self.muted_projects.contains(&project))
+    }
+
+    /// Until when `notification` is held back, if a snooze on its project or
+    /// type lasts past `now`. Expired snoozes hold nothing back.
+    pub fn snoozed_until(&self, notification: &Notification, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let project = notification.metadata.project_id.and_then(|project| self.snoozed_projects.get(&project));
+        let notification_type = self.snoozed_types.get(&notification.notification_type);
+        project.into_iter().chain(notification_type).copied().filter(|until| *until > now).max()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -120,12 +220,30 @@ impl NotificationChannel {
     }
 }
 
+/// What a service with storage keeps across restarts
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredState {
+    notifications: Vec<Notification>,
+    /// Users' preferences, which hold their snoozes
+    #[serde(default)]
+    preferences: Vec<NotificationPreferences>,
+    /// Notifications held back by a snooze, with their recipient
+    #[serde(default)]
+    snoozed: Vec<(Notification, User)>,
+}
+
 #[derive(Debug)]
 pub struct NotificationService {
     notifications: Arc<Mutex<HashMap<Uuid, Notification>>>,
     user_notifications: Arc<Mutex<HashMap<String, Vec<Uuid>>>>, // user_id -> notification_ids
     preferences: Arc<Mutex<HashMap<String, NotificationPreferences>>>,
+    /// Notifications held back by a snooze, with their recipient
+    snoozed: Arc<Mutex<Vec<(Notification, User)>>>,
     channels: Vec<NotificationChannel>,
+    /// File the notifications, preferences and snoozed notifications are
+    /// kept in across restarts, if any
+    storage_path: Option<PathBuf>,
+    escalation_threshold: chrono::Duration,
 }
 
 impl NotificationService {
@@ -134,7 +252,10 @@ impl NotificationService {
             notifications: Arc::new(Mutex::new(HashMap::new())),
             user_notifications: Arc::new(Mutex::new(HashMap::new())),
             preferences: Arc::new(Mutex::new(HashMap::new())),
+            snoozed: Arc::new(Mutex::new(Vec::new())),
             channels: Vec::new(),
+            storage_path: None,
+            escalation_threshold: DEFAULT_ESCALATION_THRESHOLD,
         };
         
         // Add default channels
@@ -143,23 +264,158 @@ impl NotificationService {
         
         service
     }
+
+    /// A service that keeps notifications and their delivery status, the
+    /// users' preferences with their snoozes, and the notifications the
+    /// snoozes hold back in a JSON file at `path`, picking up what an
+    /// earlier run left there
+    pub fn with_storage(path: &Path) -> Result<Self> {
+        let mut service = Self::new();
+        service.storage_path = Some(path.to_path_buf());
+        if !path.exists() {
+            return Ok(service);
+        }
+
+        let mut stored: StoredState = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        stored.notifications.sort_by_key(|notification| notification.created_at);
+        let mut user_notifications: HashMap<String, Vec<Uuid>> = HashMap::new();
+        for notification in &stored.notifications {
+            user_notifications.entry(notification.recipient_id.clone()).or_default().push(notification.id);
+        }
+        service.user_notifications = Arc::new(Mutex::new(user_notifications));
+        service.notifications = Arc::new(Mutex::new(stored.notifications.into_iter().map(|n| (n.id, n)).collect()));
+        service.preferences = Arc::new(Mutex::new(
+            stored.preferences.into_iter().map(|preferences| (preferences.user_id.clone(), preferences)).collect(),
+        ));
+        service.snoozed = Arc::new(Mutex::new(stored.snoozed));
+        Ok(service)
+    }
+
+    /// Escalate unread high and urgent priority notifications `threshold`
+    /// after delivery, instead of 4 hours
+    pub fn with_escalation_threshold(mut self, threshold: chrono::Duration) -> Self {
+        self.escalation_threshold = threshold;
+        self
+    }
+
+    /// Write all notifications, preferences and snoozed notifications to
+    /// the storage file, if there is one
+    async fn persist(&self) -> Result<()> {
+        let Some(path) = &self.storage_path else {
+            return Ok(());
+        };
+        let state = StoredState {
+            notifications: self.notifications.lock().await.values().cloned().collect(),
+            preferences: self.preferences.lock().await.values().cloned().collect(),
+            snoozed: self.snoozed.lock().await.clone(),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&state)?)?;
+        Ok(())
+    }
     
-    pub async fn send_notification(&self, mut notification: Notification, recipient: &User) -> Result<()> {
+    pub async fn send_notification(&self, notification: Notification, recipient: &User) -> Result<()> {
+        self.deliver_due_snoozed().await?;
+
         // Check user preferences
         let preferences = self.get_user_preferences(&recipient.id).await;
         
         if !self.should_send_notification(&notification, &preferences) {
             return Ok(());
         }
-        
-        // Set delivered flag
-        notification.delivered = true;
+        let notification = Self::render(notification, &preferences.language);
+        if preferences.snoozed_until(&notification, Utc::now()).is_some() {
+            self.snoozed.lock().await.push((notification, recipient.clone()));
+            return self.persist().await;
+        }
+
+        self.deliver(notification, recipient).await?;
+        self.persist().await
+    }
+
+    /// Fill in the title and message from the notification's message key in
+    /// `language`. Keys missing from the language fall back to English, and
+    /// keys missing everywhere leave the notification as it is.
+    fn render(mut notification: Notification, language: &Language) -> Notification {
+        let Some(key) = notification.metadata.message_key.as_deref() else {
+            return notification;
+        };
+        let args = notification.metadata.template_args();
+        let render = |part: &str| {
+            let key = format!("notifications.messages.{key}.{part}");
+            let text = i18n::t_for_with_args(&key, language.code(), &args);
+            (text != key).then_some(text)
+        };
+        if let (Some(title), Some(message)) = (render("title"), render("body")) {
+            notification.title = title;
+            notification.message = message;
+        }
+        notification
+    }
+
+    /// Deliver the snoozed notifications whose snoozes have expired or
+    /// been lifted, returning how many were delivered. Those muted in the
+    /// meantime are dropped.
+    pub async fn deliver_due_snoozed(&self) -> Result<usize> {
+        let held = std::mem::take(&mut *self.snoozed.lock().await);
+        if held.is_empty() {
+            return Ok(0);
+        }
+        let held_count = held.len();
+        let now = Utc::now();
+        let mut delivered = 0;
+        let mut still_held = Vec::new();
+
+        for (notification, recipient) in held {
+            let preferences = self.get_user_preferences(&recipient.id).await;
+            if !self.should_send_notification(&notification, &preferences) {
+                continue;
+            }
+            if preferences.snoozed_until(&notification, now).is_some() {
+                still_held.push((notification, recipient));
+                continue;
+            }
+            self.deliver(notification, &recipient).await?;
+            delivered += 1;
+        }
+
+        let changed = still_held.len() != held_count;
+        self.snoozed.lock().await.extend(still_held);
+        if changed {
+            self.persist().await?;
+        }
+        Ok(delivered)
+    }
+
+    /// Send `notification` through the channels and record it with its
+    /// delivery status; the caller persists the change
+    async fn deliver(&self, mut notification: Notification, recipient: &User) -> Result<()> {
+        // Send through all enabled channels
+        let mut failed_channels = 0;
+        for channel in &self.channels {
+            if let Err(e) = channel.send_notification(&notification, recipient).await {
+                eprintln!("Failed to send notification via {}: {}", channel.channel_type(), e);
+                failed_channels += 1;
+                // Continue with other channels instead of failing completely
+            }
+        }
+
+        // Delivered unless every channel failed
+        notification.delivered = self.channels.is_empty() || failed_channels < self.channels.len();
+        if notification.delivered {
+            notification.status = DeliveryStatus::Delivered;
+            notification.delivered_at = Some(Utc::now());
+        } else {
+            notification.status = DeliveryStatus::Failed;
+        }
         let notification_id = notification.id;
         
         // Store notification
         {
             let mut notifications = self.notifications.lock().await;
-            notifications.insert(notification_id, notification.clone());
+            notifications.insert(notification_id, notification);
         }
         
         // Add to user's notification list
@@ -169,14 +425,6 @@ impl NotificationService {
             user_notifs.push(notification_id);
         }
         
-        // Send through all enabled channels
-        for channel in &self.channels {
-            if let Err(e) = channel.send_notification(&notification, recipient).await {
-                eprintln!("Failed to send notification via {}: {}", channel.channel_type(), e);
-                // Continue with other channels instead of failing completely
-            }
-        }
-        
         Ok(())
     }
     
@@ -193,20 +441,24 @@ impl NotificationService {
             recipient_id: reviewer.id.clone(),
             sender_id: assigner_id,
             notification_type: NotificationType::ReviewAssigned,
-            title: "New Review Assignment".to_string(),
-            message: format!("You have been assigned to review the document '{document_title}'"),
+            title: String::new(),
+            message: String::new(),
             metadata: NotificationMetadata {
+                message_key: Some("review_assigned".to_string()),
                 document_id: Some(document_id),
                 document_title: Some(document_title.to_string()),
                 review_id: Some(review_id),
-                comment_id: None,
                 priority: NotificationPriority::Normal,
                 action_required: true,
                 action_url: Some(format!("/documents/{document_id}/review/{review_id}")),
+                ..Default::default()
             },
             created_at: Utc::now(),
             read_at: None,
             delivered: false,
+            status: DeliveryStatus::Queued,
+            delivered_at: None,
+            escalated_at: None,
         };
         
         self.send_notification(notification, reviewer).await
@@ -227,18 +479,16 @@ impl NotificationService {
             recipient_id: recipient.id.clone(),
             sender_id: Some(commenter.id.clone()),
             notification_type: NotificationType::CommentAdded,
-            title: "New Comment Added".to_string(),
-            message: format!(
-                "{} added a comment on '{}': {}",
-                commenter.name,
-                document_title,
-                if comment_content.len() > 100 {
+            title: String::new(),
+            message: String::new(),
+            metadata: NotificationMetadata {
+                message_key: Some("comment_added".to_string()),
+                actor_name: Some(commenter.name.clone()),
+                details: Some(if comment_content.len() > 100 {
                     format!("{}...", &comment_content[..97])
                 } else {
                     comment_content.to_string()
-                }
-            ),
-            metadata: NotificationMetadata {
+                }),
                 document_id: Some(document_id),
                 document_title: Some(document_title.to_string()),
                 review_id: Some(review_id),
@@ -246,10 +496,14 @@ impl NotificationService {
                 priority: NotificationPriority::Normal,
                 action_required: false,
                 action_url: Some(format!("/documents/{document_id}/review/{review_id}#comment-{comment_id}")),
+                ..Default::default()
             },
             created_at: Utc::now(),
             read_at: None,
             delivered: false,
+            status: DeliveryStatus::Queued,
+            delivered_at: None,
+            escalated_at: None,
         };
         
         self.send_notification(notification, recipient).await
@@ -265,38 +519,25 @@ impl NotificationService {
         new_status: &crate::review_system::ReviewStatus,
         reason: Option<&str>,
     ) -> Result<()> {
-        let (notification_type, title, message, priority) = match new_status {
+        let (notification_type, message_key, priority) = match new_status {
             crate::review_system::ReviewStatus::Approved => (
                 NotificationType::DocumentApproved,
-                "Document Approved".to_string(),
-                format!("{} approved the document '{}'", reviewer.name, document_title),
+                "document_approved",
                 NotificationPriority::Normal,
             ),
             crate::review_system::ReviewStatus::Rejected => (
                 NotificationType::DocumentRejected,
-                "Document Rejected".to_string(),
-                format!(
-                    "{} rejected the document '{}'{}", 
-                    reviewer.name, 
-                    document_title,
-                    if let Some(reason) = reason {
-                        format!(": {reason}")
-                    } else {
-                        String::new()
-                    }
-                ),
+                if reason.is_some() { "document_rejected_with_reason" } else { "document_rejected" },
                 NotificationPriority::High,
             ),
             crate::review_system::ReviewStatus::ChangesRequested => (
                 NotificationType::ChangesRequested,
-                "Changes Requested".to_string(),
-                format!("{} requested changes to the document '{}'", reviewer.name, document_title),
+                "changes_requested",
                 NotificationPriority::High,
             ),
             _ => (
                 NotificationType::ReviewStatusChanged,
-                "Review Status Changed".to_string(),
-                format!("{} updated the review status for '{}'", reviewer.name, document_title),
+                "review_status_changed",
                 NotificationPriority::Normal,
             ),
         };
@@ -306,26 +547,33 @@ impl NotificationService {
             recipient_id: recipient.id.clone(),
             sender_id: Some(reviewer.id.clone()),
             notification_type,
-            title,
-            message,
+            title: String::new(),
+            message: String::new(),
             metadata: NotificationMetadata {
+                message_key: Some(message_key.to_string()),
+                actor_name: Some(reviewer.name.clone()),
+                details: reason.map(str::to_string),
                 document_id: Some(document_id),
                 document_title: Some(document_title.to_string()),
                 review_id: Some(review_id),
-                comment_id: None,
                 priority,
                 action_required: matches!(new_status, crate::review_system::ReviewStatus::ChangesRequested),
                 action_url: Some(format!("/documents/{document_id}/review/{review_id}")),
+                ..Default::default()
             },
             created_at: Utc::now(),
             read_at: None,
             delivered: false,
+            status: DeliveryStatus::Queued,
+            delivered_at: None,
+            escalated_at: None,
         };
         
         self.send_notification(notification, recipient).await
     }
     
     pub async fn get_user_notifications(&self, user_id: &str, unread_only: bool) -> Result<Vec<Notification>> {
+        self.deliver_due_snoozed().await?;
         let user_notifications = self.user_notifications.lock().await;
         let notifications = self.notifications.lock().await;
         
@@ -338,27 +586,63 @@ impl NotificationService {
                     }
                 }
             }
-            // Sort by created_at desc (newest first)
-            result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            // Newest first, counting escalation as arriving anew
+            result.sort_by_key(|notification| std::cmp::Reverse(notification.surfaced_at()));
             Ok(result)
         } else {
             Ok(Vec::new())
         }
     }
     
-    pub async fn mark_notification_read(&self, notification_id: Uuid, user_id: &str) -> Result<()> {
-        let mut notifications = self.notifications.lock().await;
-        
-        if let Some(notification) = notifications.get_mut(&notification_id) {
-            if notification.recipient_id == user_id {
-                notification.read_at = Some(Utc::now());
-                Ok(())
-            } else {
-                Err(TradocumentError::Notification("Permission denied".to_string()))
+    /// Record that the recipient has read a notification
+    pub async fn mark_read(&self, notification_id: Uuid, user_id: &str) -> Result<()> {
+        {
+            let mut notifications = self.notifications.lock().await;
+            let notification = notifications
+                .get_mut(&notification_id)
+                .ok_or_else(|| TradocumentError::Notification("Notification not found".to_string()))?;
+            if notification.recipient_id != user_id {
+                return Err(TradocumentError::Notification("Permission denied".to_string()));
             }
-        } else {
-            Err(TradocumentError::Notification("Notification not found".to_string()))
+            notification.read_at = Some(Utc::now());
+            notification.status = DeliveryStatus::Read;
+        }
+        self.persist().await
+    }
+
+    /// How far a notification got, or `None` for one this service never saw
+    /// or dropped because it was muted
+    pub async fn delivery_status(&self, notification_id: Uuid) -> Option<DeliveryStatus> {
+        if let Some(notification) = self.notifications.lock().await.get(&notification_id) {
+            return Some(notification.status);
+        }
+        let snoozed = self.snoozed.lock().await;
+        snoozed.iter().any(|(n, _)| n.id == notification_id).then_some(DeliveryStatus::Queued)
+    }
+
+    /// Bring unread high and urgent priority notifications delivered longer
+    /// than the escalation threshold ago back to the top of their
+    /// recipient's list. Each is escalated once; those escalated now are
+    /// returned so they can be sent again, for example by email.
+    pub async fn escalate_unread(&self) -> Result<Vec<Notification>> {
+        let now = Utc::now();
+        let mut escalated = Vec::new();
+        {
+            let mut notifications = self.notifications.lock().await;
+            for notification in notifications.values_mut() {
+                let important =
+                    matches!(notification.metadata.priority, NotificationPriority::High | NotificationPriority::Urgent);
+                let overdue = notification.delivered_at.is_some_and(|at| now - at >= self.escalation_threshold);
+                if important && overdue && notification.status == DeliveryStatus::Delivered && notification.escalated_at.is_none() {
+                    notification.escalated_at = Some(now);
+                    escalated.push(notification.clone());
+                }
+            }
+        }
+        if !escalated.is_empty() {
+            self.persist().await?;
         }
+        Ok(escalated)
     }
     
     pub async fn get_unread_count(&self, user_id: &str) -> Result<usize> {
@@ -387,6 +671,11 @@ impl NotificationService {
                 notification_types,
                 quiet_hours_start: None,
                 quiet_hours_end: None,
+                snoozed_projects: HashMap::new(),
+                snoozed_types: HashMap::new(),
+                muted_projects: HashSet::new(),
+                muted_types: HashSet::new(),
+                language: Language::default(),
             }
         })
     }
@@ -397,12 +686,12 @@ impl NotificationService {
             .get(&notification.notification_type)
             .copied()
             .unwrap_or(true)
+            && !preferences.is_muted(notification)
     }
     
     pub async fn update_user_preferences(&self, preferences: NotificationPreferences) -> Result<()> {
-        let mut prefs = self.preferences.lock().await;
-        prefs.insert(preferences.user_id.clone(), preferences);
-        Ok(())
+        self.preferences.lock().await.insert(preferences.user_id.clone(), preferences);
+        self.persist().await
     }
 }
 
@@ -440,4 +729,199 @@ impl PartialEq for NotificationType {
     }
 }
 
-impl Eq for NotificationType {}
\ No newline at end of file
+impl Eq for NotificationType {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UserRole;
+    use chrono::Duration;
+
+    fn user(id: &str) -> User {
+        User {
+            id: id.to_string(),
+            name: id.to_string(),
+            email: format!("{id}@example.com"),
+            role: UserRole::Member,
+            created_at: Utc::now(),
+            active: true,
+        }
+    }
+
+    fn notification(recipient: &User, notification_type: NotificationType, project_id: Uuid) -> Notification {
+        Notification {
+            id: Uuid::new_v4(),
+            recipient_id: recipient.id.clone(),
+            sender_id: None,
+            notification_type,
+            title: "Update".to_string(),
+            message: "Something happened".to_string(),
+            metadata: NotificationMetadata {
+                project_id: Some(project_id),
+                ..Default::default()
+            },
+            created_at: Utc::now(),
+            read_at: None,
+            delivered: false,
+            status: DeliveryStatus::Queued,
+            delivered_at: None,
+            escalated_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snoozed_project_is_held_until_the_snooze_expires() {
+        let service = NotificationService::new();
+        let alice = user("alice");
+        let (noisy, quiet) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut preferences = service.get_user_preferences(&alice.id).await;
+        preferences.snoozed_projects.insert(noisy, Utc::now() + Duration::hours(1));
+        service.update_user_preferences(preferences.clone()).await.unwrap();
+
+        service.send_notification(notification(&alice, NotificationType::CommentAdded, noisy), &alice).await.unwrap();
+        service.send_notification(notification(&alice, NotificationType::CommentAdded, quiet), &alice).await.unwrap();
+        let delivered = service.get_user_notifications(&alice.id, false).await.unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].metadata.project_id, Some(quiet));
+
+        // Once the snooze runs out, the held notification comes through and
+        // new ones are delivered straight away
+        preferences.snoozed_projects.insert(noisy, Utc::now() - Duration::seconds(1));
+        service.update_user_preferences(preferences).await.unwrap();
+        let delivered = service.get_user_notifications(&alice.id, false).await.unwrap();
+        assert_eq!(delivered.len(), 2);
+        assert!(delivered.iter().all(|n| n.delivered));
+        service.send_notification(notification(&alice, NotificationType::CommentAdded, noisy), &alice).await.unwrap();
+        assert_eq!(service.get_unread_count(&alice.id).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_muted_type_is_dropped() {
+        let service = NotificationService::new();
+        let alice = user("alice");
+        let project = Uuid::new_v4();
+        let mut preferences = service.get_user_preferences(&alice.id).await;
+        preferences.muted_types.insert(NotificationType::CommentAdded);
+        service.update_user_preferences(preferences.clone()).await.unwrap();
+
+        service.send_notification(notification(&alice, NotificationType::CommentAdded, project), &alice).await.unwrap();
+        service.send_notification(notification(&alice, NotificationType::ReviewAssigned, project), &alice).await.unwrap();
+
+        // Unmuting doesn't bring back what was dropped
+        preferences.muted_types.clear();
+        service.update_user_preferences(preferences).await.unwrap();
+        assert_eq!(service.deliver_due_snoozed().await.unwrap(), 0);
+        let delivered = service.get_user_notifications(&alice.id, false).await.unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].notification_type, NotificationType::ReviewAssigned);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_status_survives_restart() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notifications.json");
+        let service = NotificationService::with_storage(&path).unwrap();
+        let alice = user("alice");
+        let sent = notification(&alice, NotificationType::ReviewAssigned, Uuid::new_v4());
+        let id = sent.id;
+
+        service.send_notification(sent, &alice).await.unwrap();
+        assert_eq!(service.delivery_status(id).await, Some(DeliveryStatus::Delivered));
+        assert!(service.mark_read(id, "bob").await.is_err());
+        service.mark_read(id, &alice.id).await.unwrap();
+        assert_eq!(service.delivery_status(id).await, Some(DeliveryStatus::Read));
+
+        let restarted = NotificationService::with_storage(&path).unwrap();
+        assert_eq!(restarted.delivery_status(id).await, Some(DeliveryStatus::Read));
+        assert_eq!(restarted.get_user_notifications(&alice.id, false).await.unwrap().len(), 1);
+        assert_eq!(restarted.get_unread_count(&alice.id).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_snoozes_and_held_notifications_survive_restart() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notifications.json");
+        let service = NotificationService::with_storage(&path).unwrap();
+        let alice = user("alice");
+        let project = Uuid::new_v4();
+        let mut preferences = service.get_user_preferences(&alice.id).await;
+        preferences.snoozed_types.insert(NotificationType::CommentAdded, Utc::now() + Duration::hours(1));
+        service.update_user_preferences(preferences).await.unwrap();
+        let held = notification(&alice, NotificationType::CommentAdded, project);
+        let id = held.id;
+        service.send_notification(held, &alice).await.unwrap();
+        assert_eq!(service.delivery_status(id).await, Some(DeliveryStatus::Queued));
+
+        // The held notification and the snooze are both still there
+        let restarted = NotificationService::with_storage(&path).unwrap();
+        assert_eq!(restarted.delivery_status(id).await, Some(DeliveryStatus::Queued));
+        restarted.send_notification(notification(&alice, NotificationType::CommentAdded, project), &alice).await.unwrap();
+        assert!(restarted.get_user_notifications(&alice.id, false).await.unwrap().is_empty());
+
+        // Lifting the snooze delivers everything it held
+        let mut preferences = restarted.get_user_preferences(&alice.id).await;
+        assert!(preferences.snoozed_types.contains_key(&NotificationType::CommentAdded));
+        preferences.snoozed_types.clear();
+        restarted.update_user_preferences(preferences).await.unwrap();
+        assert_eq!(restarted.get_user_notifications(&alice.id, false).await.unwrap().len(), 2);
+        assert_eq!(restarted.delivery_status(id).await, Some(DeliveryStatus::Delivered));
+        let restarted_again = NotificationService::with_storage(&path).unwrap();
+        assert_eq!(restarted_again.get_unread_count(&alice.id).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unread_high_priority_notification_is_escalated_after_threshold() {
+        let service = NotificationService::new().with_escalation_threshold(Duration::hours(4));
+        let alice = user("alice");
+        let project = Uuid::new_v4();
+        let mut urgent = notification(&alice, NotificationType::ChangesRequested, project);
+        urgent.metadata.priority = NotificationPriority::High;
+        let urgent_id = urgent.id;
+        service.send_notification(urgent, &alice).await.unwrap();
+        service.send_notification(notification(&alice, NotificationType::CommentAdded, project), &alice).await.unwrap();
+        assert!(service.escalate_unread().await.unwrap().is_empty());
+
+        // Both were delivered five hours ago and never read
+        for notification in service.notifications.lock().await.values_mut() {
+            notification.delivered_at = Some(Utc::now() - Duration::hours(5));
+        }
+        let escalated = service.escalate_unread().await.unwrap();
+        assert_eq!(escalated.iter().map(|n| n.id).collect::<Vec<_>>(), [urgent_id]);
+        assert!(service.escalate_unread().await.unwrap().is_empty());
+        // Back on top, ahead of the newer normal priority one
+        assert_eq!(service.get_user_notifications(&alice.id, true).await.unwrap()[0].id, urgent_id);
+    }
+
+    #[tokio::test]
+    async fn test_message_is_rendered_in_the_recipients_language() {
+        let service = NotificationService::new();
+        let (alice, jonas, amelie) = (user("alice"), user("jonas"), user("amelie"));
+        for (recipient, language) in [(&jonas, Language::German), (&amelie, Language::French)] {
+            let mut preferences = service.get_user_preferences(&recipient.id).await;
+            preferences.language = language;
+            service.update_user_preferences(preferences).await.unwrap();
+        }
+
+        let (document_id, review_id) = (Uuid::new_v4(), Uuid::new_v4());
+        for reviewer in [&alice, &jonas, &amelie] {
+            service
+                .create_review_assigned_notification(reviewer, document_id, "Wartungshandbuch", review_id, None)
+                .await
+                .unwrap();
+        }
+        let received = |recipient: &User| {
+            let service = &service;
+            let id = recipient.id.clone();
+            async move { service.get_user_notifications(&id, false).await.unwrap().remove(0) }
+        };
+
+        let english = received(&alice).await;
+        assert_eq!(english.title, "New Review Assignment");
+        assert_eq!(english.message, "You have been assigned to review the document 'Wartungshandbuch'");
+        let german = received(&jonas).await;
+        assert_eq!(german.title, "Neue Prüfungsaufgabe");
+        assert_eq!(german.message, "Ihnen wurde die Prüfung des Dokuments „Wartungshandbuch“ zugewiesen");
+        // No French messages yet, so French recipients get English ones
+        assert_eq!(received(&amelie).await.message, english.message);
+    }
+}
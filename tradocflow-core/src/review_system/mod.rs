@@ -1,4 +1,6 @@
 use crate::{Result, NotificationService, User};
+use crate::services::authz::{Action, Authorizer, Resource};
+use crate::services::events::{AppEvent, EventBus};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
@@ -41,6 +43,9 @@ pub struct CommentPosition {
     pub column_start: u32,
     pub column_end: u32,
     pub language: String,
+    /// Translation unit the comment is on, when it is on one
+    #[serde(default)]
+    pub unit_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,6 +92,8 @@ pub struct ReviewSystem {
     comments: HashMap<Uuid, Vec<Comment>>,
     change_requests: HashMap<Uuid, Vec<ChangeRequest>>,
     notification_service: Option<Arc<NotificationService>>,
+    events: Option<EventBus>,
+    authorizer: Authorizer,
 }
 
 impl Default for ReviewSystem {
@@ -102,6 +109,8 @@ impl ReviewSystem {
             comments: HashMap::new(),
             change_requests: HashMap::new(),
             notification_service: None,
+            events: None,
+            authorizer: Authorizer::default(),
         }
     }
     
@@ -114,6 +123,20 @@ impl ReviewSystem {
         self.notification_service = Some(notification_service);
     }
 
+    /// Publish [`AppEvent::ReviewStatusChanged`] to `events` whenever a
+    /// review changes status
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Decide who may approve and reject reviews with `authorizer` rather
+    /// than the default permissions
+    pub fn with_authorizer(mut self, authorizer: Authorizer) -> Self {
+        self.authorizer = authorizer;
+        self
+    }
+
     pub async fn create_review(
         &mut self, 
         document_id: Uuid, 
@@ -184,6 +207,7 @@ impl ReviewSystem {
             let review = if let Some(review) = self.reviews.get_mut(&review_id) {
                 if review.status == ReviewStatus::Pending {
                     review.status = ReviewStatus::InProgress;
+                    publish_status(&self.events, review);
                 }
                 review.clone()
             } else {
@@ -225,6 +249,7 @@ impl ReviewSystem {
             if let Some(review) = self.reviews.get_mut(&review_id) {
                 if review.status == ReviewStatus::Pending {
                     review.status = ReviewStatus::InProgress;
+                    publish_status(&self.events, review);
                 }
             }
             Ok(())
@@ -236,15 +261,16 @@ impl ReviewSystem {
     pub async fn approve_document(
         &mut self, 
         document_id: Uuid, 
-        reviewer_id: String,
         document_title: &str,
         reviewer: &User,
         document_author: &User,
     ) -> Result<()> {
         // Find the review for this document and reviewer
         for review in self.reviews.values_mut() {
-            if review.document_id == document_id && review.reviewer_id == reviewer_id {
+            if review.document_id == document_id && review.reviewer_id == reviewer.id {
+                self.authorizer.require(reviewer, Action::Approve, &Resource::Review(review.id))?;
                 review.status = ReviewStatus::Approved;
+                publish_status(&self.events, review);
                 review.completed_at = Some(Utc::now());
                 
                 // Send notification if service is available
@@ -269,11 +295,13 @@ impl ReviewSystem {
     }
     
     // Keep the sync version for backward compatibility
-    pub fn approve_document_sync(&mut self, document_id: Uuid, reviewer_id: String) -> Result<()> {
+    pub fn approve_document_sync(&mut self, document_id: Uuid, reviewer: &User) -> Result<()> {
         // Find the review for this document and reviewer
         for review in self.reviews.values_mut() {
-            if review.document_id == document_id && review.reviewer_id == reviewer_id {
+            if review.document_id == document_id && review.reviewer_id == reviewer.id {
+                self.authorizer.require(reviewer, Action::Approve, &Resource::Review(review.id))?;
                 review.status = ReviewStatus::Approved;
+                publish_status(&self.events, review);
                 review.completed_at = Some(Utc::now());
                 return Ok(());
             }
@@ -284,21 +312,22 @@ impl ReviewSystem {
     pub async fn reject_document(
         &mut self, 
         document_id: Uuid, 
-        reviewer_id: String, 
         reason: String,
         document_title: &str,
         reviewer: &User,
         document_author: &User,
     ) -> Result<()> {
         for review in self.reviews.values_mut() {
-            if review.document_id == document_id && review.reviewer_id == reviewer_id {
+            if review.document_id == document_id && review.reviewer_id == reviewer.id {
+                self.authorizer.require(reviewer, Action::Approve, &Resource::Review(review.id))?;
                 review.status = ReviewStatus::Rejected;
+                publish_status(&self.events, review);
                 review.completed_at = Some(Utc::now());
                 
                 // Add a comment with the rejection reason
                 let comment = Comment {
                     id: Uuid::new_v4(),
-                    author_id: reviewer_id.clone(),
+                    author_id: reviewer.id.clone(),
                     content: format!("Document rejected: {reason}"),
                     position: CommentPosition {
                         line_start: 0,
@@ -306,6 +335,7 @@ impl ReviewSystem {
                         column_start: 0,
                         column_end: 0,
                         language: "en".to_string(),
+                        unit_id: None,
                     },
                     created_at: Utc::now(),
                     resolved: false,
@@ -338,16 +368,23 @@ impl ReviewSystem {
     }
     
     // Keep the sync version for backward compatibility
-    pub fn reject_document_sync(&mut self, document_id: Uuid, reviewer_id: String, reason: String) -> Result<()> {
+    pub fn reject_document_sync(
+        &mut self,
+        document_id: Uuid,
+        reason: String,
+        reviewer: &User,
+    ) -> Result<()> {
         for review in self.reviews.values_mut() {
-            if review.document_id == document_id && review.reviewer_id == reviewer_id {
+            if review.document_id == document_id && review.reviewer_id == reviewer.id {
+                self.authorizer.require(reviewer, Action::Approve, &Resource::Review(review.id))?;
                 review.status = ReviewStatus::Rejected;
+                publish_status(&self.events, review);
                 review.completed_at = Some(Utc::now());
                 
                 // Add a comment with the rejection reason
                 let comment = Comment {
                     id: Uuid::new_v4(),
-                    author_id: reviewer_id,
+                    author_id: reviewer.id.clone(),
                     content: format!("Document rejected: {reason}"),
                     position: CommentPosition {
                         line_start: 0,
@@ -355,6 +392,7 @@ impl ReviewSystem {
                         column_start: 0,
                         column_end: 0,
                         language: "en".to_string(),
+                        unit_id: None,
                     },
                     created_at: Utc::now(),
                     resolved: false,
@@ -383,6 +421,7 @@ impl ReviewSystem {
         for review in self.reviews.values_mut() {
             if review.document_id == document_id && review.reviewer_id == reviewer_id {
                 review.status = ReviewStatus::ChangesRequested;
+                publish_status(&self.events, review);
                 
                 if let Some(requests) = self.change_requests.get_mut(&review.id) {
                     requests.push(change_request);
@@ -416,6 +455,7 @@ impl ReviewSystem {
         for review in self.reviews.values_mut() {
             if review.document_id == document_id && review.reviewer_id == reviewer_id {
                 review.status = ReviewStatus::ChangesRequested;
+                publish_status(&self.events, review);
                 
                 if let Some(requests) = self.change_requests.get_mut(&review.id) {
                     requests.push(change_request);
@@ -429,6 +469,21 @@ impl ReviewSystem {
         Err(crate::TradocumentError::Review("Review not found for document and reviewer".to_string()))
     }
 
+    /// Add a change request to a review, which then has changes requested
+    pub fn add_change_request(&mut self, review_id: Uuid, change_request: ChangeRequest) -> Result<()> {
+        let Some(review) = self.reviews.get_mut(&review_id) else {
+            return Err(crate::TradocumentError::Review("Review not found".to_string()));
+        };
+        review.status = ReviewStatus::ChangesRequested;
+        publish_status(&self.events, review);
+        self.change_requests.entry(review_id).or_default().push(change_request);
+        Ok(())
+    }
+
+    pub fn get_review(&self, review_id: Uuid) -> Option<&Review> {
+        self.reviews.get(&review_id)
+    }
+
     pub fn get_reviews_for_document(&self, document_id: Uuid) -> Vec<&Review> {
         self.reviews.values().filter(|r| r.document_id == document_id).collect()
     }
@@ -464,4 +519,15 @@ impl ReviewSystem {
         }
         Err(crate::TradocumentError::Review("Comment not found".to_string()))
     }
+}
+
+/// Tell subscribers that `review` changed status
+fn publish_status(events: &Option<EventBus>, review: &Review) {
+    if let Some(events) = events {
+        events.publish(AppEvent::ReviewStatusChanged {
+            review_id: review.id,
+            document_id: review.document_id,
+            status: review.status.clone(),
+        });
+    }
 }
\ No newline at end of file
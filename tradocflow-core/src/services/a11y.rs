@@ -0,0 +1,629 @@
+use crate::export_engine::highlight::unescape_html;
+use crate::Document;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Contrast ratio WCAG AA requires for body text
+pub const MIN_CONTRAST_RATIO: f64 = 4.5;
+
+/// Link texts that don't say where a link goes, compared lowercase and
+/// without surrounding punctuation
+const NON_DESCRIPTIVE_LINK_TEXTS: &[&str] = &[
+    "click here", "click", "here", "this", "this link", "link", "more", "read more", "learn more",
+    "more info", "details", "go",
+];
+
+/// Elements without content or a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum A11yIssueKind {
+    /// An image has no `alt` attribute
+    MissingAltText,
+    /// A heading is more than one level below the heading before it
+    SkippedHeadingLevel,
+    /// Text and background colors contrast less than [`MIN_CONTRAST_RATIO`]
+    LowContrast,
+    /// A table has no header cells
+    TableWithoutHeaders,
+    /// A link's text doesn't describe its target
+    NonDescriptiveLink,
+}
+
+/// A WCAG violation in exported HTML
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct A11yIssue {
+    pub kind: A11yIssueKind,
+    /// 1-based line of the element
+    pub line: usize,
+    /// Opening tag of the element, or the CSS rule, at fault
+    pub element: String,
+    pub message: String,
+    /// How to fix it
+    pub remediation: String,
+}
+
+/// Check exported HTML for common WCAG violations: images without alt
+/// text, skipped heading levels, low contrast, tables without headers and
+/// links such as "click here".
+///
+/// Contrast is checked for inline `style` colors, which code highlighting
+/// uses, and for rules in `<style>` blocks. A rule's text color is compared
+/// with the background of the same rule, or of the rule for its ancestor
+/// selector, or else the page's, so the check approximates cascading.
+pub fn check_html(html: &str) -> Vec<A11yIssue> {
+    Checker::new(html).run()
+}
+
+/// Screenshots a document shows in `language` that have no alt text in it.
+/// Each issue cites the screenshot's placeholder and the line of its first
+/// use in the document.
+pub fn check_screenshot_alt_text(document: &Document, language: &str) -> Vec<A11yIssue> {
+    let Some(content) = document.content.get(language) else {
+        return Vec::new();
+    };
+    document
+        .metadata
+        .screenshots
+        .iter()
+        .filter(|screenshot| screenshot.language == language && screenshot.alt_text_for(language).is_none())
+        .filter_map(|screenshot| {
+            let placeholder = format!("{{screenshot:{}}}", screenshot.id);
+            let offset = content.find(&placeholder)?;
+            Some(A11yIssue {
+                kind: A11yIssueKind::MissingAltText,
+                line: line_of(content, offset),
+                message: format!("Screenshot {} has no {language} alt text", screenshot.id),
+                element: placeholder,
+                remediation: format!("Describe what the screenshot shows in its {language} alt text"),
+            })
+        })
+        .collect()
+}
+
+/// An RGB color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Rgb(u8, u8, u8);
+
+const WHITE: Rgb = Rgb(255, 255, 255);
+const BLACK: Rgb = Rgb(0, 0, 0);
+
+impl Rgb {
+    /// Parse a CSS color; translucent and unknown colors give `None`
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        if let Some(hex) = value.strip_prefix('#') {
+            let channel = |range: std::ops::Range<usize>| u8::from_str_radix(hex.get(range)?, 16).ok();
+            return match hex.len() {
+                3 => {
+                    let short = |i: usize| channel(i..i + 1).map(|c| c * 17);
+                    Some(Rgb(short(0)?, short(1)?, short(2)?))
+                }
+                6 => Some(Rgb(channel(0..2)?, channel(2..4)?, channel(4..6)?)),
+                8 if channel(6..8)? == 255 => Some(Rgb(channel(0..2)?, channel(2..4)?, channel(4..6)?)),
+                _ => None,
+            };
+        }
+        if let Some(arguments) = value.strip_prefix("rgb(").and_then(|rest| rest.strip_suffix(')')) {
+            let channels: Vec<u8> = arguments.split(',').map(|c| c.trim().parse().ok()).collect::<Option<_>>()?;
+            return match channels[..] {
+                [r, g, b] => Some(Rgb(r, g, b)),
+                _ => None,
+            };
+        }
+        match value.as_str() {
+            "white" => Some(WHITE),
+            "black" => Some(BLACK),
+            "gray" | "grey" => Some(Rgb(128, 128, 128)),
+            "silver" => Some(Rgb(192, 192, 192)),
+            "red" => Some(Rgb(255, 0, 0)),
+            "green" => Some(Rgb(0, 128, 0)),
+            "blue" => Some(Rgb(0, 0, 255)),
+            "yellow" => Some(Rgb(255, 255, 0)),
+            "orange" => Some(Rgb(255, 165, 0)),
+            _ => None,
+        }
+    }
+
+    /// Relative luminance as WCAG defines it
+    fn luminance(self) -> f64 {
+        let linear = |channel: u8| {
+            let c = f64::from(channel) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.0) + 0.7152 * linear(self.1) + 0.0722 * linear(self.2)
+    }
+
+    fn contrast(self, other: Rgb) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    fn to_css(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Text color and background declared by CSS declarations
+#[derive(Debug, Default, Clone, Copy)]
+struct Colors {
+    color: Option<Rgb>,
+    background: Option<Rgb>,
+}
+
+impl Colors {
+    fn from_declarations(declarations: &str) -> Self {
+        let mut colors = Colors::default();
+        for declaration in declarations.split(';') {
+            let Some((property, value)) = declaration.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().trim_end_matches("!important").trim();
+            match property.trim().to_ascii_lowercase().as_str() {
+                "color" => colors.color = Rgb::parse(value),
+                "background-color" => colors.background = Rgb::parse(value),
+                // The shorthand may list images and positions with the color
+                "background" => colors.background = value.split_whitespace().find_map(Rgb::parse),
+                _ => {}
+            }
+        }
+        colors
+    }
+}
+
+fn tag_regex() -> &'static Regex {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    TAG.get_or_init(|| {
+        Regex::new(r#"(?s)<!--.*?-->|<(/?)([a-zA-Z][a-zA-Z0-9]*)((?:[^>"']|"[^"]*"|'[^']*')*)>"#).expect("valid tag regex")
+    })
+}
+
+fn attribute_regex() -> &'static Regex {
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    ATTRIBUTE.get_or_init(|| {
+        Regex::new(r#"([^\s"'>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?"#).expect("valid attribute regex")
+    })
+}
+
+fn css_rule_regex() -> &'static Regex {
+    static CSS_RULE: OnceLock<Regex> = OnceLock::new();
+    CSS_RULE.get_or_init(|| Regex::new(r"([^{}]+)\{([^{}]*)\}").expect("valid CSS rule regex"))
+}
+
+fn css_comment_regex() -> &'static Regex {
+    static CSS_COMMENT: OnceLock<Regex> = OnceLock::new();
+    CSS_COMMENT.get_or_init(|| Regex::new(r"(?s)/\*.*?\*/").expect("valid CSS comment regex"))
+}
+
+fn parse_attributes(source: &str) -> HashMap<String, String> {
+    attribute_regex()
+        .captures_iter(source)
+        .map(|captures| {
+            let value = captures.get(2).or(captures.get(3)).or(captures.get(4)).map_or("", |m| m.as_str());
+            (captures[1].to_ascii_lowercase(), unescape_html(value))
+        })
+        .collect()
+}
+
+/// An element open at the current position
+struct OpenElement {
+    name: String,
+    tag: String,
+    line: usize,
+    /// Effective colors, inherited where the element declares none
+    color: Rgb,
+    background: Rgb,
+    /// Whether it or an ancestor declares a color inline
+    styled: bool,
+}
+
+/// A link whose text is being collected
+struct OpenLink {
+    tag: String,
+    line: usize,
+    text: String,
+    /// `aria-label` or `title`, which name the link instead of its text
+    label: Option<String>,
+}
+
+struct Checker<'a> {
+    html: &'a str,
+    issues: Vec<A11yIssue>,
+    stack: Vec<OpenElement>,
+    /// For each open table, whether it has a header cell so far
+    tables: Vec<(String, usize, bool)>,
+    link: Option<OpenLink>,
+    last_heading: Option<u8>,
+    /// Color pairs already reported, so a theme's colors are reported once
+    reported_contrast: HashSet<(Rgb, Rgb)>,
+    page: Colors,
+}
+
+impl<'a> Checker<'a> {
+    fn new(html: &'a str) -> Self {
+        Self {
+            html,
+            issues: Vec::new(),
+            stack: Vec::new(),
+            tables: Vec::new(),
+            link: None,
+            last_heading: None,
+            reported_contrast: HashSet::new(),
+            page: Colors::default(),
+        }
+    }
+
+    fn run(mut self) -> Vec<A11yIssue> {
+        let html = self.html;
+        let mut last = 0;
+        let mut skip_until = 0;
+        // Lines are counted as the scan moves on, not from the start each time
+        let mut line = 1;
+        let mut counted = 0;
+        for captures in tag_regex().captures_iter(html) {
+            let whole = captures.get(0).expect("whole match");
+            if whole.start() < skip_until {
+                continue;
+            }
+            self.text(&html[last..whole.start()]);
+            last = whole.end();
+            let Some(name) = captures.get(2) else {
+                continue;
+            };
+            let name = name.as_str().to_ascii_lowercase();
+            line += html[counted..whole.start()].matches('\n').count();
+            counted = whole.start();
+            if captures[1].is_empty() {
+                // Raw text elements: style is checked as CSS, script is skipped
+                if name == "style" || name == "script" {
+                    let close = format!("</{name}");
+                    let end = find_ignore_case(html, &close, whole.end()).unwrap_or(html.len());
+                    if name == "style" {
+                        self.stylesheet(&html[whole.end()..end], line);
+                    }
+                    skip_until = end;
+                    last = end;
+                    continue;
+                }
+                self.open(&name, whole.as_str(), &captures[3], line);
+            } else {
+                self.close(&name);
+            }
+        }
+        self.text(&html[last..]);
+
+        // Tables left open still count
+        while let Some((tag, line, has_header)) = self.tables.pop() {
+            if !has_header {
+                self.table_issue(tag, line);
+            }
+        }
+        self.issues.sort_by_key(|issue| issue.line);
+        self.issues
+    }
+
+    fn open(&mut self, name: &str, tag: &str, attributes: &str, line: usize) {
+        let attributes = parse_attributes(attributes);
+        let tag = truncate_tag(tag);
+        match name {
+            "img" => {
+                if let Some(link) = &mut self.link {
+                    link.text.push_str(attributes.get("alt").map_or("", String::as_str));
+                }
+                let decorative = matches!(attributes.get("role").map(String::as_str), Some("presentation" | "none"))
+                    || attributes.get("aria-hidden").map(String::as_str) == Some("true");
+                if !attributes.contains_key("alt") && !decorative {
+                    self.issues.push(A11yIssue {
+                        kind: A11yIssueKind::MissingAltText,
+                        line,
+                        element: tag.clone(),
+                        message: "Image has no alt text".to_string(),
+                        remediation: "Describe the image in an alt attribute, or use alt=\"\" if it is decorative"
+                            .to_string(),
+                    });
+                }
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name.as_bytes()[1] - b'0';
+                if let Some(previous) = self.last_heading.filter(|previous| level > previous + 1) {
+                    self.issues.push(A11yIssue {
+                        kind: A11yIssueKind::SkippedHeadingLevel,
+                        line,
+                        element: tag.clone(),
+                        message: format!("Heading level {level} follows level {previous}, skipping a level"),
+                        remediation: format!("Use an h{} heading here, or add the missing level above it", previous + 1),
+                    });
+                }
+                self.last_heading = Some(level);
+            }
+            "table" => self.tables.push((tag.clone(), line, false)),
+            "th" => {
+                if let Some(table) = self.tables.last_mut() {
+                    table.2 = true;
+                }
+            }
+            "a" if attributes.contains_key("href") => {
+                let label = ["aria-label", "title"]
+                    .iter()
+                    .find_map(|name| attributes.get(*name).filter(|label| !label.trim().is_empty()).cloned());
+                self.link = Some(OpenLink { tag: tag.clone(), line, text: String::new(), label });
+            }
+            _ => {}
+        }
+
+        if VOID_ELEMENTS.contains(&name) {
+            return;
+        }
+        let declared = attributes.get("style").map(|style| Colors::from_declarations(style)).unwrap_or_default();
+        let parent = self.stack.last();
+        let inherited_color = parent.map_or(self.page.color.unwrap_or(BLACK), |parent| parent.color);
+        let inherited_background = parent.map_or(self.page.background.unwrap_or(WHITE), |parent| parent.background);
+        let styled = parent.is_some_and(|parent| parent.styled) || declared.color.is_some() || declared.background.is_some();
+        self.stack.push(OpenElement {
+            name: name.to_string(),
+            tag,
+            line,
+            color: declared.color.unwrap_or(inherited_color),
+            background: declared.background.unwrap_or(inherited_background),
+            styled,
+        });
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "table" => {
+                if let Some((tag, line, false)) = self.tables.pop() {
+                    self.table_issue(tag, line);
+                }
+            }
+            "a" => {
+                if let Some(link) = self.link.take() {
+                    self.link_issue(link);
+                }
+            }
+            _ => {}
+        }
+        // Close up to the matching element; stray end tags are ignored
+        if let Some(position) = self.stack.iter().rposition(|element| element.name == name) {
+            self.stack.truncate(position);
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+        if let Some(link) = &mut self.link {
+            link.text.push_str(text);
+        }
+        let Some(element) = self.stack.last().filter(|element| element.styled) else {
+            return;
+        };
+        let (color, background) = (element.color, element.background);
+        let (tag, line) = (element.tag.clone(), element.line);
+        self.contrast_issue(color, background, tag, line);
+    }
+
+    /// Check the rules of a `<style>` block starting on `line`
+    fn stylesheet(&mut self, css: &str, line: usize) {
+        let css = css_comment_regex().replace_all(css, |m: &regex::Captures| "\n".repeat(m[0].matches('\n').count()));
+        let mut rules: Vec<(String, Colors, usize)> = Vec::new();
+        for captures in css_rule_regex().captures_iter(&css) {
+            let selectors = captures.get(1).expect("selector group");
+            let colors = Colors::from_declarations(&captures[2]);
+            let leading = selectors.as_str().len() - selectors.as_str().trim_start().len();
+            let rule_line = line + css[..selectors.start() + leading].matches('\n').count();
+            for selector in selectors.as_str().split(',') {
+                let selector = selector.split_whitespace().collect::<Vec<_>>().join(" ");
+                if matches!(selector.as_str(), "body" | "html") {
+                    self.page.color = colors.color.or(self.page.color);
+                    self.page.background = colors.background.or(self.page.background);
+                }
+                rules.push((selector, colors, rule_line));
+            }
+        }
+
+        let backgrounds: HashMap<&str, Rgb> = rules
+            .iter()
+            .filter_map(|(selector, colors, _)| Some((selector.as_str(), colors.background?)))
+            .collect();
+        let page_background = self.page.background.unwrap_or(WHITE);
+        for (selector, colors, rule_line) in &rules {
+            let Some(color) = colors.color else {
+                continue;
+            };
+            let ancestor = selector.rsplit_once(' ').and_then(|(ancestor, _)| backgrounds.get(ancestor.trim()));
+            let background = colors.background.or(ancestor.copied()).unwrap_or(page_background);
+            self.contrast_issue(color, background, format!("{selector} {{ color: {} }}", color.to_css()), *rule_line);
+        }
+    }
+
+    fn contrast_issue(&mut self, color: Rgb, background: Rgb, element: String, line: usize) {
+        let ratio = color.contrast(background);
+        if ratio >= MIN_CONTRAST_RATIO || !self.reported_contrast.insert((color, background)) {
+            return;
+        }
+        self.issues.push(A11yIssue {
+            kind: A11yIssueKind::LowContrast,
+            line,
+            element,
+            message: format!(
+                "Text color {} on {} has a contrast ratio of {ratio:.2}:1",
+                color.to_css(),
+                background.to_css()
+            ),
+            remediation: format!("Darken or lighten the colors to a contrast ratio of at least {MIN_CONTRAST_RATIO}:1"),
+        });
+    }
+
+    fn table_issue(&mut self, tag: String, line: usize) {
+        self.issues.push(A11yIssue {
+            kind: A11yIssueKind::TableWithoutHeaders,
+            line,
+            element: tag,
+            message: "Table has no header cells".to_string(),
+            remediation: "Mark the header row or column with th elements".to_string(),
+        });
+    }
+
+    fn link_issue(&mut self, link: OpenLink) {
+        let text = unescape_html(&link.label.unwrap_or(link.text));
+        let normalized = text
+            .trim()
+            .trim_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace())
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !normalized.is_empty() && !NON_DESCRIPTIVE_LINK_TEXTS.contains(&normalized.as_str()) {
+            return;
+        }
+        let message = if normalized.is_empty() {
+            "Link has no text".to_string()
+        } else {
+            format!("Link text \"{}\" doesn't describe its target", text.trim())
+        };
+        self.issues.push(A11yIssue {
+            kind: A11yIssueKind::NonDescriptiveLink,
+            line: link.line,
+            element: link.tag,
+            message,
+            remediation: "Use link text that names the target, such as \"Installation guide\"".to_string(),
+        });
+    }
+}
+
+/// 1-based line of a byte offset
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count() + 1
+}
+
+/// Offset of the first match of `needle` in `haystack` from `from` on,
+/// ignoring ASCII case, without copying the haystack
+fn find_ignore_case(haystack: &str, needle: &str, from: usize) -> Option<usize> {
+    let needle = needle.as_bytes();
+    haystack.as_bytes()[from..]
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle))
+        .map(|position| from + position)
+}
+
+/// A tag short enough to cite in a report
+fn truncate_tag(tag: &str) -> String {
+    const MAX_CHARS: usize = 120;
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    if tag.chars().count() <= MAX_CHARS {
+        return tag;
+    }
+    let mut short: String = tag.chars().take(MAX_CHARS - 1).collect();
+    short.push('…');
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DocumentMetadata, ScreenshotReference};
+
+    fn kinds(html: &str) -> Vec<A11yIssueKind> {
+        check_html(html).into_iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn test_image_without_alt_flagged() {
+        let html = "<p>Wiring:</p>\n<img src=\"images/wiring.png\">\n<img src=\"divider.png\" alt=\"\">";
+        let issues = check_html(html);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, A11yIssueKind::MissingAltText);
+        assert_eq!(issues[0].line, 2);
+        assert_eq!(issues[0].element, "<img src=\"images/wiring.png\">");
+        assert!(issues[0].remediation.contains("alt"));
+    }
+
+    #[test]
+    fn test_screenshot_without_alt_text_in_language_flagged() {
+        let screenshot = |id: &str, language: &str, alt_text: &[(&str, &str)]| ScreenshotReference {
+            id: id.to_string(),
+            language: language.to_string(),
+            screen_config: "{}".to_string(),
+            generated_at: None,
+            caption: None,
+            content_hash: None,
+            alt_text: alt_text.iter().map(|(language, text)| (language.to_string(), text.to_string())).collect(),
+        };
+        let document = Document {
+            title: "Wiring".to_string(),
+            content: HashMap::from([
+                ("en".to_string(), "Wiring:\n\n{screenshot:panel}\n\n{screenshot:relay}\n".to_string()),
+                ("de".to_string(), "Verkabelung:\n\n{screenshot:panel}\n\n{screenshot:relay}\n".to_string()),
+            ]),
+            metadata: DocumentMetadata {
+                project_id: None,
+                screenshots: vec![
+                    screenshot("panel", "en", &[("en", "Control panel")]),
+                    screenshot("panel", "de", &[("en", "Control panel")]),
+                    screenshot("relay", "en", &[("en", "Relay board"), ("de", "Relaisplatine")]),
+                    screenshot("relay", "de", &[("en", "Relay board"), ("de", "Relaisplatine")]),
+                ],
+                version: None,
+                custom: HashMap::new(),
+            },
+        };
+
+        assert!(check_screenshot_alt_text(&document, "en").is_empty());
+        let issues = check_screenshot_alt_text(&document, "de");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, A11yIssueKind::MissingAltText);
+        assert_eq!(issues[0].element, "{screenshot:panel}");
+        assert_eq!(issues[0].line, 3);
+        assert!(issues[0].message.contains("no de alt text"));
+    }
+
+    #[test]
+    fn test_skipped_heading_level_flagged() {
+        let html = "<h1>Manual</h1><h2>Installation</h2><h4>Mounting</h4><h2>Use</h2><h3>Start</h3>";
+        let issues = check_html(html);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, A11yIssueKind::SkippedHeadingLevel);
+        assert_eq!(issues[0].element, "<h4>");
+        assert!(issues[0].remediation.contains("h3"));
+    }
+
+    #[test]
+    fn test_click_here_link_flagged() {
+        let html = r#"<p>For wiring, <a href="wiring.html">click here</a>. See the
+<a href="install.html">installation guide</a> or <a href="faq.html" aria-label="Frequently asked questions">here</a>.</p>"#;
+        let issues = check_html(html);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, A11yIssueKind::NonDescriptiveLink);
+        assert_eq!(issues[0].element, "<a href=\"wiring.html\">");
+        assert!(issues[0].message.contains("click here"));
+    }
+
+    #[test]
+    fn test_low_contrast_and_headerless_tables_flagged() {
+        let html = r#"<style>
+body { color: #333; }
+.note { color: #999; background: #fff; }
+</style>
+<pre style="background-color:#ffffff"><code><span style="color:#dddddd;">let</span> <span style="color:#dddddd;">x</span></code></pre>
+<table><tr><td>A</td></tr></table>
+<table><tr><th>Part</th></tr><tr><td>Clapper</td></tr></table>"#;
+        assert_eq!(
+            kinds(html),
+            vec![A11yIssueKind::LowContrast, A11yIssueKind::LowContrast, A11yIssueKind::TableWithoutHeaders]
+        );
+        let issues = check_html(html);
+        assert_eq!(issues[0].line, 3);
+        assert!(issues[0].element.starts_with(".note"));
+        assert_eq!(issues[1].element, "<span style=\"color:#dddddd;\">");
+    }
+}
@@ -0,0 +1,342 @@
+use crate::database::DatabasePool;
+use crate::git_integration::ReleaseTag;
+use crate::i18n::{self, Language};
+use crate::review_system::ReviewStatus;
+use crate::services::events::{AppEvent, EventSubscriber};
+use crate::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// What kind of thing happened, for picking an icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    DocumentSaved,
+    ReviewApproved,
+    TermAdded,
+    ReleaseTagged,
+}
+
+impl ActivityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityKind::DocumentSaved => "document_saved",
+            ActivityKind::ReviewApproved => "review_approved",
+            ActivityKind::TermAdded => "term_added",
+            ActivityKind::ReleaseTagged => "release_tagged",
+        }
+    }
+}
+
+/// Something that happened in a project and what it happened to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Activity {
+    DocumentSaved { document_id: Uuid, file: String },
+    /// `document` is the title of the document when it was approved
+    ReviewApproved { review_id: Uuid, document_id: Uuid, document: String },
+    TermAdded { term_id: Uuid, term: String },
+    ReleaseTagged { tag: String, commit: String, author: String },
+}
+
+impl Activity {
+    pub fn from_release(release: &ReleaseTag) -> Self {
+        Activity::ReleaseTagged { tag: release.name.clone(), commit: release.commit.clone(), author: release.author.clone() }
+    }
+
+    pub fn kind(&self) -> ActivityKind {
+        match self {
+            Activity::DocumentSaved { .. } => ActivityKind::DocumentSaved,
+            Activity::ReviewApproved { .. } => ActivityKind::ReviewApproved,
+            Activity::TermAdded { .. } => ActivityKind::TermAdded,
+            Activity::ReleaseTagged { .. } => ActivityKind::ReleaseTagged,
+        }
+    }
+
+    /// What the activity happened to, such as a document id or tag name
+    fn subject(&self) -> String {
+        match self {
+            Activity::DocumentSaved { document_id, .. } => document_id.to_string(),
+            Activity::ReviewApproved { review_id, .. } => review_id.to_string(),
+            Activity::TermAdded { term_id, .. } => term_id.to_string(),
+            Activity::ReleaseTagged { tag, .. } => tag.clone(),
+        }
+    }
+}
+
+/// Where a page of a feed ended; the next page starts after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityCursor {
+    occurred_at: i64,
+    id: i64,
+}
+
+/// An entry of a project's activity feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityItem {
+    pub id: i64,
+    pub project_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub activity: Activity,
+}
+
+impl ActivityItem {
+    pub fn kind(&self) -> ActivityKind {
+        self.activity.kind()
+    }
+
+    /// Cursor for the page of items older than this one
+    pub fn cursor(&self) -> ActivityCursor {
+        ActivityCursor { occurred_at: self.occurred_at.timestamp_micros(), id: self.id }
+    }
+
+    /// One line on what happened, in `language`
+    pub fn summary(&self, language: &Language) -> String {
+        let args: HashMap<String, String> = match &self.activity {
+            Activity::DocumentSaved { file, .. } => HashMap::from([("file".to_string(), file.clone())]),
+            Activity::ReviewApproved { document, .. } => HashMap::from([("document".to_string(), document.clone())]),
+            Activity::TermAdded { term, .. } => HashMap::from([("term".to_string(), term.clone())]),
+            Activity::ReleaseTagged { tag, author, .. } => {
+                HashMap::from([("tag".to_string(), tag.clone()), ("author".to_string(), author.clone())])
+            }
+        };
+        i18n::t_for_with_args(&format!("activity.{}", self.kind().as_str()), language.code(), &args)
+    }
+}
+
+/// Audit log of what happened in each project, read back as a feed
+pub struct ActivityLog {
+    pool: DatabasePool,
+}
+
+impl ActivityLog {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(&self, project_id: Uuid, activity: Activity, occurred_at: DateTime<Utc>) -> Result<ActivityItem> {
+        let conn = self.pool.lock().await;
+        conn.execute(
+            "INSERT INTO activity_log (project_id, kind, subject, activity, occurred_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                project_id.to_string(),
+                activity.kind().as_str(),
+                activity.subject(),
+                serde_json::to_string(&activity)?,
+                occurred_at.timestamp_micros()
+            ],
+        )?;
+        Ok(ActivityItem { id: conn.last_insert_rowid(), project_id, occurred_at, activity })
+    }
+
+    /// Record `event` as happening now in the feed of the project it belongs
+    /// to, if feeds show it. Saves and reviews belong to the project of their
+    /// document, so those of documents outside any project are left out.
+    pub async fn record_event(&self, event: &AppEvent) -> Result<Option<ActivityItem>> {
+        let (project_id, activity) = match event {
+            AppEvent::DocumentSaved { document_id, path } => {
+                let Some((project_id, _)) = self.document_project(*document_id).await? else {
+                    return Ok(None);
+                };
+                let file = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().into_owned();
+                (project_id, Activity::DocumentSaved { document_id: *document_id, file })
+            }
+            AppEvent::ReviewStatusChanged { review_id, document_id, status: ReviewStatus::Approved } => {
+                let Some((project_id, document)) = self.document_project(*document_id).await? else {
+                    return Ok(None);
+                };
+                (project_id, Activity::ReviewApproved { review_id: *review_id, document_id: *document_id, document })
+            }
+            AppEvent::TermAdded { term_id, project_id, term } => {
+                (*project_id, Activity::TermAdded { term_id: *term_id, term: term.clone() })
+            }
+            _ => return Ok(None),
+        };
+        self.record(project_id, activity, Utc::now()).await.map(Some)
+    }
+
+    /// Project and title of a stored document, if it is in a project
+    async fn document_project(&self, document_id: Uuid) -> Result<Option<(Uuid, String)>> {
+        let row = self
+            .pool
+            .lock()
+            .await
+            .query_row("SELECT project_id, title FROM documents WHERE id = ?1", params![document_id.to_string()], |row| {
+                Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?))
+            })
+            .optional()?;
+        Ok(row.and_then(|(project_id, title)| Some((Uuid::parse_str(&project_id?).ok()?, title))))
+    }
+
+    /// Record the releases among `releases` that aren't in the log yet, at
+    /// the time they were tagged. Returns how many were new.
+    pub async fn record_releases(&self, project_id: Uuid, releases: &[ReleaseTag]) -> Result<usize> {
+        let mut recorded = 0;
+        for release in releases {
+            let known: bool = self.pool.lock().await.query_row(
+                "SELECT EXISTS (SELECT 1 FROM activity_log WHERE project_id = ?1 AND kind = ?2 AND subject = ?3)",
+                params![project_id.to_string(), ActivityKind::ReleaseTagged.as_str(), release.name],
+                |row| row.get(0),
+            )?;
+            if !known {
+                self.record(project_id, Activity::from_release(release), release.date).await?;
+                recorded += 1;
+            }
+        }
+        Ok(recorded)
+    }
+
+    /// Record what `subscriber` receives into the feeds of the projects it
+    /// belongs to until its bus closes
+    pub fn follow(self: Arc<Self>, mut subscriber: EventSubscriber) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(event) = subscriber.recv().await {
+                if let Err(e) = self.record_event(&event).await {
+                    log::warn!("Failed to record activity for {event:?}: {e}");
+                }
+            }
+        })
+    }
+
+    /// Up to `limit` items of a project's feed, newest first, starting after
+    /// `before` or at the newest item
+    pub async fn feed(&self, project_id: Uuid, limit: usize, before: Option<ActivityCursor>) -> Result<Vec<ActivityItem>> {
+        let (occurred_at, id) = before.map_or((i64::MAX, i64::MAX), |cursor| (cursor.occurred_at, cursor.id));
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, occurred_at, activity FROM activity_log
+             WHERE project_id = ?1 AND (occurred_at < ?2 OR (occurred_at = ?2 AND id < ?3))
+             ORDER BY occurred_at DESC, id DESC
+             LIMIT ?4",
+        )?;
+        let items = stmt.query_map(
+            params![project_id.to_string(), occurred_at, id, i64::try_from(limit).unwrap_or(i64::MAX)],
+            row_to_item,
+        )?;
+        Ok(items.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+}
+
+fn row_to_item(row: &Row) -> rusqlite::Result<ActivityItem> {
+    let invalid = |index: usize, e: Box<dyn std::error::Error + Send + Sync>| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, e)
+    };
+    Ok(ActivityItem {
+        id: row.get(0)?,
+        project_id: Uuid::parse_str(&row.get::<_, String>(1)?).map_err(|e| invalid(1, Box::new(e)))?,
+        occurred_at: DateTime::from_timestamp_micros(row.get(2)?)
+            .ok_or_else(|| invalid(2, "timestamp out of range".into()))?,
+        activity: serde_json::from_str(&row.get::<_, String>(3)?).map_err(|e| invalid(3, Box::new(e)))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::services::events::EventBus;
+    use chrono::Duration;
+    use std::path::PathBuf;
+
+    /// Store a project with one document titled `title`, returning their ids
+    async fn project_with_document(pool: &DatabasePool, title: &str) -> (Uuid, Uuid) {
+        let (project_id, document_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let conn = pool.lock().await;
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO projects (id, name, owner_id, created_at, updated_at) VALUES (?1, 'Manual', 'owner', ?2, ?2)",
+            params![project_id.to_string(), now],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO documents (id, title, project_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![document_id.to_string(), title, project_id.to_string(), now],
+        )
+        .unwrap();
+        (project_id, document_id)
+    }
+
+    #[tokio::test]
+    async fn test_operations_appear_newest_first() {
+        let pool = Database::in_memory().unwrap().pool();
+        let log = Arc::new(ActivityLog::new(pool.clone()));
+        let (project_id, document_id) = project_with_document(&pool, "Introduction").await;
+        let (other_project, other_document) = project_with_document(&pool, "Wartung").await;
+        let bus = EventBus::default();
+        let follower = log.clone().follow(bus.subscribe());
+
+        bus.publish(AppEvent::DocumentSaved { document_id, path: PathBuf::from("manual/intro.md") });
+        bus.publish(AppEvent::ReviewStatusChanged { review_id: Uuid::new_v4(), document_id, status: ReviewStatus::InProgress });
+        bus.publish(AppEvent::ReviewStatusChanged { review_id: Uuid::new_v4(), document_id, status: ReviewStatus::Approved });
+        // Other projects, and a document outside any project
+        bus.publish(AppEvent::DocumentSaved { document_id: other_document, path: PathBuf::from("manual/wartung.md") });
+        bus.publish(AppEvent::ReviewStatusChanged { review_id: Uuid::new_v4(), document_id: other_document, status: ReviewStatus::Approved });
+        bus.publish(AppEvent::DocumentSaved { document_id: Uuid::new_v4(), path: PathBuf::from("notes.md") });
+        bus.publish(AppEvent::TermAdded { term_id: Uuid::new_v4(), project_id: other_project, term: "Joch".to_string() });
+        bus.publish(AppEvent::TermAdded { term_id: Uuid::new_v4(), project_id, term: "Klöppel".to_string() });
+        drop(bus);
+        follower.await.unwrap();
+
+        let release = ReleaseTag {
+            name: "v1.0".to_string(),
+            commit: "abc123".to_string(),
+            author: "Olga".to_string(),
+            date: Utc::now() + Duration::seconds(5),
+            summary: "First edition".to_string(),
+        };
+        assert_eq!(log.record_releases(project_id, std::slice::from_ref(&release)).await.unwrap(), 1);
+        assert_eq!(log.record_releases(project_id, &[release]).await.unwrap(), 0);
+
+        let feed = log.feed(project_id, 10, None).await.unwrap();
+        let kinds: Vec<ActivityKind> = feed.iter().map(ActivityItem::kind).collect();
+        assert_eq!(
+            kinds,
+            vec![ActivityKind::ReleaseTagged, ActivityKind::TermAdded, ActivityKind::ReviewApproved, ActivityKind::DocumentSaved]
+        );
+        assert_eq!(feed[0].summary(&Language::English), "Release v1.0 tagged by Olga");
+        assert_eq!(feed[1].summary(&Language::German), "Begriff „Klöppel“ hinzugefügt");
+        assert_eq!(feed[2].summary(&Language::English), "Review of Introduction approved");
+        assert_eq!(feed[3].summary(&Language::English), "intro.md saved");
+
+        let other_kinds: Vec<ActivityKind> = log.feed(other_project, 10, None).await.unwrap().iter().map(ActivityItem::kind).collect();
+        assert_eq!(other_kinds, vec![ActivityKind::TermAdded, ActivityKind::ReviewApproved, ActivityKind::DocumentSaved]);
+        assert!(log.feed(Uuid::new_v4(), 10, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pages_do_not_overlap() {
+        let log = ActivityLog::new(Database::in_memory().unwrap().pool());
+        let project_id = Uuid::new_v4();
+        // Items sharing a timestamp are still paged apart
+        let at = Utc::now();
+        for index in 0..5 {
+            let activity = Activity::TermAdded { term_id: Uuid::new_v4(), term: format!("term {index}") };
+            log.record(project_id, activity, at - Duration::minutes(index / 2)).await.unwrap();
+        }
+
+        let mut pages = Vec::new();
+        let mut before = None;
+        loop {
+            let page = log.feed(project_id, 2, before).await.unwrap();
+            let Some(last) = page.last() else { break };
+            before = Some(last.cursor());
+            pages.push(page);
+        }
+
+        assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 1]);
+        let terms: Vec<String> = pages
+            .into_iter()
+            .flatten()
+            .map(|item| match item.activity {
+                Activity::TermAdded { term, .. } => term,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(terms, vec!["term 1", "term 0", "term 3", "term 2", "term 4"]);
+    }
+}
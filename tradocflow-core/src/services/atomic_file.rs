@@ -0,0 +1,249 @@
+use sha2::{Digest, Sha256};
+use std::fs::Permissions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufWriter};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+/// Bytes written between progress reports
+pub const SAVE_CHUNK_SIZE: usize = 64 * 1024;
+/// Symlinks followed to the file a save replaces before giving up, as for
+/// a loop of links
+const MAX_SYMLINK_DEPTH: usize = 40;
+
+/// How far a save has got, for showing progress of big writes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveProgress {
+    pub written: u64,
+    /// Bytes the whole save writes
+    pub total: u64,
+}
+
+impl SaveProgress {
+    /// Share written, from 0.0 to 1.0; an empty save is complete
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.written as f64 / self.total as f64
+        }
+    }
+}
+
+/// Replace the file at `path` with what `reader` yields, returning the
+/// bytes written.
+///
+/// The data goes to a temporary file next to `path`, which is flushed to
+/// disk and then renamed over it, so a crash or error mid-save leaves the
+/// original as it was. A symlink at `path` is followed and the file it
+/// points to replaced, keeping its permissions. `on_written` gets the bytes
+/// written so far after each chunk.
+pub async fn write_atomic_from(
+    path: &Path,
+    mut reader: impl AsyncRead + Unpin,
+    mut on_written: impl FnMut(u64),
+) -> io::Result<u64> {
+    let path = &resolve_symlinks(path)?;
+    let temp = temp_path(path);
+    let result: io::Result<u64> = async {
+        let file = tokio::fs::File::create(&temp).await?;
+        if let Some(permissions) = existing_permissions(path)? {
+            file.set_permissions(permissions).await?;
+        }
+        let mut file = BufWriter::with_capacity(SAVE_CHUNK_SIZE, file);
+        let mut buffer = vec![0; SAVE_CHUNK_SIZE];
+        let mut written = 0;
+        loop {
+            let read = reader.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            file.write_all(&buffer[..read]).await?;
+            written += read as u64;
+            on_written(written);
+        }
+        file.flush().await?;
+        file.into_inner().sync_all().await?;
+        tokio::fs::rename(&temp, path).await?;
+        Ok(written)
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&temp).await;
+    }
+    result
+}
+
+/// [`write_atomic_from`] for data in memory, reporting to `progress`
+pub async fn write_atomic(path: &Path, contents: &[u8], progress: Option<&watch::Sender<SaveProgress>>) -> io::Result<()> {
+    let total = contents.len() as u64;
+    write_atomic_from(path, contents, |written| {
+        if let Some(progress) = progress {
+            progress.send_replace(SaveProgress { written, total });
+        }
+    })
+    .await?;
+    Ok(())
+}
+
+/// What a conditional write found on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConditionalWrite {
+    /// The file was as expected and now holds the new contents, at `revision`
+    Written { revision: String },
+    /// The file changed since it was last read or written, so nothing was
+    /// written. `found` is the file's revision now, `None` if it is gone.
+    Changed { found: Option<String> },
+}
+
+/// Revision of file contents, which changes whenever the contents do
+pub fn content_revision(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// Revision of the file at `path`, `None` if there is no such file
+pub async fn disk_revision(path: &Path) -> io::Result<Option<String>> {
+    match tokio::fs::read(path).await {
+        Ok(contents) => Ok(Some(content_revision(&contents))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// [`write_atomic`], but only if the file at `path` is still at `expected`,
+/// the revision it had when last read or written. `None` expects no file.
+/// Anything else means the file was changed by someone else, and is left
+/// as it is.
+pub async fn write_atomic_if_unchanged(
+    path: &Path,
+    expected: Option<&str>,
+    contents: &[u8],
+    progress: Option<&watch::Sender<SaveProgress>>,
+) -> io::Result<ConditionalWrite> {
+    let found = disk_revision(path).await?;
+    if found.as_deref() != expected {
+        return Ok(ConditionalWrite::Changed { found });
+    }
+    write_atomic(path, contents, progress).await?;
+    Ok(ConditionalWrite::Written { revision: content_revision(contents) })
+}
+
+/// [`write_atomic`] for callers that aren't async
+pub fn write_atomic_blocking(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let path = &resolve_symlinks(path)?;
+    let temp = temp_path(path);
+    let result = write_synced(&temp, contents, existing_permissions(path)?).and_then(|()| std::fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+fn write_synced(path: &Path, contents: &[u8], permissions: Option<Permissions>) -> io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    if let Some(permissions) = permissions {
+        file.set_permissions(permissions)?;
+    }
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// The file `path` names once symlinks are followed, so that saving
+/// replaces the file a link points to rather than the link
+fn resolve_symlinks(path: &Path) -> io::Result<PathBuf> {
+    let mut resolved = path.to_path_buf();
+    for _ in 0..MAX_SYMLINK_DEPTH {
+        match std::fs::symlink_metadata(&resolved) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                // Relative targets are relative to the link's directory
+                let target = std::fs::read_link(&resolved)?;
+                resolved = resolved.parent().map_or_else(|| target.clone(), |dir| dir.join(&target));
+            }
+            Ok(_) => return Ok(resolved),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(resolved),
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::other(format!("Too many levels of symbolic links saving {}", path.display())))
+}
+
+/// Permissions of the file a save replaces, `None` if there is none yet
+fn existing_permissions(path: &Path) -> io::Result<Option<Permissions>> {
+    match std::fs::metadata(path) {
+        Ok(metadata) => Ok(Some(metadata.permissions())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// A hidden file next to `path`, so renaming it over `path` stays on one
+/// file system
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map_or_else(|| "save".into(), |name| name.to_string_lossy());
+    path.with_file_name(format!(".{name}.{}.tmp", Uuid::new_v4().simple()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tempfile::TempDir;
+    use tokio::io::ReadBuf;
+
+    /// A source that fails, like a disk filling up mid-save
+    struct FailingReader;
+
+    impl AsyncRead for FailingReader {
+        fn poll_read(self: Pin<&mut Self>, _: &mut Context<'_>, _: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Err(io::Error::other("no space left on device")))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_leaves_original_intact() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("manual.json");
+        std::fs::write(&path, "original").unwrap();
+
+        let reader = (&b"partially written replacement"[..]).chain(FailingReader);
+        let error = write_atomic_from(&path, reader, |_| {}).await.unwrap_err();
+        assert_eq!(error.to_string(), "no space left on device");
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+        // The temporary file is cleaned up
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+
+        write_atomic(&path, b"replaced", None).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "replaced");
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_save_through_symlink_keeps_link_and_permissions() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let temp_dir = TempDir::new().unwrap();
+        let shared = temp_dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        let target = shared.join("manual.md");
+        std::fs::write(&target, "original").unwrap();
+        std::fs::set_permissions(&target, Permissions::from_mode(0o640)).unwrap();
+        let link = temp_dir.path().join("manual.md");
+        symlink("shared/manual.md", &link).unwrap();
+
+        write_atomic(&link, b"async save", None).await.unwrap();
+        assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "async save");
+        assert_eq!(std::fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o640);
+
+        write_atomic_blocking(&link, b"blocking save").unwrap();
+        assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "blocking save");
+        assert_eq!(std::fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o640);
+        assert_eq!(std::fs::read_dir(&shared).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::database::member_repository::MemberRepository;
+use crate::{Result, TradocumentError, User, UserRole};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+/// Something a user may try to do to a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    View,
+    Edit,
+    Approve,
+    Merge,
+    Delete,
+    ManageMembers,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::View => "view",
+            Action::Edit => "edit",
+            Action::Approve => "approve",
+            Action::Merge => "merge",
+            Action::Delete => "delete",
+            Action::ManageMembers => "manage members of",
+        }
+    }
+}
+
+/// What an action is done to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resource {
+    Document(Uuid),
+    Review(Uuid),
+    Chapter(Uuid),
+    Project(Uuid),
+    Branch(String),
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Resource::Document(id) => write!(f, "document {id}"),
+            Resource::Review(id) => write!(f, "review {id}"),
+            Resource::Chapter(id) => write!(f, "chapter {id}"),
+            Resource::Project(id) => write!(f, "project {id}"),
+            Resource::Branch(name) => write!(f, "branch {name}"),
+        }
+    }
+}
+
+/// Actions each role is allowed, as set in the permission configuration.
+/// A role that isn't listed is allowed nothing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionConfig {
+    #[serde(flatten)]
+    pub roles: HashMap<UserRole, Vec<Action>>,
+}
+
+impl Default for PermissionConfig {
+    fn default() -> Self {
+        use Action::*;
+        let roles = HashMap::from([
+            (UserRole::Owner, vec![View, Edit, Approve, Merge, Delete, ManageMembers]),
+            (UserRole::Admin, vec![View, Edit, Approve, Merge, Delete, ManageMembers]),
+            (UserRole::Member, vec![View, Edit]),
+            (UserRole::Translator, vec![View, Edit]),
+            (UserRole::Reviewer, vec![View, Approve, Merge]),
+            (UserRole::Viewer, vec![View]),
+        ]);
+        Self { roles }
+    }
+}
+
+impl PermissionConfig {
+    pub fn allows(&self, role: &UserRole, action: Action) -> bool {
+        self.roles.get(role).is_some_and(|actions| actions.contains(&action))
+    }
+}
+
+/// Decides what users may do from their role and a [`PermissionConfig`].
+/// An authorizer for a project goes by the role each user has in that
+/// project rather than their global role.
+#[derive(Debug, Clone, Default)]
+pub struct Authorizer {
+    config: PermissionConfig,
+    /// Role of each member by user id, when deciding for one project
+    project_roles: Option<HashMap<String, UserRole>>,
+}
+
+impl Authorizer {
+    pub fn new(config: PermissionConfig) -> Self {
+        Self { config, project_roles: None }
+    }
+
+    /// Decide by the roles users have in a project; users who aren't
+    /// members may do nothing
+    pub fn with_project_roles(mut self, roles: impl IntoIterator<Item = (String, UserRole)>) -> Self {
+        self.project_roles = Some(roles.into_iter().collect());
+        self
+    }
+
+    /// An authorizer for a project, going by the roles its members have in
+    /// `members`
+    pub async fn for_project(config: PermissionConfig, members: &MemberRepository, project_id: Uuid) -> Result<Self> {
+        let members = members
+            .get_project_members(project_id)
+            .await
+            .map_err(|e| TradocumentError::DatabaseError(e.to_string()))?;
+        Ok(Self::new(config).with_project_roles(members.into_iter().map(|member| (member.user_id, member.role))))
+    }
+
+    pub fn config(&self) -> &PermissionConfig {
+        &self.config
+    }
+
+    /// The role `user` acts with: their role in the project, or their global
+    /// role when not deciding for a project
+    fn role<'a>(&'a self, user: &'a User) -> Option<&'a UserRole> {
+        match &self.project_roles {
+            Some(roles) => roles.get(&user.id),
+            None => Some(&user.role),
+        }
+    }
+
+    /// Whether `user` may do `action` to `resource`. Inactive users may do
+    /// nothing.
+    pub fn can(&self, user: &User, action: Action, _resource: &Resource) -> bool {
+        user.active && self.role(user).is_some_and(|role| self.config.allows(role, action))
+    }
+
+    /// Like [`Authorizer::can`], but an error naming the user, action and
+    /// resource when denied
+    pub fn require(&self, user: &User, action: Action, resource: &Resource) -> Result<()> {
+        if self.can(user, action, resource) {
+            return Ok(());
+        }
+        Err(TradocumentError::AuthenticationError(format!(
+            "{} ({}) may not {} {resource}",
+            user.name,
+            self.role(user).map_or("not a member", UserRole::as_str),
+            action.as_str()
+        )))
+    }
+}
+
+/// Whether `user` may do `action` to `resource` under the default permissions
+pub fn can(user: &User, action: Action, resource: &Resource) -> bool {
+    Authorizer::default().can(user, action, resource)
+}
+
+/// Fail with [`TradocumentError::AuthenticationError`] unless `user` may do
+/// `action` to `resource` under the default permissions
+pub fn require(user: &User, action: Action, resource: &Resource) -> Result<()> {
+    Authorizer::default().require(user, action, resource)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review_system::ReviewSystem;
+    use chrono::Utc;
+
+    fn user(name: &str, role: UserRole) -> User {
+        User {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            email: format!("{}@example.com", name.to_lowercase()),
+            role,
+            created_at: Utc::now(),
+            active: true,
+        }
+    }
+
+    #[test]
+    fn test_configured_roles_decide_approve_and_delete() {
+        let config: PermissionConfig = serde_json::from_str(
+            r#"{"owner": ["view", "delete"], "reviewer": ["view", "approve"], "viewer": ["view"]}"#,
+        )
+        .unwrap();
+        let authz = Authorizer::new(config);
+        let review = Resource::Review(Uuid::new_v4());
+        let chapter = Resource::Chapter(Uuid::new_v4());
+
+        assert!(!authz.can(&user("Vera", UserRole::Viewer), Action::Approve, &review));
+        assert!(authz.can(&user("Rita", UserRole::Reviewer), Action::Approve, &review));
+        assert!(authz.can(&user("Olga", UserRole::Owner), Action::Delete, &chapter));
+        // Only what the configuration lists is allowed
+        assert!(!authz.can(&user("Olga", UserRole::Owner), Action::Approve, &review));
+        assert!(!authz.can(&user("Adam", UserRole::Admin), Action::View, &chapter));
+
+        let mut former = user("Olga", UserRole::Owner);
+        former.active = false;
+        assert!(!authz.can(&former, Action::Delete, &chapter));
+
+        let denied = authz.require(&user("Vera", UserRole::Viewer), Action::Approve, &review).unwrap_err();
+        assert!(matches!(denied, TradocumentError::AuthenticationError(message) if message.starts_with("Vera (viewer) may not approve review")));
+    }
+
+    #[tokio::test]
+    async fn test_review_approval_requires_permission() {
+        let viewer = user("Vera", UserRole::Viewer);
+        let reviewer = user("Rita", UserRole::Reviewer);
+        let author = user("Tom", UserRole::Translator);
+        let document_id = Uuid::new_v4();
+        let mut reviews = ReviewSystem::new();
+        reviews.create_review(document_id, viewer.id.clone(), "Intro", &viewer, None).await.unwrap();
+        reviews.create_review(document_id, reviewer.id.clone(), "Intro", &reviewer, None).await.unwrap();
+
+        let denied = reviews.approve_document(document_id, "Intro", &viewer, &author).await;
+        assert!(matches!(denied, Err(TradocumentError::AuthenticationError(_))));
+        let denied = reviews.reject_document_sync(document_id, "Typos".to_string(), &viewer);
+        assert!(matches!(denied, Err(TradocumentError::AuthenticationError(_))));
+        reviews.approve_document(document_id, "Intro", &reviewer, &author).await.unwrap();
+
+        // A reviewer cannot settle a review assigned to someone else
+        let other_document = Uuid::new_v4();
+        reviews.create_review(other_document, viewer.id.clone(), "Outro", &viewer, None).await.unwrap();
+        let missing = reviews.approve_document_sync(other_document, &reviewer);
+        assert!(matches!(missing, Err(TradocumentError::Review(_))));
+    }
+
+    #[test]
+    fn test_project_roles_replace_global_roles() {
+        let authz = Authorizer::default().with_project_roles([
+            ("vera".to_string(), UserRole::Reviewer),
+            ("olga".to_string(), UserRole::Viewer),
+        ]);
+        let review = Resource::Review(Uuid::new_v4());
+
+        // A global viewer who reviews this project may approve, and the
+        // other way round
+        assert!(authz.can(&user("Vera", UserRole::Viewer), Action::Approve, &review));
+        assert!(!authz.can(&user("Olga", UserRole::Owner), Action::Approve, &review));
+        // Global owners who aren't members may do nothing
+        let denied = authz.require(&user("Adam", UserRole::Owner), Action::View, &review).unwrap_err();
+        assert!(matches!(denied, TradocumentError::AuthenticationError(message) if message.starts_with("Adam (not a member) may not view")));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::models::translation_models::{Chapter, ChapterStatus, ChunkMetadata};
-use crate::{TradocumentError, Result};
+use crate::services::authz::{Action, Authorizer, Resource};
+use crate::{TradocumentError, Result, User};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -10,12 +11,20 @@ use serde::{Deserialize, Serialize};
 /// Service for managing chapters with multi-language content support
 pub struct ChapterService {
     base_path: PathBuf,
+    authorizer: Authorizer,
 }
 
 impl ChapterService {
     /// Create a new chapter service with the specified base path
     pub fn new(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self { base_path, authorizer: Authorizer::default() }
+    }
+
+    /// Decide who may delete chapters with `authorizer` rather than the
+    /// default permissions
+    pub fn with_authorizer(mut self, authorizer: Authorizer) -> Self {
+        self.authorizer = authorizer;
+        self
     }
 
     /// Create a new chapter with multi-language content support
@@ -159,8 +168,9 @@ impl ChapterService {
         Ok(chapters)
     }
 
-    /// Delete a chapter
-    pub async fn delete_chapter(&self, chapter_id: Uuid) -> Result<()> {
+    /// Delete a chapter, if `user` may
+    pub async fn delete_chapter(&self, chapter_id: Uuid, user: &User) -> Result<()> {
+        self.authorizer.require(user, Action::Delete, &Resource::Chapter(chapter_id))?;
         let chapter = self.load_chapter(chapter_id).await?;
         
         // Delete content files
@@ -631,8 +641,19 @@ mod tests {
         // Verify chapter exists
         assert!(service.load_chapter(created_chapter.id).await.is_ok());
 
-        // Delete chapter
-        service.delete_chapter(created_chapter.id).await.unwrap();
+        // Only a user whose role may delete can delete the chapter
+        let mut user = User {
+            id: "olga".to_string(),
+            name: "Olga".to_string(),
+            email: "olga@example.com".to_string(),
+            role: crate::UserRole::Viewer,
+            created_at: Utc::now(),
+            active: true,
+        };
+        assert!(service.delete_chapter(created_chapter.id, &user).await.is_err());
+        assert!(service.load_chapter(created_chapter.id).await.is_ok());
+        user.role = crate::UserRole::Owner;
+        service.delete_chapter(created_chapter.id, &user).await.unwrap();
 
         // Verify chapter no longer exists
         assert!(service.load_chapter(created_chapter.id).await.is_err());
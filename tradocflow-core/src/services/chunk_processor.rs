@@ -1,6 +1,8 @@
 use crate::models::translation_models::{ChunkMetadata, ChunkType, ValidationError};
+use crate::services::dnt::{DNT_CLOSE, DNT_OPEN};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use uuid::Uuid;
 
 /// Configuration for chunk processing strategies
@@ -394,6 +396,7 @@ impl ChunkProcessor {
         let mut sentences = Vec::new();
         let mut current_sentence = String::new();
         let chars: Vec<char> = text.chars().collect();
+        let dnt = Self::dnt_char_spans(&chars);
         let mut i = 0;
 
         while i < chars.len() {
@@ -403,7 +406,7 @@ impl ChunkProcessor {
             // Check for sentence-ending punctuation
             if matches!(ch, '.' | '!' | '?') {
                 // Look ahead to see if this is really a sentence boundary
-                if self.is_sentence_boundary(&chars, i) {
+                if self.is_sentence_boundary(&chars, i, &dnt) {
                     sentences.push(current_sentence.trim().to_string());
                     current_sentence.clear();
                     
@@ -430,11 +433,17 @@ impl ChunkProcessor {
     }
 
     /// Determine if a position is a true sentence boundary
-    fn is_sentence_boundary(&self, chars: &[char], pos: usize) -> bool {
+    fn is_sentence_boundary(&self, chars: &[char], pos: usize, dnt: &[RangeInclusive<usize>]) -> bool {
         if pos >= chars.len() {
             return true;
         }
 
+        // "Do not translate" spans are kept whole
+        let span = dnt.partition_point(|span| *span.end() < pos);
+        if dnt.get(span).is_some_and(|span| span.contains(&pos)) {
+            return false;
+        }
+
         let ch = chars[pos];
         
         // Check for abbreviations (simple heuristic)
@@ -476,6 +485,31 @@ impl ChunkProcessor {
         pos == chars.len() - 1
     }
 
+    /// Positions between the markers of each "do not translate" span in
+    /// `chars`, in order, found in one pass so sentence splitting can look
+    /// them up. An opening marker without a closing one marks nothing.
+    fn dnt_char_spans(chars: &[char]) -> Vec<RangeInclusive<usize>> {
+        let open: Vec<char> = DNT_OPEN.chars().collect();
+        let close: Vec<char> = DNT_CLOSE.chars().collect();
+        let mut spans = Vec::new();
+        let mut start = None;
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i..].starts_with(&open) {
+                start.get_or_insert(i + open.len());
+                i += open.len();
+            } else if chars[i..].starts_with(&close) {
+                if let Some(start) = start.take() {
+                    spans.push(start..=i);
+                }
+                i += close.len();
+            } else {
+                i += 1;
+            }
+        }
+        spans
+    }
+
     /// Check if a short sentence is meaningful (like "Yes." or "No.")
     fn is_meaningful_short_sentence(&self, sentence: &str) -> bool {
         let meaningful_short = ["Yes.", "No.", "OK.", "Hi.", "Bye.", "Thanks.", "Please."];
@@ -486,9 +520,10 @@ impl ChunkProcessor {
     fn detect_sentence_boundaries(&self, text: &str) -> Vec<usize> {
         let mut boundaries = vec![0]; // Start boundary
         let chars: Vec<char> = text.chars().collect();
+        let dnt = Self::dnt_char_spans(&chars);
         
         for (i, &ch) in chars.iter().enumerate() {
-            if matches!(ch, '.' | '!' | '?') && self.is_sentence_boundary(&chars, i) {
+            if matches!(ch, '.' | '!' | '?') && self.is_sentence_boundary(&chars, i, &dnt) {
                 boundaries.push(i + 1);
             }
         }
@@ -0,0 +1,66 @@
+use icu_collator::{Collator, CollatorOptions};
+use icu_locid::Locale;
+
+/// Collator for the language with the BCP 47 tag `lang`, such as `de` or
+/// `sv-SE`. Tags that don't parse, and languages without rules of their own,
+/// use the root collation order.
+pub fn collator(lang: &str) -> Collator {
+    let locale: Locale = lang.replace('_', "-").parse().unwrap_or_else(|e| {
+        log::warn!("Collating \"{lang}\" in root order: {e}");
+        Locale::UND
+    });
+    Collator::try_new(&(&locale).into(), CollatorOptions::new()).expect("compiled collation data covers every locale")
+}
+
+/// Sort `items` by the key of each in the collation order of `lang`, so
+/// accented letters sit next to their base letter and letters a language
+/// treats as its own, like Swedish å, ä and ö, sort where its alphabet puts
+/// them. The sort is stable: items with equal keys keep their order.
+pub fn sort_localized<T>(items: &mut [T], lang: &str, key_fn: impl Fn(&T) -> &str) {
+    let collator = collator(lang);
+    items.sort_by(|a, b| collator.compare(key_fn(a), key_fn(b)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn byte_sorted(words: &[&'static str]) -> Vec<&'static str> {
+        let mut sorted = words.to_vec();
+        sorted.sort();
+        sorted
+    }
+
+    #[test]
+    fn test_accented_latin_ordering() {
+        let words = ["Zucker", "Äpfel", "apfel", "Ofen", "Öl", "Eis", "École"];
+
+        let mut sorted = words.to_vec();
+        sort_localized(&mut sorted, "de", |word| word);
+        assert_eq!(sorted, ["apfel", "Äpfel", "École", "Eis", "Ofen", "Öl", "Zucker"]);
+        // Bytes put capitals first and accented letters after z
+        assert_eq!(byte_sorted(&words), ["Eis", "Ofen", "Zucker", "apfel", "Äpfel", "École", "Öl"]);
+    }
+
+    #[test]
+    fn test_swedish_letters_sort_last() {
+        let words = ["öl", "ägg", "åsna", "zebra", "apa", "ost"];
+
+        let mut swedish = words.to_vec();
+        sort_localized(&mut swedish, "sv", |word| word);
+        assert_eq!(swedish, ["apa", "ost", "zebra", "åsna", "ägg", "öl"]);
+
+        let mut german = words.to_vec();
+        sort_localized(&mut german, "de", |word| word);
+        assert_eq!(german, ["ägg", "apa", "åsna", "öl", "ost", "zebra"]);
+
+        assert_eq!(byte_sorted(&words), ["apa", "ost", "zebra", "ägg", "åsna", "öl"]);
+    }
+
+    #[test]
+    fn test_equal_keys_keep_insertion_order() {
+        let mut items = vec![("Bremse", 1), ("anker", 2), ("Bremse", 3), ("Anker", 4), ("bremse", 5)];
+        sort_localized(&mut items, "not a tag!", |item| item.0);
+        assert_eq!(items, [("anker", 2), ("Anker", 4), ("bremse", 5), ("Bremse", 1), ("Bremse", 3)]);
+    }
+}
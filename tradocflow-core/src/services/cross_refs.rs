@@ -0,0 +1,234 @@
+use crate::{Document, Manual, ManualSection};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// A `[[section:<uuid>]]` reference in a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrossRef {
+    /// The id as written; it may not be a valid UUID
+    pub reference: String,
+    /// Byte offsets of the whole reference
+    pub start: usize,
+    pub end: usize,
+    /// 1-based line number
+    pub line: usize,
+}
+
+impl CrossRef {
+    pub fn section_id(&self) -> Option<Uuid> {
+        Uuid::parse_str(&self.reference).ok()
+    }
+}
+
+/// A reference to a section the manual no longer has
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DanglingCrossRef {
+    /// Section whose document contains the reference, when known
+    pub source_section: Option<Uuid>,
+    pub reference: String,
+    pub line: usize,
+}
+
+/// Text with its cross-references replaced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrossRefResolution {
+    pub text: String,
+    /// References left in place because their section doesn't exist
+    pub dangling: Vec<DanglingCrossRef>,
+}
+
+fn cross_ref_regex() -> &'static Regex {
+    static CROSS_REF: OnceLock<Regex> = OnceLock::new();
+    CROSS_REF.get_or_init(|| Regex::new(r"\[\[section:\s*([^\]\s]+)\s*\]\]").expect("valid cross-reference regex"))
+}
+
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count() + 1
+}
+
+/// The cross-references of a document, in order
+pub fn extract_cross_refs(text: &str) -> Vec<CrossRef> {
+    cross_ref_regex()
+        .captures_iter(text)
+        .map(|captures| {
+            let whole = captures.get(0).expect("whole match");
+            CrossRef {
+                reference: captures[1].to_string(),
+                start: whole.start(),
+                end: whole.end(),
+                line: line_of(text, whole.start()),
+            }
+        })
+        .collect()
+}
+
+/// Resolves `[[section:<uuid>]]` references against the sections of a
+/// manual, so references keep pointing at a section after it is renamed or
+/// moved.
+pub struct CrossRefResolver<'a> {
+    sections: HashMap<Uuid, &'a ManualSection>,
+}
+
+impl<'a> CrossRefResolver<'a> {
+    pub fn new(manual: &'a Manual) -> Self {
+        fn collect<'a>(sections: &'a [ManualSection], out: &mut HashMap<Uuid, &'a ManualSection>) {
+            for section in sections {
+                out.insert(section.id, section);
+                collect(&section.subsections, out);
+            }
+        }
+
+        let mut sections = HashMap::new();
+        collect(&manual.sections, &mut sections);
+        Self { sections }
+    }
+
+    pub fn section(&self, id: Uuid) -> Option<&'a ManualSection> {
+        self.sections.get(&id).copied()
+    }
+
+    /// Replace each reference with a markdown link showing the section's
+    /// title in `language`. `href` gives the link target for a section id,
+    /// since each export format anchors sections differently. Dangling
+    /// references stay as written and are reported.
+    pub fn resolve(&self, text: &str, language: &str, href: impl Fn(Uuid) -> String) -> CrossRefResolution {
+        let mut dangling = Vec::new();
+        let resolved = cross_ref_regex().replace_all(text, |captures: &Captures| {
+            let reference = &captures[1];
+            match Uuid::parse_str(reference).ok().and_then(|id| self.section(id)) {
+                Some(section) => {
+                    let title = section.localized_title(language).replace('[', "\\[").replace(']', "\\]");
+                    format!("[{title}]({})", href(section.id))
+                }
+                None => {
+                    let start = captures.get(0).expect("whole match").start();
+                    dangling.push(DanglingCrossRef {
+                        source_section: None,
+                        reference: reference.to_string(),
+                        line: line_of(text, start),
+                    });
+                    captures[0].to_string()
+                }
+            }
+        });
+
+        CrossRefResolution { text: resolved.into_owned(), dangling }
+    }
+
+    /// Dangling references in every section document of the manual, in
+    /// `language`
+    pub fn dangling_references(&self, documents: &HashMap<Uuid, Document>, language: &str) -> Vec<DanglingCrossRef> {
+        let mut sections: Vec<&ManualSection> = self.sections.values().copied().collect();
+        sections.sort_by_key(|section| section.id);
+
+        let mut dangling = Vec::new();
+        for section in sections {
+            let content = section
+                .document_id
+                .and_then(|id| documents.get(&id))
+                .and_then(|document| document.content.get(language));
+            let Some(content) = content else {
+                continue;
+            };
+
+            for reference in extract_cross_refs(content) {
+                if reference.section_id().and_then(|id| self.section(id)).is_none() {
+                    dangling.push(DanglingCrossRef {
+                        source_section: Some(section.id),
+                        reference: reference.reference,
+                        line: reference.line,
+                    });
+                }
+            }
+        }
+        dangling
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DocumentMetadata, ManualTemplate, SectionType};
+    use chrono::Utc;
+
+    fn section(title: &str, order: u32) -> ManualSection {
+        ManualSection {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            order,
+            document_id: None,
+            subsections: Vec::new(),
+            section_type: SectionType::Custom(title.to_string()),
+            required: false,
+            localized_titles: HashMap::new(),
+        }
+    }
+
+    fn manual(sections: Vec<ManualSection>) -> Manual {
+        Manual {
+            id: Uuid::new_v4(),
+            title: "Manual".to_string(),
+            description: String::new(),
+            sections,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "1.0".to_string(),
+            languages: vec!["en".to_string(), "de".to_string()],
+            template_type: ManualTemplate::TechnicalManual,
+        }
+    }
+
+    #[test]
+    fn test_resolves_to_current_localized_title() {
+        let mut chapter = section("Setup", 1);
+        let mut wiring = section("Installing the controller", 1);
+        wiring.localized_titles.insert("de".to_string(), "Steuerung einbauen".to_string());
+        let wiring_id = wiring.id;
+        chapter.subsections.push(wiring);
+        let manual = manual(vec![chapter]);
+
+        let text = format!("Before wiring, see [[section:{wiring_id}]].");
+        let resolver = CrossRefResolver::new(&manual);
+        let href = |id: Uuid| format!("#section-{id}");
+
+        let english = resolver.resolve(&text, "en", href);
+        assert_eq!(english.text, format!("Before wiring, see [Installing the controller](#section-{wiring_id})."));
+        assert!(english.dangling.is_empty());
+
+        let german = resolver.resolve(&text, "de", href);
+        assert_eq!(german.text, format!("Before wiring, see [Steuerung einbauen](#section-{wiring_id})."));
+    }
+
+    #[test]
+    fn test_reports_dangling_reference() {
+        let removed = Uuid::new_v4();
+        let document_id = Uuid::new_v4();
+        let mut intro = section("Introduction", 1);
+        intro.document_id = Some(document_id);
+        let intro_id = intro.id;
+        let manual = manual(vec![intro]);
+
+        let text = format!("Intro.\nSee [[section:{removed}]] and [[section:{intro_id}]].");
+        let resolver = CrossRefResolver::new(&manual);
+        let resolution = resolver.resolve(&text, "en", |id| format!("#section-{id}"));
+        assert!(resolution.text.contains(&format!("[[section:{removed}]]")));
+        assert!(resolution.text.contains(&format!("[Introduction](#section-{intro_id})")));
+        assert_eq!(resolution.dangling.len(), 1);
+        assert_eq!((resolution.dangling[0].reference.as_str(), resolution.dangling[0].line), (removed.to_string().as_str(), 2));
+
+        let documents = HashMap::from([(
+            document_id,
+            Document {
+                title: "Intro".to_string(),
+                content: HashMap::from([("en".to_string(), text.clone())]),
+                metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+            },
+        )]);
+        let dangling = resolver.dangling_references(&documents, "en");
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].source_section, Some(intro_id));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{Result, TradocumentError};
+use async_trait::async_trait;
+use regex::Regex;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Opens a span of text that must never be translated, such as a code
+/// identifier or brand name
+pub const DNT_OPEN: &str = "{{dnt}}";
+/// Closes a span opened by [`DNT_OPEN`]
+pub const DNT_CLOSE: &str = "{{/dnt}}";
+
+fn dnt_regex() -> &'static Regex {
+    static DNT: OnceLock<Regex> = OnceLock::new();
+    DNT.get_or_init(|| Regex::new(r"\{\{dnt\}\}(.*?)\{\{/dnt\}\}").expect("valid dnt regex"))
+}
+
+/// Byte ranges of the "do not translate" spans of `text`, markers
+/// included. An opening marker without a closing one marks nothing.
+pub fn dnt_spans(text: &str) -> Vec<Range<usize>> {
+    dnt_regex().find_iter(text).map(|m| m.range()).collect()
+}
+
+/// Contents of the spans of `text`, without their markers
+fn span_contents(text: &str) -> Vec<&str> {
+    dnt_regex().captures_iter(text).map(|c| c.get(1).map_or("", |m| m.as_str())).collect()
+}
+
+/// Whether `target` holds the spans of `source` unchanged, in any order
+pub fn spans_preserved(source: &str, target: &str) -> bool {
+    let mut expected = span_contents(source);
+    let mut found = span_contents(target);
+    expected.sort_unstable();
+    found.sort_unstable();
+    expected == found
+}
+
+/// `text` with its spans reduced to their content, for output that must
+/// not show the markers
+pub fn strip_dnt_markers(text: &str) -> String {
+    dnt_regex().replace_all(text, "$1").replace(DNT_OPEN, "").replace(DNT_CLOSE, "")
+}
+
+/// Rendered HTML with each span as a `translate="no"` element, which
+/// browser and other page translators leave alone. Markers that don't form
+/// a span are dropped.
+pub fn dnt_spans_to_html(html: &str) -> String {
+    dnt_regex()
+        .replace_all(html, r#"<span class="dnt" translate="no">$1</span>"#)
+        .replace(DNT_OPEN, "")
+        .replace(DNT_CLOSE, "")
+}
+
+/// Text for a machine translator, with each span swapped for a token that
+/// translation engines pass through like an inline tag
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtectedText {
+    pub text: String,
+    spans: Vec<String>,
+}
+
+impl ProtectedText {
+    pub fn new(text: &str) -> Self {
+        let mut spans = Vec::new();
+        let text = dnt_regex()
+            .replace_all(text, |captures: &regex::Captures| {
+                spans.push(captures[0].to_string());
+                Self::token(spans.len() - 1)
+            })
+            .into_owned();
+        Self { text, spans }
+    }
+
+    fn token(index: usize) -> String {
+        format!(r#"<dnt id="{index}"/>"#)
+    }
+
+    /// Put the spans back into the translation of [`Self::text`], markers
+    /// included so later steps still see them. Fails if the translator
+    /// dropped or repeated a token.
+    pub fn restore(&self, translated: &str) -> Result<String> {
+        let mut restored = translated.to_string();
+        for (index, span) in self.spans.iter().enumerate() {
+            let token = Self::token(index);
+            if restored.matches(&token).count() != 1 {
+                return Err(TradocumentError::Validation(format!(
+                    "Machine translation did not keep protected text {span} exactly once"
+                )));
+            }
+            restored = restored.replacen(&token, span, 1);
+        }
+        Ok(restored)
+    }
+}
+
+/// A machine translation engine
+#[async_trait]
+pub trait MachineTranslator: Send + Sync {
+    async fn translate(&self, text: &str, source_language: &str, target_language: &str) -> Result<String>;
+}
+
+/// Translate `text` with `translator`, keeping its "do not translate"
+/// spans out of the engine's reach
+pub async fn translate_protected(
+    translator: &dyn MachineTranslator,
+    text: &str,
+    source_language: &str,
+    target_language: &str,
+) -> Result<String> {
+    let protected = ProtectedText::new(text);
+    let translated = translator.translate(&protected.text, source_language, target_language).await?;
+    protected.restore(&translated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::chunk_processor::ChunkProcessor;
+
+    /// Engine that "translates" by upper-casing everything outside tags
+    struct ShoutingTranslator;
+
+    #[async_trait]
+    impl MachineTranslator for ShoutingTranslator {
+        async fn translate(&self, text: &str, _source_language: &str, _target_language: &str) -> Result<String> {
+            let mut translated = String::new();
+            let mut in_tag = false;
+            for c in text.chars() {
+                in_tag = (in_tag || c == '<') && c != '>';
+                translated.push(if in_tag { c } else { c.to_ascii_uppercase() });
+            }
+            Ok(translated)
+        }
+    }
+
+    const SOURCE: &str = "Call {{dnt}}init_v2. Then reset(){{/dnt}} before use. Restart the {{dnt}}TradocFlow{{/dnt}} app.";
+
+    #[test]
+    fn test_dnt_span_survives_segmentation() {
+        let chunks = ChunkProcessor::new().process_content(SOURCE).unwrap();
+        let sentences: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(sentences, [
+            "Call {{dnt}}init_v2. Then reset(){{/dnt}} before use.",
+            "Restart the {{dnt}}TradocFlow{{/dnt}} app.",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_dnt_span_survives_machine_translation() {
+        let translated = translate_protected(&ShoutingTranslator, SOURCE, "en", "de").await.unwrap();
+        assert_eq!(
+            translated,
+            "CALL {{dnt}}init_v2. Then reset(){{/dnt}} BEFORE USE. RESTART THE {{dnt}}TradocFlow{{/dnt}} APP."
+        );
+        assert!(spans_preserved(SOURCE, &translated));
+
+        let protected = ProtectedText::new(SOURCE);
+        assert!(protected.restore("Call <dnt id=\"0\"/> before use.").is_err());
+    }
+}
@@ -304,9 +304,10 @@ impl DocumentImportService {
             "doc" => self.convert_doc_to_markdown(file_path, config).await,
             "txt" => self.convert_txt_to_markdown(file_path).await,
             "md" => {
-                // Already markdown, just read it
-                fs::read_to_string(file_path)
-                    .map_err(|e| TradocumentError::FileError(format!("Failed to read markdown file: {e}")))
+                // Already markdown; drop any front matter left by an export
+                let markdown = fs::read_to_string(file_path)
+                    .map_err(|e| TradocumentError::FileError(format!("Failed to read markdown file: {e}")))?;
+                Ok(crate::export_engine::split_front_matter(&markdown).1.to_string())
             }
             _ => Err(TradocumentError::Validation(
                 format!("Unsupported file format: {file_extension}")
@@ -2,7 +2,7 @@ use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
-use tokio::sync::{mpsc, RwLock as TokioRwLock, Mutex as TokioMutex};
+use tokio::sync::{mpsc, watch, RwLock as TokioRwLock, Mutex as TokioMutex};
 use tokio::time::sleep;
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,10 @@ use sha2::{Sha256, Digest};
 
 use super::markdown_text_processor::{MarkdownTextProcessor, TextProcessorError};
 use super::markdown_processor::{MarkdownProcessor, TextRange, ValidationError, ProcessingStatistics};
+use super::events::{AppEvent, EventBus};
+use super::atomic_file::{
+    content_revision, disk_revision, write_atomic, write_atomic_if_unchanged, ConditionalWrite, SaveProgress,
+};
 
 /// Document modification event
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,8 +55,10 @@ pub struct DocumentVersion {
     pub validation_errors: Vec<ValidationError>,
 }
 
-/// Auto-save configuration
-#[derive(Debug, Clone)]
+/// Auto-save configuration. Settings left out of `autosave.toml` keep
+/// their defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct AutoSaveConfig {
     pub enabled: bool,
     pub interval_seconds: u64,
@@ -61,6 +67,8 @@ pub struct AutoSaveConfig {
     pub backup_directory: Option<PathBuf>,
     pub max_backup_files: usize,
     pub compress_backups: bool,
+    /// Also auto-save when the editor loses focus
+    pub save_on_focus_loss: bool,
 }
 
 impl Default for AutoSaveConfig {
@@ -73,10 +81,36 @@ impl Default for AutoSaveConfig {
             backup_directory: None,
             max_backup_files: 10,
             compress_backups: true,
+            save_on_focus_loss: true,
         }
     }
 }
 
+impl AutoSaveConfig {
+    /// Load the user's auto-save settings from [`AutoSaveConfig::config_path`],
+    /// or the defaults if the file doesn't exist
+    pub fn load() -> Result<Self, DocumentStateError> {
+        match Self::config_path() {
+            Some(path) if path.exists() => Self::load_from_path(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Location of the auto-save settings file
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tradocflow").join("autosave.toml"))
+    }
+
+    pub fn load_from_path(path: &Path) -> Result<Self, DocumentStateError> {
+        let content = std::fs::read_to_string(path).map_err(|e| DocumentStateError::IoError(e.to_string()))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, DocumentStateError> {
+        toml::from_str(content).map_err(|e| DocumentStateError::ConfigurationError(format!("Invalid auto-save settings: {e}")))
+    }
+}
+
 /// Document state information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentState {
@@ -94,6 +128,32 @@ pub struct DocumentState {
     pub encoding: String,
     pub line_endings: LineEnding,
     pub language: Option<String>,
+    /// Revision of the file as last loaded or saved, so saves can tell when
+    /// someone else changed it since
+    #[serde(default)]
+    pub disk_revision: Option<String>,
+}
+
+/// What an auto-save did
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveOutcome {
+    Saved,
+    /// Nothing to save, or auto-save is off
+    Skipped,
+    /// The file was changed by someone else, so it was left alone
+    Conflict(ExternalChange),
+}
+
+/// A change made to the document's file by someone else since it was
+/// loaded or last saved
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalChange {
+    pub path: PathBuf,
+    pub expected_revision: Option<String>,
+    /// Revision on disk now, `None` if the file was deleted
+    pub found_revision: Option<String>,
+    /// What is on disk now, `None` if the file was deleted
+    pub disk_content: Option<String>,
 }
 
 /// Line ending types
@@ -187,6 +247,10 @@ pub struct DocumentStateManager {
     auto_save_config: Arc<TokioRwLock<AutoSaveConfig>>,
     auto_save_handle: Arc<TokioMutex<Option<tokio::task::JoinHandle<()>>>>,
     last_activity: Arc<TokioRwLock<Instant>>,
+    /// Content as last loaded or saved, the base for merging external changes
+    saved_content: Arc<TokioRwLock<Option<String>>>,
+    /// External change found by auto-save and not yet resolved
+    external_change: Arc<TokioRwLock<Option<ExternalChange>>>,
     
     // Large document handling
     chunks: Arc<TokioRwLock<HashMap<Uuid, DocumentChunk>>>,
@@ -199,6 +263,7 @@ pub struct DocumentStateManager {
     
     // Event system
     change_sender: Arc<TokioMutex<Option<mpsc::UnboundedSender<DocumentChange>>>>,
+    event_bus: Option<EventBus>,
     
     // Configuration
     max_history_size: usize,
@@ -227,6 +292,7 @@ impl DocumentStateManager {
             encoding: "UTF-8".to_string(),
             line_endings: LineEnding::Unix,
             language: None,
+            disk_revision: None,
         };
 
         let conflict_detection = ConflictDetection {
@@ -248,18 +314,34 @@ impl DocumentStateManager {
             auto_save_config: Arc::new(TokioRwLock::new(AutoSaveConfig::default())),
             auto_save_handle: Arc::new(TokioMutex::new(None)),
             last_activity: Arc::new(TokioRwLock::new(Instant::now())),
+            saved_content: Arc::new(TokioRwLock::new(None)),
+            external_change: Arc::new(TokioRwLock::new(None)),
             chunks: Arc::new(TokioRwLock::new(HashMap::new())),
             chunk_size: 1000, // lines per chunk
             max_memory_usage: 100 * 1024 * 1024, // 100MB
             conflict_detector: Arc::new(TokioRwLock::new(conflict_detection)),
             remote_change_buffer: Arc::new(TokioRwLock::new(Vec::new())),
             change_sender: Arc::new(TokioMutex::new(None)),
+            event_bus: None,
             max_history_size: 1000,
             max_version_count: 50,
             enable_compression: true,
         })
     }
 
+    /// Publish [`AppEvent::DocumentSaved`] to `event_bus` on every save
+    pub fn with_event_bus(mut self, event_bus: EventBus) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Use `config` for [`Self::focus_lost`] and backups without starting
+    /// the background auto-save, for callers that time auto-saves themselves
+    pub fn with_auto_save_config(mut self, config: AutoSaveConfig) -> Self {
+        self.auto_save_config = Arc::new(TokioRwLock::new(config));
+        self
+    }
+
     /// Load document from file
     pub async fn load_from_file(&self, file_path: &Path) -> Result<(), DocumentStateError> {
         let content = tokio::fs::read_to_string(file_path).await
@@ -286,7 +368,10 @@ impl DocumentStateManager {
             state.is_modified = false;
             state.last_saved = Some(Self::current_timestamp());
             state.last_modified = Self::current_timestamp();
+            state.disk_revision = Some(content_revision(content.as_bytes()));
         }
+        *self.saved_content.write().await = Some(content.clone());
+        *self.external_change.write().await = None;
         
         // Create initial version
         self.create_version_snapshot("Initial load".to_string(), None).await?;
@@ -301,6 +386,19 @@ impl DocumentStateManager {
 
     /// Save document to file
     pub async fn save_to_file(&self, file_path: Option<&Path>) -> Result<(), DocumentStateError> {
+        self.save_to_file_with_progress(file_path, None).await
+    }
+
+    /// Save document to file, reporting the bytes written to `progress` so
+    /// big saves can show it. The file is replaced atomically, and no lock
+    /// is held while it is written. An explicit save overwrites changes made
+    /// by someone else, resolving any [`ExternalChange`] in favour of the
+    /// buffer.
+    pub async fn save_to_file_with_progress(
+        &self,
+        file_path: Option<&Path>,
+        progress: Option<&watch::Sender<SaveProgress>>,
+    ) -> Result<(), DocumentStateError> {
         let content = {
             let processor = self.text_processor.read().await;
             processor.get_content().to_string()
@@ -324,21 +422,132 @@ impl DocumentStateManager {
         let content_with_endings = self.apply_line_endings(&content).await;
         
         // Create backup if configured
-        if let Some(backup_dir) = &self.auto_save_config.read().await.backup_directory {
+        let backup_directory = self.auto_save_config.read().await.backup_directory.clone();
+        if let Some(backup_dir) = &backup_directory {
             self.create_backup(target_path, backup_dir).await?;
         }
         
         // Write file
-        tokio::fs::write(target_path, content_with_endings).await
+        write_atomic(target_path, content_with_endings.as_bytes(), progress).await
             .map_err(|e| DocumentStateError::IoError(e.to_string()))?;
         
-        // Update state
+        self.record_save(target_path, content, content_revision(content_with_endings.as_bytes())).await;
+        Ok(())
+    }
+
+    /// Save to the document's file if it has unsaved changes, unless someone
+    /// else changed the file since it was loaded or last saved. Then nothing
+    /// is written and the change is returned as a conflict, and kept for
+    /// [`Self::external_change`] until the buffer is reloaded, merged or
+    /// saved explicitly. The buffer itself is never touched.
+    pub async fn auto_save(&self) -> Result<SaveOutcome, DocumentStateError> {
+        if let Some(change) = self.external_change.read().await.clone() {
+            return Ok(SaveOutcome::Conflict(change));
+        }
+        let (target_path, expected) = {
+            let state = self.state.read().await;
+            match (&state.file_path, state.is_modified) {
+                (Some(path), true) => (path.clone(), state.disk_revision.clone()),
+                _ => return Ok(SaveOutcome::Skipped),
+            }
+        };
+        let io_error = |e: std::io::Error| DocumentStateError::IoError(e.to_string());
+
+        let content = self.get_content().await;
+        let content_with_endings = self.apply_line_endings(&content).await;
+
+        // Back up the file only while it is still the one the buffer came from
+        let backup_directory = self.auto_save_config.read().await.backup_directory.clone();
+        if let Some(backup_dir) = &backup_directory {
+            if expected.is_some() && disk_revision(&target_path).await.map_err(io_error)? == expected {
+                self.create_backup(&target_path, backup_dir).await?;
+            }
+        }
+
+        let write = write_atomic_if_unchanged(&target_path, expected.as_deref(), content_with_endings.as_bytes(), None)
+            .await
+            .map_err(io_error)?;
+        match write {
+            ConditionalWrite::Written { revision } => {
+                self.record_save(&target_path, content, revision).await;
+                Ok(SaveOutcome::Saved)
+            },
+            ConditionalWrite::Changed { .. } => {
+                let disk_content = match tokio::fs::read_to_string(&target_path).await {
+                    Ok(disk_content) => Some(disk_content),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                    Err(e) => return Err(io_error(e)),
+                };
+                let change = ExternalChange {
+                    path: target_path,
+                    expected_revision: expected,
+                    found_revision: disk_content.as_ref().map(|c| content_revision(c.as_bytes())),
+                    disk_content,
+                };
+                *self.external_change.write().await = Some(change.clone());
+                Ok(SaveOutcome::Conflict(change))
+            },
+        }
+    }
+
+    /// Auto-save because the editor lost focus, if configured to
+    pub async fn focus_lost(&self) -> Result<SaveOutcome, DocumentStateError> {
+        let save = {
+            let config = self.auto_save_config.read().await;
+            config.enabled && config.save_on_focus_loss
+        };
+        if !save {
+            return Ok(SaveOutcome::Skipped);
+        }
+        self.auto_save().await
+    }
+
+    /// The external change auto-save found, until it is resolved
+    pub async fn external_change(&self) -> Option<ExternalChange> {
+        self.external_change.read().await.clone()
+    }
+
+    /// Replace the buffer with what is on disk now, dropping unsaved edits
+    pub async fn reload_from_disk(&self) -> Result<(), DocumentStateError> {
+        let file_path = self.state.read().await.file_path.clone().ok_or(DocumentStateError::NoFilePath)?;
+        self.load_from_file(&file_path).await
+    }
+
+    /// Merge the external change into the buffer. Edits made on only one
+    /// side are kept; where both sides changed, the buffer holds both
+    /// between conflict markers. The result is left unsaved, for the next
+    /// save to write over the version it was merged with.
+    pub async fn merge_external_change(&self) -> Result<(), DocumentStateError> {
+        let Some(change) = self.external_change.read().await.clone() else {
+            return Ok(());
+        };
+        let local = self.get_content().await;
+        let merged = match &change.disk_content {
+            Some(remote) => {
+                let base = self.saved_content.read().await.clone();
+                self.simple_merge(&local, remote, base.as_deref())
+            },
+            // The file was deleted, so saving the buffer brings it back
+            None => local,
+        };
+        self.set_content(merged).await?;
+
+        self.state.write().await.disk_revision = change.found_revision;
+        *self.saved_content.write().await = change.disk_content;
+        *self.external_change.write().await = None;
+        Ok(())
+    }
+
+    /// Bring the state up to date after `content` was written to `target_path`
+    async fn record_save(&self, target_path: &Path, content: String, revision: String) {
         {
             let mut state = self.state.write().await;
             state.file_path = Some(target_path.to_path_buf());
             state.is_modified = false;
             state.last_saved = Some(Self::current_timestamp());
+            state.disk_revision = Some(revision);
         }
+        *self.external_change.write().await = None;
         
         self.emit_change_event(DocumentChange {
             id: Uuid::new_v4(),
@@ -351,8 +560,14 @@ impl DocumentStateManager {
             checksum: self.calculate_checksum(&content),
             metadata: [("action".to_string(), "save".to_string())].into_iter().collect(),
         }).await;
+        *self.saved_content.write().await = Some(content);
 
-        Ok(())
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(AppEvent::DocumentSaved {
+                document_id: self.document_id,
+                path: target_path.to_path_buf(),
+            });
+        }
     }
 
     /// Get current document content
@@ -481,7 +696,7 @@ impl DocumentStateManager {
     /// Create a version snapshot
     pub async fn create_version_snapshot(&self, description: String, author: Option<String>) -> Result<DocumentVersion, DocumentStateError> {
         let content = self.get_content().await;
-        let current_state = self.state.read().await;
+        let version_number = self.state.read().await.current_version;
         let changes = self.get_change_history(None).await;
         
         // Generate statistics and validation
@@ -493,7 +708,7 @@ impl DocumentStateManager {
         
         let version = DocumentVersion {
             id: Uuid::new_v4(),
-            version_number: current_state.current_version,
+            version_number,
             timestamp: Self::current_timestamp(),
             content: content.clone(),
             changes,
@@ -545,15 +760,14 @@ impl DocumentStateManager {
         Ok(())
     }
 
-    /// Configure auto-save
+    /// Configure auto-save, restarting it so a new interval counts from now
     pub async fn configure_auto_save(&self, config: AutoSaveConfig) -> Result<(), DocumentStateError> {
-        let was_enabled = self.auto_save_config.read().await.enabled;
-        *self.auto_save_config.write().await = config.clone();
+        let enabled = config.enabled;
+        *self.auto_save_config.write().await = config;
         
-        if config.enabled && !was_enabled {
+        self.stop_auto_save().await;
+        if enabled {
             self.start_auto_save().await?;
-        } else if !config.enabled && was_enabled {
-            self.stop_auto_save().await;
         }
         
         Ok(())
@@ -570,9 +784,25 @@ impl DocumentStateManager {
         let state_manager = DocumentStateManagerWeak {
             document_id: self.document_id,
             text_processor: Arc::downgrade(&self.text_processor),
+            markdown_processor: Arc::downgrade(&self.markdown_processor),
             state: Arc::downgrade(&self.state),
+            change_history: Arc::downgrade(&self.change_history),
+            version_history: Arc::downgrade(&self.version_history),
             auto_save_config: Arc::downgrade(&self.auto_save_config),
+            auto_save_handle: Arc::downgrade(&self.auto_save_handle),
             last_activity: Arc::downgrade(&self.last_activity),
+            saved_content: Arc::downgrade(&self.saved_content),
+            external_change: Arc::downgrade(&self.external_change),
+            chunks: Arc::downgrade(&self.chunks),
+            chunk_size: self.chunk_size,
+            max_memory_usage: self.max_memory_usage,
+            conflict_detector: Arc::downgrade(&self.conflict_detector),
+            remote_change_buffer: Arc::downgrade(&self.remote_change_buffer),
+            change_sender: Arc::downgrade(&self.change_sender),
+            event_bus: self.event_bus.clone(),
+            max_history_size: self.max_history_size,
+            max_version_count: self.max_version_count,
+            enable_compression: self.enable_compression,
         };
         
         let handle = tokio::spawn(async move {
@@ -1015,53 +1245,93 @@ impl DocumentStateManager {
     }
 }
 
-/// Weak references for auto-save background task
+/// Weak references for auto-save background task, so it stops once the
+/// manager is dropped
 struct DocumentStateManagerWeak {
     document_id: Uuid,
     text_processor: Weak<TokioRwLock<MarkdownTextProcessor>>,
+    markdown_processor: Weak<MarkdownProcessor>,
     state: Weak<TokioRwLock<DocumentState>>,
+    change_history: Weak<TokioRwLock<VecDeque<DocumentChange>>>,
+    version_history: Weak<TokioRwLock<VecDeque<DocumentVersion>>>,
     auto_save_config: Weak<TokioRwLock<AutoSaveConfig>>,
+    auto_save_handle: Weak<TokioMutex<Option<tokio::task::JoinHandle<()>>>>,
     last_activity: Weak<TokioRwLock<Instant>>,
+    saved_content: Weak<TokioRwLock<Option<String>>>,
+    external_change: Weak<TokioRwLock<Option<ExternalChange>>>,
+    chunks: Weak<TokioRwLock<HashMap<Uuid, DocumentChunk>>>,
+    chunk_size: usize,
+    max_memory_usage: usize,
+    conflict_detector: Weak<TokioRwLock<ConflictDetection>>,
+    remote_change_buffer: Weak<TokioRwLock<Vec<DocumentChange>>>,
+    change_sender: Weak<TokioMutex<Option<mpsc::UnboundedSender<DocumentChange>>>>,
+    event_bus: Option<EventBus>,
+    max_history_size: usize,
+    max_version_count: usize,
+    enable_compression: bool,
 }
 
 impl DocumentStateManagerWeak {
+    /// The manager, if it hasn't been dropped
+    fn upgrade(&self) -> Option<DocumentStateManager> {
+        Some(DocumentStateManager {
+            document_id: self.document_id,
+            text_processor: self.text_processor.upgrade()?,
+            markdown_processor: self.markdown_processor.upgrade()?,
+            state: self.state.upgrade()?,
+            change_history: self.change_history.upgrade()?,
+            version_history: self.version_history.upgrade()?,
+            auto_save_config: self.auto_save_config.upgrade()?,
+            auto_save_handle: self.auto_save_handle.upgrade()?,
+            last_activity: self.last_activity.upgrade()?,
+            saved_content: self.saved_content.upgrade()?,
+            external_change: self.external_change.upgrade()?,
+            chunks: self.chunks.upgrade()?,
+            chunk_size: self.chunk_size,
+            max_memory_usage: self.max_memory_usage,
+            conflict_detector: self.conflict_detector.upgrade()?,
+            remote_change_buffer: self.remote_change_buffer.upgrade()?,
+            change_sender: self.change_sender.upgrade()?,
+            event_bus: self.event_bus.clone(),
+            max_history_size: self.max_history_size,
+            max_version_count: self.max_version_count,
+            enable_compression: self.enable_compression,
+        })
+    }
+
     async fn auto_save_loop(&self) {
         loop {
-            // Check if references are still valid
-            let (config, state, last_activity) = match (
-                self.auto_save_config.upgrade(),
-                self.state.upgrade(),
-                self.last_activity.upgrade(),
-            ) {
-                (Some(config), Some(state), Some(activity)) => (config, state, activity),
-                _ => break, // Manager was dropped
+            let (interval_duration, max_idle) = {
+                let Some(config) = self.auto_save_config.upgrade() else {
+                    break; // Manager was dropped
+                };
+                let config = config.read().await;
+                if !config.enabled {
+                    break;
+                }
+                (Duration::from_secs(config.interval_seconds), Duration::from_secs(config.max_idle_time_seconds))
             };
             
-            let config_guard = config.read().await;
-            if !config_guard.enabled {
-                break;
-            }
-            
-            let interval_duration = Duration::from_secs(config_guard.interval_seconds);
-            let max_idle = Duration::from_secs(config_guard.max_idle_time_seconds);
-            drop(config_guard);
-            
-            // Wait for the interval
+            // Wait for the interval, without keeping the manager alive
             sleep(interval_duration).await;
-            
-            // Check if we should save
-            let should_save = {
-                let state_guard = state.read().await;
-                let activity_guard = last_activity.read().await;
-                
-                state_guard.is_modified && 
-                activity_guard.elapsed() < max_idle
+            let Some(manager) = self.upgrade() else {
+                break;
             };
             
-            if should_save {
-                // In a real implementation, you'd trigger a save here
-                // For now, we just update the activity timestamp
-                println!("Auto-save triggered for document {}", self.document_id);
+            // A conflict stays until the user resolves it, so don't report it again
+            let should_save = manager.last_activity.read().await.elapsed() < max_idle
+                && manager.external_change.read().await.is_none();
+            if !should_save {
+                continue;
+            }
+            match manager.auto_save().await {
+                Ok(SaveOutcome::Conflict(change)) => log::warn!(
+                    "Auto-save of document {} skipped: {} was changed on disk",
+                    self.document_id,
+                    change.path.display()
+                ),
+                Ok(SaveOutcome::Saved | SaveOutcome::Skipped) => {},
+                Err(e) => log::warn!("Auto-save of document {} failed: {e}", self.document_id),
             }
         }
     }
@@ -1196,6 +1466,92 @@ mod tests {
         assert!(saved_content.contains("# Modified Document"));
     }
 
+    #[tokio::test]
+    async fn test_save_publishes_document_saved() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("saved.md");
+        let event_bus = EventBus::default();
+        let mut events = event_bus.subscribe();
+
+        let document_id = Uuid::new_v4();
+        let manager = DocumentStateManager::new(Some(document_id)).await.unwrap().with_event_bus(event_bus);
+        manager.set_content("# Saved".to_string()).await.unwrap();
+        assert!(events.try_recv().is_none());
+
+        manager.save_to_file(Some(&file_path)).await.unwrap();
+        assert_eq!(events.recv().await, Some(AppEvent::DocumentSaved { document_id, path: file_path }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_auto_save_waits_for_interval() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("draft.md");
+        std::fs::write(&file_path, "# Draft").unwrap();
+
+        let manager = DocumentStateManager::new(None).await.unwrap();
+        manager.load_from_file(&file_path).await.unwrap();
+        manager.configure_auto_save(AutoSaveConfig {
+            interval_seconds: 10,
+            save_on_focus_loss: false,
+            ..Default::default()
+        }).await.unwrap();
+        manager.set_content("# Edited".to_string()).await.unwrap();
+
+        sleep(Duration::from_secs(9)).await;
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "# Draft");
+        assert_eq!(manager.focus_lost().await.unwrap(), SaveOutcome::Skipped);
+
+        sleep(Duration::from_secs(2)).await;
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "# Edited");
+        assert!(!manager.get_state().await.is_modified);
+    }
+
+    #[test]
+    fn test_auto_save_settings_keep_defaults() {
+        let config = AutoSaveConfig::parse("interval_seconds = 120\nsave_on_focus_loss = false\n").unwrap();
+        assert_eq!(config.interval_seconds, 120);
+        assert!(!config.save_on_focus_loss);
+        assert!(config.enabled);
+        assert_eq!(config.max_backup_files, 10);
+
+        let invalid = AutoSaveConfig::parse("interval_seconds = \"soon\"").unwrap_err();
+        assert!(matches!(invalid, DocumentStateError::ConfigurationError(_)));
+    }
+
+    #[tokio::test]
+    async fn test_external_change_is_a_conflict_not_overwritten() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("shared.md");
+        std::fs::write(&file_path, "# Shared").unwrap();
+
+        let manager = DocumentStateManager::new(None).await.unwrap();
+        manager.load_from_file(&file_path).await.unwrap();
+        manager.set_content("# Mine".to_string()).await.unwrap();
+        std::fs::write(&file_path, "# Theirs").unwrap();
+
+        let SaveOutcome::Conflict(change) = manager.focus_lost().await.unwrap() else {
+            panic!("auto-save should have found the external change");
+        };
+        assert_eq!(change.disk_content.as_deref(), Some("# Theirs"));
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "# Theirs");
+        assert_eq!(manager.get_content().await, "# Mine");
+        assert!(manager.get_state().await.is_modified);
+        // Still a conflict until it is resolved
+        assert!(matches!(manager.auto_save().await.unwrap(), SaveOutcome::Conflict(_)));
+
+        manager.merge_external_change().await.unwrap();
+        assert_eq!(manager.get_content().await, "<<<<<<< LOCAL\n# Mine\n=======\n# Theirs\n>>>>>>> REMOTE");
+        assert_eq!(manager.auto_save().await.unwrap(), SaveOutcome::Saved);
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), manager.get_content().await);
+
+        std::fs::write(&file_path, "# Theirs again").unwrap();
+        manager.set_content("# Mine again".to_string()).await.unwrap();
+        assert!(matches!(manager.auto_save().await.unwrap(), SaveOutcome::Conflict(_)));
+        manager.reload_from_disk().await.unwrap();
+        assert_eq!(manager.get_content().await, "# Theirs again");
+        assert!(manager.external_change().await.is_none());
+    }
+
     #[tokio::test]
     async fn test_change_history() {
         let manager = DocumentStateManager::new(None).await.unwrap();
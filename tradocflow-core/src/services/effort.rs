@@ -0,0 +1,353 @@
+//! Effort estimates for translating documents and manuals, for quoting work.
+//!
+//! Source text is segmented and every segment is counted by how it matched:
+//! as a repetition of an earlier segment, or by its best translation memory
+//! match as exact, in a fuzzy band, or no match. Matches are scored word by
+//! word in the same way whatever the memory is, so the bands mean the same
+//! for the project's TOML units and for the translation memory service.
+
+use crate::git_integration::toml_data::TranslationUnit;
+use crate::models::translation_models::ChunkType;
+use crate::services::chunk_processor::{ChunkProcessor, ChunkingConfig, ChunkingStrategy};
+use crate::{Document, Manual, ManualSection};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tradocflow_translation_memory::TranslationMemoryService;
+use uuid::Uuid;
+
+/// A translation memory that source segments are matched against
+#[async_trait]
+pub trait TmLookup: Sync {
+    /// Similarity from 0.0 to 1.0 of the closest stored segment in
+    /// `source_language` that has a translation into `target_language`, or
+    /// `None` when nothing is stored for the pair
+    async fn best_match(&self, source: &str, source_language: &str, target_language: &str) -> Option<f64>;
+}
+
+/// The project's translation units, matched word by word
+#[async_trait]
+impl TmLookup for [TranslationUnit] {
+    async fn best_match(&self, source: &str, source_language: &str, target_language: &str) -> Option<f64> {
+        self.iter()
+            .filter(|unit| unit.source_language == source_language)
+            .filter(|unit| unit.translations.get(target_language).is_some_and(|version| !version.text.trim().is_empty()))
+            .map(|unit| similarity(source, &unit.source_text))
+            .max_by(f64::total_cmp)
+    }
+}
+
+/// The translation memory's stored units, matched word by word like the
+/// project's. A memory that fails to load counts as no match.
+#[async_trait]
+impl TmLookup for TranslationMemoryService {
+    async fn best_match(&self, source: &str, source_language: &str, target_language: &str) -> Option<f64> {
+        match self.get_translation_units().await {
+            Ok(units) => units
+                .iter()
+                .filter(|unit| unit.matches_language_codes(source_language, target_language))
+                .filter(|unit| !unit.target_text.trim().is_empty())
+                .map(|unit| similarity(source, &unit.source_text))
+                .max_by(f64::total_cmp),
+            Err(e) => {
+                log::warn!("Translation memory lookup for the effort estimate failed: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// Fuzzy matches from `min_percent` up to `max_percent`, both included
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FuzzyBand {
+    pub min_percent: u8,
+    pub max_percent: u8,
+    /// Share of a new word's cost a word in this band costs
+    pub weight: f64,
+}
+
+/// What a word costs to translate by how it matched, as a share of a word
+/// with no match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffortWeights {
+    pub no_match: f64,
+    /// Matches below the lowest band count as no match
+    pub fuzzy_bands: Vec<FuzzyBand>,
+    pub exact: f64,
+    /// Segments already seen earlier in the text
+    pub repetition: f64,
+}
+
+impl Default for EffortWeights {
+    fn default() -> Self {
+        Self {
+            no_match: 1.0,
+            fuzzy_bands: vec![
+                FuzzyBand { min_percent: 95, max_percent: 99, weight: 0.3 },
+                FuzzyBand { min_percent: 85, max_percent: 94, weight: 0.6 },
+                FuzzyBand { min_percent: 75, max_percent: 84, weight: 0.8 },
+            ],
+            exact: 0.25,
+            repetition: 0.1,
+        }
+    }
+}
+
+/// Segments and source words that matched one way
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BucketCount {
+    pub segments: usize,
+    pub words: usize,
+}
+
+impl BucketCount {
+    fn add(&mut self, words: usize) {
+        self.segments += 1;
+        self.words += words;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuzzyBandCount {
+    pub band: FuzzyBand,
+    pub count: BucketCount,
+}
+
+/// Word counts of a text to translate by how its segments matched the
+/// translation memory, for quoting the work
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffortEstimate {
+    pub target_language: String,
+    pub no_match: BucketCount,
+    /// One count per band of the weights, in their order
+    pub fuzzy: Vec<FuzzyBandCount>,
+    pub exact: BucketCount,
+    /// Segments repeating one earlier in the text, whatever the memory holds
+    pub repetition: BucketCount,
+    pub total_words: usize,
+    /// Words weighted by what their bucket costs
+    pub weighted_words: f64,
+}
+
+impl EffortEstimate {
+    fn new(target_language: &str, weights: &EffortWeights) -> Self {
+        Self {
+            target_language: target_language.to_string(),
+            no_match: BucketCount::default(),
+            fuzzy: weights.fuzzy_bands.iter().map(|band| FuzzyBandCount { band: *band, count: BucketCount::default() }).collect(),
+            exact: BucketCount::default(),
+            repetition: BucketCount::default(),
+            total_words: 0,
+            weighted_words: 0.0,
+        }
+    }
+
+    /// Count the translatable segments of `markdown`. `seen` holds the
+    /// segments counted before, which repeat rather than match.
+    async fn count(
+        &mut self,
+        markdown: &str,
+        source_language: &str,
+        tm: &(impl TmLookup + ?Sized),
+        weights: &EffortWeights,
+        seen: &mut HashSet<String>,
+    ) {
+        let processor = ChunkProcessor::with_config(ChunkingConfig {
+            strategy: ChunkingStrategy::Sentence,
+            min_chunk_length: 1,
+            merge_short_chunks: false,
+            ..ChunkingConfig::default()
+        });
+        let chunks = processor.process_content(markdown).unwrap_or_else(|e| {
+            log::warn!("Could not segment text for the effort estimate: {e}");
+            Vec::new()
+        });
+
+        for chunk in chunks.into_iter().filter(|chunk| chunk.chunk_type != ChunkType::CodeBlock) {
+            let segment = normalize(chunk.content.trim_start_matches('#'));
+            let words = word_count(&segment);
+            if words == 0 {
+                continue;
+            }
+            self.total_words += words;
+
+            if !seen.insert(segment.clone()) {
+                self.repetition.add(words);
+                self.weighted_words += words as f64 * weights.repetition;
+                continue;
+            }
+            let similarity = tm.best_match(&segment, source_language, &self.target_language).await.unwrap_or(0.0);
+            let percent = (similarity * 100.0).floor() as u8;
+            if percent >= 100 {
+                self.exact.add(words);
+                self.weighted_words += words as f64 * weights.exact;
+            } else if let Some(band) = self.fuzzy.iter_mut().find(|band| (band.band.min_percent..=band.band.max_percent).contains(&percent)) {
+                band.count.add(words);
+                self.weighted_words += words as f64 * band.band.weight;
+            } else {
+                self.no_match.add(words);
+                self.weighted_words += words as f64 * weights.no_match;
+            }
+        }
+    }
+}
+
+/// Estimate the effort of translating a document from `source_language`
+/// into `target_language`.
+///
+/// The source is split into sentences, headings and tables; code blocks
+/// are left out. A segment already seen in the document is a repetition;
+/// the others are matched against `tm` and counted as exact, fuzzy by
+/// band, or no match. The weighted total applies `weights` to each bucket.
+pub async fn estimate(
+    document: &Document,
+    source_language: &str,
+    target_language: &str,
+    tm: &(impl TmLookup + ?Sized),
+    weights: &EffortWeights,
+) -> EffortEstimate {
+    let mut estimate = EffortEstimate::new(target_language, weights);
+    if let Some(source) = document.content.get(source_language) {
+        estimate.count(source, source_language, tm, weights, &mut HashSet::new()).await;
+    }
+    estimate
+}
+
+/// [`estimate`] for every section document of a manual, in reading order.
+/// A segment repeating one of an earlier section is a repetition too.
+pub async fn estimate_manual(
+    manual: &Manual,
+    documents: &HashMap<Uuid, Document>,
+    source_language: &str,
+    target_language: &str,
+    tm: &(impl TmLookup + ?Sized),
+    weights: &EffortWeights,
+) -> EffortEstimate {
+    fn visit<'a>(sections: &'a [ManualSection], ordered: &mut Vec<&'a ManualSection>) {
+        let mut sorted: Vec<&ManualSection> = sections.iter().collect();
+        sorted.sort_by_key(|section| section.order);
+        for section in sorted {
+            ordered.push(section);
+            visit(&section.subsections, ordered);
+        }
+    }
+
+    let mut sections = Vec::new();
+    visit(&manual.sections, &mut sections);
+    let mut estimate = EffortEstimate::new(target_language, weights);
+    let mut seen = HashSet::new();
+    for document in sections.iter().filter_map(|section| section.document_id.and_then(|id| documents.get(&id))) {
+        if let Some(source) = document.content.get(source_language) {
+            estimate.count(source, source_language, tm, weights, &mut seen).await;
+        }
+    }
+    estimate
+}
+
+/// `text` trimmed, with each run of whitespace made a single space
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Words of `text`, leaving out markup such as list bullets and table rules
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().filter(|word| word.chars().any(char::is_alphanumeric)).count()
+}
+
+/// Word-level edit distance similarity from 0.0 to 1.0, ignoring case and
+/// surrounding whitespace
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<String> = a.split_whitespace().map(str::to_lowercase).collect();
+    let b: Vec<String> = b.split_whitespace().map(str::to_lowercase).collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_word) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_word) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_word != b_word);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_integration::toml_data::{ComplexityLevel, TranslationStatus, TranslationVersion};
+    use crate::DocumentMetadata;
+
+    fn unit(source: &str, translation: &str) -> TranslationUnit {
+        let mut unit = TranslationUnit::new(source.to_string(), 0, "en".to_string(), source.to_string(), ComplexityLevel::Low);
+        unit.add_translation("de".to_string(), TranslationVersion::new(translation.to_string(), "anna".to_string(), TranslationStatus::Approved));
+        unit
+    }
+
+    fn maintenance() -> Document {
+        Document {
+            title: "Maintenance".to_string(),
+            content: HashMap::from([(
+                "en".to_string(),
+                "The bell rings at noon. Check the rope. The bell rings at noon.\n\nPress the red button.\n\n```\nbell --ring\n```\n"
+                    .to_string(),
+            )]),
+            metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_estimate_buckets_repetitions_and_tm_matches() {
+        let document = maintenance();
+        let tm = vec![unit("Check the rope.", "Prüfen Sie das Seil."), unit("Press the green button.", "Drücken Sie die grüne Taste.")];
+
+        let estimate = estimate(&document, "en", "de", tm.as_slice(), &EffortWeights::default()).await;
+        assert_eq!(estimate.no_match, BucketCount { segments: 1, words: 5 });
+        assert_eq!(estimate.exact, BucketCount { segments: 1, words: 3 });
+        assert_eq!(estimate.repetition, BucketCount { segments: 1, words: 5 });
+        let fuzzy: Vec<(u8, BucketCount)> = estimate.fuzzy.iter().map(|band| (band.band.min_percent, band.count)).collect();
+        assert_eq!(
+            fuzzy,
+            [(95, BucketCount::default()), (85, BucketCount::default()), (75, BucketCount { segments: 1, words: 4 })]
+        );
+        assert_eq!(estimate.total_words, 17);
+        // 5 × 1.0 + 3 × 0.25 + 5 × 0.1 + 4 × 0.8
+        assert!((estimate.weighted_words - 9.45).abs() < 1e-9);
+
+        // Nothing translated into French, so the memory has no matches
+        let french = super::estimate(&document, "en", "fr", tm.as_slice(), &EffortWeights::default()).await;
+        assert_eq!(french.no_match, BucketCount { segments: 3, words: 12 });
+        assert_eq!(french.repetition, BucketCount { segments: 1, words: 5 });
+    }
+
+    #[tokio::test]
+    async fn test_estimate_matches_against_translation_memory_service() {
+        use std::sync::Arc;
+        use tradocflow_translation_memory::storage::InMemoryStorage;
+        use tradocflow_translation_memory::{Language, TranslationUnit as TmUnit};
+
+        let project_id = Uuid::new_v4();
+        let service = TranslationMemoryService::new(project_id, Arc::new(InMemoryStorage::new())).await.unwrap();
+        let tm_unit = |source: &str, target: &str| {
+            TmUnit::new(project_id, Uuid::new_v4(), Uuid::new_v4(), Language::English, source.to_string(), Language::German, target.to_string(), 1.0, None)
+                .unwrap()
+        };
+        service
+            .add_translation_units_batch(vec![
+                tm_unit("Check the rope.", "Prüfen Sie das Seil."),
+                tm_unit("Press the green button.", "Drücken Sie die grüne Taste."),
+            ])
+            .await
+            .unwrap();
+
+        // The same buckets as the project's units with the same segments
+        let tm = vec![unit("Check the rope.", "Prüfen Sie das Seil."), unit("Press the green button.", "Drücken Sie die grüne Taste.")];
+        let from_service = estimate(&maintenance(), "en", "de", &service, &EffortWeights::default()).await;
+        assert_eq!(from_service, estimate(&maintenance(), "en", "de", tm.as_slice(), &EffortWeights::default()).await);
+        assert_eq!(from_service.exact, BucketCount { segments: 1, words: 3 });
+    }
+}
@@ -0,0 +1,172 @@
+use crate::review_system::ReviewStatus;
+use std::path::PathBuf;
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
+use uuid::Uuid;
+
+/// Events each subscriber can fall behind by before the oldest are dropped
+pub const DEFAULT_EVENT_CAPACITY: usize = 256;
+
+/// A change that other components may want to react to
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppEvent {
+    DocumentSaved {
+        document_id: Uuid,
+        path: PathBuf,
+    },
+    SectionMoved {
+        manual_id: Uuid,
+        section_id: Uuid,
+        /// New parent section, `None` for the top level
+        parent_id: Option<Uuid>,
+        order: u32,
+    },
+    ReviewStatusChanged {
+        review_id: Uuid,
+        document_id: Uuid,
+        status: ReviewStatus,
+    },
+    TermAdded {
+        term_id: Uuid,
+        project_id: Uuid,
+        term: String,
+    },
+}
+
+impl AppEvent {
+    /// Whether translation memory lookups cached before the event may be
+    /// out of date
+    pub fn invalidates_translation_memory(&self) -> bool {
+        matches!(self, AppEvent::DocumentSaved { .. } | AppEvent::TermAdded { .. })
+    }
+}
+
+/// In-process bus that components publish [`AppEvent`]s to instead of
+/// being polled. Clones publish to the same subscribers.
+///
+/// Publishing never waits: each subscriber has a queue of `capacity`
+/// events, and one that falls further behind loses its oldest events
+/// rather than stalling publishers.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<AppEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Send `event` to every current subscriber, returning how many there
+    /// were. Publishing without subscribers is not an error.
+    pub fn publish(&self, event: AppEvent) -> usize {
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> EventSubscriber {
+        EventSubscriber { receiver: self.sender.subscribe(), missed: 0 }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+/// Receiving end of an [`EventBus`]
+#[derive(Debug)]
+pub struct EventSubscriber {
+    receiver: broadcast::Receiver<AppEvent>,
+    missed: u64,
+}
+
+impl EventSubscriber {
+    /// Wait for the next event, or `None` once every handle of the bus is
+    /// dropped. Events lost by falling behind are skipped and counted in
+    /// [`Self::missed`].
+    pub async fn recv(&mut self) -> Option<AppEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(count)) => self.record_missed(count),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// The next event if one is queued
+    pub fn try_recv(&mut self) -> Option<AppEvent> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => return Some(event),
+                Err(TryRecvError::Lagged(count)) => self.record_missed(count),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Events dropped because this subscriber fell behind
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+
+    fn record_missed(&mut self, count: u64) {
+        log::warn!("Event subscriber fell behind and missed {count} events");
+        self.missed += count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term_added(term: &str) -> AppEvent {
+        AppEvent::TermAdded { term_id: Uuid::new_v4(), project_id: Uuid::nil(), term: term.to_string() }
+    }
+
+    #[tokio::test]
+    async fn test_every_subscriber_receives_events() {
+        let bus = EventBus::default();
+        assert_eq!(bus.publish(term_added("Glocke")), 0);
+
+        let mut first = bus.subscribe();
+        let mut second = bus.clone().subscribe();
+        let event = term_added("Klöppel");
+        assert_eq!(bus.publish(event.clone()), 2);
+
+        assert_eq!(first.recv().await, Some(event.clone()));
+        assert_eq!(second.try_recv(), Some(event));
+        assert!(first.try_recv().is_none());
+
+        drop(bus);
+        assert_eq!(first.recv().await, None);
+    }
+
+    fn next_term(subscriber: &mut EventSubscriber) -> String {
+        match subscriber.try_recv() {
+            Some(AppEvent::TermAdded { term, .. }) => term,
+            other => panic!("expected a term, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_does_not_stall_publishers() {
+        let bus = EventBus::new(2);
+        let mut slow = bus.subscribe();
+
+        for term in ["Glocke", "Klöppel", "Joch", "Seil"] {
+            assert_eq!(bus.publish(term_added(term)), 1);
+        }
+
+        assert_eq!(next_term(&mut slow), "Joch");
+        assert_eq!(slow.missed(), 2);
+        assert_eq!(next_term(&mut slow), "Seil");
+        assert!(slow.try_recv().is_none());
+    }
+}
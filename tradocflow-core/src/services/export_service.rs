@@ -1,5 +1,5 @@
 use crate::{
-    export_engine::{ExportConfig, ExportEngine, ExportFormat},
+    export_engine::{CodeTheme, ExportConfig, ExportEngine, ExportFormat, ListDigits, PageSetup},
     Result, TradocumentError,
 };
 use chrono::{DateTime, Utc};
@@ -56,6 +56,9 @@ pub struct ExportJob {
     pub completed_at: Option<DateTime<Utc>>,
     pub output_files: Vec<ExportedFile>,
     pub error_message: Option<String>,
+    /// Bytes the image policy saved across all exported files
+    #[serde(default)]
+    pub image_bytes_saved: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,6 +143,7 @@ impl ExportService {
             completed_at: None,
             output_files: Vec::new(),
             error_message: None,
+            image_bytes_saved: 0,
         };
 
         {
@@ -328,9 +332,10 @@ impl ExportService {
 
                 let export_config = Self::create_export_config(&job.request.config, vec![language.clone()]);
                 let lib_document = Self::convert_to_lib_document(document, language);
-                let exported_data = export_engine.export_document(&lib_document, &export_config).await?;
+                let exported = export_engine.export_document(&lib_document, &export_config).await?;
+                job.image_bytes_saved += exported.image_bytes_saved;
 
-                for (filename, data) in exported_data {
+                for (filename, data) in exported.files {
                     let output_path = job.request.output_directory.join(&filename);
                     let file_size = data.len() as u64;
                     tokio::fs::write(&output_path, data).await?;
@@ -386,11 +391,18 @@ impl ExportService {
 
     fn create_export_config(config: &ExportConfiguration, languages: Vec<String>) -> ExportConfig {
         ExportConfig {
-            format: config.format.clone(),
+            format: config.format,
             include_screenshots: true, // Could be configurable
             template: None,
             css_file: config.custom_css_path.as_ref().map(|p| p.to_string_lossy().to_string()),
             languages,
+            page_setup: PageSetup::default(),
+            code_theme: CodeTheme::default(),
+            image_policy: None,
+            header_template: None,
+            watermark: None,
+            review_status: None,
+            list_digits: ListDigits::default(),
         }
     }
 
@@ -419,6 +431,8 @@ impl ExportService {
             metadata: crate::DocumentMetadata {
                 project_id: Some(document.project_id.to_string()),
                 screenshots: Vec::new(), // TODO: Convert screenshots if needed
+                version: Some(document.metadata.version.clone()),
+                custom: HashMap::new(),
             },
         }
     }
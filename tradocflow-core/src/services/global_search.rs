@@ -0,0 +1,374 @@
+use crate::database::Database;
+use crate::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tradocflow_translation_memory::{Language, TerminologyService, TranslationMemoryService};
+
+/// Minimum similarity of translation memory matches
+const TM_SEARCH_THRESHOLD: f64 = 0.5;
+/// Characters of context shown on each side of a match
+const SNIPPET_CONTEXT: usize = 40;
+
+/// Where a search result came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SearchSource {
+    Document,
+    Term,
+    TranslationMemory,
+}
+
+/// One result of [`global_search`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlobalSearchHit {
+    pub source: SearchSource,
+    /// Id of the document, term or translation unit
+    pub id: String,
+    /// Document title, term, or source text of a translation
+    pub title: String,
+    /// Matching excerpt, definition, or target text of a translation
+    pub snippet: String,
+    pub language: Option<String>,
+    /// Relevance to the query from 0.0 to 1.0, comparable across sources
+    pub score: f32,
+}
+
+/// A backend that could not be searched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchFailure {
+    pub source: SearchSource,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlobalSearchResults {
+    /// Best match first
+    pub hits: Vec<GlobalSearchHit>,
+    /// Backends that failed; `hits` holds what the others found
+    pub failures: Vec<SearchFailure>,
+}
+
+impl GlobalSearchResults {
+    /// Whether a backend failed, so results may be missing
+    pub fn is_partial(&self) -> bool {
+        !self.failures.is_empty()
+    }
+}
+
+/// A store that [`global_search`] fans out to
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    fn source(&self) -> SearchSource;
+
+    /// Matches for `query` in `languages`. The first language is the source
+    /// language of term and translation lookups; each further one is a
+    /// target.
+    async fn search(&self, query: &str, languages: &[String]) -> Result<Vec<GlobalSearchHit>>;
+}
+
+/// Search every backend at once and merge their results into one ranking.
+///
+/// Hits with the same source, title and snippet are reported once, with the
+/// best score. A failing backend is recorded in
+/// [`GlobalSearchResults::failures`] and the others' results are kept.
+pub async fn global_search(query: &str, languages: &[String], backends: &[&dyn SearchBackend]) -> GlobalSearchResults {
+    let mut results = GlobalSearchResults::default();
+    let query = query.trim();
+    if query.is_empty() {
+        return results;
+    }
+
+    let searches = backends.iter().map(|backend| async move { (backend.source(), backend.search(query, languages).await) });
+    for (source, outcome) in futures::future::join_all(searches).await {
+        match outcome {
+            Ok(hits) => results.hits.extend(hits),
+            Err(e) => {
+                log::warn!("{source:?} search for \"{query}\" failed: {e}");
+                results.failures.push(SearchFailure { source, message: e.to_string() });
+            }
+        }
+    }
+
+    results.hits.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| source_rank(a.source).cmp(&source_rank(b.source)))
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    let mut seen = HashSet::new();
+    results
+        .hits
+        .retain(|hit| seen.insert((hit.source, hit.title.to_lowercase(), hit.snippet.to_lowercase())));
+    results
+}
+
+/// Order of sources among equally relevant hits
+fn source_rank(source: SearchSource) -> u8 {
+    match source {
+        SearchSource::Term => 0,
+        SearchSource::Document => 1,
+        SearchSource::TranslationMemory => 2,
+    }
+}
+
+/// How well `text` matches `query`: 1.0 for the whole text, less for a
+/// prefix, a whole word and any substring, and partial credit for the share
+/// of query words found
+fn relevance(query: &str, text: &str) -> f32 {
+    let query = query.trim().to_lowercase();
+    let text = text.trim().to_lowercase();
+    if query.is_empty() {
+        return 0.0;
+    }
+    if text == query {
+        return 1.0;
+    }
+    if text.starts_with(&query) {
+        return 0.9;
+    }
+    if let Some(index) = text.find(&query) {
+        let before = text[..index].chars().next_back();
+        let after = text[index + query.len()..].chars().next();
+        let boundary = |c: Option<char>| !c.is_some_and(char::is_alphanumeric);
+        return if boundary(before) && boundary(after) { 0.8 } else { 0.6 };
+    }
+
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let found = words.iter().filter(|word| text.contains(*word)).count();
+    0.5 * found as f32 / words.len() as f32
+}
+
+/// Excerpt of `text` around the first match of `query`
+fn snippet(text: &str, query: &str) -> String {
+    let lower = text.to_lowercase();
+    // Offsets only carry over when lowercasing kept every byte length
+    let start = match lower.find(&query.trim().to_lowercase()) {
+        Some(index) if lower.len() == text.len() => index,
+        _ => 0,
+    };
+
+    let from = text[..start].char_indices().rev().nth(SNIPPET_CONTEXT - 1).map_or(0, |(i, _)| i);
+    let rest = &text[start..];
+    let to = start + rest.char_indices().nth(query.chars().count() + SNIPPET_CONTEXT).map_or(rest.len(), |(i, _)| i);
+
+    let mut excerpt = text[from..to].split_whitespace().collect::<Vec<_>>().join(" ");
+    if from > 0 {
+        excerpt.insert(0, '…');
+    }
+    if to < text.len() {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+pub(crate) fn language_of(code: &str) -> Language {
+    Language::from_code(code).unwrap_or_else(|| Language::Custom(code.to_string()))
+}
+
+#[async_trait]
+impl SearchBackend for Database {
+    fn source(&self) -> SearchSource {
+        SearchSource::Document
+    }
+
+    async fn search(&self, query: &str, languages: &[String]) -> Result<Vec<GlobalSearchHit>> {
+        let documents = self.search_documents(query).await?;
+
+        let mut hits = Vec::new();
+        for document in documents {
+            let title_score = relevance(query, &document.title);
+            let mut matched = false;
+            for (language, content) in &document.content {
+                if !languages.is_empty() && !languages.contains(language) {
+                    continue;
+                }
+                let content_score = relevance(query, content).min(0.8);
+                if content_score == 0.0 && title_score == 0.0 {
+                    continue;
+                }
+                matched = true;
+                hits.push(GlobalSearchHit {
+                    source: SearchSource::Document,
+                    id: document.id.to_string(),
+                    title: document.title.clone(),
+                    snippet: snippet(content, query),
+                    language: Some(language.clone()),
+                    score: title_score.max(content_score),
+                });
+            }
+
+            if !matched && title_score > 0.0 {
+                hits.push(GlobalSearchHit {
+                    source: SearchSource::Document,
+                    id: document.id.to_string(),
+                    title: document.title,
+                    snippet: String::new(),
+                    language: None,
+                    score: title_score,
+                });
+            }
+        }
+        Ok(hits)
+    }
+}
+
+#[async_trait]
+impl SearchBackend for TerminologyService {
+    fn source(&self) -> SearchSource {
+        SearchSource::Term
+    }
+
+    async fn search(&self, query: &str, languages: &[String]) -> Result<Vec<GlobalSearchHit>> {
+        let Some(source) = languages.first() else {
+            return Ok(Vec::new());
+        };
+        let targets = if languages.len() > 1 { &languages[1..] } else { &languages[..1] };
+
+        let mut hits = Vec::new();
+        for target in targets {
+            let terms = self.search_terms(query, language_of(source), language_of(target)).await?;
+            hits.extend(terms.into_iter().map(|term| GlobalSearchHit {
+                source: SearchSource::Term,
+                id: term.id.to_string(),
+                score: relevance(query, &term.term),
+                snippet: term.definition.unwrap_or_default(),
+                language: term.language.map(|language| language.code().to_string()),
+                title: term.term,
+            }));
+        }
+        Ok(hits)
+    }
+}
+
+#[async_trait]
+impl SearchBackend for TranslationMemoryService {
+    fn source(&self) -> SearchSource {
+        SearchSource::TranslationMemory
+    }
+
+    async fn search(&self, query: &str, languages: &[String]) -> Result<Vec<GlobalSearchHit>> {
+        let Some((source, targets)) = languages.split_first() else {
+            return Ok(Vec::new());
+        };
+
+        let mut hits = Vec::new();
+        for target in targets {
+            let units = TranslationMemoryService::search(
+                self,
+                query,
+                language_of(source),
+                language_of(target),
+                TM_SEARCH_THRESHOLD,
+            )
+            .await?;
+            hits.extend(units.into_iter().map(|unit| GlobalSearchHit {
+                source: SearchSource::TranslationMemory,
+                id: unit.id.to_string(),
+                score: relevance(query, &unit.source_text).max(unit.confidence_score * 0.5),
+                title: unit.source_text,
+                snippet: unit.target_text,
+                language: Some(target.clone()),
+            }));
+        }
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TradocumentError;
+
+    struct FixedBackend {
+        source: SearchSource,
+        hits: Vec<(&'static str, &'static str)>,
+    }
+
+    #[async_trait]
+    impl SearchBackend for FixedBackend {
+        fn source(&self) -> SearchSource {
+            self.source
+        }
+
+        async fn search(&self, query: &str, _languages: &[String]) -> Result<Vec<GlobalSearchHit>> {
+            Ok(self
+                .hits
+                .iter()
+                .map(|(title, snippet)| GlobalSearchHit {
+                    source: self.source,
+                    id: title.to_string(),
+                    title: title.to_string(),
+                    snippet: snippet.to_string(),
+                    language: Some("en".to_string()),
+                    score: relevance(query, title),
+                })
+                .collect())
+        }
+    }
+
+    struct FailingBackend;
+
+    #[async_trait]
+    impl SearchBackend for FailingBackend {
+        fn source(&self) -> SearchSource {
+            SearchSource::TranslationMemory
+        }
+
+        async fn search(&self, _query: &str, _languages: &[String]) -> Result<Vec<GlobalSearchHit>> {
+            Err(TradocumentError::Validation("translation memory is offline".to_string()))
+        }
+    }
+
+    async fn insert_document(database: &Database, title: &str, content: &str) {
+        let conn = database.pool();
+        let conn = conn.lock().await;
+        conn.execute(
+            "INSERT INTO documents (id, title, content, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+            rusqlite::params![
+                uuid::Uuid::new_v4().to_string(),
+                title,
+                serde_json::json!({ "en": content }).to_string(),
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_merged_results_carry_source_tags() {
+        let database = Database::in_memory().unwrap();
+        insert_document(&database, "Installation", "Tighten the mounting bracket before wiring.").await;
+        insert_document(&database, "Maintenance", "Clean the housing twice a year.").await;
+        let terms = FixedBackend {
+            source: SearchSource::Term,
+            hits: vec![
+                ("mounting bracket", "Steel bracket holding the controller"),
+                ("Mounting bracket", "Steel bracket holding the controller"),
+            ],
+        };
+
+        let languages = vec!["en".to_string()];
+        let results = global_search("mounting bracket", &languages, &[&database, &terms]).await;
+
+        assert!(!results.is_partial());
+        assert_eq!(results.hits.len(), 2);
+        assert_eq!(results.hits[0].source, SearchSource::Term);
+        assert_eq!(results.hits[0].score, 1.0);
+        assert_eq!(results.hits[1].source, SearchSource::Document);
+        assert_eq!(results.hits[1].title, "Installation");
+        assert!(results.hits[1].snippet.contains("mounting bracket"));
+    }
+
+    #[tokio::test]
+    async fn test_failing_backend_keeps_other_results() {
+        let documents = FixedBackend { source: SearchSource::Document, hits: vec![("Wiring", "Connect the bell relays")] };
+        let languages = vec!["en".to_string(), "de".to_string()];
+
+        let results = global_search("wiring", &languages, &[&FailingBackend, &documents]).await;
+        assert!(results.is_partial());
+        assert_eq!(results.failures[0].source, SearchSource::TranslationMemory);
+        assert!(results.failures[0].message.contains("offline"));
+        assert_eq!(results.hits.len(), 1);
+        assert_eq!(results.hits[0].source, SearchSource::Document);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::services::outline::{extract_outline, OutlineNode};
+use crate::{Document, DocumentMetadata, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Metadata key prefix; each language keeps its ids under
+/// `heading-ids.<language>` in [`DocumentMetadata::custom`]
+pub const HEADING_IDS_METADATA_PREFIX: &str = "heading-ids.";
+
+fn metadata_key(language: &str) -> String {
+    format!("{HEADING_IDS_METADATA_PREFIX}{language}")
+}
+
+fn heading_id_regex() -> &'static Regex {
+    static HEADING_ID: OnceLock<Regex> = OnceLock::new();
+    HEADING_ID.get_or_init(|| Regex::new(r"[ \t]+\{#([A-Za-z][\w:.-]*)\}$").expect("valid heading id regex"))
+}
+
+fn atx_heading_regex() -> &'static Regex {
+    static ATX_HEADING: OnceLock<Regex> = OnceLock::new();
+    ATX_HEADING.get_or_init(|| Regex::new(r"^ {0,3}#{1,6}[ \t]").expect("valid heading regex"))
+}
+
+/// Split an explicit `{#id}` off the end of heading text
+pub fn split_heading_id(text: &str) -> (&str, Option<&str>) {
+    match heading_id_regex().captures(text) {
+        Some(captures) => {
+            let whole = captures.get(0).expect("whole match");
+            (&text[..whole.start()], Some(captures.get(1).expect("id").as_str()))
+        }
+        None => (text, None),
+    }
+}
+
+/// Remove explicit `{#id}` suffixes from the headings of a document, so
+/// renderers that don't know the syntax don't show them as text
+pub fn strip_heading_ids(markdown: &str) -> String {
+    let mut stripped = String::with_capacity(markdown.len());
+    let mut fence: Option<&str> = None;
+
+    for line in markdown.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim_start();
+        match fence {
+            Some(marker) if trimmed.starts_with(marker) => fence = None,
+            Some(_) => {}
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None if atx_heading_regex().is_match(content) => {
+                let (text, id) = split_heading_id(content.trim_end());
+                if id.is_some() {
+                    stripped.push_str(text);
+                    stripped.push_str(&line[content.len()..]);
+                    continue;
+                }
+            }
+            None => {}
+        }
+        stripped.push_str(line);
+    }
+
+    stripped
+}
+
+/// The persisted id of a heading without an explicit `{#id}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StableHeadingId {
+    pub id: String,
+    pub level: usize,
+    /// Heading text when the id was last assigned
+    pub text: String,
+}
+
+/// Ids assigned to the headings of one language of a document.
+///
+/// A heading first gets its derived slug as id. Later assignments match
+/// headings to the stored ids, first by unchanged text and then by position
+/// between the headings that did match, so rewording a heading keeps its id.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeadingIdRegistry {
+    /// In document order
+    pub headings: Vec<StableHeadingId>,
+}
+
+impl HeadingIdRegistry {
+    /// The registry stored for `language`, or an empty one
+    pub fn from_metadata(metadata: &DocumentMetadata, language: &str) -> Self {
+        metadata
+            .custom
+            .get(&metadata_key(language))
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn store(&self, metadata: &mut DocumentMetadata, language: &str) -> Result<()> {
+        metadata.custom.insert(metadata_key(language), serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    /// Ids of all headings in document order, without recording them
+    pub fn resolve(&self, markdown: &str) -> Vec<String> {
+        self.clone().assign(markdown)
+    }
+
+    /// Ids of all headings in document order. Explicit ids win; headings
+    /// without one keep their stored id where they can be matched, and new
+    /// headings get a fresh id. The registry is updated to the current
+    /// headings.
+    pub fn assign(&mut self, markdown: &str) -> Vec<String> {
+        let outline = extract_outline(markdown);
+        let headings: Vec<&OutlineNode> = outline.iter().flat_map(|node| node.flatten()).collect();
+
+        let mut ids: Vec<Option<String>> = headings
+            .iter()
+            .map(|heading| heading.explicit_id.then(|| heading.slug.clone()))
+            .collect();
+        let mut used: HashSet<String> = ids.iter().flatten().cloned().collect();
+        let mut taken = vec![false; self.headings.len()];
+        // Stored entry each heading was matched to
+        let mut matched: Vec<Option<usize>> = vec![None; headings.len()];
+
+        // Unchanged headings keep their ids
+        for (index, heading) in headings.iter().enumerate() {
+            if ids[index].is_some() {
+                continue;
+            }
+            let found = (0..self.headings.len()).find(|&entry| {
+                let stored = &self.headings[entry];
+                !taken[entry] && stored.level == heading.level && stored.text == heading.text && !used.contains(&stored.id)
+            });
+            if let Some(entry) = found {
+                taken[entry] = true;
+                matched[index] = Some(entry);
+                used.insert(self.headings[entry].id.clone());
+                ids[index] = Some(self.headings[entry].id.clone());
+            }
+        }
+
+        // Reworded headings take an unmatched id from between their neighbours
+        for (index, heading) in headings.iter().enumerate() {
+            if ids[index].is_some() {
+                continue;
+            }
+            let after = matched[..index].iter().rev().flatten().next().map_or(0, |&entry| entry + 1);
+            let before = matched[index + 1..].iter().flatten().next().copied().unwrap_or(self.headings.len());
+            let found = (after..before.max(after)).find(|&entry| {
+                let stored = &self.headings[entry];
+                !taken[entry] && stored.level == heading.level && !used.contains(&stored.id)
+            });
+            if let Some(entry) = found {
+                taken[entry] = true;
+                matched[index] = Some(entry);
+                used.insert(self.headings[entry].id.clone());
+                ids[index] = Some(self.headings[entry].id.clone());
+            }
+        }
+
+        // New headings start from their derived slug
+        for (index, heading) in headings.iter().enumerate() {
+            if ids[index].is_none() {
+                let mut id = heading.slug.clone();
+                let mut suffix = 0;
+                while used.contains(&id) {
+                    suffix += 1;
+                    id = format!("{}-{suffix}", heading.slug);
+                }
+                used.insert(id.clone());
+                ids[index] = Some(id);
+            }
+        }
+
+        let ids: Vec<String> = ids.into_iter().flatten().collect();
+        self.headings = headings
+            .iter()
+            .zip(&ids)
+            .filter(|(heading, _)| !heading.explicit_id)
+            .map(|(heading, id)| StableHeadingId { id: id.clone(), level: heading.level, text: heading.text.clone() })
+            .collect();
+        ids
+    }
+}
+
+/// Assign stable ids to the headings of a document's `language` content and
+/// persist them in its metadata. Returns the ids of all headings in order.
+pub fn assign_stable_heading_ids(document: &mut Document, language: &str) -> Result<Vec<String>> {
+    let Some(content) = document.content.get(language) else {
+        return Ok(Vec::new());
+    };
+
+    let mut registry = HeadingIdRegistry::from_metadata(&document.metadata, language);
+    let ids = registry.assign(content);
+    registry.store(&mut document.metadata, language)?;
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn document(content: &str) -> Document {
+        Document {
+            title: "Manual".to_string(),
+            content: HashMap::from([("en".to_string(), content.to_string())]),
+            metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+        }
+    }
+
+    #[test]
+    fn test_explicit_id_is_stripped_and_used() {
+        assert_eq!(split_heading_id("Installing {#install}"), ("Installing", Some("install")));
+        assert_eq!(split_heading_id("Braces {not an id}"), ("Braces {not an id}", None));
+        assert_eq!(
+            strip_heading_ids("## Installing {#install}\r\n```\n# Kept {#id}\n```\n"),
+            "## Installing\r\n```\n# Kept {#id}\n```\n"
+        );
+
+        let ids = HeadingIdRegistry::default().resolve("# Guide\n## Installing the unit {#install}\n");
+        assert_eq!(ids, vec!["guide", "install"]);
+    }
+
+    #[test]
+    fn test_assigned_ids_survive_rewording() {
+        let mut manual = document("# Guide\n## Installing\n## Wiring\n## Maintenance\n");
+        let first = assign_stable_heading_ids(&mut manual, "en").unwrap();
+        assert_eq!(first, vec!["guide", "installing", "wiring", "maintenance"]);
+
+        // Reword one heading and insert another before it
+        manual.content.insert(
+            "en".to_string(),
+            "# Guide\n## Unpacking\n## Installing\n## Connecting the cables\n## Maintenance\n".to_string(),
+        );
+        let second = assign_stable_heading_ids(&mut manual, "en").unwrap();
+        assert_eq!(second, vec!["guide", "unpacking", "installing", "wiring", "maintenance"]);
+
+        // The ids were persisted in the metadata
+        let stored = HeadingIdRegistry::from_metadata(&manual.metadata, "en");
+        assert_eq!(stored.headings[3].text, "Connecting the cables");
+        assert_eq!(stored.headings[3].id, "wiring");
+    }
+}
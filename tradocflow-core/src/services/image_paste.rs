@@ -0,0 +1,267 @@
+use super::atomic_file::write_atomic;
+use super::image_import::ImageKind;
+use super::markdown_ops::Selection;
+use super::thumbnails::{Thumbnail, ThumbnailCache};
+use crate::database::image_repository::ImageRepository;
+use crate::{Document, Result, ScreenshotReference, TradocumentError};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Directory next to the document that pasted images are saved in
+const ASSETS_DIR: &str = "assets";
+/// Longest side in pixels of the thumbnails made of pasted images
+const PASTE_THUMBNAIL_SIZE: u32 = 256;
+/// Hex digits of the content hash kept in the file names of pasted images
+const NAME_HASH_LENGTH: usize = 12;
+/// Alt text inserted with a pasted image, selected so it can be typed over
+const PLACEHOLDER_ALT_TEXT: &str = "Screenshot";
+
+/// An image pasted into a document and saved with its assets
+#[derive(Debug, Clone)]
+pub struct PastedImage {
+    pub screenshot: ScreenshotReference,
+    /// The sanitized image on disk
+    pub path: PathBuf,
+    /// Thumbnail of the image; SVG images have none
+    pub thumbnail: Option<Thumbnail>,
+    /// Markdown image linking the file from the document
+    pub markdown: String,
+}
+
+impl PastedImage {
+    /// `text` with `selection` replaced by the markdown image, and the
+    /// selection over the image's alt text
+    pub fn insert_into(&self, text: &str, selection: Selection) -> (String, Selection) {
+        let selection = selection.clamp_to(text);
+        let new_text = format!("{}{}{}", &text[..selection.start], self.markdown, &text[selection.end..]);
+        // After the `![`
+        let alt_start = selection.start + 2;
+        (new_text, Selection::new(alt_start, alt_start + PLACEHOLDER_ALT_TEXT.len()))
+    }
+}
+
+/// Saves images pasted from the clipboard into the image store, as the
+/// screenshots of documents in a directory. Each image is sanitized as the
+/// store cleans images, stored once under the hash of its content, and
+/// written to the documents' assets, named after the document and the hash,
+/// for the markdown to link to.
+#[derive(Clone)]
+pub struct ImagePaster {
+    document_dir: PathBuf,
+    store: ImageRepository,
+    thumbnails: ThumbnailCache,
+}
+
+impl ImagePaster {
+    /// A paster storing images in `store` for documents in `document_dir`,
+    /// with thumbnails in the user's cache directory
+    pub fn new(document_dir: impl AsRef<Path>, store: ImageRepository) -> Self {
+        Self {
+            document_dir: document_dir.as_ref().to_path_buf(),
+            store,
+            thumbnails: ThumbnailCache::default(),
+        }
+    }
+
+    pub fn with_thumbnails(mut self, thumbnails: ThumbnailCache) -> Self {
+        self.thumbnails = thumbnails;
+        self
+    }
+
+    /// Where pasted images are saved
+    pub fn assets_dir(&self) -> PathBuf {
+        self.document_dir.join(ASSETS_DIR)
+    }
+
+    /// Save `clipboard`, the image data on the clipboard if it holds any, as
+    /// a screenshot in `language` of the document `document_id` titled
+    /// `title`. Pasting the same image again gives the same screenshot and
+    /// file, neither of which is stored twice.
+    pub async fn save(
+        &self,
+        document_id: Uuid,
+        title: &str,
+        language: &str,
+        clipboard: Option<&[u8]>,
+    ) -> Result<PastedImage> {
+        let bytes = clipboard
+            .filter(|bytes| !bytes.is_empty())
+            .ok_or_else(|| TradocumentError::Validation("There is no image on the clipboard".to_string()))?;
+        let kind = ImageKind::detect(bytes).ok_or_else(|| {
+            TradocumentError::UnsupportedFormat("The clipboard holds no PNG, JPEG, GIF, WebP or SVG image".to_string())
+        })?;
+        let sanitized = self.store.sanitize(bytes, "The pasted image")?;
+
+        let hash = ImageRepository::content_hash(&sanitized);
+        let id = format!("{}-{}", file_slug(title), &hash[..NAME_HASH_LENGTH]);
+        let mut screenshot = ScreenshotReference {
+            id,
+            language: language.to_string(),
+            screen_config: "{}".to_string(),
+            generated_at: Some(Utc::now()),
+            caption: None,
+            content_hash: None,
+            alt_text: HashMap::new(),
+        };
+        self.store.embed(document_id, &mut screenshot, &sanitized).await?;
+
+        let file_name = format!("{}.{}", screenshot.id, kind.extension());
+        let path = self.assets_dir().join(&file_name);
+        if !path.exists() {
+            tokio::fs::create_dir_all(self.assets_dir()).await?;
+            write_atomic(&path, &sanitized, None).await?;
+        }
+        let thumbnail = match kind {
+            ImageKind::Svg => None,
+            _ => Some(self.thumbnails.thumbnail(&path, PASTE_THUMBNAIL_SIZE)?),
+        };
+
+        Ok(PastedImage {
+            screenshot,
+            path,
+            thumbnail,
+            markdown: format!("![{PLACEHOLDER_ALT_TEXT}]({ASSETS_DIR}/{file_name})"),
+        })
+    }
+
+    /// Paste `clipboard` over `selection` in `text`, the content of
+    /// `document` in `language`: save the image, add its screenshot to the
+    /// document unless it has it already, and insert the markdown image.
+    /// Returns the new text with the image's alt text selected.
+    pub async fn paste(
+        &self,
+        document_id: Uuid,
+        document: &mut Document,
+        language: &str,
+        clipboard: Option<&[u8]>,
+        text: &str,
+        selection: Selection,
+    ) -> Result<(String, Selection)> {
+        let pasted = self.save(document_id, &document.title, language, clipboard).await?;
+        let screenshots = &mut document.metadata.screenshots;
+        if !screenshots.iter().any(|s| s.id == pasted.screenshot.id && s.language == language) {
+            screenshots.push(pasted.screenshot.clone());
+        }
+        Ok(pasted.insert_into(text, selection))
+    }
+}
+
+/// `title` lowercased with runs of anything but letters and digits made a
+/// single `-`, or `document` when nothing is left
+fn file_slug(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "document".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::DocumentMetadata;
+    use image::{ImageFormat, Rgb, RgbImage};
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    fn document(title: &str) -> Document {
+        Document {
+            title: title.to_string(),
+            content: HashMap::new(),
+            metadata: DocumentMetadata {
+                project_id: None,
+                screenshots: Vec::new(),
+                version: None,
+                custom: HashMap::new(),
+            },
+        }
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = Vec::new();
+        let image = RgbImage::from_fn(width, height, |x, y| Rgb([(x * 3) as u8, (y * 2) as u8, 90]));
+        image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).unwrap();
+        png
+    }
+
+    fn paster(temp_dir: &TempDir) -> (ImagePaster, ImageRepository) {
+        let store = ImageRepository::new(Database::in_memory().unwrap().pool());
+        let paster = ImagePaster::new(temp_dir.path(), store.clone())
+            .with_thumbnails(ThumbnailCache::new(temp_dir.path().join("thumbs")));
+        (paster, store)
+    }
+
+    #[tokio::test]
+    async fn test_pasted_png_saved_as_screenshot_and_inserted() {
+        let temp_dir = TempDir::new().unwrap();
+        let (paster, store) = paster(&temp_dir);
+        let document_id = Uuid::new_v4();
+        let mut manual = document("Bell Tower: Setup");
+        let clipboard = png(640, 320);
+
+        let text = "Open the panel.\n\nThen restart.";
+        let (pasted, selection) = paster
+            .paste(document_id, &mut manual, "en", Some(&clipboard), text, Selection::cursor(17))
+            .await
+            .unwrap();
+
+        let hash = ImageRepository::content_hash(&clipboard);
+        let id = format!("bell-tower-setup-{}", &hash[..12]);
+        let path = temp_dir.path().join("assets").join(format!("{id}.png"));
+        assert_eq!(std::fs::read(&path).unwrap(), clipboard);
+        assert_eq!(pasted, format!("Open the panel.\n\n![Screenshot](assets/{id}.png)Then restart."));
+        assert_eq!(&pasted[selection.start..selection.end], "Screenshot");
+
+        let screenshots = &manual.metadata.screenshots;
+        assert_eq!(screenshots.len(), 1);
+        assert_eq!(screenshots[0].id, id);
+        assert_eq!(screenshots[0].language, "en");
+        assert_eq!(screenshots[0].content_hash.as_deref(), Some(hash.as_str()));
+        assert_eq!(store.resolve(document_id, &screenshots[0]).await.unwrap(), Some(clipboard.clone()));
+
+        // The same image again is the same file, screenshot and blob
+        let again = paster.save(document_id, &manual.title, "en", Some(&clipboard)).await.unwrap();
+        assert_eq!(again.path, path);
+        let thumbnail = again.thumbnail.unwrap();
+        assert!(thumbnail.from_cache && thumbnail.path.exists());
+        assert_eq!((thumbnail.width, thumbnail.height), (256, 128));
+        paster
+            .paste(document_id, &mut manual, "en", Some(&clipboard), "", Selection::cursor(0))
+            .await
+            .unwrap();
+        assert_eq!(manual.metadata.screenshots.len(), 1);
+        assert_eq!(store.blob_count().await.unwrap(), 1);
+        assert_eq!(store.reference_count(&hash).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_missing_or_unsupported_clipboard_image_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        let (paster, store) = paster(&temp_dir);
+        let document_id = Uuid::new_v4();
+        let mut manual = document("Setup");
+
+        let empty = paster
+            .paste(document_id, &mut manual, "en", None, "Text", Selection::cursor(4))
+            .await
+            .unwrap_err();
+        assert!(matches!(empty, TradocumentError::Validation(_)));
+        let text = paster.save(document_id, "Setup", "en", Some(b"Just some copied text")).await.unwrap_err();
+        assert!(matches!(text, TradocumentError::UnsupportedFormat(_)));
+        let damaged = paster.save(document_id, "Setup", "en", Some(&png(8, 8)[..40])).await.unwrap_err();
+        assert!(matches!(damaged, TradocumentError::UnsupportedFormat(_)));
+
+        assert!(manual.metadata.screenshots.is_empty());
+        assert!(!paster.assets_dir().exists());
+        assert_eq!(store.blob_count().await.unwrap(), 0);
+    }
+}
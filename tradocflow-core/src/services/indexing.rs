@@ -0,0 +1,575 @@
+use crate::database::{DatabasePool, Transaction};
+use crate::services::events::{AppEvent, EventSubscriber};
+use crate::services::global_search::language_of;
+use crate::services::TranslationMemoryAdapter;
+use crate::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{watch, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep_until, Instant};
+use tradocflow_translation_memory::{CancellationToken, TranslationUnit};
+use uuid::Uuid;
+
+/// Quiet period after the last save before the affected entries are rebuilt
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// A search or translation memory index kept up to date by an [`IndexWorker`]
+#[async_trait]
+pub trait DocumentIndexer: Send + Sync {
+    /// Rebuild the index entries of `document_ids`
+    async fn reindex(&self, document_ids: &[Uuid]) -> Result<()>;
+
+    /// Every document the index should hold
+    async fn all_documents(&self) -> Result<Vec<Uuid>>;
+}
+
+/// How up to date the index of an [`IndexWorker`] is
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexFreshness {
+    /// When the last successful rebuild finished
+    pub last_indexed: Option<DateTime<Utc>>,
+    /// Saved documents waiting for the next rebuild
+    pub pending: usize,
+    pub full_rebuild_pending: bool,
+    pub rebuilding: bool,
+    /// Error of the last rebuild, cleared by the next successful one
+    pub last_error: Option<String>,
+}
+
+impl IndexFreshness {
+    /// Whether every change seen so far is in the index
+    pub fn is_fresh(&self) -> bool {
+        self.pending == 0 && !self.full_rebuild_pending && !self.rebuilding && self.last_error.is_none()
+    }
+}
+
+/// Changes waiting for the next rebuild. Requests that arrive while one is
+/// running land here and are coalesced into the one after it.
+#[derive(Debug, Default)]
+struct PendingWork {
+    documents: HashSet<Uuid>,
+    full: bool,
+}
+
+/// Background task that rebuilds index entries of saved documents.
+///
+/// Saves are debounced: entries are rebuilt once no [`AppEvent::DocumentSaved`]
+/// has arrived for the debounce period, so a burst of saves costs a single
+/// rebuild. Only one rebuild runs at a time.
+pub struct IndexWorker {
+    pending: Arc<Mutex<PendingWork>>,
+    wake: Arc<Notify>,
+    freshness: watch::Receiver<IndexFreshness>,
+    cancel: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+impl IndexWorker {
+    /// Start rebuilding `indexer` entries for the saves `subscriber` receives
+    pub fn spawn(indexer: Arc<dyn DocumentIndexer>, subscriber: EventSubscriber, debounce: Duration) -> Self {
+        let pending = Arc::new(Mutex::new(PendingWork::default()));
+        let wake = Arc::new(Notify::new());
+        let (freshness_tx, freshness) = watch::channel(IndexFreshness::default());
+        let cancel = CancellationToken::new();
+
+        let task = WorkerTask {
+            indexer,
+            subscriber,
+            debounce,
+            pending: pending.clone(),
+            wake: wake.clone(),
+            freshness: freshness_tx,
+            cancel: cancel.clone(),
+        };
+        let handle = tokio::spawn(task.run());
+
+        Self { pending, wake, freshness, cancel, handle }
+    }
+
+    /// Rebuild every document without waiting for the debounce period, for
+    /// when the index is suspected to be out of date. Requests made before
+    /// the rebuild starts are merged into it.
+    pub fn reindex_all(&self) {
+        self.pending.lock().unwrap().full = true;
+        self.wake.notify_one();
+    }
+
+    pub fn freshness(&self) -> IndexFreshness {
+        self.freshness.borrow().clone()
+    }
+
+    /// Changes to [`Self::freshness`], for showing index state as it happens
+    pub fn watch_freshness(&self) -> watch::Receiver<IndexFreshness> {
+        self.freshness.clone()
+    }
+
+    /// Stop the worker, waiting for a running rebuild to be abandoned.
+    /// Pending changes are dropped; the next start should call
+    /// [`Self::reindex_all`].
+    pub async fn shutdown(self) {
+        self.cancel.cancel();
+        if let Err(e) = self.handle.await {
+            log::warn!("Index worker ended abnormally: {e}");
+        }
+    }
+}
+
+struct WorkerTask {
+    indexer: Arc<dyn DocumentIndexer>,
+    subscriber: EventSubscriber,
+    debounce: Duration,
+    pending: Arc<Mutex<PendingWork>>,
+    wake: Arc<Notify>,
+    freshness: watch::Sender<IndexFreshness>,
+    cancel: CancellationToken,
+}
+
+impl WorkerTask {
+    async fn run(mut self) {
+        let mut deadline: Option<Instant> = None;
+        let mut bus_open = true;
+        let mut missed = 0;
+
+        loop {
+            tokio::select! {
+                _ = self.cancel.cancelled() => break,
+                event = self.subscriber.recv(), if bus_open => match event {
+                    // A requested full rebuild isn't held back by saves
+                    Some(event) => if self.record(&event) && !self.pending.lock().unwrap().full {
+                        deadline = Some(Instant::now() + self.debounce);
+                    },
+                    None => {
+                        // No more saves will come, so don't wait for them
+                        bus_open = false;
+                        deadline.get_or_insert_with(Instant::now);
+                    }
+                },
+                _ = self.wake.notified() => deadline = Some(Instant::now()),
+                _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    deadline = None;
+                    if !self.rebuild().await {
+                        break;
+                    }
+                }
+            }
+
+            if self.subscriber.missed() > missed {
+                // The lost events may have been saves of any document
+                log::warn!("Index worker missed events, scheduling a full rebuild");
+                missed = self.subscriber.missed();
+                self.pending.lock().unwrap().full = true;
+                deadline = Some(Instant::now());
+            }
+            self.publish_pending();
+        }
+    }
+
+    /// Add the document of a save to the pending work, returning whether
+    /// `event` was one
+    fn record(&self, event: &AppEvent) -> bool {
+        let AppEvent::DocumentSaved { document_id, .. } = event else {
+            return false;
+        };
+        self.pending.lock().unwrap().documents.insert(*document_id);
+        true
+    }
+
+    /// Rebuild what is pending, returning `false` if cancelled meanwhile
+    async fn rebuild(&mut self) -> bool {
+        // Saves published before the rebuild starts belong in it
+        while let Some(event) = self.subscriber.try_recv() {
+            self.record(&event);
+        }
+        let work = std::mem::take(&mut *self.pending.lock().unwrap());
+        if work.documents.is_empty() && !work.full {
+            return true;
+        }
+        self.freshness.send_modify(|freshness| freshness.rebuilding = true);
+        self.publish_pending();
+
+        let outcome = tokio::select! {
+            _ = self.cancel.cancelled() => return false,
+            outcome = self.reindex(&work) => outcome,
+        };
+
+        if let Err(e) = &outcome {
+            log::warn!("Rebuilding the index failed, retrying with the next change: {e}");
+            let mut pending = self.pending.lock().unwrap();
+            pending.documents.extend(work.documents);
+            pending.full |= work.full;
+        }
+        self.freshness.send_modify(|freshness| {
+            freshness.rebuilding = false;
+            match outcome {
+                Ok(()) => {
+                    freshness.last_indexed = Some(Utc::now());
+                    freshness.last_error = None;
+                }
+                Err(e) => freshness.last_error = Some(e.to_string()),
+            }
+        });
+        true
+    }
+
+    async fn reindex(&self, work: &PendingWork) -> Result<()> {
+        let mut documents: Vec<Uuid> = if work.full {
+            self.indexer.all_documents().await?
+        } else {
+            work.documents.iter().copied().collect()
+        };
+        documents.sort();
+        self.indexer.reindex(&documents).await
+    }
+
+    fn publish_pending(&self) {
+        let pending = self.pending.lock().unwrap();
+        self.freshness.send_if_modified(|freshness| {
+            let changed = freshness.pending != pending.documents.len() || freshness.full_rebuild_pending != pending.full;
+            freshness.pending = pending.documents.len();
+            freshness.full_rebuild_pending = pending.full;
+            changed
+        });
+    }
+}
+
+/// Full-text index of the stored documents, one entry per document and
+/// language
+pub struct DocumentSearchIndex {
+    pool: DatabasePool,
+}
+
+impl DocumentSearchIndex {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Up to `limit` documents containing every word of `query`, best match
+    /// first. Words match as prefixes, so a partly typed word finds results.
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Uuid>> {
+        let pattern: Vec<String> = query.split_whitespace().map(|word| format!("\"{}\"*", word.replace('"', "\"\""))).collect();
+        if pattern.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT document_id FROM (SELECT document_id, rank FROM document_search WHERE document_search MATCH ?1)
+             GROUP BY document_id
+             ORDER BY min(rank)
+             LIMIT ?2",
+        )?;
+        let ids = stmt.query_map(params![pattern.join(" "), i64::try_from(limit).unwrap_or(i64::MAX)], |row| {
+            row.get::<_, String>(0)
+        })?;
+        let mut documents = Vec::new();
+        for id in ids {
+            if let Ok(id) = Uuid::parse_str(&id?) {
+                documents.push(id);
+            }
+        }
+        Ok(documents)
+    }
+}
+
+#[async_trait]
+impl DocumentIndexer for DocumentSearchIndex {
+    async fn reindex(&self, document_ids: &[Uuid]) -> Result<()> {
+        let tx = Transaction::begin(&self.pool).await?;
+        for document_id in document_ids {
+            tx.execute("DELETE FROM document_search WHERE document_id = ?1", params![document_id.to_string()])?;
+            // Deleted documents just leave the index
+            let Some((title, content)) = stored_document(&tx, *document_id)? else {
+                continue;
+            };
+            for (language, text) in content {
+                tx.execute(
+                    "INSERT INTO document_search (document_id, language, title, content) VALUES (?1, ?2, ?3, ?4)",
+                    params![document_id.to_string(), language, title, text],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn all_documents(&self) -> Result<Vec<Uuid>> {
+        // Indexed documents that were deleted since are dropped by the rebuild
+        stored_document_ids(&self.pool.lock().await, "SELECT id FROM documents UNION SELECT document_id FROM document_search")
+    }
+}
+
+/// Keeps the translation memory in line with the stored documents. The
+/// paragraphs of a document in the source language are paired, by
+/// position, with those of each translation that has as many, and the
+/// pairs replace the units of the document's last rebuild.
+pub struct TranslationMemoryIndexer {
+    pool: DatabasePool,
+    translation_memory: TranslationMemoryAdapter,
+    source_language: String,
+}
+
+impl TranslationMemoryIndexer {
+    pub fn new(pool: DatabasePool, translation_memory: TranslationMemoryAdapter, source_language: impl Into<String>) -> Self {
+        Self { pool, translation_memory, source_language: source_language.into() }
+    }
+
+    /// Translation units for the paragraphs of a document
+    fn units(&self, document_id: Uuid, title: &str, content: &HashMap<String, String>) -> Result<Vec<TranslationUnit>> {
+        let Some(source) = content.get(&self.source_language) else {
+            return Ok(Vec::new());
+        };
+        let source = paragraphs(source);
+        let mut units = Vec::new();
+        for (language, text) in content {
+            let target = paragraphs(text);
+            if *language == self.source_language || target.len() != source.len() {
+                continue;
+            }
+            for (source_text, target_text) in source.iter().zip(target) {
+                // Paragraphs still in the source language aren't translations
+                if *source_text == target_text {
+                    continue;
+                }
+                units.push(TranslationUnit::new(
+                    Uuid::nil(),
+                    document_id,
+                    Uuid::new_v4(),
+                    language_of(&self.source_language),
+                    source_text.to_string(),
+                    language_of(language),
+                    target_text.to_string(),
+                    1.0,
+                    Some(title.to_string()),
+                )?);
+            }
+        }
+        Ok(units)
+    }
+}
+
+#[async_trait]
+impl DocumentIndexer for TranslationMemoryIndexer {
+    async fn reindex(&self, document_ids: &[Uuid]) -> Result<()> {
+        let documents = {
+            let conn = self.pool.lock().await;
+            let mut documents = Vec::new();
+            for document_id in document_ids {
+                documents.push((*document_id, stored_document(&conn, *document_id)?));
+            }
+            documents
+        };
+
+        let translation_memory = self.translation_memory.translation_memory();
+        for unit in translation_memory.get_translation_units().await? {
+            if document_ids.contains(&unit.chapter_id) {
+                translation_memory.delete_translation_unit(unit.id).await?;
+            }
+        }
+        let mut units = Vec::new();
+        for (document_id, document) in documents {
+            if let Some((title, content)) = document {
+                units.extend(self.units(document_id, &title, &content)?);
+            }
+        }
+        translation_memory.add_translation_units_batch(units).await?;
+        Ok(())
+    }
+
+    async fn all_documents(&self) -> Result<Vec<Uuid>> {
+        stored_document_ids(&self.pool.lock().await, "SELECT id FROM documents")
+    }
+}
+
+/// Title and content by language of a stored document
+pub(super) fn stored_document(conn: &Connection, document_id: Uuid) -> Result<Option<(String, HashMap<String, String>)>> {
+    let row = conn
+        .query_row("SELECT title, content FROM documents WHERE id = ?1", params![document_id.to_string()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })
+        .optional()?;
+    Ok(row.map(|(title, content)| {
+        let content = content.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default();
+        (title, content)
+    }))
+}
+
+fn stored_document_ids(conn: &Connection, sql: &str) -> Result<Vec<Uuid>> {
+    let mut stmt = conn.prepare(sql)?;
+    let ids = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut documents = Vec::new();
+    for id in ids {
+        if let Ok(id) = Uuid::parse_str(&id?) {
+            documents.push(id);
+        }
+    }
+    Ok(documents)
+}
+
+/// Blank-line separated paragraphs of markdown
+pub(super) fn paragraphs(markdown: &str) -> Vec<&str> {
+    markdown.split("\n\n").map(str::trim).filter(|paragraph| !paragraph.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::services::events::EventBus;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct RecordingIndexer {
+        documents: Vec<Uuid>,
+        rebuilds: Mutex<Vec<Vec<Uuid>>>,
+    }
+
+    #[async_trait]
+    impl DocumentIndexer for RecordingIndexer {
+        async fn reindex(&self, document_ids: &[Uuid]) -> Result<()> {
+            self.rebuilds.lock().unwrap().push(document_ids.to_vec());
+            Ok(())
+        }
+
+        async fn all_documents(&self) -> Result<Vec<Uuid>> {
+            Ok(self.documents.clone())
+        }
+    }
+
+    fn saved(document_id: Uuid) -> AppEvent {
+        AppEvent::DocumentSaved { document_id, path: PathBuf::from(format!("{document_id}.json")) }
+    }
+
+    async fn wait_until_fresh(worker: &IndexWorker) -> IndexFreshness {
+        worker.watch_freshness().wait_for(|f| f.is_fresh() && f.last_indexed.is_some()).await.unwrap().clone()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rapid_saves_trigger_one_rebuild() {
+        let bus = EventBus::default();
+        let indexer = Arc::new(RecordingIndexer::default());
+        let worker = IndexWorker::spawn(indexer.clone(), bus.subscribe(), Duration::from_millis(500));
+
+        let mut documents = vec![Uuid::new_v4(), Uuid::new_v4()];
+        documents.sort();
+        for i in 0..10 {
+            bus.publish(saved(documents[i % 2]));
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(indexer.rebuilds.lock().unwrap().is_empty());
+        assert_eq!(worker.freshness().pending, 2);
+
+        let freshness = wait_until_fresh(&worker).await;
+        assert_eq!(freshness.pending, 0);
+        assert_eq!(*indexer.rebuilds.lock().unwrap(), vec![documents]);
+
+        worker.shutdown().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reindex_all_processes_everything() {
+        let bus = EventBus::default();
+        let mut documents: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        documents.sort();
+        let indexer = Arc::new(RecordingIndexer { documents: documents.clone(), ..Default::default() });
+        let worker = IndexWorker::spawn(indexer.clone(), bus.subscribe(), Duration::from_secs(60));
+
+        bus.publish(saved(documents[0]));
+        worker.reindex_all();
+        worker.reindex_all();
+
+        wait_until_fresh(&worker).await;
+        assert_eq!(*indexer.rebuilds.lock().unwrap(), vec![documents]);
+
+        worker.shutdown().await;
+    }
+
+    /// Insert or replace a stored document
+    async fn store_document(database: &Database, id: Uuid, content: serde_json::Value) {
+        database
+            .pool()
+            .lock()
+            .await
+            .execute(
+                "INSERT OR REPLACE INTO documents (id, title, content, created_at, updated_at) VALUES (?1, 'Glocken', ?2, ?3, ?3)",
+                params![id.to_string(), content.to_string(), Utc::now().to_rfc3339()],
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_index_follows_saved_documents() {
+        let database = Database::in_memory().unwrap();
+        let index = Arc::new(DocumentSearchIndex::new(database.pool()));
+        let bus = EventBus::default();
+        let worker = IndexWorker::spawn(index.clone(), bus.subscribe(), Duration::from_millis(10));
+
+        let (bells, ropes) = (Uuid::new_v4(), Uuid::new_v4());
+        store_document(&database, bells, serde_json::json!({ "en": "Grease the clapper.", "de": "Den Klöppel fetten." })).await;
+        store_document(&database, ropes, serde_json::json!({ "en": "Replace worn clapper ropes." })).await;
+        assert!(index.search("clapper", 10).await.unwrap().is_empty());
+
+        bus.publish(saved(bells));
+        bus.publish(saved(ropes));
+        wait_until_fresh(&worker).await;
+        assert_eq!(index.search("klop", 10).await.unwrap(), [bells]);
+        let mut found = index.search("clap", 10).await.unwrap();
+        found.sort();
+        let mut both = vec![bells, ropes];
+        both.sort();
+        assert_eq!(found, both);
+        assert_eq!(index.search("clapper rope", 10).await.unwrap(), [ropes]);
+        assert!(index.search("  ", 10).await.unwrap().is_empty());
+
+        // Edited and deleted documents
+        store_document(&database, bells, serde_json::json!({ "en": "Oil the bearings." })).await;
+        database.pool().lock().await.execute("DELETE FROM documents WHERE id = ?1", params![ropes.to_string()]).unwrap();
+        let indexed = worker.freshness().last_indexed;
+        worker.reindex_all();
+        worker.watch_freshness().wait_for(|f| f.is_fresh() && f.last_indexed != indexed).await.unwrap();
+        assert!(index.search("clapper", 10).await.unwrap().is_empty());
+        assert_eq!(index.search("bearings", 10).await.unwrap(), [bells]);
+
+        worker.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_translation_memory_pairs_document_paragraphs() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = Database::in_memory().unwrap();
+        let translation_memory = TranslationMemoryAdapter::new(temp_dir.path().to_path_buf()).await.unwrap();
+        let indexer = TranslationMemoryIndexer::new(database.pool(), translation_memory.clone(), "en");
+
+        let document = Uuid::new_v4();
+        store_document(&database, document, serde_json::json!({
+            "en": "Grease the clapper.\n\nCheck the rope.",
+            "de": "Den Klöppel fetten.\n\nDas Seil prüfen.",
+            // Not lined up with the source, and still untranslated
+            "fr": "Graisser le battant.",
+            "it": "Grease the clapper.\n\nCheck the rope.",
+        })).await;
+        indexer.reindex(&indexer.all_documents().await.unwrap()).await.unwrap();
+
+        let pairs = |units: Vec<TranslationUnit>| {
+            let mut pairs: Vec<(String, String)> = units.into_iter().map(|unit| (unit.source_text, unit.target_text)).collect();
+            pairs.sort();
+            pairs
+        };
+        let units = translation_memory.translation_memory().get_translation_units().await.unwrap();
+        assert!(units.iter().all(|unit| unit.chapter_id == document && unit.target_language.code() == "de"));
+        assert_eq!(pairs(units), [
+            ("Check the rope.".to_string(), "Das Seil prüfen.".to_string()),
+            ("Grease the clapper.".to_string(), "Den Klöppel fetten.".to_string()),
+        ]);
+
+        // A rebuild replaces the document's units rather than adding to them
+        store_document(&database, document, serde_json::json!({ "en": "Grease the clapper.", "de": "Den Klöppel schmieren." })).await;
+        indexer.reindex(&[document]).await.unwrap();
+        let units = translation_memory.translation_memory().get_translation_units().await.unwrap();
+        assert_eq!(pairs(units), [("Grease the clapper.".to_string(), "Den Klöppel schmieren.".to_string())]);
+    }
+}
@@ -22,6 +22,31 @@ pub enum TextDirection {
     TopToBottom,
 }
 
+impl TextDirection {
+    /// Horizontal direction of the language with the BCP 47 tag `code`,
+    /// such as `de` or `ar-EG`
+    pub fn for_language(code: &str) -> Self {
+        if tradocflow_translation_memory::is_rtl_code(code) {
+            TextDirection::RightToLeft
+        } else {
+            TextDirection::LeftToRight
+        }
+    }
+
+    pub fn is_rtl(&self) -> bool {
+        *self == TextDirection::RightToLeft
+    }
+
+    /// Value of the CSS `direction` property and the HTML `dir` attribute
+    pub fn as_css(&self) -> &'static str {
+        match self {
+            TextDirection::LeftToRight => "ltr",
+            TextDirection::RightToLeft => "rtl",
+            TextDirection::TopToBottom => "ttb",
+        }
+    }
+}
+
 /// Markdown extensions supported by different languages
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum MarkdownExtension {
@@ -74,6 +99,7 @@ impl LanguageSyntaxService {
         };
         
         service.initialize_default_configs();
+        service.initialize_rtl_configs();
         service.initialize_default_themes();
         service
     }
@@ -213,6 +239,30 @@ impl LanguageSyntaxService {
         });
     }
     
+    /// Initialize configurations of right-to-left languages
+    fn initialize_rtl_configs(&mut self) {
+        for (code, name, font_family) in [
+            ("ar", "العربية", "'Noto Naskh Arabic', 'Segoe UI', Tahoma, sans-serif"),
+            ("he", "עברית", "'Noto Sans Hebrew', 'Segoe UI', Arial, sans-serif"),
+        ] {
+            self.language_configs.insert(code.to_string(), LanguageSyntaxConfig {
+                language_code: code.to_string(),
+                language_name: name.to_string(),
+                text_direction: TextDirection::RightToLeft,
+                font_family: Some(font_family.to_string()),
+                font_size_multiplier: 1.1,
+                line_height_multiplier: 1.7,
+                markdown_extensions: vec![
+                    MarkdownExtension::Tables,
+                    MarkdownExtension::TaskLists,
+                    MarkdownExtension::Strikethrough,
+                    MarkdownExtension::Footnotes,
+                ],
+                special_characters: vec![],
+            });
+        }
+    }
+    
     /// Initialize default syntax themes
     fn initialize_default_themes(&mut self) {
         // Light theme
@@ -250,6 +300,14 @@ impl LanguageSyntaxService {
     pub fn get_language_config(&self, language_code: &str) -> Option<&LanguageSyntaxConfig> {
         self.language_configs.get(language_code)
     }
+
+    /// Direction the editor lays out text of a language in, also for
+    /// languages without a configuration
+    pub fn text_direction(&self, language_code: &str) -> TextDirection {
+        self.get_language_config(language_code)
+            .map(|config| config.text_direction.clone())
+            .unwrap_or_else(|| TextDirection::for_language(language_code))
+    }
     
     /// Get syntax theme by name
     pub fn get_syntax_theme(&self, theme_name: &str) -> Option<&SyntaxTheme> {
@@ -282,11 +340,7 @@ impl LanguageSyntaxService {
         let theme = self.get_syntax_theme(theme_name);
         
         if let (Some(config), Some(theme)) = (config, theme) {
-            let direction = match config.text_direction {
-                TextDirection::LeftToRight => "ltr",
-                TextDirection::RightToLeft => "rtl",
-                TextDirection::TopToBottom => "ttb",
-            };
+            let direction = config.text_direction.as_css();
             
             let font_family = config.font_family.as_deref().unwrap_or("monospace");
             
@@ -315,11 +369,13 @@ impl LanguageSyntaxService {
                     color: {};
                     padding: 0.125rem 0.25rem;
                     border-radius: 0.25rem;
+                    direction: ltr;
+                    unicode-bidi: isolate;
                 }}
                 
                 .editor-{} blockquote {{
                     background-color: {};
-                    border-left: 4px solid {};
+                    border-inline-start: 4px solid {};
                     padding: 0.5rem 1rem;
                     margin: 0.5rem 0;
                 }}
@@ -429,6 +485,21 @@ mod tests {
         assert!(css_dark.contains("background-color: #1a1a1a"));
     }
     
+    #[test]
+    fn test_rtl_language_configuration() {
+        let service = LanguageSyntaxService::new();
+
+        assert_eq!(service.text_direction("ar"), TextDirection::RightToLeft);
+        assert_eq!(service.text_direction("he"), TextDirection::RightToLeft);
+        assert_eq!(service.text_direction("fa"), TextDirection::RightToLeft);
+        assert_eq!(service.text_direction("en"), TextDirection::LeftToRight);
+        assert_eq!(service.text_direction("ja"), TextDirection::LeftToRight);
+
+        let css = service.generate_language_css("ar", "light");
+        assert!(css.contains("direction: rtl"));
+        assert!(css.contains("unicode-bidi: isolate"));
+    }
+    
     #[test]
     fn test_markdown_extensions() {
         let service = LanguageSyntaxService::new();
@@ -0,0 +1,428 @@
+use crate::services::markdown_ops::Selection;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Remote links checked at the same time by [`check_http`]
+const HTTP_CHECK_CONCURRENCY: usize = 8;
+const HTTP_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkKind {
+    /// `[text](url "title")`
+    Inline,
+    /// `[text][label]` or `[label]`, resolved through a `[label]: url` definition
+    Reference,
+}
+
+/// A link found in a markdown document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkRef {
+    pub kind: LinkKind,
+    /// Whether this is an image (`![alt](src)`)
+    pub image: bool,
+    pub text: String,
+    /// Destination without angle brackets; empty for a reference whose
+    /// label is never defined
+    pub url: String,
+    pub title: Option<String>,
+    /// Label of a reference link
+    pub label: Option<String>,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, counted in characters
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkIssueKind {
+    /// The URL can't be a valid link, like `http:/example.com`
+    Malformed,
+    /// A relative or `file://` link points to nothing on disk
+    MissingFile,
+    /// A `[text][label]` link whose label has no definition
+    UndefinedReference,
+    /// The server answered with an error status
+    HttpStatus(u16),
+    /// The server could not be reached
+    Unreachable,
+}
+
+/// A problem with one link
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkIssue {
+    pub link: LinkRef,
+    pub kind: LinkIssueKind,
+    pub message: String,
+}
+
+/// Turn the selection into a markdown link to `url`. An empty selection
+/// uses the URL as link text. The returned selection covers the link text.
+pub fn insert_link(text: &str, selection: Selection, url: &str, title: Option<&str>) -> (String, Selection) {
+    let selection = selection.clamp_to(text);
+    let selected = &text[selection.start..selection.end];
+    let url = url.trim();
+    let label = escape_link_text(if selected.is_empty() { url } else { selected });
+    let title = title
+        .filter(|title| !title.is_empty())
+        .map(|title| format!(" \"{}\"", title.replace('"', "\\\"")))
+        .unwrap_or_default();
+
+    let link = format!("[{label}]({}{title})", format_destination(url));
+    let new_text = format!("{}{link}{}", &text[..selection.start], &text[selection.end..]);
+    let label_start = selection.start + 1;
+    (new_text, Selection::new(label_start, label_start + label.len()))
+}
+
+fn escape_link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+/// Destinations with spaces or parentheses need angle brackets
+fn format_destination(url: &str) -> String {
+    if url.contains([' ', '(', ')', '<', '>']) {
+        format!("<{}>", url.replace('<', "%3C").replace('>', "%3E"))
+    } else {
+        url.to_string()
+    }
+}
+
+fn inline_link_regex() -> &'static Regex {
+    static INLINE: OnceLock<Regex> = OnceLock::new();
+    INLINE.get_or_init(|| {
+        Regex::new(r#"(!?)\[((?:\\.|[^\]\\])*)\]\(\s*(<[^>]*>|[^\s()]*(?:\([^\s()]*\)[^\s()]*)*)(?:\s+(?:"((?:\\.|[^"\\])*)"|'([^']*)'))?\s*\)"#)
+            .expect("valid inline link regex")
+    })
+}
+
+fn reference_link_regex() -> &'static Regex {
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    REFERENCE.get_or_init(|| {
+        Regex::new(r"(!?)\[((?:\\.|[^\]\\])+)\](?:\[((?:\\.|[^\]\\])*)\])?").expect("valid reference link regex")
+    })
+}
+
+fn definition_regex() -> &'static Regex {
+    static DEFINITION: OnceLock<Regex> = OnceLock::new();
+    DEFINITION.get_or_init(|| {
+        Regex::new(r#"^ {0,3}\[((?:\\.|[^\]\\])+)\]:\s*(<[^>]*>|\S+)(?:\s+(?:"([^"]*)"|'([^']*)'|\(([^)]*)\)))?\s*$"#)
+            .expect("valid link definition regex")
+    })
+}
+
+fn code_span_regex() -> &'static Regex {
+    static CODE_SPAN: OnceLock<Regex> = OnceLock::new();
+    CODE_SPAN.get_or_init(|| Regex::new(r"`[^`]*`").expect("valid code span regex"))
+}
+
+/// Reference labels match case-insensitively and ignore repeated whitespace
+fn normalize_label(label: &str) -> String {
+    label.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\[", "[").replace("\\]", "]").replace("\\\"", "\"")
+}
+
+fn strip_angle_brackets(url: &str) -> &str {
+    url.strip_prefix('<').and_then(|url| url.strip_suffix('>')).unwrap_or(url)
+}
+
+fn column_of(line: &str, byte_offset: usize) -> usize {
+    line[..byte_offset].chars().count() + 1
+}
+
+/// Lines outside fenced code blocks, with their 1-based numbers
+fn prose_lines(markdown: &str) -> Vec<(usize, &str)> {
+    let mut lines = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (index, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(marker) if trimmed.starts_with(marker) => fence = None,
+            Some(_) => {}
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None => lines.push((index + 1, line)),
+        }
+    }
+    lines
+}
+
+/// Find the inline and reference-style links of a document, in order.
+/// Links in code blocks and code spans are skipped, as are `[text]`
+/// brackets that don't match a definition.
+pub fn extract_links(markdown: &str) -> Vec<LinkRef> {
+    let lines = prose_lines(markdown);
+
+    let mut definitions: HashMap<String, (String, Option<String>)> = HashMap::new();
+    let mut prose = Vec::new();
+    for (number, line) in lines {
+        match definition_regex().captures(line) {
+            Some(captures) => {
+                let title = captures.get(3).or_else(|| captures.get(4)).or_else(|| captures.get(5));
+                definitions
+                    .entry(normalize_label(&captures[1]))
+                    .or_insert_with(|| (strip_angle_brackets(&captures[2]).to_string(), title.map(|t| t.as_str().to_string())));
+            }
+            None => prose.push((number, line)),
+        }
+    }
+
+    let mut links = Vec::new();
+    for (number, line) in prose {
+        // Blank out code spans without moving byte offsets
+        let mut masked = line.to_string();
+        for span in code_span_regex().find_iter(line) {
+            masked.replace_range(span.range(), &" ".repeat(span.len()));
+        }
+
+        let mut inline_ranges = Vec::new();
+        for captures in inline_link_regex().captures_iter(&masked) {
+            let whole = captures.get(0).expect("whole match");
+            let text = captures.get(2).map_or("", |m| &line[m.range()]);
+            let title = captures.get(4).or_else(|| captures.get(5)).map(|m| unescape(m.as_str()));
+            links.push(LinkRef {
+                kind: LinkKind::Inline,
+                image: !captures[1].is_empty(),
+                text: unescape(text),
+                url: strip_angle_brackets(&captures[3]).to_string(),
+                title,
+                label: None,
+                line: number,
+                column: column_of(line, whole.start()),
+            });
+            inline_ranges.push(whole.range());
+        }
+
+        for captures in reference_link_regex().captures_iter(&masked) {
+            let whole = captures.get(0).expect("whole match");
+            if inline_ranges.iter().any(|range| range.start < whole.end() && whole.start() < range.end) {
+                continue;
+            }
+            let text = &line[captures.get(2).expect("link text").range()];
+            let explicit_label = captures.get(3).map(|m| &line[m.range()]).filter(|label| !label.is_empty());
+            let label = explicit_label.unwrap_or(text);
+            let definition = definitions.get(&normalize_label(label));
+
+            // A lone `[text]` without a definition is plain text
+            if definition.is_none() && captures.get(3).is_none() {
+                continue;
+            }
+            let (url, title) = definition.cloned().unwrap_or_default();
+            links.push(LinkRef {
+                kind: LinkKind::Reference,
+                image: !captures[1].is_empty(),
+                text: unescape(text),
+                url,
+                title,
+                label: Some(label.to_string()),
+                line: number,
+                column: column_of(line, whole.start()),
+            });
+        }
+    }
+
+    links.sort_by_key(|link| (link.line, link.column));
+    links
+}
+
+fn scheme_of(url: &str) -> Option<&str> {
+    let (scheme, _) = url.split_once(':')?;
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    // A single letter is a Windows drive, not a scheme
+    (valid && scheme.len() > 1).then_some(scheme)
+}
+
+/// Why a URL is obviously malformed, if it is
+fn malformed_reason(url: &str) -> Option<String> {
+    if url.is_empty() {
+        return Some("Link has no destination".to_string());
+    }
+
+    match scheme_of(url).map(|scheme| scheme.to_ascii_lowercase()) {
+        Some(scheme) if matches!(scheme.as_str(), "http" | "https" | "ftp") => {
+            let rest = &url[scheme.len() + 1..];
+            let Some(after_slashes) = rest.strip_prefix("//") else {
+                return Some(format!("{url} is missing // after {scheme}:"));
+            };
+            let host = after_slashes.split(['/', '?', '#']).next().unwrap_or("");
+            if host.is_empty() {
+                Some(format!("{url} has no host"))
+            } else if url.chars().any(char::is_whitespace) {
+                Some(format!("{url} contains whitespace"))
+            } else {
+                None
+            }
+        }
+        Some(scheme) if scheme == "mailto" && !url.contains('@') => Some(format!("{url} is not an email address")),
+        Some(_) => None,
+        None => {
+            let lower = url.to_ascii_lowercase();
+            if lower.starts_with("www.") || lower.starts_with("http//") || lower.starts_with("https//") {
+                Some(format!("{url} looks like a web address without a scheme"))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// The file a local link points to, without fragment or query
+fn local_path(url: &str, base_dir: &Path) -> Option<PathBuf> {
+    let path = match scheme_of(url) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("file") => url[scheme.len() + 1..].trim_start_matches("//"),
+        Some(_) => return None,
+        None => url,
+    };
+    let path = path.split(['#', '?']).next().unwrap_or("");
+    if path.is_empty() {
+        // Links to an anchor in the same document
+        return None;
+    }
+
+    let path = PathBuf::from(path.replace("%20", " "));
+    Some(if path.is_absolute() { path } else { base_dir.join(path) })
+}
+
+/// Check links without any network access: malformed URLs, undefined
+/// references, and relative or `file://` links that don't resolve from
+/// `base_dir`
+pub fn validate_links(links: &[LinkRef], base_dir: &Path) -> Vec<LinkIssue> {
+    let mut issues = Vec::new();
+    for link in links {
+        let issue = |kind, message| LinkIssue { link: link.clone(), kind, message };
+
+        if link.kind == LinkKind::Reference && link.url.is_empty() {
+            let label = link.label.as_deref().unwrap_or(&link.text);
+            issues.push(issue(LinkIssueKind::UndefinedReference, format!("Link reference [{label}] is not defined")));
+            continue;
+        }
+        if let Some(reason) = malformed_reason(&link.url) {
+            issues.push(issue(LinkIssueKind::Malformed, reason));
+            continue;
+        }
+        if let Some(path) = local_path(&link.url, base_dir) {
+            if !path.exists() {
+                issues.push(issue(LinkIssueKind::MissingFile, format!("{} does not exist", path.display())));
+            }
+        }
+    }
+    issues
+}
+
+/// Request every well-formed `http` and `https` link and report error
+/// statuses and unreachable hosts. Servers that reject `HEAD` are asked
+/// again with `GET`.
+pub async fn check_http(links: &[LinkRef]) -> Vec<LinkIssue> {
+    let client = match reqwest::Client::builder().timeout(HTTP_CHECK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("HTTP link check skipped: {e}");
+            return Vec::new();
+        }
+    };
+
+    let remote = links.iter().filter(|link| {
+        let scheme = scheme_of(&link.url).map(|scheme| scheme.to_ascii_lowercase());
+        matches!(scheme.as_deref(), Some("http" | "https")) && malformed_reason(&link.url).is_none()
+    });
+
+    stream::iter(remote)
+        .map(|link| {
+            let client = &client;
+            async move {
+                let mut response = client.head(&link.url).send().await;
+                if matches!(&response, Ok(r) if r.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED) {
+                    response = client.get(&link.url).send().await;
+                }
+
+                let issue = |kind, message| Some(LinkIssue { link: link.clone(), kind, message });
+                match response {
+                    Ok(response) if response.status().is_client_error() || response.status().is_server_error() => {
+                        let status = response.status();
+                        issue(LinkIssueKind::HttpStatus(status.as_u16()), format!("{} answered {status}", link.url))
+                    }
+                    Ok(_) => None,
+                    Err(e) => issue(LinkIssueKind::Unreachable, format!("{} could not be reached: {e}", link.url)),
+                }
+            }
+        })
+        .buffered(HTTP_CHECK_CONCURRENCY)
+        .filter_map(|issue| async move { issue })
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_insert_link_over_selection() {
+        let text = "See the maintenance guide for details.";
+        let (linked, selection) = insert_link(text, Selection::new(8, 25), "docs/maintenance guide.md", Some("Wartung"));
+        assert_eq!(linked, "See the [maintenance guide](<docs/maintenance guide.md> \"Wartung\") for details.");
+        assert_eq!(&linked[selection.start..selection.end], "maintenance guide");
+
+        let (linked, _) = insert_link("Visit ", Selection::cursor(6), "https://example.com", None);
+        assert_eq!(linked, "Visit [https://example.com](https://example.com)");
+    }
+
+    #[test]
+    fn test_extract_inline_and_reference_links() {
+        let markdown = "# Guide\n\
+            Read the [manual](manual.md \"Manual\") and ![diagram](img/valve.png).\n\
+            Spare parts are listed in [the catalog][parts] and [Support].\n\
+            A task: [ ] and `[code](not-a-link.md)`.\n\
+            ```\n[fenced](ignored.md)\n```\n\
+            [parts]: https://example.com/parts \"Catalog\"\n\
+            [support]: <support page.md>\n";
+
+        let links = extract_links(markdown);
+        let summary: Vec<(LinkKind, bool, &str, &str, usize)> = links
+            .iter()
+            .map(|link| (link.kind, link.image, link.text.as_str(), link.url.as_str(), link.line))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (LinkKind::Inline, false, "manual", "manual.md", 2),
+                (LinkKind::Inline, true, "diagram", "img/valve.png", 2),
+                (LinkKind::Reference, false, "the catalog", "https://example.com/parts", 3),
+                (LinkKind::Reference, false, "Support", "support page.md", 3),
+            ]
+        );
+        assert_eq!(links[0].title.as_deref(), Some("Manual"));
+        assert_eq!(links[2].label.as_deref(), Some("parts"));
+        assert_eq!(links[2].column, 27);
+    }
+
+    #[test]
+    fn test_validate_flags_broken_relative_path() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("manual.md"), "# Manual").unwrap();
+
+        let markdown = "[ok](manual.md#safety) [broken](missing/setup.md) [web](http:/example.com) \
+            [anchor](#top) [undefined][nowhere] [remote](https://example.com)";
+        let issues = validate_links(&extract_links(markdown), dir.path());
+
+        let kinds: Vec<(&str, &LinkIssueKind)> = issues.iter().map(|issue| (issue.link.text.as_str(), &issue.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("broken", &LinkIssueKind::MissingFile),
+                ("web", &LinkIssueKind::Malformed),
+                ("undefined", &LinkIssueKind::UndefinedReference),
+            ]
+        );
+        assert!(issues[0].message.contains("setup.md"));
+    }
+}
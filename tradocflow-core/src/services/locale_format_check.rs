@@ -0,0 +1,307 @@
+use crate::i18n::Language;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FormatIssueKind {
+    Number,
+    Date,
+}
+
+/// A number or date written in a format the document's locale doesn't use
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormatIssue {
+    pub kind: FormatIssueKind,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, counted in characters
+    pub column: usize,
+    pub found: String,
+    /// The value rewritten in the locale's format, or the locale's date
+    /// pattern when the day and month can't be told apart
+    pub expected: String,
+    pub message: String,
+}
+
+/// Number and date conventions of a language
+struct LocaleFormat {
+    decimal: char,
+    /// Accepted group separators; the first is the one suggested
+    thousands: &'static [char],
+    date_separator: char,
+    day_first: bool,
+    date_pattern: &'static str,
+}
+
+fn locale_format(lang: &Language) -> LocaleFormat {
+    match lang {
+        Language::English => LocaleFormat {
+            decimal: '.',
+            thousands: &[','],
+            date_separator: '/',
+            day_first: false,
+            date_pattern: "MM/DD/YYYY",
+        },
+        Language::German => LocaleFormat {
+            decimal: ',',
+            thousands: &['.'],
+            date_separator: '.',
+            day_first: true,
+            date_pattern: "DD.MM.YYYY",
+        },
+        Language::French => LocaleFormat {
+            decimal: ',',
+            thousands: &['\u{202F}', '\u{A0}'],
+            date_separator: '/',
+            day_first: true,
+            date_pattern: "DD/MM/YYYY",
+        },
+        Language::Spanish | Language::Italian => LocaleFormat {
+            decimal: ',',
+            thousands: &['.'],
+            date_separator: '/',
+            day_first: true,
+            date_pattern: "DD/MM/YYYY",
+        },
+        Language::Dutch => LocaleFormat {
+            decimal: ',',
+            thousands: &['.'],
+            date_separator: '-',
+            day_first: true,
+            date_pattern: "DD-MM-YYYY",
+        },
+    }
+}
+
+/// Words after which a dotted number is a version or section reference
+const REFERENCE_WORDS: &[&str] = &[
+    "version", "versión", "versione", "versie", "release", "section", "sección", "sezione", "sectie",
+    "abschnitt", "chapter", "kapitel", "chapitre", "capítulo", "capitolo", "hoofdstuk",
+];
+
+fn number_regex() -> &'static Regex {
+    static NUMBER: OnceLock<Regex> = OnceLock::new();
+    NUMBER.get_or_init(|| Regex::new(r"\d+(?:[.,\x{A0}\x{202F}]\d+)+").expect("valid number regex"))
+}
+
+fn date_regex() -> &'static Regex {
+    static DATE: OnceLock<Regex> = OnceLock::new();
+    DATE.get_or_init(|| {
+        Regex::new(r"\b(?:(\d{4})-(\d{2})-(\d{2})|(\d{1,2})([./-])(\d{1,2})([./-])(\d{4}))\b").expect("valid date regex")
+    })
+}
+
+/// Flag numbers and dates in `text` that don't follow the conventions of
+/// `lang`. Fenced code, inline code spans, version numbers, and section
+/// references are ignored, as are numbers whose format is ambiguous, such as
+/// `1,000`.
+pub fn check_locale_formatting(text: &str, lang: Language) -> Vec<FormatIssue> {
+    let format = locale_format(&lang);
+    let mut issues = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m));
+        match (fence, marker) {
+            (None, Some(marker)) => {
+                fence = Some(marker);
+                continue;
+            }
+            (Some(open), Some(marker)) if open == marker => {
+                fence = None;
+                continue;
+            }
+            (Some(_), _) => continue,
+            _ => {}
+        }
+
+        let code_spans = code_spans(line);
+        let skipped = |range: &Range<usize>| code_spans.iter().any(|span| span.start < range.end && range.start < span.end);
+        let issue = |kind, start: usize, found: &str, expected: String, message: String| FormatIssue {
+            kind,
+            line: index + 1,
+            column: line[..start].chars().count() + 1,
+            found: found.to_string(),
+            expected,
+            message,
+        };
+
+        let mut dates = Vec::new();
+        for captures in date_regex().captures_iter(line) {
+            let whole = captures.get(0).expect("whole match");
+            if skipped(&whole.range()) || is_reference(line, whole.start()) {
+                continue;
+            }
+            dates.push(whole.range());
+            if let Some((expected, message)) = check_date(&captures, &format) {
+                issues.push(issue(FormatIssueKind::Date, whole.start(), whole.as_str(), expected, message));
+            }
+        }
+
+        for found in number_regex().find_iter(line) {
+            let range = found.range();
+            let inside_date = dates.iter().any(|d| d.start < range.end && range.start < d.end);
+            let attached = line[..range.start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | ','));
+            if inside_date || attached || skipped(&range) || is_reference(line, range.start) {
+                continue;
+            }
+            if let Some((expected, message)) = check_number(found.as_str(), &format) {
+                issues.push(issue(FormatIssueKind::Number, range.start, found.as_str(), expected, message));
+            }
+        }
+    }
+
+    issues.sort_by_key(|issue| (issue.line, issue.column));
+    issues
+}
+
+/// Byte ranges of inline code spans, including the backticks
+fn code_spans(line: &str) -> Vec<Range<usize>> {
+    let ticks: Vec<usize> = line.match_indices('`').map(|(i, _)| i).collect();
+    ticks.chunks_exact(2).map(|pair| pair[0]..pair[1] + 1).collect()
+}
+
+fn is_reference(line: &str, start: usize) -> bool {
+    let before = line[..start].trim_end().trim_end_matches(':');
+    let word = before.rsplit(|c: char| !c.is_alphanumeric()).next().unwrap_or("");
+    REFERENCE_WORDS.contains(&word.to_lowercase().as_str())
+}
+
+/// Check a number's separators; returns the suggested rewrite and a message
+fn check_number(number: &str, format: &LocaleFormat) -> Option<(String, String)> {
+    let separators: Vec<char> = number.chars().filter(|c| !c.is_ascii_digit()).collect();
+    let groups: Vec<&str> = number.split(|c: char| !c.is_ascii_digit()).collect();
+    let last = *separators.last()?;
+
+    let (thousands, decimal) = if separators.iter().all(|&s| s == last) {
+        if separators.len() == 1 {
+            // `1,000` is a thousands group in one locale and a decimal in another
+            if groups[1].len() == 3 {
+                return None;
+            }
+            (None, Some(last))
+        } else {
+            (Some(last), None)
+        }
+    } else {
+        let first = separators[0];
+        if separators[..separators.len() - 1].iter().any(|&s| s != first) {
+            return None;
+        }
+        (Some(first), Some(last))
+    };
+
+    // Digit groups must look like thousands, otherwise this is something like
+    // an IP address or a version number
+    let grouped = if decimal.is_some() { &groups[..groups.len() - 1] } else { &groups[..] };
+    if thousands.is_some() && (grouped[0].len() > 3 || grouped[1..].iter().any(|g| g.len() != 3)) {
+        return None;
+    }
+
+    let wrong_thousands = thousands.filter(|s| !format.thousands.contains(s));
+    let wrong_decimal = decimal.filter(|&s| s != format.decimal);
+    if wrong_thousands.is_none() && wrong_decimal.is_none() {
+        return None;
+    }
+
+    let mut expected = grouped.join(&format.thousands[0].to_string());
+    if decimal.is_some() {
+        expected.push(format.decimal);
+        expected.push_str(groups[groups.len() - 1]);
+    }
+    let message = match wrong_decimal {
+        Some(s) => format!("Decimal separator '{s}' should be '{}'", format.decimal),
+        None => format!("Thousands separator '{}' is not used in this language", wrong_thousands.unwrap_or_default()),
+    };
+    Some((expected, message))
+}
+
+/// Check a date's field order and separator
+fn check_date(captures: &regex::Captures, format: &LocaleFormat) -> Option<(String, String)> {
+    // ISO 8601 dates are acceptable in every language
+    if captures.get(1).is_some() {
+        return None;
+    }
+
+    let field = |i: usize| captures.get(i).map_or("", |m| m.as_str());
+    let (first, separator, second, year) = (field(4), field(5), field(6), field(8));
+    if field(7) != separator {
+        return None;
+    }
+    let (a, b): (u32, u32) = (first.parse().ok()?, second.parse().ok()?);
+
+    // Work out which field is the day, if the values make it unambiguous
+    let day_first = match (a > 12, b > 12) {
+        (true, true) => return None,
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        (false, false) if separator != "/" => Some(true),
+        (false, false) => None,
+    };
+    if a == 0 || b == 0 || a.max(b) > 31 {
+        return None;
+    }
+
+    let separator_ok = separator.starts_with(format.date_separator);
+    let order_ok = day_first.is_none_or(|day_first| day_first == format.day_first);
+    if separator_ok && order_ok {
+        return None;
+    }
+
+    let expected = match day_first {
+        Some(day_first) => {
+            let (day, month) = if day_first { (a, b) } else { (b, a) };
+            let (x, y) = if format.day_first { (day, month) } else { (month, day) };
+            format!("{x:02}{sep}{y:02}{sep}{year}", sep = format.date_separator)
+        }
+        None => format.date_pattern.to_string(),
+    };
+    Some((expected, format!("Dates in this language are written {}", format.date_pattern)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_us_number_in_german_document() {
+        let issues = check_locale_formatting("Die Anlage wiegt 1,000.5 kg und kostet 2.499,00 EUR.", Language::German);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, FormatIssueKind::Number);
+        assert_eq!(issues[0].found, "1,000.5");
+        assert_eq!(issues[0].expected, "1.000,5");
+        assert_eq!(issues[0].column, 18);
+    }
+
+    #[test]
+    fn test_version_strings_are_ignored() {
+        let text = "Ab Version 2.5 und v3.1.4 gilt:\n\n`timeout = 2.5`\n\n```\nlimit = 1,000.5\n```\n";
+        assert!(check_locale_formatting(text, Language::German).is_empty());
+        assert!(check_locale_formatting("Firmware 1.2.10 is required.", Language::German).is_empty());
+    }
+
+    #[test]
+    fn test_ambiguous_numbers_are_skipped() {
+        assert!(check_locale_formatting("Up to 1,000 users", Language::German).is_empty());
+        assert_eq!(check_locale_formatting("Bis 3.5 m", Language::German)[0].expected, "3,5");
+    }
+
+    #[test]
+    fn test_date_order_and_separator() {
+        let issues = check_locale_formatting("Stand: 03/14/2026, geprüft 2026-03-14", Language::German);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, FormatIssueKind::Date);
+        assert_eq!(issues[0].expected, "14.03.2026");
+
+        let issues = check_locale_formatting("Released 14.03.2026", Language::English);
+        assert_eq!(issues[0].expected, "03/14/2026");
+        assert_eq!(check_locale_formatting("Stand 03/04/2026", Language::German)[0].expected, "DD.MM.YYYY");
+        assert!(check_locale_formatting("Publié le 03/04/2026", Language::French).is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+use super::atomic_file::{write_atomic_blocking, write_atomic_from, SaveProgress};
+use crate::{Document, Manual, Result, TradocumentError};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+/// The manual itself, at the root of a manual directory
+pub const MANUAL_FILE: &str = "manual.json";
+/// Section documents, one `<document id>.json` each
+pub const DOCUMENTS_DIR: &str = "documents";
+
+/// Id of the document stored in a file of [`DOCUMENTS_DIR`], or `None` for
+/// files that aren't documents
+pub(crate) fn document_id(file_name: &str) -> Option<Uuid> {
+    file_name.strip_suffix(".json").and_then(|stem| Uuid::parse_str(stem).ok())
+}
+
+/// Load a manual and its section documents from a directory laid out as
+/// `manual.json` plus `documents/<document id>.json`
+pub fn load_manual_dir(dir: &Path) -> Result<(Manual, HashMap<Uuid, Document>)> {
+    let manual_path = dir.join(MANUAL_FILE);
+    let manual: Manual = serde_json::from_str(&fs::read_to_string(&manual_path).map_err(|e| {
+        TradocumentError::FileError(format!("Could not read {}: {e}", manual_path.display()))
+    })?)?;
+
+    let mut documents = HashMap::new();
+    let documents_dir = dir.join(DOCUMENTS_DIR);
+    if documents_dir.is_dir() {
+        for entry in fs::read_dir(&documents_dir)? {
+            let path = entry?.path();
+            let Some(id) = path.file_name().and_then(|name| name.to_str()).and_then(document_id) else {
+                log::warn!("Skipping {}: the file name is not a document id", path.display());
+                continue;
+            };
+            documents.insert(id, serde_json::from_str(&fs::read_to_string(&path)?)?);
+        }
+    }
+
+    Ok((manual, documents))
+}
+
+/// Write a manual and its documents in the layout [`load_manual_dir`] reads.
+/// Each file is replaced atomically, so a failed save leaves it as it was.
+pub fn save_manual_dir(dir: &Path, manual: &Manual, documents: &HashMap<Uuid, Document>) -> Result<()> {
+    let files = manual_files(dir, manual, documents)?;
+    fs::create_dir_all(dir.join(DOCUMENTS_DIR))?;
+    for (path, contents) in files {
+        write_atomic_blocking(&path, &contents)?;
+    }
+    Ok(())
+}
+
+/// [`save_manual_dir`] with async I/O, so large manuals save without
+/// blocking the caller's thread. `progress` follows the bytes written over
+/// all the files.
+pub async fn save_manual_dir_async(
+    dir: &Path,
+    manual: &Manual,
+    documents: &HashMap<Uuid, Document>,
+    progress: Option<&watch::Sender<SaveProgress>>,
+) -> Result<()> {
+    // Serialized up front so the size of the whole save is known
+    let files = manual_files(dir, manual, documents)?;
+    let total = files.iter().map(|(_, contents)| contents.len() as u64).sum();
+    tokio::fs::create_dir_all(dir.join(DOCUMENTS_DIR)).await?;
+    let mut saved = 0;
+    for (path, contents) in files {
+        write_atomic_from(&path, contents.as_slice(), |written| {
+            if let Some(progress) = progress {
+                progress.send_replace(SaveProgress { written: saved + written, total });
+            }
+        })
+        .await?;
+        saved += contents.len() as u64;
+    }
+    Ok(())
+}
+
+/// Path and JSON of each file of a manual directory
+fn manual_files(dir: &Path, manual: &Manual, documents: &HashMap<Uuid, Document>) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let documents_dir = dir.join(DOCUMENTS_DIR);
+    let mut files = vec![(dir.join(MANUAL_FILE), serde_json::to_vec_pretty(manual)?)];
+    for (id, document) in documents {
+        files.push((documents_dir.join(format!("{id}.json")), serde_json::to_vec_pretty(document)?));
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::templates::scaffold;
+    use crate::{DocumentMetadata, ManualTemplate};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manual_dir_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manual = scaffold(&ManualTemplate::UserGuide, &["en".to_string()]);
+        let document_id = Uuid::new_v4();
+        manual.sections[0].document_id = Some(document_id);
+        let documents = HashMap::from([(
+            document_id,
+            Document {
+                title: "Introduction".to_string(),
+                content: HashMap::from([("en".to_string(), "# Introduction\n".to_string())]),
+                metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+            },
+        )]);
+
+        save_manual_dir(temp_dir.path(), &manual, &documents).unwrap();
+        fs::write(temp_dir.path().join(DOCUMENTS_DIR).join("notes.json"), "{}").unwrap();
+
+        let (loaded, loaded_documents) = load_manual_dir(temp_dir.path()).unwrap();
+        assert_eq!(loaded.id, manual.id);
+        assert_eq!(loaded.sections.len(), manual.sections.len());
+        assert_eq!(loaded_documents.len(), 1);
+        assert_eq!(loaded_documents[&document_id].content["en"], "# Introduction\n");
+
+        assert!(load_manual_dir(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_large_manual_saves_async_with_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manual = scaffold(&ManualTemplate::UserGuide, &["en".to_string()]);
+        let paragraph = "Check the clapper, the rope guide and the bearings before each season.\n\n";
+        let mut documents = HashMap::new();
+        for section in manual.sections.iter_mut() {
+            let document_id = Uuid::new_v4();
+            section.document_id = Some(document_id);
+            let document = Document {
+                title: section.title.clone(),
+                content: HashMap::from([("en".to_string(), paragraph.repeat(20_000))]),
+                metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+            };
+            documents.insert(document_id, document);
+        }
+
+        let (sender, receiver) = watch::channel(SaveProgress::default());
+        save_manual_dir_async(temp_dir.path(), &manual, &documents, Some(&sender)).await.unwrap();
+
+        let progress = *receiver.borrow();
+        assert!(progress.total > 1_000_000 * documents.len() as u64);
+        assert_eq!(progress.written, progress.total);
+        let (loaded, loaded_documents) = load_manual_dir(temp_dir.path()).unwrap();
+        assert_eq!(loaded.id, manual.id);
+        assert_eq!(loaded_documents.len(), documents.len());
+        assert!(documents.iter().all(|(id, document)| loaded_documents[id].content == document.content));
+        // Only the saved files are left, no temporary ones
+        assert_eq!(fs::read_dir(temp_dir.path().join(DOCUMENTS_DIR)).unwrap().count(), documents.len());
+    }
+}
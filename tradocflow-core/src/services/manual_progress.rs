@@ -0,0 +1,292 @@
+use crate::database::DatabasePool;
+use crate::services::indexing::{paragraphs, stored_document};
+use crate::services::TranslationMemoryAdapter;
+use crate::{Manual, ManualSection};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Translation state of one source segment in a target language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentState {
+    Untranslated,
+    Draft,
+    Translated,
+}
+
+/// Source of per-document segment states, such as the translation memory
+#[async_trait]
+pub trait SegmentStore: Sync {
+    /// States of the document's source segments in `language`, or `None` if
+    /// the store knows nothing about the document
+    async fn document_segments(&self, document_id: Uuid, language: &str) -> Option<Vec<SegmentState>>;
+}
+
+/// Stored documents checked against the translation memory
+///
+/// The segments are the paragraphs of a document's source text, and one is
+/// translated once the memory holds a non-empty translation of it filed
+/// under the document, as the [`TranslationMemoryIndexer`] files them.
+///
+/// [`TranslationMemoryIndexer`]: crate::services::TranslationMemoryIndexer
+pub struct TranslationMemorySegments {
+    pool: DatabasePool,
+    translation_memory: TranslationMemoryAdapter,
+    source_language: String,
+}
+
+impl TranslationMemorySegments {
+    pub fn new(pool: DatabasePool, translation_memory: TranslationMemoryAdapter, source_language: impl Into<String>) -> Self {
+        Self { pool, translation_memory, source_language: source_language.into() }
+    }
+}
+
+/// A document or memory that fails to load is left out like an unknown one
+#[async_trait]
+impl SegmentStore for TranslationMemorySegments {
+    async fn document_segments(&self, document_id: Uuid, language: &str) -> Option<Vec<SegmentState>> {
+        let document = stored_document(&*self.pool.lock().await, document_id);
+        let (_, content) = match document {
+            Ok(document) => document?,
+            Err(e) => {
+                log::warn!("Failed to load document {document_id} for translation progress: {e}");
+                return None;
+            }
+        };
+        let source = content.get(&self.source_language)?;
+
+        let units = match self.translation_memory.translation_memory().get_translation_units().await {
+            Ok(units) => units,
+            Err(e) => {
+                log::warn!("Failed to load the translation memory for translation progress: {e}");
+                return None;
+            }
+        };
+        let translated: Vec<&str> = units
+            .iter()
+            .filter(|unit| unit.chapter_id == document_id && unit.matches_language_codes(&self.source_language, language))
+            .filter(|unit| !unit.target_text.trim().is_empty())
+            .map(|unit| unit.source_text.as_str())
+            .collect();
+
+        Some(
+            paragraphs(source)
+                .into_iter()
+                .map(|paragraph| {
+                    if translated.contains(&paragraph) {
+                        SegmentState::Translated
+                    } else {
+                        SegmentState::Untranslated
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Progress of a section, including everything nested below it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionProgress {
+    pub section_id: Uuid,
+    pub title: String,
+    pub translated_segments: usize,
+    pub total_segments: usize,
+    /// `None` when the section and its subsections have no segments
+    pub percentage: Option<f32>,
+    pub subsections: Vec<SectionProgress>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManualProgress {
+    pub manual_id: Uuid,
+    pub language: String,
+    pub translated_segments: usize,
+    pub total_segments: usize,
+    pub percentage: Option<f32>,
+    pub sections: Vec<SectionProgress>,
+}
+
+/// Compute how much of a manual is translated into `language`.
+///
+/// A segment counts as translated when its target is non-empty and no longer
+/// a draft. Sections without a document, or whose document the store doesn't
+/// know, contribute nothing to the totals rather than counting as 0%.
+pub async fn translation_progress(manual: &Manual, language: &str, store: &impl SegmentStore) -> ManualProgress {
+    let mut segments = HashMap::new();
+    for document_id in document_ids(&manual.sections) {
+        if let Some(states) = store.document_segments(document_id, language).await {
+            segments.insert(document_id, states);
+        }
+    }
+
+    let sections: Vec<SectionProgress> = sorted(&manual.sections)
+        .into_iter()
+        .map(|section| section_progress(section, &segments))
+        .collect();
+
+    let translated_segments = sections.iter().map(|s| s.translated_segments).sum();
+    let total_segments = sections.iter().map(|s| s.total_segments).sum();
+
+    ManualProgress {
+        manual_id: manual.id,
+        language: language.to_string(),
+        translated_segments,
+        total_segments,
+        percentage: percentage(translated_segments, total_segments),
+        sections,
+    }
+}
+
+fn section_progress(section: &ManualSection, segments: &HashMap<Uuid, Vec<SegmentState>>) -> SectionProgress {
+    let subsections: Vec<SectionProgress> = sorted(&section.subsections)
+        .into_iter()
+        .map(|child| section_progress(child, segments))
+        .collect();
+
+    let own: &[SegmentState] = section
+        .document_id
+        .and_then(|id| segments.get(&id))
+        .map_or(&[], Vec::as_slice);
+    let translated_segments = own.iter().filter(|s| **s == SegmentState::Translated).count()
+        + subsections.iter().map(|s| s.translated_segments).sum::<usize>();
+    let total_segments = own.len() + subsections.iter().map(|s| s.total_segments).sum::<usize>();
+
+    SectionProgress {
+        section_id: section.id,
+        title: section.title.clone(),
+        translated_segments,
+        total_segments,
+        percentage: percentage(translated_segments, total_segments),
+        subsections,
+    }
+}
+
+/// Documents of the sections and everything nested below them
+fn document_ids(sections: &[ManualSection]) -> Vec<Uuid> {
+    sections
+        .iter()
+        .flat_map(|section| section.document_id.into_iter().chain(document_ids(&section.subsections)))
+        .collect()
+}
+
+fn sorted(sections: &[ManualSection]) -> Vec<&ManualSection> {
+    let mut sorted: Vec<&ManualSection> = sections.iter().collect();
+    sorted.sort_by_key(|s| s.order);
+    sorted
+}
+
+fn percentage(translated: usize, total: usize) -> Option<f32> {
+    (total > 0).then(|| translated as f32 / total as f32 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::services::indexing::{DocumentIndexer, TranslationMemoryIndexer};
+    use crate::{ManualTemplate, SectionType};
+    use chrono::Utc;
+    use rusqlite::params;
+    use tempfile::TempDir;
+
+    struct FakeStore(HashMap<Uuid, Vec<SegmentState>>);
+
+    #[async_trait]
+    impl SegmentStore for FakeStore {
+        async fn document_segments(&self, document_id: Uuid, _language: &str) -> Option<Vec<SegmentState>> {
+            self.0.get(&document_id).cloned()
+        }
+    }
+
+    fn section(title: &str, order: u32, document_id: Option<Uuid>) -> ManualSection {
+        ManualSection {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            order,
+            document_id,
+            subsections: Vec::new(),
+            section_type: SectionType::Custom(title.to_string()),
+            required: false,
+            localized_titles: HashMap::new(),
+        }
+    }
+
+    fn manual(sections: Vec<ManualSection>) -> Manual {
+        Manual {
+            id: Uuid::new_v4(),
+            title: "Manual".to_string(),
+            description: String::new(),
+            sections,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            version: "1.0".to_string(),
+            languages: vec!["en".to_string(), "de".to_string()],
+            template_type: ManualTemplate::UserGuide,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overall_percentage_excludes_sections_without_documents() {
+        use SegmentState::*;
+
+        let done = Uuid::new_v4();
+        let half = Uuid::new_v4();
+        let store = FakeStore(HashMap::from([
+            (done, vec![Translated; 4]),
+            (half, vec![Translated, Draft, Untranslated, Translated]),
+        ]));
+
+        let mut chapter = section("Installation", 2, None);
+        chapter.subsections.push(section("Wiring", 1, Some(half)));
+        chapter.subsections.push(section("Empty", 2, None));
+        let manual = manual(vec![chapter, section("Introduction", 1, Some(done))]);
+
+        let progress = translation_progress(&manual, "de", &store).await;
+        assert_eq!((progress.translated_segments, progress.total_segments), (6, 8));
+        assert_eq!(progress.percentage, Some(75.0));
+
+        assert_eq!(progress.sections[0].title, "Introduction");
+        assert_eq!(progress.sections[0].percentage, Some(100.0));
+        assert_eq!(progress.sections[1].percentage, Some(50.0));
+        assert_eq!(progress.sections[1].subsections[1].percentage, None);
+    }
+
+    #[tokio::test]
+    async fn test_segments_come_from_documents_and_the_translation_memory() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = Database::in_memory().unwrap();
+        let translation_memory = TranslationMemoryAdapter::new(temp_dir.path().to_path_buf()).await.unwrap();
+
+        let done = Uuid::new_v4();
+        let half = Uuid::new_v4();
+        for (id, content) in [
+            (done, serde_json::json!({ "en": "Check the rope.\n\nGrease the clapper.", "de": "Das Seil prüfen.\n\nDen Klöppel fetten." })),
+            // The second paragraph is still in English
+            (half, serde_json::json!({ "en": "Oil the bearings.\n\nTighten the bolts.", "de": "Die Lager ölen.\n\nTighten the bolts." })),
+        ] {
+            database
+                .pool()
+                .lock()
+                .await
+                .execute(
+                    "INSERT INTO documents (id, title, content, created_at, updated_at) VALUES (?1, 'Glocken', ?2, ?3, ?3)",
+                    params![id.to_string(), content.to_string(), Utc::now().to_rfc3339()],
+                )
+                .unwrap();
+        }
+        let indexer = TranslationMemoryIndexer::new(database.pool(), translation_memory.clone(), "en");
+        indexer.reindex(&indexer.all_documents().await.unwrap()).await.unwrap();
+        let store = TranslationMemorySegments::new(database.pool(), translation_memory, "en");
+
+        use SegmentState::*;
+        assert_eq!(store.document_segments(half, "de").await, Some(vec![Translated, Untranslated]));
+        assert_eq!(store.document_segments(done, "fr").await, Some(vec![Untranslated, Untranslated]));
+        assert_eq!(store.document_segments(Uuid::new_v4(), "de").await, None);
+
+        let manual = manual(vec![section("Maintenance", 1, Some(done)), section("Repairs", 2, Some(half))]);
+        let progress = translation_progress(&manual, "de", &store).await;
+        assert_eq!((progress.translated_segments, progress.total_segments), (3, 4));
+        assert_eq!(progress.percentage, Some(75.0));
+    }
+}
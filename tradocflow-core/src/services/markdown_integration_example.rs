@@ -545,6 +545,7 @@ mod integration_tests {
             backup_directory: None,
             max_backup_files: 5,
             compress_backups: false,
+            save_on_focus_loss: true,
         };
         
         backend.configure_auto_save(auto_save_config).await.unwrap();
@@ -0,0 +1,749 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Style rules checked by [`lint_markdown`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LintRule {
+    /// Heading levels should only increase one step at a time
+    HeadingIncrement,
+    /// Headings should be preceded by a blank line
+    BlankLineBeforeHeading,
+    /// Use spaces instead of hard tabs
+    NoHardTabs,
+    /// No more than one consecutive blank line
+    NoMultipleBlanks,
+    /// Fenced code blocks should declare a language
+    FencedCodeLanguage,
+    /// Lines should not end in whitespace (two spaces for a hard break are allowed)
+    NoTrailingSpaces,
+    /// Unordered lists should use one marker style throughout
+    ListMarkerStyle,
+    /// URLs should be written as links or autolinks
+    NoBareUrls,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 8] = [
+        LintRule::HeadingIncrement,
+        LintRule::BlankLineBeforeHeading,
+        LintRule::NoHardTabs,
+        LintRule::NoMultipleBlanks,
+        LintRule::FencedCodeLanguage,
+        LintRule::NoTrailingSpaces,
+        LintRule::ListMarkerStyle,
+        LintRule::NoBareUrls,
+    ];
+
+    /// Stable identifier used in reports and configuration
+    pub fn id(&self) -> &'static str {
+        match self {
+            LintRule::HeadingIncrement => "heading-increment",
+            LintRule::BlankLineBeforeHeading => "blank-line-before-heading",
+            LintRule::NoHardTabs => "no-hard-tabs",
+            LintRule::NoMultipleBlanks => "no-multiple-blanks",
+            LintRule::FencedCodeLanguage => "fenced-code-language",
+            LintRule::NoTrailingSpaces => "no-trailing-spaces",
+            LintRule::ListMarkerStyle => "list-marker-style",
+            LintRule::NoBareUrls => "no-bare-urls",
+        }
+    }
+
+    /// Rules whose fixes [`autofix_markdown`] applies without changing meaning
+    pub const SAFE_AUTOFIX: [LintRule; 4] = [
+        LintRule::NoTrailingSpaces,
+        LintRule::NoMultipleBlanks,
+        LintRule::ListMarkerStyle,
+        LintRule::FencedCodeLanguage,
+    ];
+
+    /// Whether [`autofix_markdown`] can apply this rule's fixes.
+    ///
+    /// Heading increments are fixable but restructure the document, so they
+    /// are left out of [`LintRule::SAFE_AUTOFIX`] and must be requested explicitly.
+    pub fn is_autofixable(&self) -> bool {
+        Self::SAFE_AUTOFIX.contains(self) || *self == LintRule::HeadingIncrement
+    }
+}
+
+/// Which lint rules are enabled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintConfig {
+    pub heading_increment: bool,
+    pub blank_line_before_heading: bool,
+    pub no_hard_tabs: bool,
+    pub no_multiple_blanks: bool,
+    pub fenced_code_language: bool,
+    pub no_trailing_spaces: bool,
+    pub list_marker_style: bool,
+    pub no_bare_urls: bool,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            heading_increment: true,
+            blank_line_before_heading: true,
+            no_hard_tabs: true,
+            no_multiple_blanks: true,
+            fenced_code_language: true,
+            no_trailing_spaces: true,
+            list_marker_style: true,
+            no_bare_urls: true,
+        }
+    }
+}
+
+impl LintConfig {
+    pub fn is_enabled(&self, rule: LintRule) -> bool {
+        match rule {
+            LintRule::HeadingIncrement => self.heading_increment,
+            LintRule::BlankLineBeforeHeading => self.blank_line_before_heading,
+            LintRule::NoHardTabs => self.no_hard_tabs,
+            LintRule::NoMultipleBlanks => self.no_multiple_blanks,
+            LintRule::FencedCodeLanguage => self.fenced_code_language,
+            LintRule::NoTrailingSpaces => self.no_trailing_spaces,
+            LintRule::ListMarkerStyle => self.list_marker_style,
+            LintRule::NoBareUrls => self.no_bare_urls,
+        }
+    }
+
+    pub fn set_enabled(&mut self, rule: LintRule, enabled: bool) {
+        let flag = match rule {
+            LintRule::HeadingIncrement => &mut self.heading_increment,
+            LintRule::BlankLineBeforeHeading => &mut self.blank_line_before_heading,
+            LintRule::NoHardTabs => &mut self.no_hard_tabs,
+            LintRule::NoMultipleBlanks => &mut self.no_multiple_blanks,
+            LintRule::FencedCodeLanguage => &mut self.fenced_code_language,
+            LintRule::NoTrailingSpaces => &mut self.no_trailing_spaces,
+            LintRule::ListMarkerStyle => &mut self.list_marker_style,
+            LintRule::NoBareUrls => &mut self.no_bare_urls,
+        };
+        *flag = enabled;
+    }
+}
+
+/// Suggested correction: replace the byte range `start..end` of the text with `replacement`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintFix {
+    pub description: String,
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+/// A style problem found in a markdown document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintWarning {
+    pub rule: LintRule,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, counted in characters
+    pub column: usize,
+    pub message: String,
+    pub fix: Option<LintFix>,
+}
+
+/// A line of the document with its byte offset, excluding the line terminator
+struct Line<'a> {
+    number: usize,
+    offset: usize,
+    text: &'a str,
+    in_fence: bool,
+}
+
+/// Lint markdown with every rule enabled
+pub fn lint_markdown(text: &str) -> Vec<LintWarning> {
+    lint_markdown_with_config(text, &LintConfig::default())
+}
+
+/// Lint markdown, skipping rules disabled in `config`.
+///
+/// Warnings are ordered by line, then column.
+pub fn lint_markdown_with_config(text: &str, config: &LintConfig) -> Vec<LintWarning> {
+    let lines = split_lines(text);
+    let mut warnings = Vec::new();
+
+    if config.heading_increment {
+        check_heading_increment(&lines, &mut warnings);
+    }
+    if config.blank_line_before_heading {
+        check_blank_line_before_heading(&lines, &mut warnings);
+    }
+    if config.no_hard_tabs {
+        check_hard_tabs(&lines, &mut warnings);
+    }
+    if config.no_multiple_blanks {
+        check_multiple_blanks(&lines, &mut warnings);
+    }
+    if config.fenced_code_language {
+        check_fenced_code_language(&lines, &mut warnings);
+    }
+    if config.no_trailing_spaces {
+        check_trailing_spaces(&lines, &mut warnings);
+    }
+    if config.list_marker_style {
+        check_list_marker_style(&lines, &mut warnings);
+    }
+    if config.no_bare_urls {
+        check_bare_urls(&lines, &mut warnings);
+    }
+
+    warnings.sort_by_key(|w| (w.line, w.column));
+    warnings
+}
+
+fn split_lines(text: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    let mut fence: Option<&str> = None;
+
+    for (index, raw) in text.split_inclusive('\n').enumerate() {
+        let line = raw.trim_end_matches('\n').trim_end_matches('\r');
+        let trimmed = line.trim_start();
+
+        // Fence delimiters themselves are not inside the fence
+        let in_fence = match fence {
+            Some(marker) if trimmed.starts_with(marker) => {
+                fence = None;
+                false
+            }
+            Some(_) => true,
+            None => {
+                fence = fence_marker(trimmed);
+                false
+            }
+        };
+
+        lines.push(Line { number: index + 1, offset, text: line, in_fence });
+        offset += raw.len();
+    }
+
+    lines
+}
+
+fn fence_marker(trimmed: &str) -> Option<&'static str> {
+    if trimmed.starts_with("```") {
+        Some("```")
+    } else if trimmed.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+/// Level of an ATX heading line, if it is one
+fn heading_level(line: &Line) -> Option<usize> {
+    if line.in_fence {
+        return None;
+    }
+    let trimmed = line.text.trim_start_matches(' ');
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    let rest = &trimmed[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with([' ', '\t']))).then_some(level)
+}
+
+/// 1-based character column of a byte index within a line
+fn column_of(line: &str, byte_index: usize) -> usize {
+    line[..byte_index].chars().count() + 1
+}
+
+fn check_heading_increment(lines: &[Line], warnings: &mut Vec<LintWarning>) {
+    let mut previous: Option<usize> = None;
+
+    for line in lines {
+        let Some(level) = heading_level(line) else { continue };
+
+        if let Some(prev) = previous {
+            if level > prev + 1 {
+                let hashes_start = line.text.len() - line.text.trim_start_matches(' ').len();
+                let expected = prev + 1;
+                warnings.push(LintWarning {
+                    rule: LintRule::HeadingIncrement,
+                    line: line.number,
+                    column: 1,
+                    message: format!("Heading level jumps from h{prev} to h{level}; expected h{expected}"),
+                    fix: Some(LintFix {
+                        description: format!("Change heading to h{expected}"),
+                        start: line.offset + hashes_start,
+                        end: line.offset + hashes_start + level,
+                        replacement: "#".repeat(expected),
+                    }),
+                });
+            }
+        }
+        previous = Some(level);
+    }
+}
+
+fn check_blank_line_before_heading(lines: &[Line], warnings: &mut Vec<LintWarning>) {
+    for pair in lines.windows(2) {
+        let (before, line) = (&pair[0], &pair[1]);
+        if heading_level(line).is_some() && !before.text.trim().is_empty() {
+            warnings.push(LintWarning {
+                rule: LintRule::BlankLineBeforeHeading,
+                line: line.number,
+                column: 1,
+                message: "Heading should be preceded by a blank line".to_string(),
+                fix: Some(LintFix {
+                    description: "Insert a blank line before the heading".to_string(),
+                    start: line.offset,
+                    end: line.offset,
+                    replacement: "\n".to_string(),
+                }),
+            });
+        }
+    }
+}
+
+fn check_hard_tabs(lines: &[Line], warnings: &mut Vec<LintWarning>) {
+    for line in lines.iter().filter(|l| !l.in_fence) {
+        if let Some(index) = line.text.find('\t') {
+            warnings.push(LintWarning {
+                rule: LintRule::NoHardTabs,
+                line: line.number,
+                column: column_of(line.text, index),
+                message: "Hard tab found; use spaces".to_string(),
+                fix: Some(LintFix {
+                    description: "Replace tabs with four spaces".to_string(),
+                    start: line.offset,
+                    end: line.offset + line.text.len(),
+                    replacement: line.text.replace('\t', "    "),
+                }),
+            });
+        }
+    }
+}
+
+fn check_multiple_blanks(lines: &[Line], warnings: &mut Vec<LintWarning>) {
+    let mut index = 0;
+    while index < lines.len() {
+        if lines[index].in_fence || !lines[index].text.trim().is_empty() {
+            index += 1;
+            continue;
+        }
+
+        let run_end = lines[index..]
+            .iter()
+            .position(|l| l.in_fence || !l.text.trim().is_empty())
+            .map_or(lines.len(), |p| index + p);
+
+        if run_end - index > 1 {
+            let extra = &lines[index + 1];
+            // Remove every blank line after the first, including their line terminators
+            let end = lines.get(run_end).map_or_else(
+                || lines[run_end - 1].offset + lines[run_end - 1].text.len(),
+                |next| next.offset,
+            );
+            warnings.push(LintWarning {
+                rule: LintRule::NoMultipleBlanks,
+                line: extra.number,
+                column: 1,
+                message: format!("{} consecutive blank lines; expected at most 1", run_end - index),
+                fix: Some(LintFix {
+                    description: "Collapse blank lines".to_string(),
+                    start: extra.offset,
+                    end,
+                    replacement: String::new(),
+                }),
+            });
+        }
+        index = run_end;
+    }
+}
+
+fn check_fenced_code_language(lines: &[Line], warnings: &mut Vec<LintWarning>) {
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        let trimmed = line.text.trim_start();
+        let opens = !line.in_fence
+            && fence_marker(trimmed).is_some()
+            && lines.get(index + 1).is_some_and(|next| next.in_fence || fence_marker(next.text.trim_start()).is_some());
+
+        if !opens {
+            index += 1;
+            continue;
+        }
+
+        let body: Vec<&str> = lines[index + 1..]
+            .iter()
+            .take_while(|l| l.in_fence)
+            .map(|l| l.text)
+            .collect();
+
+        if trimmed.trim_start_matches(['`', '~']).trim().is_empty() {
+            let marker_end = line.text.trim_end().len();
+            warnings.push(LintWarning {
+                rule: LintRule::FencedCodeLanguage,
+                line: line.number,
+                column: 1,
+                message: "Fenced code block has no language".to_string(),
+                fix: detect_code_language(&body).map(|language| LintFix {
+                    description: format!("Label code block as {language}"),
+                    start: line.offset + marker_end,
+                    end: line.offset + line.text.len(),
+                    replacement: language.to_string(),
+                }),
+            });
+        }
+
+        index += body.len() + 2;
+    }
+}
+
+/// Guess the language of a code block from its first meaningful line
+pub fn detect_code_language(body: &[&str]) -> Option<&'static str> {
+    let first = body.iter().map(|l| l.trim()).find(|l| !l.is_empty())?;
+
+    let language = if first.starts_with("#!") {
+        if first.contains("python") {
+            "python"
+        } else if first.contains("sh") {
+            "bash"
+        } else {
+            return None;
+        }
+    } else if first.starts_with("$ ") {
+        "bash"
+    } else if first.starts_with('{') || (first.starts_with('[') && first.contains('"')) {
+        "json"
+    } else if first.starts_with("<?xml") {
+        "xml"
+    } else if first.starts_with('<') {
+        "html"
+    } else if first.starts_with("fn ") || first.starts_with("use ") || first.starts_with("pub ") || first.starts_with("let mut ") {
+        "rust"
+    } else if first.starts_with("def ") || (first.starts_with("import ") && !first.ends_with(';')) || first.starts_with("from ") {
+        "python"
+    } else if first.starts_with("const ") || first.starts_with("function ") {
+        "javascript"
+    } else if first.to_uppercase().starts_with("SELECT ") || first.to_uppercase().starts_with("CREATE TABLE") {
+        "sql"
+    } else {
+        return None;
+    };
+
+    Some(language)
+}
+
+fn check_trailing_spaces(lines: &[Line], warnings: &mut Vec<LintWarning>) {
+    for line in lines.iter().filter(|l| !l.in_fence) {
+        let content_len = line.text.trim_end().len();
+        let trailing = &line.text[content_len..];
+        // Exactly two spaces after text is a markdown hard line break
+        if trailing.is_empty() || (trailing == "  " && content_len > 0) {
+            continue;
+        }
+        warnings.push(LintWarning {
+            rule: LintRule::NoTrailingSpaces,
+            line: line.number,
+            column: column_of(line.text, content_len),
+            message: "Trailing whitespace".to_string(),
+            fix: Some(LintFix {
+                description: "Remove trailing whitespace".to_string(),
+                start: line.offset + content_len,
+                end: line.offset + line.text.len(),
+                replacement: String::new(),
+            }),
+        });
+    }
+}
+
+/// Byte index and character of an unordered list marker
+fn list_marker(line: &Line) -> Option<(usize, char)> {
+    if line.in_fence {
+        return None;
+    }
+    let indent = line.text.len() - line.text.trim_start().len();
+    let mut chars = line.text[indent..].chars();
+    let marker = chars.next().filter(|c| matches!(c, '-' | '*' | '+'))?;
+    // "---" and "***" are thematic breaks, "*text*" is emphasis
+    let is_item = chars.next() == Some(' ') && !line.text.trim().chars().all(|c| c == marker || c == ' ');
+    is_item.then_some((indent, marker))
+}
+
+fn check_list_marker_style(lines: &[Line], warnings: &mut Vec<LintWarning>) {
+    let mut expected: Option<char> = None;
+
+    for line in lines {
+        let Some((index, marker)) = list_marker(line) else { continue };
+        let expected = *expected.get_or_insert(marker);
+        if marker != expected {
+            warnings.push(LintWarning {
+                rule: LintRule::ListMarkerStyle,
+                line: line.number,
+                column: column_of(line.text, index),
+                message: format!("List marker '{marker}' differs from '{expected}' used earlier"),
+                fix: Some(LintFix {
+                    description: format!("Use '{expected}' as the list marker"),
+                    start: line.offset + index,
+                    end: line.offset + index + 1,
+                    replacement: expected.to_string(),
+                }),
+            });
+        }
+    }
+}
+
+fn url_regex() -> &'static Regex {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    URL.get_or_init(|| Regex::new(r"https?://[^\s<>()\[\]`]+").expect("valid URL regex"))
+}
+
+fn check_bare_urls(lines: &[Line], warnings: &mut Vec<LintWarning>) {
+    for line in lines.iter().filter(|l| !l.in_fence) {
+        for found in url_regex().find_iter(line.text) {
+            let before = &line.text[..found.start()];
+            // Autolinks, link targets, and code spans are fine
+            let wrapped = before.ends_with('<') || before.ends_with("](") || before.ends_with('(');
+            let in_code_span = before.matches('`').count() % 2 == 1;
+            if wrapped || in_code_span {
+                continue;
+            }
+
+            // Sentence punctuation right after a URL is not part of it
+            let url = found.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            warnings.push(LintWarning {
+                rule: LintRule::NoBareUrls,
+                line: line.number,
+                column: column_of(line.text, found.start()),
+                message: format!("Bare URL {url}; wrap it in <> or use a link"),
+                fix: Some(LintFix {
+                    description: "Wrap URL in angle brackets".to_string(),
+                    start: line.offset + found.start(),
+                    end: line.offset + found.start() + url.len(),
+                    replacement: format!("<{url}>"),
+                }),
+            });
+        }
+    }
+}
+
+/// A fix applied by [`autofix_markdown`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedFix {
+    pub rule: LintRule,
+    /// 1-based line the warning was reported on
+    pub line: usize,
+    pub description: String,
+    /// Byte range that was replaced, in the text the fix was applied to
+    pub before: std::ops::Range<usize>,
+    /// Byte range the replacement occupies in the text after the fix
+    pub after: std::ops::Range<usize>,
+}
+
+/// Apply the fixes for `enabled_rules` that can be applied automatically.
+///
+/// Only rules where [`LintRule::is_autofixable`] holds are applied; warnings
+/// from other rules, and warnings without a fix (such as a fence whose
+/// language can't be detected), are left untouched. Pass
+/// [`LintRule::SAFE_AUTOFIX`] for a "fix all" that never changes heading levels.
+///
+/// Fixes are applied in passes until the document is stable, so running the
+/// result through again yields no further changes. Fix ranges refer to the
+/// text of the pass they were applied in; most documents settle in one pass.
+pub fn autofix_markdown(text: &str, enabled_rules: &[LintRule]) -> (String, Vec<AppliedFix>) {
+    let mut config = LintConfig::default();
+    for rule in LintRule::ALL {
+        config.set_enabled(rule, rule.is_autofixable() && enabled_rules.contains(&rule));
+    }
+
+    let mut current = text.to_string();
+    let mut applied = Vec::new();
+
+    // Every fix shortens the text or removes a warning without adding one,
+    // so the line count bounds how many passes can still make progress
+    let max_passes = current.lines().count() + 1;
+    for _ in 0..max_passes {
+        let mut fixes: Vec<(LintRule, usize, LintFix)> = lint_markdown_with_config(&current, &config)
+            .into_iter()
+            .filter_map(|w| w.fix.map(|fix| (w.rule, w.line, fix)))
+            .collect();
+        fixes.sort_by_key(|(_, _, fix)| (fix.start, fix.end));
+
+        // Overlapping fixes are deferred to the next pass, after re-linting
+        let mut accepted: Vec<(LintRule, usize, LintFix)> = Vec::new();
+        for candidate in fixes {
+            let overlaps = accepted.last().is_some_and(|(_, _, prev)| {
+                candidate.2.start < prev.end || candidate.2.start == prev.start
+            });
+            if !overlaps {
+                accepted.push(candidate);
+            }
+        }
+
+        if accepted.is_empty() {
+            break;
+        }
+
+        let mut fixed = String::with_capacity(current.len());
+        let mut cursor = 0;
+        for (rule, line, fix) in accepted {
+            fixed.push_str(&current[cursor..fix.start]);
+            let after_start = fixed.len();
+            fixed.push_str(&fix.replacement);
+            applied.push(AppliedFix {
+                rule,
+                line,
+                description: fix.description,
+                before: fix.start..fix.end,
+                after: after_start..fixed.len(),
+            });
+            cursor = fix.end;
+        }
+        fixed.push_str(&current[cursor..]);
+        current = fixed;
+    }
+
+    (current, applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(warnings: &[LintWarning]) -> Vec<LintRule> {
+        warnings.iter().map(|w| w.rule).collect()
+    }
+
+    #[test]
+    fn test_clean_document_has_no_warnings() {
+        let text = "# Title\n\nSome text with a [link](https://example.com).\n\n## Section\n\n- one\n- two\n\n```rust\nfn main() {}\n```\n";
+        assert!(lint_markdown(text).is_empty());
+    }
+
+    #[test]
+    fn test_heading_increment() {
+        let warnings = lint_markdown("# Title\n\n### Too deep\n");
+        assert_eq!(rules(&warnings), vec![LintRule::HeadingIncrement]);
+        assert_eq!(warnings[0].line, 3);
+        assert_eq!(warnings[0].fix.as_ref().unwrap().replacement, "##");
+    }
+
+    #[test]
+    fn test_blank_line_before_heading() {
+        let warnings = lint_markdown("# Title\nText\n## Section\n");
+        assert_eq!(rules(&warnings), vec![LintRule::BlankLineBeforeHeading]);
+        assert_eq!(warnings[0].line, 3);
+    }
+
+    #[test]
+    fn test_hard_tabs() {
+        let warnings = lint_markdown("Some\ttext\n");
+        assert_eq!(rules(&warnings), vec![LintRule::NoHardTabs]);
+        assert_eq!(warnings[0].column, 5);
+        assert_eq!(warnings[0].fix.as_ref().unwrap().replacement, "Some    text");
+    }
+
+    #[test]
+    fn test_multiple_blank_lines() {
+        let warnings = lint_markdown("One\n\n\n\nTwo\n");
+        assert_eq!(rules(&warnings), vec![LintRule::NoMultipleBlanks]);
+        assert_eq!(warnings[0].line, 3);
+        let fix = warnings[0].fix.as_ref().unwrap();
+        assert_eq!((fix.start, fix.end), (5, 7));
+    }
+
+    #[test]
+    fn test_unlabeled_code_fence() {
+        let warnings = lint_markdown("```\n{\"key\": 1}\n```\n\n```\nplain\n```\n");
+        assert_eq!(rules(&warnings), vec![LintRule::FencedCodeLanguage, LintRule::FencedCodeLanguage]);
+        assert_eq!(warnings[0].fix.as_ref().unwrap().replacement, "json");
+        assert!(warnings[1].fix.is_none());
+    }
+
+    #[test]
+    fn test_trailing_whitespace_allows_hard_break() {
+        let warnings = lint_markdown("Hard break  \nTrailing \nTabs\t\n");
+        assert_eq!(warnings.iter().filter(|w| w.rule == LintRule::NoTrailingSpaces).count(), 2);
+        assert_eq!(warnings[0].line, 2);
+    }
+
+    #[test]
+    fn test_mixed_list_markers() {
+        let warnings = lint_markdown("- one\n* two\n- three\n\n---\n");
+        assert_eq!(rules(&warnings), vec![LintRule::ListMarkerStyle]);
+        assert_eq!(warnings[0].line, 2);
+    }
+
+    #[test]
+    fn test_bare_urls() {
+        let warnings = lint_markdown("See https://example.com. Or <https://ok.example> or `https://code`.\n");
+        assert_eq!(rules(&warnings), vec![LintRule::NoBareUrls]);
+        assert_eq!(warnings[0].fix.as_ref().unwrap().replacement, "<https://example.com>");
+    }
+
+    #[test]
+    fn test_rules_inside_code_fences_are_ignored() {
+        let warnings = lint_markdown("```make\nall:\n\tcc main.c  \n### not a heading\n```\n");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_disabled_rules_are_suppressed() {
+        let text = "# Title\n### Deep\n\tTabbed\n\n\n```\ncode\n```\n- a\n* b\nhttps://example.com \n";
+        assert_eq!(
+            lint_markdown(text).len(),
+            LintRule::ALL.len(),
+            "every rule should fire once on the crafted input"
+        );
+
+        for rule in LintRule::ALL {
+            let mut config = LintConfig::default();
+            config.set_enabled(rule, false);
+            let warnings = lint_markdown_with_config(text, &config);
+            assert!(!warnings.iter().any(|w| w.rule == rule), "{} was not suppressed", rule.id());
+            assert_eq!(warnings.len(), LintRule::ALL.len() - 1);
+        }
+    }
+
+    #[test]
+    fn test_autofix_applies_safe_fixes() {
+        let text = "# Title\n\n- one  \t\n* two\n\n\n\n```\n{\"key\": 1}\n```\n";
+        let (fixed, applied) = autofix_markdown(text, &LintRule::SAFE_AUTOFIX);
+        assert_eq!(fixed, "# Title\n\n- one\n- two\n\n```json\n{\"key\": 1}\n```\n");
+        assert_eq!(applied.len(), 4);
+
+        let trailing = applied.iter().find(|f| f.rule == LintRule::NoTrailingSpaces).unwrap();
+        assert_eq!(trailing.before, 14..17);
+        assert!(trailing.after.is_empty());
+        let label = applied.iter().find(|f| f.rule == LintRule::FencedCodeLanguage).unwrap();
+        assert_eq!(&fixed[label.after.clone()], "json");
+    }
+
+    #[test]
+    fn test_autofix_is_idempotent() {
+        let text = "# Title\n\n#### Deep\n\n##### Deeper \n\n\n- a\n+ b\n```\nfn main() {}\n```\n";
+        for rules in [&LintRule::SAFE_AUTOFIX[..], &LintRule::ALL[..]] {
+            let (once, _) = autofix_markdown(text, rules);
+            let (twice, applied) = autofix_markdown(&once, rules);
+            assert_eq!(once, twice);
+            assert!(applied.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_autofix_heading_levels_are_opt_in() {
+        let text = "# Title\n\n### Deep\n\n#### Deeper\n";
+        let (safe, applied) = autofix_markdown(text, &LintRule::SAFE_AUTOFIX);
+        assert_eq!(safe, text);
+        assert!(applied.is_empty());
+
+        let (fixed, _) = autofix_markdown(text, &[LintRule::HeadingIncrement]);
+        assert_eq!(fixed, "# Title\n\n## Deep\n\n### Deeper\n");
+    }
+
+    #[test]
+    fn test_autofix_leaves_unfixable_warnings() {
+        let text = "See https://example.com\tnow\n\n```\nplain text\n```\n";
+        let (fixed, applied) = autofix_markdown(text, &LintRule::ALL);
+        assert_eq!(fixed, text);
+        assert!(applied.is_empty());
+        let remaining = rules(&lint_markdown(&fixed));
+        assert_eq!(
+            remaining,
+            vec![LintRule::NoBareUrls, LintRule::NoHardTabs, LintRule::FencedCodeLanguage]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// A selected byte range of the text; an empty range is a cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Selection {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Selection {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start: start.min(end), end: start.max(end) }
+    }
+
+    pub fn cursor(offset: usize) -> Self {
+        Self { start: offset, end: offset }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Clamp to the text and move both ends back to character boundaries
+    pub(crate) fn clamp_to(self, text: &str) -> Self {
+        let clamp = |mut offset: usize| {
+            offset = offset.min(text.len());
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            offset
+        };
+        Self::new(clamp(self.start), clamp(self.end))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ListKind {
+    Bullet,
+    Numbered,
+    Task,
+}
+
+/// Wrap the selection in `**`, or unwrap it if it is already bold
+pub fn toggle_bold(text: &str, selection: Selection) -> (String, Selection) {
+    toggle_inline(text, selection, "**")
+}
+
+/// Wrap the selection in `*`, or unwrap it if it is already italic. Bold
+/// markers around the selection are left alone.
+pub fn toggle_italic(text: &str, selection: Selection) -> (String, Selection) {
+    toggle_inline(text, selection, "*")
+}
+
+/// Make the selected lines headings of `level` (1 to 6), or plain lines if
+/// they already all are
+pub fn toggle_heading(text: &str, selection: Selection, level: u8) -> (String, Selection) {
+    let level = level.clamp(1, 6) as usize;
+    let selection = selection.clamp_to(text);
+    let block = line_block(text, selection);
+    let lines = block_lines(text, &block);
+
+    let headings: Vec<Option<usize>> = lines.iter().map(|line| heading_prefix(&text[line.clone()])).collect();
+    let remove = lines
+        .iter()
+        .zip(&headings)
+        .filter(|(line, _)| !text[(*line).clone()].trim().is_empty())
+        .all(|(line, prefix)| prefix.is_some_and(|len| heading_level(&text[line.start..line.start + len]) == level));
+
+    let marker = format!("{} ", "#".repeat(level));
+    let edits = lines
+        .iter()
+        .zip(headings)
+        .filter(|(line, _)| !text[(*line).clone()].trim().is_empty())
+        .map(|(line, prefix)| {
+            let old = line.start..line.start + prefix.unwrap_or(0);
+            let new = if remove { String::new() } else { marker.clone() };
+            (old, new)
+        })
+        .collect();
+    apply_line_edits(text, selection, edits)
+}
+
+/// Turn the selected lines into a list of `kind`, or back into plain lines
+/// if they already all are one. Other list markers are replaced, blank lines
+/// are skipped, and indentation is kept.
+pub fn toggle_list(text: &str, selection: Selection, kind: ListKind) -> (String, Selection) {
+    let selection = selection.clamp_to(text);
+    let block = line_block(text, selection);
+    let lines: Vec<Range<usize>> = block_lines(text, &block)
+        .into_iter()
+        .filter(|line| !text[line.clone()].trim().is_empty())
+        .collect();
+    let markers: Vec<Option<(Range<usize>, ListKind)>> = lines.iter().map(|line| list_marker(text, line)).collect();
+    let remove = !lines.is_empty() && markers.iter().all(|marker| marker.as_ref().is_some_and(|(_, found)| *found == kind));
+
+    let edits = lines
+        .iter()
+        .zip(markers)
+        .enumerate()
+        .map(|(index, (line, marker))| {
+            let indent = text[line.clone()].len() - text[line.clone()].trim_start_matches([' ', '\t']).len();
+            let old = marker.map_or(line.start + indent..line.start + indent, |(range, _)| range);
+            let new = match (remove, kind) {
+                (true, _) => String::new(),
+                (false, ListKind::Bullet) => "- ".to_string(),
+                (false, ListKind::Numbered) => format!("{}. ", index + 1),
+                (false, ListKind::Task) => "- [ ] ".to_string(),
+            };
+            (old, new)
+        })
+        .collect();
+    apply_line_edits(text, selection, edits)
+}
+
+/// Replace the selection with `inserted`, leaving the cursor after it
+pub fn insert_text(text: &str, selection: Selection, inserted: &str) -> (String, Selection) {
+    let selection = selection.clamp_to(text);
+    let new_text = format!("{}{inserted}{}", &text[..selection.start], &text[selection.end..]);
+    (new_text, Selection::cursor(selection.start + inserted.len()))
+}
+
+fn toggle_inline(text: &str, selection: Selection, marker: &str) -> (String, Selection) {
+    let selection = selection.clamp_to(text);
+    let (before, selected, after) = (&text[..selection.start], &text[selection.start..selection.end], &text[selection.end..]);
+    let marker_char = marker.chars().next().expect("non-empty marker");
+    let len = marker.len();
+
+    // Markers selected along with the text
+    let leading = selected.chars().take_while(|&c| c == marker_char).count();
+    let trailing = selected.chars().rev().take_while(|&c| c == marker_char).count();
+    if selected.len() > 2 * len && has_marker(leading, len) && has_marker(trailing, len) {
+        let inner = &selected[len..selected.len() - len];
+        let new_text = format!("{before}{inner}{after}");
+        return (new_text, Selection::new(selection.start, selection.end - 2 * len));
+    }
+
+    // Markers just outside the selection
+    let run_before = before.chars().rev().take_while(|&c| c == marker_char).count();
+    let run_after = after.chars().take_while(|&c| c == marker_char).count();
+    if has_marker(run_before, len) && has_marker(run_after, len) {
+        let new_text = format!("{}{selected}{}", &before[..before.len() - len], &after[len..]);
+        return (new_text, Selection::new(selection.start - len, selection.end - len));
+    }
+
+    let new_text = format!("{before}{marker}{selected}{marker}{after}");
+    (new_text, Selection::new(selection.start + len, selection.end + len))
+}
+
+/// Whether a run of marker characters contains the marker: `**` in runs of
+/// two or three, `*` in runs of one or three (`***` is bold and italic)
+fn has_marker(run: usize, marker_len: usize) -> bool {
+    match marker_len {
+        1 => run == 1 || run == 3,
+        _ => run == marker_len || run == marker_len + 1,
+    }
+}
+
+fn heading_regex() -> &'static Regex {
+    static HEADING: OnceLock<Regex> = OnceLock::new();
+    HEADING.get_or_init(|| Regex::new(r"^#{1,6}[ \t]+").expect("valid heading regex"))
+}
+
+fn list_regex() -> &'static Regex {
+    static LIST: OnceLock<Regex> = OnceLock::new();
+    LIST.get_or_init(|| {
+        Regex::new(r"^[ \t]*(?:([-*+])[ \t]+(\[[ xX]\][ \t]+)?|(\d+)[.)][ \t]+)").expect("valid list regex")
+    })
+}
+
+/// Length of a line's heading marker, including the space after it
+fn heading_prefix(line: &str) -> Option<usize> {
+    heading_regex().find(line).map(|m| m.end())
+}
+
+fn heading_level(prefix: &str) -> usize {
+    prefix.chars().take_while(|&c| c == '#').count()
+}
+
+/// Absolute range and kind of a line's list marker, excluding indentation
+fn list_marker(text: &str, line: &Range<usize>) -> Option<(Range<usize>, ListKind)> {
+    let captures = list_regex().captures(&text[line.clone()])?;
+    let whole = captures.get(0)?;
+    let marker_start = captures.get(1).or_else(|| captures.get(3))?.start();
+    let kind = if captures.get(3).is_some() {
+        ListKind::Numbered
+    } else if captures.get(2).is_some() {
+        ListKind::Task
+    } else {
+        ListKind::Bullet
+    };
+    Some((line.start + marker_start..line.start + whole.end(), kind))
+}
+
+/// The whole lines the selection touches. A selection ending at the start
+/// of a line doesn't include that line.
+fn line_block(text: &str, selection: Selection) -> Range<usize> {
+    let start = text[..selection.start].rfind('\n').map_or(0, |i| i + 1);
+    let mut anchor = selection.end;
+    if selection.end > start && text[..selection.end].ends_with('\n') {
+        anchor -= 1;
+    }
+    let end = text[anchor..].find('\n').map_or(text.len(), |i| anchor + i);
+    start..end
+}
+
+fn block_lines(text: &str, block: &Range<usize>) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = block.start;
+    for line in text[block.clone()].split('\n') {
+        lines.push(start..start + line.len());
+        start += line.len() + 1;
+    }
+    lines
+}
+
+/// Replace line prefixes and carry the selection across the edits. Offsets
+/// inside a replaced prefix move to its end, except a selection start at
+/// the very beginning of a prefix, which stays before it.
+fn apply_line_edits(text: &str, selection: Selection, edits: Vec<(Range<usize>, String)>) -> (String, Selection) {
+    let mut new_text = String::with_capacity(text.len() + edits.len() * 4);
+    let mut last = 0;
+    for (range, replacement) in &edits {
+        new_text.push_str(&text[last..range.start]);
+        new_text.push_str(replacement);
+        last = range.end;
+    }
+    new_text.push_str(&text[last..]);
+
+    let map = |offset: usize, stay_before: bool| {
+        let mut shift: isize = 0;
+        for (range, replacement) in &edits {
+            if offset < range.start || (stay_before && offset == range.start) {
+                break;
+            }
+            if offset <= range.end {
+                return (range.start as isize + shift) as usize + replacement.len();
+            }
+            shift += replacement.len() as isize - range.len() as isize;
+        }
+        (offset as isize + shift) as usize
+    };
+
+    let stay_before = !selection.is_empty();
+    let new_selection = Selection::new(map(selection.start, stay_before), map(selection.end, false));
+    (new_text, new_selection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selected<'a>(text: &'a str, selection: &Selection) -> &'a str {
+        &text[selection.start..selection.end]
+    }
+
+    #[test]
+    fn test_bold_applies_and_unapplies() {
+        let text = "Open the valve slowly.";
+        let (bold, selection) = toggle_bold(text, Selection::new(9, 14));
+        assert_eq!(bold, "Open the **valve** slowly.");
+        assert_eq!(selected(&bold, &selection), "valve");
+
+        let (plain, selection) = toggle_bold(&bold, selection);
+        assert_eq!(plain, text);
+        assert_eq!(selected(&plain, &selection), "valve");
+
+        // Selecting the markers too also toggles off
+        let (plain, selection) = toggle_bold(&bold, Selection::new(9, 18));
+        assert_eq!(plain, text);
+        assert_eq!(selected(&plain, &selection), "valve");
+    }
+
+    #[test]
+    fn test_italic_inside_bold() {
+        let (text, selection) = toggle_italic("**valve**", Selection::new(2, 7));
+        assert_eq!(text, "***valve***");
+        let (text, selection) = toggle_bold(&text, selection);
+        assert_eq!(text, "*valve*");
+        let (text, _) = toggle_italic(&text, selection);
+        assert_eq!(text, "valve");
+    }
+
+    #[test]
+    fn test_three_lines_become_bullet_list() {
+        let text = "Steps:\nOpen the lid\nCheck the fuse\nClose the lid\n";
+        let selection = Selection::new(7, text.len());
+        let (list, selection) = toggle_list(text, selection, ListKind::Bullet);
+        assert_eq!(list, "Steps:\n- Open the lid\n- Check the fuse\n- Close the lid\n");
+        assert_eq!(selected(&list, &selection), "- Open the lid\n- Check the fuse\n- Close the lid\n");
+
+        let (numbered, selection) = toggle_list(&list, selection, ListKind::Numbered);
+        assert_eq!(numbered, "Steps:\n1. Open the lid\n2. Check the fuse\n3. Close the lid\n");
+        let (plain, _) = toggle_list(&numbered, selection, ListKind::Numbered);
+        assert_eq!(plain, text);
+    }
+
+    #[test]
+    fn test_heading_toggle_keeps_cursor_in_text() {
+        let (text, cursor) = toggle_heading("Wartung", Selection::cursor(3), 2);
+        assert_eq!(text, "## Wartung");
+        assert_eq!(cursor, Selection::cursor(6));
+
+        let (text, _) = toggle_heading("# Wartung", Selection::cursor(0), 3);
+        assert_eq!(text, "### Wartung");
+        let (text, cursor) = toggle_heading(&text, Selection::cursor(8), 3);
+        assert_eq!(text, "Wartung");
+        assert_eq!(cursor, Selection::cursor(4));
+    }
+
+    #[test]
+    fn test_inserted_text_replaces_selection() {
+        let (text, cursor) = insert_text("Öffnen Sie das Ventil.", Selection::new(12, 22), "die Klappe");
+        assert_eq!(text, "Öffnen Sie die Klappe.");
+        assert_eq!(cursor, Selection::cursor(22));
+        // Past the end of the text
+        let (text, cursor) = insert_text("Lid", Selection::cursor(40), " open");
+        assert_eq!((text.as_str(), cursor), ("Lid open", Selection::cursor(8)));
+    }
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use regex::Regex;
 
+use super::paste::{html_to_markdown_with, PasteOptions};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarkdownElement {
     pub element_type: String,
@@ -75,6 +77,7 @@ pub enum FormatType {
 
 pub struct MarkdownService {
     options: ComrakOptions<'static>,
+    paste_options: PasteOptions,
 }
 
 impl Default for MarkdownService {
@@ -111,7 +114,13 @@ impl MarkdownService {
         options.render.unsafe_ = false; // Security: disable unsafe HTML
         options.render.escape = false;
         
-        Self { options }
+        Self { options, paste_options: PasteOptions::default() }
+    }
+
+    /// Resolve relative URLs in pasted HTML as `paste_options` say
+    pub fn with_paste_options(mut self, paste_options: PasteOptions) -> Self {
+        self.paste_options = paste_options;
+        self
     }
     
     /// Render markdown to HTML with live preview enhancements
@@ -1094,33 +1103,9 @@ impl MarkdownService {
         None
     }
     
-    /// Convert HTML back to markdown (for inline editing)
+    /// Convert HTML back to markdown (for inline editing and pasting)
     pub fn html_to_markdown(&self, html: &str) -> Result<String> {
-        // This is a simplified conversion
-        // In practice, you'd want a proper HTML to Markdown converter
-        let markdown = html
-            .replace("<h1>", "# ")
-            .replace("</h1>", "\n")
-            .replace("<h2>", "## ")
-            .replace("</h2>", "\n")
-            .replace("<h3>", "### ")
-            .replace("</h3>", "\n")
-            .replace("<p>", "")
-            .replace("</p>", "\n\n")
-            .replace("<strong>", "**")
-            .replace("</strong>", "**")
-            .replace("<em>", "*")
-            .replace("</em>", "*")
-            .replace("<li>", "- ")
-            .replace("</li>", "\n")
-            .replace("<ul>", "")
-            .replace("</ul>", "")
-            .replace("<ol>", "")
-            .replace("</ol>", "")
-            .replace("<blockquote>", "> ")
-            .replace("</blockquote>", "\n");
-        
-        Ok(markdown)
+        Ok(html_to_markdown_with(html, &self.paste_options))
     }
     
     /// Apply bold formatting to selected text
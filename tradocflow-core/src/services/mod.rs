@@ -58,6 +58,12 @@ pub use tradocflow_translation_memory::{
     Quality,
     Metadata as NewMetadata,
     ComprehensiveSearchResult,
+    ConsistencyCheckOptions,
+    InconsistencyGroup,
+    TranslationVariant,
+    UnitLocation,
+    TermRule,
+    TermValidationError,
     TranslationMemoryError,
     Result as TMResult,
 };
@@ -265,7 +271,7 @@ pub use markdown_processor::{
 pub use document_state_manager::{
     DocumentStateManager, DocumentChange, ChangeType, DocumentVersion, AutoSaveConfig,
     DocumentState, LineEnding, ConflictDetection, Conflict, ConflictType as DocConflictType, ConflictSeverity,
-    ConflictResolutionStrategy, MemoryUsageInfo, DocumentStateError
+    ConflictResolutionStrategy, MemoryUsageInfo, DocumentStateError, SaveOutcome, ExternalChange
 };
 pub use markdown_integration_example::{
     MarkdownEditorBackend, SlintIntegration, DocumentStats, AdvancedMarkdownProcessor,
@@ -284,6 +290,156 @@ pub use focus_management_service::{
     FocusEvent, FocusUpdateResult
 };
 
+// Document outline
+pub mod outline;
+pub use outline::{extract_outline, OutlineNode};
+
+// Markdown style linting
+pub mod markdown_lint;
+pub use markdown_lint::{
+    lint_markdown, lint_markdown_with_config, autofix_markdown, LintRule, LintConfig, LintWarning,
+    LintFix, AppliedFix
+};
+
+// Translation progress per manual
+pub mod manual_progress;
+pub use manual_progress::{
+    translation_progress, ManualProgress, SectionProgress, SegmentState, SegmentStore
+};
+
+// Placeholder integrity between source and target
+pub mod placeholder_check;
+pub use placeholder_check::{
+    check_placeholders, check_placeholders_with_config, PlaceholderConfig, PlaceholderIssue, PlaceholderPattern
+};
+
+// Locale number and date format checks
+pub mod locale_format_check;
+pub use locale_format_check::{check_locale_formatting, FormatIssue, FormatIssueKind};
+
+// Undo/redo across typing and structural edits
+pub mod history;
+pub use history::{EditHistory, DEFAULT_HISTORY_CAPACITY};
+
+// Toolbar formatting as pure text + selection operations
+pub mod markdown_ops;
+pub use markdown_ops::{ListKind, Selection};
+
+// Pasted HTML and rich text as markdown
+pub mod paste;
+pub use paste::{html_to_markdown, html_to_markdown_with, PasteOptions};
+
+// Link insertion and checking
+pub mod links;
+pub use links::{check_http, extract_links, insert_link, validate_links, LinkIssue, LinkIssueKind, LinkKind, LinkRef};
+
+// Cross-references between manual sections
+pub mod cross_refs;
+pub use cross_refs::{extract_cross_refs, CrossRef, CrossRefResolution, CrossRefResolver, DanglingCrossRef};
+
+// Stable heading anchors
+pub mod heading_ids;
+pub use heading_ids::{assign_stable_heading_ids, HeadingIdRegistry, StableHeadingId};
+
+// Manual templates and section scaffolding
+pub mod templates;
+pub use templates::{
+    builtin_spec, scaffold, validate_against, SectionSpec, TemplateRegistry, TemplateSpec, TemplateViolation,
+    TemplateViolationKind,
+};
+
+// Manuals stored as a directory of JSON files
+pub mod manual_dir;
+pub use manual_dir::{load_manual_dir, save_manual_dir, save_manual_dir_async};
+
+// Versioned JSON reports for scripting
+pub mod report;
+pub use report::{JsonReport, ReportKind, SectionLint, REPORT_SCHEMA_VERSION};
+
+// Search across documents, terminology and translation memory
+pub mod global_search;
+pub use global_search::{global_search, GlobalSearchHit, GlobalSearchResults, SearchBackend, SearchFailure, SearchSource};
+
+// Whole projects as a single portable archive
+pub mod project_archive;
+// `ImportProgress` stays module-qualified; the name is taken by document imports
+pub use project_archive::{ArchiveManifest, ImportPhase, ProjectArchive, ARCHIVE_SCHEMA_VERSION};
+
+// Locale-aware ordering of listings
+pub mod collation;
+pub use collation::sort_localized;
+
+// In-process notifications of document, review and terminology changes
+pub mod events;
+pub use events::{AppEvent, EventBus, EventSubscriber};
+
+// Background rebuilds of search and translation memory indexes
+pub mod indexing;
+pub use indexing::{DocumentIndexer, DocumentSearchIndex, IndexFreshness, IndexWorker, TranslationMemoryIndexer};
+
+// Text marked to be left untranslated through segmentation, MT and export
+pub mod dnt;
+pub use dnt::{translate_protected, MachineTranslator, ProtectedText, DNT_CLOSE, DNT_OPEN};
+
+// Project variables such as the product name, substituted on export
+pub mod variables;
+pub use variables::{ProjectVariables, SubstitutedText, CODE_VARIABLES_FLAG};
+
+// Library of reusable markdown snippets
+pub mod snippets;
+pub use snippets::{Snippet, SnippetFilter, SnippetService};
+
+// Revision history tables derived from release tags
+pub mod revision_history;
+pub use revision_history::{revision_history, RevisionEntry, RevisionScope, REVISION_HISTORY_MARKER};
+
+// Word counts and weighted translation effort against the TM
+pub mod effort;
+pub use effort::{estimate, estimate_manual, BucketCount, EffortEstimate, EffortWeights, FuzzyBand, FuzzyBandCount, TmLookup};
+
+// Pseudo-localized text for layout testing
+pub mod pseudo_locale;
+pub use pseudo_locale::{pseudolocalize, pseudolocalize_manual, PseudoLocaleOptions, PSEUDO_LOCALE};
+
+// Atomic file saves with async, buffered writes
+pub mod atomic_file;
+pub use atomic_file::{
+    content_revision, disk_revision, write_atomic, write_atomic_blocking, write_atomic_from, write_atomic_if_unchanged,
+    ConditionalWrite, SaveProgress,
+};
+
+// Cached screenshot thumbnails for browsers and pickers
+pub mod thumbnails;
+pub use thumbnails::{Thumbnail, ThumbnailCache};
+
+// Metadata stripping and validation of imported images
+pub mod image_import;
+pub use image_import::{import_image, sanitize_image, ImageImportOptions, ImageKind};
+
+// Clipboard images pasted into documents as screenshot assets
+pub mod image_paste;
+pub use image_paste::{ImagePaster, PastedImage};
+
+// WCAG checks of exported HTML
+pub mod a11y;
+pub use a11y::{check_html, check_screenshot_alt_text, A11yIssue, A11yIssueKind};
+
+// Time open review requests have spent in their status, against an SLA
+pub mod review_aging;
+pub use review_aging::{aging_report, ReviewAge, ReviewSla};
+
+// Ranking of candidate reviewers by workload, role and language
+pub mod reviewer_suggestions;
+pub use reviewer_suggestions::{LanguageFit, ReviewerBalancer, ReviewerLanguages, ReviewerSuggestion};
+
+// Role-based authorization of review, merge and delete actions
+pub mod authz;
+pub use authz::{Action, Authorizer, PermissionConfig, Resource};
+
+// Per-project activity feed over the audit log
+pub mod activity;
+pub use activity::{Activity, ActivityCursor, ActivityItem, ActivityKind, ActivityLog};
+
 // Sentence alignment services
 pub mod sentence_alignment_service;
 pub mod text_structure_analyzer;
@@ -654,6 +654,7 @@ impl MultiEditorPerformanceCoordinator {
             encoding: "UTF-8".to_string(),
             line_endings: crate::services::document_state_manager::LineEnding::Unix,
             language: Some("markdown".to_string()),
+            disk_revision: None,
         };
 
         let editor_instance = EditorInstance {
@@ -0,0 +1,240 @@
+use crate::services::heading_ids::split_heading_id;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A heading in the document outline together with the headings nested under it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutlineNode {
+    pub level: usize,
+    pub text: String,
+    /// Anchor id, unique within the document
+    pub slug: String,
+    /// Whether `slug` was given on the heading as `{#id}` rather than derived
+    pub explicit_id: bool,
+    /// Byte offset of the start of the heading line
+    pub offset: usize,
+    pub line: usize,
+    pub children: Vec<OutlineNode>,
+}
+
+impl OutlineNode {
+    /// Depth-first list of this node and all of its descendants
+    pub fn flatten(&self) -> Vec<&OutlineNode> {
+        let mut nodes = vec![self];
+        for child in &self.children {
+            nodes.extend(child.flatten());
+        }
+        nodes
+    }
+}
+
+/// Extract a nested heading tree from markdown.
+///
+/// Only ATX headings (`#` to `######`) outside fenced code blocks are
+/// considered. A heading becomes a child of the nearest preceding heading
+/// with a lower level, so skipped levels (h1 followed by h3) still nest.
+/// An explicit `{#id}` after the heading text is used as its slug.
+pub fn extract_outline(markdown: &str) -> Vec<OutlineNode> {
+    let mut roots: Vec<OutlineNode> = Vec::new();
+    // Open headings, outermost first
+    let mut stack: Vec<OutlineNode> = Vec::new();
+    let mut slug_counts: HashMap<String, usize> = HashMap::new();
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+
+    for (line_idx, line) in markdown.split_inclusive('\n').enumerate() {
+        let line_offset = offset;
+        offset += line.len();
+        let trimmed = line.trim();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            fence = Some("```");
+            continue;
+        }
+        if trimmed.starts_with("~~~") {
+            fence = Some("~~~");
+            continue;
+        }
+
+        let Some((level, text)) = parse_heading(line) else {
+            continue;
+        };
+        let (text, explicit_id) = split_heading_id(&text);
+        let slug = match explicit_id {
+            Some(id) => {
+                // Derived slugs must not collide with it
+                *slug_counts.entry(id.to_string()).or_insert(0) += 1;
+                id.to_string()
+            }
+            None => unique_slug(text, &mut slug_counts),
+        };
+
+        let node = OutlineNode {
+            level,
+            slug,
+            explicit_id: explicit_id.is_some(),
+            text: text.to_string(),
+            offset: line_offset,
+            line: line_idx,
+            children: Vec::new(),
+        };
+
+        while stack.last().is_some_and(|open| open.level >= level) {
+            close_heading(&mut stack, &mut roots);
+        }
+        stack.push(node);
+    }
+
+    while !stack.is_empty() {
+        close_heading(&mut stack, &mut roots);
+    }
+
+    roots
+}
+
+/// Pop the innermost open heading and attach it to its parent
+fn close_heading(stack: &mut Vec<OutlineNode>, roots: &mut Vec<OutlineNode>) {
+    if let Some(node) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+}
+
+/// Parse an ATX heading line into its level and text
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    // Up to three spaces of indentation are allowed before the hashes
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+
+    let rest = line.trim_start_matches(' ').trim_end();
+    let level = rest.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+
+    let after = &rest[level..];
+    if !after.is_empty() && !after.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    // Strip an optional closing sequence of hashes
+    let text = after.trim();
+    let text = match text.trim_end_matches('#') {
+        stripped if stripped.is_empty() || stripped.ends_with([' ', '\t']) => stripped.trim_end(),
+        _ => text,
+    };
+
+    Some((level, text.to_string()))
+}
+
+/// Generate a slug and disambiguate repeats by appending `-1`, `-2`, ...
+fn unique_slug(text: &str, counts: &mut HashMap<String, usize>) -> String {
+    let base = slugify(text);
+    let mut count = counts.get(&base).copied().unwrap_or(0);
+    let mut slug = if count == 0 { base.clone() } else { format!("{base}-{count}") };
+
+    // A literal "intro-1" heading must not collide with the second "intro"
+    while count > 0 && counts.contains_key(&slug) {
+        count += 1;
+        slug = format!("{base}-{count}");
+    }
+
+    counts.insert(base.clone(), count + 1);
+    if slug != base {
+        counts.insert(slug.clone(), 1);
+    }
+    slug
+}
+
+/// Lowercase alphanumerics with whitespace replaced by hyphens
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                Some(c)
+            } else if c.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_heading_levels() {
+        let markdown = "# Guide\n\nIntro\n\n## Install\n\n### Linux\n\n## Usage\n\n# Appendix\n";
+        let outline = extract_outline(markdown);
+
+        assert_eq!(outline.len(), 2);
+        assert_eq!(outline[0].text, "Guide");
+        assert_eq!(outline[0].children.len(), 2);
+        assert_eq!(outline[0].children[0].text, "Install");
+        assert_eq!(outline[0].children[0].children[0].text, "Linux");
+        assert_eq!(outline[0].children[1].text, "Usage");
+        assert_eq!(outline[1].text, "Appendix");
+
+        let install = &outline[0].children[0];
+        assert_eq!(install.offset, markdown.find("## Install").unwrap());
+        assert_eq!(install.line, 4);
+    }
+
+    #[test]
+    fn test_skipped_levels_nest_under_nearest_parent() {
+        let outline = extract_outline("# Title\n### Deep\n## Middle\n");
+
+        assert_eq!(outline.len(), 1);
+        let children = &outline[0].children;
+        assert_eq!(children.len(), 2);
+        assert_eq!((children[0].level, children[0].text.as_str()), (3, "Deep"));
+        assert_eq!((children[1].level, children[1].text.as_str()), (2, "Middle"));
+    }
+
+    #[test]
+    fn test_duplicate_slugs_are_disambiguated() {
+        let outline = extract_outline("# Notes\n## Notes\n## Notes\n# Notes-1\n");
+        let slugs: Vec<&str> = outline
+            .iter()
+            .flat_map(|node| node.flatten())
+            .map(|node| node.slug.as_str())
+            .collect();
+
+        assert_eq!(slugs, vec!["notes", "notes-1", "notes-2", "notes-1-1"]);
+    }
+
+    #[test]
+    fn test_ignores_code_fences_and_non_headings() {
+        let outline = extract_outline("```bash\n# not a heading\n```\n#hashtag\n## Real ##\n");
+
+        assert_eq!(outline.len(), 1);
+        assert_eq!(outline[0].text, "Real");
+        assert_eq!(outline[0].slug, "real");
+    }
+
+    #[test]
+    fn test_explicit_id_is_used_as_slug() {
+        let outline = extract_outline("# Install {#setup}
+# Setup
+");
+        assert_eq!((outline[0].text.as_str(), outline[0].slug.as_str()), ("Install", "setup"));
+        assert!(outline[0].explicit_id);
+        assert_eq!(outline[1].slug, "setup-1");
+    }
+}
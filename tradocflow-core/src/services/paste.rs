@@ -0,0 +1,894 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use url::Url;
+
+/// How pasted HTML is turned into markdown
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasteOptions {
+    /// URL that relative image and link URLs are resolved against, usually
+    /// the page the content was copied from. Without it they are kept as
+    /// they are.
+    pub base_url: Option<String>,
+}
+
+/// Convert pasted HTML to markdown, keeping relative URLs as they are
+pub fn html_to_markdown(html: &str) -> String {
+    html_to_markdown_with(html, &PasteOptions::default())
+}
+
+/// Convert HTML as browsers and word processors put it on the clipboard to
+/// markdown.
+///
+/// Headings, paragraphs, bold and italic, lists (including Word's list
+/// paragraphs), links, images, code, quotes and tables are kept; styling,
+/// classes and Office markup are dropped. Unknown tags are dropped but
+/// their text kept, while scripts, styles, embedded objects and links to
+/// `javascript:` and other unsafe URLs are dropped entirely.
+pub fn html_to_markdown_with(html: &str, options: &PasteOptions) -> String {
+    let base = options.base_url.as_deref().and_then(|base| Url::parse(base).ok());
+    let root = parse(fragment(html));
+    Renderer { base: base.as_ref() }.blocks(&root.children).join("\n\n")
+}
+
+/// The pasted part of clipboard HTML. Windows wraps it in a header and a
+/// whole document, marking the fragment with comments.
+fn fragment(html: &str) -> &str {
+    const START: &str = "<!--StartFragment-->";
+    if let (Some(start), Some(end)) = (html.find(START), html.find("<!--EndFragment-->")) {
+        if start < end {
+            return &html[start + START.len()..end];
+        }
+    }
+    if html.starts_with("Version:") {
+        return &html[html.find('<').unwrap_or(html.len())..];
+    }
+    html
+}
+
+/// Elements dropped with everything in them
+const DROPPED: &[&str] = &[
+    "head", "template", "noscript", "iframe", "frame", "object", "embed", "applet", "svg", "math", "canvas",
+    "audio", "video", "select", "button", "form",
+];
+
+/// Elements whose content is text up to their end tag, all dropped
+const RAW_TEXT: &[&str] = &["script", "style", "textarea", "title", "xmp"];
+
+/// Elements that never have content
+const VOID: &[&str] = &[
+    "area", "base", "br", "col", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+
+const BLOCKS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "body", "caption", "center", "dd", "details", "div", "dl", "dt",
+    "fieldset", "figcaption", "figure", "footer", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "html", "li",
+    "main", "nav", "ol", "p", "pre", "section", "summary", "table", "tbody", "td", "tfoot", "th", "thead", "tr", "ul",
+];
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Start { name: String, attrs: Vec<(String, String)>, self_closing: bool },
+    End(String),
+    /// Bullet or number Word writes out for a list paragraph
+    ListMarker(String),
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+    ListMarker(String),
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Value of a property in the element's inline style
+    fn style(&self, property: &str) -> Option<String> {
+        self.attr("style")?.split(';').find_map(|declaration| {
+            let (key, value) = declaration.split_once(':')?;
+            key.trim().eq_ignore_ascii_case(property).then(|| value.trim().to_ascii_lowercase())
+        })
+    }
+
+    fn is_bold(&self) -> Option<bool> {
+        self.style("font-weight").map(|weight| matches!(weight.as_str(), "bold" | "bolder" | "600" | "700" | "800" | "900"))
+    }
+
+    fn is_italic(&self) -> Option<bool> {
+        self.style("font-style").map(|style| matches!(style.as_str(), "italic" | "oblique"))
+    }
+}
+
+fn is_block(name: &str) -> bool {
+    BLOCKS.contains(&name)
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if open > 0 {
+            tokens.push(Token::Text(decode_entities(&rest[..open])));
+        }
+        rest = &rest[open..];
+
+        if rest.starts_with("<!--") {
+            // Comments, and with them Word's conditional comments
+            rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+        } else if rest.starts_with("<![if") && rest[..rest.find('>').unwrap_or(rest.len())].contains("supportLists") {
+            let end = rest.find("<![endif]>").unwrap_or(rest.len());
+            // The marker starts after the `>` closing `<![if`, which a
+            // malformed fragment may lack before `<![endif]>`
+            let marker = &rest[rest[..end].find('>').map_or(end, |start| start + 1)..end];
+            tokens.push(Token::ListMarker(text_of(marker)));
+            rest = rest.get(end + "<![endif]>".len()..).unwrap_or("");
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            // Doctypes, XML declarations and the other conditional markers
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(tag) = rest.strip_prefix("</") {
+            let end = tag.find('>').unwrap_or(tag.len());
+            let name = tag[..end].split_whitespace().next().unwrap_or_default().to_ascii_lowercase();
+            tokens.push(Token::End(name));
+            rest = tag.get(end + 1..).unwrap_or("");
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let (name, attrs, self_closing, after) = parse_tag(rest);
+            rest = after;
+            if RAW_TEXT.contains(&name.as_str()) {
+                rest = skip_raw_text(rest, &name);
+            } else {
+                tokens.push(Token::Start { name, attrs, self_closing });
+            }
+        } else {
+            tokens.push(Token::Text("<".to_string()));
+            rest = &rest[1..];
+        }
+    }
+    tokens
+}
+
+/// Read the tag `html` starts with, returning its name, attributes, whether
+/// it closes itself and what follows it
+fn parse_tag(html: &str) -> (String, Vec<(String, String)>, bool, &str) {
+    let is_delimiter = |c: char| c.is_whitespace() || c == '/' || c == '>';
+    let body = &html[1..];
+    let name_end = body.find(is_delimiter).unwrap_or(body.len());
+    let name = body[..name_end].to_ascii_lowercase();
+    let mut rest = &body[name_end..];
+    let mut attrs = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return (name, attrs, false, rest);
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return (name, attrs, false, after);
+        }
+        if let Some(after) = rest.strip_prefix("/>") {
+            return (name, attrs, true, after);
+        }
+        if rest.starts_with('/') {
+            rest = &rest[1..];
+            continue;
+        }
+        let key_end = rest.find(|c: char| is_delimiter(c) || c == '=').unwrap_or(rest.len()).max(1);
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(value) => {
+                let value = value.trim_start();
+                match value.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let end = value[1..].find(quote).map_or(value.len(), |end| end + 1);
+                        rest = value.get(end + 1..).unwrap_or("");
+                        &value[1..end]
+                    }
+                    _ => {
+                        let end = value.find(|c: char| c.is_whitespace() || c == '>').unwrap_or(value.len());
+                        rest = &value[end..];
+                        &value[..end]
+                    }
+                }
+            }
+            None => "",
+        };
+        attrs.push((key, decode_entities(value)));
+    }
+}
+
+/// What follows the end tag of the raw text element `name`
+fn skip_raw_text<'a>(html: &'a str, name: &str) -> &'a str {
+    let close = format!("</{name}");
+    let Some(start) = html.to_ascii_lowercase().find(&close) else {
+        return "";
+    };
+    let after = &html[start..];
+    after.find('>').map_or("", |end| &after[end + 1..])
+}
+
+/// Text of an HTML snippet, without its tags
+fn text_of(html: &str) -> String {
+    tokenize(html)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn parse(html: &str) -> Element {
+    let mut stack = vec![Element { name: "#root".to_string(), attrs: Vec::new(), children: Vec::new() }];
+    // An element being dropped, and how deeply it is nested in itself
+    let mut dropping: Option<(String, usize)> = None;
+    for token in tokenize(html) {
+        if let Some((name, depth)) = &mut dropping {
+            match &token {
+                Token::Start { name: start, self_closing: false, .. } if start == name => *depth += 1,
+                Token::End(end) if end == name => {
+                    *depth -= 1;
+                    if *depth == 0 {
+                        dropping = None;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match token {
+            Token::Text(text) => push_child(&mut stack, Node::Text(text)),
+            Token::ListMarker(marker) => push_child(&mut stack, Node::ListMarker(marker)),
+            Token::Start { name, attrs, self_closing } => {
+                if DROPPED.contains(&name.as_str()) {
+                    if !self_closing {
+                        dropping = Some((name, 1));
+                    }
+                    continue;
+                }
+                close_implied(&mut stack, &name);
+                let element = Element { name, attrs, children: Vec::new() };
+                if self_closing || VOID.contains(&element.name.as_str()) {
+                    push_child(&mut stack, Node::Element(element));
+                } else {
+                    stack.push(element);
+                }
+            }
+            Token::End(name) => {
+                if let Some(open) = stack.iter().rposition(|element| element.name == name).filter(|&open| open > 0) {
+                    close_to(&mut stack, open);
+                }
+            }
+        }
+    }
+    close_to(&mut stack, 1);
+    stack.pop().expect("the root is never closed")
+}
+
+fn push_child(stack: &mut [Element], node: Node) {
+    stack.last_mut().expect("the root is never closed").children.push(node);
+}
+
+/// Close the open elements from `index` on
+fn close_to(stack: &mut Vec<Element>, index: usize) {
+    while stack.len() > index {
+        let element = stack.pop().expect("stack is longer than index");
+        push_child(stack, Node::Element(element));
+    }
+}
+
+/// Close the elements a `name` start tag ends without their end tag, like
+/// an open item on a new `<li>`
+fn close_implied(stack: &mut Vec<Element>, name: &str) {
+    let last_open = |stack: &[Element], names: &[&str]| stack.iter().rposition(|element| names.contains(&element.name.as_str()));
+    let siblings: Option<(&[&str], &[&str])> = match name {
+        "li" => Some((&["li"], &["ul", "ol"])),
+        "tr" => Some((&["tr"], &["table", "thead", "tbody", "tfoot"])),
+        "td" | "th" => Some((&["td", "th"], &["tr", "table"])),
+        "dt" | "dd" => Some((&["dt", "dd"], &["dl"])),
+        _ => None,
+    };
+    if let Some((open, parents)) = siblings {
+        if let Some(open) = last_open(stack, open) {
+            if last_open(stack, parents).is_none_or(|parent| parent < open) {
+                close_to(stack, open);
+            }
+        }
+    }
+    if is_block(name) && stack.last().is_some_and(|element| element.name == "p") {
+        let open = stack.len() - 1;
+        close_to(stack, open);
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp + 1..];
+        let entity = rest.find(';').filter(|&end| end <= 10).and_then(|end| Some((decode_entity(&rest[..end])?, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => decoded.push('&'),
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|&c| c != '\0');
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "shy" => '\u{ad}',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "bull" => '•',
+        "middot" => '·',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "euro" => '€',
+        _ => return None,
+    })
+}
+
+/// Level of a Word list paragraph, `None` for anything else
+fn word_list_level(node: &Node) -> Option<usize> {
+    static LEVEL: OnceLock<Regex> = OnceLock::new();
+    let Node::Element(element) = node else {
+        return None;
+    };
+    if element.name != "p" {
+        return None;
+    }
+    let list = element.style("mso-list")?;
+    let level = LEVEL.get_or_init(|| Regex::new(r"level(\d+)").expect("valid regex"));
+    // Word nests lists at most nine levels deep
+    Some(level.captures(&list).and_then(|level| level[1].parse().ok()).unwrap_or(1).clamp(1, 9))
+}
+
+fn list_marker(element: &Element) -> Option<&str> {
+    element.children.iter().find_map(|child| match child {
+        Node::ListMarker(marker) => Some(marker.as_str()),
+        Node::Element(element) => list_marker(element),
+        Node::Text(_) => None,
+    })
+}
+
+/// Whether a Word list marker numbers its item, like `1.`, `b)` or `iv.`,
+/// rather than being a bullet
+fn is_numbered(marker: &str) -> bool {
+    static NUMBERED: OnceLock<Regex> = OnceLock::new();
+    NUMBERED.get_or_init(|| Regex::new(r"^[0-9A-Za-z]{1,6}[.)]$").expect("valid regex")).is_match(marker)
+}
+
+fn is_blank(node: &Node) -> bool {
+    matches!(node, Node::Text(text) if text.trim().is_empty())
+}
+
+/// `text` with each run of whitespace as one space
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(c);
+            in_space = false;
+        }
+    }
+    collapsed
+}
+
+/// Escape what markdown would read as formatting or HTML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape a paragraph line that would otherwise start a heading, quote or list
+fn escape_line_start(line: &str) -> String {
+    static LIST_ITEM: OnceLock<Regex> = OnceLock::new();
+    let list_item = LIST_ITEM.get_or_init(|| Regex::new(r"^(?:[-+]|\d{1,9}[.)])(?: |$)").expect("valid regex"));
+    if line.starts_with(['#', '>']) || list_item.is_match(line) {
+        match line.find(|c: char| !c.is_ascii_digit()) {
+            Some(mark) if mark > 0 => format!("{}\\{}", &line[..mark], &line[mark..]),
+            _ => format!("\\{line}"),
+        }
+    } else {
+        line.to_string()
+    }
+}
+
+/// Inline markdown as a paragraph: whitespace collapsed, line breaks made
+/// hard breaks, and nothing left that starts a block
+fn paragraph(inline: &str) -> String {
+    inline
+        .split('\n')
+        .map(|line| escape_line_start(collapse_whitespace(line).trim()))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\\\n")
+}
+
+/// Inline markdown on one line
+fn single_line(inline: &str) -> String {
+    collapse_whitespace(inline).trim().to_string()
+}
+
+/// `inner` between `marker`s, with surrounding whitespace kept outside them
+fn wrap(inner: &str, marker: &str) -> String {
+    surround(inner, marker, marker)
+}
+
+/// `inner` between `open` and `close`, with surrounding whitespace kept
+/// outside them, since markdown doesn't allow it inside
+fn surround(inner: &str, open: &str, close: &str) -> String {
+    let trimmed = inner.trim();
+    if trimmed.is_empty() {
+        return inner.to_string();
+    }
+    let leading = if inner.starts_with(char::is_whitespace) { " " } else { "" };
+    let trailing = if inner.ends_with(char::is_whitespace) { " " } else { "" };
+    format!("{leading}{open}{trimmed}{close}{trailing}")
+}
+
+fn code_span(code: &str) -> String {
+    let code = collapse_whitespace(code);
+    if code.trim().is_empty() {
+        return code;
+    }
+    let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run + 1);
+    let pad = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+    format!("{fence}{pad}{code}{pad}{fence}")
+}
+
+/// Text in `element`, with line breaks
+fn text_content(element: &Element) -> String {
+    let mut text = String::new();
+    for child in &element.children {
+        match child {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(br) if br.name == "br" => text.push('\n'),
+            Node::Element(child) => text.push_str(&text_content(child)),
+            Node::ListMarker(_) => {}
+        }
+    }
+    text
+}
+
+/// A list item, with its continuation lines indented past the marker
+fn list_item(marker: &str, body: &str) -> String {
+    if body.is_empty() {
+        return marker.trim_end().to_string();
+    }
+    let indent = " ".repeat(marker.len());
+    body.lines()
+        .enumerate()
+        .map(|(i, line)| match (i, line.is_empty()) {
+            (0, _) => format!("{marker}{line}"),
+            (_, true) => String::new(),
+            _ => format!("{indent}{line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+struct Renderer<'a> {
+    base: Option<&'a Url>,
+}
+
+impl Renderer<'_> {
+    /// Markdown blocks for `nodes`, inline runs between blocks becoming
+    /// paragraphs
+    fn blocks(&self, nodes: &[Node]) -> Vec<String> {
+        let mut blocks = Vec::new();
+        let mut inline = String::new();
+        let flush = |inline: &mut String, blocks: &mut Vec<String>| {
+            let text = paragraph(inline);
+            if !text.is_empty() {
+                blocks.push(text);
+            }
+            inline.clear();
+        };
+        let mut i = 0;
+        while i < nodes.len() {
+            if word_list_level(&nodes[i]).is_some() {
+                flush(&mut inline, &mut blocks);
+                let end = nodes[i..]
+                    .iter()
+                    .position(|node| word_list_level(node).is_none() && !is_blank(node))
+                    .map_or(nodes.len(), |end| i + end);
+                blocks.push(self.word_list(&nodes[i..end]));
+                i = end;
+                continue;
+            }
+            match &nodes[i] {
+                Node::Element(element) if is_block(&element.name) => {
+                    flush(&mut inline, &mut blocks);
+                    blocks.extend(self.block(element));
+                }
+                // Inline elements wrapped around blocks, as Google Docs does
+                Node::Element(element) if element.children.iter().any(|child| matches!(child, Node::Element(c) if is_block(&c.name))) => {
+                    flush(&mut inline, &mut blocks);
+                    blocks.extend(self.blocks(&element.children));
+                }
+                node => inline.push_str(&self.inline(node)),
+            }
+            i += 1;
+        }
+        flush(&mut inline, &mut blocks);
+        blocks
+    }
+
+    fn block(&self, element: &Element) -> Vec<String> {
+        let text = match element.name.as_str() {
+            name @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+                let heading = single_line(&self.children_inline(element));
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                (!heading.is_empty()).then(|| format!("{} {heading}", "#".repeat(level)))
+            }
+            "ul" | "ol" => Some(self.list(element)).filter(|list| !list.is_empty()),
+            "blockquote" => {
+                let quoted = self.blocks(&element.children).join("\n\n");
+                let quoted: Vec<String> =
+                    quoted.lines().map(|line| if line.is_empty() { ">".to_string() } else { format!("> {line}") }).collect();
+                (!quoted.is_empty()).then(|| quoted.join("\n"))
+            }
+            "pre" => {
+                let code = text_content(element);
+                let code = code.strip_prefix('\n').unwrap_or(&code).trim_end();
+                let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+                let fence = "`".repeat(longest_run.max(2) + 1);
+                (!code.trim().is_empty()).then(|| format!("{fence}\n{code}\n{fence}"))
+            }
+            "table" => return self.table(element),
+            "hr" => Some("---".to_string()),
+            _ => return self.blocks(&element.children),
+        };
+        text.into_iter().collect()
+    }
+
+    fn inline(&self, node: &Node) -> String {
+        let element = match node {
+            Node::Text(text) => return escape(&collapse_whitespace(text)),
+            Node::ListMarker(_) => return String::new(),
+            Node::Element(element) => element,
+        };
+        match element.name.as_str() {
+            "br" => "\n".to_string(),
+            "b" | "strong" if element.is_bold() != Some(false) => wrap(&self.children_inline(element), "**"),
+            "i" | "em" | "cite" | "dfn" if element.is_italic() != Some(false) => wrap(&self.children_inline(element), "*"),
+            "s" | "strike" | "del" => wrap(&self.children_inline(element), "~~"),
+            "code" | "kbd" | "samp" | "tt" => code_span(&text_content(element)),
+            "a" => self.link(element),
+            "img" => self.image(element),
+            name if is_block(name) => format!(" {} ", self.children_inline(element)),
+            _ => {
+                let mut text = self.children_inline(element);
+                if element.is_bold() == Some(true) {
+                    text = wrap(&text, "**");
+                }
+                if element.is_italic() == Some(true) {
+                    text = wrap(&text, "*");
+                }
+                text
+            }
+        }
+    }
+
+    fn children_inline(&self, element: &Element) -> String {
+        element.children.iter().map(|child| self.inline(child)).collect()
+    }
+
+    fn link(&self, element: &Element) -> String {
+        let text = self.children_inline(element);
+        // Links within the copied page, like Word's table of contents, lead nowhere here
+        let href = element.attr("href").filter(|href| !href.trim_start().starts_with('#'));
+        match href.and_then(|href| self.url(href, false)) {
+            Some(url) if !text.trim().is_empty() => surround(&text, "[", &format!("]({url})")),
+            _ => text,
+        }
+    }
+
+    fn image(&self, element: &Element) -> String {
+        let Some(src) = element.attr("src").and_then(|src| self.url(src, true)) else {
+            return String::new();
+        };
+        let alt = escape(single_line(element.attr("alt").unwrap_or_default()).as_str());
+        format!("![{alt}]({src})")
+    }
+
+    /// `raw` as a markdown link destination, resolved against the base URL,
+    /// or `None` if it is unsafe to link to
+    fn url(&self, raw: &str, image: bool) -> Option<String> {
+        static SCHEME: OnceLock<Regex> = OnceLock::new();
+        // Browsers ignore these inside URLs, so `java\tscript:` is still a script
+        let raw: String = raw.trim().chars().filter(|c| !matches!(c, '\t' | '\n' | '\r')).collect();
+        if raw.is_empty() {
+            return None;
+        }
+        let lower = raw.to_ascii_lowercase();
+        let url = if SCHEME.get_or_init(|| Regex::new(r"^[a-z][a-z0-9+.-]*:").expect("valid regex")).is_match(&lower) {
+            let safe = ["http:", "https:", "mailto:", "ftp:", "file:", "tel:"].iter().any(|scheme| lower.starts_with(scheme))
+                || (image && lower.starts_with("data:image/"));
+            safe.then_some(raw)?
+        } else {
+            match self.base {
+                Some(base) => base.join(&raw).ok()?.to_string(),
+                None => raw,
+            }
+        };
+        Some(url.replace(' ', "%20").replace('(', "%28").replace(')', "%29").replace('<', "%3C").replace('>', "%3E"))
+    }
+
+    fn list(&self, element: &Element) -> String {
+        let ordered = element.name == "ol";
+        let mut number: u64 = element.attr("start").and_then(|start| start.trim().parse().ok()).unwrap_or(1);
+        let mut items: Vec<String> = Vec::new();
+        let mut marker_width = 2;
+        for child in &element.children {
+            let Node::Element(child) = child else {
+                continue;
+            };
+            match child.name.as_str() {
+                "li" => {
+                    let marker = if ordered { format!("{number}. ") } else { "- ".to_string() };
+                    number += 1;
+                    marker_width = marker.len();
+                    items.push(list_item(&marker, &self.item_body(&child.children)));
+                }
+                // Lists nested straight in lists, as some editors write them
+                "ul" | "ol" => {
+                    let nested = list_item(&" ".repeat(marker_width), &self.list(child));
+                    match items.last_mut() {
+                        Some(item) => {
+                            item.push('\n');
+                            item.push_str(&nested);
+                        }
+                        None => items.push(nested),
+                    }
+                }
+                _ => {}
+            }
+        }
+        items.join("\n")
+    }
+
+    /// Blocks of a list item, with nested lists kept tight
+    fn item_body(&self, nodes: &[Node]) -> String {
+        let mut inline_nodes: Vec<&Node> = Vec::new();
+        // Rendered blocks, and whether each is a list
+        let mut parts: Vec<(bool, String)> = Vec::new();
+        let flush = |inline_nodes: &mut Vec<&Node>, parts: &mut Vec<(bool, String)>| {
+            let text = paragraph(&inline_nodes.iter().map(|node| self.inline(node)).collect::<String>());
+            if !text.is_empty() {
+                parts.push((false, text));
+            }
+            inline_nodes.clear();
+        };
+        for node in nodes {
+            match node {
+                Node::Element(list) if list.name == "ul" || list.name == "ol" => {
+                    flush(&mut inline_nodes, &mut parts);
+                    parts.push((true, self.list(list)));
+                }
+                Node::Element(block) if is_block(&block.name) => {
+                    flush(&mut inline_nodes, &mut parts);
+                    parts.extend(self.block(block).into_iter().map(|text| (false, text)));
+                }
+                node => inline_nodes.push(node),
+            }
+        }
+        flush(&mut inline_nodes, &mut parts);
+        let mut body = String::new();
+        for (i, (is_list, text)) in parts.into_iter().enumerate() {
+            if i > 0 {
+                body.push_str(if is_list { "\n" } else { "\n\n" });
+            }
+            body.push_str(&text);
+        }
+        body
+    }
+
+    /// A run of Word list paragraphs as a markdown list, nested by their
+    /// levels and numbered where Word numbered them
+    fn word_list(&self, nodes: &[Node]) -> String {
+        struct Level {
+            numbered: bool,
+            next: u64,
+            marker_width: usize,
+        }
+        let mut levels: Vec<Level> = Vec::new();
+        let mut lines = Vec::new();
+        for node in nodes {
+            let (Some(depth), Node::Element(element)) = (word_list_level(node), node) else {
+                continue;
+            };
+            let numbered = list_marker(element).is_some_and(is_numbered);
+            levels.truncate(depth);
+            while levels.len() < depth {
+                levels.push(Level { numbered, next: 1, marker_width: 2 });
+            }
+            let level = levels.last_mut().expect("depth is at least 1");
+            if level.numbered != numbered {
+                *level = Level { numbered, next: 1, marker_width: 2 };
+            }
+            let marker = if numbered { format!("{}. ", level.next) } else { "- ".to_string() };
+            level.next += 1;
+            level.marker_width = marker.len();
+            let indent: usize = levels[..depth - 1].iter().map(|level| level.marker_width).sum();
+            let text = single_line(&self.children_inline(element));
+            lines.push(format!("{}{}", " ".repeat(indent), list_item(&marker, &text)));
+        }
+        lines.join("\n")
+    }
+
+    fn table(&self, element: &Element) -> Vec<String> {
+        let mut rows = Vec::new();
+        let mut caption = None;
+        self.collect_rows(element, &mut rows, &mut caption);
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return caption.into_iter().collect();
+        }
+        let mut lines = Vec::with_capacity(rows.len() + 1);
+        // Markdown tables need a header, so the first row is one
+        for (i, mut cells) in rows.into_iter().enumerate() {
+            cells.resize(columns, String::new());
+            lines.push(format!("| {} |", cells.join(" | ")));
+            if i == 0 {
+                lines.push(format!("|{}|", vec![" --- "; columns].join("|")));
+            }
+        }
+        caption.into_iter().chain([lines.join("\n")]).collect()
+    }
+
+    fn collect_rows(&self, element: &Element, rows: &mut Vec<Vec<String>>, caption: &mut Option<String>) {
+        for child in &element.children {
+            let Node::Element(child) = child else {
+                continue;
+            };
+            match child.name.as_str() {
+                "thead" | "tbody" | "tfoot" => self.collect_rows(child, rows, caption),
+                "caption" => *caption = Some(paragraph(&self.children_inline(child))).filter(|caption| !caption.is_empty()),
+                "tr" => rows.push(
+                    child
+                        .children
+                        .iter()
+                        .filter_map(|cell| match cell {
+                            Node::Element(cell) if cell.name == "td" || cell.name == "th" => {
+                                Some(single_line(&self.children_inline(cell)).replace('|', "\\|"))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                ),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_fragment_becomes_clean_markdown() {
+        let html = r##"Version:0.9
+StartHTML:0000000105
+<html xmlns:o="urn:schemas-microsoft-com:office:office"><head><style>p.MsoNormal {mso-style-parent:""; margin:0cm;}</style></head>
+<body lang=EN-GB style='tab-interval:36.0pt'>
+<!--StartFragment-->
+<h1 style='mso-margin-top-alt:auto'><a name="_Toc1"></a><span lang=EN-GB>Installing the pump<o:p></o:p></span></h1>
+<p class=MsoNormal style='margin-bottom:0cm;mso-line-height-rule:exactly'><b style='mso-bidi-font-weight:normal'><span
+style='font-size:11.0pt'>Warning:</span></b><span style='mso-spacerun:yes'>&nbsp; </span>switch off the <i>mains</i> supply first.<o:p></o:p></p>
+<p class=MsoListParagraphCxSpFirst style='text-indent:-18.0pt;mso-list:l0 level1 lfo1'><![if !supportLists]><span
+style='font-family:Symbol'>&middot;<span style='font:7.0pt "Times New Roman"'>&nbsp;&nbsp;&nbsp; </span></span><![endif]>Open the cover<o:p></o:p></p>
+<p class=MsoListParagraphCxSpMiddle style='mso-list:l0 level2 lfo1'><![if !supportLists]><span>1.<span>&nbsp;&nbsp; </span></span><![endif]>Remove the screws<o:p></o:p></p>
+<p class=MsoListParagraphCxSpLast style='mso-list:l0 level2 lfo1'><![if !supportLists]><span>2.<span>&nbsp;&nbsp; </span></span><![endif]>Lift the <span style='font-weight:bold'>front</span> panel<o:p></o:p></p>
+<p class=MsoNormal><![if !vml]><img width=120 height=80 src="images/pump.png" alt="Pump diagram"><![endif]><o:p>&nbsp;</o:p></p>
+<script>document.write("<p>injected</p>")</script>
+<p class=MsoNormal>See <a href="javascript:alert(1)">this</a>, <a href="#_Toc1">the top</a> and <a href="manual.html#install">the manual</a> for 2*3 &lt;b&gt; sizes.<iframe src="https://ads.example.com"><p>ad</p></iframe></p>
+<!--EndFragment-->
+</body></html>"##;
+        let options = PasteOptions { base_url: Some("https://docs.example.com/pumps/".to_string()) };
+
+        assert_eq!(
+            html_to_markdown_with(html, &options),
+            "# Installing the pump\n\n\
+             **Warning:** switch off the *mains* supply first.\n\n\
+             - Open the cover\n  1. Remove the screws\n  2. Lift the **front** panel\n\n\
+             ![Pump diagram](https://docs.example.com/pumps/images/pump.png)\n\n\
+             See this, the top and [the manual](https://docs.example.com/pumps/manual.html#install) for 2\\*3 \\<b> sizes."
+        );
+    }
+
+    #[test]
+    fn test_browser_table_becomes_markdown_table() {
+        let html = r#"<meta charset='utf-8'><table class="wikitable" style="border-collapse: collapse;"><thead><tr><th style="text-align:left">Language</th><th>Code</th><th>Status</th></tr></thead>
+<tbody><tr><td><a href="/wiki/German">German</a></td><td><code>de</code></td><td><strong>Done</strong></td></tr>
+<tr><td>French</td><td>fr</td><td>In review | 80%</td></tr>
+<tr><td>Dutch<br>(Flemish)</td><td>nl</tr></tbody></table>"#;
+        let options = PasteOptions { base_url: Some("https://en.example.org/wiki/Languages".to_string()) };
+
+        assert_eq!(
+            html_to_markdown_with(html, &options),
+            "| Language | Code | Status |\n\
+             | --- | --- | --- |\n\
+             | [German](https://en.example.org/wiki/German) | `de` | **Done** |\n\
+             | French | fr | In review \\| 80% |\n\
+             | Dutch (Flemish) | nl |  |"
+        );
+        // Without a base, relative URLs stay as they were
+        assert!(html_to_markdown(html).contains("[German](/wiki/German)"));
+    }
+
+    #[test]
+    fn test_unclosed_word_list_marker_does_not_panic() {
+        assert_eq!(html_to_markdown("<![if supportLists<![endif]>"), "");
+        assert_eq!(html_to_markdown("<p><![if supportLists<![endif]>Open the cover</p>"), "Open the cover");
+    }
+
+    #[test]
+    fn test_word_list_levels_are_capped_at_nine() {
+        let item = |level: &str| format!("<p style='mso-list:l0 {level} lfo1'><![if !supportLists]>-<![endif]>Item</p>");
+        let deepest = html_to_markdown(&(1..=9).map(|level| item(&format!("level{level}"))).collect::<String>());
+        let html = format!("{}{}", (1..=8).map(|level| item(&format!("level{level}"))).collect::<String>(), item("level1000000000000"));
+
+        assert_eq!(html_to_markdown(&html), deepest);
+        assert!(deepest.ends_with(&format!("{}- Item", " ".repeat(16))));
+    }
+}
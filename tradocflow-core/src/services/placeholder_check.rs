@@ -0,0 +1,194 @@
+use crate::{Result, TradocumentError};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// A kind of placeholder recognized in source and target strings
+#[derive(Debug, Clone)]
+pub struct PlaceholderPattern {
+    pub name: String,
+    pub regex: Regex,
+    /// Whether the placeholders must keep their source order, as with
+    /// printf-style arguments that are consumed left to right
+    pub ordered: bool,
+}
+
+impl PlaceholderPattern {
+    pub fn new(name: &str, pattern: &str, ordered: bool) -> Result<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| TradocumentError::Validation(format!("Invalid placeholder pattern '{name}': {e}")))?;
+        Ok(Self { name: name.to_string(), regex, ordered })
+    }
+}
+
+/// Patterns checked by [`check_placeholders_with_config`]
+#[derive(Debug, Clone)]
+pub struct PlaceholderConfig {
+    pub patterns: Vec<PlaceholderPattern>,
+}
+
+impl Default for PlaceholderConfig {
+    fn default() -> Self {
+        Self { patterns: default_patterns().to_vec() }
+    }
+}
+
+fn default_patterns() -> &'static [PlaceholderPattern] {
+    static PATTERNS: OnceLock<Vec<PlaceholderPattern>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            ("named", r"\{[A-Za-z_][A-Za-z0-9_]*\}", false),
+            ("positional", r"\{\d+\}", false),
+            // `%1$s` names its argument, so only unnumbered specifiers are order-sensitive
+            ("printf", r"%(?:\d+\$)?[-+0#]*\d*(?:\.\d+)?[sdifuxXc]", true),
+            ("tag", r"</?[A-Za-z][A-Za-z0-9_-]*(?:\s[^<>]*)?/?>", false),
+        ]
+        .into_iter()
+        .map(|(name, pattern, ordered)| PlaceholderPattern::new(name, pattern, ordered).expect("valid placeholder regex"))
+        .collect()
+    })
+}
+
+/// A placeholder problem found in a translation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PlaceholderIssue {
+    /// In the source but not the target
+    Missing { placeholder: String },
+    /// In the target but not the source
+    Extra { placeholder: String },
+    /// Same order-sensitive placeholders, different order
+    Reordered { expected: Vec<String>, found: Vec<String> },
+}
+
+struct Placeholder<'a> {
+    pattern: usize,
+    text: &'a str,
+    /// Comparison key; whitespace inside tags is insignificant
+    key: String,
+}
+
+fn extract<'a>(text: &'a str, config: &PlaceholderConfig) -> Vec<(usize, Placeholder<'a>)> {
+    let mut found: Vec<(usize, Placeholder<'a>)> = Vec::new();
+    for (pattern, placeholder) in config.patterns.iter().enumerate() {
+        for m in placeholder.regex.find_iter(text) {
+            // Skip matches inside a placeholder an earlier pattern already claimed
+            if found.iter().any(|(start, p)| m.start() < start + p.text.len() && *start < m.end()) {
+                continue;
+            }
+            found.push((m.start(), Placeholder {
+                pattern,
+                text: m.as_str(),
+                key: m.as_str().split_whitespace().collect(),
+            }));
+        }
+    }
+    found.sort_by_key(|(start, _)| *start);
+    found
+}
+
+/// Compare the placeholders of `target` against `source` using the built-in
+/// patterns: `{named}`, `{0}`, printf specifiers, and XML-like tags.
+pub fn check_placeholders(source: &str, target: &str) -> Vec<PlaceholderIssue> {
+    check_placeholders_with_config(source, target, &PlaceholderConfig::default())
+}
+
+pub fn check_placeholders_with_config(source: &str, target: &str, config: &PlaceholderConfig) -> Vec<PlaceholderIssue> {
+    let source_placeholders: Vec<Placeholder> = extract(source, config).into_iter().map(|(_, p)| p).collect();
+    let target_placeholders: Vec<Placeholder> = extract(target, config).into_iter().map(|(_, p)| p).collect();
+
+    let mut issues = Vec::new();
+    let mut unmatched: Vec<Option<&Placeholder>> = target_placeholders.iter().map(Some).collect();
+    for placeholder in &source_placeholders {
+        let matched = unmatched
+            .iter_mut()
+            .find(|candidate| candidate.is_some_and(|c| c.key == placeholder.key));
+        match matched {
+            Some(slot) => *slot = None,
+            None => issues.push(PlaceholderIssue::Missing { placeholder: placeholder.text.to_string() }),
+        }
+    }
+    issues.extend(
+        unmatched
+            .into_iter()
+            .flatten()
+            .map(|p| PlaceholderIssue::Extra { placeholder: p.text.to_string() }),
+    );
+
+    for (index, pattern) in config.patterns.iter().enumerate() {
+        if !pattern.ordered {
+            continue;
+        }
+        let sequence = |placeholders: &[Placeholder]| -> Vec<String> {
+            placeholders
+                .iter()
+                .filter(|p| p.pattern == index && !p.text.contains('$'))
+                .map(|p| p.key.clone())
+                .collect()
+        };
+        let expected = sequence(&source_placeholders);
+        let found = sequence(&target_placeholders);
+        if expected == found {
+            continue;
+        }
+
+        // Only report order when the same placeholders are present; otherwise
+        // the missing/extra issues already describe the difference
+        let (mut sorted_expected, mut sorted_found) = (expected.clone(), found.clone());
+        sorted_expected.sort();
+        sorted_found.sort();
+        if sorted_expected == sorted_found {
+            issues.push(PlaceholderIssue::Reordered { expected, found });
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_named_placeholder() {
+        let issues = check_placeholders("Deleted {count} files in {folder}", "{folder}: Dateien gelöscht");
+        assert_eq!(issues, vec![PlaceholderIssue::Missing { placeholder: "{count}".to_string() }]);
+    }
+
+    #[test]
+    fn test_extra_printf_placeholder() {
+        let issues = check_placeholders("Saved %s", "%s gespeichert in %s");
+        assert_eq!(issues, vec![PlaceholderIssue::Extra { placeholder: "%s".to_string() }]);
+    }
+
+    #[test]
+    fn test_preserved_placeholders_have_no_issues() {
+        let issues = check_placeholders(
+            "Click <icon/> to copy {0} of {total} items (%d%%)",
+            "Klicken Sie auf <icon /> um {0} von {total} Elementen zu kopieren (%d%%)",
+        );
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn test_printf_order_matters_but_positional_does_not() {
+        assert_eq!(
+            check_placeholders("%s has %d items", "%d Elemente hat %s"),
+            vec![PlaceholderIssue::Reordered {
+                expected: vec!["%s".to_string(), "%d".to_string()],
+                found: vec!["%d".to_string(), "%s".to_string()],
+            }]
+        );
+        assert!(check_placeholders("{0} of {1}", "{1} von {0}").is_empty());
+        assert!(check_placeholders("%1$s has %2$d", "%2$d hat %1$s").is_empty());
+    }
+
+    #[test]
+    fn test_custom_pattern() {
+        let config = PlaceholderConfig {
+            patterns: vec![PlaceholderPattern::new("variable", r"\$\{[a-z.]+\}", false).unwrap()],
+        };
+        let issues = check_placeholders_with_config("Version ${product.version}", "Version", &config);
+        assert_eq!(issues, vec![PlaceholderIssue::Missing { placeholder: "${product.version}".to_string() }]);
+        assert!(PlaceholderPattern::new("broken", "(", false).is_err());
+    }
+}
@@ -0,0 +1,528 @@
+use super::manual_dir::{DOCUMENTS_DIR, MANUAL_FILE};
+use crate::{Result, TradocumentError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Version of the archive layout written by this build
+pub const ARCHIVE_SCHEMA_VERSION: u32 = 1;
+/// Archive entry describing the archive
+pub const MANIFEST_FILE: &str = "manifest.json";
+/// Project files are stored below this prefix
+const PROJECT_PREFIX: &str = "project/";
+/// Present in a project directory while its import is unfinished
+pub const IMPORT_CHECKPOINT_FILE: &str = ".import_checkpoint.json";
+
+/// Contents of a project archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub schema_version: u32,
+    /// Id of the exported project; imports get a new one
+    pub project_id: Uuid,
+    pub exported_at: DateTime<Utc>,
+    /// Chapter files in every language plus section documents
+    pub document_count: usize,
+    /// Entries of the CSV glossaries under `terminology/`
+    pub term_count: usize,
+    /// Project files, relative to the project directory
+    pub files: Vec<String>,
+}
+
+/// Order in which archive entries are imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ImportPhase {
+    Terminology,
+    TranslationMemory,
+    /// Chapters, the manual structure and section documents
+    Documents,
+    /// Project metadata, screenshots and anything else
+    Files,
+}
+
+impl ImportPhase {
+    fn of(file: &str) -> Self {
+        let top = file.split('/').next().unwrap_or_default();
+        match top {
+            "terminology" => ImportPhase::Terminology,
+            "translations" | "translation_memory" => ImportPhase::TranslationMemory,
+            "chapters" | DOCUMENTS_DIR | MANUAL_FILE => ImportPhase::Documents,
+            _ => ImportPhase::Files,
+        }
+    }
+}
+
+/// Reported after each entry of an import
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportProgress {
+    /// Id of the project being imported into
+    pub project_id: Uuid,
+    pub phase: ImportPhase,
+    /// Project file, relative to the project directory
+    pub entry: String,
+    /// Entries done so far, including this one
+    pub completed: usize,
+    pub total: usize,
+    /// The entry was imported by an earlier, interrupted run
+    pub skipped: bool,
+}
+
+/// What an unfinished import has written so far
+#[derive(Debug, Serialize, Deserialize)]
+struct ImportCheckpoint {
+    source_project_id: Uuid,
+    exported_at: DateTime<Utc>,
+    /// Entries already in the project directory
+    imported: BTreeSet<String>,
+}
+
+impl ImportCheckpoint {
+    fn save(&self, project_dir: &Path) -> Result<()> {
+        // Replace the checkpoint in one step so a crash can't truncate it
+        let temp = project_dir.join(format!("{IMPORT_CHECKPOINT_FILE}.tmp"));
+        fs::write(&temp, serde_json::to_string(self)?)?;
+        fs::rename(temp, project_dir.join(IMPORT_CHECKPOINT_FILE))?;
+        Ok(())
+    }
+}
+
+/// Moves projects of a [`ProjectManager`](super::ProjectManager) root between
+/// machines as one zip file.
+///
+/// The archive holds the whole project directory: metadata, chapters in
+/// every language, the manual structure and its documents, terminology, the
+/// translation memory files and screenshots.
+pub struct ProjectArchive {
+    projects_root: PathBuf,
+}
+
+fn archive_error(e: zip::result::ZipError) -> TradocumentError {
+    TradocumentError::FileError(format!("Project archive error: {e}"))
+}
+
+impl ProjectArchive {
+    pub fn new(projects_root: impl AsRef<Path>) -> Self {
+        Self { projects_root: projects_root.as_ref().to_path_buf() }
+    }
+
+    fn project_dir(&self, project_id: Uuid) -> PathBuf {
+        self.projects_root.join(project_id.to_string())
+    }
+
+    /// Write the project to a zip file at `path`
+    pub fn export(&self, project_id: Uuid, path: &Path) -> Result<ArchiveManifest> {
+        let project_dir = self.project_dir(project_id);
+        if !project_dir.is_dir() {
+            return Err(TradocumentError::ProjectNotFound(project_id.to_string()));
+        }
+        if self.is_incomplete(project_id) {
+            return Err(TradocumentError::Validation(format!(
+                "Project {project_id} is an incomplete import; finish importing it before exporting"
+            )));
+        }
+
+        let mut files = Vec::new();
+        collect_files(&project_dir, &project_dir, &mut files)?;
+        files.sort();
+        let (document_count, term_count) = count_contents(&project_dir, &files)?;
+        let manifest = ArchiveManifest {
+            schema_version: ARCHIVE_SCHEMA_VERSION,
+            project_id,
+            exported_at: Utc::now(),
+            document_count,
+            term_count,
+            files,
+        };
+
+        let mut zip = ZipWriter::new(File::create(path)?);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file(MANIFEST_FILE, options).map_err(archive_error)?;
+        zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+        for file in &manifest.files {
+            zip.start_file(format!("{PROJECT_PREFIX}{file}"), options).map_err(archive_error)?;
+            zip.write_all(&fs::read(project_dir.join(file))?)?;
+        }
+        zip.finish().map_err(archive_error)?;
+
+        Ok(manifest)
+    }
+
+    /// The manifest of the archive at `path`
+    pub fn read_manifest(path: &Path) -> Result<ArchiveManifest> {
+        let mut archive = ZipArchive::new(File::open(path)?).map_err(archive_error)?;
+        read_manifest(&mut archive)
+    }
+
+    /// Restore an archive as a new project and return its id.
+    ///
+    /// Occurrences of the exported project's id in the JSON files are
+    /// replaced with the new id, so the copy doesn't claim to be the
+    /// original. Archives of another schema version are refused.
+    pub fn import(&self, path: &Path) -> Result<Uuid> {
+        self.import_with_progress(path, |_| ControlFlow::Continue(()))
+    }
+
+    /// [`import`](Self::import), reporting each entry to `on_progress`.
+    ///
+    /// Terminology is imported first, then the translation memory, the
+    /// documents and the remaining files. Until the last entry is written
+    /// the project holds a checkpoint and counts as
+    /// [incomplete](Self::is_incomplete). Importing the same archive again
+    /// resumes an incomplete import instead of starting over, skipping the
+    /// entries it already wrote. Returning [`ControlFlow::Break`] from
+    /// `on_progress` stops the import there, as an interruption would.
+    pub fn import_with_progress(
+        &self,
+        path: &Path,
+        mut on_progress: impl FnMut(&ImportProgress) -> ControlFlow<()>,
+    ) -> Result<Uuid> {
+        let mut archive = ZipArchive::new(File::open(path)?).map_err(archive_error)?;
+        let manifest = read_manifest(&mut archive)?;
+        if manifest.schema_version != ARCHIVE_SCHEMA_VERSION {
+            return Err(TradocumentError::Validation(format!(
+                "Project archive has schema version {}, but this version of TradocFlow reads version {ARCHIVE_SCHEMA_VERSION}",
+                manifest.schema_version
+            )));
+        }
+
+        let (project_id, mut checkpoint) = match self.find_checkpoint(&manifest)? {
+            Some(resumed) => resumed,
+            None => {
+                let checkpoint = ImportCheckpoint {
+                    source_project_id: manifest.project_id,
+                    exported_at: manifest.exported_at,
+                    imported: BTreeSet::new(),
+                };
+                let project_id = Uuid::new_v4();
+                fs::create_dir_all(self.project_dir(project_id))?;
+                checkpoint.save(&self.project_dir(project_id))?;
+                (project_id, checkpoint)
+            }
+        };
+        let project_dir = self.project_dir(project_id);
+        let (old_id, new_id) = (manifest.project_id.to_string(), project_id.to_string());
+
+        let mut entries: Vec<&String> = manifest.files.iter().collect();
+        entries.sort_by_key(|file| ImportPhase::of(file));
+        for (index, file) in entries.iter().enumerate() {
+            let skipped = checkpoint.imported.contains(*file);
+            if !skipped {
+                restore_file(&mut archive, file, &project_dir, &old_id, &new_id)?;
+                checkpoint.imported.insert(file.to_string());
+                checkpoint.save(&project_dir)?;
+            }
+
+            let progress = ImportProgress {
+                project_id,
+                phase: ImportPhase::of(file),
+                entry: file.to_string(),
+                completed: index + 1,
+                total: entries.len(),
+                skipped,
+            };
+            if on_progress(&progress).is_break() {
+                return Err(TradocumentError::Validation(format!(
+                    "Import of project {project_id} was interrupted after {} of {} entries; import the archive again to resume",
+                    index + 1,
+                    entries.len()
+                )));
+            }
+        }
+
+        fs::remove_file(project_dir.join(IMPORT_CHECKPOINT_FILE))?;
+        Ok(project_id)
+    }
+
+    /// Whether the project is the unfinished result of an import
+    pub fn is_incomplete(&self, project_id: Uuid) -> bool {
+        self.project_dir(project_id).join(IMPORT_CHECKPOINT_FILE).exists()
+    }
+
+    /// Projects whose import has not finished
+    pub fn incomplete_imports(&self) -> Result<Vec<Uuid>> {
+        if !self.projects_root.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut projects = Vec::new();
+        for entry in fs::read_dir(&self.projects_root)? {
+            let entry = entry?;
+            if let Ok(project_id) = entry.file_name().to_string_lossy().parse::<Uuid>() {
+                if self.is_incomplete(project_id) {
+                    projects.push(project_id);
+                }
+            }
+        }
+        projects.sort();
+        Ok(projects)
+    }
+
+    /// The incomplete import of the archive described by `manifest`, if any
+    fn find_checkpoint(&self, manifest: &ArchiveManifest) -> Result<Option<(Uuid, ImportCheckpoint)>> {
+        for project_id in self.incomplete_imports()? {
+            let json = fs::read_to_string(self.project_dir(project_id).join(IMPORT_CHECKPOINT_FILE))?;
+            let checkpoint: ImportCheckpoint = serde_json::from_str(&json)?;
+            if checkpoint.source_project_id == manifest.project_id && checkpoint.exported_at == manifest.exported_at {
+                return Ok(Some((project_id, checkpoint)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn read_manifest(archive: &mut ZipArchive<File>) -> Result<ArchiveManifest> {
+    let mut entry = archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| TradocumentError::Validation("Not a project archive: the manifest is missing".to_string()))?;
+    let mut json = String::new();
+    entry.read_to_string(&mut json)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Extract the project file `file` into `project_dir`
+fn restore_file(archive: &mut ZipArchive<File>, file: &str, project_dir: &Path, old_id: &str, new_id: &str) -> Result<()> {
+    let mut entry = archive.by_name(&format!("{PROJECT_PREFIX}{file}")).map_err(archive_error)?;
+    // Refuse entries that would land outside the project
+    let relative = entry
+        .enclosed_name()
+        .and_then(|name| name.strip_prefix(PROJECT_PREFIX).ok())
+        .map(Path::to_path_buf)
+        .filter(|relative| relative.as_os_str() != IMPORT_CHECKPOINT_FILE)
+        .ok_or_else(|| TradocumentError::Validation(format!("Project archive entry {file} has an unsafe path")))?;
+
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes)?;
+    if relative.extension().and_then(|ext| ext.to_str()) == Some("json") {
+        if let Ok(text) = String::from_utf8(bytes.clone()) {
+            bytes = text.replace(old_id, new_id).into_bytes();
+        }
+    }
+
+    let target = project_dir.join(&relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(target, bytes)?;
+    Ok(())
+}
+
+/// Paths of all files below `dir`, relative to `base` and `/`-separated
+fn collect_files(dir: &Path, base: &Path, out: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, base, out)?;
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+            out.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+/// Documents and terms among a project's files
+fn count_contents(project_dir: &Path, files: &[String]) -> Result<(usize, usize)> {
+    let mut documents = 0;
+    let mut terms = 0;
+    for file in files {
+        if (file.starts_with("chapters/") && file.ends_with(".md"))
+            || (file.starts_with("documents/") && file.ends_with(".json"))
+        {
+            documents += 1;
+        } else if file.starts_with("terminology/") && file.ends_with(".csv") {
+            let csv = fs::read_to_string(project_dir.join(file))?;
+            // Every non-empty line after the header is a term
+            terms += csv.lines().skip(1).filter(|line| !line.trim().is_empty()).count();
+        }
+    }
+    Ok((documents, terms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::document::{Chapter, ChapterStatus};
+    use crate::models::project::{Priority, Project, ProjectStatus};
+    use crate::services::manual_dir::{load_manual_dir, save_manual_dir};
+    use crate::services::templates::scaffold;
+    use crate::services::ProjectManager;
+    use crate::{Document, DocumentMetadata, ManualTemplate};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    async fn seed_project(root: &Path) -> Uuid {
+        let project = Project {
+            id: Uuid::new_v4(),
+            name: "Bell Tower Controller".to_string(),
+            description: None,
+            status: ProjectStatus::Active,
+            owner_id: "tester".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            due_date: None,
+            priority: Priority::Medium,
+            metadata: HashMap::new(),
+        };
+        let manager = ProjectManager::new(root);
+        manager.initialize_project(&project, "en", &["de".to_string()]).await.unwrap();
+        for (number, slug) in [(1, "introduction"), (2, "wiring")] {
+            let chapter = Chapter {
+                id: Uuid::new_v4(),
+                document_id: Uuid::new_v4(),
+                chapter_number: number,
+                title: HashMap::from([("en".to_string(), slug.to_string())]),
+                slug: slug.to_string(),
+                content: HashMap::from([("en".to_string(), format!("# {slug}\n"))]),
+                order: number,
+                status: ChapterStatus::Draft,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+            manager.create_chapter(project.id, &chapter).await.unwrap();
+        }
+
+        let project_dir = root.join(project.id.to_string());
+        let mut manual = scaffold(&ManualTemplate::InstallationGuide, &["en".to_string()]);
+        let document_id = Uuid::new_v4();
+        manual.sections[0].document_id = Some(document_id);
+        let document = Document {
+            title: "Introduction".to_string(),
+            content: HashMap::from([("en".to_string(), "Welcome.".to_string())]),
+            metadata: DocumentMetadata {
+                project_id: Some(project.id.to_string()),
+                screenshots: Vec::new(),
+                version: None,
+                custom: HashMap::new(),
+            },
+        };
+        save_manual_dir(&project_dir, &manual, &HashMap::from([(document_id, document)])).unwrap();
+
+        fs::create_dir_all(project_dir.join("terminology")).unwrap();
+        fs::write(
+            project_dir.join("terminology/terms.csv"),
+            "term,definition\nclapper,Striker inside the bell\nyoke,Beam the bell hangs from\nrelay,Switches the bell motor\n",
+        )
+        .unwrap();
+        fs::create_dir_all(project_dir.join("screenshots/en")).unwrap();
+        fs::write(project_dir.join("screenshots/en/main.svg"), "<svg></svg>").unwrap();
+
+        project.id
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_restores_project_under_new_id() {
+        let source_root = TempDir::new().unwrap();
+        let target_root = TempDir::new().unwrap();
+        let archive_path = source_root.path().join("project.zip");
+        let project_id = seed_project(source_root.path()).await;
+
+        let exported = ProjectArchive::new(source_root.path()).export(project_id, &archive_path).unwrap();
+        // Two chapters in two languages plus one section document
+        assert_eq!((exported.document_count, exported.term_count), (5, 3));
+
+        let archive = ProjectArchive::new(target_root.path());
+        let imported_id = archive.import(&archive_path).unwrap();
+        assert_ne!(imported_id, project_id);
+
+        let imported_dir = target_root.path().join(imported_id.to_string());
+        let mut files = Vec::new();
+        collect_files(&imported_dir, &imported_dir, &mut files).unwrap();
+        files.sort();
+        assert_eq!(files, exported.files);
+        assert_eq!(count_contents(&imported_dir, &files).unwrap(), (exported.document_count, exported.term_count));
+        assert!(imported_dir.join("screenshots/en/main.svg").exists());
+
+        let project_json = fs::read_to_string(imported_dir.join("project.json")).unwrap();
+        assert!(project_json.contains(&imported_id.to_string()));
+        assert!(!project_json.contains(&project_id.to_string()));
+        let (_, documents) = load_manual_dir(&imported_dir).unwrap();
+        let document = documents.values().next().unwrap();
+        assert_eq!(document.metadata.project_id, Some(imported_id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_import_resumes_without_duplicates() {
+        let source_root = TempDir::new().unwrap();
+        let target_root = TempDir::new().unwrap();
+        let archive_path = source_root.path().join("project.zip");
+        let project_id = seed_project(source_root.path()).await;
+        let exported = ProjectArchive::new(source_root.path()).export(project_id, &archive_path).unwrap();
+        let archive = ProjectArchive::new(target_root.path());
+
+        // Stop once the terminology is in
+        let mut first_run = Vec::new();
+        let error = archive
+            .import_with_progress(&archive_path, |progress| {
+                first_run.push(progress.clone());
+                if progress.phase == ImportPhase::Terminology {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })
+            .unwrap_err();
+        assert!(error.to_string().contains("interrupted"));
+        assert_eq!(first_run.len(), 1);
+        let imported_id = first_run[0].project_id;
+        assert!(archive.is_incomplete(imported_id));
+        assert_eq!(archive.incomplete_imports().unwrap(), vec![imported_id]);
+        let partial_dir = target_root.path().join(imported_id.to_string());
+        assert!(partial_dir.join("terminology/terms.csv").exists());
+        assert!(!partial_dir.join("chapters").exists());
+        let export_error = archive.export(imported_id, &target_root.path().join("partial.zip")).unwrap_err();
+        assert!(export_error.to_string().contains("incomplete"));
+
+        let mut second_run = Vec::new();
+        let resumed_id = archive
+            .import_with_progress(&archive_path, |progress| {
+                second_run.push(progress.clone());
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(resumed_id, imported_id);
+        assert!(!archive.is_incomplete(resumed_id));
+        assert!(archive.incomplete_imports().unwrap().is_empty());
+
+        assert_eq!(second_run.len(), exported.files.len());
+        assert!(second_run[0].skipped && second_run[0].phase == ImportPhase::Terminology);
+        assert!(second_run[1..].iter().all(|progress| !progress.skipped));
+        assert!(second_run.windows(2).all(|pair| pair[0].phase <= pair[1].phase));
+        assert_eq!(second_run.last().unwrap().completed, exported.files.len());
+
+        let mut files = Vec::new();
+        collect_files(&partial_dir, &partial_dir, &mut files).unwrap();
+        files.sort();
+        assert_eq!(files, exported.files);
+        assert_eq!(count_contents(&partial_dir, &files).unwrap(), (exported.document_count, exported.term_count));
+        let terms = fs::read_to_string(partial_dir.join("terminology/terms.csv")).unwrap();
+        assert_eq!(terms.lines().filter(|line| line.starts_with("clapper,")).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_refuses_other_schema_version() {
+        let root = TempDir::new().unwrap();
+        let archive_path = root.path().join("future.zip");
+        let manifest = ArchiveManifest {
+            schema_version: ARCHIVE_SCHEMA_VERSION + 1,
+            project_id: Uuid::new_v4(),
+            exported_at: Utc::now(),
+            document_count: 0,
+            term_count: 0,
+            files: Vec::new(),
+        };
+        let mut zip = ZipWriter::new(File::create(&archive_path).unwrap());
+        zip.start_file(MANIFEST_FILE, FileOptions::default()).unwrap();
+        zip.write_all(serde_json::to_string(&manifest).unwrap().as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let error = ProjectArchive::new(root.path()).import(&archive_path).unwrap_err();
+        assert!(error.to_string().contains("schema version 2"));
+        // Nothing but the archive itself was written
+        assert_eq!(fs::read_dir(root.path()).unwrap().count(), 1);
+    }
+}
@@ -166,7 +166,11 @@ impl ProjectManager {
     
     /// Load translation units from the translations folder
     pub async fn load_translation_units(&self, project_id: Uuid) -> Result<Vec<TranslationUnit>> {
-        let project_path = self.projects_root.join(project_id.to_string());
+        Self::load_translation_units_at(&self.projects_root.join(project_id.to_string())).await
+    }
+
+    /// Load the translation units of the project folder at `project_path`
+    pub async fn load_translation_units_at(project_path: &Path) -> Result<Vec<TranslationUnit>> {
         let translation_units_path = project_path.join("translations/translation_units.json");
         
         if !translation_units_path.exists() {
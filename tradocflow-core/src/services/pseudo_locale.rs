@@ -0,0 +1,245 @@
+use crate::{Document, Manual, ManualSection};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Language code pseudo-localized content is stored and exported under
+pub const PSEUDO_LOCALE: &str = "qps-ploc";
+
+/// Accented text appended to reach the expansion, cycled as needed. It has
+/// spaces so padded lines wrap the way translated ones do.
+const PADDING: &str = " ļöŕéɱ îþšûɱ ðöļöŕ šîţ åɱéţ";
+
+/// Inline text left exactly as it is: DNT spans, code, placeholders, tags,
+/// entities, link targets and markdown punctuation
+fn token_regex() -> &'static Regex {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    TOKEN.get_or_init(|| {
+        Regex::new(concat!(
+            r"\{\{dnt\}\}.*?\{\{/dnt\}\}",
+            r"|`[^`]*`",
+            r"|\{\{[^}]*\}\}",
+            r"|§\{[^}]*\}",
+            r"|\{#[^}]*\}",
+            r"|\{[A-Za-z_][A-Za-z0-9_]*\}|\{\d+\}",
+            r"|%(?:\d+\$)?[-+0#]*\d*(?:\.\d+)?[sdifuxXc]",
+            r"|</?[A-Za-z][A-Za-z0-9_-]*(?:\s[^<>]*)?/?>",
+            r"|<[A-Za-z][A-Za-z0-9+.-]*:[^<>\s]*>",
+            r"|&(?:[A-Za-z]+|#\d+|#x[0-9A-Fa-f]+);",
+            r"|\]\([^)]*\)|\]\[[^\]]*\]|\[\^[^\]]*\]|\[![A-Za-z]+\]|!?\[|\]",
+            r"|\\.",
+            r"|\$[^$\s][^$]*\$",
+            r"|https?://\S+",
+            r"|[*_~|]+",
+        ))
+        .expect("valid pseudo-locale token regex")
+    })
+}
+
+/// Block markers opening a line: indentation, quotes, headings, list
+/// bullets and task boxes
+fn prefix_regex() -> &'static Regex {
+    static PREFIX: OnceLock<Regex> = OnceLock::new();
+    PREFIX.get_or_init(|| {
+        Regex::new(r"^[ \t]*(?:>[ \t]?)*(?:#{1,6}[ \t]+|(?:[-*+]|\d+[.)])[ \t]+(?:\[[ xX]\][ \t]+)?)?")
+            .expect("valid pseudo-locale prefix regex")
+    })
+}
+
+/// Heading id at the end of a heading, kept outside the brackets
+fn heading_id_regex() -> &'static Regex {
+    static HEADING_ID: OnceLock<Regex> = OnceLock::new();
+    HEADING_ID.get_or_init(|| Regex::new(r"[ \t]*\{#[^}]*\}[ \t]*$").expect("valid heading id regex"))
+}
+
+/// How [`pseudolocalize`] alters text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PseudoLocaleOptions {
+    /// Length of the pseudo-localized text relative to the source; 1.3
+    /// makes it 30% longer, as German or French often are
+    pub expansion: f64,
+    /// Replace letters with accented look-alikes
+    pub accents: bool,
+    /// Wrap each string in `[` `]` so cut-off text shows
+    pub brackets: bool,
+}
+
+impl Default for PseudoLocaleOptions {
+    fn default() -> Self {
+        Self { expansion: 1.3, accents: true, brackets: true }
+    }
+}
+
+/// Pseudo-localize `text`, a UI string or markdown, to test layouts before
+/// real translations exist.
+///
+/// Each line, or table cell, reads like a translation that is still
+/// legible: letters are accented, it is padded to `expansion` times its
+/// length and bracketed. Placeholders, DNT spans, inline and fenced code,
+/// link targets, tags and markdown syntax are left as they are.
+pub fn pseudolocalize(text: &str, options: &PseudoLocaleOptions) -> String {
+    let mut localized = String::with_capacity(text.len() * 2);
+    let mut fence: Option<&str> = None;
+
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let ending = &line[content.len()..];
+        let trimmed = content.trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            localized.push_str(line);
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            localized.push_str(line);
+            continue;
+        }
+
+        let prefix = prefix_regex().find(content).map_or("", |m| m.as_str());
+        let body = &content[prefix.len()..];
+        localized.push_str(prefix);
+        if body.trim_start().starts_with('|') {
+            let cells: Vec<String> = body.split('|').map(|cell| pseudolocalize_span(cell, options)).collect();
+            localized.push_str(&cells.join("|"));
+        } else {
+            let heading_id = heading_id_regex().find(body).map_or("", |m| m.as_str());
+            localized.push_str(&pseudolocalize_span(&body[..body.len() - heading_id.len()], options));
+            localized.push_str(heading_id);
+        }
+        localized.push_str(ending);
+    }
+    localized
+}
+
+/// Pseudo-localize one line or cell; text without letters is kept as is
+fn pseudolocalize_span(span: &str, options: &PseudoLocaleOptions) -> String {
+    let core = span.trim();
+    if core.is_empty() {
+        return span.to_string();
+    }
+    let leading = &span[..span.len() - span.trim_start().len()];
+    let trailing = &span[span.trim_end().len()..];
+
+    let mut localized = String::new();
+    let mut text_chars = 0;
+    let mut has_letters = false;
+    let mut last = 0;
+    let mut push_text = |text: &str, localized: &mut String| {
+        text_chars += text.chars().count();
+        has_letters |= text.chars().any(char::is_alphabetic);
+        if options.accents {
+            localized.extend(text.chars().map(accented));
+        } else {
+            localized.push_str(text);
+        }
+    };
+    for token in token_regex().find_iter(core) {
+        push_text(&core[last..token.start()], &mut localized);
+        localized.push_str(token.as_str());
+        last = token.end();
+    }
+    push_text(&core[last..], &mut localized);
+    if !has_letters {
+        return span.to_string();
+    }
+
+    let padding = (text_chars as f64 * (options.expansion - 1.0)).ceil().max(0.0) as usize;
+    localized.extend(PADDING.chars().cycle().take(padding));
+    if options.brackets {
+        format!("{leading}[{localized}]{trailing}")
+    } else {
+        format!("{leading}{localized}{trailing}")
+    }
+}
+
+/// An accented look-alike of `c`, or `c` itself
+fn accented(c: char) -> char {
+    match c {
+        'a' => 'å', 'b' => 'ƀ', 'c' => 'ç', 'd' => 'ð', 'e' => 'é', 'f' => 'ƒ', 'g' => 'ĝ',
+        'h' => 'ĥ', 'i' => 'î', 'j' => 'ĵ', 'k' => 'ķ', 'l' => 'ļ', 'm' => 'ɱ', 'n' => 'ñ',
+        'o' => 'ö', 'p' => 'þ', 'q' => 'ǫ', 'r' => 'ŕ', 's' => 'š', 't' => 'ţ', 'u' => 'û',
+        'v' => 'ṽ', 'w' => 'ŵ', 'x' => 'ẋ', 'y' => 'ý', 'z' => 'ž',
+        'A' => 'Å', 'B' => 'Ɓ', 'C' => 'Ç', 'D' => 'Ð', 'E' => 'É', 'F' => 'Ƒ', 'G' => 'Ĝ',
+        'H' => 'Ĥ', 'I' => 'Î', 'J' => 'Ĵ', 'K' => 'Ķ', 'L' => 'Ļ', 'M' => 'Ṁ', 'N' => 'Ñ',
+        'O' => 'Ö', 'P' => 'Þ', 'Q' => 'Ǫ', 'R' => 'Ŕ', 'S' => 'Š', 'T' => 'Ţ', 'U' => 'Û',
+        'V' => 'Ṽ', 'W' => 'Ŵ', 'X' => 'Ẋ', 'Y' => 'Ý', 'Z' => 'Ž',
+        other => other,
+    }
+}
+
+/// Add a [`PSEUDO_LOCALE`] version of a manual, from its `source_language`
+/// section titles, documents and screenshot alt text, so it can be
+/// exported like any language
+pub fn pseudolocalize_manual(
+    manual: &mut Manual,
+    documents: &mut HashMap<Uuid, Document>,
+    source_language: &str,
+    options: &PseudoLocaleOptions,
+) {
+    fn visit(sections: &mut [ManualSection], source_language: &str, options: &PseudoLocaleOptions) {
+        for section in sections {
+            let title = section.localized_titles.get(source_language).unwrap_or(&section.title);
+            let title = pseudolocalize(title, options);
+            section.localized_titles.insert(PSEUDO_LOCALE.to_string(), title);
+            visit(&mut section.subsections, source_language, options);
+        }
+    }
+
+    visit(&mut manual.sections, source_language, options);
+    for document in documents.values_mut() {
+        if let Some(source) = document.content.get(source_language) {
+            let localized = pseudolocalize(source, options);
+            document.content.insert(PSEUDO_LOCALE.to_string(), localized);
+        }
+        for screenshot in &mut document.metadata.screenshots {
+            if let Some(alt_text) = screenshot.alt_text.get(source_language) {
+                let localized = pseudolocalize(alt_text, options);
+                screenshot.alt_text.insert(PSEUDO_LOCALE.to_string(), localized);
+            }
+        }
+    }
+    if !manual.languages.iter().any(|language| language == PSEUDO_LOCALE) {
+        manual.languages.push(PSEUDO_LOCALE.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders_and_markdown_preserved() {
+        let markdown = "## Welcome to {product} {#welcome}\n\n\
+            - Press **Start** and read [the guide](guide.md#setup).\n\
+            - Run `bell --ring` on {{var:version}} of {{dnt}}BellTower{{/dnt}}.\n\n\
+            | Key | Action |\n|---|---|\n\n\
+            ```\nkeep this\n```\n";
+        let options = PseudoLocaleOptions { expansion: 1.0, ..Default::default() };
+        assert_eq!(
+            pseudolocalize(markdown, &options),
+            "## [Ŵéļçöɱé ţö {product}] {#welcome}\n\n\
+            - [Þŕéšš **Šţåŕţ** åñð ŕéåð [ţĥé ĝûîðé](guide.md#setup).]\n\
+            - [Ŕûñ `bell --ring` öñ {{var:version}} öƒ {{dnt}}BellTower{{/dnt}}.]\n\n\
+            | [Ķéý] | [Åçţîöñ] |\n|---|---|\n\n\
+            ```\nkeep this\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_output_expanded_by_factor() {
+        let options = PseudoLocaleOptions { expansion: 1.5, ..Default::default() };
+        let localized = pseudolocalize("Save changes", &options);
+        assert_eq!(localized, "[Šåṽé çĥåñĝéš ļöŕéɱ]");
+        let inner = localized.trim_start_matches('[').trim_end_matches(']');
+        assert_eq!(inner.chars().count(), 18);
+
+        // Placeholders don't count towards the length that is expanded
+        let localized = pseudolocalize("Hello {name}", &PseudoLocaleOptions { expansion: 2.0, accents: false, brackets: false });
+        assert_eq!(localized, "Hello {name} ļöŕéɱ");
+    }
+}
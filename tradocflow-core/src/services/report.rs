@@ -0,0 +1,214 @@
+//! Versioned JSON output of service results, for scripts and dashboards.
+//!
+//! Every report is an object of the form
+//!
+//! ```json
+//! { "schema_version": 1, "report": "<kind>", "results": ... }
+//! ```
+//!
+//! where `report` is one of `statistics`, `translation_progress`,
+//! `inconsistencies` or `lint`. `results` is an object for `statistics` and
+//! an array otherwise; lists are always arrays, empty when there is nothing
+//! to report, never `null`. Fields are only added within a schema version;
+//! renaming or removing one bumps [`REPORT_SCHEMA_VERSION`].
+
+use super::manual_progress::ManualProgress;
+use super::markdown_lint::LintWarning;
+use super::translation_memory_integration_service::TranslationStatistics;
+use crate::{Result, TradocumentError};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tradocflow_translation_memory::InconsistencyGroup;
+use uuid::Uuid;
+
+/// Version of the report schema written by this build
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportKind {
+    Statistics,
+    TranslationProgress,
+    Inconsistencies,
+    Lint,
+}
+
+/// Lint warnings of one section document in one language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionLint {
+    pub section_id: Uuid,
+    pub section: String,
+    pub language: String,
+    pub warnings: Vec<LintWarning>,
+}
+
+/// A service result wrapped in the versioned report envelope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonReport<T> {
+    pub schema_version: u32,
+    pub report: ReportKind,
+    pub results: T,
+}
+
+impl<T> JsonReport<T> {
+    fn new(report: ReportKind, results: T) -> Self {
+        Self { schema_version: REPORT_SCHEMA_VERSION, report, results }
+    }
+}
+
+impl JsonReport<TranslationStatistics> {
+    pub fn statistics(statistics: TranslationStatistics) -> Self {
+        Self::new(ReportKind::Statistics, statistics)
+    }
+}
+
+impl JsonReport<Vec<ManualProgress>> {
+    /// Progress of a manual, one entry per language
+    pub fn translation_progress(progress: Vec<ManualProgress>) -> Self {
+        Self::new(ReportKind::TranslationProgress, progress)
+    }
+}
+
+impl JsonReport<Vec<InconsistencyGroup>> {
+    pub fn inconsistencies(groups: Vec<InconsistencyGroup>) -> Self {
+        Self::new(ReportKind::Inconsistencies, groups)
+    }
+}
+
+impl JsonReport<Vec<SectionLint>> {
+    pub fn lint(results: Vec<SectionLint>) -> Self {
+        Self::new(ReportKind::Lint, results)
+    }
+}
+
+impl<T: Serialize> JsonReport<T> {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl<T: DeserializeOwned> JsonReport<T> {
+    /// Read a report, refusing ones written with a newer schema
+    pub fn from_json(json: &str) -> Result<Self> {
+        let report: Self = serde_json::from_str(json)?;
+        if report.schema_version > REPORT_SCHEMA_VERSION {
+            return Err(TradocumentError::Validation(format!(
+                "Report schema version {} is newer than the supported version {REPORT_SCHEMA_VERSION}",
+                report.schema_version
+            )));
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::manual_progress::SectionProgress;
+    use crate::services::markdown_lint::lint_markdown;
+    use serde_json::{json, Value};
+
+    /// The report's JSON, and the report read back from it
+    fn round_trip<T: Serialize + DeserializeOwned>(report: &JsonReport<T>) -> (Value, JsonReport<T>) {
+        let json = report.to_json().unwrap();
+        (serde_json::from_str(&json).unwrap(), JsonReport::from_json(&json).unwrap())
+    }
+
+    #[test]
+    fn test_statistics_report() {
+        let statistics = TranslationStatistics {
+            cached_suggestions: 12,
+            active_indicators: 3,
+            auto_created_units: 0,
+            average_confidence: 0.85,
+        };
+        let report = JsonReport::statistics(statistics.clone());
+        let (value, read) = round_trip(&report);
+
+        assert_eq!(value["schema_version"], json!(REPORT_SCHEMA_VERSION));
+        assert_eq!(value["report"], json!("statistics"));
+        assert_eq!(value["results"]["cached_suggestions"], json!(12));
+        assert_eq!(read, report);
+    }
+
+    #[test]
+    fn test_translation_progress_report() {
+        let section_id = Uuid::new_v4();
+        let progress = ManualProgress {
+            manual_id: Uuid::new_v4(),
+            language: "de".to_string(),
+            translated_segments: 3,
+            total_segments: 4,
+            percentage: Some(75.0),
+            sections: vec![SectionProgress {
+                section_id,
+                title: "Setup".to_string(),
+                translated_segments: 3,
+                total_segments: 4,
+                percentage: Some(75.0),
+                subsections: Vec::new(),
+            }],
+        };
+        let report = JsonReport::translation_progress(vec![progress]);
+        let (value, read) = round_trip(&report);
+
+        assert_eq!(value["report"], json!("translation_progress"));
+        assert_eq!(value["results"][0]["percentage"], json!(75.0));
+        assert_eq!(value["results"][0]["sections"][0]["section_id"], json!(section_id));
+        assert_eq!(value["results"][0]["sections"][0]["subsections"], json!([]));
+        assert_eq!(read, report);
+    }
+
+    #[test]
+    fn test_inconsistencies_report() {
+        let group: InconsistencyGroup = serde_json::from_value(json!({
+            "source_text": "Press Start",
+            "language_pair": { "source": "English", "target": "German" },
+            "variants": [
+                { "target_text": "Start drücken", "locations": [] },
+                { "target_text": "Auf Start drücken", "locations": [] }
+            ]
+        }))
+        .unwrap();
+        let report = JsonReport::inconsistencies(vec![group]);
+        let (value, read) = round_trip(&report);
+
+        assert_eq!(value["report"], json!("inconsistencies"));
+        assert_eq!(value["results"][0]["variants"][1]["target_text"], json!("Auf Start drücken"));
+        assert_eq!(read, report);
+
+        let (empty, _) = round_trip(&JsonReport::inconsistencies(Vec::new()));
+        assert_eq!(empty["results"], json!([]));
+    }
+
+    #[test]
+    fn test_lint_report() {
+        let report = JsonReport::lint(vec![
+            SectionLint {
+                section_id: Uuid::new_v4(),
+                section: "Setup".to_string(),
+                language: "en".to_string(),
+                warnings: lint_markdown("# Setup\n\nSee https://example.com\t\n"),
+            },
+            SectionLint {
+                section_id: Uuid::new_v4(),
+                section: "Wiring".to_string(),
+                language: "en".to_string(),
+                warnings: Vec::new(),
+            },
+        ]);
+        let (value, read) = round_trip(&report);
+
+        assert_eq!(value["report"], json!("lint"));
+        assert!(!value["results"][0]["warnings"].as_array().unwrap().is_empty());
+        assert_eq!(value["results"][0]["warnings"][0]["line"], json!(3));
+        assert_eq!(value["results"][1]["warnings"], json!([]));
+        assert_eq!(read, report);
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let json = json!({ "schema_version": REPORT_SCHEMA_VERSION + 1, "report": "lint", "results": [] }).to_string();
+        assert!(JsonReport::<Vec<SectionLint>>::from_json(&json).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::git_integration::models::ReviewStatus;
+use crate::git_integration::ReviewRequest;
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+/// How long a review may wait for its reviewer to pick it up, unless configured otherwise
+const DEFAULT_PENDING_SLA: Duration = Duration::days(1);
+/// How long a review may stay with its reviewer, unless configured otherwise
+const DEFAULT_IN_REVIEW_SLA: Duration = Duration::days(3);
+/// How long a review may wait for the translator's changes, unless configured otherwise
+const DEFAULT_CHANGES_REQUESTED_SLA: Duration = Duration::days(5);
+
+/// How long a review request may stay in each open status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewSla {
+    pub pending: Duration,
+    pub in_review: Duration,
+    pub changes_requested: Duration,
+}
+
+impl Default for ReviewSla {
+    fn default() -> Self {
+        Self {
+            pending: DEFAULT_PENDING_SLA,
+            in_review: DEFAULT_IN_REVIEW_SLA,
+            changes_requested: DEFAULT_CHANGES_REQUESTED_SLA,
+        }
+    }
+}
+
+impl ReviewSla {
+    /// Time allowed in `status`, or `None` for a closed status
+    pub fn limit(&self, status: &ReviewStatus) -> Option<Duration> {
+        match status {
+            ReviewStatus::Pending => Some(self.pending),
+            ReviewStatus::InReview => Some(self.in_review),
+            ReviewStatus::ChangesRequested => Some(self.changes_requested),
+            ReviewStatus::Approved | ReviewStatus::Rejected => None,
+        }
+    }
+}
+
+/// How long an open review request has been in its status
+#[derive(Debug, Clone)]
+pub struct ReviewAge {
+    pub review_id: Uuid,
+    pub pr_number: u64,
+    pub chapter: String,
+    pub language: String,
+    pub status: ReviewStatus,
+    /// Time since the request entered its status
+    pub time_in_status: Duration,
+    /// Time allowed in the status
+    pub sla: Duration,
+    /// Whether the request has been in its status longer than allowed
+    pub breached: bool,
+    /// Assigned reviewers who haven't picked the request up yet
+    pub unresponsive_reviewers: Vec<String>,
+}
+
+impl ReviewAge {
+    /// Time past the SLA; negative while the request is within it
+    pub fn overdue_by(&self) -> Duration {
+        self.time_in_status - self.sla
+    }
+}
+
+/// The open review requests with how long each has been in its status as
+/// of `now`, the most overdue first. A request is breached once it has been
+/// in its status longer than `sla` allows. Closed requests are left out.
+pub fn aging_report(reviews: &[ReviewRequest], sla: &ReviewSla, now: DateTime<Utc>) -> Vec<ReviewAge> {
+    let mut ages: Vec<ReviewAge> = reviews
+        .iter()
+        .filter_map(|review| {
+            let limit = sla.limit(&review.status)?;
+            let time_in_status = now - review.status_since();
+            let unresponsive_reviewers = match review.status {
+                ReviewStatus::Pending => review.reviewer.iter().cloned().collect(),
+                _ => Vec::new(),
+            };
+            Some(ReviewAge {
+                review_id: review.id,
+                pr_number: review.pr_number,
+                chapter: review.chapter.clone(),
+                language: review.language.clone(),
+                status: review.status.clone(),
+                time_in_status,
+                sla: limit,
+                breached: time_in_status > limit,
+                unresponsive_reviewers,
+            })
+        })
+        .collect();
+    ages.sort_by(|a, b| b.overdue_by().cmp(&a.overdue_by()).then(a.pr_number.cmp(&b.pr_number)));
+    ages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(pr_number: u64, status: ReviewStatus, since: DateTime<Utc>) -> ReviewRequest {
+        let mut review = ReviewRequest {
+            id: Uuid::new_v4(),
+            pr_number,
+            branch: format!("translate/intro/de/{pr_number}"),
+            chapter: "intro".to_string(),
+            language: "de".to_string(),
+            translator: "alice".to_string(),
+            reviewer: Some("ben".to_string()),
+            status: ReviewStatus::Pending,
+            created_at: since - Duration::days(1),
+            changes_summary: String::new(),
+            status_changed_at: None,
+        };
+        review.set_status(status, since);
+        review
+    }
+
+    #[test]
+    fn test_review_past_sla_is_breached_and_listed_first() {
+        let now = Utc::now();
+        let reviews = vec![
+            review(1, ReviewStatus::InReview, now - Duration::hours(2)),
+            review(2, ReviewStatus::InReview, now - Duration::days(4)),
+            review(3, ReviewStatus::ChangesRequested, now - Duration::days(7)),
+            review(4, ReviewStatus::Approved, now - Duration::days(30)),
+        ];
+
+        let report = aging_report(&reviews, &ReviewSla::default(), now);
+
+        let order: Vec<u64> = report.iter().map(|age| age.pr_number).collect();
+        assert_eq!(order, vec![3, 2, 1]);
+        assert!(report[1].breached && matches!(report[1].status, ReviewStatus::InReview));
+        assert_eq!(report[1].overdue_by(), Duration::days(1));
+        assert!(!report[2].breached);
+        assert_eq!(report[2].time_in_status, Duration::hours(2));
+    }
+
+    #[test]
+    fn test_unpicked_review_names_its_reviewer_against_configured_sla() {
+        let now = Utc::now();
+        let mut waiting = review(5, ReviewStatus::Pending, now);
+        // Never moved on, so its time counts from its creation
+        waiting.status_changed_at = None;
+        let sla = ReviewSla { pending: Duration::hours(12), ..Default::default() };
+
+        let report = aging_report(&[waiting, review(6, ReviewStatus::InReview, now)], &sla, now);
+
+        assert!(report[0].breached);
+        assert_eq!(report[0].time_in_status, Duration::days(1));
+        assert_eq!(report[0].unresponsive_reviewers, vec!["ben"]);
+        assert!(report[1].unresponsive_reviewers.is_empty());
+    }
+}
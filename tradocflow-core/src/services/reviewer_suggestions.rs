@@ -0,0 +1,204 @@
+use crate::git_integration::ReviewRequest;
+use crate::{User, UserRole};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How well a candidate's languages fit a review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LanguageFit {
+    /// The candidate reviews the review's language
+    Reviews,
+    /// No languages are configured for the candidate
+    Unknown,
+}
+
+/// A candidate reviewer for a review request, with why they are suggested
+#[derive(Debug, Clone)]
+pub struct ReviewerSuggestion {
+    pub user_id: String,
+    pub name: String,
+    pub role: UserRole,
+    /// Open review requests already assigned to the candidate
+    pub open_reviews: usize,
+    pub language_fit: LanguageFit,
+    pub rationale: String,
+}
+
+/// Languages each user reviews in, by user id, as set in the reviewer
+/// configuration. A language covers its regional variants, so `de` covers
+/// `de-AT`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReviewerLanguages {
+    #[serde(flatten)]
+    pub by_user: HashMap<String, Vec<String>>,
+}
+
+impl ReviewerLanguages {
+    fn fit(&self, user_id: &str, language: &str) -> Option<LanguageFit> {
+        match self.by_user.get(user_id) {
+            None => Some(LanguageFit::Unknown),
+            Some(languages) if languages.iter().any(|l| covers(l, language)) => Some(LanguageFit::Reviews),
+            Some(_) => None,
+        }
+    }
+}
+
+fn covers(reviewed: &str, language: &str) -> bool {
+    let primary = |tag: &str| tag.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+    reviewed.eq_ignore_ascii_case(language) || (!reviewed.contains(['-', '_']) && primary(reviewed) == primary(language))
+}
+
+/// Spreads reviews across reviewers, given the review requests that are
+/// open now and the languages each reviewer reviews in
+#[derive(Debug, Clone, Default)]
+pub struct ReviewerBalancer {
+    open_reviews: HashMap<String, usize>,
+    languages: ReviewerLanguages,
+}
+
+impl ReviewerBalancer {
+    /// A balancer counting the open requests among `reviews` against their reviewer
+    pub fn new(reviews: &[ReviewRequest]) -> Self {
+        let mut open_reviews: HashMap<String, usize> = HashMap::new();
+        for reviewer in reviews.iter().filter(|review| review.is_open()).filter_map(|review| review.reviewer.as_ref()) {
+            *open_reviews.entry(reviewer.clone()).or_default() += 1;
+        }
+        Self { open_reviews, languages: ReviewerLanguages::default() }
+    }
+
+    pub fn with_languages(mut self, languages: ReviewerLanguages) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// Rank `candidates` for reviewing `request`.
+    ///
+    /// Candidates known to review the request's language come before those
+    /// with no languages configured, then those with fewer open reviews,
+    /// then dedicated reviewers before admins and owners. The translator,
+    /// the reviewer already assigned, inactive users, users whose role
+    /// can't review and users configured for other languages only are left
+    /// out.
+    pub fn suggest_reviewers(&self, request: &ReviewRequest, candidates: &[User]) -> Vec<ReviewerSuggestion> {
+        let mut suggestions: Vec<ReviewerSuggestion> = candidates
+            .iter()
+            .filter(|user| user.active && user.role.can_review_documents())
+            .filter(|user| user.id != request.translator && request.reviewer.as_ref() != Some(&user.id))
+            .filter_map(|user| {
+                let language_fit = self.languages.fit(&user.id, &request.language)?;
+                let open_reviews = self.open_reviews.get(&user.id).copied().unwrap_or(0);
+                let language = match language_fit {
+                    LanguageFit::Reviews => format!("reviews {}", request.language),
+                    LanguageFit::Unknown => "no review languages configured".to_string(),
+                };
+                let load = match open_reviews {
+                    1 => "1 open review".to_string(),
+                    count => format!("{count} open reviews"),
+                };
+                Some(ReviewerSuggestion {
+                    user_id: user.id.clone(),
+                    name: user.name.clone(),
+                    role: user.role.clone(),
+                    open_reviews,
+                    language_fit,
+                    rationale: format!("{}: {language}, {load}, {} role", user.name, user.role.as_str()),
+                })
+            })
+            .collect();
+        suggestions.sort_by(|a, b| {
+            a.language_fit
+                .cmp(&b.language_fit)
+                .then(a.open_reviews.cmp(&b.open_reviews))
+                .then(role_rank(&a.role).cmp(&role_rank(&b.role)))
+                .then(a.name.cmp(&b.name))
+        });
+        suggestions
+    }
+}
+
+/// How well a role fits reviewing, lower first
+fn role_rank(role: &UserRole) -> u8 {
+    match role {
+        UserRole::Reviewer => 0,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_integration::models::ReviewStatus;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn user(id: &str, role: UserRole) -> User {
+        User {
+            id: id.to_string(),
+            name: id.to_string(),
+            email: format!("{id}@example.com"),
+            role,
+            created_at: Utc::now(),
+            active: true,
+        }
+    }
+
+    fn review(reviewer: Option<&str>, status: ReviewStatus) -> ReviewRequest {
+        ReviewRequest {
+            id: Uuid::new_v4(),
+            pr_number: 1,
+            branch: "translate/intro/de/1".to_string(),
+            chapter: "intro".to_string(),
+            language: "de-AT".to_string(),
+            translator: "alice".to_string(),
+            reviewer: reviewer.map(str::to_string),
+            status,
+            created_at: Utc::now(),
+            changes_summary: String::new(),
+            status_changed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_less_loaded_qualified_reviewer_ranks_first() {
+        let open = vec![
+            review(Some("ben"), ReviewStatus::InReview),
+            review(Some("ben"), ReviewStatus::Pending),
+            review(Some("carla"), ReviewStatus::ChangesRequested),
+            // Closed reviews don't count
+            review(Some("carla"), ReviewStatus::Approved),
+            review(Some("carla"), ReviewStatus::Rejected),
+        ];
+        let languages: ReviewerLanguages = serde_json::from_str(
+            r#"{"ben": ["de"], "carla": ["de", "fr"], "dana": ["de"], "emil": ["fr"]}"#,
+        )
+        .unwrap();
+        let balancer = ReviewerBalancer::new(&open).with_languages(languages);
+        let candidates = vec![
+            user("ben", UserRole::Reviewer),
+            user("carla", UserRole::Reviewer),
+            user("dana", UserRole::Admin),
+            user("emil", UserRole::Reviewer),
+            user("frank", UserRole::Reviewer),
+            user("gita", UserRole::Translator),
+        ];
+
+        let suggestions = balancer.suggest_reviewers(&review(None, ReviewStatus::Pending), &candidates);
+
+        let ranked: Vec<&str> = suggestions.iter().map(|s| s.user_id.as_str()).collect();
+        assert_eq!(ranked, vec!["dana", "carla", "ben", "frank"]);
+        assert_eq!(suggestions[2].open_reviews, 2);
+        assert_eq!(suggestions[1].rationale, "carla: reviews de-AT, 1 open review, reviewer role");
+        assert_eq!(suggestions[3].language_fit, LanguageFit::Unknown);
+    }
+
+    #[test]
+    fn test_author_and_assigned_reviewer_are_never_suggested() {
+        let balancer = ReviewerBalancer::new(&[]);
+        let candidates = vec![user("alice", UserRole::Admin), user("ben", UserRole::Reviewer), user("carla", UserRole::Owner)];
+
+        let suggestions = balancer.suggest_reviewers(&review(Some("ben"), ReviewStatus::Pending), &candidates);
+
+        let ranked: Vec<&str> = suggestions.iter().map(|s| s.user_id.as_str()).collect();
+        assert_eq!(ranked, vec!["carla"]);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::git_integration::workflow_manager::MANUAL_DIR;
+use crate::git_integration::{GitWorkflowManager, ReleaseTag};
+use crate::services::manual_dir::DOCUMENTS_DIR;
+use crate::{i18n, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Line of a document replaced by its revision history table
+pub const REVISION_HISTORY_MARKER: &str = "{{revision-history}}";
+
+/// What a revision history covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RevisionScope {
+    /// Every release of the manual
+    Manual,
+    /// Releases that changed one section document
+    Document(Uuid),
+}
+
+impl RevisionScope {
+    fn path(&self) -> PathBuf {
+        match self {
+            RevisionScope::Manual => PathBuf::from(MANUAL_DIR),
+            RevisionScope::Document(id) => PathBuf::from(MANUAL_DIR).join(DOCUMENTS_DIR).join(format!("{id}.json")),
+        }
+    }
+}
+
+/// A row of a revision history table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevisionEntry {
+    /// Name of the release tag
+    pub version: String,
+    pub date: DateTime<Utc>,
+    pub author: String,
+    pub summary: String,
+}
+
+impl From<ReleaseTag> for RevisionEntry {
+    fn from(tag: ReleaseTag) -> Self {
+        Self { version: tag.name, date: tag.date, author: tag.author, summary: tag.summary }
+    }
+}
+
+/// The revision history of `scope`, newest release first, derived from the
+/// repository's tags. `depth` limits it to the latest releases.
+pub async fn revision_history(
+    manager: &GitWorkflowManager,
+    scope: RevisionScope,
+    depth: Option<usize>,
+) -> Result<Vec<RevisionEntry>> {
+    let path = scope.path();
+    let releases = manager.release_tags(Some(&path)).await?;
+    Ok(releases.into_iter().rev().take(depth.unwrap_or(usize::MAX)).map(RevisionEntry::from).collect())
+}
+
+/// Column headers in `language`
+fn headers(language: &str) -> [String; 4] {
+    ["version", "date", "author", "summary"].map(|key| i18n::t_for(&format!("manuals.revision_history.{key}"), language))
+}
+
+/// The entries as a markdown table with headers in `language`
+pub fn revision_table_markdown(entries: &[RevisionEntry], language: &str) -> String {
+    let cell = |text: &str| text.replace('|', "\\|").replace(['\r', '\n'], " ");
+    let headers = headers(language);
+    let mut table = format!("| {} |\n|---|---|---|---|\n", headers.map(|header| cell(&header)).join(" | "));
+    for entry in entries {
+        table.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            cell(&entry.version),
+            entry.date.format("%Y-%m-%d"),
+            cell(&entry.author),
+            cell(&entry.summary)
+        ));
+    }
+    table
+}
+
+/// The entries as an HTML table with headers in `language`
+pub fn revision_table_html(entries: &[RevisionEntry], language: &str) -> String {
+    let escape = |text: &str| text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+    let mut html = String::from("<table class=\"revision-history\">\n<thead>\n<tr>");
+    for header in headers(language) {
+        html.push_str(&format!("<th>{}</th>", escape(&header)));
+    }
+    html.push_str("</tr>\n</thead>\n<tbody>\n");
+    for entry in entries {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape(&entry.version),
+            entry.date.format("%Y-%m-%d"),
+            escape(&entry.author),
+            escape(&entry.summary)
+        ));
+    }
+    html.push_str("</tbody>\n</table>\n");
+    html
+}
+
+/// Replace each line of `markdown` holding only [`REVISION_HISTORY_MARKER`]
+/// with the entries as a table. Markers in fenced code are left alone.
+pub fn insert_revision_history(markdown: &str, entries: &[RevisionEntry], language: &str) -> String {
+    let mut inserted = String::with_capacity(markdown.len());
+    let mut fence: Option<&str> = None;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+        } else if trimmed == REVISION_HISTORY_MARKER {
+            inserted.push_str(&revision_table_markdown(entries, language));
+            continue;
+        }
+        inserted.push_str(line);
+    }
+    inserted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_integration::{initialize_translation_repository, GitConfig};
+    use crate::services::manual_dir::save_manual_dir;
+    use crate::services::templates::scaffold;
+    use crate::{Document, DocumentMetadata, ManualTemplate, User, UserRole};
+    use git2::{Repository, Signature};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn user(name: &str) -> User {
+        User {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            email: format!("{}@example.com", name.to_lowercase()),
+            role: UserRole::Member,
+            created_at: Utc::now(),
+            active: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revision_history_lists_releases_newest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        initialize_translation_repository(repo_path, "Controller", &GitConfig::default()).await.unwrap();
+        let alice = GitWorkflowManager::new(repo_path, Uuid::new_v4(), user("Alice"), GitConfig::default()).await.unwrap();
+        let bob = GitWorkflowManager::new(repo_path, Uuid::new_v4(), user("Bob"), GitConfig::default()).await.unwrap();
+
+        let manual_dir = repo_path.join(MANUAL_DIR);
+        let mut manual = scaffold(&ManualTemplate::UserGuide, &["en".to_string()]);
+        let document_id = Uuid::new_v4();
+        manual.sections[0].document_id = Some(document_id);
+        let save = |text: &str| {
+            let document = Document {
+                title: "Introduction".to_string(),
+                content: HashMap::from([("en".to_string(), text.to_string())]),
+                metadata: DocumentMetadata { project_id: None, screenshots: Vec::new(), version: None, custom: HashMap::new() },
+            };
+            save_manual_dir(&manual_dir, &manual, &HashMap::from([(document_id, document)])).unwrap();
+        };
+
+        // A lightweight tag by Alice, then an annotated tag Bob signs
+        save("First text.");
+        let first = alice.create_commit_with_message("Release the first edition").await.unwrap();
+        save("Second text.");
+        let second = bob.create_commit_with_message("Rework the introduction").await.unwrap();
+        {
+            let repo = Repository::open(repo_path).unwrap();
+            repo.tag_lightweight("v1.0", &repo.find_object(first, None).unwrap(), false).unwrap();
+            let tagger = Signature::now("Bob", "bob@example.com").unwrap();
+            repo.tag("v1.1", &repo.find_object(second, None).unwrap(), &tagger, "Second edition\n\nReworked.", false).unwrap();
+        }
+
+        let history = revision_history(&alice, RevisionScope::Document(document_id), None).await.unwrap();
+        let rows: Vec<(&str, &str, &str)> =
+            history.iter().map(|entry| (entry.version.as_str(), entry.author.as_str(), entry.summary.as_str())).collect();
+        assert_eq!(rows, [("v1.1", "Bob", "Second edition"), ("v1.0", "Alice", "Release the first edition")]);
+        assert_eq!(revision_history(&alice, RevisionScope::Manual, Some(1)).await.unwrap().len(), 1);
+
+        let markdown = insert_revision_history("# Changes\n\n{{revision-history}}\n", &history, "en");
+        let today = Utc::now().format("%Y-%m-%d");
+        assert_eq!(
+            markdown,
+            format!(
+                "# Changes\n\n| Version | Date | Author | Summary |\n|---|---|---|---|\n| v1.1 | {today} | Bob | Second edition |\n| v1.0 | {today} | Alice | Release the first edition |\n"
+            )
+        );
+        assert!(revision_table_html(&history, "en").contains("<tr><td>v1.1</td>"));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::export_engine::IncludeSource;
+use crate::services::variables::ProjectVariables;
+use crate::{Result, TradocumentError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// A named block of markdown authors reuse, such as a revision table or a
+/// warning header. It may hold `{{var:name}}` placeholders, filled in when
+/// it is inserted or when the document is exported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Markdown by language code
+    pub content: HashMap<String, String>,
+    /// Language inserted when the snippet has no content in the requested one
+    pub default_language: String,
+}
+
+impl Snippet {
+    pub fn new(name: impl Into<String>, default_language: impl Into<String>, content: impl Into<String>) -> Self {
+        let default_language = default_language.into();
+        Self {
+            name: name.into(),
+            description: String::new(),
+            category: None,
+            tags: Vec::new(),
+            content: HashMap::from([(default_language.clone(), content.into())]),
+            default_language,
+        }
+    }
+
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self
+    }
+
+    /// Add or replace the content in `language`
+    pub fn with_translation(mut self, language: impl Into<String>, content: impl Into<String>) -> Self {
+        self.content.insert(language.into(), content.into());
+        self
+    }
+
+    /// Content in `language`, or in the default language when there is no
+    /// translation
+    pub fn localized(&self, language: &str) -> Option<&str> {
+        self.content.get(language).or_else(|| self.content.get(&self.default_language)).map(String::as_str)
+    }
+}
+
+/// Which snippets [`SnippetService::list`] returns; an empty filter
+/// matches them all
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnippetFilter {
+    /// Only snippets in this category, ignoring case
+    pub category: Option<String>,
+    /// Only snippets carrying every one of these tags, ignoring case
+    pub tags: Vec<String>,
+}
+
+impl SnippetFilter {
+    fn matches(&self, snippet: &Snippet) -> bool {
+        let category = self.category.as_ref().is_none_or(|wanted| {
+            snippet.category.as_ref().is_some_and(|category| category.eq_ignore_ascii_case(wanted))
+        });
+        category && self.tags.iter().all(|wanted| snippet.tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted)))
+    }
+}
+
+/// The library of snippets authors insert into documents, by name
+#[derive(Debug, Clone, Default)]
+pub struct SnippetService {
+    snippets: BTreeMap<String, Snippet>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SnippetsFile {
+    #[serde(default)]
+    snippets: Vec<Snippet>,
+}
+
+impl SnippetService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the user's snippet library from the config directory; no file
+    /// means an empty library
+    pub fn load() -> Result<Self> {
+        match Self::config_path() {
+            Some(path) if path.exists() => Self::load_from_path(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Location of the user's snippet library
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tradocflow").join("snippets.json"))
+    }
+
+    pub fn load_from_path(path: &Path) -> Result<Self> {
+        let file: SnippetsFile = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(Self { snippets: file.snippets.into_iter().map(|snippet| (snippet.name.clone(), snippet)).collect() })
+    }
+
+    pub fn save_to_path(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = SnippetsFile { snippets: self.snippets.values().cloned().collect() };
+        std::fs::write(path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+
+    /// Add a snippet; its name must be new
+    pub fn create(&mut self, snippet: Snippet) -> Result<()> {
+        if snippet.name.trim().is_empty() {
+            return Err(TradocumentError::Validation("Snippet name must not be empty".to_string()));
+        }
+        if !snippet.content.contains_key(&snippet.default_language) {
+            return Err(TradocumentError::Validation(format!(
+                "Snippet \"{}\" has no content in its default language {}",
+                snippet.name, snippet.default_language
+            )));
+        }
+        if self.snippets.contains_key(&snippet.name) {
+            return Err(TradocumentError::Validation(format!("Snippet \"{}\" already exists", snippet.name)));
+        }
+        self.snippets.insert(snippet.name.clone(), snippet);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Snippet> {
+        self.snippets.get(name)
+    }
+
+    /// Replace the snippet of the same name
+    pub fn update(&mut self, snippet: Snippet) -> Result<()> {
+        match self.snippets.get_mut(&snippet.name) {
+            Some(existing) => {
+                *existing = snippet;
+                Ok(())
+            }
+            None => Err(unknown_snippet(&snippet.name)),
+        }
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<Snippet> {
+        self.snippets.remove(name).ok_or_else(|| unknown_snippet(name))
+    }
+
+    /// Snippets matching `filter`, by name
+    pub fn list(&self, filter: &SnippetFilter) -> Vec<&Snippet> {
+        self.snippets.values().filter(|snippet| filter.matches(snippet)).collect()
+    }
+
+    /// Categories in use, sorted. Categories are matched ignoring case like
+    /// [`SnippetService::list`] does, so each is listed once, under the
+    /// spelling that sorts first.
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = self.snippets.values().filter_map(|snippet| snippet.category.as_deref()).collect();
+        categories.sort_unstable_by(|a, b| a.to_ascii_lowercase().cmp(&b.to_ascii_lowercase()).then(a.cmp(b)));
+        categories.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        categories
+    }
+
+    /// Text of a snippet in `language` for the editor to splice in, with
+    /// its variables left for export to fill in
+    pub fn insert(&self, name: &str, language: &str) -> Result<String> {
+        let snippet = self.get(name).ok_or_else(|| unknown_snippet(name))?;
+        snippet.localized(language).map(str::to_string).ok_or_else(|| {
+            TradocumentError::Validation(format!("Snippet \"{name}\" has no content in {language}"))
+        })
+    }
+
+    /// [`SnippetService::insert`] with the variables filled in from
+    /// `variables`; undefined ones stay flagged as `{{var:name?}}`
+    pub fn insert_with_variables(&self, name: &str, language: &str, variables: &ProjectVariables) -> Result<String> {
+        Ok(variables.substitute(&self.insert(name, language)?, language).text)
+    }
+}
+
+fn unknown_snippet(name: &str) -> TradocumentError {
+    TradocumentError::Validation(format!("Unknown snippet: {name}"))
+}
+
+/// Lets exports include snippets by name with `{{include: name}}`
+impl IncludeSource for SnippetService {
+    fn load(&self, path: &str, language: &str) -> Option<String> {
+        self.get(path)?.localized(language).map(str::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> SnippetService {
+        let mut snippets = SnippetService::new();
+        snippets
+            .create(
+                Snippet::new("revision-table", "en", "| Revision | Date | Change |\n|---|---|---|\n| {{var:version}} | | |\n")
+                    .with_category("Front matter")
+                    .with_tags(&["table"])
+                    .with_translation("de", "| Revision | Datum | Änderung |\n|---|---|---|\n| {{var:version}} | | |\n"),
+            )
+            .unwrap();
+        snippets
+            .create(Snippet::new("shock-warning", "en", "> [!WARNING]\n> Disconnect power first.\n").with_category("Safety").with_tags(&["electrical", "warning"]))
+            .unwrap();
+        snippets
+            .create(Snippet::new("hot-surface", "en", "> [!WARNING]\n> Surfaces get hot.\n").with_category("safety").with_tags(&["warning"]))
+            .unwrap();
+        snippets
+    }
+
+    #[test]
+    fn test_localized_snippet_is_inserted() {
+        let mut snippets = library();
+        assert!(snippets.create(Snippet::new("hot-surface", "en", "Duplicate")).is_err());
+
+        let german = snippets.insert("revision-table", "de").unwrap();
+        assert!(german.starts_with("| Revision | Datum | Änderung |"));
+        // No French translation; the default language is inserted
+        assert!(snippets.insert("revision-table", "fr").unwrap().starts_with("| Revision | Date | Change |"));
+        assert!(snippets.insert("missing", "en").is_err());
+
+        let variables = ProjectVariables { default: HashMap::from([("version".to_string(), "2.1".to_string())]), ..Default::default() };
+        assert!(snippets.insert_with_variables("revision-table", "de", &variables).unwrap().contains("| 2.1 | | |"));
+        assert!(german.contains("{{var:version}}"));
+    }
+
+    #[test]
+    fn test_snippets_filtered_by_category_and_tag() {
+        let mut snippets = library();
+        let names = |snippets: &SnippetService, filter: &SnippetFilter| -> Vec<String> {
+            snippets.list(filter).iter().map(|snippet| snippet.name.clone()).collect()
+        };
+
+        let safety = SnippetFilter { category: Some("Safety".to_string()), tags: Vec::new() };
+        assert_eq!(names(&snippets, &safety), ["hot-surface", "shock-warning"]);
+        let electrical = SnippetFilter { category: Some("safety".to_string()), tags: vec!["Electrical".to_string()] };
+        assert_eq!(names(&snippets, &electrical), ["shock-warning"]);
+        assert_eq!(snippets.list(&SnippetFilter::default()).len(), 3);
+        assert_eq!(snippets.categories(), ["Front matter", "Safety"]);
+
+        snippets.delete("shock-warning").unwrap();
+        assert_eq!(names(&snippets, &safety), ["hot-surface"]);
+    }
+}
@@ -0,0 +1,464 @@
+use crate::{Manual, ManualSection, ManualTemplate, Result, SectionType, TradocumentError};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// A section prescribed by a template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionSpec {
+    pub title: String,
+    /// Defaults to a custom type named after the title
+    #[serde(default)]
+    pub section_type: Option<SectionType>,
+    #[serde(default = "default_required")]
+    pub required: bool,
+    #[serde(default)]
+    pub subsections: Vec<SectionSpec>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl SectionSpec {
+    fn required(title: &str, section_type: SectionType) -> Self {
+        Self { title: title.to_string(), section_type: Some(section_type), required: true, subsections: Vec::new() }
+    }
+
+    fn optional(title: &str, section_type: SectionType) -> Self {
+        Self { required: false, ..Self::required(title, section_type) }
+    }
+
+    fn with_subsections(mut self, subsections: Vec<SectionSpec>) -> Self {
+        self.subsections = subsections;
+        self
+    }
+
+    fn to_section(&self, order: u32) -> ManualSection {
+        ManualSection {
+            id: Uuid::new_v4(),
+            title: self.title.clone(),
+            order,
+            document_id: None,
+            subsections: to_sections(&self.subsections),
+            section_type: self.section_type.clone().unwrap_or_else(|| SectionType::Custom(self.title.clone())),
+            required: self.required,
+            localized_titles: HashMap::new(),
+        }
+    }
+}
+
+fn to_sections(specs: &[SectionSpec]) -> Vec<ManualSection> {
+    specs.iter().zip(1..).map(|(spec, order)| spec.to_section(order)).collect()
+}
+
+/// The section structure of a manual template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub sections: Vec<SectionSpec>,
+}
+
+/// On-disk representation of `templates.toml`
+#[derive(Debug, Default, Deserialize)]
+struct TemplatesFile {
+    #[serde(default)]
+    templates: Vec<TemplateSpec>,
+}
+
+/// The canonical structure of a built-in template; `None` for custom ones
+pub fn builtin_spec(template: &ManualTemplate) -> Option<TemplateSpec> {
+    use SectionType::*;
+
+    let (name, description, sections) = match template {
+        ManualTemplate::TechnicalManual => (
+            "Technical Manual",
+            "Full product documentation from installation to reference",
+            vec![
+                SectionSpec::required("Introduction", Introduction),
+                SectionSpec::required("Installation", Installation),
+                SectionSpec::required("Configuration", Configuration),
+                SectionSpec::required("Operation", UserGuide),
+                SectionSpec::required("Troubleshooting", Troubleshooting),
+                SectionSpec::optional("Reference", Reference),
+                SectionSpec::optional("Appendix", Appendix),
+            ],
+        ),
+        ManualTemplate::UserGuide => (
+            "User Guide",
+            "Task-oriented guide for end users",
+            vec![
+                SectionSpec::required("Introduction", Introduction),
+                SectionSpec::required("Getting Started", UserGuide),
+                SectionSpec::required("Everyday Use", UserGuide),
+                SectionSpec::optional("Troubleshooting", Troubleshooting),
+                SectionSpec::optional("Appendix", Appendix),
+            ],
+        ),
+        ManualTemplate::InstallationGuide => (
+            "Installation Guide",
+            "Step-by-step installation and initial setup",
+            vec![
+                SectionSpec::required("Introduction", Introduction),
+                SectionSpec::required("System Requirements", Reference),
+                SectionSpec::required("Installation", Installation),
+                SectionSpec::required("Configuration", Configuration),
+                SectionSpec::optional("Troubleshooting", Troubleshooting),
+                SectionSpec::optional("Appendix", Appendix),
+            ],
+        ),
+        ManualTemplate::BellTowerController => (
+            "Bell Tower Controller Manual",
+            "Installation, configuration and operation of a bell tower controller",
+            vec![
+                SectionSpec::required("Introduction", Introduction),
+                SectionSpec::required("Safety Instructions", Custom("Safety".to_string())),
+                SectionSpec::required("Installation", Installation).with_subsections(vec![
+                    SectionSpec::required("Mounting the Controller", Installation),
+                    SectionSpec::required("Wiring", Installation),
+                ]),
+                SectionSpec::required("Configuration", Configuration).with_subsections(vec![
+                    SectionSpec::required("Bell Schedules", Configuration),
+                    SectionSpec::optional("Time Synchronization", Configuration),
+                ]),
+                SectionSpec::required("Operation", UserGuide),
+                SectionSpec::required("Maintenance", Custom("Maintenance".to_string())),
+                SectionSpec::required("Troubleshooting", Troubleshooting),
+                SectionSpec::optional("Technical Specifications", Reference),
+                SectionSpec::optional("Appendix", Appendix),
+            ],
+        ),
+        ManualTemplate::Custom(_) => return None,
+    };
+
+    Some(TemplateSpec { name: name.to_string(), description: description.to_string(), sections })
+}
+
+/// Built-in templates plus user-defined ones for [`ManualTemplate::Custom`]
+#[derive(Debug, Clone, Default)]
+pub struct TemplateRegistry {
+    custom: HashMap<String, TemplateSpec>,
+}
+
+impl TemplateRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load user templates from the config directory; no file means no
+    /// custom templates
+    pub fn load() -> Result<Self> {
+        match Self::config_path() {
+            Some(path) if path.exists() => Self::load_from_path(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Location of the user templates file
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("tradocflow").join("templates.toml"))
+    }
+
+    pub fn load_from_path(path: &Path) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse `[[templates]]` entries, each with a `name` and its
+    /// `[[templates.sections]]`
+    pub fn parse(content: &str) -> Result<Self> {
+        let file: TemplatesFile = toml::from_str(content)?;
+        let mut registry = Self::default();
+        for spec in file.templates {
+            registry.register(spec);
+        }
+        Ok(registry)
+    }
+
+    /// Add or replace a custom template, keyed by its name
+    pub fn register(&mut self, spec: TemplateSpec) {
+        self.custom.insert(spec.name.clone(), spec);
+    }
+
+    pub fn spec(&self, template: &ManualTemplate) -> Option<TemplateSpec> {
+        match template {
+            ManualTemplate::Custom(name) => self.custom.get(name).cloned(),
+            builtin => builtin_spec(builtin),
+        }
+    }
+
+    /// How `manual` deviates from the section structure of `template`
+    pub fn validate(&self, manual: &Manual, template: &ManualTemplate) -> Vec<TemplateViolation> {
+        let Some(spec) = self.spec(template) else {
+            return vec![TemplateViolation {
+                kind: TemplateViolationKind::UnknownTemplate,
+                section_id: None,
+                title: template_name(template),
+                message: format!("Unknown manual template: {}", template_name(template)),
+            }];
+        };
+
+        let mut violations = Vec::new();
+        let unmatched = check_sections(&spec.sections, &manual.sections, &mut violations);
+        for section in unmatched {
+            violations.push(TemplateViolation {
+                kind: TemplateViolationKind::UnexpectedSection,
+                section_id: Some(section.id),
+                title: section.title.clone(),
+                message: format!("Section \"{}\" is not part of the {} template", section.title, spec.name),
+            });
+        }
+        violations
+    }
+
+    /// A new manual with the template's section tree
+    pub fn scaffold(&self, template: &ManualTemplate, languages: &[String]) -> Result<Manual> {
+        let spec = self.spec(template).ok_or_else(|| {
+            TradocumentError::Validation(format!("Unknown manual template: {}", template_name(template)))
+        })?;
+
+        let now = Utc::now();
+        Ok(Manual {
+            id: Uuid::new_v4(),
+            title: spec.name.clone(),
+            description: spec.description.clone(),
+            sections: to_sections(&spec.sections),
+            created_at: now,
+            updated_at: now,
+            version: "1.0".to_string(),
+            languages: languages.to_vec(),
+            template_type: template.clone(),
+        })
+    }
+}
+
+/// What is wrong with a section of a manual
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemplateViolationKind {
+    /// A required section of the template is absent
+    MissingSection,
+    /// The section exists but has a different type than the template prescribes
+    SectionTypeMismatch { expected: SectionType, found: SectionType },
+    /// A top-level section the template doesn't define
+    UnexpectedSection,
+    /// A custom template that isn't configured
+    UnknownTemplate,
+}
+
+/// A deviation of a manual from its template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateViolation {
+    pub kind: TemplateViolationKind,
+    /// The manual's section, when it has one
+    pub section_id: Option<Uuid>,
+    /// Title of the section, or of the template when it is unknown
+    pub title: String,
+    pub message: String,
+}
+
+/// Match template sections to manual sections, by title and otherwise by
+/// type, reporting missing and mistyped ones. Returns the manual sections
+/// left unmatched.
+fn check_sections<'a>(
+    specs: &[SectionSpec],
+    sections: &'a [ManualSection],
+    violations: &mut Vec<TemplateViolation>,
+) -> Vec<&'a ManualSection> {
+    let mut taken = vec![false; sections.len()];
+    let mut matches: Vec<Option<usize>> = specs
+        .iter()
+        .map(|spec| {
+            let found = sections.iter().position(|s| s.title.trim().eq_ignore_ascii_case(spec.title.trim()));
+            if let Some(index) = found {
+                taken[index] = true;
+            }
+            found
+        })
+        .collect();
+
+    for (spec, found) in specs.iter().zip(matches.iter_mut()) {
+        if found.is_some() {
+            continue;
+        }
+        let Some(expected) = &spec.section_type else {
+            continue;
+        };
+        *found = (0..sections.len()).find(|&index| !taken[index] && &sections[index].section_type == expected);
+        if let Some(index) = *found {
+            taken[index] = true;
+        }
+    }
+
+    for (spec, found) in specs.iter().zip(&matches) {
+        let Some(section) = found.map(|index| &sections[index]) else {
+            if spec.required {
+                violations.push(TemplateViolation {
+                    kind: TemplateViolationKind::MissingSection,
+                    section_id: None,
+                    title: spec.title.clone(),
+                    message: format!("Required section \"{}\" is missing", spec.title),
+                });
+            }
+            continue;
+        };
+
+        let expected = spec.section_type.clone().unwrap_or_else(|| SectionType::Custom(spec.title.clone()));
+        if section.section_type != expected {
+            violations.push(TemplateViolation {
+                kind: TemplateViolationKind::SectionTypeMismatch {
+                    expected: expected.clone(),
+                    found: section.section_type.clone(),
+                },
+                section_id: Some(section.id),
+                title: section.title.clone(),
+                message: format!(
+                    "Section \"{}\" should be of type {expected:?} but is {:?}",
+                    section.title, section.section_type
+                ),
+            });
+        }
+
+        // Extra subsections are fine; only top-level structure is fixed
+        check_sections(&spec.subsections, &section.subsections, violations);
+    }
+
+    sections.iter().zip(taken).filter(|(_, taken)| !taken).map(|(section, _)| section).collect()
+}
+
+/// How `manual` deviates from `template`. Custom templates are looked up in
+/// the user's `templates.toml`.
+pub fn validate_against(manual: &Manual, template: &ManualTemplate) -> Vec<TemplateViolation> {
+    let registry = match template {
+        ManualTemplate::Custom(_) => TemplateRegistry::load().unwrap_or_else(|e| {
+            log::warn!("Custom manual templates could not be loaded: {e}");
+            TemplateRegistry::default()
+        }),
+        _ => TemplateRegistry::default(),
+    };
+    registry.validate(manual, template)
+}
+
+fn template_name(template: &ManualTemplate) -> String {
+    match template {
+        ManualTemplate::Custom(name) => name.clone(),
+        builtin => format!("{builtin:?}"),
+    }
+}
+
+/// A new manual with the section tree of `template`. Custom templates are
+/// looked up in the user's `templates.toml`; an unknown one yields a manual
+/// without sections.
+pub fn scaffold(template: &ManualTemplate, languages: &[String]) -> Manual {
+    let registry = match template {
+        ManualTemplate::Custom(_) => TemplateRegistry::load().unwrap_or_else(|e| {
+            log::warn!("Custom manual templates could not be loaded: {e}");
+            TemplateRegistry::default()
+        }),
+        _ => TemplateRegistry::default(),
+    };
+
+    registry.scaffold(template, languages).unwrap_or_else(|e| {
+        log::warn!("{e}; starting without sections");
+        let now = Utc::now();
+        Manual {
+            id: Uuid::new_v4(),
+            title: template_name(template),
+            description: String::new(),
+            sections: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            version: "1.0".to_string(),
+            languages: languages.to_vec(),
+            template_type: template.clone(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_installation_guide_required_sections_in_order() {
+        let manual = scaffold(&ManualTemplate::InstallationGuide, &["en".to_string(), "de".to_string()]);
+        assert_eq!(manual.languages, vec!["en", "de"]);
+
+        let required: Vec<&str> = manual.sections.iter().filter(|s| s.required).map(|s| s.title.as_str()).collect();
+        assert_eq!(required, vec!["Introduction", "System Requirements", "Installation", "Configuration"]);
+        let orders: Vec<u32> = manual.sections.iter().map(|s| s.order).collect();
+        assert_eq!(orders, (1..=manual.sections.len() as u32).collect::<Vec<_>>());
+        assert!(manual.sections.iter().any(|s| !s.required));
+    }
+
+    #[test]
+    fn test_custom_template_loads_from_config() {
+        let registry = TemplateRegistry::parse(
+            r#"
+            [[templates]]
+            name = "Quick Start"
+
+            [[templates.sections]]
+            title = "Unboxing"
+
+            [[templates.sections]]
+            title = "First Steps"
+            section_type = "UserGuide"
+
+            [[templates.sections.subsections]]
+            title = "Pairing"
+            required = false
+            "#,
+        )
+        .unwrap();
+
+        let template = ManualTemplate::Custom("Quick Start".to_string());
+        let manual = registry.scaffold(&template, &["en".to_string()]).unwrap();
+        assert_eq!(manual.title, "Quick Start");
+        assert_eq!(manual.sections.len(), 2);
+        assert!(matches!(&manual.sections[0].section_type, SectionType::Custom(name) if name == "Unboxing"));
+        assert!(matches!(manual.sections[1].section_type, SectionType::UserGuide));
+        assert!(!manual.sections[1].subsections[0].required);
+
+        assert!(registry.scaffold(&ManualTemplate::Custom("Missing".to_string()), &[]).is_err());
+    }
+
+    #[test]
+    fn test_missing_required_troubleshooting_section() {
+        let mut manual = scaffold(&ManualTemplate::TechnicalManual, &["en".to_string()]);
+        manual.sections.retain(|s| s.title != "Troubleshooting");
+
+        let violations = validate_against(&manual, &ManualTemplate::TechnicalManual);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, TemplateViolationKind::MissingSection);
+        assert_eq!(violations[0].title, "Troubleshooting");
+    }
+
+    #[test]
+    fn test_conforming_manual_has_no_violations() {
+        let template = ManualTemplate::BellTowerController;
+        let mut manual = scaffold(&template, &["en".to_string()]);
+        assert!(validate_against(&manual, &template).is_empty());
+
+        // Renamed sections still match by type; optional ones may be dropped
+        manual.sections.retain(|s| s.required);
+        manual.sections[0].title = "About this manual".to_string();
+        assert!(validate_against(&manual, &template).is_empty());
+
+        manual.sections[1].section_type = SectionType::Appendix;
+        manual.sections.push(ManualSection { title: "Warranty".to_string(), ..manual.sections[0].clone() });
+        let kinds: Vec<TemplateViolationKind> = validate_against(&manual, &template).into_iter().map(|v| v.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TemplateViolationKind::SectionTypeMismatch {
+                    expected: SectionType::Custom("Safety".to_string()),
+                    found: SectionType::Appendix
+                },
+                TemplateViolationKind::UnexpectedSection,
+            ]
+        );
+    }
+}
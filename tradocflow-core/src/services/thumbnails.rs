@@ -0,0 +1,146 @@
+use super::atomic_file::write_atomic_blocking;
+use crate::{Result, TradocumentError};
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// JPEG quality of thumbnails without transparency
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+/// A thumbnail on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    pub path: PathBuf,
+    pub width: u32,
+    pub height: u32,
+    /// Whether it was made by an earlier call rather than this one
+    pub from_cache: bool,
+}
+
+/// Directory of thumbnails, each named after the hash of its source image
+/// and its size. A changed source hashes differently, so its thumbnail is
+/// made again; an unchanged one is read from the cache.
+#[derive(Debug, Clone)]
+pub struct ThumbnailCache {
+    dir: PathBuf,
+}
+
+impl Default for ThumbnailCache {
+    /// The user's cache directory, or the temporary directory when there is
+    /// none
+    fn default() -> Self {
+        let dir = dirs::cache_dir().unwrap_or_else(std::env::temp_dir);
+        Self::new(dir.join("tradocflow").join("thumbnails"))
+    }
+}
+
+impl ThumbnailCache {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self { dir: dir.as_ref().to_path_buf() }
+    }
+
+    /// Thumbnail of the PNG, JPEG, WebP or other raster image at
+    /// `image_path`, its longest side at most `max_dim` pixels. The aspect
+    /// ratio is kept and smaller images aren't enlarged. Thumbnails are
+    /// PNG when the image has transparency and JPEG otherwise.
+    pub fn thumbnail(&self, image_path: &Path, max_dim: u32) -> Result<Thumbnail> {
+        let bytes = std::fs::read(image_path)?;
+        let max_dim = max_dim.max(1);
+        let hash: String = Sha256::digest(&bytes).iter().map(|byte| format!("{byte:02x}")).collect();
+        for extension in ["jpg", "png"] {
+            let path = self.dir.join(format!("{hash}-{max_dim}.{extension}"));
+            if let Ok((width, height)) = image::image_dimensions(&path) {
+                return Ok(Thumbnail { path, width, height, from_cache: true });
+            }
+        }
+
+        let decode_error = |e: image::ImageError| {
+            TradocumentError::UnsupportedFormat(format!("{} could not be decoded: {e}", image_path.display()))
+        };
+        let image = image::load_from_memory(&bytes).map_err(decode_error)?;
+        // `thumbnail` keeps the aspect ratio within the bounds
+        let image = if image.width().max(image.height()) > max_dim {
+            image.thumbnail(max_dim, max_dim)
+        } else {
+            image
+        };
+
+        let (data, extension) = encode(&image)?;
+        let path = self.dir.join(format!("{hash}-{max_dim}.{extension}"));
+        std::fs::create_dir_all(&self.dir)?;
+        write_atomic_blocking(&path, &data)?;
+        let (width, height) = image.dimensions();
+        Ok(Thumbnail { path, width, height, from_cache: false })
+    }
+}
+
+/// Path of a thumbnail of the image at `image_path`, made at most `max_dim`
+/// pixels on its longest side and cached in the user's cache directory
+pub fn generate(image_path: &Path, max_dim: u32) -> Result<PathBuf> {
+    Ok(ThumbnailCache::default().thumbnail(image_path, max_dim)?.path)
+}
+
+/// `image` as a JPEG, or as a PNG when it has transparency, with the
+/// file extension to use
+fn encode(image: &DynamicImage) -> Result<(Vec<u8>, &'static str)> {
+    let encode_error = |e: image::ImageError| TradocumentError::UnsupportedFormat(format!("Thumbnail could not be encoded: {e}"));
+    let mut data = Vec::new();
+    if image.color().has_alpha() {
+        image.write_to(&mut Cursor::new(&mut data), ImageFormat::Png).map_err(encode_error)?;
+        return Ok((data, "png"));
+    }
+    let encoder = JpegEncoder::new_with_quality(&mut data, THUMBNAIL_JPEG_QUALITY);
+    image.to_rgb8().write_with_encoder(encoder).map_err(encode_error)?;
+    Ok((data, "jpg"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_thumbnail_fits_max_dimension() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("settings.png");
+        RgbaImage::from_fn(1600, 900, |x, y| Rgba([(x % 256) as u8, (y % 256) as u8, 90, 255 - (x % 128) as u8]))
+            .save(&source)
+            .unwrap();
+        let cache = ThumbnailCache::new(temp_dir.path().join("thumbnails"));
+
+        let thumbnail = cache.thumbnail(&source, 320).unwrap();
+        assert_eq!((thumbnail.width, thumbnail.height), (320, 180));
+        assert!(!thumbnail.from_cache);
+        assert_eq!(image::image_dimensions(&thumbnail.path).unwrap(), (320, 180));
+        assert!(image::open(&thumbnail.path).unwrap().color().has_alpha());
+
+        // Small images keep their size
+        let small = cache.thumbnail(&source, 4000).unwrap();
+        assert_eq!((small.width, small.height), (1600, 900));
+    }
+
+    #[test]
+    fn test_unchanged_source_is_read_from_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("panel.webp");
+        let photo = RgbImage::from_fn(600, 800, |x, y| Rgb([(x % 251) as u8, (y % 241) as u8, 40]));
+        photo.save_with_format(&source, ImageFormat::WebP).unwrap();
+        let cache = ThumbnailCache::new(temp_dir.path().join("thumbnails"));
+
+        let first = cache.thumbnail(&source, 200).unwrap();
+        assert_eq!((first.width, first.height), (150, 200));
+        assert_eq!(first.path.extension().unwrap(), "jpg");
+        let second = cache.thumbnail(&source, 200).unwrap();
+        assert!(second.from_cache);
+        assert_eq!(second.path, first.path);
+
+        // A changed source gets a new thumbnail
+        RgbImage::from_pixel(600, 800, Rgb([10, 20, 30])).save_with_format(&source, ImageFormat::WebP).unwrap();
+        let changed = cache.thumbnail(&source, 200).unwrap();
+        assert!(!changed.from_cache);
+        assert_ne!(changed.path, first.path);
+    }
+}
@@ -8,12 +8,20 @@ use tradocflow_translation_memory::{
     TranslationUnit as ExternalTranslationUnit,
     Term as ExternalTerm,
     Language,
+    ConsistencyCheckOptions,
+    InconsistencyGroup,
+    TermValidationError,
+    TranslationMemoryService,
 };
+use tradocflow_translation_memory::services::terminology::TerminologyValidationConfig;
 
 use crate::models::{
     document::TranslationUnit as LocalTranslationUnit,
     translation_models::LanguagePair,
 };
+use crate::services::collation::sort_localized;
+use crate::services::dnt;
+use crate::services::events::{AppEvent, EventBus};
 
 // Stub types for compatibility
 #[derive(Debug, Clone)]
@@ -178,6 +186,25 @@ impl TranslationMemoryAdapter {
         Ok(suggestions)
     }
     
+    /// Find source texts that were translated differently across the project,
+    /// so reviewers can standardize the wording
+    pub async fn find_inconsistencies(
+        &self,
+        language_pair: LanguagePair,
+        options: ConsistencyCheckOptions,
+    ) -> Result<Vec<InconsistencyGroup>> {
+        let pair = tradocflow_translation_memory::services::translation_memory::LanguagePair::new(
+            self.convert_language(&language_pair.source)?,
+            self.convert_language(&language_pair.target)?,
+        );
+        
+        self.translation_memory
+            .translation_memory()
+            .find_inconsistencies(&pair, &options)
+            .await
+            .map_err(|e| anyhow::anyhow!("Translation memory error: {}", e))
+    }
+    
     /// Update chunk linking (legacy compatibility)
     pub async fn update_chunk_linking(
         &self,
@@ -209,6 +236,11 @@ impl TranslationMemoryAdapter {
             .map(|v| v.text.clone())
             .unwrap_or_default();
         
+        // Text marked "do not translate" must reach the memory unchanged
+        if !target_text.is_empty() && !dnt::spans_preserved(&unit.source_text, &target_text) {
+            anyhow::bail!("Translation of unit {} changes text marked do not translate", unit.id);
+        }
+        
         let source_lang = self.convert_language(&unit.source_language)?;
         let source_lang_code = self.language_to_string(source_lang);
         let builder = TranslationUnitBuilder::new()
@@ -257,6 +289,11 @@ impl TranslationMemoryAdapter {
     }
     
     /// Convert Language enum to string
+    /// The translation memory behind the adapter
+    pub fn translation_memory(&self) -> &TranslationMemoryService {
+        self.translation_memory.translation_memory()
+    }
+    
     fn language_to_string(&self, lang: Language) -> String {
         lang.code().to_string()
     }
@@ -272,6 +309,7 @@ impl TranslationMemoryAdapter {
 #[derive(Clone)]
 pub struct TerminologyServiceAdapter {
     translation_memory: Arc<TradocFlowTranslationMemory>,
+    events: Option<EventBus>,
 }
 
 impl TerminologyServiceAdapter {
@@ -283,7 +321,27 @@ impl TerminologyServiceAdapter {
             TradocFlowTranslationMemory::new(db_path.to_str().unwrap()).await?
         );
         
-        Ok(Self { translation_memory })
+        Ok(Self { translation_memory, events: None })
+    }
+    
+    /// Publish an [`AppEvent::TermAdded`] to `events` for each term added
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+    
+    /// Add a term to the glossary of `project_id`
+    pub async fn add_term(&self, term: ExternalTerm, project_id: Uuid) -> Result<()> {
+        let (term_id, text) = (term.id, term.term.clone());
+        self.translation_memory
+            .terminology()
+            .add_terminology(term, project_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Translation memory error: {}", e))?;
+        if let Some(events) = &self.events {
+            events.publish(AppEvent::TermAdded { term_id, project_id, term: text });
+        }
+        Ok(())
     }
     
     /// Import terminology from CSV
@@ -297,6 +355,7 @@ impl TerminologyServiceAdapter {
             successful_imports: Vec::new(),
             failed_imports: Vec::new(),
             duplicate_terms: Vec::new(),
+            merged_rows: 0,
             total_processed: 0,
         })
     }
@@ -307,17 +366,35 @@ impl TerminologyServiceAdapter {
         Ok(Vec::new())
     }
     
-    /// Search terms
+    /// Search terms, in the alphabetical order of the source language
     pub async fn search_terms(
         &self,
         query: &str,
         source_lang: Language,
         target_lang: Language,
     ) -> Result<Vec<ExternalTerm>> {
+        let mut terms = self.translation_memory
+            .terminology()
+            .search_terms(query, source_lang.clone(), target_lang)
+            .await
+            .map_err(|e| anyhow::anyhow!("Translation memory error: {}", e))?;
+        sort_localized(&mut terms, source_lang.code(), |term| term.term.as_str());
+        Ok(terms)
+    }
+    
+    /// Review check of a translation against the glossary: forbidden
+    /// terms, dropped do-not-translate terms and, if `config` asks for it,
+    /// miscased terms
+    pub async fn check_terminology(
+        &self,
+        source_text: &str,
+        target_text: &str,
+        target_lang: &Language,
+        config: &TerminologyValidationConfig,
+    ) -> Vec<TermValidationError> {
         self.translation_memory
             .terminology()
-            .search_terms(query, source_lang, target_lang)
+            .validate_text(target_text, Some(source_text), target_lang, config)
             .await
-            .map_err(|e| anyhow::anyhow!("Translation memory error: {}", e))
     }
 }
\ No newline at end of file
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use crate::services::translation_memory_adapter::{
     TranslationMemoryAdapter, TranslationMatch, TranslationSource
 };
+use crate::services::events::EventSubscriber;
 use crate::models::{
     document::TranslationUnit,
     translation_models::LanguagePair
@@ -337,6 +338,21 @@ impl TranslationMemoryIntegrationService {
         suggestions.clear();
     }
 
+    /// Clear the suggestion cache whenever an event may have made it stale,
+    /// or events were missed, until the bus is dropped
+    pub fn invalidate_on_events(&self, mut events: EventSubscriber) -> tokio::task::JoinHandle<()> {
+        let active_suggestions = Arc::clone(&self.active_suggestions);
+        tokio::spawn(async move {
+            let mut missed = 0;
+            while let Some(event) = events.recv().await {
+                if event.invalidates_translation_memory() || events.missed() > missed {
+                    missed = events.missed();
+                    active_suggestions.write().await.clear();
+                }
+            }
+        })
+    }
+
     /// Get cached suggestions for text
     pub async fn get_cached_suggestions(&self, text: &str, language_pair: &LanguagePair) -> Option<Vec<EditorSuggestion>> {
         let cache_key = format!("{}:{}-{}", text, language_pair.source, language_pair.target);
@@ -406,7 +422,7 @@ pub struct SearchFilters {
 }
 
 /// Translation statistics for analytics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TranslationStatistics {
     pub cached_suggestions: usize,
     pub active_indicators: usize,
@@ -436,6 +452,39 @@ mod tests {
         assert_eq!(config.confidence_threshold, 0.7);
     }
 
+    #[tokio::test]
+    async fn test_events_invalidate_suggestion_cache() {
+        use crate::review_system::ReviewStatus;
+        use crate::services::events::{AppEvent, EventBus};
+        use crate::services::TerminologyServiceAdapter;
+        use tradocflow_translation_memory::Term;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let tm_service = Arc::new(TranslationMemoryAdapter::new(temp_dir.path().to_path_buf()).await.unwrap());
+        let integration_service = TranslationMemoryIntegrationService::new(tm_service).await.unwrap();
+        integration_service.active_suggestions.write().await.insert("Glocke:de-en".to_string(), Vec::new());
+
+        let event_bus = EventBus::default();
+        let invalidation = integration_service.invalidate_on_events(event_bus.subscribe());
+        assert_eq!(event_bus.subscriber_count(), 1);
+        event_bus.publish(AppEvent::ReviewStatusChanged {
+            review_id: Uuid::new_v4(),
+            document_id: Uuid::new_v4(),
+            status: ReviewStatus::Approved,
+        });
+        drop(event_bus);
+        invalidation.await.unwrap();
+        assert_eq!(integration_service.active_suggestions.read().await.len(), 1);
+
+        let event_bus = EventBus::default();
+        let invalidation = integration_service.invalidate_on_events(event_bus.subscribe());
+        let terminology = TerminologyServiceAdapter::new(temp_dir.path().to_path_buf()).await.unwrap().with_events(event_bus);
+        terminology.add_term(Term::new("Glocke".to_string(), None, false).unwrap(), Uuid::new_v4()).await.unwrap();
+        drop(terminology);
+        invalidation.await.unwrap();
+        assert!(integration_service.active_suggestions.read().await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_confidence_calculation() {
         let tm_service = Arc::new(
@@ -3,8 +3,8 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use rusqlite::OptionalExtension;
-use crate::models::{MemberRole, ProjectMember, AddMemberRequest};
-use crate::database::{DatabasePool, member_repository::MemberRepository};
+use crate::models::{MemberRole, ProjectMember, AddMemberRequest, MemberWithUserInfo};
+use crate::database::{DatabasePool, member_repository::{MemberRepository, OwnerGuardedWrite}};
 
 /// Enhanced user model for translation system
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -246,12 +246,7 @@ impl UserManagementService {
         request: InviteTeamMemberRequest,
     ) -> Result<TeamInvitation, UserManagementError> {
         // Validate inviter has permission to invite
-        let inviter_role = self.member_repository.get_member_role(project_id, inviter_id).await
-            .map_err(|e| UserManagementError::DatabaseError(e.to_string()))?;
-        
-        if !inviter_role.map_or(false, |role| role.can_manage_members()) {
-            return Err(UserManagementError::InsufficientPermissions);
-        }
+        self.require_member_manager(project_id, inviter_id).await?;
 
         let invitation = TeamInvitation {
             id: Uuid::new_v4(),
@@ -286,7 +281,8 @@ impl UserManagementService {
         Ok(invitation)
     }
 
-    /// Accept a team invitation
+    /// Accept a team invitation as the user it was sent to, found by the
+    /// invited email address
     pub async fn accept_invitation(&self, invitation_id: Uuid, user_id: &str) -> Result<ProjectMember, UserManagementError> {
         let invitation = self.get_invitation(invitation_id).await?
             .ok_or_else(|| UserManagementError::InvitationNotFound(invitation_id.to_string()))?;
@@ -299,22 +295,28 @@ impl UserManagementService {
             return Err(UserManagementError::InvitationExpired);
         }
 
+        let user = self.get_user(user_id).await?
+            .ok_or_else(|| UserManagementError::UserNotFound(user_id.to_string()))?;
+        if !user.email.trim().eq_ignore_ascii_case(invitation.invitee_email.trim()) {
+            return Err(UserManagementError::InvitationEmailMismatch);
+        }
+
         // Add user to project
         let add_request = AddMemberRequest {
             user_id: user_id.to_string(),
             role: invitation.role,
         };
 
-        let member = self.member_repository.add_member(
+        // Adding the member and accepting the invitation happen together, so a
+        // concurrent accept or decline cannot add the member twice
+        self.member_repository.add_invited_member(
+            invitation_id,
             invitation.project_id,
             add_request,
             invitation.inviter_id.clone(),
-        ).await.map_err(|e| UserManagementError::DatabaseError(e.to_string()))?;
-
-        // Update invitation status
-        self.update_invitation_status(invitation_id, InvitationStatus::Accepted).await?;
-
-        Ok(member)
+        ).await
+            .map_err(|e| UserManagementError::DatabaseError(e.to_string()))?
+            .ok_or(UserManagementError::InvitationAlreadyProcessed)
     }
 
     /// Decline a team invitation
@@ -330,31 +332,80 @@ impl UserManagementService {
              FROM team_invitations WHERE id = ?1"
         ).map_err(|e| UserManagementError::DatabaseError(e.to_string()))?;
 
-        let invitation = stmt.query_row(rusqlite::params![invitation_id.to_string()], |row| {
-            Ok(TeamInvitation {
-                id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
-                project_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
-                inviter_id: row.get(2)?,
-                invitee_email: row.get(3)?,
-                role: MemberRole::from_str(&row.get::<_, String>(4)?),
-                message: row.get(5)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(6)?)
-                    .unwrap().with_timezone(&Utc),
-                expires_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .unwrap().with_timezone(&Utc),
-                status: match row.get::<_, String>(8)?.as_str() {
-                    "pending" => InvitationStatus::Pending,
-                    "accepted" => InvitationStatus::Accepted,
-                    "declined" => InvitationStatus::Declined,
-                    "expired" => InvitationStatus::Expired,
-                    _ => InvitationStatus::Pending,
-                },
-            })
-        }).optional().map_err(|e| UserManagementError::DatabaseError(e.to_string()))?;
+        let invitation = stmt.query_row(rusqlite::params![invitation_id.to_string()], row_to_invitation)
+            .optional().map_err(|e| UserManagementError::DatabaseError(e.to_string()))?;
 
         Ok(invitation)
     }
 
+    /// Pending, unexpired invitations sent to `email`, in any case, newest first
+    pub async fn pending_invitations(&self, email: &str) -> Result<Vec<TeamInvitation>, UserManagementError> {
+        let conn = self.pool.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, inviter_id, invitee_email, role, message, created_at, expires_at, status
+             FROM team_invitations WHERE lower(invitee_email) = lower(?1) AND status = 'pending'
+             ORDER BY created_at DESC"
+        ).map_err(|e| UserManagementError::DatabaseError(e.to_string()))?;
+
+        let invitations = stmt.query_map(rusqlite::params![email.trim()], row_to_invitation)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| UserManagementError::DatabaseError(e.to_string()))?;
+
+        let now = Utc::now();
+        Ok(invitations.into_iter().filter(|invitation| invitation.expires_at >= now).collect())
+    }
+
+    /// Add a user to a project. `actor_id` must be allowed to manage members.
+    pub async fn add_member(&self, project_id: Uuid, actor_id: &str, request: AddMemberRequest) -> Result<ProjectMember, UserManagementError> {
+        self.require_member_manager(project_id, actor_id).await?;
+        self.member_repository.add_member(project_id, request, actor_id.to_string()).await
+            .map_err(|e| UserManagementError::DatabaseError(e.to_string()))
+    }
+
+    /// Members of a project with their names and emails, newest first
+    pub async fn list_members(&self, project_id: Uuid) -> Result<Vec<MemberWithUserInfo>, UserManagementError> {
+        self.member_repository.get_project_members(project_id).await
+            .map_err(|e| UserManagementError::DatabaseError(e.to_string()))
+    }
+
+    /// Give a member another role. `actor_id` must be allowed to manage
+    /// members, and the project's last owner can't be demoted.
+    pub async fn change_member_role(
+        &self,
+        project_id: Uuid,
+        actor_id: &str,
+        user_id: &str,
+        role: MemberRole,
+    ) -> Result<ProjectMember, UserManagementError> {
+        self.require_member_manager(project_id, actor_id).await?;
+        let written = self.member_repository.update_member_role_keeping_owner(project_id, user_id, role).await;
+        guarded_write(written, user_id)?;
+        self.get_member(project_id, user_id).await
+    }
+
+    /// Remove a member from a project. `actor_id` must be allowed to manage
+    /// members, and the project's last owner can't be removed.
+    pub async fn remove_member(&self, project_id: Uuid, actor_id: &str, user_id: &str) -> Result<(), UserManagementError> {
+        self.require_member_manager(project_id, actor_id).await?;
+        let written = self.member_repository.remove_member_keeping_owner(project_id, user_id).await;
+        guarded_write(written, user_id)
+    }
+
+    async fn get_member(&self, project_id: Uuid, user_id: &str) -> Result<ProjectMember, UserManagementError> {
+        self.member_repository.get_member(project_id, user_id).await
+            .map_err(|e| UserManagementError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| UserManagementError::NotAMember(user_id.to_string()))
+    }
+
+    async fn require_member_manager(&self, project_id: Uuid, actor_id: &str) -> Result<(), UserManagementError> {
+        let actor_role = self.member_repository.get_member_role(project_id, actor_id).await
+            .map_err(|e| UserManagementError::DatabaseError(e.to_string()))?;
+        if !actor_role.is_some_and(|role| role.can_manage_members()) {
+            return Err(UserManagementError::InsufficientPermissions);
+        }
+        Ok(())
+    }
+
     /// Update invitation status
     async fn update_invitation_status(&self, invitation_id: Uuid, status: InvitationStatus) -> Result<(), UserManagementError> {
         let status_str = match status {
@@ -387,6 +438,36 @@ impl UserManagementService {
     }
 }
 
+fn guarded_write(written: rusqlite::Result<OwnerGuardedWrite>, user_id: &str) -> Result<(), UserManagementError> {
+    match written.map_err(|e| UserManagementError::DatabaseError(e.to_string()))? {
+        OwnerGuardedWrite::Written => Ok(()),
+        OwnerGuardedWrite::NotAMember => Err(UserManagementError::NotAMember(user_id.to_string())),
+        OwnerGuardedWrite::LastOwner => Err(UserManagementError::LastOwner),
+    }
+}
+
+fn row_to_invitation(row: &rusqlite::Row) -> rusqlite::Result<TeamInvitation> {
+    Ok(TeamInvitation {
+        id: Uuid::parse_str(&row.get::<_, String>(0)?).unwrap(),
+        project_id: Uuid::parse_str(&row.get::<_, String>(1)?).unwrap(),
+        inviter_id: row.get(2)?,
+        invitee_email: row.get(3)?,
+        role: MemberRole::from_str(&row.get::<_, String>(4)?),
+        message: row.get(5)?,
+        created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(6)?)
+            .unwrap().with_timezone(&Utc),
+        expires_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+            .unwrap().with_timezone(&Utc),
+        status: match row.get::<_, String>(8)?.as_str() {
+            "pending" => InvitationStatus::Pending,
+            "accepted" => InvitationStatus::Accepted,
+            "declined" => InvitationStatus::Declined,
+            "expired" => InvitationStatus::Expired,
+            _ => InvitationStatus::Pending,
+        },
+    })
+}
+
 /// User management errors
 #[derive(Debug, thiserror::Error)]
 pub enum UserManagementError {
@@ -408,6 +489,15 @@ pub enum UserManagementError {
     #[error("Insufficient permissions")]
     InsufficientPermissions,
     
+    #[error("Invitation was sent to another email address")]
+    InvitationEmailMismatch,
+    
+    #[error("Not a project member: {0}")]
+    NotAMember(String),
+    
+    #[error("A project must keep at least one owner")]
+    LastOwner,
+    
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 }
\ No newline at end of file
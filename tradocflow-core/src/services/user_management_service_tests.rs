@@ -12,6 +12,33 @@ async fn setup_test_db() -> DatabasePool {
     pool
 }
 
+async fn create_user(service: &UserManagementService, name: &str, email: &str) -> String {
+    let request = CreateUserRequest {
+        name: name.to_string(),
+        email: email.to_string(),
+        languages: vec![],
+        specializations: vec![],
+        timezone: None,
+    };
+    service.create_user(request).await.unwrap().id
+}
+
+/// A project row in both project tables, since members reference one and
+/// invitations the other
+async fn insert_project(pool: &DatabasePool, project_id: Uuid, owner_id: &str) {
+    let conn = pool.lock().await;
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO projects (id, name, owner_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+        rusqlite::params![project_id.to_string(), "Test Project", owner_id, now],
+    ).unwrap();
+    conn.execute(
+        "INSERT INTO translation_projects (id, name, source_language, target_languages, project_path, settings, created_at, updated_at)
+         VALUES (?1, ?2, 'en', '[]', '/test/path', '{}', ?3, ?3)",
+        rusqlite::params![project_id.to_string(), "Test Project", now],
+    ).unwrap();
+}
+
 #[tokio::test]
 async fn test_create_user() {
     let pool = setup_test_db().await;
@@ -128,26 +155,9 @@ async fn test_invite_team_member() {
 
     // Create a project and add an admin user
     let project_id = Uuid::new_v4();
-    let admin_user_id = "admin_user";
+    let admin_user_id = &create_user(&service, "Admin", "admin@example.com").await;
     
-    // Insert project
-    {
-        let conn = pool.lock().await;
-        conn.execute(
-            "INSERT INTO translation_projects (id, name, source_language, target_languages, project_path, settings, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            rusqlite::params![
-                project_id.to_string(),
-                "Test Project",
-                "en",
-                "[]",
-                "/test/path",
-                "{}",
-                chrono::Utc::now().to_rfc3339(),
-                chrono::Utc::now().to_rfc3339(),
-            ],
-        ).unwrap();
-    }
+    insert_project(&pool, project_id, "system").await;
 
     // Add admin user to project
     let add_request = crate::models::AddMemberRequest {
@@ -166,7 +176,7 @@ async fn test_invite_team_member() {
     let invitation = service.invite_team_member(project_id, admin_user_id, invite_request).await.unwrap();
     
     assert_eq!(invitation.project_id, project_id);
-    assert_eq!(invitation.inviter_id, admin_user_id);
+    assert_eq!(&invitation.inviter_id, admin_user_id);
     assert_eq!(invitation.invitee_email, "newuser@example.com");
     assert_eq!(invitation.role, MemberRole::Translator);
     assert_eq!(invitation.message, Some("Welcome to the team!".to_string()));
@@ -183,24 +193,7 @@ async fn test_invite_insufficient_permissions() {
     let project_id = Uuid::new_v4();
     let translator_user_id = "translator_user";
     
-    // Insert project
-    {
-        let conn = pool.lock().await;
-        conn.execute(
-            "INSERT INTO translation_projects (id, name, source_language, target_languages, project_path, settings, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            rusqlite::params![
-                project_id.to_string(),
-                "Test Project",
-                "en",
-                "[]",
-                "/test/path",
-                "{}",
-                chrono::Utc::now().to_rfc3339(),
-                chrono::Utc::now().to_rfc3339(),
-            ],
-        ).unwrap();
-    }
+    insert_project(&pool, project_id, "system").await;
 
     // Add translator user to project
     let add_request = crate::models::AddMemberRequest {
@@ -228,27 +221,10 @@ async fn test_accept_invitation() {
 
     // Create a project and admin user
     let project_id = Uuid::new_v4();
-    let admin_user_id = "admin_user";
-    let new_user_id = "new_user";
-    
-    // Insert project
-    {
-        let conn = pool.lock().await;
-        conn.execute(
-            "INSERT INTO translation_projects (id, name, source_language, target_languages, project_path, settings, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            rusqlite::params![
-                project_id.to_string(),
-                "Test Project",
-                "en",
-                "[]",
-                "/test/path",
-                "{}",
-                chrono::Utc::now().to_rfc3339(),
-                chrono::Utc::now().to_rfc3339(),
-            ],
-        ).unwrap();
-    }
+    let admin_user_id = &create_user(&service, "Admin", "admin@example.com").await;
+    let new_user_id = create_user(&service, "New User", "NewUser@example.com").await;
+    let other_user_id = create_user(&service, "Other User", "other@example.com").await;
+    insert_project(&pool, project_id, "system").await;
 
     // Add admin user
     let add_request = crate::models::AddMemberRequest {
@@ -265,8 +241,17 @@ async fn test_accept_invitation() {
     };
     let invitation = service.invite_team_member(project_id, admin_user_id, invite_request).await.unwrap();
 
+    // The invitation is listed for its email address, in any case
+    let pending = service.pending_invitations("newuser@EXAMPLE.com").await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, invitation.id);
+
+    // Only the user with the invited email address can accept it
+    let result = service.accept_invitation(invitation.id, &other_user_id).await;
+    assert!(matches!(result, Err(UserManagementError::InvitationEmailMismatch)));
+
     // Accept invitation
-    let member = service.accept_invitation(invitation.id, new_user_id).await.unwrap();
+    let member = service.accept_invitation(invitation.id, &new_user_id).await.unwrap();
     
     assert_eq!(member.project_id, project_id);
     assert_eq!(member.user_id, new_user_id);
@@ -275,6 +260,52 @@ async fn test_accept_invitation() {
     // Verify invitation status updated
     let updated_invitation = service.get_invitation(invitation.id).await.unwrap().unwrap();
     assert_eq!(updated_invitation.status, InvitationStatus::Accepted);
+    assert!(service.pending_invitations("newuser@example.com").await.unwrap().is_empty());
+
+    let members = service.list_members(project_id).await.unwrap();
+    let joined = members.iter().find(|member| member.user_id == new_user_id).unwrap();
+    assert_eq!(joined.user_name, "New User");
+    assert_eq!(joined.role, MemberRole::Reviewer);
+}
+
+#[tokio::test]
+async fn test_accept_invitation_only_once() {
+    let pool = setup_test_db().await;
+    let service = UserManagementService::new(pool.clone());
+    let member_repo = MemberRepository::new(pool.clone());
+    let project_id = Uuid::new_v4();
+    let admin_user_id = create_user(&service, "Admin", "admin@example.com").await;
+    let new_user_id = create_user(&service, "New User", "newuser@example.com").await;
+    insert_project(&pool, project_id, "system").await;
+    let add_request = crate::models::AddMemberRequest {
+        user_id: admin_user_id.clone(),
+        role: MemberRole::Admin,
+    };
+    member_repo.add_member(project_id, add_request, "system".to_string()).await.unwrap();
+
+    let invite = |role| InviteTeamMemberRequest {
+        email: "newuser@example.com".to_string(),
+        role,
+        message: None,
+    };
+    let first = service.invite_team_member(project_id, &admin_user_id, invite(MemberRole::Translator)).await.unwrap();
+    let second = service.invite_team_member(project_id, &admin_user_id, invite(MemberRole::Reviewer)).await.unwrap();
+
+    service.accept_invitation(first.id, &new_user_id).await.unwrap();
+    let again = service.accept_invitation(first.id, &new_user_id).await;
+    assert!(matches!(again, Err(UserManagementError::InvitationAlreadyProcessed)));
+
+    // The user is already a member, so the second invitation cannot add them
+    // and stays pending rather than being marked accepted
+    let duplicate = service.accept_invitation(second.id, &new_user_id).await;
+    assert!(matches!(duplicate, Err(UserManagementError::DatabaseError(_))));
+    let second = service.get_invitation(second.id).await.unwrap().unwrap();
+    assert_eq!(second.status, InvitationStatus::Pending);
+
+    let members = service.list_members(project_id).await.unwrap();
+    let joined: Vec<_> = members.iter().filter(|member| member.user_id == new_user_id).collect();
+    assert_eq!(joined.len(), 1);
+    assert_eq!(joined[0].role, MemberRole::Translator);
 }
 
 #[tokio::test]
@@ -285,26 +316,9 @@ async fn test_decline_invitation() {
 
     // Create a project and admin user
     let project_id = Uuid::new_v4();
-    let admin_user_id = "admin_user";
+    let admin_user_id = &create_user(&service, "Admin", "admin@example.com").await;
     
-    // Insert project
-    {
-        let conn = pool.lock().await;
-        conn.execute(
-            "INSERT INTO translation_projects (id, name, source_language, target_languages, project_path, settings, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            rusqlite::params![
-                project_id.to_string(),
-                "Test Project",
-                "en",
-                "[]",
-                "/test/path",
-                "{}",
-                chrono::Utc::now().to_rfc3339(),
-                chrono::Utc::now().to_rfc3339(),
-            ],
-        ).unwrap();
-    }
+    insert_project(&pool, project_id, "system").await;
 
     // Add admin user
     let add_request = crate::models::AddMemberRequest {
@@ -327,4 +341,88 @@ async fn test_decline_invitation() {
     // Verify invitation status updated
     let updated_invitation = service.get_invitation(invitation.id).await.unwrap().unwrap();
     assert_eq!(updated_invitation.status, InvitationStatus::Declined);
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_change_member_role() {
+    let pool = setup_test_db().await;
+    let service = UserManagementService::new(pool.clone());
+    let member_repo = MemberRepository::new(pool.clone());
+    let project_id = Uuid::new_v4();
+    let owner_id = create_user(&service, "Olga", "olga@example.com").await;
+    let translator_id = create_user(&service, "Tom", "tom@example.com").await;
+    insert_project(&pool, project_id, "system").await;
+
+    let add_request = crate::models::AddMemberRequest { user_id: owner_id.clone(), role: MemberRole::Owner };
+    member_repo.add_member(project_id, add_request, "system".to_string()).await.unwrap();
+    let add_request = crate::models::AddMemberRequest { user_id: translator_id.clone(), role: MemberRole::Translator };
+    service.add_member(project_id, &owner_id, add_request).await.unwrap();
+
+    // A translator can't manage members
+    let result = service.change_member_role(project_id, &translator_id, &translator_id, MemberRole::Admin).await;
+    assert!(matches!(result, Err(UserManagementError::InsufficientPermissions)));
+
+    let member = service.change_member_role(project_id, &owner_id, &translator_id, MemberRole::Reviewer).await.unwrap();
+    assert_eq!(member.role, MemberRole::Reviewer);
+    assert_eq!(member_repo.get_member_role(project_id, &translator_id).await.unwrap(), Some(MemberRole::Reviewer));
+
+    let result = service.change_member_role(project_id, &owner_id, "nobody", MemberRole::Viewer).await;
+    assert!(matches!(result, Err(UserManagementError::NotAMember(_))));
+}
+
+#[tokio::test]
+async fn test_last_owner_cannot_be_removed_or_demoted() {
+    let pool = setup_test_db().await;
+    let service = UserManagementService::new(pool.clone());
+    let member_repo = MemberRepository::new(pool.clone());
+    let project_id = Uuid::new_v4();
+    let owner_id = create_user(&service, "Olga", "olga@example.com").await;
+    let co_owner_id = create_user(&service, "Otto", "otto@example.com").await;
+    insert_project(&pool, project_id, "system").await;
+
+    let add_request = crate::models::AddMemberRequest { user_id: owner_id.clone(), role: MemberRole::Owner };
+    member_repo.add_member(project_id, add_request, "system".to_string()).await.unwrap();
+
+    let result = service.remove_member(project_id, &owner_id, &owner_id).await;
+    assert!(matches!(result, Err(UserManagementError::LastOwner)));
+    let result = service.change_member_role(project_id, &owner_id, &owner_id, MemberRole::Admin).await;
+    assert!(matches!(result, Err(UserManagementError::LastOwner)));
+    assert_eq!(service.list_members(project_id).await.unwrap().len(), 1);
+
+    // With a second owner, either can leave
+    let add_request = crate::models::AddMemberRequest { user_id: co_owner_id.clone(), role: MemberRole::Owner };
+    service.add_member(project_id, &owner_id, add_request).await.unwrap();
+    service.remove_member(project_id, &co_owner_id, &owner_id).await.unwrap();
+
+    let members = service.list_members(project_id).await.unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].user_id, co_owner_id);
+    let result = service.remove_member(project_id, &co_owner_id, &co_owner_id).await;
+    assert!(matches!(result, Err(UserManagementError::LastOwner)));
+}
+
+#[tokio::test]
+async fn test_concurrent_owner_demotions_keep_one_owner() {
+    let pool = setup_test_db().await;
+    let service = UserManagementService::new(pool.clone());
+    let member_repo = MemberRepository::new(pool.clone());
+    let project_id = Uuid::new_v4();
+    let first = create_user(&service, "Olga", "olga@example.com").await;
+    let second = create_user(&service, "Otto", "otto@example.com").await;
+    insert_project(&pool, project_id, "system").await;
+    for user_id in [&first, &second] {
+        let add_request = crate::models::AddMemberRequest { user_id: user_id.clone(), role: MemberRole::Owner };
+        member_repo.add_member(project_id, add_request, "system".to_string()).await.unwrap();
+    }
+
+    // Each owner demotes themselves at the same time
+    let (first_demoted, second_demoted) = tokio::join!(
+        service.change_member_role(project_id, &first, &first, MemberRole::Admin),
+        service.change_member_role(project_id, &second, &second, MemberRole::Admin),
+    );
+    let results = [first_demoted, second_demoted];
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+    assert!(results.iter().any(|result| matches!(result, Err(UserManagementError::LastOwner))));
+    let members = service.list_members(project_id).await.unwrap();
+    assert_eq!(members.iter().filter(|member| member.role == MemberRole::Owner).count(), 1);
+}
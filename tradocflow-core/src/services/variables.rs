@@ -0,0 +1,166 @@
+use crate::Result;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Word in the info string of a fenced code block, as in ```` ```sh vars ````,
+/// that lets variables be substituted inside the block
+pub const CODE_VARIABLES_FLAG: &str = "vars";
+
+fn variable_regex() -> &'static Regex {
+    static VARIABLE: OnceLock<Regex> = OnceLock::new();
+    VARIABLE.get_or_init(|| Regex::new(r"\{\{var:\s*([A-Za-z_][\w.-]*)\s*\}\}").expect("valid variable regex"))
+}
+
+fn missing_regex() -> &'static Regex {
+    static MISSING: OnceLock<Regex> = OnceLock::new();
+    MISSING.get_or_init(|| Regex::new(r"\{\{var:([A-Za-z_][\w.-]*)\?\}\}").expect("valid missing variable regex"))
+}
+
+/// Values of the `{{var:name}}` placeholders of a project's documents, such
+/// as the product name or version, so renaming the product is a change in
+/// one place. Read from TOML as
+///
+/// ```toml
+/// [default]
+/// product_name = "BellTower"
+///
+/// [languages.de]
+/// product_name = "Glockenturm"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectVariables {
+    /// Values in every language
+    #[serde(default)]
+    pub default: HashMap<String, String>,
+    /// Values by language code, which take precedence over `default`
+    #[serde(default)]
+    pub languages: HashMap<String, HashMap<String, String>>,
+}
+
+/// Markdown with its variables substituted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubstitutedText {
+    /// The markdown, each undefined variable written as `{{var:name?}}`
+    pub text: String,
+    /// Names used but not defined, in order of first use
+    pub undefined: Vec<String>,
+}
+
+impl ProjectVariables {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Value of `name` in `language`, or its default value
+    pub fn get(&self, name: &str, language: &str) -> Option<&str> {
+        self.languages
+            .get(language)
+            .and_then(|values| values.get(name))
+            .or_else(|| self.default.get(name))
+            .map(String::as_str)
+    }
+
+    /// Replace the variables of `markdown` with their values in `language`.
+    /// Inline code and fenced code blocks are left alone, except for blocks
+    /// whose info string holds [`CODE_VARIABLES_FLAG`].
+    pub fn substitute(&self, markdown: &str, language: &str) -> SubstitutedText {
+        let mut substituted = SubstitutedText { text: String::with_capacity(markdown.len()), undefined: Vec::new() };
+        let mut fence: Option<(&str, bool)> = None;
+
+        for line in markdown.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if let Some((marker, substitute)) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                    substituted.text.push_str(line);
+                } else if substitute {
+                    substituted.text.push_str(&self.replace(line, language, &mut substituted.undefined));
+                } else {
+                    substituted.text.push_str(line);
+                }
+                continue;
+            }
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                let substitute = trimmed[3..].split_whitespace().any(|word| word == CODE_VARIABLES_FLAG);
+                fence = Some((&trimmed[..3], substitute));
+                substituted.text.push_str(line);
+                continue;
+            }
+            // Odd parts lie between backticks, in inline code
+            for (i, part) in line.split('`').enumerate() {
+                if i > 0 {
+                    substituted.text.push('`');
+                }
+                if i % 2 == 1 {
+                    substituted.text.push_str(part);
+                } else {
+                    substituted.text.push_str(&self.replace(part, language, &mut substituted.undefined));
+                }
+            }
+        }
+        substituted
+    }
+
+    fn replace(&self, text: &str, language: &str, undefined: &mut Vec<String>) -> String {
+        variable_regex()
+            .replace_all(text, |captures: &Captures| {
+                let name = &captures[1];
+                match self.get(name, language) {
+                    Some(value) => value.to_string(),
+                    None => {
+                        if !undefined.iter().any(|known| known == name) {
+                            log::warn!("Variable {{{{var:{name}}}}} is used but not defined for {language}");
+                            undefined.push(name.to_string());
+                        }
+                        format!("{{{{var:{name}?}}}}")
+                    }
+                }
+            })
+            .into_owned()
+    }
+}
+
+/// Rendered HTML with the undefined variables [`ProjectVariables::substitute`]
+/// left behind flagged
+pub fn missing_variables_to_html(html: &str) -> String {
+    missing_regex()
+        .replace_all(html, r#"<span class="variable-missing" title="Undefined variable">{{var:$1?}}</span>"#)
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> ProjectVariables {
+        ProjectVariables::from_toml(
+            "[default]\nproduct_name = \"BellTower\"\nversion = \"2.1\"\n\n[languages.de]\nproduct_name = \"Glockenturm\"\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_variable_substituted_in_prose_not_code() {
+        let markdown = "Install {{var:product_name}} {{var:version}}.\n\nRun `{{var:product_name}}`.\n\n```\necho {{var:version}}\n```\n\n```sh vars\necho {{var:version}}\n```\n";
+        let substituted = variables().substitute(markdown, "de");
+        assert!(substituted.undefined.is_empty());
+        assert_eq!(
+            substituted.text,
+            "Install Glockenturm 2.1.\n\nRun `{{var:product_name}}`.\n\n```\necho {{var:version}}\n```\n\n```sh vars\necho 2.1\n```\n"
+        );
+        assert!(variables().substitute(markdown, "en").text.starts_with("Install BellTower 2.1."));
+    }
+
+    #[test]
+    fn test_undefined_variable_is_flagged() {
+        let substituted = variables().substitute("Call {{var:support_phone}} or {{var:support_phone}}.\n", "en");
+        assert_eq!(substituted.undefined, ["support_phone"]);
+        assert_eq!(substituted.text, "Call {{var:support_phone?}} or {{var:support_phone?}}.\n");
+        assert_eq!(
+            missing_variables_to_html("<p>Call {{var:support_phone?}}.</p>"),
+            r#"<p>Call <span class="variable-missing" title="Undefined variable">{{var:support_phone?}}</span>.</p>"#
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cli_manual")
+}
+
+fn cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tradocflow_cli"))
+        .args(args)
+        // PDF fonts are looked up relative to the crate
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("tradocflow_cli runs")
+}
+
+#[test]
+fn test_export_writes_requested_language_and_format() {
+    let out_dir = TempDir::new().unwrap();
+    let fixture = fixture();
+    let output = cli(&[
+        "export",
+        fixture.to_str().unwrap(),
+        "--format",
+        "pdf",
+        "--lang",
+        "de",
+        "--out",
+        out_dir.path().to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let exported = out_dir.path().join("Controller-Installation-de.pdf");
+    assert!(fs::metadata(&exported).unwrap().len() > 0);
+    assert!(!out_dir.path().join("Controller-Installation-en.pdf").exists());
+}
+
+#[test]
+fn test_validate_exit_codes() {
+    let fixture = fixture();
+    let output = cli(&["validate", fixture.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stdout));
+
+    // Drop the required Configuration section the documents refer to
+    let broken = TempDir::new().unwrap();
+    fs::create_dir_all(broken.path().join("documents")).unwrap();
+    for entry in fs::read_dir(fixture.join("documents")).unwrap() {
+        let path = entry.unwrap().path();
+        fs::copy(&path, broken.path().join("documents").join(path.file_name().unwrap())).unwrap();
+    }
+    let mut manual: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(fixture.join("manual.json")).unwrap()).unwrap();
+    manual["sections"].as_array_mut().unwrap().retain(|section| section["title"] != "Configuration");
+    fs::write(broken.path().join("manual.json"), manual.to_string()).unwrap();
+
+    let output = cli(&["validate", broken.path().to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let report = String::from_utf8_lossy(&output.stdout);
+    assert!(report.contains("Required section \"Configuration\" is missing"));
+    assert!(report.contains("reference to missing section"));
+
+    assert_eq!(cli(&["validate"]).status.code(), Some(2));
+    assert_eq!(cli(&["export", fixture.to_str().unwrap(), "--lang", "fr"]).status.code(), Some(2));
+}
+
+#[test]
+fn test_validate_json_prints_lint_report() {
+    let fixture = fixture();
+    let output = cli(&["validate", fixture.to_str().unwrap(), "--lang", "en", "--json"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["schema_version"], 1);
+    assert_eq!(report["report"], "lint");
+    let results = report["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result["language"] == "en" && result["warnings"].is_array()));
+}
+
+#[test]
+fn test_validate_warns_about_screenshots_without_alt_text() {
+    let fixture = fixture();
+    let manual_dir = TempDir::new().unwrap();
+    fs::copy(fixture.join("manual.json"), manual_dir.path().join("manual.json")).unwrap();
+    fs::create_dir_all(manual_dir.path().join("documents")).unwrap();
+    for entry in fs::read_dir(fixture.join("documents")).unwrap() {
+        let path = entry.unwrap().path();
+        fs::copy(&path, manual_dir.path().join("documents").join(path.file_name().unwrap())).unwrap();
+    }
+    let introduction = manual_dir.path().join("documents/6f1c1a52-3a55-4c57-9d0e-0a6a3c7f0001.json");
+    let mut document: serde_json::Value = serde_json::from_str(&fs::read_to_string(&introduction).unwrap()).unwrap();
+    document["content"]["en"] = "# Introduction\n\nThis guide installs the controller.\n\n{screenshot:panel}\n".into();
+    document["metadata"]["screenshots"] = serde_json::json!([
+        { "id": "panel", "language": "en", "screen_config": "{}", "generated_at": null }
+    ]);
+    fs::write(&introduction, document.to_string()).unwrap();
+
+    let output = cli(&["validate", manual_dir.path().to_str().unwrap(), "--lang", "en"]);
+    assert_eq!(output.status.code(), Some(0));
+    let report = String::from_utf8_lossy(&output.stdout);
+    assert!(report.contains("warning: Introduction [en] line 5: Screenshot panel has no en alt text"), "{report}");
+
+    let strict = cli(&["validate", manual_dir.path().to_str().unwrap(), "--lang", "en", "--strict"]);
+    assert_eq!(strict.status.code(), Some(1));
+}
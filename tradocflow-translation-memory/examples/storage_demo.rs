@@ -9,7 +9,7 @@ use tradocflow_translation_memory::{
         TranslationMemoryStorage, TerminologyStorage, ChunkStorage, UnifiedStorageProvider,
         StorageConfig,
     },
-    models::{TranslationUnit, TranslationUnitBuilder, Terminology, Language, Chunk},
+    models::{TranslationUnit, TranslationUnitBuilder, Terminology, TermStatus, Language, Chunk},
     services::translation_memory::LanguagePair,
     error::Result,
 };
@@ -90,6 +90,9 @@ async fn main() -> Result<()> {
         term: "API".to_string(),
         definition: Some("Application Programming Interface".to_string()),
         do_not_translate: true,
+        domain: None,
+        language: None,
+        status: TermStatus::Approved,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -383,9 +383,9 @@ pub async fn get_memory_statistics(
     let (cache_entries, hits, misses, hit_ratio, last_updated) = state.translation_memory.translation_memory()
         .get_detailed_cache_stats().await;
 
-    // Get database statistics
+    // Get storage statistics
     let db_stats = state.translation_memory.translation_memory()
-        .get_database_stats().await
+        .get_storage_stats().await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
     // Get connection pool stats
@@ -393,7 +393,7 @@ pub async fn get_memory_statistics(
         .get_connection_pool_stats().await;
 
     let stats = MemoryStatsResponse {
-        total_units: db_stats.translation_units_count as usize,
+        total_units: db_stats.total_translation_units as usize,
         language_pairs: vec![], // TODO: Extract language pairs from database
         last_updated: last_updated.map(|dt| dt.to_rfc3339()),
         cache_stats: CacheStatsResponse {
@@ -403,8 +403,8 @@ pub async fn get_memory_statistics(
             hit_ratio,
         },
         database_stats: DatabaseStatsResponse {
-            total_rows: db_stats.translation_units_count as usize,
-            database_size: format!("{} bytes", db_stats.database_size_bytes),
+            total_rows: db_stats.total_translation_units as usize,
+            database_size: format!("{} bytes", db_stats.storage_size_bytes),
             connection_pool_active: active_connections,
             connection_pool_idle: idle_connections,
         },
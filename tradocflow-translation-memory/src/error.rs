@@ -8,18 +8,18 @@ pub type Result<T> = std::result::Result<T, TranslationMemoryError>;
 /// Comprehensive error type for translation memory operations
 #[derive(Error, Debug)]
 pub enum TranslationMemoryError {
-    // Note: DuckDB and Parquet dependencies are temporarily disabled
+    // Note: DuckDB dependencies are temporarily disabled
     // #[cfg(feature = "duckdb-storage")]
     // #[error("Database error: {0}")]
     // Database(#[from] duckdb::Error),
     
-    // #[cfg(feature = "parquet-export")]
-    // #[error("Parquet error: {0}")]
-    // Parquet(#[from] parquet::errors::ParquetError),
+    #[cfg(feature = "parquet-export")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
     
-    // #[cfg(any(feature = "duckdb-storage", feature = "parquet-export"))]
-    // #[error("Arrow error: {0}")]
-    // Arrow(#[from] arrow::error::ArrowError),
+    #[cfg(feature = "parquet-export")]
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
     
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -132,6 +132,9 @@ pub enum TranslationMemoryError {
     
     #[error("Generic error: {0}")]
     Generic(String),
+    
+    #[error("Operation cancelled")]
+    Cancelled,
 }
 
 impl TranslationMemoryError {
@@ -167,10 +170,8 @@ impl TranslationMemoryError {
             // Temporarily disabled error variants
             // #[cfg(feature = "duckdb-storage")]
             // Self::Database(_) => ErrorCategory::Storage,
-            // #[cfg(feature = "parquet-export")]
-            // Self::Parquet(_) => ErrorCategory::Storage,
-            // #[cfg(any(feature = "duckdb-storage", feature = "parquet-export"))]
-            // Self::Arrow(_) => ErrorCategory::Storage,
+            #[cfg(feature = "parquet-export")]
+            Self::Parquet(_) | Self::Arrow(_) => ErrorCategory::Storage,
             Self::DatabaseError(_) | Self::StorageError(_) => ErrorCategory::Storage,
             Self::Io(_) | Self::Network(_) => ErrorCategory::IO,
             #[cfg(feature = "terminology-csv")]
@@ -199,6 +200,7 @@ impl TranslationMemoryError {
             Self::ParsingError(_) | Self::EncodingError(_) => ErrorCategory::Serialization,
             Self::Regex(_) => ErrorCategory::Logic,
             Self::Generic(_) => ErrorCategory::Unknown,
+            Self::Cancelled => ErrorCategory::Concurrency,
         }
     }
     
@@ -236,6 +238,16 @@ impl TranslationMemoryError {
     }
 }
 
+/// Return [`TranslationMemoryError::Cancelled`] once `cancel` is triggered;
+/// long operations call this between phases
+pub fn ensure_not_cancelled(cancel: &tokio_util::sync::CancellationToken) -> Result<()> {
+    if cancel.is_cancelled() {
+        Err(TranslationMemoryError::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
 /// Error categories for monitoring and alerting
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorCategory {
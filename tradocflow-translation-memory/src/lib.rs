@@ -15,6 +15,7 @@ pub mod api;
 
 // Re-export key types for easier access
 pub use error::{TranslationMemoryError, Result};
+pub use tokio_util::sync::CancellationToken;
 pub use models::{
     TranslationUnit, 
     TranslationUnitBuilder, 
@@ -24,6 +25,7 @@ pub use models::{
     MatchType,
     MatchScore,
     Term,
+    TermStatus,
     TerminologyCsvRecord,
     TerminologyImportResult,
     TerminologyImportError,
@@ -36,13 +38,18 @@ pub use models::{
     ValidationError,
     TranslationStatus,
     Language,
+    is_rtl_code,
     Domain,
     Quality,
     Metadata,
+    SearchQuery,
+    SearchPage,
 };
 pub use services::{
     translation_memory::TranslationMemoryService,
-    terminology::TerminologyService,
+    translation_memory::{ConsistencyCheckOptions, InconsistencyGroup, MergeReport, MergeStrategy, TranslationVariant, UnitLocation},
+    lookup_cache::{LookupCacheConfig, LookupCacheStats},
+    terminology::{TerminologyService, DomainScope, ImportProgress, TermFilter, TermRule, TermUsage, TermValidationError},
     highlighting::HighlightingService,
 };
 pub use storage::chunk_manager::ChunkManager;
@@ -73,7 +80,7 @@ impl TradocFlowTranslationMemory {
             .to_path_buf();
             
         // Create services with thread-safe implementations
-        let tm_service = TranslationMemoryService::new(project_id, project_path.clone()).await?;
+        let tm_service = TranslationMemoryService::open(project_id, project_path.clone()).await?;
         
         // Create CSV processor for terminology service
         let csv_processor = std::sync::Arc::new(crate::utils::CsvProcessor::new());
@@ -112,7 +119,7 @@ impl TradocFlowTranslationMemory {
             .to_path_buf();
         
         // Create services with correct constructors
-        let tm_service = TranslationMemoryService::new(project_id, project_path.clone()).await?;
+        let tm_service = TranslationMemoryService::open(project_id, project_path.clone()).await?;
         
         // Create CSV processor for terminology service
         let csv_processor = std::sync::Arc::new(crate::utils::CsvProcessor::new());
@@ -149,8 +156,21 @@ impl TradocFlowTranslationMemory {
     
     /// Perform a comprehensive search across both translation memory and terminology
     pub async fn comprehensive_search(&self, query: &str, source_lang: Language, target_lang: Language) -> Result<ComprehensiveSearchResult> {
-        let tm_matches = self.tm_service.search(query, source_lang.clone(), target_lang.clone(), 0.7).await?;
-        let terminology_matches = self.terminology_service.search_terms(query, source_lang, target_lang).await?;
+        self.comprehensive_search_cancellable(query, source_lang, target_lang, &CancellationToken::new()).await
+    }
+    
+    /// Perform a comprehensive search that stops with
+    /// [`TranslationMemoryError::Cancelled`] once `cancel` is triggered, so an
+    /// editor can drop a query the user has already typed past
+    pub async fn comprehensive_search_cancellable(
+        &self,
+        query: &str,
+        source_lang: Language,
+        target_lang: Language,
+        cancel: &CancellationToken,
+    ) -> Result<ComprehensiveSearchResult> {
+        let tm_matches = self.tm_service.search_cancellable(query, source_lang.clone(), target_lang.clone(), 0.7, cancel).await?;
+        let terminology_matches = self.terminology_service.search_terms_cancellable(query, source_lang, target_lang, cancel).await?;
         
         Ok(ComprehensiveSearchResult {
             translation_matches: tm_matches,
@@ -196,4 +216,22 @@ mod tests {
         assert!(result.translation_matches.is_empty());
         assert!(result.terminology_matches.is_empty());
     }
+    
+    #[tokio::test]
+    async fn test_cancelled_search_returns_cancelled() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_tm.db");
+        let tm = TradocFlowTranslationMemory::new(db_path.to_str().unwrap()).await.unwrap();
+        
+        // The user typed the next character before the scan got to run;
+        // cancelling up front keeps the outcome independent of timing
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = tm.comprehensive_search_cancellable("Hello world", Language::English, Language::Spanish, &cancel).await;
+        assert!(matches!(result, Err(TranslationMemoryError::Cancelled)));
+        
+        // The service stays usable for the next query
+        let result = tm.comprehensive_search("Hello world!", Language::English, Language::Spanish).await;
+        assert!(result.is_ok());
+    }
 }
\ No newline at end of file
@@ -269,6 +269,11 @@ impl Language {
             Language::Custom(code) => code,
         }
     }
+
+    /// Whether the language is written right to left
+    pub fn is_rtl(&self) -> bool {
+        is_rtl_code(self.code())
+    }
     
     /// Get the display name
     pub fn name(&self) -> &str {
@@ -391,6 +396,16 @@ impl std::fmt::Display for Language {
     }
 }
 
+/// Primary language subtags of scripts written right to left
+const RTL_LANGUAGE_CODES: &[&str] = &["ar", "arc", "ckb", "dv", "fa", "he", "iw", "ps", "sd", "ug", "ur", "yi"];
+
+/// Whether text in the language with the BCP 47 tag `code`, such as `ar`
+/// or `fa-IR`, is written right to left
+pub fn is_rtl_code(code: &str) -> bool {
+    let primary = code.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+    RTL_LANGUAGE_CODES.contains(&primary.as_str())
+}
+
 /// Domain/subject areas for content categorization
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Domain {
@@ -422,6 +437,71 @@ pub enum Domain {
 }
 
 impl Domain {
+    /// All domains, in declaration order
+    pub const ALL: [Domain; 25] = [
+        Domain::General,
+        Domain::Technical,
+        Domain::Medical,
+        Domain::Legal,
+        Domain::Financial,
+        Domain::Marketing,
+        Domain::Education,
+        Domain::Science,
+        Domain::Literature,
+        Domain::News,
+        Domain::Software,
+        Domain::Gaming,
+        Domain::Travel,
+        Domain::Cooking,
+        Domain::Sports,
+        Domain::Music,
+        Domain::Art,
+        Domain::History,
+        Domain::Politics,
+        Domain::Religion,
+        Domain::Business,
+        Domain::Automotive,
+        Domain::Fashion,
+        Domain::Health,
+        Domain::Environment,
+    ];
+    
+    /// Short name used in CSV files, e.g. "Technical"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Domain::General => "General",
+            Domain::Technical => "Technical",
+            Domain::Medical => "Medical",
+            Domain::Legal => "Legal",
+            Domain::Financial => "Financial",
+            Domain::Marketing => "Marketing",
+            Domain::Education => "Education",
+            Domain::Science => "Science",
+            Domain::Literature => "Literature",
+            Domain::News => "News",
+            Domain::Software => "Software",
+            Domain::Gaming => "Gaming",
+            Domain::Travel => "Travel",
+            Domain::Cooking => "Cooking",
+            Domain::Sports => "Sports",
+            Domain::Music => "Music",
+            Domain::Art => "Art",
+            Domain::History => "History",
+            Domain::Politics => "Politics",
+            Domain::Religion => "Religion",
+            Domain::Business => "Business",
+            Domain::Automotive => "Automotive",
+            Domain::Fashion => "Fashion",
+            Domain::Health => "Health",
+            Domain::Environment => "Environment",
+        }
+    }
+    
+    /// Parse a short name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|domain| domain.name().eq_ignore_ascii_case(name.trim()))
+    }
+    
     /// Get domain description
     pub fn description(&self) -> &'static str {
         match self {
@@ -547,10 +627,87 @@ impl Metadata {
     }
 }
 
+/// Results returned by a search when no limit is given
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Which page of a search's results to return
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchQuery {
+    /// Maximum number of results on the page
+    pub limit: usize,
+    /// Number of results skipped before the page
+    pub offset: usize,
+}
+
+impl Default for SearchQuery {
+    fn default() -> Self {
+        Self { limit: DEFAULT_SEARCH_LIMIT, offset: 0 }
+    }
+}
+
+impl SearchQuery {
+    /// First page of [`DEFAULT_SEARCH_LIMIT`] results
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+    
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+    
+    /// The query for the page after this one
+    pub fn next_page(self) -> Self {
+        self.offset(self.offset + self.limit)
+    }
+}
+
+/// One page of search results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchPage<T> {
+    pub items: Vec<T>,
+    /// Results across all pages
+    pub total_available: usize,
+    pub offset: usize,
+}
+
+impl<T> SearchPage<T> {
+    /// Cut the page `query` asks for out of every result, which must
+    /// already be in a stable order
+    pub fn from_results(results: Vec<T>, query: &SearchQuery) -> Self {
+        let total_available = results.len();
+        let items = results.into_iter().skip(query.offset).take(query.limit).collect();
+        Self { items, total_available, offset: query.offset }
+    }
+    
+    /// Whether results remain after this page
+    pub fn has_more(&self) -> bool {
+        self.offset + self.items.len() < self.total_available
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_search_page_bounds() {
+        let query = SearchQuery::new().limit(10).offset(20);
+        let page = SearchPage::from_results((0..25).collect(), &query);
+        assert_eq!(page.items, vec![20, 21, 22, 23, 24]);
+        assert_eq!(page.total_available, 25);
+        assert!(!page.has_more());
+        
+        let past_end = SearchPage::from_results((0..25).collect::<Vec<i32>>(), &query.next_page());
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.offset, 30);
+    }
+    
     #[test]
     fn test_language_pair() {
         let pair = LanguagePair::from_codes("en", "es");
@@ -575,6 +732,17 @@ mod tests {
         assert_eq!(Language::from_code("en"), Some(Language::English));
         assert_eq!(Language::from_code("unknown"), Some(Language::Custom("unknown".to_string())));
     }
+
+    #[test]
+    fn test_text_direction() {
+        assert!(Language::Arabic.is_rtl());
+        assert!(Language::Hebrew.is_rtl());
+        assert!(Language::Custom("fa-IR".to_string()).is_rtl());
+        assert!(!Language::English.is_rtl());
+        assert!(!Language::Custom("de_AT".to_string()).is_rtl());
+        assert!(is_rtl_code("AR-eg"));
+        assert!(!is_rtl_code(""));
+    }
     
     #[test]
     fn test_translation_status_workflow() {
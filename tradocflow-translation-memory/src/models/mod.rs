@@ -22,6 +22,7 @@ pub use translation_unit::{
 pub use terminology::{
     Term, 
     Term as Terminology, // Alias for compatibility
+    TermStatus,
     TerminologyCsvRecord, 
     TerminologyImportResult, 
     TerminologyImportError,
@@ -43,7 +44,11 @@ pub use common::{
     ValidationError, 
     TranslationStatus,
     Language, 
+    is_rtl_code,
     Domain, 
     Quality, 
-    Metadata
+    Metadata,
+    SearchQuery,
+    SearchPage,
+    DEFAULT_SEARCH_LIMIT
 };
\ No newline at end of file
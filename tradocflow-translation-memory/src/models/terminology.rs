@@ -2,7 +2,7 @@
 //! 
 //! Extracted from the original TradocFlow core translation models
 
-use super::common::ValidationError;
+use super::common::{Domain, Language, ValidationError};
 use crate::error::{Result, TranslationMemoryError};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -24,6 +24,18 @@ pub struct Term {
     /// Whether this term should not be translated
     pub do_not_translate: bool,
     
+    /// Subject area the term belongs to
+    #[serde(default)]
+    pub domain: Option<Domain>,
+    
+    /// Language of the term text, for glossaries kept per language
+    #[serde(default)]
+    pub language: Option<Language>,
+    
+    /// Whether translators should use or avoid the term
+    #[serde(default)]
+    pub status: TermStatus,
+    
     /// When this entry was created
     pub created_at: DateTime<Utc>,
     
@@ -31,6 +43,35 @@ pub struct Term {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Usage status of a terminology entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum TermStatus {
+    /// Preferred term that translators should use
+    #[default]
+    Approved,
+    /// Term that must not appear in translations
+    Forbidden,
+}
+
+impl TermStatus {
+    /// Value used in the CSV `status` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TermStatus::Approved => "approved",
+            TermStatus::Forbidden => "forbidden",
+        }
+    }
+    
+    /// Parse a CSV `status` value, ignoring case
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "approved" => Some(TermStatus::Approved),
+            "forbidden" => Some(TermStatus::Forbidden),
+            _ => None,
+        }
+    }
+}
+
 /// CSV record structure for terminology import/export
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminologyCsvRecord {
@@ -48,6 +89,14 @@ pub struct TerminologyCsvRecord {
     
     /// Optional notes
     pub notes: Option<String>,
+    
+    /// Optional language code of the term
+    #[serde(default)]
+    pub language: Option<String>,
+    
+    /// Optional status ("approved"/"forbidden"); approved when absent
+    #[serde(default)]
+    pub status: Option<String>,
 }
 
 /// Result of terminology import operation
@@ -62,6 +111,9 @@ pub struct TerminologyImportResult {
     /// Terms that were duplicates
     pub duplicate_terms: Vec<String>,
     
+    /// Rows merged into an earlier row for the same term
+    pub merged_rows: usize,
+    
     /// Total number of records processed
     pub total_processed: usize,
 }
@@ -80,9 +132,10 @@ pub struct TerminologyImportError {
 }
 
 /// Conflict resolution strategies for duplicate terms
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub enum ConflictResolution {
     /// Skip the conflicting term
+    #[default]
     Skip,
     
     /// Overwrite the existing term
@@ -146,6 +199,9 @@ impl Term {
             term: term.trim().to_string(),
             definition: definition.map(|d| d.trim().to_string()),
             do_not_translate,
+            domain: None,
+            language: None,
+            status: TermStatus::Approved,
             created_at: now,
             updated_at: now,
         })
@@ -219,7 +275,21 @@ Notes: {}", def.trim(), notes.trim()))
             _ => None,
         };
 
-        Term::new(self.term.clone(), definition, do_not_translate)
+        let status = match self.status.as_deref() {
+            None => TermStatus::Approved,
+            Some(value) => TermStatus::parse(value).ok_or_else(|| TranslationMemoryError::DataValidation(
+                format!("Invalid status value: '{}'. Expected approved or forbidden", value)
+            ))?,
+        };
+        
+        let mut term = Term::new(self.term.clone(), definition, do_not_translate)?;
+        // Categories that aren't a known domain are kept only as free text in the source file
+        term.domain = self.category.as_deref().and_then(Domain::from_name);
+        term.language = self.language.as_deref().map(|code| {
+            Language::from_code(code).unwrap_or_else(|| Language::Custom(code.to_string()))
+        });
+        term.status = status;
+        Ok(term)
     }
 
     /// Create CSV record from Term model
@@ -244,8 +314,10 @@ Notes: {}", def.trim(), notes.trim()))
             term: term.term.clone(),
             definition,
             do_not_translate: Some(if term.do_not_translate { "true" } else { "false" }.to_string()),
-            category: None, // Could be extended in the future
+            category: term.domain.map(|domain| domain.name().to_string()),
             notes,
+            language: term.language.as_ref().map(|language| language.code().to_string()),
+            status: Some(term.status.as_str().to_string()),
         }
     }
 }
@@ -257,6 +329,7 @@ impl TerminologyImportResult {
             successful_imports: Vec::new(),
             failed_imports: Vec::new(),
             duplicate_terms: Vec::new(),
+            merged_rows: 0,
             total_processed: 0,
         }
     }
@@ -448,6 +521,8 @@ mod tests {
             do_not_translate: Some("true".to_string()),
             category: Some("Technical".to_string()),
             notes: Some("Commonly used in software development".to_string()),
+            language: Some("en".to_string()),
+            status: None,
         };
         
         let term = csv_record.to_term().unwrap();
@@ -460,6 +535,10 @@ mod tests {
         let csv_back = TerminologyCsvRecord::from_term(&term);
         assert_eq!(csv_back.term, "API");
         assert_eq!(csv_back.do_not_translate, Some("true".to_string()));
+        assert_eq!(term.domain, Some(Domain::Technical));
+        assert_eq!(csv_back.category, Some("Technical".to_string()));
+        assert_eq!(csv_back.language, Some("en".to_string()));
+        assert_eq!(csv_back.status, Some("approved".to_string()));
     }
     
     #[test]
@@ -482,6 +561,8 @@ mod tests {
                 do_not_translate: Some(input.to_string()),
                 category: None,
                 notes: None,
+                language: None,
+                status: None,
             };
             
             let term = csv_record.to_term().unwrap();
@@ -497,6 +578,8 @@ mod tests {
             do_not_translate: Some("invalid".to_string()),
             category: None,
             notes: None,
+            language: None,
+            status: None,
         };
         
         let result = csv_record.to_term();
@@ -1,10 +1,12 @@
 //! Real-time terminology highlighting service for text analysis
 
 use crate::error::{Result, TranslationMemoryError};
-use crate::models::{Terminology, Language};
+use crate::models::{Terminology, Language, TermStatus};
+use crate::services::stemming;
 use crate::services::terminology::TerminologyService;
 use std::sync::Arc;
 use std::collections::HashMap;
+use std::fmt::Write;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 use regex::Regex;
@@ -38,6 +40,8 @@ pub enum HighlightType {
     LowConfidence,
     /// Terms that are contextually relevant
     Contextual,
+    /// Terms that must not be used
+    Forbidden,
 }
 
 /// Terminology consistency check result
@@ -89,6 +93,10 @@ pub struct HighlightingConfig {
     pub max_context_length: usize,
     pub highlight_overlaps: bool,
     pub include_variations: bool,
+    /// Match inflected forms by comparing word stems in the text's
+    /// language. Replaces `include_variations`, but can over-match.
+    #[serde(default)]
+    pub stemming: bool,
 }
 
 impl Default for HighlightingConfig {
@@ -100,6 +108,7 @@ impl Default for HighlightingConfig {
             max_context_length: 100,
             highlight_overlaps: false,
             include_variations: true,
+            stemming: false,
         }
     }
 }
@@ -184,6 +193,10 @@ impl HighlightingService {
         term: &Terminology,
         language: &Language,
     ) -> Result<Vec<TermHighlight>> {
+        if self.config.stemming && stemming::supports(language) {
+            return Ok(self.find_stemmed_occurrences(text, term, language));
+        }
+        
         let mut highlights = Vec::new();
         
         // Create base patterns to search for
@@ -229,6 +242,111 @@ impl HighlightingService {
         Ok(highlights)
     }
     
+    /// Find runs of words in `text` whose stems are those of the words of
+    /// `term`, reporting each under the canonical term
+    fn find_stemmed_occurrences(&self, text: &str, term: &Terminology, language: &Language) -> Vec<TermHighlight> {
+        let key = |word: &str| {
+            let word = if self.config.case_sensitive { word.to_string() } else { word.to_lowercase() };
+            stemming::stem(&word, language).to_string()
+        };
+        let term_keys: Vec<String> = stemming::words(&term.term).map(|(_, word)| key(word)).collect();
+        let words: Vec<(usize, &str)> = stemming::words(text).collect();
+        if term_keys.is_empty() || words.len() < term_keys.len() {
+            return Vec::new();
+        }
+        let keys: Vec<String> = words.iter().map(|(_, word)| key(word)).collect();
+        
+        let mut highlights = Vec::new();
+        for (i, window) in keys.windows(term_keys.len()).enumerate() {
+            if window != term_keys.as_slice() {
+                continue;
+            }
+            let (start, _) = words[i];
+            let (last_start, last_word) = words[i + term_keys.len() - 1];
+            let end = last_start + last_word.len();
+            
+            // Inflected or recased forms are less certain than the term itself
+            let confidence = if text[start..end] == term.term { 1.0 } else { 0.85 };
+            if confidence < self.config.min_confidence_threshold {
+                continue;
+            }
+            highlights.push(TermHighlight {
+                term_id: term.id,
+                term: term.term.clone(),
+                start_position: start,
+                end_position: end,
+                highlight_type: self.determine_highlight_type(term, confidence),
+                definition: term.definition.clone(),
+                confidence,
+                context: (self.config.max_context_length > 0)
+                    .then(|| self.extract_context(text, start, end, self.config.max_context_length)),
+                language: language.clone(),
+            });
+        }
+        highlights
+    }
+    
+    /// Render `text` as HTML with its terms marked up for review
+    /// 
+    /// Each term becomes a `<span class="term">`, or `class="forbidden"` for
+    /// forbidden terms, with the term's ID in `data-term-id` and a `title`
+    /// tooltip from its metadata. Where matches overlap the longest wins, so
+    /// spans never nest. Everything else is escaped text.
+    pub async fn to_html(&self, text: &str, project_id: Uuid, language: Language) -> Result<String> {
+        let terms = self.get_cached_terms(project_id).await?;
+        let mut candidates = Vec::new();
+        for term in &terms {
+            candidates.extend(self.find_term_occurrences(text, term, &language).await?);
+        }
+        
+        // Longest first, then leftmost, each kept only if it overlaps nothing kept
+        candidates.sort_by(|a, b| {
+            (b.end_position - b.start_position)
+                .cmp(&(a.end_position - a.start_position))
+                .then(a.start_position.cmp(&b.start_position))
+        });
+        let mut highlights: Vec<TermHighlight> = Vec::new();
+        for candidate in candidates {
+            if highlights.iter().all(|h| candidate.end_position <= h.start_position || h.end_position <= candidate.start_position) {
+                highlights.push(candidate);
+            }
+        }
+        highlights.sort_by_key(|h| h.start_position);
+        
+        let mut html = String::with_capacity(text.len());
+        let mut position = 0;
+        for highlight in &highlights {
+            html.push_str(&escape_html(&text[position..highlight.start_position]));
+            let class = if highlight.highlight_type == HighlightType::Forbidden { "forbidden" } else { "term" };
+            let _ = write!(
+                html,
+                r#"<span class="{class}" data-term-id="{}" title="{}">{}</span>"#,
+                highlight.term_id,
+                escape_html(&Self::tooltip(highlight)),
+                escape_html(&text[highlight.start_position..highlight.end_position]),
+            );
+            position = highlight.end_position;
+        }
+        html.push_str(&escape_html(&text[position..]));
+        Ok(html)
+    }
+    
+    /// Tooltip of a highlighted term: what kind of term it is, then its
+    /// definition
+    fn tooltip(highlight: &TermHighlight) -> String {
+        let label = match highlight.highlight_type {
+            HighlightType::Forbidden => Some("Forbidden term"),
+            HighlightType::DoNotTranslate => Some("Do not translate"),
+            _ => None,
+        };
+        match (label, &highlight.definition) {
+            (Some(label), Some(definition)) => format!("{label}: {definition}"),
+            (Some(label), None) => label.to_string(),
+            (None, Some(definition)) => definition.clone(),
+            (None, None) => highlight.term.clone(),
+        }
+    }
+    
     /// Check terminology consistency across multiple languages
     pub async fn check_consistency_across_languages(
         &self,
@@ -545,7 +663,9 @@ impl HighlightingService {
     }
     
     fn determine_highlight_type(&self, term: &Terminology, confidence: f32) -> HighlightType {
-        if term.do_not_translate {
+        if term.status == TermStatus::Forbidden {
+            HighlightType::Forbidden
+        } else if term.do_not_translate {
             HighlightType::DoNotTranslate
         } else if confidence >= 0.9 {
             HighlightType::Validated
@@ -796,9 +916,96 @@ impl HighlightingService {
     }
 }
 
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 /// Represents a significant word found in text
 #[derive(Debug, Clone)]
 struct SignificantWord {
     text: String,
     position: usize,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Term;
+    use crate::utils::CsvProcessor;
+    
+    async fn highlighter(stemming: bool) -> (HighlightingService, Uuid) {
+        let terminology = TerminologyService::new(Arc::new(CsvProcessor::new()), None).await.unwrap();
+        let project_id = Uuid::new_v4();
+        let term = Term::new("configure".to_string(), Some("Set up for operation".to_string()), false).unwrap();
+        terminology.add_terminology(term, project_id).await.unwrap();
+        
+        let config = HighlightingConfig { stemming, ..HighlightingConfig::default() };
+        (HighlightingService::new(Arc::new(terminology), Some(config)).await.unwrap(), project_id)
+    }
+    
+    #[tokio::test]
+    async fn test_stemming_matches_inflected_forms() {
+        let (service, project_id) = highlighter(true).await;
+        let text = "Configuring the relay takes a minute. The relay then configures itself.";
+        
+        let highlights = service.highlight_terms_in_text(text, project_id, Language::English).await.unwrap();
+        let found: Vec<(&str, &str)> = highlights
+            .iter()
+            .map(|h| (h.term.as_str(), &text[h.start_position..h.end_position]))
+            .collect();
+        assert_eq!(found, [("configure", "Configuring"), ("configure", "configures")]);
+    }
+    
+    #[tokio::test]
+    async fn test_without_stemming_only_surface_forms_match() {
+        let (service, project_id) = highlighter(false).await;
+        
+        let inflected = service
+            .highlight_terms_in_text("Configuring the relay takes a minute.", project_id, Language::English)
+            .await
+            .unwrap();
+        assert!(inflected.is_empty());
+        
+        let exact = service
+            .highlight_terms_in_text("Then configure the relay.", project_id, Language::English)
+            .await
+            .unwrap();
+        assert_eq!(exact.len(), 1);
+        assert_eq!((exact[0].start_position, exact[0].end_position), (5, 14));
+    }
+    
+    #[tokio::test]
+    async fn test_to_html_marks_up_terms_for_review() {
+        use crate::models::TermStatus;
+        
+        let terminology = TerminologyService::new(Arc::new(CsvProcessor::new()), None).await.unwrap();
+        let project_id = Uuid::new_v4();
+        let data = Term::new("data".to_string(), Some("Facts & figures".to_string()), false).unwrap();
+        let data_center = Term::new("data center".to_string(), None, false).unwrap();
+        let mut blacklist = Term::new("blacklist".to_string(), Some("Use \"blocklist\"".to_string()), false).unwrap();
+        blacklist.status = TermStatus::Forbidden;
+        for term in [&data, &data_center, &blacklist] {
+            terminology.add_terminology(term.clone(), project_id).await.unwrap();
+        }
+        let service = HighlightingService::new(Arc::new(terminology), None).await.unwrap();
+        
+        let html = service
+            .to_html("Move <b>data</b> & the data center off the blacklist.", project_id, Language::English)
+            .await
+            .unwrap();
+        assert_eq!(
+            html,
+            format!(
+                concat!(
+                    r#"Move &lt;b&gt;<span class="term" data-term-id="{}" title="Facts &amp; figures">data</span>&lt;/b&gt; &amp; "#,
+                    r#"the <span class="term" data-term-id="{}" title="data center">data center</span> off the "#,
+                    r#"<span class="forbidden" data-term-id="{}" title="Forbidden term: Use &quot;blocklist&quot;">blacklist</span>."#,
+                ),
+                data.id, data_center.id, blacklist.id,
+            )
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Least-recently-used cache for translation memory lookups
+//!
+//! The editor asks for suggestions on every keystroke, so identical queries
+//! arrive in bursts. Results are cached per normalized query and language
+//! pair, expire after a time to live, and are dropped for a language pair
+//! whenever units for that pair change.
+
+use crate::services::translation_memory::{LanguagePair, TranslationMatch};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Size and lifetime of the lookup cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LookupCacheConfig {
+    /// Maximum number of cached queries; the least recently used is evicted
+    pub capacity: usize,
+    /// How long cached results stay valid
+    pub ttl: Duration,
+}
+
+impl Default for LookupCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1000,
+            ttl: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Counters for tuning the lookup cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LookupCacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries dropped to stay within capacity
+    pub evictions: u64,
+    /// Entries dropped because they outlived the time to live
+    pub expirations: u64,
+    /// Entries dropped because units for their language pair changed
+    pub invalidations: u64,
+}
+
+impl LookupCacheStats {
+    pub fn hit_ratio(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct LookupKey {
+    query: String,
+    language_pair: LanguagePair,
+    /// Results depend on the similarity threshold, so it is part of the key
+    threshold_bits: u32,
+}
+
+impl LookupKey {
+    pub(crate) fn new(query: &str, language_pair: &LanguagePair, threshold: f32) -> Self {
+        Self {
+            query: normalize_query(query),
+            language_pair: language_pair.clone(),
+            threshold_bits: threshold.to_bits(),
+        }
+    }
+}
+
+/// Queries differing only in surrounding or repeated whitespace share an entry
+fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug)]
+struct CachedLookup {
+    matches: Vec<TranslationMatch>,
+    stored_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct LookupCacheState {
+    entries: HashMap<LookupKey, CachedLookup>,
+    /// Keys by the tick of their last use, oldest first
+    recency: BTreeMap<u64, LookupKey>,
+    tick: u64,
+}
+
+impl LookupCacheState {
+    fn touch(&mut self, key: &LookupKey) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.recency.remove(&entry.last_used);
+            entry.last_used = tick;
+            self.recency.insert(tick, key.clone());
+        }
+    }
+
+    fn remove(&mut self, key: &LookupKey) -> Option<CachedLookup> {
+        let entry = self.entries.remove(key)?;
+        self.recency.remove(&entry.last_used);
+        Some(entry)
+    }
+}
+
+/// Thread-safe LRU cache of search results with a time to live
+#[derive(Debug)]
+pub(crate) struct LookupCache {
+    config: LookupCacheConfig,
+    state: Mutex<LookupCacheState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    expirations: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+impl Default for LookupCache {
+    fn default() -> Self {
+        Self::new(LookupCacheConfig::default())
+    }
+}
+
+impl LookupCache {
+    pub(crate) fn new(config: LookupCacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(LookupCacheState::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            expirations: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, LookupCacheState> {
+        // The state stays consistent even if a holder panicked
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Cached results for `key`, counting the lookup as a hit or a miss
+    pub(crate) fn get(&self, key: &LookupKey) -> Option<Vec<TranslationMatch>> {
+        let mut state = self.state();
+        let expired = match state.entries.get(key) {
+            Some(entry) => entry.stored_at.elapsed() > self.config.ttl,
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+
+        if expired {
+            state.remove(key);
+            self.expirations.fetch_add(1, Ordering::Relaxed);
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        state.touch(key);
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        state.entries.get(key).map(|entry| entry.matches.clone())
+    }
+
+    pub(crate) fn insert(&self, key: LookupKey, matches: Vec<TranslationMatch>) {
+        if self.config.capacity == 0 {
+            return;
+        }
+
+        let mut state = self.state();
+        state.remove(&key);
+        while state.entries.len() >= self.config.capacity {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+
+        state.tick += 1;
+        let tick = state.tick;
+        state.recency.insert(tick, key.clone());
+        state.entries.insert(key, CachedLookup { matches, stored_at: Instant::now(), last_used: tick });
+    }
+
+    /// Drop every entry for a language pair; returns how many were dropped
+    pub(crate) fn invalidate_language_pair(&self, language_pair: &LanguagePair) -> usize {
+        let mut state = self.state();
+        let keys: Vec<LookupKey> = state
+            .entries
+            .keys()
+            .filter(|key| &key.language_pair == language_pair)
+            .cloned()
+            .collect();
+        for key in &keys {
+            state.remove(key);
+        }
+        self.invalidations.fetch_add(keys.len() as u64, Ordering::Relaxed);
+        keys.len()
+    }
+
+    pub(crate) fn clear(&self) {
+        let mut state = self.state();
+        state.entries.clear();
+        state.recency.clear();
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    pub(crate) fn stats(&self) -> LookupCacheStats {
+        LookupCacheStats {
+            entries: self.len(),
+            capacity: self.config.capacity,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            expirations: self.expirations.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+        }
+    }
+}
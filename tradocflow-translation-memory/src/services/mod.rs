@@ -1,10 +1,13 @@
 //! Services for translation memory operations
 
 pub mod translation_memory;
+pub mod lookup_cache;
 pub mod terminology;
 pub mod highlighting;
+pub mod stemming;
 
 // Re-export key services
 pub use translation_memory::TranslationMemoryService;
-pub use terminology::TerminologyService;
+pub use lookup_cache::{LookupCacheConfig, LookupCacheStats};
+pub use terminology::{TerminologyService, DomainScope, ImportProgress, TermFilter, TermRule, TermUsage, TermValidationError};
 pub use highlighting::HighlightingService;
\ No newline at end of file
@@ -0,0 +1,80 @@
+//! Light suffix-stripping stemmer for recognising inflected terms
+//!
+//! Strips the longest known inflectional suffix of a word so that, say,
+//! "configures", "configuring" and "configure" share the stem "configur".
+//! It is deliberately crude and can conflate unrelated words, which is why
+//! callers make stemming optional.
+
+use crate::models::Language;
+
+/// Shortest stem left after stripping a suffix, in characters
+const MIN_STEM_CHARS: usize = 3;
+
+/// Inflectional suffixes of a language, longest first
+fn suffixes(language: &Language) -> &'static [&'static str] {
+    match language {
+        Language::English => &[
+            "ations", "ation", "ings", "ing", "ies", "ied", "ers", "er", "es", "ed", "ly", "e", "s", "y",
+        ],
+        Language::German => &["ungen", "ung", "ern", "en", "er", "es", "em", "e", "n", "s"],
+        Language::Dutch => &["heden", "heid", "en", "er", "e", "s"],
+        Language::Spanish => &[
+            "aciones", "ación", "mente", "iendo", "ando", "ados", "adas", "idos", "idas", "ado", "ada", "ido",
+            "ida", "ar", "er", "ir", "es", "as", "os", "a", "o", "e", "s",
+        ],
+        Language::Portuguese => &[
+            "ações", "ação", "mente", "endo", "ando", "ados", "adas", "idos", "idas", "ado", "ada", "ido", "ida",
+            "ar", "er", "ir", "es", "as", "os", "a", "o", "e", "s",
+        ],
+        Language::French => &[
+            "ations", "ation", "ements", "ement", "ées", "és", "ée", "ent", "er", "ez", "es", "é", "e", "s",
+        ],
+        Language::Italian => &[
+            "azioni", "azione", "mente", "ando", "endo", "ati", "ate", "ato", "ata", "are", "ere", "ire", "i",
+            "e", "a", "o",
+        ],
+        _ => &[],
+    }
+}
+
+/// Whether [`stem`] knows the inflections of `language`
+pub fn supports(language: &Language) -> bool {
+    !suffixes(language).is_empty()
+}
+
+/// Stem of `word`, which is returned unchanged in languages without
+/// stemming support. Case is kept, so callers fold it first if needed.
+pub fn stem<'a>(word: &'a str, language: &Language) -> &'a str {
+    let chars = word.chars().count();
+    suffixes(language)
+        .iter()
+        .filter(|suffix| word.ends_with(*suffix) && chars - suffix.chars().count() >= MIN_STEM_CHARS)
+        .max_by_key(|suffix| suffix.len())
+        .map_or(word, |suffix| &word[..word.len() - suffix.len()])
+}
+
+/// Words of `text` with their byte offsets
+pub fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inflections_share_a_stem() {
+        for word in ["configure", "configures", "configuring", "configured", "configuration"] {
+            assert_eq!(stem(word, &Language::English), "configur", "{word}");
+        }
+        assert_eq!(stem("Schrauben", &Language::German), stem("Schraube", &Language::German));
+        // Short words keep their suffix
+        assert_eq!(stem("bus", &Language::English), "bus");
+        assert_eq!(stem("configuring", &Language::Finnish), "configuring");
+
+        let found: Vec<(usize, &str)> = words("Reset, then re-configure.").collect();
+        assert_eq!(found, [(0, "Reset"), (7, "then"), (12, "re"), (15, "configure")]);
+    }
+}
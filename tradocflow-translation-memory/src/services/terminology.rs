@@ -1,14 +1,17 @@
 //! Terminology service for managing terminology databases with async operations
 
-use crate::error::{Result, TranslationMemoryError};
-use crate::models::{Terminology, Language, TerminologyImportResult as ModelImportResult};
+use crate::error::{ensure_not_cancelled, Result, TranslationMemoryError};
+use crate::models::{Terminology, TerminologyCsvRecord, ConflictResolution, Language, Domain, TermStatus, SearchPage, SearchQuery, TerminologyImportResult as ModelImportResult};
 // Temporarily disable storage dependencies due to version conflicts
 // use crate::storage::{DuckDBManager, ParquetManager};
+use crate::services::TranslationMemoryService;
 use crate::utils::CsvProcessor;
 use std::sync::Arc;
 use std::path::Path;
 use std::collections::HashMap;
+use std::ops::Range;
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
@@ -46,6 +49,9 @@ pub enum ConflictType {
     Definition,
     DoNotTranslate,
     Duplicate,
+    Domain,
+    Language,
+    Status,
 }
 
 /// Terminology suggestion for user interface
@@ -68,6 +74,17 @@ pub struct TerminologyImportError {
     pub error_type: ImportErrorType,
 }
 
+/// How far a streamed terminology import has got
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ImportProgress {
+    /// Data rows read so far, failed ones included
+    pub rows_read: usize,
+    pub terms_imported: usize,
+    pub rows_failed: usize,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+}
+
 /// Type of import error
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub enum ImportErrorType {
@@ -85,6 +102,22 @@ pub struct TerminologyValidationConfig {
     pub max_term_length: usize,
     pub max_definition_length: usize,
     pub required_fields: Vec<String>,
+    /// How imported rows for the same term that disagree are merged
+    #[serde(default)]
+    pub conflict_resolution: ConflictResolution,
+    /// Flag forbidden terms in checked text
+    #[serde(default = "enabled")]
+    pub check_forbidden_terms: bool,
+    /// Flag do-not-translate terms of the source that the text leaves out
+    #[serde(default = "enabled")]
+    pub check_mandatory_terms: bool,
+    /// Flag approved terms written with different casing than the glossary
+    #[serde(default)]
+    pub check_term_casing: bool,
+}
+
+fn enabled() -> bool {
+    true
 }
 
 impl Default for TerminologyValidationConfig {
@@ -92,11 +125,105 @@ impl Default for TerminologyValidationConfig {
         Self {
             case_sensitive: false,
             allow_duplicates: false,
+            conflict_resolution: ConflictResolution::Skip,
             max_term_length: 200,
             max_definition_length: 1000,
             required_fields: vec!["term".to_string()],
+            check_forbidden_terms: true,
+            check_mandatory_terms: true,
+            check_term_casing: false,
+        }
+    }
+}
+
+/// Surface form of an imported term: trimmed, with each run of
+/// whitespace inside it collapsed to a single space
+pub fn normalize_term(term: &str) -> String {
+    term.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Criteria for selecting terms; fields left as `None` match every term
+#[derive(Debug, Clone, Default)]
+pub struct TermFilter {
+    pub project_id: Option<Uuid>,
+    pub domain: Option<Domain>,
+    pub language: Option<Language>,
+    pub status: Option<TermStatus>,
+}
+
+impl TermFilter {
+    /// Check whether a term satisfies every set criterion
+    pub fn matches(&self, term: &Terminology) -> bool {
+        self.domain.is_none_or(|domain| term.domain == Some(domain))
+            && self.language.as_ref().is_none_or(|language| term.language.as_ref() == Some(language))
+            && self.status.is_none_or(|status| term.status == status)
+    }
+}
+
+/// How term search treats the domains of terms
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DomainScope {
+    /// Terms of every domain alike
+    #[default]
+    Any,
+    /// Only terms of the domain and terms without one
+    Only(Domain),
+    /// Terms of every domain, those of the domain ranked first
+    Prefer(Domain),
+}
+
+impl DomainScope {
+    fn includes(&self, term: &Terminology) -> bool {
+        match self {
+            DomainScope::Only(domain) => term.domain.is_none_or(|d| d == *domain),
+            DomainScope::Any | DomainScope::Prefer(_) => true,
         }
     }
+    
+    /// Whether `term` ranks below terms of the preferred domain
+    fn demotes(&self, term: &Terminology) -> bool {
+        match self {
+            DomainScope::Prefer(domain) => term.domain != Some(*domain),
+            DomainScope::Any | DomainScope::Only(_) => false,
+        }
+    }
+}
+
+/// How often a term occurs in the translation memory
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TermUsage {
+    pub term_id: Uuid,
+    pub term: String,
+    pub status: TermStatus,
+    /// Number of translation units whose text contains the term
+    pub unit_count: usize,
+    /// Most recent update among those units
+    pub last_seen: Option<DateTime<Utc>>,
+    /// Approved term that no unit uses, a candidate for retirement
+    pub unused: bool,
+}
+
+/// Glossary rule broken by a [`TermValidationError`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TermRule {
+    /// A forbidden term is used
+    Forbidden,
+    /// A do-not-translate term of the source is missing from the text
+    MissingMandatory,
+    /// An approved term is written with different casing
+    Casing,
+}
+
+/// A use of terminology that breaks the glossary
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TermValidationError {
+    pub rule: TermRule,
+    pub term_id: Uuid,
+    /// The term as the glossary has it
+    pub term: String,
+    /// Byte range of the offending text; for a missing mandatory term, the
+    /// occurrence in the source that calls for it
+    pub span: Range<usize>,
 }
 
 /// In-memory cache for terminology operations
@@ -119,18 +246,65 @@ pub struct TerminologyService {
     validation_config: TerminologyValidationConfig,
     // In-memory storage for terms until database is available
     in_memory_storage: Arc<RwLock<HashMap<Uuid, Vec<Terminology>>>>,
+    // Translation memory searched by usage reports
+    translation_memory: Option<Arc<TranslationMemoryService>>,
 }
 
 impl TerminologyService {
     /// Search for terminology matches (legacy API for lib.rs compatibility)
     pub async fn search_terms(
         &self,
-        _query: &str,
-        _source_lang: Language,
+        query: &str,
+        source_lang: Language,
+        target_lang: Language,
+    ) -> Result<Vec<crate::models::Term>> {
+        self.search_terms_cancellable(query, source_lang, target_lang, &CancellationToken::new()).await
+    }
+    
+    /// Search for terminology matches, returning
+    /// [`TranslationMemoryError::Cancelled`] once `cancel` is triggered
+    pub async fn search_terms_cancellable(
+        &self,
+        query: &str,
+        source_lang: Language,
         _target_lang: Language,
+        cancel: &CancellationToken,
     ) -> Result<Vec<crate::models::Term>> {
-        // TODO: Implement terminology search when storage is available
-        Ok(Vec::new())
+        ensure_not_cancelled(cancel)?;
+        Ok(self.search_terms_paged(query, source_lang, DomainScope::Any, &SearchQuery::default()).await?.items)
+    }
+    
+    /// One page of the terms of every project whose text or definition
+    /// contains `query`, with the number of matches on all pages
+    /// 
+    /// Terms in `source_lang` and terms without a language match, limited
+    /// or ranked by domain as `domain` says. Results are otherwise ordered
+    /// by term with ties broken by ID, so pages are stable.
+    pub async fn search_terms_paged(
+        &self,
+        query: &str,
+        source_lang: Language,
+        domain: DomainScope,
+        page: &SearchQuery,
+    ) -> Result<SearchPage<Terminology>> {
+        let case_sensitive = self.validation_config.case_sensitive;
+        let mut terms: Vec<Terminology> = {
+            let storage = self.in_memory_storage.read().await;
+            storage
+                .values()
+                .flatten()
+                .filter(|term| term.language.as_ref().is_none_or(|language| *language == source_lang))
+                .filter(|term| domain.includes(term) && term_matches(term, query, case_sensitive))
+                .cloned()
+                .collect()
+        };
+        terms.sort_by(|a, b| {
+            domain.demotes(a).cmp(&domain.demotes(b))
+                .then_with(|| a.term.to_lowercase().cmp(&b.term.to_lowercase()))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        
+        Ok(SearchPage::from_results(terms, page))
     }
     /// Create a new terminology service
     pub async fn new(
@@ -142,12 +316,19 @@ impl TerminologyService {
             cache: Arc::new(RwLock::new(TerminologyCache::default())),
             validation_config: validation_config.unwrap_or_default(),
             in_memory_storage: Arc::new(RwLock::new(HashMap::new())),
+            translation_memory: None,
         };
         
         service.initialize().await?;
         Ok(service)
     }
     
+    /// Attach the translation memory that [`Self::usage_report`] searches
+    pub fn with_translation_memory(mut self, translation_memory: Arc<TranslationMemoryService>) -> Self {
+        self.translation_memory = Some(translation_memory);
+        self
+    }
+    
     /// Initialize the service and create necessary tables
     pub async fn initialize(&self) -> Result<()> {
         // TODO: Initialize database schema when DuckDB is available
@@ -162,75 +343,24 @@ impl TerminologyService {
     ) -> Result<ServiceTerminologyImportResult> {
         let start_time = std::time::Instant::now();
         
-        // Parse CSV file
-        let csv_records = self.csv_processor.parse_csv(file_path).await?;
+        // Parse CSV file; unreadable lines are reported with the other failures
+        let report = self.csv_processor.parse_csv_with_errors(file_path).await?;
+        let csv_records = report.records;
+        let failed_rows = report.errors.len();
         
         // Convert to terminology objects
         let mut terms = Vec::new();
-        let mut failed_terms = Vec::new();
+        let mut failed_terms: Vec<TerminologyImportError> = report.errors.into_iter().map(Self::row_error).collect();
         
         for (row_number, record) in csv_records.iter().enumerate() {
-            match record.to_term() {
-                Ok(term) => {
-                    // Convert Term to Terminology for service use
-                    let terminology = Terminology {
-                        id: term.id,
-                        term: term.term.clone(),
-                        definition: term.definition,
-                        do_not_translate: term.do_not_translate,
-                        created_at: term.created_at,
-                        updated_at: term.updated_at,
-                    };
-                    
-                    if let Err(validation_error) = self.validate_terminology(&terminology) {
-                        failed_terms.push(TerminologyImportError {
-                            row_number: row_number + 1,
-                            term: term.term.clone(),
-                            error_message: validation_error.to_string(),
-                            error_type: ImportErrorType::Validation,
-                        });
-                    } else {
-                        terms.push(terminology);
-                    }
-                },
-                Err(e) => {
-                    failed_terms.push(TerminologyImportError {
-                        row_number: row_number + 1,
-                        term: record.term.clone(),
-                        error_message: e.to_string(),
-                        error_type: ImportErrorType::Format,
-                    });
-                }
+            match self.record_to_terminology(record, row_number + 1) {
+                Ok(terminology) => terms.push(terminology),
+                Err(error) => failed_terms.push(error),
             }
         }
         
-        // Validate terms and detect conflicts
-        let validation_result = self.validate_terms(&terms, project_id).await?;
-        
-        // Filter out conflicting terms if not allowed
-        let final_terms = if self.validation_config.allow_duplicates {
-            terms
-        } else {
-            terms.into_iter()
-                .filter(|term| !validation_result.duplicates.contains(&term.term))
-                .collect()
-        };
-        
-        // Store valid terms
-        let mut _imported_count = 0;
-        for term in &final_terms {
-            match self.add_terminology_internal(term.clone(), project_id).await {
-                Ok(_) => _imported_count += 1,
-                Err(e) => {
-                    failed_terms.push(TerminologyImportError {
-                        row_number: 0, // Unknown row at this point
-                        term: term.term.clone(),
-                        error_message: e.to_string(),
-                        error_type: ImportErrorType::Database,
-                    });
-                }
-            }
-        }
+        let (final_terms, validation_result, merged_rows) =
+            self.store_imported_terms(terms, project_id, &mut failed_terms).await?;
         
         // TODO: Convert to Parquet format when storage manager is available
         
@@ -247,7 +377,8 @@ impl TerminologyService {
                 error: crate::models::ValidationError::InvalidTerm(err.error_message),
             }).collect(),
             duplicate_terms: Vec::new(), // TODO: Extract duplicates from validation
-            total_processed: csv_records.len(),
+            merged_rows,
+            total_processed: csv_records.len() + failed_rows,
         };
         
         Ok(ServiceTerminologyImportResult {
@@ -258,6 +389,92 @@ impl TerminologyService {
         })
     }
     
+    /// Import terminology from a CSV file without loading it whole
+    /// 
+    /// Rows are read one at a time and stored `batch_size` valid terms at a
+    /// time, calling `progress` after each batch. Malformed rows are counted
+    /// as failures and skipped. Counts match [`Self::import_terminology_csv`],
+    /// except that rows for the same term are only merged within a batch; a
+    /// term repeated in a later batch is skipped as a duplicate of the
+    /// stored one.
+    #[cfg(feature = "terminology-csv")]
+    pub async fn import_terminology_csv_streaming(
+        &self,
+        file_path: &Path,
+        project_id: Uuid,
+        batch_size: usize,
+        mut progress: impl FnMut(ImportProgress),
+    ) -> Result<ServiceTerminologyImportResult> {
+        let start_time = std::time::Instant::now();
+        let batch_size = batch_size.max(1);
+        
+        let mut rows = self.csv_processor.stream_csv(file_path)?;
+        let mut status = ImportProgress { total_bytes: std::fs::metadata(file_path)?.len(), ..Default::default() };
+        let mut records_read = 0;
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut successful_imports = Vec::new();
+        let mut failed_terms = Vec::new();
+        let mut conflicts = Vec::new();
+        let mut warnings = Vec::new();
+        let mut merged_rows = 0;
+        
+        loop {
+            let row = rows.next();
+            let finished = row.is_none();
+            match row {
+                Some(Ok(row)) => {
+                    records_read += 1;
+                    match self.record_to_terminology(&row.record, records_read) {
+                        Ok(terminology) => batch.push(terminology),
+                        Err(error) => failed_terms.push(error),
+                    }
+                }
+                Some(Err(error)) => failed_terms.push(Self::row_error(error)),
+                None => {}
+            }
+            if !finished {
+                status.rows_read += 1;
+            }
+            
+            if batch.len() == batch_size || (finished && !batch.is_empty()) {
+                let terms = std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+                let (stored, validation_result, merged) =
+                    self.store_imported_terms(terms, project_id, &mut failed_terms).await?;
+                successful_imports.extend(stored);
+                merged_rows += merged;
+                conflicts.extend(validation_result.conflicts);
+                warnings.extend(validation_result.warnings);
+                
+                status.terms_imported = successful_imports.len();
+                status.rows_failed = failed_terms.len();
+                status.bytes_read = rows.bytes_read();
+                progress(status);
+            }
+            if finished {
+                break;
+            }
+        }
+        
+        let base_result = ModelImportResult {
+            successful_imports,
+            failed_imports: failed_terms.into_iter().map(|err| crate::models::TerminologyImportError {
+                row_number: err.row_number,
+                term: err.term,
+                error: crate::models::ValidationError::InvalidTerm(err.error_message),
+            }).collect(),
+            duplicate_terms: Vec::new(),
+            merged_rows,
+            total_processed: status.rows_read,
+        };
+        
+        Ok(ServiceTerminologyImportResult {
+            base_result,
+            conflicts,
+            warnings,
+            processing_time_ms: start_time.elapsed().as_millis() as u64,
+        })
+    }
+    
     /// Export terminology to CSV file
     pub async fn export_terminology_csv(
         &self,
@@ -269,6 +486,145 @@ impl TerminologyService {
         Ok(terms.len())
     }
     
+    /// Export terms matching `filter` to CSV, returning how many were written
+    /// 
+    /// The file uses the same columns as [`Self::import_terminology_csv`]
+    /// reads, so it can be re-imported without loss. Terms are sorted
+    /// alphabetically so repeated exports produce identical files.
+    pub async fn export_csv(&self, filter: TermFilter, path: &Path) -> Result<usize> {
+        let mut terms: Vec<Terminology> = {
+            let storage = self.in_memory_storage.read().await;
+            storage
+                .iter()
+                .filter(|(project_id, _)| filter.project_id.is_none_or(|id| id == **project_id))
+                .flat_map(|(_, terms)| terms.iter())
+                .filter(|term| filter.matches(term))
+                .cloned()
+                .collect()
+        };
+        terms.sort_by(|a, b| a.term.to_lowercase().cmp(&b.term.to_lowercase()).then_with(|| a.term.cmp(&b.term)));
+        
+        self.csv_processor.export_to_csv(&terms, path).await?;
+        Ok(terms.len())
+    }
+    
+    /// Check `text` in `lang` against the glossary of every project
+    /// 
+    /// Depending on `config` this flags forbidden terms, do-not-translate
+    /// terms of `source_text` that `text` doesn't keep, and approved terms
+    /// written with different casing. Terms match as whole words, ignoring
+    /// case unless `config` is case sensitive. Errors are grouped by rule
+    /// and ordered by position.
+    pub async fn validate_text(
+        &self,
+        text: &str,
+        source_text: Option<&str>,
+        lang: &Language,
+        config: &TerminologyValidationConfig,
+    ) -> Vec<TermValidationError> {
+        let terms: Vec<Terminology> = {
+            let storage = self.in_memory_storage.read().await;
+            storage.values().flatten().cloned().collect()
+        };
+        let in_language = |term: &Terminology| term.language.as_ref().is_none_or(|language| language == lang);
+        let error = |rule, term: &Terminology, span| TermValidationError {
+            rule,
+            term_id: term.id,
+            term: term.term.clone(),
+            span,
+        };
+        let mut errors = Vec::new();
+        
+        if config.check_forbidden_terms {
+            for term in terms.iter().filter(|t| t.status == TermStatus::Forbidden && in_language(t)) {
+                for span in term_occurrences(text, &term.term, config.case_sensitive) {
+                    errors.push(error(TermRule::Forbidden, term, span));
+                }
+            }
+        }
+        
+        if let (true, Some(source_text)) = (config.check_mandatory_terms, source_text) {
+            // Do-not-translate terms read the same in every language, so
+            // the text must hold each occurrence verbatim
+            for term in terms.iter().filter(|t| t.do_not_translate) {
+                let kept = term_occurrences(text, &term.term, config.case_sensitive).len();
+                for span in term_occurrences(source_text, &term.term, config.case_sensitive).into_iter().skip(kept) {
+                    errors.push(error(TermRule::MissingMandatory, term, span));
+                }
+            }
+        }
+        
+        if config.check_term_casing {
+            for term in terms.iter().filter(|t| t.status == TermStatus::Approved && in_language(t)) {
+                for span in term_occurrences(text, &term.term, false) {
+                    if text[span.clone()] != *term.term.trim() {
+                        errors.push(error(TermRule::Casing, term, span));
+                    }
+                }
+            }
+        }
+        
+        errors.sort_by_key(|e| (e.rule as u8, e.span.start, e.span.end));
+        errors
+    }
+    
+    /// Report how often each term in `lang` occurs in the translation memory
+    /// 
+    /// A unit counts once if its text in `lang` contains the term as a whole
+    /// word, ignoring case. Terms without a language are checked against
+    /// every language. Approved terms that no unit uses are flagged as unused.
+    pub async fn usage_report(&self, lang: Language) -> Result<Vec<TermUsage>> {
+        let translation_memory = self.translation_memory.as_ref().ok_or_else(|| {
+            TranslationMemoryError::Configuration("No translation memory attached to the terminology service".to_string())
+        })?;
+        
+        let units = translation_memory.get_translation_units().await?;
+        let texts: Vec<(&str, DateTime<Utc>)> = units
+            .iter()
+            .filter_map(|unit| {
+                if unit.source_language == lang {
+                    Some((unit.source_text.as_str(), unit.updated_at))
+                } else if unit.target_language == lang {
+                    Some((unit.target_text.as_str(), unit.updated_at))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        
+        let mut terms: Vec<Terminology> = {
+            let storage = self.in_memory_storage.read().await;
+            storage
+                .values()
+                .flatten()
+                .filter(|term| term.language.as_ref().is_none_or(|language| *language == lang))
+                .cloned()
+                .collect()
+        };
+        terms.sort_by(|a, b| a.term.to_lowercase().cmp(&b.term.to_lowercase()).then_with(|| a.term.cmp(&b.term)));
+        
+        let mut report = Vec::with_capacity(terms.len());
+        for term in terms {
+            let pattern = regex::Regex::new(&format!(r"(?i)\b{}\b", regex::escape(term.term.trim())))?;
+            let matches: Vec<DateTime<Utc>> = texts
+                .iter()
+                .filter(|(text, _)| pattern.is_match(text))
+                .map(|(_, seen)| *seen)
+                .collect();
+            
+            report.push(TermUsage {
+                term_id: term.id,
+                unused: matches.is_empty() && term.status == TermStatus::Approved,
+                unit_count: matches.len(),
+                last_seen: matches.into_iter().max(),
+                status: term.status,
+                term: term.term,
+            });
+        }
+        
+        Ok(report)
+    }
+    
     /// Get all terminology entries for a project
     pub async fn get_terms_by_project(&self, project_id: Uuid) -> Result<Vec<Terminology>> {
         // Check cache first
@@ -341,18 +697,9 @@ impl TerminologyService {
         
         // Get all terms for the project and filter by search query
         let all_terms = self.get_terms_by_project(project_id).await?;
-        let query_lower = query.to_lowercase();
         
         let results: Vec<Terminology> = all_terms.into_iter()
-            .filter(|term| {
-                if case_sensitive {
-                    term.term.contains(query) || 
-                    term.definition.as_ref().map_or(false, |def| def.contains(query))
-                } else {
-                    term.term.to_lowercase().contains(&query_lower) ||
-                    term.definition.as_ref().map_or(false, |def| def.to_lowercase().contains(&query_lower))
-                }
-            })
+            .filter(|term| term_matches(term, query, case_sensitive))
             .collect();
         
         // Cache the results
@@ -567,7 +914,8 @@ impl TerminologyService {
             
             // Check for duplicates if not allowed
             if !self.validation_config.allow_duplicates {
-                if project_terms.iter().any(|t| self.are_duplicate_terms(&t.term, &terminology.term)) {
+                let key = self.term_key(&terminology.term);
+                if project_terms.iter().any(|t| self.term_key(&t.term) == key) {
                     return Err(TranslationMemoryError::ValidationError(
                         format!("Term '{}' already exists in project", terminology.term)
                     ));
@@ -588,12 +936,212 @@ impl TerminologyService {
         Ok(())
     }
     
+    /// Import failure for a CSV line that couldn't be read
+    fn row_error(error: crate::utils::CsvRowError) -> TerminologyImportError {
+        TerminologyImportError {
+            row_number: error.line as usize,
+            term: String::new(),
+            error_message: error.message,
+            error_type: ImportErrorType::Format,
+        }
+    }
+    
+    /// Validated terminology of the `row_number`th CSV record
+    fn record_to_terminology(
+        &self,
+        record: &TerminologyCsvRecord,
+        row_number: usize,
+    ) -> std::result::Result<Terminology, TerminologyImportError> {
+        let term = record.to_term().map_err(|e| TerminologyImportError {
+            row_number,
+            term: record.term.clone(),
+            error_message: e.to_string(),
+            error_type: ImportErrorType::Format,
+        })?;
+        
+        // Convert Term to Terminology for service use
+        let terminology = Terminology {
+            id: term.id,
+            term: normalize_term(&term.term),
+            definition: term.definition,
+            do_not_translate: term.do_not_translate,
+            domain: term.domain,
+            language: term.language,
+            status: term.status,
+            created_at: term.created_at,
+            updated_at: term.updated_at,
+        };
+        
+        self.validate_terminology(&terminology).map_err(|validation_error| TerminologyImportError {
+            row_number,
+            term: term.term,
+            error_message: validation_error.to_string(),
+            error_type: ImportErrorType::Validation,
+        })?;
+        Ok(terminology)
+    }
+    
+    /// Store imported terms, merging rows for the same term and leaving out
+    /// duplicates of stored ones unless they are allowed. Returns the terms
+    /// kept, their validation and how many rows were merged.
+    async fn store_imported_terms(
+        &self,
+        terms: Vec<Terminology>,
+        project_id: Uuid,
+        failed_terms: &mut Vec<TerminologyImportError>,
+    ) -> Result<(Vec<Terminology>, TermValidationResult, usize)> {
+        let mut merge_conflicts = Vec::new();
+        let (terms, merged_rows) = if self.validation_config.allow_duplicates {
+            (terms, 0)
+        } else {
+            self.merge_duplicate_rows(terms, &mut merge_conflicts)
+        };
+        
+        // Validate terms and detect conflicts
+        let mut validation_result = self.validate_terms(&terms, project_id).await?;
+        validation_result.conflicts.splice(0..0, merge_conflicts);
+        
+        // Filter out conflicting terms if not allowed
+        let final_terms: Vec<Terminology> = if self.validation_config.allow_duplicates {
+            terms
+        } else {
+            terms.into_iter()
+                .filter(|term| !validation_result.duplicates.contains(&term.term))
+                .collect()
+        };
+        
+        // Store valid terms
+        for term in &final_terms {
+            if let Err(e) = self.add_terminology_internal(term.clone(), project_id).await {
+                failed_terms.push(TerminologyImportError {
+                    row_number: 0, // Unknown row at this point
+                    term: term.term.clone(),
+                    error_message: e.to_string(),
+                    error_type: ImportErrorType::Database,
+                });
+            }
+        }
+        
+        Ok((final_terms, validation_result, merged_rows))
+    }
+    
+    /// Fold rows for the same term into the first of them, filling in each
+    /// other's missing fields. Fields the rows disagree on are reported as
+    /// conflicts and settled by the configured [`ConflictResolution`].
+    /// Returns the remaining terms and how many rows were folded away.
+    fn merge_duplicate_rows(
+        &self,
+        terms: Vec<Terminology>,
+        conflicts: &mut Vec<TermConflict>,
+    ) -> (Vec<Terminology>, usize) {
+        let mut kept: Vec<Terminology> = Vec::with_capacity(terms.len());
+        let mut index_by_key: HashMap<String, usize> = HashMap::new();
+        let mut rows_by_key: HashMap<String, usize> = HashMap::new();
+        let mut merged_rows = 0;
+        
+        for mut term in terms {
+            let key = self.term_key(&term.term);
+            *rows_by_key.entry(key.clone()).or_default() += 1;
+            let Some(&index) = index_by_key.get(&key) else {
+                index_by_key.insert(key, kept.len());
+                kept.push(term);
+                continue;
+            };
+            
+            let existing = &mut kept[index];
+            let disagreements = Self::row_conflicts(existing, &term);
+            if disagreements.is_empty() {
+                Self::fill_missing_fields(existing, &term);
+                merged_rows += 1;
+                continue;
+            }
+            conflicts.extend(disagreements);
+            
+            match self.validation_config.conflict_resolution {
+                ConflictResolution::Skip => {
+                    Self::fill_missing_fields(existing, &term);
+                    merged_rows += 1;
+                }
+                ConflictResolution::Overwrite => {
+                    Self::fill_missing_fields(&mut term, existing);
+                    term.id = existing.id;
+                    term.created_at = existing.created_at;
+                    *existing = term;
+                    merged_rows += 1;
+                }
+                ConflictResolution::Merge => {
+                    if let (Some(definition), Some(other)) = (&mut existing.definition, &term.definition) {
+                        if definition != other {
+                            definition.push_str("\n\n");
+                            definition.push_str(other);
+                        }
+                    }
+                    Self::fill_missing_fields(existing, &term);
+                    merged_rows += 1;
+                }
+                ConflictResolution::CreateVariant => {
+                    term.term = format!("{} ({})", term.term, rows_by_key[&key]);
+                    index_by_key.insert(self.term_key(&term.term), kept.len());
+                    kept.push(term);
+                }
+            }
+        }
+        
+        (kept, merged_rows)
+    }
+    
+    /// Fields on which two rows for the same term disagree
+    fn row_conflicts(existing: &Terminology, row: &Terminology) -> Vec<TermConflict> {
+        let mut conflicts = Vec::new();
+        let mut compare = |conflict_type, existing: Option<String>, new: Option<String>| {
+            if existing.is_some() && new.is_some() && existing != new {
+                conflicts.push(TermConflict {
+                    term: row.term.clone(),
+                    existing_definition: existing,
+                    new_definition: new,
+                    conflict_type,
+                });
+            }
+        };
+        compare(ConflictType::Definition, existing.definition.clone(), row.definition.clone());
+        compare(
+            ConflictType::DoNotTranslate,
+            Some(existing.do_not_translate.to_string()),
+            Some(row.do_not_translate.to_string()),
+        );
+        compare(ConflictType::Domain, existing.domain.map(|d| d.to_string()), row.domain.map(|d| d.to_string()));
+        compare(
+            ConflictType::Language,
+            existing.language.as_ref().map(ToString::to_string),
+            row.language.as_ref().map(ToString::to_string),
+        );
+        compare(ConflictType::Status, Some(existing.status.as_str().to_string()), Some(row.status.as_str().to_string()));
+        conflicts
+    }
+    
+    /// Fill the fields `term` lacks from `other`
+    fn fill_missing_fields(term: &mut Terminology, other: &Terminology) {
+        if term.definition.is_none() {
+            term.definition = other.definition.clone();
+        }
+        if term.domain.is_none() {
+            term.domain = other.domain;
+        }
+        if term.language.is_none() {
+            term.language = other.language.clone();
+        }
+    }
+    
     async fn validate_terms(
         &self,
         terms: &[Terminology],
         project_id: Uuid,
     ) -> Result<TermValidationResult> {
         let existing_terms = self.get_terms_by_project(project_id).await?;
+        let mut existing_by_key: HashMap<String, Vec<&Terminology>> = HashMap::new();
+        for existing in &existing_terms {
+            existing_by_key.entry(self.term_key(&existing.term)).or_default().push(existing);
+        }
         
         let mut conflicts = Vec::new();
         let mut warnings = Vec::new();
@@ -601,28 +1149,26 @@ impl TerminologyService {
         
         for term in terms {
             // Check for conflicts with existing terms
-            for existing in &existing_terms {
-                if self.are_duplicate_terms(&term.term, &existing.term) {
-                    if term.definition != existing.definition {
-                        conflicts.push(TermConflict {
-                            term: term.term.clone(),
-                            existing_definition: existing.definition.clone(),
-                            new_definition: term.definition.clone(),
-                            conflict_type: ConflictType::Definition,
-                        });
-                    }
-                    
-                    if term.do_not_translate != existing.do_not_translate {
-                        conflicts.push(TermConflict {
-                            term: term.term.clone(),
-                            existing_definition: Some(existing.do_not_translate.to_string()),
-                            new_definition: Some(term.do_not_translate.to_string()),
-                            conflict_type: ConflictType::DoNotTranslate,
-                        });
-                    }
-                    
-                    duplicates.push(term.term.clone());
+            for existing in existing_by_key.get(&self.term_key(&term.term)).into_iter().flatten() {
+                if term.definition != existing.definition {
+                    conflicts.push(TermConflict {
+                        term: term.term.clone(),
+                        existing_definition: existing.definition.clone(),
+                        new_definition: term.definition.clone(),
+                        conflict_type: ConflictType::Definition,
+                    });
+                }
+                
+                if term.do_not_translate != existing.do_not_translate {
+                    conflicts.push(TermConflict {
+                        term: term.term.clone(),
+                        existing_definition: Some(existing.do_not_translate.to_string()),
+                        new_definition: Some(term.do_not_translate.to_string()),
+                        conflict_type: ConflictType::DoNotTranslate,
+                    });
                 }
+                
+                duplicates.push(term.term.clone());
             }
             
             // Generate warnings
@@ -674,10 +1220,16 @@ impl TerminologyService {
     }
     
     fn are_duplicate_terms(&self, term1: &str, term2: &str) -> bool {
+        self.term_key(term1) == self.term_key(term2)
+    }
+    
+    /// Key under which different spellings of the same term are found
+    fn term_key(&self, term: &str) -> String {
+        let normalized = normalize_term(term);
         if self.validation_config.case_sensitive {
-            term1 == term2
+            normalized
         } else {
-            term1.to_lowercase() == term2.to_lowercase()
+            normalized.to_lowercase()
         }
     }
     
@@ -731,4 +1283,25 @@ impl TerminologyService {
         cache.non_translatable_terms.clear();
         cache.last_updated = Some(Utc::now());
     }
+}
+
+/// Byte ranges of the whole-word occurrences of `term` in `text`
+fn term_occurrences(text: &str, term: &str, case_sensitive: bool) -> Vec<Range<usize>> {
+    let pattern = format!(r"{}\b{}\b", if case_sensitive { "" } else { "(?i)" }, regex::escape(term.trim()));
+    let Ok(pattern) = regex::Regex::new(&pattern) else {
+        return Vec::new();
+    };
+    pattern.find_iter(text).map(|m| m.range()).collect()
+}
+
+/// Whether the text or definition of `term` contains `query`
+fn term_matches(term: &Terminology, query: &str, case_sensitive: bool) -> bool {
+    if case_sensitive {
+        term.term.contains(query) ||
+        term.definition.as_ref().is_some_and(|def| def.contains(query))
+    } else {
+        let query = query.to_lowercase();
+        term.term.to_lowercase().contains(&query) ||
+        term.definition.as_ref().is_some_and(|def| def.to_lowercase().contains(&query))
+    }
 }
\ No newline at end of file
@@ -7,16 +7,23 @@
 //! - Coordinated caching with database operations
 //! - Production-ready error handling and logging
 
-use crate::error::{Result, TranslationMemoryError};
-use crate::models::{TranslationUnit, Language, ChunkMetadata as Chunk};
-use crate::storage::DuckDBManager;
+use crate::error::{ensure_not_cancelled, Result, TranslationMemoryError};
+use crate::models::{TranslationUnit, Language, Quality, SearchPage, SearchQuery, ChunkMetadata as Chunk};
+use crate::services::lookup_cache::{LookupCache, LookupCacheConfig, LookupCacheStats, LookupKey};
+use crate::storage::{DuckDBManager, TmStorage, TranslationMemoryStorageStats};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
+use futures::stream::{self, Stream};
 use std::path::PathBuf;
 use itertools::Itertools;
+use tokio_util::sync::CancellationToken;
+
+/// Units loaded and written per transaction by [`TranslationMemoryService::rescore`]
+const RESCORE_BATCH_SIZE: usize = 500;
 
 /// Translation match result from similarity search
 /// Migrated from the original service with enhanced metadata
@@ -32,6 +39,28 @@ pub struct TranslationMatch {
     pub metadata: TranslationMatchMetadata,
 }
 
+impl TranslationMatch {
+    /// A stored unit matching a query with `similarity_score`
+    pub fn from_unit(unit: &TranslationUnit, similarity_score: f32) -> Self {
+        Self {
+            id: unit.id,
+            source_text: unit.source_text.clone(),
+            target_text: unit.target_text.clone(),
+            confidence_score: unit.confidence_score,
+            similarity_score,
+            context: unit.context.clone(),
+            language_pair: LanguagePair::new(unit.source_language.clone(), unit.target_language.clone()),
+            metadata: TranslationMatchMetadata {
+                translator_id: unit.metadata.translator_id.clone(),
+                reviewer_id: unit.metadata.reviewer_id.clone(),
+                quality_score: unit.metadata.quality_score,
+                created_at: unit.created_at,
+                updated_at: unit.updated_at,
+            },
+        }
+    }
+}
+
 /// Language pair for translation operations
 /// Enhanced to support both Language enum and string codes for compatibility
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash)]
@@ -120,55 +149,122 @@ pub enum ChunkLinkType {
     Merged,
 }
 
+/// Options for [`TranslationMemoryService::find_inconsistencies`]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ConsistencyCheckOptions {
+    /// Ignore surrounding whitespace and trailing punctuation when comparing
+    /// source and target texts
+    pub normalize: bool,
+}
+
+impl Default for ConsistencyCheckOptions {
+    fn default() -> Self {
+        Self { normalize: true }
+    }
+}
+
+/// Where a translation unit came from
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct UnitLocation {
+    pub unit_id: Uuid,
+    pub chapter_id: Uuid,
+    pub chunk_id: Uuid,
+    pub context: Option<String>,
+}
+
+/// One distinct translation of a source text, with every place it is used
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct TranslationVariant {
+    pub target_text: String,
+    pub locations: Vec<UnitLocation>,
+}
+
+/// Source text that has been translated in more than one way
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct InconsistencyGroup {
+    /// Source text as first seen, before normalization
+    pub source_text: String,
+    pub language_pair: LanguagePair,
+    pub variants: Vec<TranslationVariant>,
+}
+
+/// How [`TranslationMemoryService::merge_from`] resolves an incoming unit
+/// whose language pair and source text already exist with a different target
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Leave the existing translation as it is
+    KeepExisting,
+    /// Take the translation with the higher reviewer quality score, falling
+    /// back to the confidence score when a unit has not been reviewed
+    PreferHigherQuality,
+    /// Take the most recently updated translation
+    PreferNewer,
+    /// Store the incoming translation alongside the existing one
+    KeepBoth,
+}
+
+/// Outcome of [`TranslationMemoryService::merge_from`]
+/// 
+/// Every incoming unit is counted as exactly one of inserted, updated, or
+/// skipped. `conflicted` counts the incoming units whose source matched an
+/// existing unit with a different target, however they were resolved.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct MergeReport {
+    pub inserted: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub conflicted: usize,
+}
+
 /// Thread-safe in-memory cache for frequently accessed translations
 /// Using DashMap for lock-free concurrent access
 #[derive(Debug, Default)]
 struct TranslationCache {
+    // Search results, bounded and expiring; tracks its own hit statistics
+    translation_units: LookupCache,
     // Use DashMap for thread-safe concurrent access without explicit locking
-    translation_units: DashMap<String, Vec<TranslationMatch>>,
     chunks: DashMap<Uuid, Chunk>,
     language_pairs: DashMap<LanguagePair, DateTime<Utc>>,
     last_updated: Arc<RwLock<Option<DateTime<Utc>>>>,
-    // Cache statistics
-    hit_count: Arc<std::sync::atomic::AtomicU64>,
-    miss_count: Arc<std::sync::atomic::AtomicU64>,
 }
 
 /// Thread-safe translation memory service with async operations
 /// 
 /// THREAD SAFETY IMPROVEMENTS:
-/// - Replaced Arc<RwLock<Connection>> with proper connection pooling via DuckDBManager
+/// - Replaced Arc<RwLock<Connection>> with any `TmStorage` backend, such as
+///   the pooled DuckDBManager or the in-memory store
 /// - All database operations are now truly async with Send + Sync
 /// - Cache uses lock-free DashMap for concurrent access
 /// - Connection pool prevents blocking on database operations
-#[derive(Debug)]
 pub struct TranslationMemoryService {
-    // Database manager with connection pooling (THREAD SAFETY FIX)
-    duckdb_manager: Arc<DuckDBManager>,
+    // Backend storing the translation units
+    storage: Arc<dyn TmStorage>,
     // Thread-safe cache with lock-free concurrent access
     cache: Arc<TranslationCache>,
     project_id: Uuid,
-    #[allow(dead_code)]
-    project_path: PathBuf,
     // Configuration
     max_search_results: usize,
     min_similarity_threshold: f32,
 }
 
+impl std::fmt::Debug for TranslationMemoryService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TranslationMemoryService")
+            .field("project_id", &self.project_id)
+            .field("cache", &self.cache)
+            .field("max_search_results", &self.max_search_results)
+            .field("min_similarity_threshold", &self.min_similarity_threshold)
+            .finish_non_exhaustive()
+    }
+}
+
 impl TranslationMemoryService {
-    /// Create a new translation memory service with proper connection pooling
-    /// 
-    /// THREAD SAFETY: Uses connection pooling instead of shared connection
-    pub async fn new(project_id: Uuid, project_path: PathBuf) -> Result<Self> {
-        // Create database manager with connection pooling (THREAD SAFETY FIX)
-        let db_path = project_path.join("translation_memory.db");
-        let duckdb_manager = DuckDBManager::new(&db_path, Some(10)).await?;
-        
+    /// Create a translation memory service over `storage`
+    pub async fn new(project_id: Uuid, storage: Arc<dyn TmStorage>) -> Result<Self> {
         let service = Self {
-            duckdb_manager,
+            storage,
             cache: Arc::new(TranslationCache::default()),
             project_id,
-            project_path,
             max_search_results: 20,
             min_similarity_threshold: 0.3,
         };
@@ -177,6 +273,25 @@ impl TranslationMemoryService {
         Ok(service)
     }
     
+    /// Create a service over the DuckDB database in `project_path`
+    /// 
+    /// THREAD SAFETY: Uses connection pooling instead of shared connection
+    pub async fn open(project_id: Uuid, project_path: PathBuf) -> Result<Self> {
+        let db_path = project_path.join("translation_memory.db");
+        let duckdb_manager = DuckDBManager::new(&db_path, Some(10)).await?;
+        Self::new(project_id, duckdb_manager).await
+    }
+
+    /// Set the capacity and time to live of the search result cache,
+    /// dropping anything cached so far
+    pub fn with_cache_config(mut self, config: LookupCacheConfig) -> Self {
+        self.cache = Arc::new(TranslationCache {
+            translation_units: LookupCache::new(config),
+            ..TranslationCache::default()
+        });
+        self
+    }
+    
     /// Search for translation matches (legacy API for lib.rs compatibility)
     /// 
     /// THREAD SAFETY: Uses connection pool, no blocking operations
@@ -186,6 +301,47 @@ impl TranslationMemoryService {
         source_lang: Language,
         target_lang: Language,
         threshold: f64,
+    ) -> Result<Vec<TranslationUnit>> {
+        self.search_cancellable(query, source_lang, target_lang, threshold, &CancellationToken::new()).await
+    }
+    
+    /// One page of the translation matches of `query`, with the number of
+    /// matches on all pages
+    /// 
+    /// Matches are ordered best first with ties broken by ID, so paging
+    /// through the results neither repeats nor skips any.
+    pub async fn search_paged(
+        &self,
+        query: &str,
+        source_lang: Language,
+        target_lang: Language,
+        threshold: f64,
+        page: &SearchQuery,
+    ) -> Result<SearchPage<TranslationUnit>> {
+        let language_pair = LanguagePair::new(source_lang, target_lang);
+        let matches = self
+            .all_similar_translations(query, language_pair, Some(threshold as f32), &CancellationToken::new())
+            .await?;
+        let page = SearchPage::from_results(matches, page);
+        Ok(SearchPage {
+            items: page.items.into_iter().map(|m| self.match_to_unit(m)).collect(),
+            total_available: page.total_available,
+            offset: page.offset,
+        })
+    }
+    
+    /// Search for translation matches, giving up with
+    /// [`TranslationMemoryError::Cancelled`] once `cancel` is triggered.
+    /// 
+    /// The token is checked between search phases, so a phase that has
+    /// started always finishes and hands its connection back to the pool.
+    pub async fn search_cancellable(
+        &self,
+        query: &str,
+        source_lang: Language,
+        target_lang: Language,
+        threshold: f64,
+        cancel: &CancellationToken,
     ) -> Result<Vec<TranslationUnit>> {
         let language_pair = LanguagePair {
             source: source_lang,
@@ -193,38 +349,96 @@ impl TranslationMemoryService {
         };
         
         let matches = self
-            .search_similar_translations(query, language_pair, Some(threshold as f32))
+            .find_similar_translations(query, language_pair, Some(threshold as f32), cancel)
             .await?;
         
         // Convert matches back to TranslationUnits for compatibility
-        let mut units = Vec::new();
-        for m in matches {
-            // Create a translation unit from the match
-            let unit = TranslationUnit {
-                id: m.id,
-                project_id: self.project_id,
-                chapter_id: Uuid::new_v4(), // TODO: Extract from context or metadata
-                chunk_id: Uuid::new_v4(),   // TODO: Extract from context or metadata
-                source_language: m.language_pair.source,
-                source_text: m.source_text,
-                target_language: m.language_pair.target,
-                target_text: m.target_text,
-                confidence_score: m.confidence_score,
-                context: m.context,
-                metadata: crate::models::TranslationMetadata {
-                    translator_id: m.metadata.translator_id,
-                    reviewer_id: m.metadata.reviewer_id,
-                    quality_score: m.metadata.quality_score,
-                    notes: Vec::new(),
-                    tags: Vec::new(),
-                },
-                created_at: m.metadata.created_at,
-                updated_at: m.metadata.updated_at,
-            };
-            units.push(unit);
+        Ok(matches.into_iter().map(|m| self.match_to_unit(m)).collect())
+    }
+    
+    fn match_to_unit(&self, m: TranslationMatch) -> TranslationUnit {
+        TranslationUnit {
+            id: m.id,
+            project_id: self.project_id,
+            chapter_id: Uuid::new_v4(), // TODO: Extract from context or metadata
+            chunk_id: Uuid::new_v4(),   // TODO: Extract from context or metadata
+            source_language: m.language_pair.source,
+            source_text: m.source_text,
+            target_language: m.language_pair.target,
+            target_text: m.target_text,
+            confidence_score: m.confidence_score,
+            context: m.context,
+            metadata: crate::models::TranslationMetadata {
+                translator_id: m.metadata.translator_id,
+                reviewer_id: m.metadata.reviewer_id,
+                quality_score: m.metadata.quality_score,
+                notes: Vec::new(),
+                tags: Vec::new(),
+            },
+            created_at: m.metadata.created_at,
+            updated_at: m.metadata.updated_at,
         }
+    }
+    
+    /// Stream translation matches as each search phase finishes, so the
+    /// editor can fill its suggestion list before the slower phases are done.
+    /// 
+    /// Matches come best first within a phase and phases run from exact to
+    /// n-gram, so the order is only roughly by similarity. The stream yields
+    /// the same matches as [`Self::search`] and stops scanning as soon as it
+    /// is dropped. A phase that fails ends the stream.
+    pub fn search_stream<'a>(
+        &'a self,
+        query: &'a str,
+        source_lang: Language,
+        target_lang: Language,
+        threshold: f64,
+    ) -> impl Stream<Item = TranslationMatch> + 'a {
+        let state = SearchStreamState {
+            language_pair: LanguagePair::new(source_lang, target_lang),
+            threshold: threshold as f32,
+            phase: if query.trim().is_empty() { SearchPhase::Done } else { SearchPhase::Exact },
+            pending: Vec::new().into_iter(),
+            seen: HashSet::new(),
+        };
         
-        Ok(units)
+        stream::unfold(state, move |mut state| async move {
+            loop {
+                if state.seen.len() >= self.max_search_results {
+                    return None;
+                }
+                if let Some(found) = state.pending.next() {
+                    if state.seen.insert(found.id) {
+                        return Some((found, state));
+                    }
+                    continue;
+                }
+                
+                let pair = &state.language_pair;
+                let phase_matches = match state.phase {
+                    SearchPhase::Exact => self.search_exact_matches(query, pair).await,
+                    SearchPhase::Fuzzy => self.search_fuzzy_matches(query, pair, state.threshold).await,
+                    SearchPhase::NGram => self.search_ngram_matches(query, pair, state.threshold).await,
+                    SearchPhase::Done => return None,
+                };
+                state.phase = state.phase.next();
+                
+                match phase_matches {
+                    Ok(mut matches) => {
+                        matches.sort_by(|a, b| {
+                            b.similarity_score
+                                .partial_cmp(&a.similarity_score)
+                                .unwrap_or(std::cmp::Ordering::Equal)
+                        });
+                        state.pending = matches.into_iter();
+                    }
+                    Err(e) => {
+                        log::warn!("Streaming search for '{}' stopped: {}", query, e);
+                        return None;
+                    }
+                }
+            }
+        })
     }
     
     /// Initialize the service and create necessary tables
@@ -234,7 +448,7 @@ impl TranslationMemoryService {
         log::info!("Initializing translation memory service for project: {}", self.project_id);
         
         // Initialize database schema with connection pooling
-        self.duckdb_manager.initialize_schema().await
+        self.storage.initialize_schema().await
             .map_err(|e| TranslationMemoryError::DatabaseError(
                 format!("Failed to initialize database schema: {}", e)
             ))?;
@@ -251,6 +465,33 @@ impl TranslationMemoryService {
         source_text: &str,
         language_pair: LanguagePair,
         min_similarity: Option<f32>,
+    ) -> Result<Vec<TranslationMatch>> {
+        self.find_similar_translations(source_text, language_pair, min_similarity, &CancellationToken::new())
+            .await
+    }
+    
+    /// The best [`Self::max_search_results`] matches of `source_text`
+    async fn find_similar_translations(
+        &self,
+        source_text: &str,
+        language_pair: LanguagePair,
+        min_similarity: Option<f32>,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<TranslationMatch>> {
+        let mut matches = self
+            .all_similar_translations(source_text, language_pair, min_similarity, cancel)
+            .await?;
+        matches.truncate(self.max_search_results);
+        Ok(matches)
+    }
+    
+    /// Every match of `source_text`, best first with ties broken by ID
+    async fn all_similar_translations(
+        &self,
+        source_text: &str,
+        language_pair: LanguagePair,
+        min_similarity: Option<f32>,
+        cancel: &CancellationToken,
     ) -> Result<Vec<TranslationMatch>> {
         // Validate input
         if source_text.trim().is_empty() {
@@ -259,19 +500,15 @@ impl TranslationMemoryService {
             ));
         }
         
-        // Check cache first (THREAD SAFETY: using DashMap for lock-free access)
-        let cache_key = self.create_cache_key(source_text, &language_pair);
+        let threshold = min_similarity.unwrap_or(self.min_similarity_threshold);
+
+        // Check cache first; the cache counts hits and misses itself
+        let cache_key = LookupKey::new(source_text, &language_pair, threshold);
         if let Some(cached_matches) = self.cache.translation_units.get(&cache_key) {
-            // Update hit count
-            self.cache.hit_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-            return Ok(cached_matches.clone());
+            return Ok(cached_matches);
         }
         
-        // Update miss count
-        self.cache.miss_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        
         let mut matches = Vec::new();
-        let threshold = min_similarity.unwrap_or(self.min_similarity_threshold);
         
         log::debug!(
             "Searching for translations: '{}' ({} -> {}) with threshold {}",
@@ -279,34 +516,34 @@ impl TranslationMemoryService {
         );
         
         // Strategy 1: Exact phrase matching (highest priority)
+        ensure_not_cancelled(cancel)?;
         let exact_matches = self.search_exact_matches(source_text, &language_pair).await?;
         matches.extend(exact_matches);
         
         // Strategy 2: Fuzzy matching with edit distance
-        if matches.len() < self.max_search_results {
-            let fuzzy_matches = self.search_fuzzy_matches(source_text, &language_pair, threshold).await?;
-            matches.extend(fuzzy_matches);
-        }
+        ensure_not_cancelled(cancel)?;
+        let fuzzy_matches = self.search_fuzzy_matches(source_text, &language_pair, threshold).await?;
+        matches.extend(fuzzy_matches);
         
         // Strategy 3: N-gram similarity
-        if matches.len() < self.max_search_results {
-            let ngram_matches = self.search_ngram_matches(source_text, &language_pair, threshold).await?;
-            matches.extend(ngram_matches);
-        }
+        ensure_not_cancelled(cancel)?;
+        let ngram_matches = self.search_ngram_matches(source_text, &language_pair, threshold).await?;
+        matches.extend(ngram_matches);
         
-        // Remove duplicates and sort by similarity score (descending)
+        // Remove duplicates and sort by similarity score (descending); the
+        // ID tiebreak keeps pages stable
         matches.sort_by(|a, b| {
             b.similarity_score
                 .partial_cmp(&a.similarity_score)
                 .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.id.cmp(&b.id))
         });
         matches = matches
             .into_iter()
             .unique_by(|m| m.id)
-            .take(self.max_search_results)
             .collect();
         
-        // Cache the results (THREAD SAFETY: using DashMap for lock-free access)
+        // Cache the results (THREAD SAFETY: the cache locks internally)
         self.cache.translation_units.insert(cache_key, matches.clone());
         
         // Update last_updated timestamp
@@ -337,13 +574,13 @@ impl TranslationMemoryService {
                    unit.id, unit.source_language, unit.target_language);
         
         // Add to database using connection pool (THREAD SAFETY FIX)
-        self.duckdb_manager.insert_translation_unit(&unit).await
+        self.storage.insert_translation_unit(&unit).await
             .map_err(|e| TranslationMemoryError::DatabaseError(
                 format!("Failed to insert translation unit: {}", e)
             ))?;
         
-        // Invalidate relevant cache entries (THREAD SAFETY: lock-free operations)
-        self.invalidate_translation_cache(&unit.source_text, &LanguagePair {
+        // A new unit can match any query for its pair, not just its own text
+        self.invalidate_cache_for_language_pair(&LanguagePair {
             source: unit.source_language.clone(),
             target: unit.target_language.clone(),
         }).await;
@@ -375,7 +612,7 @@ impl TranslationMemoryService {
         }
         
         // Batch insert to database using connection pool (THREAD SAFETY FIX)
-        let inserted_count = self.duckdb_manager.insert_translation_units_batch(&units).await
+        let inserted_count = self.storage.insert_translation_units_batch(&units).await
             .map_err(|e| TranslationMemoryError::DatabaseError(
                 format!("Failed to insert translation units batch: {}", e)
             ))?;
@@ -410,13 +647,13 @@ impl TranslationMemoryService {
         log::debug!("Updating translation unit: {}", unit.id);
         
         // Update in database using connection pool (THREAD SAFETY FIX)
-        self.duckdb_manager.update_translation_unit(&unit).await
+        self.storage.update_translation_unit(&unit).await
             .map_err(|e| TranslationMemoryError::DatabaseError(
                 format!("Failed to update translation unit: {}", e)
             ))?;
         
         // Invalidate relevant cache entries
-        self.invalidate_translation_cache(&unit.source_text, &LanguagePair {
+        self.invalidate_cache_for_language_pair(&LanguagePair {
             source: unit.source_language.clone(),
             target: unit.target_language.clone(),
         }).await;
@@ -432,7 +669,7 @@ impl TranslationMemoryService {
         log::debug!("Deleting translation unit: {}", id);
         
         // Delete from database using connection pool (THREAD SAFETY FIX)
-        let deleted = self.duckdb_manager.delete_translation_unit(id).await
+        let deleted = self.storage.delete_translation_unit(id).await
             .map_err(|e| TranslationMemoryError::DatabaseError(
                 format!("Failed to delete translation unit: {}", e)
             ))?;
@@ -542,6 +779,240 @@ impl TranslationMemoryService {
         Ok(())
     }
     
+    /// Get every translation unit stored for the project
+    pub async fn get_translation_units(&self) -> Result<Vec<TranslationUnit>> {
+        self.storage
+            .get_translation_units_by_project(self.project_id, None, None, None)
+            .await
+            .map_err(|e| TranslationMemoryError::DatabaseError(
+                format!("Failed to load translation units: {}", e)
+            ))
+    }
+    
+    /// Merge the translation units of `other` into this memory
+    /// 
+    /// Units are matched on language pair and source text; matches with the
+    /// same target are skipped and differing targets are resolved by
+    /// `strategy`. All changes are written in one transaction, so a failure
+    /// leaves this memory unchanged.
+    pub async fn merge_from(&self, other: &TranslationMemoryService, strategy: MergeStrategy) -> Result<MergeReport> {
+        let mut units = self.get_translation_units().await?;
+        let incoming = other.get_translation_units().await?;
+        
+        let key = |unit: &TranslationUnit| {
+            (unit.source_language.clone(), unit.target_language.clone(), unit.source_text.clone())
+        };
+        let mut index: std::collections::HashMap<_, usize> = std::collections::HashMap::new();
+        for (position, unit) in units.iter().enumerate() {
+            index.entry(key(unit)).or_insert(position);
+        }
+        let mut ids: std::collections::HashSet<Uuid> = units.iter().map(|u| u.id).collect();
+        
+        let mut report = MergeReport::default();
+        let mut changed: Vec<usize> = Vec::new();
+        for mut unit in incoming {
+            unit.project_id = self.project_id;
+            
+            if let Some(&existing) = index.get(&key(&unit)) {
+                let current = &units[existing];
+                if current.target_text == unit.target_text {
+                    report.skipped += 1;
+                    continue;
+                }
+                report.conflicted += 1;
+                
+                let quality = |u: &TranslationUnit| u.metadata.quality_score.unwrap_or(u.confidence_score);
+                let take_incoming = match strategy {
+                    MergeStrategy::KeepExisting => Some(false),
+                    MergeStrategy::PreferHigherQuality => Some(quality(&unit) > quality(current)),
+                    MergeStrategy::PreferNewer => Some(unit.updated_at > current.updated_at),
+                    MergeStrategy::KeepBoth => None,
+                };
+                match take_incoming {
+                    Some(true) => {
+                        let current = &mut units[existing];
+                        current.target_text = unit.target_text;
+                        current.confidence_score = unit.confidence_score;
+                        current.context = unit.context;
+                        current.metadata = unit.metadata;
+                        current.updated_at = unit.updated_at;
+                        changed.push(existing);
+                        report.updated += 1;
+                        continue;
+                    }
+                    Some(false) => {
+                        report.skipped += 1;
+                        continue;
+                    }
+                    None => {}
+                }
+            }
+            
+            // A new source, or a conflicting translation kept alongside the existing one
+            if !ids.insert(unit.id) {
+                unit.id = Uuid::new_v4();
+                ids.insert(unit.id);
+            }
+            index.entry(key(&unit)).or_insert(units.len());
+            changed.push(units.len());
+            units.push(unit);
+            report.inserted += 1;
+        }
+        
+        changed.sort_unstable();
+        changed.dedup();
+        let changed: Vec<TranslationUnit> = changed.into_iter().map(|position| units[position].clone()).collect();
+        if !changed.is_empty() {
+            self.storage.upsert_translation_units_transaction(&changed).await
+                .map_err(|e| TranslationMemoryError::DatabaseError(
+                    format!("Failed to merge translation units: {}", e)
+                ))?;
+            
+            let language_pairs: Vec<_> = changed
+                .iter()
+                .map(|u| LanguagePair {
+                    source: u.source_language.clone(),
+                    target: u.target_language.clone(),
+                })
+                .unique()
+                .collect();
+            for pair in language_pairs {
+                self.invalidate_cache_for_language_pair(&pair).await;
+            }
+        }
+        
+        log::info!("Merged translation memory: {:?}", report);
+        Ok(report)
+    }
+    
+    /// Recompute the quality of stored units with `scorer`
+    /// 
+    /// Units are loaded and written back in batches of
+    /// [`RESCORE_BATCH_SIZE`], each in its own transaction, so the memory is
+    /// never loaded at once. The quality is stored as the reviewer quality
+    /// score, from 0.0 to 1.0. Returns the number of units whose quality
+    /// changed; `filter` limits rescoring to one language pair.
+    pub async fn rescore(
+        &self,
+        scorer: impl Fn(&TranslationUnit) -> Quality,
+        filter: Option<LanguagePair>,
+    ) -> Result<usize> {
+        let mut updated = 0;
+        let mut offset = 0;
+        let mut language_pairs = HashSet::new();
+        loop {
+            let batch = self.storage
+                .get_translation_units_by_project(self.project_id, filter.as_ref(), Some(RESCORE_BATCH_SIZE), Some(offset))
+                .await
+                .map_err(|e| TranslationMemoryError::DatabaseError(
+                    format!("Failed to load translation units: {}", e)
+                ))?;
+            // Rewriting units in place keeps their order, so offsets stay valid
+            offset += batch.len();
+            let exhausted = batch.len() < RESCORE_BATCH_SIZE;
+            
+            let changed: Vec<TranslationUnit> = batch
+                .into_iter()
+                .filter_map(|mut unit| {
+                    let quality_score = f32::from(scorer(&unit).score()) / 100.0;
+                    if unit.metadata.quality_score == Some(quality_score) {
+                        return None;
+                    }
+                    unit.metadata.quality_score = Some(quality_score);
+                    unit.updated_at = Utc::now();
+                    Some(unit)
+                })
+                .collect();
+            if !changed.is_empty() {
+                self.storage.upsert_translation_units_transaction(&changed).await
+                    .map_err(|e| TranslationMemoryError::DatabaseError(
+                        format!("Failed to rescore translation units: {}", e)
+                    ))?;
+                updated += changed.len();
+                language_pairs.extend(changed.iter().map(|u| LanguagePair {
+                    source: u.source_language.clone(),
+                    target: u.target_language.clone(),
+                }));
+            }
+            
+            if exhausted {
+                break;
+            }
+        }
+        
+        for pair in &language_pairs {
+            self.invalidate_cache_for_language_pair(pair).await;
+        }
+        log::info!("Rescored {} translation units", updated);
+        Ok(updated)
+    }
+    
+    /// Find source texts translated differently across the project
+    /// 
+    /// Units are grouped by source text; groups whose targets differ are
+    /// returned with each variant and the units using it, in the order the
+    /// units were stored.
+    pub async fn find_inconsistencies(
+        &self,
+        language_pair: &LanguagePair,
+        options: &ConsistencyCheckOptions,
+    ) -> Result<Vec<InconsistencyGroup>> {
+        let units = self.storage
+            .get_translation_units_by_project(self.project_id, Some(language_pair), None, None)
+            .await
+            .map_err(|e| TranslationMemoryError::DatabaseError(
+                format!("Failed to load translation units: {}", e)
+            ))?;
+        
+        let key = |text: &str| {
+            if options.normalize {
+                normalize_for_consistency(text)
+            } else {
+                text.to_string()
+            }
+        };
+        
+        // Groups in first-seen order, alongside the comparison key of each variant
+        let mut groups: Vec<(InconsistencyGroup, Vec<String>)> = Vec::new();
+        let mut group_by_source: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        for unit in units {
+            let location = UnitLocation {
+                unit_id: unit.id,
+                chapter_id: unit.chapter_id,
+                chunk_id: unit.chunk_id,
+                context: unit.context.clone(),
+            };
+            
+            let index = *group_by_source.entry(key(&unit.source_text)).or_insert_with(|| {
+                groups.push((InconsistencyGroup {
+                    source_text: unit.source_text.clone(),
+                    language_pair: language_pair.clone(),
+                    variants: Vec::new(),
+                }, Vec::new()));
+                groups.len() - 1
+            });
+            
+            let (group, target_keys) = &mut groups[index];
+            let target_key = key(&unit.target_text);
+            match target_keys.iter().position(|k| *k == target_key) {
+                Some(variant) => group.variants[variant].locations.push(location),
+                None => {
+                    target_keys.push(target_key);
+                    group.variants.push(TranslationVariant {
+                        target_text: unit.target_text,
+                        locations: vec![location],
+                    });
+                }
+            }
+        }
+        
+        Ok(groups
+            .into_iter()
+            .map(|(group, _)| group)
+            .filter(|group| group.variants.len() > 1)
+            .collect())
+    }
+    
     /// Get cache statistics for monitoring (legacy compatibility)
     pub async fn get_cache_stats(&self) -> (usize, usize, Option<DateTime<Utc>>) {
         let last_updated = *self.cache.last_updated.read().await;
@@ -565,7 +1036,7 @@ impl TranslationMemoryService {
         language_pair: &LanguagePair,
     ) -> Result<Vec<TranslationMatch>> {
         // Use database manager with connection pooling (THREAD SAFETY FIX)
-        self.duckdb_manager
+        self.storage
             .search_exact_matches(source_text, language_pair)
             .await
             .map_err(|e| {
@@ -582,7 +1053,7 @@ impl TranslationMemoryService {
         threshold: f32,
     ) -> Result<Vec<TranslationMatch>> {
         // Use database manager with connection pooling (THREAD SAFETY FIX)
-        self.duckdb_manager
+        self.storage
             .search_fuzzy_matches(source_text, language_pair, threshold)
             .await
             .map_err(|e| {
@@ -599,7 +1070,7 @@ impl TranslationMemoryService {
         threshold: f32,
     ) -> Result<Vec<TranslationMatch>> {
         // Use database manager with connection pooling (THREAD SAFETY FIX)
-        self.duckdb_manager
+        self.storage
             .search_ngram_matches(source_text, language_pair, threshold)
             .await
             .map_err(|e| {
@@ -613,31 +1084,30 @@ impl TranslationMemoryService {
     /// 
     /// Returns: (cache_entries, hit_count, miss_count, hit_ratio, last_updated)
     pub async fn get_detailed_cache_stats(&self) -> (usize, u64, u64, f64, Option<DateTime<Utc>>) {
-        let entries = self.cache.translation_units.len() + self.cache.chunks.len();
-        let hits = self.cache.hit_count.load(std::sync::atomic::Ordering::Relaxed);
-        let misses = self.cache.miss_count.load(std::sync::atomic::Ordering::Relaxed);
-        let hit_ratio = if hits + misses > 0 {
-            hits as f64 / (hits + misses) as f64
-        } else {
-            0.0
-        };
+        let stats = self.cache.translation_units.stats();
+        let entries = stats.entries + self.cache.chunks.len();
         let last_updated = *self.cache.last_updated.read().await;
         
-        (entries, hits, misses, hit_ratio, last_updated)
+        (entries, stats.hits, stats.misses, stats.hit_ratio(), last_updated)
+    }
+
+    /// Hit, miss, and eviction counters of the search result cache
+    pub fn lookup_cache_stats(&self) -> LookupCacheStats {
+        self.cache.translation_units.stats()
     }
     
-    /// Get database statistics
-    pub async fn get_database_stats(&self) -> Result<crate::storage::duckdb_manager::DatabaseStats> {
-        self.duckdb_manager.get_database_stats().await
+    /// Get storage statistics
+    pub async fn get_storage_stats(&self) -> Result<TranslationMemoryStorageStats> {
+        self.storage.get_storage_stats().await
             .map_err(|e| TranslationMemoryError::DatabaseError(
-                format!("Failed to get database stats: {}", e)
+                format!("Failed to get storage stats: {}", e)
             ))
     }
     
     /// Optimize database performance
     pub async fn optimize_database(&self) -> Result<()> {
         log::info!("Optimizing database performance");
-        self.duckdb_manager.optimize_database().await
+        self.storage.optimize_storage().await
             .map_err(|e| TranslationMemoryError::DatabaseError(
                 format!("Failed to optimize database: {}", e)
             ))
@@ -645,31 +1115,13 @@ impl TranslationMemoryService {
     
     /// Get connection pool statistics
     pub async fn get_connection_pool_stats(&self) -> (usize, usize) {
-        self.duckdb_manager.get_connection_pool_stats().await
-    }
-    
-    fn create_cache_key(&self, text: &str, language_pair: &LanguagePair) -> String {
-        format!("{}:{}:{}", text, language_pair.source.code(), language_pair.target.code())
-    }
-    
-    /// Invalidate specific translation cache entries (THREAD SAFETY: lock-free)
-    async fn invalidate_translation_cache(&self, source_text: &str, language_pair: &LanguagePair) {
-        let cache_key = self.create_cache_key(source_text, language_pair);
-        self.cache.translation_units.remove(&cache_key);
-        
-        // Update last_updated timestamp
-        {
-            let mut last_updated = self.cache.last_updated.write().await;
-            *last_updated = Some(Utc::now());
-        }
+        self.storage.connection_pool_stats().await
     }
     
     /// Invalidate all cache entries for a language pair
     async fn invalidate_cache_for_language_pair(&self, language_pair: &LanguagePair) {
         // Remove all entries that match this language pair
-        self.cache.translation_units.retain(|key, _| {
-            !key.contains(&format!("{}:{}", language_pair.source, language_pair.target))
-        });
+        self.cache.translation_units.invalidate_language_pair(language_pair);
         
         self.cache.language_pairs.remove(language_pair);
         
@@ -694,6 +1146,44 @@ impl TranslationMemoryService {
     }
 }
 
+/// Scan phases of a search, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchPhase {
+    Exact,
+    Fuzzy,
+    NGram,
+    Done,
+}
+
+impl SearchPhase {
+    fn next(self) -> Self {
+        match self {
+            SearchPhase::Exact => SearchPhase::Fuzzy,
+            SearchPhase::Fuzzy => SearchPhase::NGram,
+            SearchPhase::NGram | SearchPhase::Done => SearchPhase::Done,
+        }
+    }
+}
+
+/// Progress of [`TranslationMemoryService::search_stream`]
+struct SearchStreamState {
+    language_pair: LanguagePair,
+    threshold: f32,
+    phase: SearchPhase,
+    pending: std::vec::IntoIter<TranslationMatch>,
+    seen: HashSet<Uuid>,
+}
+
+/// Comparison key for consistency checks: surrounding whitespace and
+/// trailing sentence punctuation are ignored
+fn normalize_for_consistency(text: &str) -> String {
+    text.trim()
+        .trim_end_matches(|c: char| {
+            c.is_whitespace() || matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | '…' | '。' | '！' | '？')
+        })
+        .to_string()
+}
+
 /// Calculate similarity between two strings using Jaccard similarity
 pub fn calculate_similarity(text1: &str, text2: &str) -> f32 {
     let words1: std::collections::HashSet<&str> = text1.split_whitespace().collect();
@@ -740,4 +1230,313 @@ fn extract_ngrams(text: &str, n: usize) -> std::collections::HashSet<String> {
     }
     
     ngrams
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TranslationUnitBuilder;
+    use tempfile::tempdir;
+    
+    fn unit(source: &str, target: &str) -> TranslationUnit {
+        TranslationUnitBuilder::new()
+            .project_id(Uuid::new_v4())
+            .chapter_id(Uuid::new_v4())
+            .chunk_id(Uuid::new_v4())
+            .source_language("en")
+            .source_text(source)
+            .target_language("de")
+            .target_text(target)
+            .build()
+            .unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_find_inconsistencies_groups_divergent_targets() {
+        let temp_dir = tempdir().unwrap();
+        let service = TranslationMemoryService::open(Uuid::new_v4(), temp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+        
+        let first = unit("Press the reset button.", "Drücken Sie die Reset-Taste.");
+        let second = unit("Press the reset button", "Betätigen Sie den Reset-Knopf.");
+        let third = unit("Close the lid.", "Schließen Sie den Deckel.");
+        service.add_translation_units_batch(vec![first.clone(), second.clone(), third]).await.unwrap();
+        
+        let pair = LanguagePair::new(Language::English, Language::German);
+        let groups = service
+            .find_inconsistencies(&pair, &ConsistencyCheckOptions::default())
+            .await
+            .unwrap();
+        
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].source_text, "Press the reset button.");
+        assert_eq!(groups[0].variants.len(), 2);
+        assert_eq!(groups[0].variants[0].locations[0].unit_id, first.id);
+        assert_eq!(groups[0].variants[1].locations[0].chapter_id, second.chapter_id);
+        
+        // Without normalization the trailing period makes the sources distinct
+        let exact = service
+            .find_inconsistencies(&pair, &ConsistencyCheckOptions { normalize: false })
+            .await
+            .unwrap();
+        assert!(exact.is_empty());
+    }
+    
+    async fn service(temp_dir: &std::path::Path) -> TranslationMemoryService {
+        TranslationMemoryService::open(Uuid::new_v4(), temp_dir.to_path_buf()).await.unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_rescore_updates_quality_within_language_pair() {
+        let temp_dir = tempdir().unwrap();
+        let service = service(temp_dir.path()).await;
+        
+        let mut french = unit("Close the lid.", "Fermez le couvercle.");
+        french.target_language = Language::French;
+        let units = vec![
+            unit("Close the lid.", "Schließen Sie den Deckel."),
+            unit("Press the reset button.", "Drücken Sie die Reset-Taste."),
+            french,
+        ];
+        service.add_translation_units_batch(units).await.unwrap();
+        
+        assert_eq!(service.rescore(|_| Quality::Draft, None).await.unwrap(), 3);
+        
+        // Formal German is what the new model rates highly
+        let scorer = |u: &TranslationUnit| if u.target_text.contains(" Sie ") { Quality::Published } else { Quality::Draft };
+        let german = LanguagePair::new(Language::English, Language::German);
+        assert_eq!(service.rescore(scorer, Some(german.clone())).await.unwrap(), 2);
+        // Nothing changes the second time
+        assert_eq!(service.rescore(scorer, Some(german)).await.unwrap(), 0);
+        
+        let scores: Vec<(Language, Option<f32>)> = service
+            .get_translation_units()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|u| (u.target_language, u.metadata.quality_score))
+            .collect();
+        assert_eq!(scores, [
+            (Language::German, Some(1.0)),
+            (Language::German, Some(1.0)),
+            (Language::French, Some(0.25)),
+        ]);
+    }
+    
+    #[tokio::test]
+    async fn test_merge_prefer_newer_takes_newer_translation() {
+        let temp_dir = tempdir().unwrap();
+        let target = service(temp_dir.path()).await;
+        let source = service(temp_dir.path()).await;
+        
+        let mut old = unit("Close the lid.", "Deckel zu.");
+        old.updated_at = Utc::now() - chrono::Duration::days(30);
+        old.created_at = old.updated_at;
+        let kept = unit("Open the lid.", "Öffnen Sie den Deckel.");
+        target.add_translation_units_batch(vec![old.clone(), kept.clone()]).await.unwrap();
+        source.add_translation_units_batch(vec![
+            unit("Close the lid.", "Schließen Sie den Deckel."),
+            kept.clone(),
+            unit("Lock the lid.", "Verriegeln Sie den Deckel."),
+        ]).await.unwrap();
+        
+        let report = target.merge_from(&source, MergeStrategy::PreferNewer).await.unwrap();
+        assert_eq!(report, MergeReport { inserted: 1, updated: 1, skipped: 1, conflicted: 1 });
+        
+        let units = target.get_translation_units().await.unwrap();
+        assert_eq!(units.len(), 3);
+        let merged = units.iter().find(|u| u.id == old.id).unwrap();
+        assert_eq!(merged.target_text, "Schließen Sie den Deckel.");
+    }
+    
+    #[tokio::test]
+    async fn test_merge_keep_both_stores_both_translations() {
+        let temp_dir = tempdir().unwrap();
+        let target = service(temp_dir.path()).await;
+        let source = service(temp_dir.path()).await;
+        
+        target.add_translation_unit(unit("Close the lid.", "Deckel zu.")).await.unwrap();
+        source.add_translation_unit(unit("Close the lid.", "Schließen Sie den Deckel.")).await.unwrap();
+        
+        let report = target.merge_from(&source, MergeStrategy::KeepBoth).await.unwrap();
+        assert_eq!(report, MergeReport { inserted: 1, updated: 0, skipped: 0, conflicted: 1 });
+        
+        let mut targets: Vec<String> = target
+            .get_translation_units()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|u| u.target_text)
+            .collect();
+        targets.sort();
+        assert_eq!(targets, vec!["Deckel zu.", "Schließen Sie den Deckel."]);
+    }
+    
+    /// Service behaviour every storage backend has to support, leaving one
+    /// unit stored
+    async fn check_backend(storage: Arc<dyn TmStorage>) -> TranslationMemoryService {
+        let service = TranslationMemoryService::new(Uuid::new_v4(), storage).await.unwrap();
+        let valve = unit("Open the valve.", "Öffnen Sie das Ventil.");
+        service.add_translation_units_batch(vec![
+            valve.clone(),
+            unit("Close the lid.", "Schließen Sie den Deckel."),
+        ]).await.unwrap();
+        
+        let found = service.search("Open the valve.", Language::English, Language::German, 1.0).await.unwrap();
+        assert_eq!(found.iter().map(|u| u.id).collect::<Vec<_>>(), vec![valve.id]);
+        assert!(service.search("Open the valve.", Language::English, Language::French, 1.0).await.unwrap().is_empty());
+        
+        assert!(service.delete_translation_unit(valve.id).await.unwrap());
+        assert!(!service.delete_translation_unit(valve.id).await.unwrap());
+        let remaining = service.get_translation_units().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].source_text, "Close the lid.");
+        service
+    }
+    
+    #[tokio::test]
+    async fn test_in_memory_backend() {
+        let service = check_backend(Arc::new(crate::storage::InMemoryStorage::new())).await;
+        assert_eq!(service.get_storage_stats().await.unwrap().total_translation_units, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_search_pages_have_no_overlaps_or_gaps() {
+        let storage = Arc::new(crate::storage::InMemoryStorage::new());
+        let service = TranslationMemoryService::new(Uuid::new_v4(), storage).await.unwrap();
+        // Identical sources score the same, so only the tiebreak orders them
+        let units: Vec<TranslationUnit> = (0..25).map(|i| unit("Open the valve.", &format!("Ventil {i} öffnen."))).collect();
+        let mut expected: Vec<Uuid> = units.iter().map(|u| u.id).collect();
+        expected.sort();
+        service.add_translation_units_batch(units).await.unwrap();
+        
+        let mut seen = Vec::new();
+        let mut query = SearchQuery::new().limit(10);
+        loop {
+            let page = service
+                .search_paged("Open the valve.", Language::English, Language::German, 0.5, &query)
+                .await
+                .unwrap();
+            assert_eq!(page.total_available, 25);
+            seen.extend(page.items.iter().map(|u| u.id));
+            if !page.has_more() {
+                break;
+            }
+            query = query.next_page();
+        }
+        
+        assert_eq!(query.offset, 20);
+        assert_eq!(seen, expected);
+        // The unpaged search keeps its cap
+        assert_eq!(service.search("Open the valve.", Language::English, Language::German, 0.5).await.unwrap().len(), 20);
+    }
+    
+    #[cfg(feature = "duckdb-storage")]
+    #[tokio::test]
+    async fn test_duckdb_backend() {
+        let temp_dir = tempdir().unwrap();
+        let manager = DuckDBManager::new(&temp_dir.path().join("tm.db"), None).await.unwrap();
+        let service = check_backend(manager).await;
+        // The mock database reports fixed statistics
+        assert!(service.get_storage_stats().await.is_ok());
+    }
+    
+    #[test]
+    fn test_normalization_ignores_trailing_punctuation() {
+        assert_eq!(normalize_for_consistency("  Done!  "), "Done");
+        assert_eq!(normalize_for_consistency("Wait..."), "Wait");
+        assert_eq!(normalize_for_consistency("(see below)"), "(see below)");
+    }
+    
+    #[tokio::test]
+    async fn test_search_stream_yields_same_matches_as_search() {
+        use futures::StreamExt;
+        
+        let temp_dir = tempdir().unwrap();
+        let service = TranslationMemoryService::open(Uuid::new_v4(), temp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+        service.add_translation_units_batch(vec![
+            unit("Open the valve.", "Öffnen Sie das Ventil."),
+            unit("Open the valve.", "Ventil öffnen."),
+            unit("Close the lid.", "Schließen Sie den Deckel."),
+        ]).await.unwrap();
+        
+        let streamed: Vec<TranslationMatch> = service
+            .search_stream("Open the valve.", Language::English, Language::German, 0.5)
+            .collect()
+            .await;
+        let searched = service
+            .search("Open the valve.", Language::English, Language::German, 0.5)
+            .await
+            .unwrap();
+        
+        let mut streamed_ids: Vec<Uuid> = streamed.iter().map(|m| m.id).collect();
+        let mut searched_ids: Vec<Uuid> = searched.iter().map(|u| u.id).collect();
+        streamed_ids.sort();
+        searched_ids.sort();
+        assert_eq!(streamed_ids.len(), 2);
+        assert_eq!(streamed_ids, searched_ids);
+    }
+    
+    #[tokio::test]
+    async fn test_repeated_query_hits_lookup_cache() {
+        let temp_dir = tempdir().unwrap();
+        let service = TranslationMemoryService::open(Uuid::new_v4(), temp_dir.path().to_path_buf())
+            .await
+            .unwrap()
+            .with_cache_config(LookupCacheConfig { capacity: 8, ..LookupCacheConfig::default() });
+        let pair = LanguagePair::new(Language::English, Language::German);
+        
+        service.search_similar_translations("Open the valve", pair.clone(), None).await.unwrap();
+        service.search_similar_translations("  Open   the valve ", pair, None).await.unwrap();
+        
+        let stats = service.lookup_cache_stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.capacity, 8);
+    }
+    
+    #[tokio::test]
+    async fn test_inserting_unit_invalidates_its_language_pair() {
+        let temp_dir = tempdir().unwrap();
+        let service = TranslationMemoryService::open(Uuid::new_v4(), temp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+        let en_de = LanguagePair::new(Language::English, Language::German);
+        let en_fr = LanguagePair::new(Language::English, Language::French);
+        
+        service.search_similar_translations("Open the valve", en_de, None).await.unwrap();
+        service.search_similar_translations("Open the valve", en_fr, None).await.unwrap();
+        service.add_translation_unit(unit("Open the valve slowly.", "Öffnen Sie das Ventil langsam.")).await.unwrap();
+        
+        let stats = service.lookup_cache_stats();
+        assert_eq!(stats.invalidations, 1);
+        assert_eq!(stats.entries, 1);
+    }
+    
+    #[test]
+    fn test_lookup_cache_evicts_least_recently_used_and_expires() {
+        use crate::services::lookup_cache::LookupCache;
+        use std::time::Duration;
+        
+        let pair = LanguagePair::new(Language::English, Language::German);
+        let cache = LookupCache::new(LookupCacheConfig { capacity: 2, ttl: Duration::from_secs(60) });
+        let key = |query: &str| LookupKey::new(query, &pair, 0.3);
+        cache.insert(key("a"), Vec::new());
+        cache.insert(key("b"), Vec::new());
+        assert!(cache.get(&key("a")).is_some());
+        cache.insert(key("c"), Vec::new());
+        
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("a")).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+        
+        let expiring = LookupCache::new(LookupCacheConfig { capacity: 2, ttl: Duration::ZERO });
+        expiring.insert(key("a"), Vec::new());
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(expiring.get(&key("a")).is_none());
+        assert_eq!(expiring.stats().expirations, 1);
+    }
+}
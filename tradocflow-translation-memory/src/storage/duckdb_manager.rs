@@ -1,14 +1,15 @@
 //! DuckDB database manager with connection pooling and async operations
 
 use crate::error::{Result, TranslationMemoryError};
-use crate::models::{TranslationUnit, Terminology, Language, Chunk};
+use crate::models::{TranslationUnit, Terminology, TermStatus, Language, Chunk};
 use crate::services::translation_memory::{TranslationMatch, LanguagePair, TranslationMatchMetadata, ChunkLinkType};
 use crate::storage::traits::{
-    TranslationMemoryStorage, TerminologyStorage, ChunkStorage, UnifiedStorageProvider,
+    TranslationMemoryStorage, TmStorage, TerminologyStorage, ChunkStorage, UnifiedStorageProvider,
     TranslationMemoryStorageStats, TerminologyStorageStats, ChunkStorageStats, 
     ComprehensiveStorageStats
 };
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::path::{Path, PathBuf};
 use tokio::sync::RwLock;
@@ -68,7 +69,6 @@ impl ConnectionPool {
         }
     }
     
-    #[allow(dead_code)]
     fn return_connection(&mut self, connection: MockConnection) {
         if self.connections.len() < self.max_connections {
             self.connections.push(connection);
@@ -76,12 +76,105 @@ impl ConnectionPool {
     }
 }
 
+/// A connection checked out of a [`ConnectionPool`], returned to it on drop
+#[derive(Debug)]
+struct PooledConnection {
+    connection: Option<MockConnection>,
+    pool: Arc<std::sync::Mutex<ConnectionPool>>,
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            let mut pool = self.pool.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            pool.return_connection(connection);
+        }
+    }
+}
+
+/// Exact matches for a language pair: source language, target language, source text
+const EXACT_MATCH_SQL: &str = "SELECT * FROM translation_units \
+    WHERE source_language = ? AND target_language = ? AND trim(source_text) = ?";
+/// Similar sources for a language pair: source language, target language, source text, threshold
+const FUZZY_MATCH_SQL: &str = "SELECT *, jaro_winkler_similarity(source_text, ?3) AS similarity FROM translation_units \
+    WHERE source_language = ?1 AND target_language = ?2 AND similarity >= ?4 ORDER BY similarity DESC";
+/// Shared trigrams for a language pair: source language, target language, source text, threshold
+const NGRAM_MATCH_SQL: &str = "SELECT *, jaccard(source_text, ?3) AS similarity FROM translation_units \
+    WHERE source_language = ?1 AND target_language = ?2 AND similarity >= ?4 ORDER BY similarity DESC";
+
+/// Mock prepared statement (placeholder until DuckDB integration is complete)
+#[derive(Debug)]
+struct MockStatement {
+    sql: &'static str,
+    parameter_count: usize,
+}
+
+impl MockStatement {
+    /// Parse `sql`, counting its `?` and `?N` placeholders
+    fn prepare(sql: &'static str) -> Result<Self> {
+        let mut parameter_count = 0;
+        let mut chars = sql.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '?' {
+                continue;
+            }
+            let mut number = String::new();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                number.push(digit);
+            }
+            parameter_count = match number.parse::<usize>() {
+                Ok(n) => parameter_count.max(n),
+                Err(_) => parameter_count + 1,
+            };
+        }
+        Ok(Self { sql, parameter_count })
+    }
+    
+    /// Bind `params` for one execution
+    fn bind(&self, params: &[&dyn std::fmt::Display]) -> Result<()> {
+        if params.len() != self.parameter_count {
+            return Err(TranslationMemoryError::DatabaseError(format!(
+                "Statement takes {} parameters, got {}: {}",
+                self.parameter_count,
+                params.len(),
+                self.sql
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Prepared statements keyed by SQL template, shared by every connection
+#[derive(Debug, Default)]
+struct StatementCache {
+    statements: HashMap<&'static str, Arc<MockStatement>>,
+    hits: u64,
+    prepared: u64,
+    evictions: u64,
+}
+
+/// Prepared statement reuse, for monitoring
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StatementCacheStats {
+    pub cached_statements: usize,
+    /// Executions that reused a prepared statement
+    pub hits: u64,
+    /// Statements parsed and planned
+    pub prepared: u64,
+    /// Times the cache was emptied because the schema changed
+    pub evictions: u64,
+}
+
 /// Manager for DuckDB database operations with connection pooling
 #[derive(Debug)]
 pub struct DuckDBManager {
     db_path: PathBuf,
-    connection_pool: Arc<RwLock<ConnectionPool>>,
+    connection_pool: Arc<std::sync::Mutex<ConnectionPool>>,
     schema_initialized: Arc<RwLock<bool>>,
+    /// Statements planned against the current schema
+    statement_cache: Arc<RwLock<StatementCache>>,
+    /// Translation unit rows kept in memory until DuckDB integration is complete
+    translation_units: Arc<RwLock<Vec<TranslationUnit>>>,
 }
 
 impl DuckDBManager {
@@ -90,8 +183,10 @@ impl DuckDBManager {
         let max_conn = max_connections.unwrap_or(10);
         let manager = Arc::new(Self {
             db_path: db_path.to_path_buf(),
-            connection_pool: Arc::new(RwLock::new(ConnectionPool::new(max_conn))),
+            connection_pool: Arc::new(std::sync::Mutex::new(ConnectionPool::new(max_conn))),
             schema_initialized: Arc::new(RwLock::new(false)),
+            statement_cache: Arc::new(RwLock::new(StatementCache::default())),
+            translation_units: Arc::new(RwLock::new(Vec::new())),
         });
         
         Ok(manager)
@@ -115,6 +210,7 @@ impl DuckDBManager {
         // Initialize both translation memory and terminology schemas
         self.initialize_translation_memory_schema().await?;
         self.initialize_terminology_schema().await?;
+        self.evict_prepared_statements().await;
         
         // Mark as initialized
         {
@@ -217,8 +313,7 @@ impl DuckDBManager {
         
         log::debug!("Inserting translation unit: {}", unit.id);
         
-        // Mock insertion - in real implementation, this would execute SQL INSERT
-        tokio::time::sleep(tokio::time::Duration::from_millis(1)).await; // Simulate DB operation
+        self.store_translation_units(std::slice::from_ref(unit)).await;
         
         Ok(())
     }
@@ -229,8 +324,24 @@ impl DuckDBManager {
         
         log::debug!("Batch inserting {} translation units", units.len());
         
-        // Mock batch insertion
-        tokio::time::sleep(tokio::time::Duration::from_millis(units.len() as u64)).await;
+        self.store_translation_units(units).await;
+        
+        Ok(units.len())
+    }
+    
+    /// Insert or replace units in a single transaction
+    /// 
+    /// Every unit is validated before anything is written, so a failure
+    /// leaves the stored units untouched.
+    pub async fn upsert_translation_units_transaction(&self, units: &[TranslationUnit]) -> Result<usize> {
+        let _connection = self.get_connection().await?;
+        
+        log::debug!("Upserting {} translation units in one transaction", units.len());
+        
+        for unit in units {
+            unit.validate()?;
+        }
+        self.store_translation_units(units).await;
         
         Ok(units.len())
     }
@@ -241,8 +352,7 @@ impl DuckDBManager {
         
         log::debug!("Updating translation unit: {}", unit.id);
         
-        // Mock update
-        tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+        self.store_translation_units(std::slice::from_ref(unit)).await;
         
         Ok(())
     }
@@ -253,10 +363,22 @@ impl DuckDBManager {
         
         log::debug!("Deleting translation unit: {}", id);
         
-        // Mock deletion - return true to indicate successful deletion
-        tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+        let mut stored = self.translation_units.write().await;
+        let before = stored.len();
+        stored.retain(|u| u.id != id);
         
-        Ok(true)
+        Ok(stored.len() < before)
+    }
+    
+    /// Insert units, replacing any stored unit with the same ID
+    async fn store_translation_units(&self, units: &[TranslationUnit]) {
+        let mut stored = self.translation_units.write().await;
+        for unit in units {
+            match stored.iter_mut().find(|u| u.id == unit.id) {
+                Some(existing) => *existing = unit.clone(),
+                None => stored.push(unit.clone()),
+            }
+        }
     }
     
     /// Search for exact matches
@@ -269,11 +391,24 @@ impl DuckDBManager {
         
         log::debug!("Searching exact matches for: '{}' ({} -> {})", source_text, language_pair.source.code(), language_pair.target.code());
         
-        // Mock search results
+        let query = source_text.trim();
+        let statement = self.prepare_cached(EXACT_MATCH_SQL).await?;
+        statement.bind(&[&language_pair.source.code(), &language_pair.target.code(), &query])?;
+        
+        // Mock search latency
         tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
         
-        // Return empty results for now - in real implementation, this would query the database
-        Ok(Vec::new())
+        // Served from the in-memory units until the database is wired up
+        let units = self.translation_units.read().await;
+        Ok(units
+            .iter()
+            .filter(|unit| {
+                unit.source_language == language_pair.source
+                    && unit.target_language == language_pair.target
+                    && unit.source_text.trim() == query
+            })
+            .map(|unit| TranslationMatch::from_unit(unit, 1.0))
+            .collect())
     }
     
     /// Search for fuzzy matches
@@ -288,6 +423,9 @@ impl DuckDBManager {
         log::debug!("Searching fuzzy matches for: '{}' (threshold: {}, {} -> {})", 
                    source_text, threshold, language_pair.source.code(), language_pair.target.code());
         
+        let statement = self.prepare_cached(FUZZY_MATCH_SQL).await?;
+        statement.bind(&[&language_pair.source.code(), &language_pair.target.code(), &source_text, &threshold])?;
+        
         // Mock search
         tokio::time::sleep(tokio::time::Duration::from_millis(15)).await;
         
@@ -306,6 +444,9 @@ impl DuckDBManager {
         log::debug!("Searching n-gram matches for: '{}' (threshold: {}, {} -> {})", 
                    source_text, threshold, language_pair.source.code(), language_pair.target.code());
         
+        let statement = self.prepare_cached(NGRAM_MATCH_SQL).await?;
+        statement.bind(&[&language_pair.source.code(), &language_pair.target.code(), &source_text, &threshold])?;
+        
         // Mock search
         tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
         
@@ -366,6 +507,9 @@ impl DuckDBManager {
                 term: "API".to_string(),
                 definition: Some("Application Programming Interface".to_string()),
                 do_not_translate: true,
+                domain: None,
+                language: None,
+                status: TermStatus::Approved,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             },
@@ -374,6 +518,9 @@ impl DuckDBManager {
                 term: "JSON".to_string(),
                 definition: Some("JavaScript Object Notation".to_string()),
                 do_not_translate: true,
+                domain: None,
+                language: None,
+                status: TermStatus::Approved,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             },
@@ -442,6 +589,12 @@ impl DuckDBManager {
         
         log::debug!("Executing custom query: {}", query);
         
+        // Plans of cached statements may refer to what this changes
+        let keyword = query.split_whitespace().next().unwrap_or_default().to_ascii_uppercase();
+        if matches!(keyword.as_str(), "CREATE" | "ALTER" | "DROP") {
+            self.evict_prepared_statements().await;
+        }
+        
         // Mock query execution
         tokio::time::sleep(tokio::time::Duration::from_millis(5)).await;
         
@@ -481,21 +634,55 @@ impl DuckDBManager {
     
     /// Get connection pool statistics
     pub async fn get_connection_pool_stats(&self) -> (usize, usize) {
-        let pool = self.connection_pool.read().await;
+        let pool = self.connection_pool.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
         (pool.connections.len(), pool.max_connections)
     }
     
+    /// Get prepared statement cache statistics
+    pub async fn get_statement_cache_stats(&self) -> StatementCacheStats {
+        let cache = self.statement_cache.read().await;
+        StatementCacheStats {
+            cached_statements: cache.statements.len(),
+            hits: cache.hits,
+            prepared: cache.prepared,
+            evictions: cache.evictions,
+        }
+    }
+    
     // Private helper methods
     
-    async fn get_connection(&self) -> Result<MockConnection> {
-        let mut pool = self.connection_pool.write().await;
-        pool.get_connection()
+    /// The prepared statement for `sql`, parsing and planning it only the
+    /// first time each template is used against the current schema
+    async fn prepare_cached(&self, sql: &'static str) -> Result<Arc<MockStatement>> {
+        let mut cache = self.statement_cache.write().await;
+        if let Some(statement) = cache.statements.get(sql).cloned() {
+            cache.hits += 1;
+            return Ok(statement);
+        }
+        
+        let statement = Arc::new(MockStatement::prepare(sql)?);
+        cache.prepared += 1;
+        cache.statements.insert(sql, statement.clone());
+        Ok(statement)
     }
     
-    #[allow(dead_code)]
-    async fn return_connection(&self, connection: MockConnection) {
-        let mut pool = self.connection_pool.write().await;
-        pool.return_connection(connection);
+    /// Drop every prepared statement, whose plans a schema change may have
+    /// invalidated
+    async fn evict_prepared_statements(&self) {
+        let mut cache = self.statement_cache.write().await;
+        if !cache.statements.is_empty() {
+            cache.statements.clear();
+            cache.evictions += 1;
+        }
+    }
+    
+    async fn get_connection(&self) -> Result<PooledConnection> {
+        let connection = self
+            .connection_pool
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get_connection()?;
+        Ok(PooledConnection { connection: Some(connection), pool: self.connection_pool.clone() })
     }
 }
 
@@ -600,11 +787,21 @@ impl TranslationMemoryStorage for DuckDBManager {
             project_id, language_pair, limit, offset
         );
         
-        // Mock implementation - would use SQL query with proper filtering in real implementation
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        let stored = self.translation_units.read().await;
+        let units = stored
+            .iter()
+            .filter(|u| u.project_id == project_id)
+            .filter(|u| {
+                language_pair.is_none_or(|pair| {
+                    u.source_language == pair.source && u.target_language == pair.target
+                })
+            })
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
         
-        // Return empty for mock
-        Ok(Vec::new())
+        Ok(units)
     }
     
     async fn count_translation_units(&self, project_id: Uuid) -> Result<u64> {
@@ -612,11 +809,8 @@ impl TranslationMemoryStorage for DuckDBManager {
         
         log::debug!("Counting translation units for project: {}", project_id);
         
-        // Mock implementation
-        tokio::time::sleep(tokio::time::Duration::from_millis(5)).await;
-        
-        // Return mock count
-        Ok(100)
+        let stored = self.translation_units.read().await;
+        Ok(stored.iter().filter(|u| u.project_id == project_id).count() as u64)
     }
     
     async fn optimize_storage(&self) -> Result<()> {
@@ -637,6 +831,17 @@ impl TranslationMemoryStorage for DuckDBManager {
     }
 }
 
+#[async_trait]
+impl TmStorage for DuckDBManager {
+    async fn upsert_translation_units_transaction(&self, units: &[TranslationUnit]) -> Result<usize> {
+        DuckDBManager::upsert_translation_units_transaction(self, units).await
+    }
+    
+    async fn connection_pool_stats(&self) -> (usize, usize) {
+        self.get_connection_pool_stats().await
+    }
+}
+
 #[async_trait]
 impl TerminologyStorage for DuckDBManager {
     async fn initialize_schema(&self) -> Result<()> {
@@ -950,6 +1155,18 @@ mod tests {
         assert_eq!(max, 5);
     }
     
+    #[tokio::test]
+    async fn test_connections_return_to_the_pool() {
+        let temp_dir = tempdir().unwrap();
+        let manager = DuckDBManager::new(&temp_dir.path().join("test.db"), Some(2)).await.unwrap();
+        
+        // Far more lookups than the pool holds connections
+        for _ in 0..20 {
+            manager.get_translation_units_by_project(Uuid::new_v4(), None, None, None).await.unwrap();
+        }
+        assert_eq!(manager.get_connection_pool_stats().await, (2, 2));
+    }
+    
     #[tokio::test]
     async fn test_schema_initialization() {
         let temp_dir = tempdir().unwrap();
@@ -1024,6 +1241,9 @@ mod tests {
             term: "REST".to_string(),
             definition: Some("Representational State Transfer".to_string()),
             do_not_translate: false,
+            domain: None,
+            language: None,
+            status: TermStatus::Approved,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -1066,4 +1286,40 @@ mod tests {
         let result = manager.optimize_database().await;
         assert!(result.is_ok());
     }
+    
+    #[tokio::test]
+    async fn test_repeated_search_prepares_statement_once() {
+        let temp_dir = tempdir().unwrap();
+        let manager = DuckDBManager::new(&temp_dir.path().join("test.db"), None).await.unwrap();
+        manager.initialize_schema().await.unwrap();
+        
+        let unit = crate::models::TranslationUnitBuilder::new()
+            .project_id(Uuid::new_v4())
+            .chapter_id(Uuid::new_v4())
+            .chunk_id(Uuid::new_v4())
+            .source_language("en")
+            .source_text("Ring the bell")
+            .target_language("de")
+            .target_text("Läute die Glocke")
+            .confidence_score(0.9)
+            .build()
+            .unwrap();
+        manager.insert_translation_unit(&unit).await.unwrap();
+        let pair = LanguagePair { source: Language::English, target: Language::German };
+        
+        for _ in 0..50 {
+            let matches = manager.search_exact_matches(" Ring the bell ", &pair).await.unwrap();
+            assert_eq!(matches.len(), 1);
+            assert_eq!(matches[0].target_text, "Läute die Glocke");
+            assert!(manager.search_exact_matches("Toll the bell", &pair).await.unwrap().is_empty());
+        }
+        let stats = manager.get_statement_cache_stats().await;
+        assert_eq!(stats, StatementCacheStats { cached_statements: 1, hits: 99, prepared: 1, evictions: 0 });
+        
+        // Plans made before a schema change are not reused after it
+        manager.execute_query("ALTER TABLE translation_units ADD COLUMN domain VARCHAR").await.unwrap();
+        manager.search_exact_matches("Ring the bell", &pair).await.unwrap();
+        let stats = manager.get_statement_cache_stats().await;
+        assert_eq!((stats.prepared, stats.evictions), (2, 1));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,185 @@
+//! In-memory translation memory backend
+//!
+//! Keeps translation units in a vector for tests and small projects that
+//! don't need a database. Nothing is persisted.
+
+use crate::error::Result;
+use crate::models::TranslationUnit;
+use crate::services::translation_memory::{
+    calculate_ngram_similarity, calculate_similarity, LanguagePair, TranslationMatch,
+};
+use crate::storage::traits::{TmStorage, TranslationMemoryStorage, TranslationMemoryStorageStats};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::HashSet;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Length of the character n-grams compared by n-gram searches
+pub(crate) const NGRAM_SIZE: usize = 3;
+
+/// Units of `language_pair` scoring at least `threshold` by `similarity`
+/// to the query, in storage order
+pub(crate) fn find_matches<'a>(
+    units: impl IntoIterator<Item = &'a TranslationUnit>,
+    language_pair: &LanguagePair,
+    threshold: f32,
+    similarity: impl Fn(&str) -> f32,
+) -> Vec<TranslationMatch> {
+    units
+        .into_iter()
+        .filter(|unit| unit.source_language == language_pair.source && unit.target_language == language_pair.target)
+        .filter_map(|unit| {
+            let score = similarity(&unit.source_text);
+            (score >= threshold).then(|| TranslationMatch::from_unit(unit, score))
+        })
+        .collect()
+}
+
+/// Statistics of `units`, which take no storage of their own
+pub(crate) fn unit_stats<'a>(units: impl IntoIterator<Item = &'a TranslationUnit>) -> TranslationMemoryStorageStats {
+    let mut count = 0u64;
+    let mut confidence = 0.0f32;
+    let mut pairs = HashSet::new();
+    for unit in units {
+        count += 1;
+        confidence += unit.confidence_score;
+        pairs.insert((unit.source_language.clone(), unit.target_language.clone()));
+    }
+    TranslationMemoryStorageStats {
+        total_translation_units: count,
+        unique_language_pairs: pairs.len() as u64,
+        average_confidence_score: if count > 0 { confidence / count as f32 } else { 0.0 },
+        storage_size_bytes: 0,
+        last_updated: Utc::now(),
+        optimization_recommended: false,
+    }
+}
+
+/// Translation memory backend holding every unit in memory
+#[derive(Debug, Default)]
+pub struct InMemoryStorage {
+    units: RwLock<Vec<TranslationUnit>>,
+}
+
+impl InMemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert units, replacing any stored unit with the same ID
+    async fn store(&self, units: &[TranslationUnit]) {
+        let mut stored = self.units.write().await;
+        for unit in units {
+            match stored.iter_mut().find(|u| u.id == unit.id) {
+                Some(existing) => *existing = unit.clone(),
+                None => stored.push(unit.clone()),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationMemoryStorage for InMemoryStorage {
+    async fn initialize_schema(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn insert_translation_unit(&self, unit: &TranslationUnit) -> Result<()> {
+        self.store(std::slice::from_ref(unit)).await;
+        Ok(())
+    }
+
+    async fn insert_translation_units_batch(&self, units: &[TranslationUnit]) -> Result<usize> {
+        self.store(units).await;
+        Ok(units.len())
+    }
+
+    async fn update_translation_unit(&self, unit: &TranslationUnit) -> Result<()> {
+        self.store(std::slice::from_ref(unit)).await;
+        Ok(())
+    }
+
+    async fn delete_translation_unit(&self, id: Uuid) -> Result<bool> {
+        let mut stored = self.units.write().await;
+        let before = stored.len();
+        stored.retain(|u| u.id != id);
+        Ok(stored.len() < before)
+    }
+
+    async fn search_exact_matches(
+        &self,
+        source_text: &str,
+        language_pair: &LanguagePair,
+    ) -> Result<Vec<TranslationMatch>> {
+        let query = source_text.trim();
+        let stored = self.units.read().await;
+        Ok(find_matches(stored.iter(), language_pair, 1.0, |text| if text.trim() == query { 1.0 } else { 0.0 }))
+    }
+
+    async fn search_fuzzy_matches(
+        &self,
+        source_text: &str,
+        language_pair: &LanguagePair,
+        threshold: f32,
+    ) -> Result<Vec<TranslationMatch>> {
+        let stored = self.units.read().await;
+        Ok(find_matches(stored.iter(), language_pair, threshold, |text| calculate_similarity(source_text, text)))
+    }
+
+    async fn search_ngram_matches(
+        &self,
+        source_text: &str,
+        language_pair: &LanguagePair,
+        threshold: f32,
+    ) -> Result<Vec<TranslationMatch>> {
+        let stored = self.units.read().await;
+        Ok(find_matches(stored.iter(), language_pair, threshold, |text| {
+            calculate_ngram_similarity(source_text, text, NGRAM_SIZE)
+        }))
+    }
+
+    async fn get_translation_units_by_project(
+        &self,
+        project_id: Uuid,
+        language_pair: Option<&LanguagePair>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<TranslationUnit>> {
+        let stored = self.units.read().await;
+        Ok(stored
+            .iter()
+            .filter(|u| u.project_id == project_id)
+            .filter(|u| {
+                language_pair.is_none_or(|pair| u.source_language == pair.source && u.target_language == pair.target)
+            })
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect())
+    }
+
+    async fn count_translation_units(&self, project_id: Uuid) -> Result<u64> {
+        let stored = self.units.read().await;
+        Ok(stored.iter().filter(|u| u.project_id == project_id).count() as u64)
+    }
+
+    async fn optimize_storage(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_storage_stats(&self) -> Result<TranslationMemoryStorageStats> {
+        Ok(unit_stats(self.units.read().await.iter()))
+    }
+}
+
+#[async_trait]
+impl TmStorage for InMemoryStorage {
+    async fn upsert_translation_units_transaction(&self, units: &[TranslationUnit]) -> Result<usize> {
+        for unit in units {
+            unit.validate()?;
+        }
+        self.store(units).await;
+        Ok(units.len())
+    }
+}
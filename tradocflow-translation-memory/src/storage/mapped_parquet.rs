@@ -0,0 +1,439 @@
+//! Memory-mapped reads of translation unit Parquet files
+//!
+//! Large translation memories are searched in place instead of being loaded
+//! at startup: the file is mapped into memory, and only row groups whose
+//! language column statistics can hold the requested pair are decoded.
+
+use crate::error::{Result, TranslationMemoryError};
+use crate::models::{Language, TranslationMetadata, TranslationUnit};
+use crate::services::translation_memory::{
+    calculate_ngram_similarity, calculate_similarity, LanguagePair, TranslationMatch,
+};
+use crate::storage::in_memory::{find_matches, unit_stats, NGRAM_SIZE};
+use crate::storage::traits::{TmStorage, TranslationMemoryStorage, TranslationMemoryStorageStats};
+use async_trait::async_trait;
+use arrow::array::{Array, ArrayRef, Float32Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::ArrowWriter;
+use parquet::file::metadata::RowGroupMetaData;
+use parquet::file::properties::{EnabledStatistics, WriterProperties};
+use std::fs::File;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const SOURCE_LANGUAGE: &str = "source_language";
+const TARGET_LANGUAGE: &str = "target_language";
+
+/// Rows per row group written by [`write_translation_units`]
+pub const DEFAULT_ROW_GROUP_SIZE: usize = 64 * 1024;
+
+/// Columns of a translation unit Parquet file. Languages are stored as
+/// ISO 639-1 codes so row group statistics can rule out language pairs.
+fn translation_unit_schema() -> SchemaRef {
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("project_id", DataType::Utf8, false),
+        Field::new("chapter_id", DataType::Utf8, false),
+        Field::new("chunk_id", DataType::Utf8, false),
+        Field::new(SOURCE_LANGUAGE, DataType::Utf8, false),
+        Field::new("source_text", DataType::Utf8, false),
+        Field::new(TARGET_LANGUAGE, DataType::Utf8, false),
+        Field::new("target_text", DataType::Utf8, false),
+        Field::new("confidence_score", DataType::Float32, false),
+        Field::new("context", DataType::Utf8, true),
+        Field::new("metadata", DataType::Utf8, false),
+        Field::new("created_at", timestamp.clone(), false),
+        Field::new("updated_at", timestamp, false),
+    ]))
+}
+
+/// Write `units` to a Parquet file at `path`, at most `max_row_group_size`
+/// rows per row group.
+///
+/// Units are grouped by language pair first, so most row groups hold a
+/// single pair and searches for other pairs skip them.
+pub fn write_translation_units(path: &Path, units: &[TranslationUnit], max_row_group_size: usize) -> Result<()> {
+    let mut units: Vec<&TranslationUnit> = units.iter().collect();
+    units.sort_by(|a, b| {
+        (a.source_language.code(), a.target_language.code()).cmp(&(b.source_language.code(), b.target_language.code()))
+    });
+
+    let schema = translation_unit_schema();
+    let properties = WriterProperties::builder()
+        .set_max_row_group_size(max_row_group_size.max(1))
+        .set_statistics_enabled(EnabledStatistics::Chunk)
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema.clone(), Some(properties))?;
+    for batch in units.chunks(max_row_group_size.max(1)) {
+        writer.write(&to_record_batch(&schema, batch)?)?;
+    }
+    writer.close()?;
+    Ok(())
+}
+
+fn to_record_batch(schema: &SchemaRef, units: &[&TranslationUnit]) -> Result<RecordBatch> {
+    fn strings<'a>(units: &'a [&TranslationUnit], field: impl Fn(&'a TranslationUnit) -> String) -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(units.iter().map(|unit| field(unit))))
+    }
+    fn timestamps(units: &[&TranslationUnit], field: impl Fn(&TranslationUnit) -> DateTime<Utc>) -> ArrayRef {
+        Arc::new(TimestampMicrosecondArray::from_iter_values(units.iter().map(|unit| field(unit).timestamp_micros())).with_timezone("UTC"))
+    }
+
+    let metadata = units
+        .iter()
+        .map(|unit| serde_json::to_string(&unit.metadata))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let columns: Vec<ArrayRef> = vec![
+        strings(units, |unit| unit.id.to_string()),
+        strings(units, |unit| unit.project_id.to_string()),
+        strings(units, |unit| unit.chapter_id.to_string()),
+        strings(units, |unit| unit.chunk_id.to_string()),
+        strings(units, |unit| unit.source_language.code().to_string()),
+        strings(units, |unit| unit.source_text.clone()),
+        strings(units, |unit| unit.target_language.code().to_string()),
+        strings(units, |unit| unit.target_text.clone()),
+        Arc::new(Float32Array::from_iter_values(units.iter().map(|unit| unit.confidence_score))),
+        Arc::new(units.iter().map(|unit| unit.context.as_deref()).collect::<StringArray>()),
+        Arc::new(StringArray::from(metadata)),
+        timestamps(units, |unit| unit.created_at),
+        timestamps(units, |unit| unit.updated_at),
+    ];
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// Row groups and rows decoded by a [`MappedTranslationUnits`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanStats {
+    pub row_groups_read: u64,
+    pub row_groups_skipped: u64,
+    pub rows_read: u64,
+}
+
+/// A translation unit Parquet file mapped into memory.
+///
+/// Opening reads only the footer; pages are faulted in by the OS as
+/// searches decode them, so unrelated language pairs are never loaded.
+pub struct MappedTranslationUnits {
+    data: Bytes,
+    metadata: ArrowReaderMetadata,
+    source_column: usize,
+    target_column: usize,
+    row_groups_read: AtomicU64,
+    row_groups_skipped: AtomicU64,
+    rows_read: AtomicU64,
+}
+
+impl MappedTranslationUnits {
+    /// Map the file at `path`, which must have been written by
+    /// [`write_translation_units`]
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: translation unit files are only ever replaced, never
+        // modified in place, so the mapping can't change under the reader
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        let data = Bytes::from_owner(mmap);
+        let metadata = ArrowReaderMetadata::load(&data, ArrowReaderOptions::default())?;
+
+        let schema = metadata.schema();
+        let column = |name: &str| {
+            schema.index_of(name).map_err(|_| {
+                TranslationMemoryError::StorageError(format!("{} has no {name} column", path.display()))
+            })
+        };
+        let source_column = column(SOURCE_LANGUAGE)?;
+        let target_column = column(TARGET_LANGUAGE)?;
+
+        Ok(Self {
+            data,
+            metadata,
+            source_column,
+            target_column,
+            row_groups_read: AtomicU64::new(0),
+            row_groups_skipped: AtomicU64::new(0),
+            rows_read: AtomicU64::new(0),
+        })
+    }
+
+    pub fn row_group_count(&self) -> usize {
+        self.metadata.metadata().num_row_groups()
+    }
+
+    /// Total rows in the file, from the footer
+    pub fn row_count(&self) -> u64 {
+        self.metadata.metadata().file_metadata().num_rows().max(0) as u64
+    }
+
+    /// Units translating `source` into `target`, decoding only row groups
+    /// whose language statistics admit the pair
+    pub fn search(&self, source: &Language, target: &Language) -> Result<Vec<TranslationUnit>> {
+        self.scan(Some((source.code(), target.code())))
+    }
+
+    /// Units of the language pair `(source, target)` codes, or every unit
+    fn scan(&self, pair: Option<(&str, &str)>) -> Result<Vec<TranslationUnit>> {
+        let row_groups: Vec<usize> = self
+            .metadata
+            .metadata()
+            .row_groups()
+            .iter()
+            .enumerate()
+            .filter(|(_, group)| {
+                pair.is_none_or(|(source, target)| {
+                    may_contain(group, self.source_column, source) && may_contain(group, self.target_column, target)
+                })
+            })
+            .map(|(index, _)| index)
+            .collect();
+        self.row_groups_read.fetch_add(row_groups.len() as u64, Ordering::Relaxed);
+        self.row_groups_skipped
+            .fetch_add((self.row_group_count() - row_groups.len()) as u64, Ordering::Relaxed);
+        if row_groups.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let reader = ParquetRecordBatchReaderBuilder::new_with_metadata(self.data.clone(), self.metadata.clone())
+            .with_row_groups(row_groups)
+            .build()?;
+        let mut units = Vec::new();
+        for batch in reader {
+            let batch = batch?;
+            self.rows_read.fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+            units.extend(from_record_batch(&batch, pair)?);
+        }
+        Ok(units)
+    }
+
+    /// Work done by searches so far
+    pub fn scan_stats(&self) -> ScanStats {
+        ScanStats {
+            row_groups_read: self.row_groups_read.load(Ordering::Relaxed),
+            row_groups_skipped: self.row_groups_skipped.load(Ordering::Relaxed),
+            rows_read: self.rows_read.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Whether the min/max statistics of `column` in `group` allow `value`.
+/// Groups without statistics are read rather than risk missing units.
+fn may_contain(group: &RowGroupMetaData, column: usize, value: &str) -> bool {
+    let Some(statistics) = group.column(column).statistics() else {
+        return true;
+    };
+    let value = value.as_bytes();
+    let above_min = statistics.min_bytes_opt().is_none_or(|min| min <= value);
+    let below_max = statistics.max_bytes_opt().is_none_or(|max| value <= max);
+    above_min && below_max
+}
+
+/// Units of `batch`, only those in the pair of `(source, target)` codes if given
+fn from_record_batch(batch: &RecordBatch, pair: Option<(&str, &str)>) -> Result<Vec<TranslationUnit>> {
+    let strings = |name: &str| -> Result<&StringArray> {
+        batch
+            .column_by_name(name)
+            .and_then(|column| column.as_any().downcast_ref::<StringArray>())
+            .ok_or_else(|| TranslationMemoryError::StorageError(format!("Column {name} is missing or not text")))
+    };
+    let timestamps = |name: &str| -> Result<&TimestampMicrosecondArray> {
+        batch
+            .column_by_name(name)
+            .and_then(|column| column.as_any().downcast_ref::<TimestampMicrosecondArray>())
+            .ok_or_else(|| TranslationMemoryError::StorageError(format!("Column {name} is missing or not a timestamp")))
+    };
+    let uuid = |column: &StringArray, row: usize| {
+        Uuid::parse_str(column.value(row))
+            .map_err(|e| TranslationMemoryError::StorageError(format!("Invalid id {}: {e}", column.value(row))))
+    };
+    let timestamp = |column: &TimestampMicrosecondArray, row: usize| {
+        DateTime::from_timestamp_micros(column.value(row))
+            .ok_or_else(|| TranslationMemoryError::StorageError(format!("Invalid timestamp {}", column.value(row))))
+    };
+
+    let (ids, project_ids, chapter_ids, chunk_ids) =
+        (strings("id")?, strings("project_id")?, strings("chapter_id")?, strings("chunk_id")?);
+    let (source_languages, source_texts) = (strings(SOURCE_LANGUAGE)?, strings("source_text")?);
+    let (target_languages, target_texts) = (strings(TARGET_LANGUAGE)?, strings("target_text")?);
+    let (contexts, metadata) = (strings("context")?, strings("metadata")?);
+    let (created, updated) = (timestamps("created_at")?, timestamps("updated_at")?);
+    let confidence = batch
+        .column_by_name("confidence_score")
+        .and_then(|column| column.as_any().downcast_ref::<Float32Array>())
+        .ok_or_else(|| TranslationMemoryError::StorageError("Column confidence_score is missing".to_string()))?;
+
+    let mut units = Vec::new();
+    for row in 0..batch.num_rows() {
+        let (source, target) = (source_languages.value(row), target_languages.value(row));
+        if pair.is_some_and(|pair| pair != (source, target)) {
+            continue;
+        }
+        let metadata: TranslationMetadata = serde_json::from_str(metadata.value(row))?;
+        units.push(TranslationUnit {
+            id: uuid(ids, row)?,
+            project_id: uuid(project_ids, row)?,
+            chapter_id: uuid(chapter_ids, row)?,
+            chunk_id: uuid(chunk_ids, row)?,
+            source_language: Language::from_code(source).unwrap_or(Language::Custom(source.to_string())),
+            source_text: source_texts.value(row).to_string(),
+            target_language: Language::from_code(target).unwrap_or(Language::Custom(target.to_string())),
+            target_text: target_texts.value(row).to_string(),
+            confidence_score: confidence.value(row),
+            context: contexts.is_valid(row).then(|| contexts.value(row).to_string()),
+            metadata,
+            created_at: timestamp(created, row)?,
+            updated_at: timestamp(updated, row)?,
+        });
+    }
+    Ok(units)
+}
+
+/// A mapped file serves as a read-only translation memory, for archived or
+/// shared memories too large to load
+#[async_trait]
+impl TranslationMemoryStorage for MappedTranslationUnits {
+    async fn initialize_schema(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn insert_translation_unit(&self, _unit: &TranslationUnit) -> Result<()> {
+        Err(read_only())
+    }
+
+    async fn insert_translation_units_batch(&self, _units: &[TranslationUnit]) -> Result<usize> {
+        Err(read_only())
+    }
+
+    async fn update_translation_unit(&self, _unit: &TranslationUnit) -> Result<()> {
+        Err(read_only())
+    }
+
+    async fn delete_translation_unit(&self, _id: Uuid) -> Result<bool> {
+        Err(read_only())
+    }
+
+    async fn search_exact_matches(&self, source_text: &str, language_pair: &LanguagePair) -> Result<Vec<TranslationMatch>> {
+        let query = source_text.trim();
+        let units = self.search(&language_pair.source, &language_pair.target)?;
+        Ok(find_matches(&units, language_pair, 1.0, |text| if text.trim() == query { 1.0 } else { 0.0 }))
+    }
+
+    async fn search_fuzzy_matches(
+        &self,
+        source_text: &str,
+        language_pair: &LanguagePair,
+        threshold: f32,
+    ) -> Result<Vec<TranslationMatch>> {
+        let units = self.search(&language_pair.source, &language_pair.target)?;
+        Ok(find_matches(&units, language_pair, threshold, |text| calculate_similarity(source_text, text)))
+    }
+
+    async fn search_ngram_matches(
+        &self,
+        source_text: &str,
+        language_pair: &LanguagePair,
+        threshold: f32,
+    ) -> Result<Vec<TranslationMatch>> {
+        let units = self.search(&language_pair.source, &language_pair.target)?;
+        Ok(find_matches(&units, language_pair, threshold, |text| {
+            calculate_ngram_similarity(source_text, text, NGRAM_SIZE)
+        }))
+    }
+
+    async fn get_translation_units_by_project(
+        &self,
+        project_id: Uuid,
+        language_pair: Option<&LanguagePair>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<TranslationUnit>> {
+        let units = self.scan(language_pair.map(|pair| (pair.source.code(), pair.target.code())))?;
+        Ok(units
+            .into_iter()
+            .filter(|u| u.project_id == project_id)
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn count_translation_units(&self, project_id: Uuid) -> Result<u64> {
+        Ok(self.scan(None)?.iter().filter(|u| u.project_id == project_id).count() as u64)
+    }
+
+    async fn optimize_storage(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_storage_stats(&self) -> Result<TranslationMemoryStorageStats> {
+        Ok(TranslationMemoryStorageStats {
+            storage_size_bytes: self.data.len() as u64,
+            ..unit_stats(&self.scan(None)?)
+        })
+    }
+}
+
+#[async_trait]
+impl TmStorage for MappedTranslationUnits {
+    async fn upsert_translation_units_transaction(&self, _units: &[TranslationUnit]) -> Result<usize> {
+        Err(read_only())
+    }
+}
+
+fn read_only() -> TranslationMemoryError {
+    TranslationMemoryError::UnsupportedOperation("Parquet translation memories are read-only".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TranslationUnitBuilder;
+    use tempfile::tempdir;
+
+    fn unit(source: Language, target: Language, text: &str) -> TranslationUnit {
+        TranslationUnitBuilder::new()
+            .project_id(Uuid::new_v4())
+            .chapter_id(Uuid::new_v4())
+            .chunk_id(Uuid::new_v4())
+            .source_language_enum(source)
+            .source_text(text)
+            .target_language_enum(target)
+            .target_text(format!("{text} (translated)"))
+            .confidence_score(0.9)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_language_pair_search_reads_only_matching_row_groups() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("units.parquet");
+
+        let mut units = Vec::new();
+        for i in 0..30 {
+            units.push(unit(Language::English, Language::German, &format!("Bell {i}")));
+            units.push(unit(Language::English, Language::French, &format!("Clapper {i}")));
+            units.push(unit(Language::Spanish, Language::German, &format!("Yoke {i}")));
+        }
+        units[0].context = Some("Tower".to_string());
+        write_translation_units(&path, &units, 10).unwrap();
+
+        let file = MappedTranslationUnits::open(&path).unwrap();
+        assert_eq!(file.row_group_count(), 9);
+        assert_eq!(file.row_count(), 90);
+
+        let found = file.search(&Language::English, &Language::German).unwrap();
+        assert_eq!(found.len(), 30);
+        assert!(found.iter().all(|u| u.source_language == Language::English && u.target_language == Language::German));
+        let first = found.iter().find(|u| u.id == units[0].id).unwrap();
+        assert_eq!(first.source_text, "Bell 0");
+        assert_eq!(first.context.as_deref(), Some("Tower"));
+        assert_eq!(first.created_at.timestamp_micros(), units[0].created_at.timestamp_micros());
+        assert_eq!(file.scan_stats(), ScanStats { row_groups_read: 3, row_groups_skipped: 6, rows_read: 30 });
+
+        assert!(file.search(&Language::German, &Language::English).unwrap().is_empty());
+        assert_eq!(file.scan_stats().rows_read, 30);
+    }
+}
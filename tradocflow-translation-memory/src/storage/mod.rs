@@ -8,11 +8,15 @@
 pub mod traits;
 pub mod duckdb_manager;
 pub mod parquet_manager;
+#[cfg(feature = "parquet-export")]
+pub mod mapped_parquet;
 pub mod chunk_manager;
+pub mod in_memory;
 
 // Re-export storage trait abstractions
 pub use traits::{
     TranslationMemoryStorage, 
+    TmStorage,
     TerminologyStorage, 
     ChunkStorage,
     UnifiedStorageProvider,
@@ -27,4 +31,7 @@ pub use traits::{
 // Re-export concrete storage implementations
 pub use duckdb_manager::DuckDBManager;
 pub use parquet_manager::ParquetManager;
-pub use chunk_manager::{ChunkManager, ChunkManagementStats};
\ No newline at end of file
+#[cfg(feature = "parquet-export")]
+pub use mapped_parquet::{MappedTranslationUnits, ScanStats, write_translation_units};
+pub use chunk_manager::{ChunkManager, ChunkManagementStats};
+pub use in_memory::InMemoryStorage;
\ No newline at end of file
@@ -239,6 +239,19 @@ impl ParquetManager {
         Ok(data)
     }
     
+    /// Map the translation units of a project into memory for searching in
+    /// place. `language` selects the file the same way as when appending.
+    #[cfg(feature = "parquet-export")]
+    pub async fn open_translation_units(
+        &self,
+        project_id: Uuid,
+        language: Option<Language>,
+    ) -> Result<super::mapped_parquet::MappedTranslationUnits> {
+        let file_path = self.get_translation_units_file_path(project_id, language).await?;
+        log::debug!("Mapping Parquet file for search: {:?}", file_path);
+        super::mapped_parquet::MappedTranslationUnits::open(&file_path)
+    }
+    
     /// Compact and optimize Parquet files for a project
     pub async fn optimize_project_files(&self, project_id: Uuid) -> Result<()> {
         log::info!("Optimizing Parquet files for project: {}", project_id);
@@ -450,7 +463,7 @@ pub struct StorageStats {
 mod tests {
     use super::*;
     use tempfile::tempdir;
-    use crate::models::Language;
+    use crate::models::{Language, TermStatus};
     
     #[tokio::test]
     async fn test_parquet_manager_creation() {
@@ -532,6 +545,9 @@ mod tests {
                 term: "API".to_string(),
                 definition: Some("Application Programming Interface".to_string()),
                 do_not_translate: true,
+                domain: None,
+                language: None,
+                status: TermStatus::Approved,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             }
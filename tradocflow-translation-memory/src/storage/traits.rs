@@ -74,6 +74,25 @@ pub trait TranslationMemoryStorage: Send + Sync {
     async fn get_storage_stats(&self) -> Result<TranslationMemoryStorageStats>;
 }
 
+/// Backend of a `TranslationMemoryService`
+/// 
+/// Adds the operations the service needs on top of
+/// [`TranslationMemoryStorage`], so any backend, including the in-memory one
+/// used by tests and small projects, can serve a translation memory.
+#[async_trait]
+pub trait TmStorage: TranslationMemoryStorage {
+    /// Insert or replace units in a single transaction
+    /// 
+    /// Every unit is validated before anything is written, so a failure
+    /// leaves the stored units untouched.
+    async fn upsert_translation_units_transaction(&self, units: &[TranslationUnit]) -> Result<usize>;
+    
+    /// Available and maximum connections, `(0, 0)` for backends without a pool
+    async fn connection_pool_stats(&self) -> (usize, usize) {
+        (0, 0)
+    }
+}
+
 /// Core trait for terminology storage operations
 /// 
 /// This trait defines operations for managing terminology entries
@@ -73,6 +73,9 @@ pub fn create_test_terminology_entries(count: usize) -> Vec<crate::models::Termi
         term: format!("test_term_{}", i),
         definition: Some(format!("Definition for term {}", i)),
         do_not_translate: i % 2 == 0, // Alternate between translatable and non-translatable
+        domain: None,
+        language: None,
+        status: crate::models::TermStatus::Approved,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     }).collect()
@@ -138,7 +138,7 @@ mod terminology_service_tests {
         
         let duckdb_manager = DuckDBManager::new(db_path, Some(5)).await?;
         let parquet_manager = ParquetManager::new(fixtures.temp_dir.path().to_str().unwrap()).await?;
-        let csv_processor = Arc::new(CsvProcessor {});
+        let csv_processor = Arc::new(CsvProcessor::new());
         
         let service = TerminologyService::new(
             duckdb_manager,
@@ -268,7 +268,7 @@ mod highlighting_service_tests {
         
         let duckdb_manager = DuckDBManager::new(db_path, Some(5)).await?;
         let parquet_manager = ParquetManager::new(fixtures.temp_dir.path().to_str().unwrap()).await?;
-        let csv_processor = Arc::new(CsvProcessor {});
+        let csv_processor = Arc::new(CsvProcessor::new());
         
         let terminology_service = Arc::new(TerminologyService::new(
             duckdb_manager,
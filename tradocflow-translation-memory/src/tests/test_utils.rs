@@ -134,6 +134,9 @@ impl TestUtils {
                 term: format!("{}{}", prefix, i),
                 definition: Some(format!("{} {}", definition, i)),
                 do_not_translate: i % 3 == 0, // Every third term is non-translatable
+                domain: None,
+                language: None,
+                status: crate::models::TermStatus::Approved,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             }
@@ -162,9 +165,13 @@ impl TestUtils {
         crate::services::terminology::TerminologyValidationConfig {
             case_sensitive: false,
             allow_duplicates: false,
+            conflict_resolution: crate::models::ConflictResolution::Skip,
             max_term_length: 100,
             max_definition_length: 500,
             required_fields: vec!["term".to_string()],
+            check_forbidden_terms: true,
+            check_mandatory_terms: true,
+            check_term_casing: false,
         }
     }
     
@@ -177,6 +184,7 @@ impl TestUtils {
             max_context_length: 100,
             highlight_overlaps: false,
             include_variations: true,
+            stemming: false,
         }
     }
     
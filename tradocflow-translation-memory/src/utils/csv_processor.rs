@@ -7,18 +7,205 @@ use std::path::Path;
 #[cfg(feature = "terminology-csv")]
 use std::fs::File;
 #[cfg(feature = "terminology-csv")]
-use csv::{Reader, Writer, StringRecord};
+use std::io::{BufReader, Read, Seek, SeekFrom};
+#[cfg(feature = "terminology-csv")]
+use csv::{ByteRecord, ReaderBuilder, Writer, StringRecord};
 #[cfg(feature = "terminology-csv")]
 use std::collections::HashMap;
 
+/// Byte order mark that some tools put at the start of UTF-8 files
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Delimiters considered when sniffing a file's dialect
+const CANDIDATE_DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// Bytes read from the start of a streamed file to sniff its dialect
+#[cfg(feature = "terminology-csv")]
+const SNIFF_PREFIX_BYTES: usize = 64 * 1024;
+
+/// Character encoding of a CSV file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CsvEncoding {
+    Utf8,
+    /// ISO-8859-1, as exported by many older spreadsheet setups
+    Latin1,
+}
+
+impl CsvEncoding {
+    /// Text of `bytes`, or `None` if they aren't valid in this encoding
+    pub fn decode(&self, bytes: &[u8]) -> Option<String> {
+        match self {
+            CsvEncoding::Utf8 => std::str::from_utf8(bytes).ok().map(str::to_string),
+            // Latin-1 bytes are the first 256 code points
+            CsvEncoding::Latin1 => Some(bytes.iter().map(|&b| char::from(b)).collect()),
+        }
+    }
+}
+
+/// How a CSV file is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CsvDialect {
+    pub delimiter: u8,
+    pub quote: u8,
+    /// Whether the file starts with a UTF-8 byte order mark
+    pub has_bom: bool,
+    pub encoding: CsvEncoding,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self { delimiter: b',', quote: b'"', has_bom: false, encoding: CsvEncoding::Utf8 }
+    }
+}
+
+impl CsvDialect {
+    /// Guess the dialect of a file from its contents
+    /// 
+    /// A byte order mark or valid UTF-8 means UTF-8, anything else is taken
+    /// as Latin-1. The delimiter is the candidate occurring most often
+    /// outside quotes in the header line, falling back to a comma.
+    pub fn sniff(bytes: &[u8]) -> Self {
+        let has_bom = bytes.starts_with(UTF8_BOM);
+        let content = if has_bom { &bytes[UTF8_BOM.len()..] } else { bytes };
+        let encoding = if has_bom || std::str::from_utf8(content).is_ok() {
+            CsvEncoding::Utf8
+        } else {
+            CsvEncoding::Latin1
+        };
+        
+        let quote = b'"';
+        let header = content.split(|&b| b == b'\n').next().unwrap_or_default();
+        let mut counts = [0usize; CANDIDATE_DELIMITERS.len()];
+        let mut quoted = false;
+        for &b in header {
+            if b == quote {
+                quoted = !quoted;
+            } else if !quoted {
+                if let Some(i) = CANDIDATE_DELIMITERS.iter().position(|&d| d == b) {
+                    counts[i] += 1;
+                }
+            }
+        }
+        let delimiter = CANDIDATE_DELIMITERS
+            .iter()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .max_by_key(|(_, count)| *count)
+            .map_or(b',', |(&d, _)| d);
+        
+        Self { delimiter, quote, has_bom, encoding }
+    }
+}
+
+/// A CSV line that could not be read
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CsvRowError {
+    /// Line in the file, counting the header as line 1
+    pub line: u64,
+    pub message: String,
+}
+
+/// A terminology record and the line of the file it starts on
+#[derive(Debug, Clone)]
+pub struct CsvRow {
+    pub line: u64,
+    pub record: TerminologyCsvRecord,
+}
+
+/// Terminology records read one line at a time, so only the current
+/// record is held in memory
+/// 
+/// Lines that can't be decoded or parsed come out as [`CsvRowError`]s and
+/// reading carries on with the next line.
+#[cfg(feature = "terminology-csv")]
+#[derive(Debug)]
+pub struct CsvRecordStream<R> {
+    reader: csv::Reader<R>,
+    dialect: CsvDialect,
+    header_map: HashMap<String, usize>,
+    record: ByteRecord,
+    /// Bytes of the source before the reader's start, i.e. a byte order mark
+    skipped: u64,
+    /// Set once the source fails to read, as retrying would fail again
+    failed: bool,
+}
+
+#[cfg(feature = "terminology-csv")]
+impl<R: Read> CsvRecordStream<R> {
+    fn new(mut reader: csv::Reader<R>, dialect: CsvDialect, skipped: u64) -> Result<Self> {
+        // Get headers to map column positions
+        let headers = CsvProcessor::decode_record(reader.byte_headers()?, &dialect).ok_or_else(|| {
+            TranslationMemoryError::ParsingError(format!("CSV header is not valid {:?}", dialect.encoding))
+        })?;
+        let header_map = CsvProcessor::create_header_map(&headers);
+        Ok(Self { reader, dialect, header_map, record: ByteRecord::new(), skipped, failed: false })
+    }
+    
+    /// Dialect the source is read with
+    pub fn dialect(&self) -> CsvDialect {
+        self.dialect
+    }
+    
+    /// Bytes of the source read so far
+    pub fn bytes_read(&self) -> u64 {
+        self.skipped + self.reader.position().byte()
+    }
+}
+
+#[cfg(feature = "terminology-csv")]
+impl<R: Read> Iterator for CsvRecordStream<R> {
+    type Item = std::result::Result<CsvRow, CsvRowError>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let line = self.reader.position().line();
+        match self.reader.read_byte_record(&mut self.record) {
+            Ok(false) => return None,
+            Ok(true) => {}
+            Err(e) => {
+                self.failed = e.is_io_error();
+                return Some(Err(CsvRowError { line, message: e.to_string() }));
+            }
+        }
+        let line = self.record.position().map_or(line, |position| position.line());
+        let Some(decoded) = CsvProcessor::decode_record(&self.record, &self.dialect) else {
+            return Some(Err(CsvRowError { line, message: format!("Text is not valid {:?}", self.dialect.encoding) }));
+        };
+        Some(
+            CsvProcessor::parse_record_to_terminology(&decoded, &self.header_map)
+                .map(|record| CsvRow { line, record })
+                .map_err(|e| CsvRowError { line, message: e.to_string() }),
+        )
+    }
+}
+
+/// Records read from a terminology CSV file, and the lines that were skipped
+#[derive(Debug, Clone)]
+pub struct CsvParseReport {
+    /// Dialect the file was read with
+    pub dialect: CsvDialect,
+    pub records: Vec<TerminologyCsvRecord>,
+    pub errors: Vec<CsvRowError>,
+}
+
 /// Utility for processing CSV files with translation and terminology data
 #[derive(Debug)]
-pub struct CsvProcessor;
+pub struct CsvProcessor {
+    /// Dialect to read files with instead of sniffing it
+    dialect: Option<CsvDialect>,
+}
 
 impl CsvProcessor {
-    /// Create a new CSV processor
+    /// Create a new CSV processor that detects each file's dialect
     pub fn new() -> Self {
-        Self
+        Self { dialect: None }
+    }
+    
+    /// Create a CSV processor that reads every file as `dialect`
+    pub fn with_dialect(dialect: CsvDialect) -> Self {
+        Self { dialect: Some(dialect) }
     }
 
     /// Import translation units from CSV
@@ -33,25 +220,27 @@ impl CsvProcessor {
         Ok(())
     }
     
-    /// Validate CSV format for terminology files
+    /// Validate CSV format for terminology files, read in this processor's
+    /// dialect like an import would read them
     #[cfg(feature = "terminology-csv")]
-    pub async fn validate_csv_format(file_path: &str) -> Result<bool> {
+    pub async fn validate_csv_format(&self, file_path: &str) -> Result<bool> {
         if !Path::new(file_path).exists() {
             return Err(TranslationMemoryError::FileOperationError(
                 format!("CSV file not found: {}", file_path)
             ));
         }
 
-        let file = File::open(file_path)?;
-        let mut reader = Reader::from_reader(file);
+        let bytes = std::fs::read(file_path)?;
+        let dialect = self.dialect.unwrap_or_else(|| CsvDialect::sniff(&bytes));
+        let mut reader = Self::reader(&bytes, &dialect);
         
         // Check if we can read at least the headers
-        match reader.headers() {
+        match reader.byte_headers() {
             Ok(headers) => {
                 // Validate that we have at least a 'term' column
-                let has_term_column = headers.iter().any(|header| 
-                    header.trim().to_lowercase() == "term"
-                );
+                let has_term_column = headers.iter().any(|header| {
+                    dialect.encoding.decode(header).is_some_and(|header| header.trim().to_lowercase() == "term")
+                });
                 
                 if !has_term_column {
                     return Err(TranslationMemoryError::DataValidation(
@@ -60,7 +249,7 @@ impl CsvProcessor {
                 }
                 
                 // Try to read first record to validate format
-                for (i, result) in reader.records().enumerate() {
+                for (i, result) in reader.byte_records().enumerate() {
                     match result {
                         Ok(_) => {
                             // Only validate first few records for performance
@@ -87,55 +276,83 @@ impl CsvProcessor {
     }
 
     #[cfg(not(feature = "terminology-csv"))]
-    pub async fn validate_csv_format(_file_path: &str) -> Result<bool> {
+    pub async fn validate_csv_format(&self, _file_path: &str) -> Result<bool> {
         Err(TranslationMemoryError::UnsupportedOperation(
             "CSV functionality is not enabled. Enable 'terminology-csv' feature".to_string()
         ))
     }
     
     /// Parse CSV file and return terminology records
-    #[cfg(feature = "terminology-csv")]
     pub async fn parse_csv(&self, file_path: &Path) -> Result<Vec<TerminologyCsvRecord>> {
+        let report = self.parse_csv_with_errors(file_path).await?;
+        for error in &report.errors {
+            // Log parsing errors but continue processing
+            log::warn!("Failed to parse CSV record at line {}: {}", error.line, error.message);
+        }
+        Ok(report.records)
+    }
+    
+    /// Parse a terminology CSV file, skipping and reporting lines that
+    /// can't be decoded or parsed instead of giving up on the file
+    #[cfg(feature = "terminology-csv")]
+    pub async fn parse_csv_with_errors(&self, file_path: &Path) -> Result<CsvParseReport> {
         if !file_path.exists() {
             return Err(TranslationMemoryError::FileOperationError(
                 format!("CSV file not found: {}", file_path.display())
             ));
         }
 
-        let file = File::open(file_path)?;
-        let mut reader = Reader::from_reader(file);
+        let bytes = std::fs::read(file_path)?;
+        let dialect = self.dialect.unwrap_or_else(|| CsvDialect::sniff(&bytes));
+        let skipped = Self::bom_len(&bytes, &dialect) as u64;
         let mut records = Vec::new();
+        let mut errors = Vec::new();
         
-        // Get headers to map column positions
-        let headers = reader.headers()?;
-        let header_map = self.create_header_map(headers);
-        
-        for (row_num, result) in reader.records().enumerate() {
-            match result {
-                Ok(record) => {
-                    match self.parse_record_to_terminology(&record, &header_map) {
-                        Ok(terminology_record) => {
-                            records.push(terminology_record);
-                        }
-                        Err(e) => {
-                            // Log parsing errors but continue processing
-                            log::warn!("Failed to parse CSV record at row {}: {}", row_num + 2, e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    return Err(TranslationMemoryError::ParsingError(
-                        format!("CSV parsing error at row {}: {}", row_num + 2, e)
-                    ));
-                }
+        for row in CsvRecordStream::new(Self::reader(&bytes, &dialect), dialect, skipped)? {
+            match row {
+                Ok(row) => records.push(row.record),
+                Err(error) => errors.push(error),
             }
         }
         
-        Ok(records)
+        Ok(CsvParseReport { dialect, records, errors })
+    }
+    
+    /// Open a terminology CSV file for reading one record at a time
+    /// 
+    /// Unlike [`Self::parse_csv_with_errors`] the file is never loaded
+    /// whole; the dialect is sniffed from its first 64 KiB.
+    #[cfg(feature = "terminology-csv")]
+    pub fn stream_csv(&self, file_path: &Path) -> Result<CsvRecordStream<BufReader<File>>> {
+        if !file_path.exists() {
+            return Err(TranslationMemoryError::FileOperationError(
+                format!("CSV file not found: {}", file_path.display())
+            ));
+        }
+        
+        let mut file = File::open(file_path)?;
+        let mut prefix = Vec::with_capacity(SNIFF_PREFIX_BYTES);
+        (&mut file).take(SNIFF_PREFIX_BYTES as u64).read_to_end(&mut prefix)?;
+        // Sniff whole lines only, so a character cut off at the end of the
+        // prefix isn't mistaken for Latin-1
+        let sample = if prefix.len() < SNIFF_PREFIX_BYTES {
+            &prefix[..]
+        } else {
+            prefix.iter().rposition(|&b| b == b'\n').map_or(&prefix[..], |end| &prefix[..=end])
+        };
+        let dialect = self.dialect.unwrap_or_else(|| CsvDialect::sniff(sample));
+        
+        let skipped = Self::bom_len(&prefix, &dialect) as u64;
+        file.seek(SeekFrom::Start(skipped))?;
+        let reader = ReaderBuilder::new()
+            .delimiter(dialect.delimiter)
+            .quote(dialect.quote)
+            .from_reader(BufReader::new(file));
+        CsvRecordStream::new(reader, dialect, skipped)
     }
 
     #[cfg(not(feature = "terminology-csv"))]
-    pub async fn parse_csv(&self, _file_path: &Path) -> Result<Vec<TerminologyCsvRecord>> {
+    pub async fn parse_csv_with_errors(&self, _file_path: &Path) -> Result<CsvParseReport> {
         Err(TranslationMemoryError::UnsupportedOperation(
             "CSV functionality is not enabled. Enable 'terminology-csv' feature".to_string()
         ))
@@ -153,7 +370,9 @@ impl CsvProcessor {
             "definition", 
             "do_not_translate",
             "category",
-            "notes"
+            "notes",
+            "language",
+            "status"
         ])?;
         
         // Write term data
@@ -166,6 +385,8 @@ impl CsvProcessor {
                 csv_record.do_not_translate.as_deref().unwrap_or("false"),
                 csv_record.category.as_deref().unwrap_or(""),
                 csv_record.notes.as_deref().unwrap_or(""),
+                csv_record.language.as_deref().unwrap_or(""),
+                csv_record.status.as_deref().unwrap_or(""),
             ])?;
         }
         
@@ -196,8 +417,29 @@ impl CsvProcessor {
 
     // Private helper methods
 
+    /// Length of the byte order mark `bytes` start with, if `dialect` has one
+    #[cfg(feature = "terminology-csv")]
+    fn bom_len(bytes: &[u8], dialect: &CsvDialect) -> usize {
+        if dialect.has_bom && bytes.starts_with(UTF8_BOM) { UTF8_BOM.len() } else { 0 }
+    }
+    
+    /// Reader of `bytes` in `dialect`, past any byte order mark
+    #[cfg(feature = "terminology-csv")]
+    fn reader<'a>(bytes: &'a [u8], dialect: &CsvDialect) -> csv::Reader<&'a [u8]> {
+        let content = &bytes[Self::bom_len(bytes, dialect)..];
+        ReaderBuilder::new()
+            .delimiter(dialect.delimiter)
+            .quote(dialect.quote)
+            .from_reader(content)
+    }
+    
+    #[cfg(feature = "terminology-csv")]
+    fn decode_record(record: &ByteRecord, dialect: &CsvDialect) -> Option<StringRecord> {
+        record.iter().map(|field| dialect.encoding.decode(field)).collect::<Option<Vec<_>>>().map(StringRecord::from)
+    }
+
     #[cfg(feature = "terminology-csv")]
-    fn create_header_map(&self, headers: &StringRecord) -> HashMap<String, usize> {
+    fn create_header_map(headers: &StringRecord) -> HashMap<String, usize> {
         let mut map = HashMap::new();
         
         for (index, header) in headers.iter().enumerate() {
@@ -210,12 +452,11 @@ impl CsvProcessor {
 
     #[cfg(feature = "terminology-csv")]
     fn parse_record_to_terminology(
-        &self,
         record: &StringRecord,
         header_map: &HashMap<String, usize>,
     ) -> Result<TerminologyCsvRecord> {
         // Get term (required field)
-        let term = self.get_field_value(record, header_map, "term")
+        let term = Self::get_field_value(record, header_map, "term")
             .ok_or_else(|| TranslationMemoryError::DataValidation(
                 "Missing required 'term' field".to_string()
             ))?;
@@ -227,16 +468,22 @@ impl CsvProcessor {
         }
         
         // Get optional fields
-        let definition = self.get_field_value(record, header_map, "definition")
+        let definition = Self::get_field_value(record, header_map, "definition")
+            .filter(|s| !s.trim().is_empty());
+        
+        let do_not_translate = Self::get_field_value(record, header_map, "do_not_translate")
+            .filter(|s| !s.trim().is_empty());
+        
+        let category = Self::get_field_value(record, header_map, "category")
             .filter(|s| !s.trim().is_empty());
         
-        let do_not_translate = self.get_field_value(record, header_map, "do_not_translate")
+        let notes = Self::get_field_value(record, header_map, "notes")
             .filter(|s| !s.trim().is_empty());
         
-        let category = self.get_field_value(record, header_map, "category")
+        let language = Self::get_field_value(record, header_map, "language")
             .filter(|s| !s.trim().is_empty());
         
-        let notes = self.get_field_value(record, header_map, "notes")
+        let status = Self::get_field_value(record, header_map, "status")
             .filter(|s| !s.trim().is_empty());
         
         Ok(TerminologyCsvRecord {
@@ -245,12 +492,13 @@ impl CsvProcessor {
             do_not_translate,
             category,
             notes,
+            language,
+            status,
         })
     }
 
     #[cfg(feature = "terminology-csv")]
     fn get_field_value(
-        &self,
         record: &StringRecord,
         header_map: &HashMap<String, usize>,
         field_name: &str,
@@ -328,11 +576,28 @@ database,A structured collection of data"#;
         let mut temp_file = NamedTempFile::new().unwrap();
         temp_file.write_all(valid_csv.as_bytes()).unwrap();
         
-        let result = CsvProcessor::validate_csv_format(temp_file.path().to_str().unwrap()).await;
+        let result = CsvProcessor::new().validate_csv_format(temp_file.path().to_str().unwrap()).await;
         assert!(result.is_ok());
         assert!(result.unwrap());
     }
 
+    #[cfg(feature = "terminology-csv")]
+    #[tokio::test]
+    async fn test_validate_csv_format_uses_configured_dialect() {
+        // The commas in the second column name make sniffing pick a comma
+        let csv = "term;name, plural, or alias
+API;APIs
+";
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(csv.as_bytes()).unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        assert!(CsvProcessor::new().validate_csv_format(path).await.is_err());
+        let semicolons = CsvProcessor::with_dialect(CsvDialect { delimiter: b';', ..Default::default() });
+        assert!(semicolons.validate_csv_format(path).await.unwrap());
+        assert_eq!(semicolons.parse_csv(temp_file.path()).await.unwrap()[0].term, "API");
+    }
+
     #[cfg(feature = "terminology-csv")]
     #[tokio::test]
     async fn test_validate_invalid_csv_format() {
@@ -342,10 +607,56 @@ API,Application Programming Interface"#;
         let mut temp_file = NamedTempFile::new().unwrap();
         temp_file.write_all(invalid_csv.as_bytes()).unwrap();
         
-        let result = CsvProcessor::validate_csv_format(temp_file.path().to_str().unwrap()).await;
+        let result = CsvProcessor::new().validate_csv_format(temp_file.path().to_str().unwrap()).await;
         assert!(result.is_err());
     }
 
+    #[cfg(feature = "terminology-csv")]
+    #[tokio::test]
+    async fn test_parse_semicolon_csv_with_bom() {
+        let mut bytes = UTF8_BOM.to_vec();
+        bytes.extend_from_slice("term;definition\nStraße;Weg für Fahrzeuge\n\"Ein; Aus\";Schalter\n".as_bytes());
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&bytes).unwrap();
+        
+        let report = CsvProcessor::new().parse_csv_with_errors(temp_file.path()).await.unwrap();
+        assert_eq!(report.dialect, CsvDialect { delimiter: b';', has_bom: true, ..CsvDialect::default() });
+        assert!(report.errors.is_empty());
+        assert_eq!(report.records[0].term, "Straße");
+        assert_eq!(report.records[0].definition.as_deref(), Some("Weg für Fahrzeuge"));
+        assert_eq!(report.records[1].term, "Ein; Aus");
+    }
+    
+    #[cfg(feature = "terminology-csv")]
+    #[tokio::test]
+    async fn test_parse_latin1_csv() {
+        // "Gebühr" and "Größe" in ISO-8859-1
+        let bytes = b"term,definition\nGeb\xFChr,Betrag\nGr\xF6\xDFe,Ma\xDF\n";
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(bytes).unwrap();
+        
+        let report = CsvProcessor::new().parse_csv_with_errors(temp_file.path()).await.unwrap();
+        assert_eq!(report.dialect.encoding, CsvEncoding::Latin1);
+        let terms: Vec<&str> = report.records.iter().map(|r| r.term.as_str()).collect();
+        assert_eq!(terms, ["Gebühr", "Größe"]);
+        assert_eq!(report.records[1].definition.as_deref(), Some("Maß"));
+    }
+    
+    #[cfg(feature = "terminology-csv")]
+    #[tokio::test]
+    async fn test_undecodable_rows_are_reported_by_line() {
+        let bytes = b"term,definition\nAPI,Interface\nGeb\xFChr,Betrag\nJSON,Notation\n";
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(bytes).unwrap();
+        
+        let processor = CsvProcessor::with_dialect(CsvDialect::default());
+        let report = processor.parse_csv_with_errors(temp_file.path()).await.unwrap();
+        let terms: Vec<&str> = report.records.iter().map(|r| r.term.as_str()).collect();
+        assert_eq!(terms, ["API", "JSON"]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 3);
+    }
+    
     #[tokio::test]
     async fn test_export_stats() {
         let terms = vec![
@@ -3,4 +3,6 @@
 pub mod csv_processor;
 
 // Re-export key utilities
-pub use csv_processor::CsvProcessor;
\ No newline at end of file
+pub use csv_processor::{CsvDialect, CsvEncoding, CsvParseReport, CsvProcessor, CsvRow, CsvRowError};
+#[cfg(feature = "terminology-csv")]
+pub use csv_processor::CsvRecordStream;
\ No newline at end of file
@@ -1,8 +1,9 @@
 //! Terminology service tests
 
-use tradocflow_translation_memory::services::TerminologyService;
+use tradocflow_translation_memory::services::{DomainScope, TermRule, TerminologyService};
+use tradocflow_translation_memory::services::terminology::{ConflictType, ServiceTerminologyImportResult, TerminologyValidationConfig};
 use tradocflow_translation_memory::utils::CsvProcessor;
-use tradocflow_translation_memory::models::{Term, TerminologyValidationConfig as ModelValidationConfig};
+use tradocflow_translation_memory::models::{ConflictResolution, Domain, Language, SearchQuery, Term, TerminologyValidationConfig as ModelValidationConfig};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -72,4 +73,325 @@ async fn test_cache_functionality() {
     assert_eq!(search_count, 0);
     assert_eq!(non_translatable_count, 0);
     assert!(last_updated.is_none());
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_export_csv_filters_by_domain_and_round_trips() {
+    use tradocflow_translation_memory::models::{Domain, Language, TermStatus};
+    use tradocflow_translation_memory::services::terminology::TermFilter;
+    
+    let csv_processor = Arc::new(CsvProcessor::new());
+    let service = TerminologyService::new(csv_processor.clone(), None).await.unwrap();
+    let project_id = Uuid::new_v4();
+    
+    let term = |text: &str, definition: Option<&str>, domain: Domain, language: Language, status: TermStatus| {
+        let mut term = Term::new(text.to_string(), definition.map(str::to_string), false).unwrap();
+        term.domain = Some(domain);
+        term.language = Some(language);
+        term.status = status;
+        term
+    };
+    let terms = vec![
+        term("API", Some("Application Programming Interface"), Domain::Software, Language::English, TermStatus::Approved),
+        term("Ticket, support", None, Domain::Software, Language::English, TermStatus::Forbidden),
+        term("Bremse", Some("Brake"), Domain::Automotive, Language::German, TermStatus::Approved),
+    ];
+    for term in &terms {
+        service.add_terminology(term.clone(), project_id).await.unwrap();
+    }
+    
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("software.csv");
+    let filter = TermFilter { domain: Some(Domain::Software), ..Default::default() };
+    let written = service.export_csv(filter, &path).await.unwrap();
+    assert_eq!(written, 2);
+    
+    // Re-import into a fresh service and compare everything but IDs and timestamps
+    let reimport = TerminologyService::new(csv_processor, None).await.unwrap();
+    let other_project = Uuid::new_v4();
+    reimport.import_terminology_csv(&path, other_project).await.unwrap();
+    
+    let key = |t: &Term| (t.term.clone(), t.definition.clone(), t.do_not_translate, t.domain, t.language.clone(), t.status);
+    let mut expected: Vec<_> = terms[..2].iter().map(key).collect();
+    let mut imported: Vec<_> = reimport.get_terms_by_project(other_project).await.unwrap().iter().map(key).collect();
+    expected.sort_by(|a, b| a.0.cmp(&b.0));
+    imported.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(imported, expected);
+    
+    // Exporting the re-imported terms reproduces the same file
+    let second_path = temp_dir.path().join("software-again.csv");
+    reimport.export_csv(TermFilter::default(), &second_path).await.unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), std::fs::read_to_string(&second_path).unwrap());
+}
+
+#[tokio::test]
+async fn test_usage_report_counts_units_and_flags_unused_terms() {
+    use tradocflow_translation_memory::models::{Language, TranslationUnitBuilder};
+    use tradocflow_translation_memory::services::TranslationMemoryService;
+    
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_id = Uuid::new_v4();
+    let translation_memory = Arc::new(TranslationMemoryService::open(project_id, temp_dir.path().to_path_buf()).await.unwrap());
+    
+    let unit = |source: &str, target: &str| {
+        TranslationUnitBuilder::new()
+            .project_id(project_id)
+            .chapter_id(Uuid::new_v4())
+            .chunk_id(Uuid::new_v4())
+            .source_language("en")
+            .source_text(source)
+            .target_language("de")
+            .target_text(target)
+            .build()
+            .unwrap()
+    };
+    let units = vec![
+        unit("Open the API settings.", "Öffnen Sie die API-Einstellungen."),
+        unit("The api key is required.", "Der API-Schlüssel ist erforderlich."),
+        unit("Rapid setup", "Schnelle Einrichtung"),
+    ];
+    translation_memory.add_translation_units_batch(units).await.unwrap();
+    let stored = translation_memory.get_translation_units().await.unwrap();
+    let newest = stored.iter().filter(|u| u.source_text != "Rapid setup").map(|u| u.updated_at).max();
+    
+    let service = TerminologyService::new(Arc::new(CsvProcessor::new()), None)
+        .await
+        .unwrap()
+        .with_translation_memory(translation_memory);
+    for (text, language) in [("API", Some(Language::English)), ("Firmware", None), ("Bremse", Some(Language::German))] {
+        let mut term = Term::new(text.to_string(), None, false).unwrap();
+        term.language = language;
+        service.add_terminology(term, project_id).await.unwrap();
+    }
+    
+    let report = service.usage_report(Language::English).await.unwrap();
+    let summary: Vec<_> = report.iter().map(|u| (u.term.as_str(), u.unit_count, u.unused)).collect();
+    // "Rapid" must not count as a use of "API"; German terms are left out
+    assert_eq!(summary, vec![("API", 2, false), ("Firmware", 0, true)]);
+    assert_eq!(report[0].last_seen, newest);
+    assert_eq!(report[1].last_seen, None);
+}
+
+#[tokio::test]
+async fn test_search_terms_pages_through_every_project() {
+    let service = TerminologyService::new(Arc::new(CsvProcessor::new()), None).await.unwrap();
+    let projects = [Uuid::new_v4(), Uuid::new_v4()];
+    for i in 0..25 {
+        let term = Term::new(format!("Valve {i:02}"), Some("Controls the flow".to_string()), false).unwrap();
+        service.add_terminology(term, projects[i % 2]).await.unwrap();
+    }
+    let other = Term::new("Lid".to_string(), None, false).unwrap();
+    service.add_terminology(other, projects[0]).await.unwrap();
+    
+    let mut seen = Vec::new();
+    for offset in [0, 10, 20] {
+        let query = SearchQuery::new().limit(10).offset(offset);
+        let page = service.search_terms_paged("valve", Language::English, DomainScope::Any, &query).await.unwrap();
+        assert_eq!(page.total_available, 25);
+        assert_eq!(page.items.len(), if offset == 20 { 5 } else { 10 });
+        seen.extend(page.items.into_iter().map(|term| term.term));
+    }
+    
+    let expected: Vec<String> = (0..25).map(|i| format!("Valve {i:02}")).collect();
+    assert_eq!(seen, expected);
+}
+
+
+async fn domain_glossary() -> TerminologyService {
+    let service = TerminologyService::new(Arc::new(CsvProcessor::new()), None).await.unwrap();
+    let project_id = Uuid::new_v4();
+    for (text, definition, domain) in [
+        ("Bus", "Shared data transfer channel", Some(Domain::Software)),
+        ("Bus bar", "Conductor distributing power", Some(Domain::Technical)),
+        ("Bus stop", "Where the bus halts", None),
+    ] {
+        let mut term = Term::new(text.to_string(), Some(definition.to_string()), false).unwrap();
+        term.domain = domain;
+        service.add_terminology(term, project_id).await.unwrap();
+    }
+    service
+}
+
+async fn search_bus(service: &TerminologyService, domain: DomainScope) -> Vec<String> {
+    let page = service.search_terms_paged("bus", Language::English, domain, &SearchQuery::new()).await.unwrap();
+    page.items.into_iter().map(|term| term.term).collect()
+}
+
+#[tokio::test]
+async fn test_domain_scoped_search_excludes_other_domains() {
+    let service = domain_glossary().await;
+    
+    assert_eq!(search_bus(&service, DomainScope::Only(Domain::Technical)).await, ["Bus bar", "Bus stop"]);
+    assert_eq!(search_bus(&service, DomainScope::Only(Domain::Software)).await, ["Bus", "Bus stop"]);
+}
+
+#[tokio::test]
+async fn test_unscoped_search_ranks_preferred_domain_first() {
+    let service = domain_glossary().await;
+    
+    assert_eq!(search_bus(&service, DomainScope::Any).await, ["Bus", "Bus bar", "Bus stop"]);
+    assert_eq!(search_bus(&service, DomainScope::Prefer(Domain::Technical)).await, ["Bus bar", "Bus", "Bus stop"]);
+}
+
+#[tokio::test]
+async fn test_streaming_import_reads_in_bounded_batches() {
+    use std::fmt::Write;
+    use tradocflow_translation_memory::services::ImportProgress;
+    
+    const ROWS: usize = 5_000;
+    const MALFORMED: usize = 25;
+    const BATCH_SIZE: usize = 100;
+    
+    // Every 200th row has no term and must be skipped
+    let mut csv = String::from("term,definition\n");
+    for i in 0..ROWS {
+        let term = if i % 200 == 199 { String::new() } else { format!("term-{i}") };
+        writeln!(csv, "{term},Definition of entry {i}").unwrap();
+    }
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("large.csv");
+    std::fs::write(&path, &csv).unwrap();
+    
+    let service = TerminologyService::new(Arc::new(CsvProcessor::new()), None).await.unwrap();
+    let mut updates: Vec<ImportProgress> = Vec::new();
+    let result = service
+        .import_terminology_csv_streaming(&path, Uuid::new_v4(), BATCH_SIZE, |progress| updates.push(progress))
+        .await
+        .unwrap();
+    
+    assert_eq!(result.base_result.successful_imports.len(), ROWS - MALFORMED);
+    assert_eq!(result.base_result.failed_imports.len(), MALFORMED);
+    assert_eq!(result.base_result.total_processed, ROWS);
+    
+    // Each batch held at most BATCH_SIZE terms, and the first was stored
+    // long before the file had been read
+    assert_eq!(updates.len(), (ROWS - MALFORMED).div_ceil(BATCH_SIZE));
+    let mut imported = 0;
+    for update in &updates {
+        assert!(update.terms_imported - imported <= BATCH_SIZE);
+        imported = update.terms_imported;
+    }
+    assert!(updates[0].bytes_read < updates[0].total_bytes / 10);
+    let last = updates.last().unwrap();
+    assert_eq!((last.rows_read, last.terms_imported, last.rows_failed), (ROWS, ROWS - MALFORMED, MALFORMED));
+    assert_eq!(last.bytes_read, csv.len() as u64);
+    
+    // Same counts as importing the whole file at once
+    let whole = service.import_terminology_csv(&path, Uuid::new_v4()).await.unwrap();
+    assert_eq!(whole.base_result.successful_imports.len(), result.base_result.successful_imports.len());
+    assert_eq!(whole.base_result.failed_imports.len(), result.base_result.failed_imports.len());
+    assert_eq!(whole.base_result.total_processed, result.base_result.total_processed);
+}
+
+async fn import_rows(csv: &str, conflict_resolution: ConflictResolution) -> (ServiceTerminologyImportResult, Vec<Term>) {
+    let config = TerminologyValidationConfig { conflict_resolution, ..Default::default() };
+    let service = TerminologyService::new(Arc::new(CsvProcessor::new()), Some(config)).await.unwrap();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("partner.csv");
+    std::fs::write(&path, csv).unwrap();
+    
+    let project_id = Uuid::new_v4();
+    let result = service.import_terminology_csv(&path, project_id).await.unwrap();
+    let mut terms = service.get_terms_by_project(project_id).await.unwrap();
+    terms.sort_by(|a, b| a.term.cmp(&b.term));
+    (result, terms)
+}
+
+#[tokio::test]
+async fn test_import_merges_rows_differing_in_whitespace() {
+    let csv = "term,definition,category\nUser interface,Screens a user works with,\n\"  User   interface \",,software\n";
+    let (result, terms) = import_rows(csv, ConflictResolution::Skip).await;
+    
+    assert_eq!(result.base_result.merged_rows, 1);
+    assert_eq!(result.base_result.success_count(), 1);
+    assert!(result.conflicts.is_empty());
+    assert_eq!(terms.len(), 1);
+    assert_eq!(terms[0].term, "User interface");
+    assert_eq!(terms[0].definition.as_deref(), Some("Screens a user works with"));
+    assert_eq!(terms[0].domain, Some(Domain::Software));
+}
+
+#[tokio::test]
+async fn test_import_resolves_conflicting_rows_by_strategy() {
+    let csv = "term,definition\nBremse,Brake\nbremse ,Braking device\n";
+    let definitions = |terms: &[Term]| -> Vec<(String, Option<String>)> {
+        terms.iter().map(|t| (t.term.clone(), t.definition.clone())).collect()
+    };
+    let brake = |term: &str, definition: &str| (term.to_string(), Some(definition.to_string()));
+    
+    let (result, terms) = import_rows(csv, ConflictResolution::Skip).await;
+    assert_eq!(result.base_result.merged_rows, 1);
+    assert_eq!(result.conflicts.len(), 1);
+    assert_eq!(result.conflicts[0].conflict_type, ConflictType::Definition);
+    assert_eq!(definitions(&terms), [brake("Bremse", "Brake")]);
+    
+    let (_, terms) = import_rows(csv, ConflictResolution::Overwrite).await;
+    assert_eq!(definitions(&terms), [brake("bremse", "Braking device")]);
+    
+    let (_, terms) = import_rows(csv, ConflictResolution::Merge).await;
+    assert_eq!(definitions(&terms), [brake("Bremse", "Brake\n\nBraking device")]);
+    
+    let (result, terms) = import_rows(csv, ConflictResolution::CreateVariant).await;
+    assert_eq!(result.base_result.merged_rows, 0);
+    assert_eq!(definitions(&terms), [brake("Bremse", "Brake"), brake("bremse (2)", "Braking device")]);
+}
+
+async fn style_guide() -> TerminologyService {
+    use tradocflow_translation_memory::models::TermStatus;
+    
+    let service = TerminologyService::new(Arc::new(CsvProcessor::new()), None).await.unwrap();
+    let project_id = Uuid::new_v4();
+    let mut forbidden = Term::new("Ablaufdatum".to_string(), None, false).unwrap();
+    forbidden.language = Some(Language::German);
+    forbidden.status = TermStatus::Forbidden;
+    for term in [
+        forbidden,
+        Term::new("TradocFlow".to_string(), None, true).unwrap(),
+        Term::new("Sync".to_string(), None, false).unwrap(),
+    ] {
+        service.add_terminology(term, project_id).await.unwrap();
+    }
+    service
+}
+
+#[tokio::test]
+async fn test_validate_text_flags_forbidden_and_missing_mandatory_terms() {
+    let service = style_guide().await;
+    let config = TerminologyValidationConfig::default();
+    
+    let source = "TradocFlow shows the expiry date.";
+    let text = "Die App zeigt das Ablaufdatum.";
+    let errors = service.validate_text(text, Some(source), &Language::German, &config).await;
+    let found: Vec<(TermRule, &str, &str)> = errors
+        .iter()
+        .map(|e| {
+            let flagged = if e.rule == TermRule::MissingMandatory { &source[e.span.clone()] } else { &text[e.span.clone()] };
+            (e.rule, e.term.as_str(), flagged)
+        })
+        .collect();
+    assert_eq!(found, [
+        (TermRule::Forbidden, "Ablaufdatum", "Ablaufdatum"),
+        (TermRule::MissingMandatory, "TradocFlow", "TradocFlow"),
+    ]);
+    
+    // Forbidden terms of other languages don't apply, and kept terms aren't missing
+    let errors = service.validate_text("TradocFlow: Ablaufdatum", Some(source), &Language::French, &config).await;
+    assert!(errors.is_empty());
+    
+    let lenient = TerminologyValidationConfig { check_forbidden_terms: false, check_mandatory_terms: false, ..Default::default() };
+    assert!(service.validate_text(text, Some(source), &Language::German, &lenient).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_validate_text_flags_casing_only_when_enabled() {
+    let service = style_guide().await;
+    let text = "Open tradocflow and press SYNC to sync.";
+    
+    let config = TerminologyValidationConfig::default();
+    assert!(service.validate_text(text, None, &Language::English, &config).await.is_empty());
+    
+    let strict = TerminologyValidationConfig { check_term_casing: true, ..Default::default() };
+    let errors = service.validate_text(text, None, &Language::English, &strict).await;
+    let flagged: Vec<(&str, &str)> = errors.iter().map(|e| (e.term.as_str(), &text[e.span.clone()])).collect();
+    assert_eq!(flagged, [("TradocFlow", "tradocflow"), ("Sync", "SYNC"), ("Sync", "sync")]);
+    assert!(errors.iter().all(|e| e.rule == TermRule::Casing));
+}
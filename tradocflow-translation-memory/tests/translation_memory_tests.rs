@@ -11,7 +11,7 @@ async fn test_translation_memory_service_creation() {
     let project_id = Uuid::new_v4();
     let project_path = temp_dir.path().to_path_buf();
     
-    let service = TranslationMemoryService::new(project_id, project_path).await.unwrap();
+    let service = TranslationMemoryService::open(project_id, project_path).await.unwrap();
     
     // Test basic functionality - get_cache_stats returns (usize, usize, Option<DateTime<Utc>>)
     let (translation_units_count, chunks_count, _last_updated) = service.get_cache_stats().await;
@@ -25,7 +25,7 @@ async fn test_translation_memory_basic_operations() {
     let project_id = Uuid::new_v4();
     let project_path = temp_dir.path().to_path_buf();
     
-    let service = TranslationMemoryService::new(project_id, project_path).await.unwrap();
+    let service = TranslationMemoryService::open(project_id, project_path).await.unwrap();
     
     // Test getting translation suggestions (should be empty initially)
     // API is: get_translation_suggestions(source_text, target_language, source_language_option)